@@ -4,7 +4,7 @@ use rquest::tls::KeyLogPolicy;
 async fn main() -> rquest::Result<()> {
     // Build a client
     let client = rquest::Client::builder()
-        .keylog(KeyLogPolicy::File("keylog.txt".into()))
+        .tls_keylog(KeyLogPolicy::File("keylog.txt".into()))
         .cert_verification(false)
         .build()?;
 
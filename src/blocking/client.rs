@@ -0,0 +1,330 @@
+use std::fmt;
+use std::time::Duration;
+
+use http::header::HeaderValue;
+
+use super::request::RequestBuilder;
+use super::response::Response;
+use super::runtime::Runtime;
+use crate::header::HeaderMap;
+use crate::proxy::IntoProxy;
+use crate::{EmulationProviderFactory, IntoUrl, Method, Request, redirect, retry};
+
+/// A `Client` to make Requests with.
+///
+/// The Client has various configuration values to tweak, but the defaults
+/// are set to what is usually the most commonly desired value. To configure a
+/// `Client`, use `Client::builder()`.
+///
+/// The `Client` holds a connection pool internally, so it is advised that
+/// you create one and **reuse** it.
+///
+/// You do **not** have to wrap the `Client` in an [`Rc`] or [`Arc`] to **reuse** it,
+/// because it already uses an [`Arc`] internally.
+///
+/// # Examples
+///
+/// ```rust
+/// use rquest::blocking::Client;
+/// #
+/// # fn run() -> Result<(), rquest::Error> {
+/// let client = Client::new();
+/// let resp = client.get("http://httpbin.org/").send()?;
+/// #   drop(resp);
+/// #   Ok(())
+/// # }
+///
+/// ```
+///
+/// [`Rc`]: std::rc::Rc
+/// [`Arc`]: std::sync::Arc
+#[derive(Clone)]
+pub struct Client {
+    inner: crate::Client,
+    runtime: Runtime,
+}
+
+/// A `ClientBuilder` can be used to create a `Client` with custom configuration.
+///
+/// # Example
+///
+/// ```
+/// # fn run() -> Result<(), rquest::Error> {
+/// use std::time::Duration;
+///
+/// let client = rquest::blocking::Client::builder()
+///     .timeout(Duration::from_secs(10))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[must_use]
+#[derive(Debug)]
+pub struct ClientBuilder {
+    inner: crate::ClientBuilder,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClientBuilder {
+    /// Constructs a new `ClientBuilder`.
+    ///
+    /// This is the same as `Client::builder()`.
+    pub fn new() -> ClientBuilder {
+        ClientBuilder {
+            inner: crate::ClientBuilder::new(),
+        }
+    }
+
+    /// Returns a `Client` that uses this `ClientBuilder` configuration.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the runtime thread cannot be started, or the
+    /// async `Client` cannot be built.
+    pub fn build(self) -> crate::Result<Client> {
+        let runtime = Runtime::new()?;
+        let inner = runtime.enter(|| self.inner.build())?;
+        Ok(Client { inner, runtime })
+    }
+
+    /// Sets the necessary values to mimic the specified impersonate client version.
+    ///
+    /// See [`ClientBuilder::emulation`](crate::ClientBuilder::emulation).
+    pub fn emulation<P>(self, factory: P) -> ClientBuilder
+    where
+        P: EmulationProviderFactory,
+    {
+        self.with_inner(|inner| inner.emulation(factory))
+    }
+
+    /// Sets the `User-Agent` header to be used by this client.
+    pub fn user_agent<V>(self, value: V) -> ClientBuilder
+    where
+        V: TryInto<HeaderValue>,
+        V::Error: Into<http::Error>,
+    {
+        self.with_inner(|inner| inner.user_agent(value))
+    }
+
+    /// Sets the default headers for every request.
+    pub fn default_headers(self, headers: HeaderMap) -> ClientBuilder {
+        self.with_inner(|inner| inner.default_headers(headers))
+    }
+
+    /// Enable a persistent cookie store for the client.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `cookies` feature to be enabled.
+    #[cfg(feature = "cookies")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cookies")))]
+    pub fn cookie_store(self, enable: bool) -> ClientBuilder {
+        self.with_inner(|inner| inner.cookie_store(enable))
+    }
+
+    /// Set a `redirect::Policy` for this client.
+    ///
+    /// Default will follow redirects up to a maximum of 10.
+    pub fn redirect(self, policy: redirect::Policy) -> ClientBuilder {
+        self.with_inner(|inner| inner.redirect(policy))
+    }
+
+    /// Set a `retry::Policy` for this client.
+    pub fn retry(self, policy: retry::Policy) -> ClientBuilder {
+        self.with_inner(|inner| inner.retry(policy))
+    }
+
+    /// Add a `Proxy` to the list of proxies the `Client` will use.
+    pub fn proxy<P>(self, proxy: P) -> ClientBuilder
+    where
+        P: IntoProxy,
+    {
+        self.with_inner(|inner| inner.proxy(proxy))
+    }
+
+    /// Clear all `Proxies`, so `Client` will use no proxy anymore.
+    pub fn no_proxy(self) -> ClientBuilder {
+        self.with_inner(|inner| inner.no_proxy())
+    }
+
+    /// Enables a total request timeout.
+    ///
+    /// The timeout is applied from when the request starts connecting until the
+    /// response body has finished.
+    ///
+    /// Default is no timeout.
+    pub fn timeout(self, timeout: Duration) -> ClientBuilder {
+        self.with_inner(|inner| inner.timeout(timeout))
+    }
+
+    /// Set a timeout for only the read phase of a `Client`.
+    ///
+    /// Default is `None`.
+    pub fn read_timeout(self, timeout: Duration) -> ClientBuilder {
+        self.with_inner(|inner| inner.read_timeout(timeout))
+    }
+
+    /// Set a timeout for only the connect phase of a `Client`.
+    ///
+    /// Default is `None`.
+    pub fn connect_timeout(self, timeout: Duration) -> ClientBuilder {
+        self.with_inner(|inner| inner.connect_timeout(timeout))
+    }
+
+    /// Controls the use of certificate validation.
+    ///
+    /// Defaults to `true`.
+    pub fn cert_verification(self, cert_verification: bool) -> ClientBuilder {
+        self.with_inner(|inner| inner.cert_verification(cert_verification))
+    }
+
+    /// Restrict the Client to be used with HTTPS only requests.
+    ///
+    /// Defaults to false.
+    pub fn https_only(self, enabled: bool) -> ClientBuilder {
+        self.with_inner(|inner| inner.https_only(enabled))
+    }
+
+    fn with_inner(
+        mut self,
+        f: impl FnOnce(crate::ClientBuilder) -> crate::ClientBuilder,
+    ) -> ClientBuilder {
+        self.inner = f(self.inner);
+        self
+    }
+}
+
+impl From<crate::ClientBuilder> for ClientBuilder {
+    fn from(inner: crate::ClientBuilder) -> ClientBuilder {
+        ClientBuilder { inner }
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Client {
+    /// Constructs a new `Client`.
+    ///
+    /// # Panic
+    ///
+    /// This method panics if the runtime thread cannot be started, or the
+    /// async `Client` cannot be built.
+    ///
+    /// Use `Client::builder()` if you wish to handle the failure as an `Error`
+    /// instead of panicking.
+    pub fn new() -> Client {
+        ClientBuilder::new().build().expect("Client::new()")
+    }
+
+    /// Creates a `ClientBuilder` to configure a `Client`.
+    ///
+    /// This is the same as `ClientBuilder::new()`.
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    /// Convenience method to make a `GET` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever supplied `Url` cannot be parsed.
+    pub fn get<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::GET, url)
+    }
+
+    /// Convenience method to make a `POST` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever supplied `Url` cannot be parsed.
+    pub fn post<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::POST, url)
+    }
+
+    /// Convenience method to make a `PUT` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever supplied `Url` cannot be parsed.
+    pub fn put<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::PUT, url)
+    }
+
+    /// Convenience method to make a `PATCH` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever supplied `Url` cannot be parsed.
+    pub fn patch<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::PATCH, url)
+    }
+
+    /// Convenience method to make a `DELETE` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever supplied `Url` cannot be parsed.
+    pub fn delete<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::DELETE, url)
+    }
+
+    /// Convenience method to make a `HEAD` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever supplied `Url` cannot be parsed.
+    pub fn head<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::HEAD, url)
+    }
+
+    /// Start building a `Request` with the `Method` and `Url`.
+    ///
+    /// Returns a `RequestBuilder`, which will allow setting headers and
+    /// request body before sending.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever supplied `Url` cannot be parsed.
+    pub fn request<U: IntoUrl>(&self, method: Method, url: U) -> RequestBuilder {
+        RequestBuilder::new(self.inner.request(method, url), self.runtime.clone())
+    }
+
+    /// Executes a `Request`.
+    ///
+    /// A `Request` can be built manually with `Request::new()` or obtained
+    /// from a RequestBuilder with `RequestBuilder::build()`.
+    ///
+    /// You should prefer to use the `RequestBuilder` and
+    /// `RequestBuilder::send()`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if there was an error while sending request,
+    /// or redirect limit was exhausted.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if called from within an async runtime.
+    pub fn execute(&self, request: Request) -> crate::Result<Response> {
+        let client = self.inner.clone();
+        let res = self
+            .runtime
+            .block_on(async move { client.execute(request).await })?;
+        Ok(Response::new(res, self.runtime.clone()))
+    }
+}
+
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
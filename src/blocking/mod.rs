@@ -0,0 +1,102 @@
+//! A blocking Client API.
+//!
+//! The blocking `Client` will block the current thread to execute, instead
+//! of returning futures that need to be executed on a runtime. It runs an
+//! async [`Client`](crate::Client) on a runtime thread of its own, so every
+//! option of the async client, including emulation, is available to it.
+//!
+//! Conversely, the functionality in `rquest::blocking` must *not* be executed
+//! within an async runtime, or it will panic when attempting to block. If
+//! calling directly from an async function, consider using an async
+//! [`rquest::Client`][crate::Client] instead.
+//!
+//! # Optional
+//!
+//! This requires the optional `blocking` feature to be enabled.
+//!
+//! # Making a GET request
+//!
+//! For a single request, you can use the [`get`] shortcut method.
+//!
+//! ```rust
+//! # use rquest::{Error, Response};
+//!
+//! # fn run() -> Result<(), Error> {
+//! let body = rquest::blocking::get("https://www.rust-lang.org")?
+//!     .text()?;
+//!
+//! println!("body = {:?}", body);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Additionally, the blocking [`Response`] struct implements Rust's
+//! `Read` trait, so many useful standard library and third party crates will
+//! have convenience methods that take a `Response` anywhere `T: Read` is
+//! acceptable.
+//!
+//! **NOTE**: If you plan to perform multiple requests, it is best to create a
+//! [`Client`] and reuse it, taking advantage of keep-alive connection pooling.
+//!
+//! # Configuring the client
+//!
+//! The blocking [`ClientBuilder`] offers the most common options directly. Any
+//! other option can be set on an async [`ClientBuilder`](crate::ClientBuilder)
+//! and converted.
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use rquest_util::Emulation;
+//!
+//! # fn run() -> Result<(), rquest::Error> {
+//! let client = rquest::blocking::Client::builder()
+//!     .emulation(Emulation::Firefox136)
+//!     .build()?;
+//!
+//! let async_builder = rquest::Client::builder().pool_max_lifetime(Duration::from_secs(60));
+//! let client = rquest::blocking::ClientBuilder::from(async_builder).build()?;
+//!
+//! let res = client.get("https://tls.peet.ws/api/all").send()?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod client;
+mod request;
+mod response;
+mod runtime;
+
+pub use self::client::{Client, ClientBuilder};
+pub use self::request::RequestBuilder;
+pub use self::response::Response;
+
+/// Shortcut method to quickly make a *blocking* `GET` request.
+///
+/// **NOTE**: This function creates a new internal `Client` on each call,
+/// and so should not be used if making many requests. Create a
+/// [`Client`](./struct.Client.html) instead.
+///
+/// # Examples
+///
+/// ```rust
+/// # fn run() -> Result<(), rquest::Error> {
+/// let body = rquest::blocking::get("https://www.rust-lang.org")?
+///     .text()?;
+/// # Ok(())
+/// # }
+/// # fn main() { }
+/// ```
+///
+/// # Errors
+///
+/// This function fails if:
+///
+/// - the native TLS backend cannot be initialized,
+/// - the supplied `Url` cannot be parsed,
+/// - there was an error while sending request,
+/// - a redirect loop was detected,
+/// - the redirect limit was exhausted, or
+/// - the total download time exceeds the timeout.
+pub fn get<T: crate::IntoUrl>(url: T) -> crate::Result<Response> {
+    Client::builder().build()?.get(url).send()
+}
@@ -0,0 +1,168 @@
+use std::fmt;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::response::Response;
+use super::runtime::Runtime;
+use crate::header::{HeaderMap, HeaderName, HeaderValue};
+use crate::{Body, Request, Version};
+
+/// A builder to construct the properties of a `Request`.
+///
+/// To construct a `RequestBuilder`, refer to the `Client` documentation.
+#[must_use = "RequestBuilder does nothing until you 'send' it"]
+pub struct RequestBuilder {
+    inner: crate::RequestBuilder,
+    runtime: Runtime,
+}
+
+impl RequestBuilder {
+    pub(super) fn new(inner: crate::RequestBuilder, runtime: Runtime) -> RequestBuilder {
+        RequestBuilder { inner, runtime }
+    }
+
+    /// Add a `Header` to this Request.
+    ///
+    /// If the header is already present, the value will be replaced.
+    pub fn header<K, V>(self, key: K, value: V) -> RequestBuilder
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<http::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<http::Error>,
+    {
+        self.map(|inner| inner.header(key, value))
+    }
+
+    /// Add a set of Headers to the existing ones on this Request.
+    ///
+    /// The headers will be merged in to any already set.
+    pub fn headers(self, headers: HeaderMap) -> RequestBuilder {
+        self.map(|inner| inner.headers(headers))
+    }
+
+    /// Enable HTTP basic authentication.
+    pub fn basic_auth<U, P>(self, username: U, password: Option<P>) -> RequestBuilder
+    where
+        U: fmt::Display,
+        P: fmt::Display,
+    {
+        self.map(|inner| inner.basic_auth(username, password))
+    }
+
+    /// Enable HTTP bearer authentication.
+    pub fn bearer_auth<T>(self, token: T) -> RequestBuilder
+    where
+        T: fmt::Display,
+    {
+        self.map(|inner| inner.bearer_auth(token))
+    }
+
+    /// Set the request body.
+    pub fn body<T: Into<Body>>(self, body: T) -> RequestBuilder {
+        self.map(|inner| inner.body(body))
+    }
+
+    /// Enables a request timeout.
+    ///
+    /// The timeout is applied from when the request starts connecting until the
+    /// response body has finished. It affects only this request and overrides
+    /// the timeout configured using `ClientBuilder::timeout()`.
+    pub fn timeout(self, timeout: Duration) -> RequestBuilder {
+        self.map(|inner| inner.timeout(timeout))
+    }
+
+    /// Modify the query string of the URL.
+    ///
+    /// See [`RequestBuilder::query`](crate::RequestBuilder::query).
+    pub fn query<T: Serialize + ?Sized>(self, query: &T) -> RequestBuilder {
+        self.map(|inner| inner.query(query))
+    }
+
+    /// Set the http version for this request.
+    pub fn version(self, version: Version) -> RequestBuilder {
+        self.map(|inner| inner.version(version))
+    }
+
+    /// Send a form body.
+    ///
+    /// See [`RequestBuilder::form`](crate::RequestBuilder::form).
+    pub fn form<T: Serialize + ?Sized>(self, form: &T) -> RequestBuilder {
+        self.map(|inner| inner.form(form))
+    }
+
+    /// Send a JSON body.
+    ///
+    /// See [`RequestBuilder::json`](crate::RequestBuilder::json).
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `json` feature enabled.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn json<T: Serialize + ?Sized>(self, json: &T) -> RequestBuilder {
+        self.map(|inner| inner.json(json))
+    }
+
+    /// Configure the request with the methods of the async
+    /// [`RequestBuilder`](crate::RequestBuilder) this builder wraps.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn run() -> Result<(), rquest::Error> {
+    /// let res = rquest::blocking::Client::new()
+    ///     .get("https://example.com")
+    ///     .map(|req| req.header_append("accept", "text/html"))
+    ///     .send()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn map(
+        mut self,
+        f: impl FnOnce(crate::RequestBuilder) -> crate::RequestBuilder,
+    ) -> RequestBuilder {
+        self.inner = f(self.inner);
+        self
+    }
+
+    /// Build a `Request`, which can be inspected, modified and executed with
+    /// `Client::execute()`.
+    pub fn build(self) -> crate::Result<Request> {
+        self.inner.build()
+    }
+
+    /// Constructs the Request and sends it the target URL, returning a Response.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if there was an error while sending request,
+    /// redirect loop was detected or redirect limit was exhausted.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if called from within an async runtime.
+    pub fn send(self) -> crate::Result<Response> {
+        // the request is built on the runtime thread, as its timers need the
+        // runtime's context
+        let inner = self.inner;
+        let res = self.runtime.block_on(async move { inner.send().await })?;
+        Ok(Response::new(res, self.runtime))
+    }
+
+    /// Attempts to clone the `RequestBuilder`.
+    ///
+    /// None is returned if a body is which can not be cloned.
+    pub fn try_clone(&self) -> Option<RequestBuilder> {
+        self.inner
+            .try_clone()
+            .map(|inner| RequestBuilder::new(inner, self.runtime.clone()))
+    }
+}
+
+impl fmt::Debug for RequestBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
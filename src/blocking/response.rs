@@ -0,0 +1,186 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::SocketAddr;
+
+use bytes::{Buf, Bytes, BytesMut};
+#[cfg(feature = "json")]
+use serde::de::DeserializeOwned;
+
+use super::runtime::Runtime;
+use crate::header::HeaderMap;
+use crate::{StatusCode, Url, Version};
+
+/// A Response to a submitted `Request`.
+///
+/// The body is read from the network as it is consumed, either with one of
+/// the methods below or through the [`Read`] implementation.
+pub struct Response {
+    // only `None` while a chunk is read on the runtime thread
+    inner: Option<crate::Response>,
+    // the part of the last chunk not read yet
+    buf: Bytes,
+    runtime: Runtime,
+}
+
+impl Response {
+    pub(super) fn new(inner: crate::Response, runtime: Runtime) -> Response {
+        Response {
+            inner: Some(inner),
+            buf: Bytes::new(),
+            runtime,
+        }
+    }
+
+    fn inner(&self) -> &crate::Response {
+        self.inner.as_ref().expect("response body is being read")
+    }
+
+    fn into_inner(mut self) -> crate::Response {
+        self.inner.take().expect("response body is being read")
+    }
+
+    /// Get the `StatusCode` of this `Response`.
+    #[inline]
+    pub fn status(&self) -> StatusCode {
+        self.inner().status()
+    }
+
+    /// Get the HTTP `Version` of this `Response`.
+    #[inline]
+    pub fn version(&self) -> Version {
+        self.inner().version()
+    }
+
+    /// Get the `Headers` of this `Response`.
+    #[inline]
+    pub fn headers(&self) -> &HeaderMap {
+        self.inner().headers()
+    }
+
+    /// Get the final `Url` of this `Response`.
+    #[inline]
+    pub fn url(&self) -> &Url {
+        self.inner().url()
+    }
+
+    /// Get the remote address used to get this `Response`.
+    pub fn remote_addr(&self) -> Option<SocketAddr> {
+        self.inner().remote_addr()
+    }
+
+    /// Get the content length of the response, if it is known.
+    ///
+    /// See [`Response::content_length`](crate::Response::content_length).
+    pub fn content_length(&self) -> Option<u64> {
+        self.inner().content_length()
+    }
+
+    /// Get the next chunk of the response body, or `None` once it has been
+    /// exhausted.
+    pub fn chunk(&mut self) -> crate::Result<Option<Bytes>> {
+        if !self.buf.is_empty() {
+            return Ok(Some(std::mem::take(&mut self.buf)));
+        }
+
+        let mut inner = self.inner.take().expect("response body is being read");
+        let (inner, chunk) = self.runtime.block_on(async move {
+            let chunk = inner.chunk().await;
+            (inner, chunk)
+        });
+        self.inner = Some(inner);
+        chunk
+    }
+
+    /// Get the rest of the response body as `Bytes`.
+    pub fn bytes(mut self) -> crate::Result<Bytes> {
+        let buf = std::mem::take(&mut self.buf);
+        let runtime = self.runtime.clone();
+        let rest = runtime.block_on(self.into_inner().bytes())?;
+        if buf.is_empty() {
+            return Ok(rest);
+        }
+
+        let mut full = BytesMut::with_capacity(buf.len() + rest.len());
+        full.extend_from_slice(&buf);
+        full.extend_from_slice(&rest);
+        Ok(full.freeze())
+    }
+
+    /// Get the rest of the response body decoded as text.
+    ///
+    /// See [`Response::text`](crate::Response::text) for how the encoding is
+    /// chosen. Once part of the body has been read with [`Read`], the rest is
+    /// decoded as UTF-8, replacing invalid sequences.
+    pub fn text(self) -> crate::Result<String> {
+        if !self.buf.is_empty() {
+            let bytes = self.bytes()?;
+            return Ok(String::from_utf8_lossy(&bytes).into_owned());
+        }
+
+        let runtime = self.runtime.clone();
+        runtime.block_on(self.into_inner().text())
+    }
+
+    /// Try to deserialize the rest of the response body as JSON.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever the response body is not in JSON format
+    /// or it cannot be properly deserialized to target type `T`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `json` feature enabled.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn json<T: DeserializeOwned>(self) -> crate::Result<T> {
+        crate::client::response::from_json(&self.bytes()?)
+    }
+
+    /// Copy the rest of the response body into a writer.
+    ///
+    /// Returns the number of bytes copied.
+    pub fn copy_to<W>(&mut self, w: &mut W) -> crate::Result<u64>
+    where
+        W: Write + ?Sized,
+    {
+        io::copy(self, w).map_err(crate::error::decode_io)
+    }
+
+    /// Turn a response into an error if the server returned an error.
+    pub fn error_for_status(self) -> crate::Result<Self> {
+        self.inner().error_for_status_ref()?;
+        Ok(self)
+    }
+
+    /// Turn a reference to a response into an error if the server returned an error.
+    pub fn error_for_status_ref(&self) -> crate::Result<&Self> {
+        self.inner().error_for_status_ref()?;
+        Ok(self)
+    }
+}
+
+impl Read for Response {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.buf.is_empty() {
+            match self.chunk().map_err(crate::Error::into_io)? {
+                Some(chunk) => self.buf = chunk,
+                None => return Ok(0),
+            }
+        }
+
+        let n = buf.len().min(self.buf.len());
+        buf[..n].copy_from_slice(&self.buf[..n]);
+        self.buf.advance(n);
+        Ok(n)
+    }
+}
+
+impl fmt::Debug for Response {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.inner {
+            Some(ref inner) => inner.fmt(f),
+            None => f.debug_struct("Response").finish_non_exhaustive(),
+        }
+    }
+}
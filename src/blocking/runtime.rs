@@ -0,0 +1,112 @@
+use std::future::Future;
+use std::sync::{Arc, mpsc};
+use std::thread::{self, JoinHandle, ThreadId};
+
+use antidote::Mutex;
+use tokio::runtime::Handle;
+use tokio::sync::oneshot;
+
+/// A runtime on a thread of its own, which runs the futures of blocking clients.
+///
+/// Clones share the thread, which stops once the last of them is dropped.
+#[derive(Clone)]
+pub(crate) struct Runtime {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    handle: Handle,
+    thread_id: ThreadId,
+    shutdown: Mutex<Option<oneshot::Sender<()>>>,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Runtime {
+    pub(crate) fn new() -> crate::Result<Runtime> {
+        let (handle_tx, handle_rx) = mpsc::channel();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+        let thread = thread::Builder::new()
+            .name("rquest-blocking".into())
+            .spawn(move || {
+                let rt = match tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                {
+                    Ok(rt) => rt,
+                    Err(err) => {
+                        let _ = handle_tx.send(Err(err));
+                        return;
+                    }
+                };
+                let _ = handle_tx.send(Ok(rt.handle().clone()));
+
+                // drives the spawned requests until every client is dropped
+                let _ = rt.block_on(shutdown_rx);
+                trace!("blocking runtime thread stopped");
+            })
+            .map_err(crate::error::builder)?;
+
+        let handle = match handle_rx.recv() {
+            Ok(Ok(handle)) => handle,
+            Ok(Err(err)) => return Err(crate::error::builder(err)),
+            Err(_) => {
+                return Err(crate::error::builder(
+                    "blocking runtime thread panicked during startup",
+                ));
+            }
+        };
+
+        Ok(Runtime {
+            inner: Arc::new(Inner {
+                handle,
+                thread_id: thread.thread().id(),
+                shutdown: Mutex::new(Some(shutdown_tx)),
+                thread: Mutex::new(Some(thread)),
+            }),
+        })
+    }
+
+    /// Run `f` with the runtime entered, for code that needs a runtime context
+    /// without awaiting anything.
+    pub(crate) fn enter<T>(&self, f: impl FnOnce() -> T) -> T {
+        let _guard = self.inner.handle.enter();
+        f()
+    }
+
+    /// Run `fut` on the runtime thread, blocking the current thread until it
+    /// completes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within an async runtime.
+    pub(crate) fn block_on<F>(&self, fut: F) -> F::Output
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        self.inner.handle.spawn(async move {
+            let _ = tx.send(fut.await);
+        });
+        rx.blocking_recv()
+            .expect("blocking runtime thread stopped while a request was in flight")
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.lock().take() {
+            let _ = shutdown.send(());
+        }
+
+        // the last reference may be dropped by a task on the runtime thread,
+        // which can't wait for itself
+        if thread::current().id() == self.thread_id {
+            return;
+        }
+        if let Some(thread) = self.thread.lock().take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,1037 @@
+//! HTTP Caching
+//!
+//! By default, a `Client` does not cache responses. A [`CacheConfig`] can be
+//! set on a `ClientBuilder` to store responses and reuse them following the
+//! rules of [RFC 9111](https://www.rfc-editor.org/rfc/rfc9111).
+//!
+//! Only responses to `GET` requests are stored, and only once their body has
+//! been read to the end. A stored response is served without contacting the
+//! server while it is fresh. Once it is stale, it is revalidated with a
+//! conditional request using its `ETag` or `Last-Modified` header, and served
+//! again if the server answers `304 Not Modified`.
+//!
+//! Responses are stored as they were received, before any decompression.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, ready};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use antidote::Mutex;
+use bytes::{Bytes, BytesMut};
+use http_body::Body as HttpBody;
+use lru::LruCache;
+use pin_project_lite::pin_project;
+
+use crate::client::body::ResponseBody;
+use crate::error::BoxError;
+use crate::header::{
+    AGE, AUTHORIZATION, CACHE_CONTROL, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_RANGE, DATE, ETAG,
+    EXPIRES, HeaderMap, HeaderName, HeaderValue, IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH,
+    IF_RANGE, IF_UNMODIFIED_SINCE, LAST_MODIFIED, PRAGMA, TRANSFER_ENCODING, VARY,
+};
+use crate::retry::parse_http_date;
+use crate::{Method, StatusCode, Url, Version};
+
+/// Configuration of the HTTP cache of a `Client`.
+///
+/// # Example
+///
+/// ```rust
+/// use rquest::cache::CacheConfig;
+///
+/// let client = rquest::Client::builder()
+///     .cache(CacheConfig::memory(512))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct CacheConfig {
+    storage: Arc<dyn CacheStorage>,
+    shared: bool,
+    max_entry_size: usize,
+}
+
+/// A place to keep cached responses.
+///
+/// Implement this trait to store responses in a custom backend. Responses can
+/// be serialized with [`CachedResponse::to_bytes`] and restored with
+/// [`CachedResponse::from_bytes`].
+pub trait CacheStorage: Send + Sync {
+    /// Get the response stored under `key`.
+    fn get(&self, key: &str) -> Option<CachedResponse>;
+
+    /// Store `response` under `key`, replacing any previous one.
+    fn put(&self, key: &str, response: CachedResponse);
+
+    /// Remove the response stored under `key`.
+    fn remove(&self, key: &str);
+}
+
+/// A response stored in the cache.
+#[derive(Clone, Debug)]
+pub struct CachedResponse {
+    status: StatusCode,
+    version: Version,
+    headers: HeaderMap,
+    body: Bytes,
+    // the request headers named by the `Vary` header of the response
+    vary: HeaderMap,
+    request_time: SystemTime,
+    response_time: SystemTime,
+}
+
+/// A [`CacheStorage`] that keeps responses in memory.
+///
+/// Once `capacity` responses are stored, the least recently used one is evicted.
+pub struct MemoryCache {
+    entries: Mutex<LruCache<String, CachedResponse>>,
+}
+
+/// A [`CacheStorage`] that keeps responses in files of a directory.
+///
+/// Each response is stored in its own file, named after a hash of its key. The
+/// directory is created when the first response is stored. Files are read and
+/// written with blocking I/O.
+#[derive(Debug)]
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+/// The result of looking up a request in the cache.
+pub(crate) enum Lookup {
+    /// No usable response is stored.
+    Miss,
+    /// A stored response can be served as is.
+    Fresh(CachedResponse),
+    /// A stored response must be revalidated first.
+    Stale(CachedResponse),
+}
+
+#[derive(Default)]
+struct Directives {
+    no_store: bool,
+    no_cache: bool,
+    private: bool,
+    public: bool,
+    must_revalidate: bool,
+    proxy_revalidate: bool,
+    max_age: Option<Duration>,
+    s_maxage: Option<Duration>,
+    min_fresh: Option<Duration>,
+    max_stale: Option<Duration>,
+}
+
+pin_project! {
+    /// A response body that stores its response once it has been read to the end.
+    struct CacheBody {
+        #[pin]
+        inner: ResponseBody,
+        buf: BytesMut,
+        limit: usize,
+        entry: Option<CachedResponse>,
+        storage: Arc<dyn CacheStorage>,
+        key: String,
+    }
+}
+
+// ===== impl CacheConfig =====
+
+impl CacheConfig {
+    /// Create a cache that keeps responses in `storage`.
+    pub fn new<S>(storage: S) -> CacheConfig
+    where
+        S: CacheStorage + 'static,
+    {
+        CacheConfig {
+            storage: Arc::new(storage),
+            shared: false,
+            max_entry_size: 8 * 1024 * 1024,
+        }
+    }
+
+    /// Create a cache that keeps up to `capacity` responses in memory.
+    pub fn memory(capacity: usize) -> CacheConfig {
+        CacheConfig::new(MemoryCache::new(capacity))
+    }
+
+    /// Create a cache that keeps responses in files of the directory `dir`.
+    pub fn disk<P: Into<PathBuf>>(dir: P) -> CacheConfig {
+        CacheConfig::new(DiskCache::new(dir))
+    }
+
+    /// Set whether the cache is shared between users.
+    ///
+    /// A shared cache, such as one in a proxy, doesn't store responses marked
+    /// `private` or responses to requests with an `Authorization` header, and
+    /// honors `s-maxage`.
+    ///
+    /// Default is `false`, like the cache of a browser.
+    pub fn shared(mut self, shared: bool) -> CacheConfig {
+        self.shared = shared;
+        self
+    }
+
+    /// Set the largest response body that will be stored, in bytes.
+    ///
+    /// Default is 8 MiB.
+    pub fn max_entry_size(mut self, max: usize) -> CacheConfig {
+        self.max_entry_size = max;
+        self
+    }
+
+    /// Look up a stored response for a request.
+    pub(crate) fn lookup(&self, method: &Method, url: &Url, headers: &HeaderMap) -> Lookup {
+        if *method != Method::GET || is_conditional(headers) {
+            return Lookup::Miss;
+        }
+
+        let req = Directives::request(headers);
+        if req.no_store {
+            return Lookup::Miss;
+        }
+
+        let entry = match self.storage.get(&key(url)) {
+            Some(entry) if entry.matches(headers) => entry,
+            _ => return Lookup::Miss,
+        };
+
+        let res = Directives::parse(&entry.headers);
+        let age = entry.age(SystemTime::now());
+        let lifetime = self.freshness_lifetime(&entry, &res);
+
+        let fresh =
+            if req.no_cache || res.no_cache || req.max_age.is_some_and(|max_age| age > max_age) {
+                false
+            } else if age < lifetime {
+                lifetime - age > req.min_fresh.unwrap_or_default()
+            } else {
+                // serving stale responses is only allowed if the request asks for it
+                let revalidate = res.must_revalidate || (self.shared && res.proxy_revalidate);
+                !revalidate
+                    && req
+                        .max_stale
+                        .is_some_and(|max_stale| age - lifetime <= max_stale)
+            };
+
+        if fresh {
+            Lookup::Fresh(entry)
+        } else if entry.has_validators() {
+            Lookup::Stale(entry)
+        } else {
+            Lookup::Miss
+        }
+    }
+
+    /// Update the cache with a response, returning the response to give to the caller.
+    ///
+    /// `stale` is the response that the request revalidates, if any.
+    pub(crate) fn on_response(
+        &self,
+        method: &Method,
+        url: &Url,
+        req_headers: &HeaderMap,
+        stale: Option<CachedResponse>,
+        request_time: SystemTime,
+        res: http::Response<ResponseBody>,
+    ) -> http::Response<ResponseBody> {
+        let key = key(url);
+        let status = res.status();
+        let response_time = SystemTime::now();
+
+        // unsafe requests that succeed invalidate the stored response
+        if !is_safe(method) {
+            if status.is_success() || status.is_redirection() {
+                self.storage.remove(&key);
+            }
+            return res;
+        }
+
+        if status == StatusCode::NOT_MODIFIED {
+            if let Some(mut entry) = stale {
+                entry.refresh(res.headers(), request_time, response_time);
+                self.storage.put(&key, entry.clone());
+                return entry.into_response();
+            }
+            return res;
+        }
+
+        if *method != Method::GET || !self.storable(req_headers, status, res.headers()) {
+            return res;
+        }
+
+        let (parts, body) = res.into_parts();
+        let entry = CachedResponse {
+            status,
+            version: parts.version,
+            headers: parts.headers.clone(),
+            body: Bytes::new(),
+            vary: vary(req_headers, &parts.headers),
+            request_time,
+            response_time,
+        };
+
+        let body = CacheBody {
+            inner: body,
+            buf: BytesMut::new(),
+            limit: self.max_entry_size,
+            entry: Some(entry),
+            storage: self.storage.clone(),
+            key,
+        };
+
+        http::Response::from_parts(parts, crate::client::body::boxed(body))
+    }
+
+    /// Whether a response may be stored (RFC 9111, section 3).
+    fn storable(&self, req_headers: &HeaderMap, status: StatusCode, headers: &HeaderMap) -> bool {
+        let req = Directives::request(req_headers);
+        let res = Directives::parse(headers);
+
+        if req.no_store || res.no_store {
+            return false;
+        }
+
+        if self.shared {
+            if res.private {
+                return false;
+            }
+            if req_headers.contains_key(AUTHORIZATION)
+                && !(res.public || res.s_maxage.is_some() || res.must_revalidate)
+            {
+                return false;
+            }
+        }
+
+        // partial and interim responses are not stored
+        if status.is_informational()
+            || status == StatusCode::PARTIAL_CONTENT
+            || status == StatusCode::NOT_MODIFIED
+        {
+            return false;
+        }
+
+        if vary_names(headers).any(|name| name.is_none()) {
+            return false;
+        }
+
+        let explicit = res.max_age.is_some()
+            || (self.shared && res.s_maxage.is_some())
+            || headers.contains_key(EXPIRES)
+            || res.public;
+        let validators = headers.contains_key(ETAG) || headers.contains_key(LAST_MODIFIED);
+
+        explicit || (heuristically_cacheable(status) && validators)
+    }
+
+    /// How long a response stays fresh (RFC 9111, section 4.2.1).
+    fn freshness_lifetime(&self, entry: &CachedResponse, res: &Directives) -> Duration {
+        if self.shared {
+            if let Some(s_maxage) = res.s_maxage {
+                return s_maxage;
+            }
+        }
+
+        if let Some(max_age) = res.max_age {
+            return max_age;
+        }
+
+        let date = header_date(&entry.headers, &DATE).unwrap_or(entry.response_time);
+
+        // an invalid `Expires` means the response has already expired
+        if entry.headers.contains_key(EXPIRES) {
+            return header_date(&entry.headers, &EXPIRES)
+                .and_then(|expires| expires.duration_since(date).ok())
+                .unwrap_or_default();
+        }
+
+        // heuristic freshness: a tenth of the time since the last modification
+        match header_date(&entry.headers, &LAST_MODIFIED) {
+            Some(last_modified) if res.public || heuristically_cacheable(entry.status) => {
+                date.duration_since(last_modified).unwrap_or_default() / 10
+            }
+            _ => Duration::ZERO,
+        }
+    }
+}
+
+impl fmt::Debug for CacheConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CacheConfig")
+            .field("shared", &self.shared)
+            .field("max_entry_size", &self.max_entry_size)
+            .finish()
+    }
+}
+
+// ===== impl CachedResponse =====
+
+impl CachedResponse {
+    /// Get the status of the response.
+    #[inline]
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Get the headers of the response.
+    #[inline]
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Get the body of the response, as it was received.
+    #[inline]
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Serialize the response, e.g. to write it to a file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.body.len() + 512);
+        buf.extend_from_slice(
+            format!(
+                "{} {:?} {} {}\n",
+                self.status.as_u16(),
+                self.version,
+                millis(self.request_time),
+                millis(self.response_time),
+            )
+            .as_bytes(),
+        );
+
+        for headers in [&self.headers, &self.vary] {
+            for (name, value) in headers {
+                buf.extend_from_slice(name.as_str().as_bytes());
+                buf.extend_from_slice(b": ");
+                buf.extend_from_slice(value.as_bytes());
+                buf.push(b'\n');
+            }
+            buf.push(b'\n');
+        }
+
+        buf.extend_from_slice(&self.body);
+        buf
+    }
+
+    /// Deserialize a response serialized with [`CachedResponse::to_bytes`].
+    ///
+    /// Returns `None` if `buf` isn't a valid serialized response.
+    pub fn from_bytes(mut buf: &[u8]) -> Option<CachedResponse> {
+        let first = std::str::from_utf8(next_line(&mut buf)?).ok()?;
+        let mut parts = first.split(' ');
+        let status = StatusCode::from_bytes(parts.next()?.as_bytes()).ok()?;
+        let version = match parts.next()? {
+            "HTTP/0.9" => Version::HTTP_09,
+            "HTTP/1.0" => Version::HTTP_10,
+            "HTTP/1.1" => Version::HTTP_11,
+            "HTTP/2.0" => Version::HTTP_2,
+            "HTTP/3.0" => Version::HTTP_3,
+            _ => return None,
+        };
+        let request_time = UNIX_EPOCH + Duration::from_millis(parts.next()?.parse().ok()?);
+        let response_time = UNIX_EPOCH + Duration::from_millis(parts.next()?.parse().ok()?);
+
+        let headers = read_headers(&mut buf)?;
+        let vary = read_headers(&mut buf)?;
+
+        Some(CachedResponse {
+            status,
+            version,
+            headers,
+            body: Bytes::copy_from_slice(buf),
+            vary,
+            request_time,
+            response_time,
+        })
+    }
+
+    /// Add the headers that make a request revalidate this response.
+    pub(crate) fn set_conditions(&self, headers: &mut HeaderMap) {
+        if let Some(etag) = self.headers.get(ETAG) {
+            headers.insert(IF_NONE_MATCH, etag.clone());
+        }
+        if let Some(last_modified) = self.headers.get(LAST_MODIFIED) {
+            headers.insert(IF_MODIFIED_SINCE, last_modified.clone());
+        }
+    }
+
+    /// Remove the headers added by [`CachedResponse::set_conditions`].
+    pub(crate) fn clear_conditions(&self, headers: &mut HeaderMap) {
+        headers.remove(IF_NONE_MATCH);
+        headers.remove(IF_MODIFIED_SINCE);
+    }
+
+    /// Turn the stored response into a response to give to the caller.
+    pub(crate) fn into_response(self) -> http::Response<ResponseBody> {
+        let age = self.age(SystemTime::now());
+
+        let mut res = http::Response::new(crate::client::body::boxed(crate::Body::from(self.body)));
+        *res.status_mut() = self.status;
+        *res.version_mut() = self.version;
+        *res.headers_mut() = self.headers;
+        res.headers_mut()
+            .insert(AGE, HeaderValue::from(age.as_secs()));
+        res
+    }
+
+    fn has_validators(&self) -> bool {
+        self.headers.contains_key(ETAG) || self.headers.contains_key(LAST_MODIFIED)
+    }
+
+    /// Whether a request selects this response according to its `Vary` header.
+    fn matches(&self, headers: &HeaderMap) -> bool {
+        vary_names(&self.headers).all(|name| match name {
+            Some(name) => headers
+                .get_all(&name)
+                .iter()
+                .eq(self.vary.get_all(&name).iter()),
+            None => false,
+        })
+    }
+
+    /// The current age of the response (RFC 9111, section 4.2.3).
+    fn age(&self, now: SystemTime) -> Duration {
+        let age_value = self
+            .headers
+            .get(AGE)
+            .and_then(|age| age.to_str().ok())
+            .and_then(|age| age.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or_default();
+        let date = header_date(&self.headers, &DATE).unwrap_or(self.response_time);
+
+        let apparent_age = self.response_time.duration_since(date).unwrap_or_default();
+        let response_delay = self
+            .response_time
+            .duration_since(self.request_time)
+            .unwrap_or_default();
+        let initial_age = apparent_age.max(age_value + response_delay);
+        let resident_time = now.duration_since(self.response_time).unwrap_or_default();
+
+        initial_age + resident_time
+    }
+
+    /// Update the response with the headers of a `304 Not Modified` response.
+    fn refresh(
+        &mut self,
+        headers: &HeaderMap,
+        request_time: SystemTime,
+        response_time: SystemTime,
+    ) {
+        for name in headers.keys() {
+            if *name == CONTENT_LENGTH
+                || *name == CONTENT_ENCODING
+                || *name == CONTENT_RANGE
+                || *name == TRANSFER_ENCODING
+            {
+                continue;
+            }
+            self.headers.remove(name);
+            for value in headers.get_all(name) {
+                self.headers.append(name.clone(), value.clone());
+            }
+        }
+        self.request_time = request_time;
+        self.response_time = response_time;
+    }
+}
+
+// ===== impl MemoryCache =====
+
+impl MemoryCache {
+    /// Create a cache that keeps up to `capacity` responses.
+    pub fn new(capacity: usize) -> MemoryCache {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        MemoryCache {
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+}
+
+impl CacheStorage for MemoryCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.entries.lock().get(key).cloned()
+    }
+
+    fn put(&self, key: &str, response: CachedResponse) {
+        self.entries.lock().put(key.to_owned(), response);
+    }
+
+    fn remove(&self, key: &str) {
+        self.entries.lock().pop(key);
+    }
+}
+
+impl fmt::Debug for MemoryCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let entries = self.entries.lock();
+        f.debug_struct("MemoryCache")
+            .field("len", &entries.len())
+            .field("capacity", &entries.cap())
+            .finish()
+    }
+}
+
+// ===== impl DiskCache =====
+
+impl DiskCache {
+    /// Create a cache that keeps responses in files of the directory `dir`.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> DiskCache {
+        DiskCache { dir: dir.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        // FNV-1a, which is stable across runs and platforms
+        let hash = key.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+        self.dir.join(format!("{:016x}", hash))
+    }
+
+    fn write(&self, key: &str, response: &CachedResponse) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        let mut buf = Vec::with_capacity(key.len() + 1);
+        buf.extend_from_slice(key.as_bytes());
+        buf.push(b'\n');
+        buf.extend_from_slice(&response.to_bytes());
+
+        // write to a temporary file first, so readers never see a partial entry
+        let path = self.path(key);
+        let tmp = path.with_extension(format!("{:x}.tmp", crate::util::fast_random()));
+        fs::write(&tmp, buf)?;
+        fs::rename(&tmp, &path).inspect_err(|_| {
+            let _ = fs::remove_file(&tmp);
+        })
+    }
+}
+
+impl CacheStorage for DiskCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let buf = fs::read(self.path(key)).ok()?;
+        let mut buf = &buf[..];
+
+        // the file may belong to another key with the same hash
+        if next_line(&mut buf)? != key.as_bytes() {
+            return None;
+        }
+        CachedResponse::from_bytes(buf)
+    }
+
+    fn put(&self, key: &str, response: CachedResponse) {
+        if let Err(_err) = self.write(key, &response) {
+            debug!("failed to store cached response for {}: {}", key, _err);
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        match fs::remove_file(self.path(key)) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(_err) => debug!("failed to remove cached response for {}: {}", key, _err),
+        }
+    }
+}
+
+// ===== impl Directives =====
+
+impl Directives {
+    /// Parse the `Cache-Control` directives of a message.
+    fn parse(headers: &HeaderMap) -> Directives {
+        let mut directives = Directives::default();
+
+        let values = headers
+            .get_all(CACHE_CONTROL)
+            .iter()
+            .filter_map(|value| value.to_str().ok());
+
+        for directive in values.flat_map(|value| value.split(',')) {
+            let (name, arg) = match directive.split_once('=') {
+                Some((name, arg)) => (name.trim(), Some(arg.trim().trim_matches('"'))),
+                None => (directive.trim(), None),
+            };
+            let secs = || {
+                arg.and_then(|arg| arg.parse().ok())
+                    .map(Duration::from_secs)
+            };
+
+            match name.to_ascii_lowercase().as_str() {
+                "no-store" => directives.no_store = true,
+                "no-cache" => directives.no_cache = true,
+                "private" => directives.private = true,
+                "public" => directives.public = true,
+                "must-revalidate" => directives.must_revalidate = true,
+                "proxy-revalidate" => directives.proxy_revalidate = true,
+                "max-age" => directives.max_age = secs(),
+                "s-maxage" => directives.s_maxage = secs(),
+                "min-fresh" => directives.min_fresh = secs(),
+                "max-stale" => directives.max_stale = Some(secs().unwrap_or(Duration::MAX)),
+                _ => {}
+            }
+        }
+
+        directives
+    }
+
+    /// Parse the directives of a request, which may use `Pragma: no-cache` instead.
+    fn request(headers: &HeaderMap) -> Directives {
+        let mut directives = Directives::parse(headers);
+        if !headers.contains_key(CACHE_CONTROL) {
+            directives.no_cache = headers
+                .get_all(PRAGMA)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .any(|value| {
+                    value
+                        .split(',')
+                        .any(|p| p.trim().eq_ignore_ascii_case("no-cache"))
+                });
+        }
+        directives
+    }
+}
+
+// ===== impl CacheBody =====
+
+impl HttpBody for CacheBody {
+    type Data = Bytes;
+    type Error = BoxError;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        let mut this = self.project();
+        let item = ready!(this.inner.as_mut().poll_frame(cx));
+
+        match item {
+            Some(Ok(ref frame)) => {
+                if let (Some(data), true) = (frame.data_ref(), this.entry.is_some()) {
+                    if this.buf.len() + data.len() > *this.limit {
+                        debug!("response for {} is too large to cache", this.key);
+                        *this.entry = None;
+                        *this.buf = BytesMut::new();
+                    } else {
+                        this.buf.extend_from_slice(data);
+                    }
+                }
+            }
+            Some(Err(_)) => *this.entry = None,
+            None => {}
+        }
+
+        let done = match item {
+            Some(Ok(_)) => this.inner.is_end_stream(),
+            Some(Err(_)) => false,
+            None => true,
+        };
+
+        // the body has been read to the end
+        if let Some(mut entry) = this.entry.take_if(|_| done) {
+            entry.body = this.buf.split().freeze();
+            this.storage.put(this.key, entry);
+        }
+        Poll::Ready(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.inner.size_hint()
+    }
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+}
+
+/// The key a response to a request for `url` is stored under.
+fn key(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url.into()
+}
+
+fn is_safe(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+    )
+}
+
+fn is_conditional(headers: &HeaderMap) -> bool {
+    [
+        IF_MATCH,
+        IF_NONE_MATCH,
+        IF_MODIFIED_SINCE,
+        IF_UNMODIFIED_SINCE,
+        IF_RANGE,
+    ]
+    .iter()
+    .any(|name| headers.contains_key(name))
+}
+
+/// Statuses that can be cached without explicit freshness (RFC 9110, section 15.1).
+fn heuristically_cacheable(status: StatusCode) -> bool {
+    matches!(
+        status.as_u16(),
+        200 | 203 | 204 | 300 | 301 | 308 | 404 | 405 | 410 | 414 | 501
+    )
+}
+
+/// The header names listed by the `Vary` header, with `None` for `*`.
+fn vary_names(headers: &HeaderMap) -> impl Iterator<Item = Option<HeaderName>> + '_ {
+    headers
+        .get_all(VARY)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| match name {
+            "*" => None,
+            name => HeaderName::from_bytes(name.as_bytes()).ok(),
+        })
+}
+
+/// Select the request headers named by the `Vary` header of a response.
+fn vary(req_headers: &HeaderMap, res_headers: &HeaderMap) -> HeaderMap {
+    let mut vary = HeaderMap::new();
+    for name in vary_names(res_headers).flatten() {
+        for value in req_headers.get_all(&name) {
+            vary.append(name.clone(), value.clone());
+        }
+    }
+    vary
+}
+
+fn header_date(headers: &HeaderMap, name: &HeaderName) -> Option<SystemTime> {
+    parse_http_date(headers.get(name)?.to_str().ok()?.trim())
+}
+
+fn millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_millis() as u64)
+}
+
+fn next_line<'a>(buf: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let end = buf.iter().position(|&b| b == b'\n')?;
+    let line = &buf[..end];
+    *buf = &buf[end + 1..];
+    Some(line)
+}
+
+fn read_headers(buf: &mut &[u8]) -> Option<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    loop {
+        let line = next_line(buf)?;
+        if line.is_empty() {
+            return Some(headers);
+        }
+        let split = line.windows(2).position(|w| w == b": ")?;
+        let name = HeaderName::from_bytes(&line[..split]).ok()?;
+        let value = HeaderValue::from_bytes(&line[split + 2..]).ok()?;
+        headers.append(name, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(headers: &[(&str, &str)]) -> CachedResponse {
+        let now = SystemTime::now();
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.append(
+                HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                HeaderValue::from_str(value).unwrap(),
+            );
+        }
+        CachedResponse {
+            status: StatusCode::OK,
+            version: Version::HTTP_11,
+            headers: map,
+            body: Bytes::from_static(b"hello"),
+            vary: HeaderMap::new(),
+            request_time: now,
+            response_time: now,
+        }
+    }
+
+    fn lookup(cache: &CacheConfig, headers: &HeaderMap) -> &'static str {
+        let url = "http://example.local/".parse().unwrap();
+        match cache.lookup(&Method::GET, &url, headers) {
+            Lookup::Miss => "miss",
+            Lookup::Fresh(_) => "fresh",
+            Lookup::Stale(_) => "stale",
+        }
+    }
+
+    fn cache_with(entry: CachedResponse) -> CacheConfig {
+        let cache = CacheConfig::memory(8);
+        cache.storage.put("http://example.local/", entry);
+        cache
+    }
+
+    #[test]
+    fn serialization_roundtrip() {
+        let mut entry = response(&[("etag", "\"v1\""), ("vary", "accept")]);
+        entry
+            .vary
+            .insert(crate::header::ACCEPT, HeaderValue::from_static("text/html"));
+
+        let decoded = CachedResponse::from_bytes(&entry.to_bytes()).unwrap();
+        assert_eq!(decoded.status, entry.status);
+        assert_eq!(decoded.version, entry.version);
+        assert_eq!(decoded.headers, entry.headers);
+        assert_eq!(decoded.vary, entry.vary);
+        assert_eq!(decoded.body, entry.body);
+        assert_eq!(millis(decoded.response_time), millis(entry.response_time));
+
+        assert!(CachedResponse::from_bytes(b"200 HTTP/1.1 0\n").is_none());
+    }
+
+    #[test]
+    fn freshness() {
+        let headers = HeaderMap::new();
+        assert_eq!(lookup(&CacheConfig::memory(8), &headers), "miss");
+
+        let cache = cache_with(response(&[("cache-control", "max-age=60")]));
+        assert_eq!(lookup(&cache, &headers), "fresh");
+
+        let cache = cache_with(response(&[("cache-control", "max-age=0")]));
+        assert_eq!(lookup(&cache, &headers), "miss");
+
+        let cache = cache_with(response(&[
+            ("cache-control", "max-age=0"),
+            ("etag", "\"v1\""),
+        ]));
+        assert_eq!(lookup(&cache, &headers), "stale");
+
+        let cache = cache_with(response(&[
+            ("cache-control", "no-cache"),
+            ("etag", "\"v1\""),
+        ]));
+        assert_eq!(lookup(&cache, &headers), "stale");
+
+        let cache = cache_with(response(&[
+            ("expires", "Thu, 01 Jan 1970 00:00:00 GMT"),
+            ("last-modified", "Thu, 01 Jan 1970 00:00:00 GMT"),
+        ]));
+        assert_eq!(lookup(&cache, &headers), "stale");
+    }
+
+    #[test]
+    fn request_directives() {
+        let cache = cache_with(response(&[
+            ("cache-control", "max-age=60"),
+            ("etag", "\"v1\""),
+            ("age", "30"),
+        ]));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=10"));
+        assert_eq!(lookup(&cache, &headers), "stale");
+
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("min-fresh=45"));
+        assert_eq!(lookup(&cache, &headers), "stale");
+
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+        assert_eq!(lookup(&cache, &headers), "miss");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(PRAGMA, HeaderValue::from_static("no-cache"));
+        assert_eq!(lookup(&cache, &headers), "stale");
+
+        let cache = cache_with(response(&[("cache-control", "max-age=10"), ("age", "30")]));
+        let mut headers = HeaderMap::new();
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-stale=60"));
+        assert_eq!(lookup(&cache, &headers), "fresh");
+    }
+
+    #[test]
+    fn vary_selects_response() {
+        let mut entry = response(&[("cache-control", "max-age=60"), ("vary", "Accept-Language")]);
+        entry.vary.insert(
+            crate::header::ACCEPT_LANGUAGE,
+            HeaderValue::from_static("en"),
+        );
+        let cache = cache_with(entry);
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(lookup(&cache, &headers), "miss");
+
+        headers.insert(
+            crate::header::ACCEPT_LANGUAGE,
+            HeaderValue::from_static("en"),
+        );
+        assert_eq!(lookup(&cache, &headers), "fresh");
+
+        headers.insert(
+            crate::header::ACCEPT_LANGUAGE,
+            HeaderValue::from_static("fr"),
+        );
+        assert_eq!(lookup(&cache, &headers), "miss");
+    }
+
+    #[test]
+    fn storable_responses() {
+        let cache = CacheConfig::memory(8);
+        let shared = CacheConfig::memory(8).shared(true);
+        let headers = |pairs: &[(&str, &str)]| response(pairs).headers;
+        let req = HeaderMap::new();
+
+        assert!(cache.storable(
+            &req,
+            StatusCode::OK,
+            &headers(&[("cache-control", "max-age=60")])
+        ));
+        assert!(cache.storable(&req, StatusCode::OK, &headers(&[("etag", "\"v1\"")])));
+        assert!(!cache.storable(&req, StatusCode::OK, &headers(&[])));
+        assert!(!cache.storable(
+            &req,
+            StatusCode::OK,
+            &headers(&[("cache-control", "no-store, max-age=60")])
+        ));
+        assert!(!cache.storable(
+            &req,
+            StatusCode::OK,
+            &headers(&[("cache-control", "max-age=60"), ("vary", "*")])
+        ));
+        assert!(!cache.storable(
+            &req,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            &headers(&[("etag", "\"v1\"")])
+        ));
+
+        let private = headers(&[("cache-control", "private, max-age=60")]);
+        assert!(cache.storable(&req, StatusCode::OK, &private));
+        assert!(!shared.storable(&req, StatusCode::OK, &private));
+    }
+
+    #[test]
+    fn disk_cache() {
+        let dir =
+            std::env::temp_dir().join(format!("rquest-cache-{:x}", crate::util::fast_random()));
+        let cache = DiskCache::new(&dir);
+
+        assert!(cache.get("http://example.local/").is_none());
+        cache.put("http://example.local/", response(&[("etag", "\"v1\"")]));
+
+        let entry = cache.get("http://example.local/").unwrap();
+        assert_eq!(entry.headers()[ETAG], "\"v1\"");
+        assert_eq!(entry.body(), "hello");
+        assert!(cache.get("http://example.local/other").is_none());
+
+        cache.remove("http://example.local/");
+        assert!(cache.get("http://example.local/").is_none());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
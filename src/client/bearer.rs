@@ -0,0 +1,204 @@
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use antidote::Mutex;
+use futures_channel::oneshot;
+
+/// A future resolving to a bearer token.
+pub type TokenFuture<'a> = Pin<Box<dyn Future<Output = String> + Send + 'a>>;
+
+/// Provides the bearer tokens a `Client` authenticates its requests with.
+///
+/// The client keeps the token and sends it in an `Authorization: Bearer`
+/// header with every request that has no `Authorization` header of its own.
+/// It calls [`token`](TokenProvider::token) for the first request, and
+/// [`refresh`](TokenProvider::refresh) when a request with the token is
+/// answered with `401 Unauthorized`, then sends that request once more with
+/// the new token. Requests rejected at the same time wait for a single
+/// refresh.
+///
+/// # Example
+///
+/// ```
+/// use rquest::{TokenFuture, TokenProvider};
+///
+/// struct ClientCredentials {
+///     http: rquest::Client,
+/// }
+///
+/// impl TokenProvider for ClientCredentials {
+///     fn token(&self) -> TokenFuture<'_> {
+///         Box::pin(async move {
+///             let res = self
+///                 .http
+///                 .post("https://auth.example.com/oauth2/token")
+///                 .form(&[("grant_type", "client_credentials")])
+///                 .send()
+///                 .await;
+///             match res {
+///                 Ok(res) => res.text().await.unwrap_or_default(),
+///                 Err(_) => String::new(),
+///             }
+///         })
+///     }
+/// }
+/// ```
+pub trait TokenProvider: Send + Sync {
+    /// Get a token.
+    fn token(&self) -> TokenFuture<'_>;
+
+    /// Get a new token, after the previous one was rejected.
+    ///
+    /// By default, this calls [`token`](TokenProvider::token).
+    fn refresh(&self) -> TokenFuture<'_> {
+        self.token()
+    }
+}
+
+/// The token of a `Client`, fetched from its `TokenProvider` by one request
+/// at a time.
+pub(crate) struct BearerAuth {
+    provider: Arc<dyn TokenProvider>,
+    state: Mutex<State>,
+}
+
+struct State {
+    token: Option<String>,
+    /// The requests waiting on the token being fetched, if one is.
+    waiters: Option<Vec<oneshot::Sender<String>>>,
+}
+
+impl BearerAuth {
+    pub(crate) fn new(provider: Arc<dyn TokenProvider>) -> BearerAuth {
+        BearerAuth {
+            provider,
+            state: Mutex::new(State {
+                token: None,
+                waiters: None,
+            }),
+        }
+    }
+
+    /// Get the current token, fetching one if there is none yet.
+    pub(crate) async fn token(&self) -> String {
+        self.fetch(None).await
+    }
+
+    /// Get a token other than `rejected`, refreshing it unless another request
+    /// already did.
+    pub(crate) async fn refresh(&self, rejected: &str) -> String {
+        self.fetch(Some(rejected)).await
+    }
+
+    async fn fetch(&self, rejected: Option<&str>) -> String {
+        loop {
+            let waiting = {
+                let mut state = self.state.lock();
+                match state.token {
+                    Some(ref token) if Some(token.as_str()) != rejected => return token.clone(),
+                    _ => {}
+                }
+                match state.waiters {
+                    Some(ref mut waiters) => {
+                        let (tx, rx) = oneshot::channel();
+                        waiters.push(tx);
+                        Some(rx)
+                    }
+                    None => {
+                        state.waiters = Some(Vec::new());
+                        None
+                    }
+                }
+            };
+
+            match waiting {
+                Some(rx) => match rx.await {
+                    Ok(token) => return token,
+                    // the request fetching the token was dropped, so take over
+                    Err(_) => continue,
+                },
+                None => {
+                    let fetching = Fetching(&self.state);
+                    let token = match rejected {
+                        Some(_) => self.provider.refresh().await,
+                        None => self.provider.token().await,
+                    };
+
+                    let mut state = self.state.lock();
+                    state.token = Some(token.clone());
+                    for waiter in state.waiters.take().into_iter().flatten() {
+                        let _ = waiter.send(token.clone());
+                    }
+                    drop(state);
+                    drop(fetching);
+                    return token;
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for BearerAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BearerAuth").finish()
+    }
+}
+
+/// Releases the waiters if the request fetching the token is dropped.
+struct Fetching<'a>(&'a Mutex<State>);
+
+impl Drop for Fetching<'_> {
+    fn drop(&mut self) {
+        self.0.lock().waiters = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Counter {
+        tokens: AtomicUsize,
+        refreshes: AtomicUsize,
+    }
+
+    impl TokenProvider for Counter {
+        fn token(&self) -> TokenFuture<'_> {
+            let n = self.tokens.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move { format!("token-{}", n) })
+        }
+
+        fn refresh(&self) -> TokenFuture<'_> {
+            let n = self.refreshes.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move {
+                tokio::task::yield_now().await;
+                format!("refreshed-{}", n)
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn caches_and_refreshes_once() {
+        let provider = Arc::new(Counter::default());
+        let auth = BearerAuth::new(provider.clone());
+
+        assert_eq!(auth.token().await, "token-0");
+        assert_eq!(auth.token().await, "token-0");
+
+        let (a, b) = tokio::join!(auth.refresh("token-0"), auth.refresh("token-0"));
+        assert_eq!(a, "refreshed-0");
+        assert_eq!(b, "refreshed-0");
+        assert_eq!(auth.token().await, "refreshed-0");
+
+        // a request rejected with a token refreshed since gets the new one
+        assert_eq!(auth.refresh("token-0").await, "refreshed-0");
+
+        assert_eq!(provider.tokens.load(Ordering::SeqCst), 1);
+        assert_eq!(provider.refreshes.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,24 +1,37 @@
 use std::fmt;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::task::{Context, Poll, ready};
 use std::time::Duration;
 
 use bytes::Bytes;
+use futures_channel::{mpsc, oneshot};
+use futures_util::task::AtomicWaker;
+use http::HeaderMap;
 use http_body::Body as HttpBody;
 use http_body_util::combinators::BoxBody;
 use pin_project_lite::pin_project;
+use sync_wrapper::SyncWrapper;
 #[cfg(feature = "stream")]
 use tokio::fs::File;
-use tokio::time::Sleep;
 #[cfg(feature = "stream")]
 use tokio_util::io::ReaderStream;
 
+use crate::core::ext::OnInformational;
+use crate::core::rt::{Sleep, Timer};
+use crate::rt::RuntimeTimer;
+
 /// An asynchronous request body.
 pub struct Body {
     inner: Inner,
+    replay: Option<Replay>,
 }
 
+/// Creates a streaming body again, so it can be sent more than once.
+type Replay = Arc<dyn Fn() -> Body + Send + Sync>;
+
 enum Inner {
     Reusable(Bytes),
     Streaming(BoxBody<Bytes, Box<dyn std::error::Error + Send + Sync>>),
@@ -32,7 +45,7 @@ pin_project! {
     pub(crate) struct TotalTimeoutBody<B> {
         #[pin]
         inner: B,
-        timeout: Pin<Box<Sleep>>,
+        timeout: Pin<Box<dyn Sleep>>,
     }
 }
 
@@ -40,12 +53,136 @@ pin_project! {
     pub(crate) struct ReadTimeoutBody<B> {
         #[pin]
         inner: B,
+        sleep: Option<Pin<Box<dyn Sleep>>>,
+        timeout: ReadTimeout,
+    }
+}
+
+/// The read timeout of a response body, with the timer of the client that
+/// received it.
+#[derive(Clone)]
+pub(crate) struct ReadTimeout {
+    timeout: Duration,
+    timer: RuntimeTimer,
+}
+
+pin_project! {
+    /// A body that yields an already buffered prefix before the frames of `inner`.
+    pub(crate) struct PrefixedBody<B> {
+        prefix: Option<Bytes>,
+        #[pin]
+        inner: B,
+    }
+}
+
+pin_project! {
+    /// A body that reports how many bytes of `inner` have been transferred.
+    pub(crate) struct ProgressBody<B> {
+        #[pin]
+        inner: B,
+        progress: Progress,
+        transferred: u64,
+        total: Option<u64>,
+    }
+}
+
+pin_project! {
+    /// A body held back until the server answers `100 Continue`, or `timeout`
+    /// has elapsed since it was first polled.
+    pub(crate) struct ContinueBody<B> {
         #[pin]
-        sleep: Option<Sleep>,
+        inner: B,
+        received: Arc<Continue>,
+        sleep: Option<Pin<Box<dyn Sleep>>>,
         timeout: Duration,
+        timer: RuntimeTimer,
+        waiting: bool,
     }
 }
 
+pin_project! {
+    /// A body that yields the trailers resolved by `trailers` after the
+    /// frames of `inner`.
+    pub(crate) struct TrailersBody<B> {
+        #[pin]
+        inner: B,
+        inner_done: bool,
+        trailers: Option<Trailers>,
+    }
+}
+
+/// A future resolving to the trailers of a body.
+type Trailers = SyncWrapper<
+    Pin<
+        Box<
+            dyn Future<Output = Result<HeaderMap, Box<dyn std::error::Error + Send + Sync>>> + Send,
+        >,
+    >,
+>;
+
+/// The sending half of a body created with [`Body::channel`].
+///
+/// The body ends when the `BodySender` is dropped, or after sending its
+/// trailers with [`BodySender::send_trailers`]. To fail the request instead,
+/// like when the data to send can't be read, call [`BodySender::abort`].
+#[must_use = "BodySender does nothing unless sent on"]
+pub struct BodySender {
+    data_tx: mpsc::Sender<Result<Bytes, Box<dyn std::error::Error + Send + Sync>>>,
+    trailers_tx: Option<oneshot::Sender<HeaderMap>>,
+}
+
+/// The receiving half of a body created with [`Body::channel`].
+struct ChannelBody {
+    data_rx: mpsc::Receiver<Result<Bytes, Box<dyn std::error::Error + Send + Sync>>>,
+    data_done: bool,
+    trailers_rx: oneshot::Receiver<HeaderMap>,
+}
+
+/// A body of a known length, which its inner body doesn't tell.
+#[cfg(feature = "stream")]
+struct SizedBody {
+    inner: Body,
+    len: u64,
+}
+
+/// Whether a `100 Continue` was received for a request.
+#[derive(Default)]
+struct Continue {
+    received: AtomicBool,
+    waker: AtomicWaker,
+}
+
+/// A callback receiving the number of body bytes transferred so far and the
+/// total length of the body, if known.
+#[derive(Clone)]
+pub(crate) struct Progress(Arc<dyn Fn(u64, Option<u64>) + Send + Sync>);
+
+/// A content encoding used to compress a request body.
+///
+/// See [`RequestBuilder::compress_body`](crate::RequestBuilder::compress_body).
+#[cfg(any(
+    feature = "gzip",
+    feature = "brotli",
+    feature = "zstd",
+    feature = "deflate"
+))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Encoding {
+    /// The `gzip` encoding.
+    #[cfg(feature = "gzip")]
+    Gzip,
+    /// The `br` encoding.
+    #[cfg(feature = "brotli")]
+    Brotli,
+    /// The `zstd` encoding.
+    #[cfg(feature = "zstd")]
+    Zstd,
+    /// The `deflate` encoding, i.e. zlib-wrapped deflate data.
+    #[cfg(feature = "deflate")]
+    Deflate,
+}
+
 /// Converts any `impl Body` into a `impl Stream` of just its DATA frames.
 #[cfg(any(feature = "stream", feature = "multipart",))]
 pub(crate) struct DataStream<B>(pub(crate) B);
@@ -95,7 +232,50 @@ impl Body {
         Body::stream(stream)
     }
 
-    #[cfg(any(feature = "stream", feature = "multipart"))]
+    /// Create a streaming body from an `AsyncRead`, like a socket, or the
+    /// output end of a compression pipeline.
+    ///
+    /// With a `len`, it's sent as the `Content-Length` of the request, rather
+    /// than sending the body chunked, and the request fails if the reader
+    /// doesn't read exactly that many bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rquest::Body;
+    /// let data: &'static [u8] = b"hello world";
+    /// let body = Body::from_async_read(data, Some(data.len() as u64));
+    /// ```
+    ///
+    /// # Optional
+    ///
+    /// This requires the `stream` feature to be enabled.
+    #[cfg(feature = "stream")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    pub fn from_async_read<R>(reader: R, len: Option<u64>) -> Body
+    where
+        R: tokio::io::AsyncRead + Send + 'static,
+    {
+        use http_body_util::BodyExt;
+
+        let body = Body::stream(ReaderStream::new(reader));
+        match len {
+            Some(len) => Body {
+                inner: Inner::Streaming(SizedBody { inner: body, len }.boxed()),
+                replay: None,
+            },
+            None => body,
+        }
+    }
+
+    #[cfg(any(
+        feature = "stream",
+        feature = "multipart",
+        feature = "gzip",
+        feature = "brotli",
+        feature = "zstd",
+        feature = "deflate"
+    ))]
     pub(crate) fn stream<S>(stream: S) -> Body
     where
         S: futures_util::stream::TryStream + Send + 'static,
@@ -113,9 +293,133 @@ impl Body {
         )));
         Body {
             inner: Inner::Streaming(body),
+            replay: None,
         }
     }
 
+    /// Create a streaming body from a function, which is called again each
+    /// time the body must be sent anew.
+    ///
+    /// Unlike [`Body::wrap_stream`], such a body can be cloned with
+    /// [`Request::try_clone`](crate::Request::try_clone), and so a request
+    /// with it can be retried, and redirected with a `307` or `308`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rquest::Body;
+    /// let body = Body::from_fn(|| {
+    ///     let chunks: Vec<Result<_, std::io::Error>> = vec![Ok("hello"), Ok(" world")];
+    ///     futures_util::stream::iter(chunks)
+    /// });
+    /// ```
+    ///
+    /// # Optional
+    ///
+    /// This requires the `stream` feature to be enabled.
+    #[cfg(feature = "stream")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    pub fn from_fn<F, S>(f: F) -> Body
+    where
+        F: Fn() -> S + Send + Sync + 'static,
+        S: futures_util::stream::TryStream + Send + 'static,
+        S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+        Bytes: From<S::Ok>,
+    {
+        Body::replayable(Arc::new(move || Body::stream(f())))
+    }
+
+    /// Create a streaming body from the file at `path`, which is opened
+    /// again each time the body must be sent anew.
+    ///
+    /// Unlike a body created from a [`File`](tokio::fs::File), such a body
+    /// can be cloned with [`Request::try_clone`](crate::Request::try_clone),
+    /// and so a request with it can be retried, and redirected with a `307`
+    /// or `308`.
+    ///
+    /// # Errors
+    ///
+    /// Errors when the file cannot be opened. If it can't be opened again,
+    /// sending the body fails.
+    ///
+    /// # Optional
+    ///
+    /// This requires the `stream` feature to be enabled.
+    #[cfg(feature = "stream")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    pub async fn file<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Body> {
+        use futures_util::{TryFutureExt, TryStreamExt};
+
+        let path = path.as_ref().to_owned();
+        let file = File::open(&path).await?;
+        let mut body = Body::from(file);
+        body.replay = Some(Arc::new(move || {
+            let open = File::open(path.clone()).map_ok(ReaderStream::new);
+            Body::stream(futures_util::stream::once(open).try_flatten())
+        }));
+        Ok(body)
+    }
+
+    /// Create a streaming body, with a sender to push its chunks from
+    /// another task.
+    ///
+    /// Each chunk is sent as soon as the connection is ready for it, and
+    /// [`BodySender::send_data`] waits until the previous chunk was taken, so
+    /// a fast producer doesn't buffer the whole body in memory. The body is
+    /// sent with `Transfer-Encoding: chunked` in HTTP/1.1, and can't be sent
+    /// again, e.g. when following a redirect.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn run() -> Result<(), rquest::Error> {
+    /// let (mut sender, body) = rquest::Body::channel();
+    ///
+    /// tokio::spawn(async move {
+    ///     for line in ["hello\n", "world\n"] {
+    ///         if sender.send_data(line).await.is_err() {
+    ///             // the request was dropped
+    ///             return;
+    ///         }
+    ///     }
+    /// });
+    ///
+    /// let res = rquest::Client::new()
+    ///     .post("http://httpbin.org/post")
+    ///     .body(body)
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn channel() -> (BodySender, Body) {
+        use http_body_util::BodyExt;
+
+        let (data_tx, data_rx) = mpsc::channel(0);
+        let (trailers_tx, trailers_rx) = oneshot::channel();
+
+        let sender = BodySender {
+            data_tx,
+            trailers_tx: Some(trailers_tx),
+        };
+        let body = ChannelBody {
+            data_rx,
+            data_done: false,
+            trailers_rx,
+        };
+        let body = Body {
+            inner: Inner::Streaming(body.boxed()),
+            replay: None,
+        };
+        (sender, body)
+    }
+
+    fn replayable(replay: Replay) -> Body {
+        let mut body = replay();
+        body.replay = Some(replay);
+        body
+    }
+
     pub(crate) fn empty() -> Body {
         Body::reusable(Bytes::new())
     }
@@ -123,6 +427,7 @@ impl Body {
     pub(crate) fn reusable(chunk: Bytes) -> Body {
         Body {
             inner: Inner::Reusable(chunk),
+            replay: None,
         }
     }
 
@@ -151,22 +456,141 @@ impl Body {
 
         Body {
             inner: Inner::Streaming(boxed),
+            replay: None,
         }
     }
 
-    pub(crate) fn try_reuse(self) -> (Option<Bytes>, Self) {
-        let reuse = match self.inner {
-            Inner::Reusable(ref chunk) => Some(chunk.clone()),
-            Inner::Streaming { .. } => None,
-        };
-
-        (reuse, self)
+    /// Returns a copy of this body to send it again later, if possible.
+    pub(crate) fn try_reuse(self) -> (Option<Body>, Self) {
+        (self.try_clone(), self)
     }
 
     pub(crate) fn try_clone(&self) -> Option<Body> {
         match self.inner {
             Inner::Reusable(ref chunk) => Some(Body::reusable(chunk.clone())),
-            Inner::Streaming { .. } => None,
+            Inner::Streaming { .. } => self.replay.clone().map(Body::replayable),
+        }
+    }
+
+    /// Compress this body with `encoding` as it is sent.
+    #[cfg(any(
+        feature = "gzip",
+        feature = "brotli",
+        feature = "zstd",
+        feature = "deflate"
+    ))]
+    pub(crate) fn compress(self, encoding: Encoding) -> Body {
+        use tokio_util::io::{ReaderStream, StreamReader};
+
+        let reader = StreamReader::new(super::decoder::IoStream::new(self));
+        match encoding {
+            #[cfg(feature = "gzip")]
+            Encoding::Gzip => Body::stream(ReaderStream::new(
+                async_compression::tokio::bufread::GzipEncoder::new(reader),
+            )),
+            #[cfg(feature = "brotli")]
+            Encoding::Brotli => Body::stream(ReaderStream::new(
+                async_compression::tokio::bufread::BrotliEncoder::new(reader),
+            )),
+            #[cfg(feature = "zstd")]
+            Encoding::Zstd => Body::stream(ReaderStream::new(
+                async_compression::tokio::bufread::ZstdEncoder::new(reader),
+            )),
+            #[cfg(feature = "deflate")]
+            Encoding::Deflate => Body::stream(ReaderStream::new(
+                async_compression::tokio::bufread::ZlibEncoder::new(reader),
+            )),
+        }
+    }
+
+    /// Attach trailers to this body, which are sent once all of its data
+    /// has been.
+    ///
+    /// The `trailers` future is only polled after the last chunk of data was
+    /// sent, so it can compute something from the data, like a checksum.
+    /// The body is always sent as a stream, with `Transfer-Encoding: chunked`
+    /// in HTTP/1.1, and can't be sent again, e.g. when following a redirect.
+    ///
+    /// HTTP/1.1 only sends the fields named in the `Trailer` header of the
+    /// request, which must be set as well.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rquest::Body;
+    /// # use rquest::header::HeaderMap;
+    /// let body = Body::from("hello").with_trailers(async {
+    ///     let mut trailers = HeaderMap::new();
+    ///     trailers.insert("x-checksum", "907060870".parse().unwrap());
+    ///     Ok::<_, std::io::Error>(trailers)
+    /// });
+    /// ```
+    pub fn with_trailers<F, E>(self, trailers: F) -> Body
+    where
+        F: Future<Output = Result<HeaderMap, E>> + Send + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        use futures_util::TryFutureExt;
+        use http_body_util::BodyExt;
+
+        let body = TrailersBody {
+            inner: self,
+            inner_done: false,
+            trailers: Some(SyncWrapper::new(Box::pin(trailers.map_err(box_err)))),
+        };
+        Body {
+            inner: Inner::Streaming(body.boxed()),
+            replay: None,
+        }
+    }
+
+    /// Hold this body back until the server answers `100 Continue`, or
+    /// `timeout` has elapsed, returning the callback that receives the
+    /// informational responses of the request.
+    pub(crate) fn expect_continue(
+        self,
+        timeout: Duration,
+        timer: RuntimeTimer,
+    ) -> (Body, OnInformational) {
+        use http_body_util::BodyExt;
+
+        let received = Arc::new(Continue::default());
+        let on_informational = OnInformational::new({
+            let received = received.clone();
+            move |res| {
+                if res.status() == http::StatusCode::CONTINUE {
+                    received.received.store(true, Ordering::Release);
+                    received.waker.wake();
+                }
+            }
+        });
+        let body = ContinueBody {
+            inner: self,
+            received,
+            sleep: None,
+            timeout,
+            timer,
+            waiting: true,
+        };
+        let body = Body {
+            inner: Inner::Streaming(body.map_err(box_err).boxed()),
+            replay: None,
+        };
+        (body, on_informational)
+    }
+
+    /// Report the bytes of this body as they are sent.
+    pub(crate) fn with_progress(self, progress: Progress) -> Body {
+        use http_body_util::BodyExt;
+
+        let total = self.size_hint().exact();
+        Body {
+            inner: Inner::Streaming(
+                progress_body(self, progress, total)
+                    .map_err(box_err)
+                    .boxed(),
+            ),
+            replay: None,
         }
     }
 
@@ -196,6 +620,7 @@ impl From<BoxBody<Bytes, Box<dyn std::error::Error + Send + Sync>>> for Body {
     fn from(body: BoxBody<Bytes, Box<dyn std::error::Error + Send + Sync>>) -> Self {
         Self {
             inner: Inner::Streaming(body),
+            replay: None,
         }
     }
 }
@@ -291,14 +716,14 @@ impl HttpBody for Body {
 
 // ===== impl TotalTimeoutBody =====
 
-pub(crate) fn total_timeout<B>(body: B, timeout: Pin<Box<Sleep>>) -> TotalTimeoutBody<B> {
+pub(crate) fn total_timeout<B>(body: B, timeout: Pin<Box<dyn Sleep>>) -> TotalTimeoutBody<B> {
     TotalTimeoutBody {
         inner: body,
         timeout,
     }
 }
 
-pub(crate) fn with_read_timeout<B>(body: B, timeout: Duration) -> ReadTimeoutBody<B> {
+pub(crate) fn with_read_timeout<B>(body: B, timeout: ReadTimeout) -> ReadTimeoutBody<B> {
     ReadTimeoutBody {
         inner: body,
         sleep: None,
@@ -306,6 +731,12 @@ pub(crate) fn with_read_timeout<B>(body: B, timeout: Duration) -> ReadTimeoutBod
     }
 }
 
+impl ReadTimeout {
+    pub(crate) fn new(timeout: Duration, timer: RuntimeTimer) -> ReadTimeout {
+        ReadTimeout { timeout, timer }
+    }
+}
+
 impl<B> crate::core::body::Body for TotalTimeoutBody<B>
 where
     B: crate::core::body::Body,
@@ -320,7 +751,9 @@ where
     ) -> Poll<Option<Result<crate::core::body::Frame<Self::Data>, Self::Error>>> {
         let this = self.project();
         if let Poll::Ready(()) = this.timeout.as_mut().poll(cx) {
-            return Poll::Ready(Some(Err(crate::error::body(crate::error::TimedOut))));
+            let err = crate::error::body(crate::error::TimedOut)
+                .with_timeout_phase(crate::error::TimeoutPhase::Body);
+            return Poll::Ready(Some(Err(err)));
         }
         Poll::Ready(
             ready!(this.inner.poll_frame(cx))
@@ -351,25 +784,23 @@ where
         self: Pin<&mut Self>,
         cx: &mut Context,
     ) -> Poll<Option<Result<crate::core::body::Frame<Self::Data>, Self::Error>>> {
-        let mut this = self.project();
+        let this = self.project();
 
         // Start the `Sleep` if not active.
-        let sleep_pinned = if let Some(some) = this.sleep.as_mut().as_pin_mut() {
-            some
-        } else {
-            this.sleep.set(Some(tokio::time::sleep(*this.timeout)));
-            this.sleep.as_mut().as_pin_mut().unwrap()
-        };
+        let timeout = &*this.timeout;
+        let sleep = this
+            .sleep
+            .get_or_insert_with(|| timeout.timer.sleep(timeout.timeout));
 
         // Error if the timeout has expired.
-        if let Poll::Ready(()) = sleep_pinned.poll(cx) {
+        if let Poll::Ready(()) = sleep.as_mut().poll(cx) {
             return Poll::Ready(Some(Err(crate::error::body(crate::error::TimedOut))));
         }
 
         let item = ready!(this.inner.poll_frame(cx))
             .map(|opt_chunk| opt_chunk.map_err(crate::error::body));
         // a ready frame means timeout is reset
-        this.sleep.set(None);
+        *this.sleep = None;
         Poll::Ready(item)
     }
 
@@ -384,6 +815,340 @@ where
     }
 }
 
+// ===== impl PrefixedBody =====
+
+pub(crate) fn prefixed<B>(prefix: Bytes, body: B) -> PrefixedBody<B> {
+    PrefixedBody {
+        prefix: Some(prefix).filter(|prefix| !prefix.is_empty()),
+        inner: body,
+    }
+}
+
+impl<B> crate::core::body::Body for PrefixedBody<B>
+where
+    B: crate::core::body::Body<Data = Bytes>,
+{
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<Result<crate::core::body::Frame<Self::Data>, Self::Error>>> {
+        let this = self.project();
+        if let Some(prefix) = this.prefix.take() {
+            return Poll::Ready(Some(Ok(crate::core::body::Frame::data(prefix))));
+        }
+        this.inner.poll_frame(cx)
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        let prefix = self.prefix.as_ref().map_or(0, |prefix| prefix.len() as u64);
+        let inner = self.inner.size_hint();
+
+        let mut hint = http_body::SizeHint::new();
+        hint.set_lower(inner.lower() + prefix);
+        if let Some(upper) = inner.upper() {
+            hint.set_upper(upper + prefix);
+        }
+        hint
+    }
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        self.prefix.is_none() && self.inner.is_end_stream()
+    }
+}
+
+#[cfg(any(
+    feature = "gzip",
+    feature = "brotli",
+    feature = "zstd",
+    feature = "deflate"
+))]
+impl Encoding {
+    /// Returns the `Content-Encoding` value of this encoding.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            #[cfg(feature = "gzip")]
+            Encoding::Gzip => "gzip",
+            #[cfg(feature = "brotli")]
+            Encoding::Brotli => "br",
+            #[cfg(feature = "zstd")]
+            Encoding::Zstd => "zstd",
+            #[cfg(feature = "deflate")]
+            Encoding::Deflate => "deflate",
+        }
+    }
+}
+
+// ===== impl ProgressBody =====
+
+pub(crate) fn progress_body<B>(body: B, progress: Progress, total: Option<u64>) -> ProgressBody<B> {
+    ProgressBody {
+        inner: body,
+        progress,
+        transferred: 0,
+        total,
+    }
+}
+
+impl<B> crate::core::body::Body for ProgressBody<B>
+where
+    B: crate::core::body::Body<Data = Bytes>,
+{
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<Result<crate::core::body::Frame<Self::Data>, Self::Error>>> {
+        let this = self.project();
+        let item = ready!(this.inner.poll_frame(cx));
+        if let Some(Ok(ref frame)) = item {
+            if let Some(data) = frame.data_ref() {
+                *this.transferred += data.len() as u64;
+                (this.progress.0)(*this.transferred, *this.total);
+            }
+        }
+        Poll::Ready(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.inner.size_hint()
+    }
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+}
+
+// ===== impl TrailersBody =====
+
+impl<B> crate::core::body::Body for TrailersBody<B>
+where
+    B: crate::core::body::Body<Data = Bytes>,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    type Data = Bytes;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<Result<crate::core::body::Frame<Self::Data>, Self::Error>>> {
+        let this = self.project();
+        if !*this.inner_done {
+            match ready!(this.inner.poll_frame(cx)) {
+                Some(Ok(frame)) => return Poll::Ready(Some(Ok(frame))),
+                Some(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
+                None => *this.inner_done = true,
+            }
+        }
+        let Some(trailers) = this.trailers.as_mut() else {
+            return Poll::Ready(None);
+        };
+        let result = ready!(trailers.get_mut().as_mut().poll(cx));
+        *this.trailers = None;
+        Poll::Ready(Some(result.map(crate::core::body::Frame::trailers)))
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        // an unknown length, for the body to be sent chunked
+        let mut hint = http_body::SizeHint::new();
+        hint.set_lower(self.inner.size_hint().lower());
+        hint
+    }
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        self.trailers.is_none() && self.inner.is_end_stream()
+    }
+}
+
+// ===== impl BodySender =====
+
+impl BodySender {
+    /// Send a chunk of the body, waiting until the previous one was taken.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the body was dropped, e.g. because the request
+    /// failed or was cancelled.
+    pub async fn send_data(&mut self, chunk: impl Into<Bytes>) -> crate::Result<()> {
+        std::future::poll_fn(|cx| self.data_tx.poll_ready(cx))
+            .await
+            .map_err(|_| crate::error::body("the body receiver was dropped"))?;
+        self.data_tx
+            .try_send(Ok(chunk.into()))
+            .map_err(|_| crate::error::body("the body receiver was dropped"))
+    }
+
+    /// Try to send a chunk of the body, without waiting.
+    ///
+    /// This is useful to send from a thread that isn't running async code.
+    ///
+    /// # Errors
+    ///
+    /// Returns the chunk back if the previous one wasn't taken yet, or the
+    /// body was dropped.
+    pub fn try_send_data(&mut self, chunk: impl Into<Bytes>) -> Result<(), Bytes> {
+        self.data_tx
+            .try_send(Ok(chunk.into()))
+            .map_err(|err| err.into_inner().expect("just sent Ok"))
+    }
+
+    /// Send the trailers of the body, which ends it.
+    ///
+    /// HTTP/1.1 only sends the fields named in the `Trailer` header of the
+    /// request, which must be set as well.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the body was dropped.
+    pub fn send_trailers(mut self, trailers: HeaderMap) -> crate::Result<()> {
+        let tx = self.trailers_tx.take().expect("trailers only sent once");
+        tx.send(trailers)
+            .map_err(|_| crate::error::body("the body receiver was dropped"))
+    }
+
+    /// Abort the body, so the request fails rather than sending a body that
+    /// is cut short.
+    pub fn abort(self) {
+        // a clone has a slot of its own, so this can't fail for a full channel
+        let _ = self
+            .data_tx
+            .clone()
+            .try_send(Err("the body sender was aborted".into()));
+    }
+
+    /// Returns `true` if the body was dropped, so nothing more can be sent.
+    pub fn is_closed(&self) -> bool {
+        self.data_tx.is_closed()
+    }
+}
+
+impl fmt::Debug for BodySender {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BodySender")
+            .field("closed", &self.is_closed())
+            .finish()
+    }
+}
+
+// ===== impl ChannelBody =====
+
+impl HttpBody for ChannelBody {
+    type Data = Bytes;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        use futures_core::Stream;
+
+        if !self.data_done {
+            match ready!(Pin::new(&mut self.data_rx).poll_next(cx)) {
+                Some(Ok(chunk)) => return Poll::Ready(Some(Ok(http_body::Frame::data(chunk)))),
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None => self.data_done = true,
+            }
+        }
+
+        // the sender was dropped without trailers when this is canceled
+        match ready!(Pin::new(&mut self.trailers_rx).poll(cx)) {
+            Ok(trailers) => Poll::Ready(Some(Ok(http_body::Frame::trailers(trailers)))),
+            Err(_) => Poll::Ready(None),
+        }
+    }
+}
+
+// ===== impl SizedBody =====
+
+#[cfg(feature = "stream")]
+impl HttpBody for SizedBody {
+    type Data = Bytes;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        Pin::new(&mut self.inner).poll_frame(cx).map_err(box_err)
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        http_body::SizeHint::with_exact(self.len)
+    }
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+}
+
+// ===== impl ContinueBody =====
+
+impl<B> crate::core::body::Body for ContinueBody<B>
+where
+    B: crate::core::body::Body<Data = Bytes>,
+{
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<Result<crate::core::body::Frame<Self::Data>, Self::Error>>> {
+        let this = self.project();
+        if *this.waiting {
+            // register before checking, not to miss a wake up in between
+            this.received.waker.register(cx.waker());
+            if !this.received.received.load(Ordering::Acquire) {
+                let timeout = *this.timeout;
+                let timer = &*this.timer;
+                let sleep = this.sleep.get_or_insert_with(|| timer.sleep(timeout));
+                ready!(sleep.as_mut().poll(cx));
+                trace!("no 100 Continue after {:?}, sending the body", timeout);
+            }
+            *this.waiting = false;
+            *this.sleep = None;
+        }
+        this.inner.poll_frame(cx)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.inner.size_hint()
+    }
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+}
+
+// ===== impl Progress =====
+
+impl Progress {
+    pub(crate) fn new<F>(f: F) -> Progress
+    where
+        F: Fn(u64, Option<u64>) + Send + Sync + 'static,
+    {
+        Progress(Arc::new(f))
+    }
+}
+
+impl fmt::Debug for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Progress").finish()
+    }
+}
+
 pub(crate) type ResponseBody =
     http_body_util::combinators::BoxBody<Bytes, Box<dyn std::error::Error + Send + Sync>>;
 
@@ -399,8 +1164,8 @@ where
 
 pub(crate) fn response<B>(
     body: B,
-    deadline: Option<Pin<Box<Sleep>>>,
-    read_timeout: Option<Duration>,
+    deadline: Option<Pin<Box<dyn Sleep>>>,
+    read_timeout: Option<ReadTimeout>,
 ) -> ResponseBody
 where
     B: crate::core::body::Body<Data = Bytes> + Send + Sync + 'static,
@@ -517,4 +1282,19 @@ mod tests {
         assert!(stream_body.is_end_stream());
         assert_eq!(stream_body.size_hint().exact(), Some(0));
     }
+
+    #[tokio::test]
+    async fn prefixed_body_yields_prefix_first() {
+        use http_body_util::BodyExt;
+
+        let body = super::prefixed("hello ".into(), Body::reusable("world".into()));
+        assert!(!body.is_end_stream());
+        assert_eq!(body.size_hint().exact(), Some(11));
+
+        let bytes = body.collect().await.unwrap().to_bytes();
+        assert_eq!(bytes, "hello world");
+
+        let body = super::prefixed(Default::default(), Body::empty());
+        assert!(body.is_end_stream());
+    }
 }
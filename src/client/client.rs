@@ -2,63 +2,99 @@ use std::borrow::Cow;
 use std::future::Future;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::num::NonZeroUsize;
+#[cfg(unix)]
+use std::path::Path;
 use std::pin::Pin;
 
-use std::sync::Arc;
-use std::task::{Context, Poll};
-use std::time::Duration;
+use std::sync::{Arc, Weak};
+use std::task::{Context, Poll, ready};
+use std::time::{Duration, Instant, SystemTime};
 use std::{collections::HashMap, convert::TryInto, net::SocketAddr};
 
-use crate::config::{RequestConfig, RequestTimeout};
+use crate::cache::{CacheConfig, CachedResponse, Lookup};
+#[cfg(any(
+    feature = "gzip",
+    feature = "brotli",
+    feature = "zstd",
+    feature = "deflate"
+))]
+use crate::config::RequestCompression;
+#[cfg(feature = "cookies")]
+use crate::config::RequestCookieJar;
+#[cfg(feature = "ntlm")]
+use crate::config::RequestNtlmAuth;
+use crate::config::{
+    DownloadProgress, RequestAwsSigner, RequestBearerToken, RequestConfig, RequestDeadline,
+    RequestDigestAuth, RequestExpectContinue, RequestMaxResponseSize, RequestReadTimeout,
+    RequestTimeout, UploadProgress,
+};
 use crate::connect::{
-    BoxedConnectorLayer, BoxedConnectorService, Connector,
+    BoxedConnectorLayer, BoxedConnectorService, Connector, TcpOptions,
     sealed::{Conn, Unnameable},
 };
 #[cfg(feature = "cookies")]
 use crate::cookie;
+#[cfg(feature = "ntlm")]
+use crate::core::client::PinnedConnection;
 use crate::core::client::{
-    Builder, Client as HyperClient, InnerRequest, NetworkScheme, NetworkSchemeBuilder,
-    connect::HttpConnector,
+    Builder, Client as HyperClient, InnerRequest, NetworkScheme, NetworkSchemeBuilder, PoolEvent,
+    connect::HttpConnector, sort_headers,
 };
-use crate::core::rt::{TokioExecutor, tokio::TokioTimer};
+use crate::core::ext::OnInformational;
+use crate::core::rt::{Sleep, Timer, TokioExecutor, tokio::TokioTimer};
 #[cfg(feature = "hickory-dns")]
-use crate::dns::hickory::{HickoryDnsResolver, LookupIpStrategy};
-use crate::dns::{DnsResolverWithOverrides, DynResolver, Resolve, gai::GaiResolver};
-use crate::error::{BoxError, Error};
+use crate::dns::hickory::{DnsCache, HickoryDnsResolver, LookupIpStrategy};
+use crate::dns::{
+    DnsResolverWithOverrides, DynResolver, IpStrategy, Resolve, gai::GaiResolver, hosts,
+};
+use crate::error::{BoxError, Error, TimeoutPhase};
+use crate::har::{HarRecorder, Recording};
 use crate::http1::Http1Config;
-use crate::http2::Http2Config;
-use crate::into_url::try_uri;
-use crate::proxy::IntoProxy;
-use crate::tls::{CertStore, CertificateInput, Identity, KeyLogPolicy, TlsConfig};
-use crate::{IntoUrl, Method, Proxy, StatusCode, Url};
+use crate::http2::{Http2Config, Http2Options};
+use crate::into_url::{IntoUrlSealed, try_uri};
+use crate::proxy::{IntoProxy, ProxyScheme};
+use crate::ratelimit::RateLimiter;
+use crate::rt::{Runtime, RuntimeExecutor, RuntimeTimer, TokioRuntime};
+use crate::tls::{
+    CertChain, CertPins, CertStore, CertVerifier, CertificateInput, Identity, KeyLogPolicy,
+    OcspPolicy, PqKeyShare, Sha256Pin, TlsConfig, TlsOptions, TlsSessionCache, lookup_host,
+};
+use crate::trace::RequestSpan;
+use crate::{IntoUrl, Method, Proxy, ProxyPool, StatusCode, Url};
 use crate::{
-    error, redirect,
+    error, redirect, retry,
     tls::{AlpnProtos, TlsConnector, TlsVersion},
 };
 
-use super::decoder::Accepts;
+use super::bearer::{BearerAuth, TokenProvider};
+use super::body::{ReadTimeout, ResponseBody};
+use super::decoder::{Accepts, Decoder};
+use super::limit::{Permits, Queued, RequestLimits};
+use super::metrics::{MetricsObserver, Recorder};
 use super::request::{Request, RequestBuilder};
 use super::response::Response;
 #[cfg(feature = "websocket")]
 use super::websocket::WebSocketRequestBuilder;
-use super::{Body, EmulationProvider, EmulationProviderFactory};
+use super::{Body, EmulationProvider, EmulationProviderFactory, Timeouts, VerboseConfig};
 
 use arc_swap::{ArcSwap, Guard};
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use http::Extensions;
 use http::{
     HeaderName, Uri, Version,
     header::{
-        CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, HeaderMap, HeaderValue, LOCATION,
-        PROXY_AUTHORIZATION, REFERER, TRANSFER_ENCODING, USER_AGENT,
+        AUTHORIZATION, CONNECTION, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, EXPECT,
+        HeaderMap, HeaderValue, LOCATION, PROXY_AUTHORIZATION, REFERER, REFRESH, TRANSFER_ENCODING,
+        USER_AGENT,
     },
     uri::Scheme,
 };
+use http_body::Body as _;
 use pin_project_lite::pin_project;
 
-use tokio::time::Sleep;
-use tower::util::BoxCloneSyncServiceLayer;
-use tower::{Layer, Service};
+use sync_wrapper::SyncWrapper;
+use tower::util::{BoxCloneSyncService, BoxCloneSyncServiceLayer};
+use tower::{Layer, Service, ServiceExt};
 
 macro_rules! impl_debug {
     ($type:ty, { $($field_name:ident),* }) => {
@@ -76,6 +112,10 @@ macro_rules! impl_debug {
 
 type HyperResponseFuture = crate::core::client::ResponseFuture;
 
+type BoxedClientService = BoxCloneSyncService<Request, Response, Error>;
+
+type BoxedClientLayer = BoxCloneSyncServiceLayer<BoxedClientService, Request, Response, Error>;
+
 /// An asynchronous `Client` to make Requests with.
 ///
 /// The Client has various configuration values to tweak, but the defaults
@@ -106,34 +146,75 @@ struct Config {
     headers_order: Option<Cow<'static, [HeaderName]>>,
     accepts: Accepts,
     connect_timeout: Option<Duration>,
+    dns_timeout: Option<Duration>,
+    tcp_connect_timeout: Option<Duration>,
+    tls_handshake_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    first_byte_timeout: Option<Duration>,
+    expect_continue: Option<u64>,
+    expect_continue_timeout: Duration,
+    happy_eyeballs_timeout: Option<Duration>,
+    ip_strategy: Option<IpStrategy>,
     connection_verbose: bool,
+    verbose: Option<VerboseConfig>,
     pool_idle_timeout: Option<Duration>,
     pool_max_idle_per_host: usize,
     pool_max_size: Option<NonZeroUsize>,
+    pool_max_lifetime: Option<Duration>,
+    pool_max_requests: Option<NonZeroUsize>,
+    pool_events: Option<Arc<dyn Fn(&PoolEvent) + Send + Sync>>,
+    unpooled_hosts: HashMap<String, ()>,
+    runtime: Option<Arc<dyn Runtime>>,
+    max_concurrent_requests: Option<usize>,
+    max_connections_per_host: Option<usize>,
+    queue_timeout: Option<Duration>,
     tcp_keepalive: Option<Duration>,
     tcp_keepalive_interval: Option<Duration>,
     tcp_keepalive_retries: Option<u32>,
+    tcp_send_buffer_size: Option<usize>,
+    tcp_recv_buffer_size: Option<usize>,
+    #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+    tcp_user_timeout: Option<Duration>,
+    #[cfg(unix)]
+    unix_socket: Option<Arc<Path>>,
     proxies: Vec<Proxy>,
+    proxy_pool: Option<ProxyPool>,
     auto_sys_proxy: bool,
     redirect_policy: redirect::Policy,
+    retry_policy: retry::Policy,
+    respect_retry_after: bool,
+    max_retry_after_wait: Duration,
+    rate_limiter: Option<RateLimiter>,
+    cache: Option<CacheConfig>,
     referer: bool,
     timeout: Option<Duration>,
     read_timeout: Option<Duration>,
+    max_response_size: Option<u64>,
     network_scheme: NetworkSchemeBuilder,
     nodelay: bool,
     #[cfg(feature = "cookies")]
-    cookie_store: Option<Arc<dyn cookie::CookieStore>>,
+    cookie_store: Option<Arc<dyn cookie::AsyncCookieStore>>,
+    bearer: Option<Arc<BearerAuth>>,
+    base_url: Option<Url>,
+    metrics: Option<Arc<dyn MetricsObserver>>,
+    har: Option<HarRecorder>,
     hickory_dns: bool,
     error: Option<Error>,
     dns_overrides: HashMap<String, Vec<SocketAddr>>,
     dns_resolver: Option<Arc<dyn Resolve>>,
     #[cfg(feature = "hickory-dns")]
     dns_strategy: Option<LookupIpStrategy>,
+    #[cfg(feature = "hickory-dns")]
+    dns_cache: DnsCache,
     https_only: bool,
     http1_config: Http1Config,
     http2_config: Http2Config,
+    http2_options: Http2Options,
     http2_max_retry_count: usize,
     connector_layers: Option<Vec<BoxedConnectorLayer>>,
+    layers: Option<Vec<BoxedClientLayer>>,
+    #[cfg(feature = "testing")]
+    mock: Option<BoxedClientLayer>,
     builder: Builder,
     alpn_protos: Option<AlpnProtos>,
     keylog_policy: Option<KeyLogPolicy>,
@@ -143,7 +224,14 @@ struct Config {
     identity: Option<Identity>,
     cert_store: Option<CertStore>,
     cert_verification: bool,
+    cert_pins: CertPins,
+    cert_verifier: Option<CertVerifier>,
+    ocsp_policy: Option<OcspPolicy>,
+    require_sct: bool,
+    tls_host_options: Vec<(String, TlsOptions)>,
+    tls_session_cache: Option<TlsSessionCache>,
     min_tls_version: Option<TlsVersion>,
+    pq_key_share: Option<Option<PqKeyShare>>,
     max_tls_version: Option<TlsVersion>,
     tls_config: TlsConfig,
 }
@@ -155,14 +243,28 @@ impl_debug!(
         headers_order,
         accepts,
         connect_timeout,
+        happy_eyeballs_timeout,
+        ip_strategy,
         connection_verbose,
+        verbose,
         pool_idle_timeout,
         pool_max_idle_per_host,
         pool_max_size,
+        pool_max_lifetime,
+        pool_max_requests,
+        max_concurrent_requests,
+        max_connections_per_host,
+        queue_timeout,
         tcp_keepalive,
         proxies,
+        proxy_pool,
         auto_sys_proxy,
         redirect_policy,
+        retry_policy,
+        respect_retry_after,
+        max_retry_after_wait,
+        rate_limiter,
+        cache,
         referer,
         timeout,
         read_timeout,
@@ -173,6 +275,7 @@ impl_debug!(
         https_only,
         http1_config,
         http2_config,
+        http2_options,
         http2_max_retry_count,
         builder,
         keylog_policy,
@@ -180,9 +283,16 @@ impl_debug!(
         tls_sni,
         verify_hostname,
         cert_verification,
+        cert_pins,
+        cert_verifier,
+        ocsp_policy,
+        require_sct,
+        tls_host_options,
+        tls_session_cache,
         cert_store,
         alpn_protos,
         min_tls_version,
+        pq_key_share,
         max_tls_version,
         tls_config
     }
@@ -206,36 +316,77 @@ impl ClientBuilder {
                 headers_order: None,
                 accepts: Accepts::default(),
                 connect_timeout: None,
+                dns_timeout: None,
+                tcp_connect_timeout: None,
+                tls_handshake_timeout: None,
+                write_timeout: None,
+                first_byte_timeout: None,
+                expect_continue: None,
+                expect_continue_timeout: Duration::from_secs(1),
+                happy_eyeballs_timeout: Some(Duration::from_millis(300)),
+                ip_strategy: None,
                 connection_verbose: false,
+                verbose: None,
                 pool_idle_timeout: Some(Duration::from_secs(90)),
                 pool_max_idle_per_host: usize::MAX,
                 pool_max_size: None,
+                pool_max_lifetime: None,
+                pool_max_requests: None,
+                pool_events: None,
+                unpooled_hosts: HashMap::new(),
+                runtime: None,
+                max_concurrent_requests: None,
+                max_connections_per_host: None,
+                queue_timeout: None,
                 // TODO: Re-enable default duration once hyper's HttpConnector is fixed
                 // to no longer error when an option fails.
                 tcp_keepalive: None,
                 tcp_keepalive_interval: None,
                 tcp_keepalive_retries: None,
+                tcp_send_buffer_size: None,
+                tcp_recv_buffer_size: None,
+                #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+                tcp_user_timeout: None,
+                #[cfg(unix)]
+                unix_socket: None,
                 proxies: Vec::new(),
+                proxy_pool: None,
                 auto_sys_proxy: true,
                 redirect_policy: redirect::Policy::none(),
+                retry_policy: retry::Policy::none(),
+                respect_retry_after: false,
+                max_retry_after_wait: Duration::from_secs(60),
+                rate_limiter: None,
+                cache: None,
                 referer: true,
                 timeout: None,
                 read_timeout: None,
+                max_response_size: None,
                 network_scheme: NetworkScheme::builder(),
                 nodelay: true,
                 hickory_dns: cfg!(feature = "hickory-dns"),
                 #[cfg(feature = "hickory-dns")]
                 dns_strategy: None,
+                #[cfg(feature = "hickory-dns")]
+                dns_cache: DnsCache::default(),
                 #[cfg(feature = "cookies")]
                 cookie_store: None,
+                bearer: None,
+                base_url: None,
+                metrics: None,
+                har: None,
                 dns_overrides: HashMap::new(),
                 dns_resolver: None,
                 builder: HyperClient::builder(TokioExecutor::new()),
                 https_only: false,
                 http1_config: Http1Config::default(),
                 http2_config: Http2Config::default(),
+                http2_options: Http2Options::default(),
                 http2_max_retry_count: 2,
                 connector_layers: None,
+                layers: None,
+                #[cfg(feature = "testing")]
+                mock: None,
                 alpn_protos: None,
                 keylog_policy: None,
                 tls_info: false,
@@ -244,7 +395,14 @@ impl ClientBuilder {
                 identity: None,
                 cert_store: None,
                 cert_verification: true,
+                cert_pins: CertPins::default(),
+                cert_verifier: None,
+                ocsp_policy: None,
+                require_sct: false,
+                tls_host_options: Vec::new(),
+                tls_session_cache: None,
                 min_tls_version: None,
+                pq_key_share: None,
                 max_tls_version: None,
                 tls_config: TlsConfig::default(),
             },
@@ -270,76 +428,137 @@ impl ClientBuilder {
         }
         let proxies_maybe_http_auth = proxies.iter().any(Proxy::maybe_has_http_auth);
 
+        let mut http2_config = config.http2_config;
+        config.http2_options.apply(&mut http2_config);
+
         config
             .builder
             .http1_config(config.http1_config)
-            .http2_config(config.http2_config)
+            .http2_config(http2_config)
             .http2_only(matches!(config.alpn_protos, Some(AlpnProtos::HTTP2)))
-            .http2_timer(TokioTimer::new())
-            .pool_timer(TokioTimer::new())
             .pool_idle_timeout(config.pool_idle_timeout)
             .pool_max_idle_per_host(config.pool_max_idle_per_host)
-            .pool_max_size(config.pool_max_size);
+            .pool_max_size(config.pool_max_size)
+            .pool_max_lifetime(config.pool_max_lifetime)
+            .pool_max_requests(config.pool_max_requests)
+            .pool_events(config.pool_events)
+            .verbose(config.verbose);
+
+        let timer = RuntimeTimer::new(
+            config
+                .runtime
+                .clone()
+                .unwrap_or_else(|| Arc::new(TokioRuntime)),
+        );
+        match config.runtime {
+            Some(runtime) => {
+                crate::rt::start(&*runtime);
+                config
+                    .builder
+                    .executor(RuntimeExecutor::new(runtime.clone()))
+                    .http2_timer(RuntimeTimer::new(runtime.clone()))
+                    .pool_timer(RuntimeTimer::new(runtime));
+            }
+            None => {
+                config
+                    .builder
+                    .http2_timer(TokioTimer::new())
+                    .pool_timer(TokioTimer::new());
+            }
+        }
 
-        let connector = {
-            let resolver = {
-                let mut resolver: Arc<dyn Resolve> = match config.dns_resolver {
-                    Some(dns_resolver) => dns_resolver,
-                    #[cfg(feature = "hickory-dns")]
-                    None if config.hickory_dns => {
-                        Arc::new(HickoryDnsResolver::new(config.dns_strategy)?)
-                    }
-                    None => Arc::new(GaiResolver::new()),
-                };
+        let tls_session_resumption = config.tls_session_cache.is_some();
+        let tls_session_cache = config.tls_session_cache.unwrap_or_default();
 
-                if !config.dns_overrides.is_empty() {
-                    resolver = Arc::new(DnsResolverWithOverrides::new(
-                        resolver,
-                        config.dns_overrides,
-                    ));
-                }
-                DynResolver::new(resolver)
-            };
+        let tls_config = {
+            let mut tls_config = config.tls_config;
 
-            let mut http = HttpConnector::new_with_resolver(resolver.clone());
-            http.set_connect_timeout(config.connect_timeout);
+            if let Some(alpn_protos) = config.alpn_protos {
+                tls_config.alpn_protos = alpn_protos;
+            }
 
-            let tls = {
-                let mut tls_config = config.tls_config;
+            if tls_session_resumption {
+                tls_config.pre_shared_key = true;
+            }
 
-                if let Some(alpn_protos) = config.alpn_protos {
-                    tls_config.alpn_protos = alpn_protos;
-                }
+            if config.min_tls_version.is_some() {
+                tls_config.min_tls_version = config.min_tls_version;
+            }
 
-                if config.min_tls_version.is_some() {
-                    tls_config.min_tls_version = config.min_tls_version;
-                }
+            if config.pq_key_share.is_some() {
+                tls_config.pq_key_share = config.pq_key_share;
+            }
 
-                if config.max_tls_version.is_some() {
-                    tls_config.max_tls_version = config.max_tls_version;
-                }
+            if config.max_tls_version.is_some() {
+                tls_config.max_tls_version = config.max_tls_version;
+            }
+
+            tls_config
+        };
 
-                TlsConnector::builder(tls_config)
-                    .keylog(config.keylog_policy.clone())
-                    .identity(config.identity.clone())
-                    .cert_store(config.cert_store.clone().unwrap_or_default())
-                    .cert_verification(config.cert_verification)
-                    .tls_sni(config.tls_sni)
-                    .verify_hostname(config.verify_hostname)
-                    .build()?
+        let resolver = {
+            let mut resolver: Arc<dyn Resolve> = match config.dns_resolver {
+                Some(dns_resolver) => dns_resolver,
+                #[cfg(feature = "hickory-dns")]
+                None if config.hickory_dns => Arc::new(HickoryDnsResolver::with_cache(
+                    config.dns_strategy,
+                    config.dns_cache,
+                )?),
+                None => Arc::new(GaiResolver::new()),
             };
 
+            if !config.dns_overrides.is_empty() {
+                resolver = Arc::new(DnsResolverWithOverrides::new(
+                    resolver,
+                    config.dns_overrides,
+                ));
+            }
+            DynResolver::new(resolver)
+        };
+
+        let connector = {
+            let mut http = HttpConnector::new_with_resolver(resolver.clone());
+            http.set_connect_timeout(config.tcp_connect_timeout.or(config.connect_timeout));
+            http.set_dns_timeout(config.dns_timeout);
+            http.set_happy_eyeballs_timeout(config.happy_eyeballs_timeout);
+            http.set_ip_strategy(config.ip_strategy);
+            http.set_send_buffer_size(config.tcp_send_buffer_size);
+            http.set_recv_buffer_size(config.tcp_recv_buffer_size);
+            #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+            http.set_tcp_user_timeout(config.tcp_user_timeout);
+
+            let tls = TlsConnector::builder(tls_config.clone())
+                .keylog(config.keylog_policy.clone())
+                .identity(config.identity.clone())
+                .cert_store(config.cert_store.clone().unwrap_or_default())
+                .cert_verification(config.cert_verification)
+                .cert_pins(config.cert_pins.clone())
+                .cert_verifier(config.cert_verifier.clone())
+                .ocsp_policy(config.ocsp_policy)
+                .require_sct(config.require_sct)
+                .handshake_timeout(config.tls_handshake_timeout)
+                .host_options(config.tls_host_options.clone())
+                .session_cache(Some(tls_session_cache.clone()))
+                .tls_sni(config.tls_sni)
+                .verify_hostname(config.verify_hostname)
+                .build()?;
+
             let builder = Connector::builder(http, tls, config.nodelay, config.tls_info)
                 .timeout(config.connect_timeout)
+                .write_timeout(config.write_timeout)
+                .metrics(config.metrics.clone())
                 .keepalive(config.tcp_keepalive)
                 .tcp_keepalive_interval(config.tcp_keepalive_interval)
                 .tcp_keepalive_retries(config.tcp_keepalive_retries)
                 .verbose(config.connection_verbose);
 
+            #[cfg(unix)]
+            let builder = builder.unix_socket(config.unix_socket);
+
             #[cfg(feature = "socks")]
             {
                 builder
-                    .socks_resolver(resolver)
+                    .socks_resolver(resolver.clone())
                     .build(config.connector_layers)
             }
             #[cfg(not(feature = "socks"))]
@@ -348,33 +567,79 @@ impl ClientBuilder {
             }
         };
 
+        #[cfg_attr(not(feature = "testing"), allow(unused_mut))]
+        let mut layers = config.layers;
+        #[cfg(feature = "testing")]
+        if let Some(mock) = config.mock {
+            layers.get_or_insert_default().insert(0, mock);
+        }
+
         Ok(Client {
-            inner: Arc::new(ArcSwap::from_pointee(ClientRef {
-                accepts: config.accepts,
-                #[cfg(feature = "cookies")]
-                cookie_store: config.cookie_store,
-                hyper: config.builder.build(connector),
-                headers: config.headers,
-                headers_order: config.headers_order,
-                redirect: config.redirect_policy,
-                referer: config.referer,
-                total_timeout: RequestConfig::new(config.timeout),
-                read_timeout: RequestConfig::new(config.read_timeout),
-                https_only: config.https_only,
-                http2_max_retry_count: config.http2_max_retry_count,
-                proxies,
-                proxies_maybe_http_auth,
-                network_scheme: config.network_scheme,
-                alpn_protos: config.alpn_protos,
-                keylog: config.keylog_policy,
-                tls_sni: config.tls_sni,
-                verify_hostname: config.verify_hostname,
-                identity: config.identity,
-                cert_store: config.cert_store,
-                cert_verification: config.cert_verification,
-                min_tls_version: config.min_tls_version,
-                max_tls_version: config.max_tls_version,
-            })),
+            inner: Arc::new_cyclic(|weak| {
+                ArcSwap::from_pointee(ClientRef {
+                    accepts: config.accepts,
+                    #[cfg(feature = "cookies")]
+                    cookie_store: config.cookie_store,
+                    bearer: config.bearer,
+                    base_url: config.base_url,
+                    metrics: config.metrics.clone(),
+                    har: config.har,
+                    limits: RequestLimits::new(
+                        config.max_concurrent_requests,
+                        config.max_connections_per_host,
+                        config.queue_timeout,
+                    ),
+                    resolver,
+                    hyper: config.builder.build(connector),
+                    headers: config.headers,
+                    headers_order: config.headers_order,
+                    redirect: config.redirect_policy,
+                    retry: config.retry_policy,
+                    respect_retry_after: config.respect_retry_after,
+                    max_retry_after_wait: config.max_retry_after_wait,
+                    rate_limiter: config.rate_limiter,
+                    cache: config.cache,
+                    referer: config.referer,
+                    total_timeout: RequestConfig::new(config.timeout),
+                    read_timeout: RequestConfig::new(config.read_timeout),
+                    max_response_size: RequestConfig::new(config.max_response_size),
+                    unpooled_hosts: config.unpooled_hosts,
+                    first_byte_timeout: config.first_byte_timeout,
+                    timer,
+                    expect_continue: config.expect_continue,
+                    expect_continue_timeout: config.expect_continue_timeout,
+                    https_only: config.https_only,
+                    http2_max_retry_count: config.http2_max_retry_count,
+                    http2_options: config.http2_options,
+                    proxies,
+                    proxies_maybe_http_auth,
+                    proxy_pool: config.proxy_pool,
+                    network_scheme: config.network_scheme,
+                    alpn_protos: config.alpn_protos,
+                    keylog: config.keylog_policy,
+                    tls_sni: config.tls_sni,
+                    verify_hostname: config.verify_hostname,
+                    identity: config.identity,
+                    cert_store: config.cert_store,
+                    cert_verification: config.cert_verification,
+                    cert_pins: config.cert_pins,
+                    cert_verifier: config.cert_verifier,
+                    ocsp_policy: config.ocsp_policy,
+                    require_sct: config.require_sct,
+                    tls_handshake_timeout: config.tls_handshake_timeout,
+                    tls_host_options: config.tls_host_options,
+                    tls_session_cache,
+                    tls_session_resumption,
+                    min_tls_version: config.min_tls_version,
+                    pq_key_share: config.pq_key_share,
+                    max_tls_version: config.max_tls_version,
+                    tls_config,
+                    service: layers
+                        .as_deref()
+                        .map(|layers| layered_service(weak, layers)),
+                    layers,
+                })
+            }),
         })
     }
 
@@ -416,6 +681,43 @@ impl ClientBuilder {
         self
     }
 
+    /// Sets the base URL that relative request URLs are resolved against.
+    ///
+    /// With a base of `https://api.example.com/v2/`, `client.get("users/42")`
+    /// requests `https://api.example.com/v2/users/42`, while a URL with its
+    /// own scheme, like `https://example.com/other`, is requested as is. A
+    /// path starting with `/` replaces the whole path of the base.
+    ///
+    /// A trailing `/` is added to the path of the base if it has none, so
+    /// `https://api.example.com/v2` works the same.
+    ///
+    /// By default, there is no base URL, and request URLs must be absolute.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # async fn doc() -> rquest::Result<()> {
+    /// let client = rquest::Client::builder()
+    ///     .base_url("https://api.example.com/v2/")
+    ///     .build()?;
+    /// let res = client.get("users/42").send().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn base_url<U: IntoUrl>(mut self, url: U) -> ClientBuilder {
+        match url.into_url() {
+            Ok(mut url) => {
+                if !url.path().ends_with('/') {
+                    let path = format!("{}/", url.path());
+                    url.set_path(&path);
+                }
+                self.config.base_url = Some(url);
+            }
+            Err(err) => self.config.error = Some(err),
+        }
+        self
+    }
+
     /// Sets the default headers for every request.
     ///
     /// # Example
@@ -502,6 +804,9 @@ impl ClientBuilder {
     /// Cookies received in responses will be passed to this store, and
     /// additional requests will query this store for cookies.
     ///
+    /// Any [`CookieStore`](cookie::CookieStore) can be used, as well as an
+    /// [`AsyncCookieStore`](cookie::AsyncCookieStore) shared with other clients.
+    ///
     /// By default, no cookie store is used.
     ///
     /// # Optional
@@ -512,7 +817,7 @@ impl ClientBuilder {
         docsrs,
         doc(cfg(any(feature = "cookies", feature = "cookies-abstract")))
     )]
-    pub fn cookie_provider<C: cookie::CookieStore + 'static>(
+    pub fn cookie_provider<C: cookie::AsyncCookieStore + 'static>(
         mut self,
         cookie_store: Arc<C>,
     ) -> ClientBuilder {
@@ -520,6 +825,41 @@ impl ClientBuilder {
         self
     }
 
+    /// Authenticate requests with bearer tokens from `provider`.
+    ///
+    /// Requests without an `Authorization` header of their own are sent with
+    /// `Authorization: Bearer <token>`. If one is answered with
+    /// `401 Unauthorized`, the token is refreshed and the request is sent
+    /// once more, unless its body can't be sent twice. See [`TokenProvider`].
+    ///
+    /// By default, no token provider is used.
+    pub fn token_provider<P: TokenProvider + 'static>(mut self, provider: Arc<P>) -> ClientBuilder {
+        self.config.bearer = Some(Arc::new(BearerAuth::new(provider)));
+        self
+    }
+
+    /// Report the timings and byte counts of every request, and the timing of
+    /// every new connection, to `observer`. See [`MetricsObserver`].
+    ///
+    /// This is cheaper than the `tracing` feature when only aggregate numbers,
+    /// like latency histograms, are needed.
+    ///
+    /// By default, no observer is used.
+    pub fn metrics<M: MetricsObserver + 'static>(mut self, observer: M) -> ClientBuilder {
+        self.config.metrics = Some(Arc::new(observer));
+        self
+    }
+
+    /// Record the traffic of the client as an HTTP Archive with `recorder`.
+    ///
+    /// See the [`har`](crate::har) module for what is recorded.
+    ///
+    /// By default, traffic isn't recorded.
+    pub fn har(mut self, recorder: HarRecorder) -> ClientBuilder {
+        self.config.har = Some(recorder);
+        self
+    }
+
     /// Enable auto gzip decompression by checking the `Content-Encoding` response header.
     ///
     /// If auto gzip decompression is turned on:
@@ -598,7 +938,8 @@ impl ClientBuilder {
     ///   The request body is **not** automatically compressed.
     /// - When receiving a response, if it's headers contain a `Content-Encoding` value that
     ///   equals to `deflate`, both values `Content-Encoding` and `Content-Length` are removed from the
-    ///   headers' set. The response body is automatically decompressed.
+    ///   headers' set. The response body is automatically decompressed, whether it is zlib-wrapped
+    ///   or raw deflate data.
     ///
     /// If the `deflate` feature is turned on, the default option is enabled.
     ///
@@ -680,6 +1021,77 @@ impl ClientBuilder {
         }
     }
 
+    /// Limit the size a compressed response body may decompress to.
+    ///
+    /// A body that decompresses to more than `bytes` fails with an error for
+    /// which [`Error::is_decompression_bomb`](crate::Error::is_decompression_bomb)
+    /// returns `true`, and the rest of it is discarded, before more of it is
+    /// decompressed into memory. Bodies that aren't compressed aren't
+    /// limited; see [`ClientBuilder::max_response_size`] to limit every body.
+    ///
+    /// Default is no limit.
+    ///
+    /// # Optional
+    ///
+    /// This requires at least one of the optional `gzip`, `brotli`, `zstd`
+    /// or `deflate` features to be enabled.
+    #[cfg(any(
+        feature = "gzip",
+        feature = "brotli",
+        feature = "zstd",
+        feature = "deflate"
+    ))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            feature = "gzip",
+            feature = "brotli",
+            feature = "zstd",
+            feature = "deflate"
+        )))
+    )]
+    pub fn max_decompressed_size(mut self, bytes: u64) -> ClientBuilder {
+        self.config.accepts.limits.max_size = Some(bytes);
+        self
+    }
+
+    /// Limit how many times larger than its compressed bytes read so far a
+    /// response body may decompress to.
+    ///
+    /// A body that decompresses `ratio` times beyond the compressed bytes
+    /// read fails like one over
+    /// [`max_decompressed_size`](ClientBuilder::max_decompressed_size). The
+    /// ratio is only checked once 64 KiB have been decompressed, as small
+    /// bodies of repeated bytes compress very well. Ordinary text rarely
+    /// compresses more than 20 times, while a decompression bomb, like a
+    /// gzip of zeros, expands around a thousand times.
+    ///
+    /// Default is no limit.
+    ///
+    /// # Optional
+    ///
+    /// This requires at least one of the optional `gzip`, `brotli`, `zstd`
+    /// or `deflate` features to be enabled.
+    #[cfg(any(
+        feature = "gzip",
+        feature = "brotli",
+        feature = "zstd",
+        feature = "deflate"
+    ))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            feature = "gzip",
+            feature = "brotli",
+            feature = "zstd",
+            feature = "deflate"
+        )))
+    )]
+    pub fn max_decompression_ratio(mut self, ratio: u32) -> ClientBuilder {
+        self.config.accepts.limits.max_ratio = Some(ratio);
+        self
+    }
+
     // Redirect options
 
     /// Set a `RedirectPolicy` for this client.
@@ -690,6 +1102,79 @@ impl ClientBuilder {
         self
     }
 
+    // Retry options
+
+    /// Set a `retry::Policy` for this client.
+    ///
+    /// Default does not retry, apart from HTTP/2 requests refused by the server.
+    pub fn retry(mut self, policy: retry::Policy) -> ClientBuilder {
+        self.config.retry_policy = policy;
+        self
+    }
+
+    /// Set whether to wait out the `Retry-After` of `429 Too Many Requests` and
+    /// `503 Service Unavailable` responses, and send the request again.
+    ///
+    /// `Retry-After` may be a number of seconds or an HTTP date. Responses
+    /// without it are returned as they are, and so is the response once the
+    /// request would wait longer than [`max_retry_after_wait`] in total, or has
+    /// been sent again 10 times. Only requests whose body can be replayed are
+    /// sent again. Responses retried by the `retry::Policy` aren't affected.
+    ///
+    /// The waits are traced as `retry_after` events when the `tracing` feature
+    /// is enabled, and reported by [`RequestMetrics::retry_after_wait`].
+    ///
+    /// Default is `false`.
+    ///
+    /// [`max_retry_after_wait`]: ClientBuilder::max_retry_after_wait
+    pub fn respect_retry_after(mut self, enable: bool) -> ClientBuilder {
+        self.config.respect_retry_after = enable;
+        self
+    }
+
+    /// Set the longest time a request waits in total on `Retry-After` headers,
+    /// when [`respect_retry_after`](ClientBuilder::respect_retry_after) is enabled.
+    ///
+    /// Default is 60 seconds.
+    pub fn max_retry_after_wait(mut self, max: Duration) -> ClientBuilder {
+        self.config.max_retry_after_wait = max;
+        self
+    }
+
+    // Rate limit options
+
+    /// Set a `RateLimiter` for the requests of this client.
+    ///
+    /// Requests over the limit of their host wait before they are sent. Default
+    /// does not limit the rate of requests. See the [`ratelimit`](crate::ratelimit)
+    /// module.
+    pub fn rate_limiter(mut self, limiter: RateLimiter) -> ClientBuilder {
+        self.config.rate_limiter = Some(limiter);
+        self
+    }
+
+    // Cache options
+
+    /// Set a cache for responses to this client's requests.
+    ///
+    /// Default does not cache responses. See the [`cache`](crate::cache) module
+    /// for how responses are stored and reused.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rquest::cache::CacheConfig;
+    ///
+    /// let client = rquest::Client::builder()
+    ///     .cache(CacheConfig::disk("/tmp/rquest-cache"))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn cache(mut self, cache: CacheConfig) -> ClientBuilder {
+        self.config.cache = Some(cache);
+        self
+    }
+
     /// Enable or disable automatic setting of the `Referer` header.
     ///
     /// Default is `true`.
@@ -741,6 +1226,34 @@ impl ClientBuilder {
     /// This also disables the automatic usage of the "system" proxy.
     pub fn no_proxy(mut self) -> ClientBuilder {
         self.config.proxies.clear();
+        self.config.proxy_pool = None;
+        self.config.auto_sys_proxy = false;
+        self
+    }
+
+    /// Rotate requests through a pool of proxies.
+    ///
+    /// Requests that aren't given their own proxy are sent through a proxy picked from
+    /// the pool. If no proxy in the pool intercepts a request, the proxies added with
+    /// [`ClientBuilder::proxy`] are used instead.
+    ///
+    /// Setting a proxy pool disables the automatic usage of the "system" proxy.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rquest::{Client, Proxy, ProxyPool, ProxyStrategy};
+    ///
+    /// let pool = ProxyPool::new([
+    ///     Proxy::all("http://proxy1:8080").unwrap(),
+    ///     Proxy::all("http://proxy2:8080").unwrap(),
+    /// ])
+    /// .strategy(ProxyStrategy::LeastErrors);
+    ///
+    /// let client = Client::builder().proxy_pool(pool).build().unwrap();
+    /// ```
+    pub fn proxy_pool(mut self, pool: ProxyPool) -> ClientBuilder {
+        self.config.proxy_pool = Some(pool);
         self.config.auto_sys_proxy = false;
         self
     }
@@ -760,6 +1273,11 @@ impl ClientBuilder {
 
     /// Set a timeout for only the read phase of a `Client`.
     ///
+    /// The timeout applies to each read of a response body, and is reset every
+    /// time data arrives. A body whose server stalls mid-way, e.g. while it is
+    /// consumed with `Response::bytes_stream()`, then fails with a timeout
+    /// error instead of hanging, however long the whole body takes.
+    ///
     /// Default is `None`.
     pub fn read_timeout(mut self, timeout: Duration) -> ClientBuilder {
         self.config.read_timeout = Some(timeout);
@@ -779,6 +1297,129 @@ impl ClientBuilder {
         self
     }
 
+    /// Set timeouts for the separate phases of a request.
+    ///
+    /// Only the timeouts set in `timeouts` change; see [`Timeouts`].
+    pub fn timeouts(mut self, timeouts: Timeouts) -> ClientBuilder {
+        if timeouts.dns.is_some() {
+            self.config.dns_timeout = timeouts.dns;
+        }
+        if timeouts.connect.is_some() {
+            self.config.tcp_connect_timeout = timeouts.connect;
+        }
+        if timeouts.tls_handshake.is_some() {
+            self.config.tls_handshake_timeout = timeouts.tls_handshake;
+        }
+        if timeouts.write.is_some() {
+            self.config.write_timeout = timeouts.write;
+        }
+        if timeouts.first_byte.is_some() {
+            self.config.first_byte_timeout = timeouts.first_byte;
+        }
+        if timeouts.read.is_some() {
+            self.config.read_timeout = timeouts.read;
+        }
+        self
+    }
+
+    /// Limit the size of response bodies to `bytes`.
+    ///
+    /// A body larger than the limit fails with an error for which
+    /// [`Error::is_response_too_large`](crate::Error::is_response_too_large)
+    /// returns `true`, and the rest of it is discarded: its HTTP/2 stream is
+    /// reset, and its HTTP/1 connection closed. The limit applies to the body
+    /// after decompression, so it also protects against a small compressed
+    /// body that expands to a huge one. A body whose `Content-Length` is over
+    /// the limit fails as soon as it's read.
+    ///
+    /// This can be overridden per request with
+    /// [`RequestBuilder::max_response_size`](crate::RequestBuilder::max_response_size).
+    ///
+    /// Default is no limit.
+    pub fn max_response_size(mut self, bytes: u64) -> ClientBuilder {
+        self.config.max_response_size = Some(bytes);
+        self
+    }
+
+    /// Send HTTP/1 requests whose body is at least `bytes` long, or of unknown
+    /// length, with `Expect: 100-continue`.
+    ///
+    /// The body of such a request is only sent once the server answers
+    /// `100 Continue`, or after the [`expect_continue_timeout`], so a server
+    /// rejecting the request early, e.g. with `401 Unauthorized` or
+    /// `413 Content Too Large`, doesn't have the whole body uploaded first.
+    /// The body is then left unsent, and the connection isn't reused.
+    ///
+    /// [`RequestBuilder::expect_continue`](crate::RequestBuilder::expect_continue)
+    /// overrides this for a request.
+    ///
+    /// By default, no request is sent with `Expect: 100-continue`.
+    ///
+    /// [`expect_continue_timeout`]: ClientBuilder::expect_continue_timeout
+    pub fn expect_continue_threshold(mut self, bytes: u64) -> ClientBuilder {
+        self.config.expect_continue = Some(bytes);
+        self
+    }
+
+    /// Set how long the body of a request sent with `Expect: 100-continue`
+    /// waits for a `100 Continue`, before it is sent anyway.
+    ///
+    /// Servers that don't support the expectation never answer it, and
+    /// HTTP/2 requests aren't answered either.
+    ///
+    /// Default is 1 second.
+    pub fn expect_continue_timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.config.expect_continue_timeout = timeout;
+        self
+    }
+
+    /// Set the delay before racing a connection to the other address family.
+    ///
+    /// When a host resolves to both IPv6 and IPv4 addresses, IPv6 is tried first.
+    /// If no connection is established before this delay elapses, IPv4 addresses
+    /// are tried in parallel and the first successful connection is used, as
+    /// described by [RFC 8305 (Happy Eyeballs)][RFC 8305].
+    ///
+    /// Pass `None` to try addresses one after another.
+    ///
+    /// Default is 300 milliseconds.
+    ///
+    /// [RFC 8305]: https://www.rfc-editor.org/rfc/rfc8305
+    pub fn happy_eyeballs_timeout<D>(mut self, timeout: D) -> ClientBuilder
+    where
+        D: Into<Option<Duration>>,
+    {
+        self.config.happy_eyeballs_timeout = timeout.into();
+        self
+    }
+
+    /// Set the IP versions of the addresses to connect to, and which is tried first.
+    ///
+    /// With [`IpStrategy::Ipv4Only`] or [`IpStrategy::Ipv6Only`], addresses of the
+    /// other version are never connected to, and a host with none of the allowed
+    /// version fails to connect. With [`IpStrategy::Ipv4ThenIpv6`], IPv4 is tried
+    /// first and IPv6 is the fallback raced after the
+    /// [`happy_eyeballs_timeout`](ClientBuilder::happy_eyeballs_timeout).
+    ///
+    /// This applies to the resolved addresses and to IP addresses in URLs.
+    ///
+    /// Default prefers IPv6, like [`IpStrategy::Ipv6ThenIpv4`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rquest::dns::IpStrategy;
+    ///
+    /// let client = rquest::Client::builder()
+    ///     .ip_strategy(IpStrategy::Ipv4Only)
+    ///     .build()?;
+    /// # Ok::<(), rquest::Error>(())
+    /// ```
+    pub fn ip_strategy(mut self, strategy: IpStrategy) -> ClientBuilder {
+        self.config.ip_strategy = Some(strategy);
+        self
+    }
+
     /// Set whether connections should emit verbose logs.
     ///
     /// Enabling this option will emit [log][] messages at the `TRACE` level
@@ -790,6 +1431,19 @@ impl ClientBuilder {
         self
     }
 
+    /// Dump the heads of the requests as they are sent, and of the responses
+    /// received, like `curl --verbose`.
+    ///
+    /// Unlike [`connection_verbose`](ClientBuilder::connection_verbose), this
+    /// doesn't need a logger, and shows HTTP/2 requests as well. See
+    /// [`VerboseConfig`] for what is dumped.
+    ///
+    /// By default, nothing is dumped.
+    pub fn verbose(mut self, verbose: VerboseConfig) -> ClientBuilder {
+        self.config.verbose = Some(verbose);
+        self
+    }
+
     // HTTP options
 
     /// Set an optional timeout for idle sockets being kept-alive.
@@ -817,29 +1471,290 @@ impl ClientBuilder {
         self
     }
 
-    /// Disable keep-alive for the client.
-    pub fn no_keepalive(mut self) -> ClientBuilder {
-        self.config.pool_max_idle_per_host = 0;
-        self.config.tcp_keepalive = None;
-        self
-    }
-
-    /// Only use HTTP/1.
-    pub fn http1_only(mut self) -> ClientBuilder {
-        self.config.alpn_protos = Some(AlpnProtos::HTTP1);
+    /// Set an optional maximum age for pooled connections.
+    ///
+    /// A connection older than this is closed once its current request completes,
+    /// instead of being reused, so the next request makes a fresh handshake.
+    ///
+    /// Pass `None` to disable the limit.
+    ///
+    /// Default is `None`.
+    pub fn pool_max_lifetime<D>(mut self, val: D) -> ClientBuilder
+    where
+        D: Into<Option<Duration>>,
+    {
+        self.config.pool_max_lifetime = val.into();
         self
     }
 
-    /// Only use HTTP/2.
-    pub fn http2_only(mut self) -> ClientBuilder {
-        self.config.alpn_protos = Some(AlpnProtos::HTTP2);
+    /// Sets the maximum number of requests sent on a single pooled connection.
+    ///
+    /// Pass `0` to disable the limit.
+    ///
+    /// Default is no limit.
+    pub fn pool_max_requests(mut self, max: usize) -> ClientBuilder {
+        self.config.pool_max_requests = NonZeroUsize::new(max);
         self
     }
 
-    /// Sets the maximum number of safe retries for HTTP/2 connections.
-    pub fn http2_max_retry_count(mut self, max: usize) -> ClientBuilder {
-        self.config.http2_max_retry_count = max;
-        self
+    /// Set a callback notified when a pooled connection is created, reused,
+    /// evicted or closed.
+    ///
+    /// The callback runs while the pool is locked, so it should return quickly
+    /// and must not call [`Client::pool_stats`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn doc() -> rquest::Result<()> {
+    /// use rquest::{EvictReason, PoolEventKind};
+    ///
+    /// let client = rquest::Client::builder()
+    ///     .pool_max_requests(100)
+    ///     .pool_events(|event| {
+    ///         if event.kind() == PoolEventKind::Evicted(EvictReason::MaxRequests) {
+    ///             println!("retired connection to {}", event.origin());
+    ///         }
+    ///     })
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pool_events<F>(mut self, on_event: F) -> ClientBuilder
+    where
+        F: Fn(&PoolEvent) + Send + Sync + 'static,
+    {
+        self.config.pool_events = Some(Arc::new(on_event));
+        self
+    }
+
+    /// Disable connection pooling for `host`.
+    ///
+    /// Requests to the host are sent with `Connection: close`, each on a new
+    /// connection that is closed after its response, while connections to other
+    /// hosts are still reused. Behind a proxy, each request opens a new tunnel,
+    /// which is useful with proxies that pick a new source IP for each one. A
+    /// host of the form `*.example.com` applies to every subdomain of
+    /// `example.com`.
+    ///
+    /// See [`RequestBuilder::close_connection`](crate::RequestBuilder::close_connection)
+    /// to do this for a single request.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let client = rquest::Client::builder()
+    ///     .proxy(rquest::Proxy::all("http://rotating.proxy:8080")?)
+    ///     .pool_disable_for_host("api.example.com")
+    ///     .build()?;
+    /// # Ok::<(), rquest::Error>(())
+    /// ```
+    pub fn pool_disable_for_host(mut self, host: &str) -> ClientBuilder {
+        self.config
+            .unpooled_hosts
+            .insert(host.to_ascii_lowercase(), ());
+        self
+    }
+
+    /// Set the async runtime the client spawns its background tasks on, such
+    /// as the connections it keeps open, and times them and its requests with.
+    ///
+    /// By default, tasks are spawned with `tokio::spawn`. See the
+    /// [`rt`](crate::rt) module for running a client on another runtime, which
+    /// still needs a Tokio reactor thread for its sockets.
+    pub fn runtime<R>(mut self, runtime: R) -> ClientBuilder
+    where
+        R: Runtime,
+    {
+        self.config.runtime = Some(Arc::new(runtime));
+        self
+    }
+
+    /// Sets the maximum number of requests the client sends at once.
+    ///
+    /// Further requests wait in a queue, and are sent in order as earlier
+    /// responses finish. A request holds its slot until its response body is
+    /// read to the end or dropped.
+    ///
+    /// Pass `0` to disable the limit.
+    ///
+    /// Default is no limit.
+    pub fn max_concurrent_requests(mut self, max: usize) -> ClientBuilder {
+        self.config.max_concurrent_requests = (max > 0).then_some(max);
+        self
+    }
+
+    /// Sets the maximum number of requests in flight to a single origin.
+    ///
+    /// This bounds the connections opened to an origin, since a request only
+    /// opens a connection when no idle one is available. Further requests to the
+    /// origin wait in a queue, like with [`ClientBuilder::max_concurrent_requests`].
+    ///
+    /// Pass `0` to disable the limit.
+    ///
+    /// Default is no limit.
+    pub fn max_connections_per_host(mut self, max: usize) -> ClientBuilder {
+        self.config.max_connections_per_host = (max > 0).then_some(max);
+        self
+    }
+
+    /// Set a timeout for a request waiting in the queue of
+    /// [`ClientBuilder::max_concurrent_requests`] or
+    /// [`ClientBuilder::max_connections_per_host`].
+    ///
+    /// A request still waiting after `timeout` fails with a timeout error, whose
+    /// [`timeout_phase`](crate::Error::timeout_phase) is [`TimeoutPhase::Queue`].
+    ///
+    /// Default is no timeout.
+    pub fn queue_timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.config.queue_timeout = Some(timeout);
+        self
+    }
+
+    /// Disable keep-alive for the client.
+    pub fn no_keepalive(mut self) -> ClientBuilder {
+        self.config.pool_max_idle_per_host = 0;
+        self.config.tcp_keepalive = None;
+        self
+    }
+
+    /// Only use HTTP/1.
+    pub fn http1_only(mut self) -> ClientBuilder {
+        self.config.alpn_protos = Some(AlpnProtos::HTTP1);
+        self
+    }
+
+    /// Only use HTTP/2.
+    pub fn http2_only(mut self) -> ClientBuilder {
+        self.config.alpn_protos = Some(AlpnProtos::HTTP2);
+        self
+    }
+
+    /// Sets the HTTP/2 connection settings.
+    ///
+    /// This controls the SETTINGS frame values and their order, the initial
+    /// WINDOW_UPDATE increment, the PRIORITY frames sent after the preface and the
+    /// pseudo-header order, replacing the settings of an [`emulation`](Self::emulation)
+    /// profile applied before it.
+    ///
+    /// To adjust a profile instead of replacing it, start from its settings with
+    /// [`EmulationProvider::http2_config`] and [`Http2Config::into_builder`].
+    ///
+    /// The `http2_*` flow control and keep-alive setters, such as
+    /// [`http2_adaptive_window`](Self::http2_adaptive_window), are kept apart from
+    /// these settings: they hold whichever profile is applied, before or after them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rquest::http2::{Http2Config, PseudoId, PseudoOrder, SettingId, SettingsOrder};
+    ///
+    /// let http2 = Http2Config::builder()
+    ///     .header_table_size(65536)
+    ///     .enable_push(false)
+    ///     .initial_stream_window_size(6291456)
+    ///     .max_header_list_size(262144)
+    ///     .initial_connection_window_size(15728640 + 65535)
+    ///     .settings_order(
+    ///         SettingsOrder::builder()
+    ///             .extend([
+    ///                 SettingId::HeaderTableSize,
+    ///                 SettingId::EnablePush,
+    ///                 SettingId::InitialWindowSize,
+    ///                 SettingId::MaxHeaderListSize,
+    ///             ])
+    ///             .build(),
+    ///     )
+    ///     .headers_pseudo_order(
+    ///         PseudoOrder::builder()
+    ///             .extend([
+    ///                 PseudoId::Method,
+    ///                 PseudoId::Authority,
+    ///                 PseudoId::Scheme,
+    ///                 PseudoId::Path,
+    ///             ])
+    ///             .build(),
+    ///     )
+    ///     .build();
+    ///
+    /// let client = rquest::Client::builder().http2_config(http2).build().unwrap();
+    /// ```
+    pub fn http2_config(mut self, config: Http2Config) -> ClientBuilder {
+        self.config.http2_config = config;
+        self
+    }
+
+    /// Sets whether HTTP/2 connections use an adaptive flow control.
+    ///
+    /// The flow control windows then grow with the bandwidth-delay product
+    /// of the connection, measured with PING frames, so large downloads over
+    /// high-latency links aren't throttled by the initial window sizes. Those
+    /// sizes are kept, so the SETTINGS frame of an
+    /// [`emulation`](Self::emulation) profile is unchanged.
+    ///
+    /// Default is `false`, or what the HTTP/2 settings set.
+    pub fn http2_adaptive_window(mut self, enabled: bool) -> ClientBuilder {
+        self.config.http2_options.adaptive_window = Some(enabled);
+        self
+    }
+
+    /// Sets the initial stream-level flow control window of HTTP/2 connections,
+    /// in bytes, sent as `SETTINGS_INITIAL_WINDOW_SIZE`, in place of the value of
+    /// the [`http2_config`](Self::http2_config).
+    pub fn http2_initial_stream_window_size(mut self, size: u32) -> ClientBuilder {
+        self.config.http2_options.initial_stream_window_size = Some(size);
+        self
+    }
+
+    /// Sets the initial connection-level flow control window of HTTP/2
+    /// connections, in bytes, raised with a `WINDOW_UPDATE` after the preface, in
+    /// place of the increment of the [`http2_config`](Self::http2_config).
+    pub fn http2_initial_connection_window_size(mut self, size: u32) -> ClientBuilder {
+        self.config.http2_options.initial_connection_window_size = Some(size);
+        self
+    }
+
+    /// Sets an interval for HTTP/2 PING frames sent to keep a connection alive.
+    ///
+    /// A connection whose PING isn't acknowledged within the
+    /// [`http2_keep_alive_timeout`](Self::http2_keep_alive_timeout) is closed,
+    /// and removed from the pool, so a dead connection behind a NAT or a load
+    /// balancer isn't used for the next request.
+    ///
+    /// Default is disabled.
+    pub fn http2_keep_alive_interval(mut self, interval: Duration) -> ClientBuilder {
+        self.config.http2_options.keep_alive_interval = Some(interval);
+        self
+    }
+
+    /// Sets how long to wait for the acknowledgement of a keep-alive PING
+    /// before closing an HTTP/2 connection.
+    ///
+    /// Does nothing without an
+    /// [`http2_keep_alive_interval`](Self::http2_keep_alive_interval).
+    ///
+    /// Default is 20 seconds.
+    pub fn http2_keep_alive_timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.config.http2_options.keep_alive_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets whether keep-alive PINGs are sent on idle HTTP/2 connections,
+    /// without any open stream, like those in the pool.
+    ///
+    /// Does nothing without an
+    /// [`http2_keep_alive_interval`](Self::http2_keep_alive_interval).
+    ///
+    /// Default is `false`.
+    pub fn http2_keep_alive_while_idle(mut self, enabled: bool) -> ClientBuilder {
+        self.config.http2_options.keep_alive_while_idle = Some(enabled);
+        self
+    }
+
+    /// Sets the maximum number of safe retries for HTTP/2 connections.
+    pub fn http2_max_retry_count(mut self, max: usize) -> ClientBuilder {
+        self.config.http2_max_retry_count = max;
+        self
     }
 
     // TCP options
@@ -854,6 +1769,9 @@ impl ClientBuilder {
 
     /// Bind to a local IP Address.
     ///
+    /// Individual requests can override it with
+    /// [`RequestBuilder::local_address`](crate::RequestBuilder::local_address).
+    ///
     /// # Example
     ///
     /// ```
@@ -884,6 +1802,10 @@ impl ClientBuilder {
 
     /// Bind to an interface by `SO_BINDTODEVICE`.
     ///
+    /// On Apple platforms, illumos and Solaris, `IP_BOUND_IF` is used instead, so
+    /// traffic leaves through the interface of that name. Individual requests can
+    /// override it with [`RequestBuilder::interface`](crate::RequestBuilder::interface).
+    ///
     /// # Example
     ///
     /// ```
@@ -927,6 +1849,32 @@ impl ClientBuilder {
         self
     }
 
+    /// Send all requests over the Unix domain socket at `path` instead of TCP.
+    ///
+    /// The request URL is still used for the `Host` header, cookies and redirects,
+    /// but every connection is made to the socket. Proxies are not used, and only
+    /// `http` URLs are supported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let client = rquest::Client::builder()
+    ///     .unix_socket("/var/run/docker.sock")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let res = client.get("http://localhost/version").send();
+    /// ```
+    #[cfg(unix)]
+    #[cfg_attr(docsrs, doc(cfg(unix)))]
+    pub fn unix_socket<P>(mut self, path: P) -> ClientBuilder
+    where
+        P: AsRef<Path>,
+    {
+        self.config.unix_socket = Some(Arc::from(path.as_ref()));
+        self
+    }
+
     /// Set that all sockets have `SO_KEEPALIVE` set with the supplied duration.
     ///
     /// If `None`, the option will not be set.
@@ -960,6 +1908,35 @@ impl ClientBuilder {
         self
     }
 
+    /// Apply several TCP socket options at once.
+    ///
+    /// Only the options set in `options` change; see [`TcpOptions`].
+    pub fn tcp_options(mut self, options: TcpOptions) -> ClientBuilder {
+        if let Some(enabled) = options.nodelay {
+            self.config.nodelay = enabled;
+        }
+        if options.keepalive.is_some() {
+            self.config.tcp_keepalive = options.keepalive;
+        }
+        if options.keepalive_interval.is_some() {
+            self.config.tcp_keepalive_interval = options.keepalive_interval;
+        }
+        if options.keepalive_retries.is_some() {
+            self.config.tcp_keepalive_retries = options.keepalive_retries;
+        }
+        if options.send_buffer_size.is_some() {
+            self.config.tcp_send_buffer_size = options.send_buffer_size;
+        }
+        if options.recv_buffer_size.is_some() {
+            self.config.tcp_recv_buffer_size = options.recv_buffer_size;
+        }
+        #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+        if options.user_timeout.is_some() {
+            self.config.tcp_user_timeout = options.user_timeout;
+        }
+        self
+    }
+
     // TLS/HTTP2 emulation options
 
     /// Configures the client builder to emulation the specified HTTP context.
@@ -1038,6 +2015,21 @@ impl ClientBuilder {
     }
 
     /// Sets the identity to be used for client certificate authentication.
+    ///
+    /// The identity's certificate and chain are sent to servers that request a
+    /// client certificate, for mutual TLS.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let pem = std::fs::read("client.pem")?;
+    /// let client = rquest::Client::builder()
+    ///     .identity(rquest::tls::Identity::from_pem(&pem)?)
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn identity(mut self, identity: Identity) -> ClientBuilder {
         self.config.identity = Some(identity);
         self
@@ -1059,6 +2051,192 @@ impl ClientBuilder {
         self
     }
 
+    /// Pins the public keys that `host` may present.
+    ///
+    /// After the handshake with `host`, and before any request is sent, the SHA-256
+    /// hashes of the SubjectPublicKeyInfo of the certificates in the chain built by
+    /// certificate verification are compared with `pins`. If none matches, the
+    /// connection is closed and the request fails with an error for which
+    /// [`Error::is_certificate_pin_mismatch`](crate::Error::is_certificate_pin_mismatch)
+    /// returns true.
+    ///
+    /// Pinning an intermediate or root key allows the leaf certificate to be
+    /// renewed without updating the pins. A host of the form `*.example.com` pins
+    /// every subdomain of `example.com`. Calling this again for the same host adds
+    /// to its pins.
+    ///
+    /// Pins are checked in addition to the usual certificate verification. If
+    /// verification is disabled or fails, only the server's own certificate is
+    /// compared, since nothing vouches for the rest of the chain it presented.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rquest::tls::Sha256Pin;
+    ///
+    /// let client = rquest::Client::builder()
+    ///     .pin_certificates(
+    ///         "api.example.com",
+    ///         [Sha256Pin::from_base64("sha256/YLh1dUR9y6Kja30RrAn7JKnbQG/uEtLMkBgFF2Fuihg=")?],
+    ///     )
+    ///     .build()?;
+    /// # Ok::<(), rquest::Error>(())
+    /// ```
+    pub fn pin_certificates<I>(mut self, host: &str, pins: I) -> ClientBuilder
+    where
+        I: IntoIterator<Item = Sha256Pin>,
+    {
+        self.config.cert_pins.add(host, pins);
+        self
+    }
+
+    /// Replaces the built-in certificate verification with `verifier`.
+    ///
+    /// After each handshake, `verifier` is called with the certificate chain the
+    /// server presented and the host being connected to, and the connection is
+    /// only used if it returns `Ok`. This allows trust decisions the certificate
+    /// store can't express, such as trust on first use, while keeping the rest
+    /// of the TLS stack unchanged. [`CertChain::is_trusted`] tells whether the
+    /// built-in verification would have accepted the chain. Pins, OCSP and SCT
+    /// checks still apply after the verifier.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let client = rquest::Client::builder()
+    ///     .cert_verifier(|chain, host| {
+    ///         if chain.is_trusted() || host == "intranet.local" {
+    ///             Ok(())
+    ///         } else {
+    ///             Err(format!("untrusted certificate for {}", host).into())
+    ///         }
+    ///     })
+    ///     .build()?;
+    /// # Ok::<(), rquest::Error>(())
+    /// ```
+    pub fn cert_verifier<F>(mut self, verifier: F) -> ClientBuilder
+    where
+        F: Fn(&CertChain, &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.config.cert_verifier = Some(CertVerifier::new(verifier));
+        self
+    }
+
+    /// Verifies server certificates with the operating system's verifier.
+    ///
+    /// On macOS, certificate chains are evaluated by Security.framework, and on
+    /// Windows by CryptoAPI, so enterprise-deployed CAs and trust policies are
+    /// honored exactly as they are by the system browsers. Other platforms have no
+    /// system verifier, and keep the built-in verification.
+    ///
+    /// This replaces any verifier set with [`ClientBuilder::cert_verifier`]. The
+    /// system verifier may block while it fetches missing intermediates or
+    /// revocation information.
+    ///
+    /// Default is `false`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `native-trust` feature to be enabled.
+    #[cfg(feature = "native-trust")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "native-trust")))]
+    pub fn native_trust(mut self, enabled: bool) -> ClientBuilder {
+        self.config.cert_verifier = enabled.then(crate::tls::native::verifier);
+        self
+    }
+
+    /// Checks the revocation status of server certificates with stapled OCSP responses.
+    ///
+    /// The client asks servers to staple an OCSP response to the handshake, and
+    /// checks that it was signed by the certificate's issuer and is current. A
+    /// certificate reported as revoked fails the connection with an error for which
+    /// [`Error::is_certificate_revoked`](crate::Error::is_certificate_revoked) returns
+    /// true. With [`OcspPolicy::Require`], connections without a valid response
+    /// reporting the certificate as good fail as well.
+    ///
+    /// Only stapled responses are checked: no OCSP responder is queried and
+    /// certificate revocation lists are not fetched, so revocation checking never
+    /// adds a request to the handshake. A [`cert_verifier`](Self::cert_verifier)
+    /// can check CRLs where they are needed.
+    ///
+    /// Default is no revocation checking.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rquest::tls::OcspPolicy;
+    ///
+    /// let client = rquest::Client::builder()
+    ///     .ocsp_stapling(OcspPolicy::Soft)
+    ///     .build()?;
+    /// # Ok::<(), rquest::Error>(())
+    /// ```
+    pub fn ocsp_stapling(mut self, policy: OcspPolicy) -> ClientBuilder {
+        self.config.ocsp_policy = Some(policy);
+        self
+    }
+
+    /// Requires server certificates to be logged in Certificate Transparency logs.
+    ///
+    /// As browsers do, the client checks that the certificate has signed certificate
+    /// timestamps (SCTs) embedded from two distinct logs, or three if it is valid for
+    /// more than 180 days. Connections to servers whose certificate falls short fail.
+    ///
+    /// Only the number of distinct logs is checked: SCT signatures are not verified
+    /// against a list of known logs, and SCTs delivered in the TLS handshake or in
+    /// OCSP responses are not counted. Certificates issued by private CAs usually
+    /// carry no SCTs.
+    ///
+    /// The SCTs of a connection are available from
+    /// [`TlsInfo::signed_certificate_timestamps`](crate::tls::TlsInfo::signed_certificate_timestamps)
+    /// with [`ClientBuilder::tls_info`].
+    ///
+    /// Default is `false`.
+    pub fn require_sct(mut self, required: bool) -> ClientBuilder {
+        self.config.require_sct = required;
+        self
+    }
+
+    /// Replaces TLS settings for connections to `host`.
+    ///
+    /// Settings left unset in `options` are taken from the client, so a single
+    /// client can relax verification for an internal host, or require a newer TLS
+    /// version for a sensitive one, while keeping its defaults elsewhere. A host of
+    /// the form `*.example.com` applies to every subdomain of `example.com`, unless
+    /// a subdomain has options of its own. Calling this again for the same host
+    /// replaces its options.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rquest::tls::TlsOptions;
+    ///
+    /// let client = rquest::Client::builder()
+    ///     .tls_for_host("internal.corp", TlsOptions::new().cert_verification(false))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn tls_for_host(mut self, host: &str, options: TlsOptions) -> ClientBuilder {
+        let host = host.to_ascii_lowercase();
+        self.config.tls_host_options.retain(|(h, _)| *h != host);
+        self.config.tls_host_options.push((host, options));
+        self
+    }
+
+    /// Sets the cache that keeps TLS sessions for resumption.
+    ///
+    /// The cache can be restored from sessions saved by an earlier process with
+    /// [`TlsSessionCache::import`], and saved again with
+    /// [`Client::export_tls_sessions`]. Setting a cache enables session resumption,
+    /// even if the emulation profile doesn't send a pre-shared key.
+    pub fn tls_session_cache(mut self, cache: TlsSessionCache) -> ClientBuilder {
+        self.config.tls_session_cache = Some(cache);
+        self
+    }
+
     /// Sets the verify certificate store for the client.
     ///
     /// This method allows you to specify a custom verify certificate store to be used
@@ -1088,12 +2266,36 @@ impl ClientBuilder {
         self
     }
 
-    /// Configures TLS key logging policy for the client.
-    pub fn keylog(mut self, policy: KeyLogPolicy) -> ClientBuilder {
+    /// Logs the TLS secrets of each connection in the NSS key log format.
+    ///
+    /// With the logged secrets, tools such as Wireshark can decrypt captured
+    /// traffic of the client, which helps when debugging fingerprint issues. Keys
+    /// can be logged to the file named by the `SSLKEYLOGFILE` environment variable,
+    /// to a given file, or to a callback.
+    ///
+    /// Anyone with the log can decrypt the traffic, so this is meant for debugging.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rquest::tls::KeyLogPolicy;
+    ///
+    /// let client = rquest::Client::builder()
+    ///     .tls_keylog(KeyLogPolicy::callback(|line| eprintln!("{}", line)))
+    ///     .build()?;
+    /// # Ok::<(), rquest::Error>(())
+    /// ```
+    pub fn tls_keylog(mut self, policy: KeyLogPolicy) -> ClientBuilder {
         self.config.keylog_policy = Some(policy);
         self
     }
 
+    /// Configures TLS key logging policy for the client.
+    #[deprecated(note = "use `ClientBuilder::tls_keylog` instead")]
+    pub fn keylog(self, policy: KeyLogPolicy) -> ClientBuilder {
+        self.tls_keylog(policy)
+    }
+
     /// Configures the use of hostname verification when connecting.
     ///
     /// Defaults to `true`.
@@ -1115,6 +2317,20 @@ impl ClientBuilder {
         self
     }
 
+    /// Sets the post-quantum hybrid key share, or leaves it out with `None`.
+    ///
+    /// This overrides the emulation profile, whose curves otherwise decide whether
+    /// a post-quantum key share is sent. See [`TlsConfigBuilder::pq_key_share`].
+    ///
+    /// [`TlsConfigBuilder::pq_key_share`]: crate::tls::TlsConfigBuilder::pq_key_share
+    pub fn pq_key_share<T>(mut self, key_share: T) -> ClientBuilder
+    where
+        T: Into<Option<PqKeyShare>>,
+    {
+        self.config.pq_key_share = Some(key_share.into());
+        self
+    }
+
     /// Set the maximum allowed TLS version for connections.
     ///
     /// By default there's no maximum.
@@ -1125,9 +2341,10 @@ impl ClientBuilder {
 
     /// Add TLS information as `TlsInfo` extension to responses.
     ///
-    /// # Optional
+    /// The information, such as the negotiated TLS version, cipher suite and
+    /// certificate chain, is available from [`Response::tls_info`](crate::Response::tls_info).
     ///
-    /// feature to be enabled.
+    /// Default is `false`.
     pub fn tls_info(mut self, tls_info: bool) -> ClientBuilder {
         self.config.tls_info = tls_info;
         self
@@ -1169,14 +2386,28 @@ impl ClientBuilder {
         self
     }
 
-    /// Override DNS resolution for specific domains to a particular IP address.
+    /// Sets how the hickory-dns resolver caches answers.
     ///
-    /// Warning
+    /// See [`DnsCache`] for the TTL bounds of successful and failed lookups.
     ///
-    /// Since the DNS protocol has no notion of ports, if you wish to send
-    /// traffic to a particular port you must include this port in the URL
-    /// itself, any port in the overridden addr will be ignored and traffic sent
-    /// to the conventional port for the given scheme (e.g. 80 for http).
+    /// # Optional
+    ///
+    /// Requires the `hickory-dns` feature to be enabled.
+    #[cfg(feature = "hickory-dns")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "hickory-dns")))]
+    pub fn hickory_dns_cache(mut self, cache: DnsCache) -> ClientBuilder {
+        self.config.dns_cache = cache;
+        self
+    }
+
+    /// Override DNS resolution for specific domains to a particular IP address.
+    ///
+    /// Warning
+    ///
+    /// Since the DNS protocol has no notion of ports, if you wish to send
+    /// traffic to a particular port you must include this port in the URL
+    /// itself, any port in the overridden addr will be ignored and traffic sent
+    /// to the conventional port for the given scheme (e.g. 80 for http).
     pub fn resolve(self, domain: &str, addr: SocketAddr) -> ClientBuilder {
         self.resolve_to_addrs(domain, &[addr])
     }
@@ -1196,6 +2427,34 @@ impl ClientBuilder {
         self
     }
 
+    /// Override DNS resolution with the entries of an `/etc/hosts`-style file.
+    ///
+    /// Each line of the file holds an IP address followed by the host names it
+    /// resolves, and `#` starts a comment. The entries are applied like
+    /// [`resolve_to_addrs`](ClientBuilder::resolve_to_addrs), so requests to these
+    /// hosts still send their name in the `Host` header and for TLS SNI.
+    ///
+    /// # Errors
+    ///
+    /// If the file can't be read, the error is returned by
+    /// [`build`](ClientBuilder::build).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// let client = rquest::Client::builder()
+    ///     .hosts_file("/etc/hosts.staging")
+    ///     .build()?;
+    /// # Ok::<(), rquest::Error>(())
+    /// ```
+    pub fn hosts_file<P: AsRef<Path>>(mut self, path: P) -> ClientBuilder {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => self.config.dns_overrides.extend(hosts::parse(&contents)),
+            Err(err) => self.config.error = Some(crate::error::builder(err)),
+        }
+        self
+    }
+
     /// Override the DNS resolver implementation.
     ///
     /// Pass an `Arc` wrapping a trait object implementing `Resolve`.
@@ -1242,6 +2501,79 @@ impl ClientBuilder {
             .push(layer);
         self
     }
+
+    /// Adds a new Tower [`Layer`](https://docs.rs/tower/latest/tower/trait.Layer.html) around
+    /// request execution.
+    ///
+    /// The layered [`Service`](https://docs.rs/tower/latest/tower/trait.Service.html) receives
+    /// every [`Request`] sent by this client and resolves to its final [`Response`], after
+    /// redirects and retries. This can be used for logging, request signing, metrics or caching.
+    ///
+    /// Each subsequent invocation of this function will wrap previous layers.
+    ///
+    /// Example usage:
+    /// ```
+    /// use rquest::header::HeaderValue;
+    /// use tower::util::MapRequestLayer;
+    ///
+    /// let client = rquest::Client::builder()
+    ///     .layer(MapRequestLayer::new(|mut req: rquest::Request| {
+    ///         req.headers_mut()
+    ///             .insert("x-signature", HeaderValue::from_static("signed"));
+    ///         req
+    ///     }))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn layer<L>(mut self, layer: L) -> ClientBuilder
+    where
+        L: Layer<BoxedClientService> + Clone + Send + Sync + 'static,
+        L::Service:
+            Service<Request, Response = Response, Error = Error> + Clone + Send + Sync + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+    {
+        let layer = BoxCloneSyncServiceLayer::new(layer);
+        self.config.layers.get_or_insert_default().push(layer);
+        self
+    }
+
+    /// Answer every request of this client with `respond`, instead of sending it.
+    ///
+    /// This lets code built on rquest be unit tested without binding sockets.
+    /// Layers added with [`ClientBuilder::layer`] still see every request.
+    /// Calling this again replaces the previous function.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `testing` feature to be enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn run() -> rquest::Result<()> {
+    /// let client = rquest::Client::builder()
+    ///     .mock(|req| {
+    ///         assert_eq!(req.url().path(), "/users/1");
+    ///         http::Response::new(r#"{"id":1}"#)
+    ///     })
+    ///     .build()?;
+    ///
+    /// let body = client.get("https://api.example.com/users/1").send().await?.text().await?;
+    /// assert_eq!(body, r#"{"id":1}"#);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "testing")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+    pub fn mock<F, B>(mut self, respond: F) -> ClientBuilder
+    where
+        F: Fn(Request) -> http::Response<B> + Send + Sync + 'static,
+        B: Into<Body>,
+    {
+        let layer = crate::testing::MockLayer::new(respond);
+        self.config.mock = Some(BoxCloneSyncServiceLayer::new(layer));
+        self
+    }
 }
 
 impl Default for Client {
@@ -1334,6 +2666,21 @@ impl Client {
         self.request(Method::HEAD, url)
     }
 
+    /// Start a new [`Session`](crate::Session) over this client.
+    ///
+    /// The session has its own, empty cookie jar, and can be given its own
+    /// default headers and proxy, while sharing the connection pool and TLS
+    /// configuration of this client.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `cookies` feature to be enabled.
+    #[cfg(feature = "cookies")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cookies")))]
+    pub fn session(&self) -> super::Session {
+        super::Session::new(self.clone())
+    }
+
     /// Start building a `Request` with the `Method` and `Url`.
     ///
     /// Returns a `RequestBuilder`, which will allow setting headers and
@@ -1343,7 +2690,14 @@ impl Client {
     ///
     /// This method fails whenever the supplied `Url` cannot be parsed.
     pub fn request<U: IntoUrl>(&self, method: Method, url: U) -> RequestBuilder {
-        let req = url.into_url().map(move |url| Request::new(method, url));
+        let url = match self.inner.load().base_url {
+            Some(ref base) => base
+                .join(url.as_str())
+                .map_err(crate::error::builder)
+                .and_then(IntoUrlSealed::into_url),
+            None => url.into_url(),
+        };
+        let req = url.map(move |url| Request::new(method, url));
         RequestBuilder::new(self.clone(), req)
     }
 
@@ -1363,7 +2717,267 @@ impl Client {
         self.execute_request(request)
     }
 
+    /// Executes a borrowed `Request`, so the same request can be sent again.
+    ///
+    /// The request is cloned for each call: the headers, URL and extensions
+    /// are copied, while a body made of bytes is shared rather than copied.
+    /// Building a request once and executing it in a loop saves parsing the
+    /// URL and the headers, and running the builder, for every send.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the body of the request is a stream, which can't
+    /// be sent more than once, or for any of the reasons `execute` fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), rquest::Error> {
+    /// let (client, req) = rquest::Client::new()
+    ///     .get("https://example.com/health")
+    ///     .header("x-probe", "1")
+    ///     .build_split();
+    /// let req = req?;
+    ///
+    /// for _ in 0..10 {
+    ///     let res = client.execute_ref(&req).await?;
+    ///     println!("{}", res.status());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn execute_ref(&self, request: &Request) -> impl Future<Output = Result<Response, Error>> {
+        match request.try_clone() {
+            Some(request) => self.execute_request(request),
+            None => Pending::new_err(
+                error::builder("request body is a stream, which can't be sent again")
+                    .with_url(request.url().clone()),
+            ),
+        }
+    }
+
+    /// Establishes a connection to the origin of `url`, and puts it in the
+    /// pool, so a later request to it doesn't wait for the handshakes.
+    ///
+    /// This resolves the host, connects, through a proxy if one applies, and
+    /// completes the TLS handshake and ALPN negotiation, the way a request to
+    /// `url` would. The connection is then kept like any idle connection, so
+    /// it's closed after the [`pool_idle_timeout`](ClientBuilder::pool_idle_timeout),
+    /// and not kept at all when the pool is disabled.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if `url` isn't a valid HTTP or HTTPS URL, or the
+    /// connection can't be established.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), rquest::Error> {
+    /// let client = rquest::Client::new();
+    /// client.preconnect("https://example.com").await?;
+    ///
+    /// // reuses the connection
+    /// let res = client.get("https://example.com/checkout").send().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn preconnect<U: IntoUrl>(&self, url: U) -> crate::Result<()> {
+        let (_, url, _, _, _, _, _, _, _, network_scheme) = self.get(url).build()?.pieces();
+
+        let scheme = url.scheme();
+        let client = self.inner.load_full();
+        if (scheme != "http" && scheme != "https") || (client.https_only && scheme != "https") {
+            return Err(error::url_bad_scheme(url));
+        }
+        let uri = match try_uri(&url) {
+            Some(uri) => uri,
+            None => return Err(error::url_bad_uri(url)),
+        };
+
+        let network_scheme = match client.pick_proxy(&uri, &network_scheme) {
+            Some((_, network_scheme)) => network_scheme,
+            None => network_scheme,
+        };
+        let network_scheme = client.network_scheme(&uri, network_scheme);
+
+        client
+            .hyper
+            .preconnect(uri, network_scheme)
+            .await
+            .map_err(|err| error::request(err).with_url(url))
+    }
+
+    /// Establishes connections to the origins of `urls` concurrently, like
+    /// [`preconnect`](Self::preconnect).
+    ///
+    /// # Errors
+    ///
+    /// This method fails with the first error, in the order of `urls`, once
+    /// all connections have been attempted.
+    pub async fn preconnect_many<I>(&self, urls: I) -> crate::Result<()>
+    where
+        I: IntoIterator,
+        I::Item: IntoUrl,
+    {
+        let preconnects = urls.into_iter().map(|url| self.preconnect(url));
+        futures_util::future::join_all(preconnects)
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    /// Serializes the TLS sessions this client can resume.
+    ///
+    /// The result can be restored with [`TlsSessionCache::import`] and given to
+    /// [`ClientBuilder::tls_session_cache`], to resume the sessions after a restart.
+    /// Sessions are only kept when session resumption is enabled, by the emulation
+    /// profile or by setting a cache.
+    ///
+    /// The result contains session secrets, see [`TlsSessionCache`].
+    pub fn export_tls_sessions(&self) -> Vec<u8> {
+        self.inner.load().tls_session_cache.export()
+    }
+
+    /// The timer the requests of this client sleep with.
+    pub(crate) fn timer(&self) -> RuntimeTimer {
+        self.inner.load().timer.clone()
+    }
+
+    /// Computes the JA3, JA4 and JA4H fingerprints of the requests this client sends.
+    ///
+    /// The fingerprints are taken from requests sent to a local listener, so they
+    /// reflect the client's emulation profile and TLS and HTTP settings. See the
+    /// [`fingerprint`](crate::fingerprint) module for details.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the requests can't be captured, e.g. when the client
+    /// only allows HTTPS or can't resolve `localhost`.
+    pub async fn fingerprint(&self) -> crate::Result<crate::fingerprint::FingerprintReport> {
+        crate::fingerprint::fingerprint(self).await
+    }
+
     pub(super) fn execute_request(&self, req: Request) -> Pending {
+        match self.inner.load().service {
+            Some(ref service) => {
+                // the innermost service only holds a weak reference to the
+                // client, so the request keeps it alive until it is sent
+                let client = self.clone();
+                let fut = service.clone().oneshot(req);
+                Pending::new_layered(async move {
+                    let _client = client;
+                    fut.await
+                })
+            }
+            None => self.execute_request_inner(req),
+        }
+    }
+
+    fn execute_request_inner(&self, req: Request) -> Pending {
+        let client = self.inner.load();
+
+        // get the bearer token, unless the request sets its own authorization.
+        let bearer = client.bearer.clone().filter(|_| {
+            !req.headers().contains_key(AUTHORIZATION)
+                && !client.headers.contains_key(AUTHORIZATION)
+        });
+
+        // load cookies from the cookie store, unless the request sets its own.
+        #[cfg(feature = "cookies")]
+        let cookie_store = client.cookie_store(req.extensions()).filter(|_| {
+            !req.headers().contains_key(crate::header::COOKIE)
+                && !client.headers.contains_key(crate::header::COOKIE)
+        });
+        #[cfg(feature = "cookies")]
+        let layered = bearer.is_some() || cookie_store.is_some();
+        #[cfg(not(feature = "cookies"))]
+        let layered = bearer.is_some();
+
+        if !layered {
+            return self.send_request(req);
+        }
+
+        let this = self.clone();
+        Pending::new_layered(async move {
+            let mut req = req;
+
+            if let Some(bearer) = bearer {
+                let token = bearer.token().await;
+                req.headers_mut()
+                    .insert(AUTHORIZATION, bearer_header(&token)?);
+                *RequestConfig::<RequestBearerToken>::get_mut(req.extensions_mut()) = Some(token);
+            }
+
+            #[cfg(feature = "cookies")]
+            if let Some(cookie_store) = cookie_store {
+                let cookies = cookie_store.load_cookies(req.url()).await;
+                for cookie in cookies.into_iter().flatten() {
+                    req.headers_mut().append(crate::header::COOKIE, cookie);
+                }
+            }
+
+            this.send_request(req).await
+        })
+    }
+
+    /// Format the curl command that sends `req` the way this client would.
+    pub(super) fn curl(&self, req: Request) -> String {
+        let (
+            method,
+            url,
+            mut headers,
+            headers_order,
+            body,
+            _extensions,
+            version,
+            _redirect,
+            _allow_compression,
+            network_scheme,
+        ) = req.pieces();
+
+        let client = self.inner.load();
+
+        for name in client.headers.keys() {
+            if !headers.contains_key(name) {
+                for value in client.headers.get_all(name) {
+                    headers.append(name, value.clone());
+                }
+            }
+        }
+
+        #[cfg(any(
+            feature = "gzip",
+            feature = "brotli",
+            feature = "zstd",
+            feature = "deflate"
+        ))]
+        if _allow_compression {
+            add_accpet_encoding_header(&client.accepts, &mut headers);
+        }
+
+        if let Some(order) = headers_order.as_deref().or(client.headers_order.as_deref()) {
+            sort_headers(&mut headers, order);
+        }
+
+        let network_scheme = match try_uri(&url) {
+            Some(uri) => client.network_scheme(&uri, network_scheme),
+            None => network_scheme,
+        };
+
+        super::curl::Command {
+            method: &method,
+            url: &url,
+            headers: &headers,
+            body: body.as_ref().and_then(Body::as_bytes),
+            version,
+            proxy: network_scheme.proxy_scheme(),
+            insecure: !client.cert_verification,
+        }
+        .format()
+    }
+
+    fn send_request(&self, req: Request) -> Pending {
         let (
             method,
             url,
@@ -1402,14 +3016,6 @@ impl Client {
             }
         }
 
-        // add cookies from the cookie store.
-        #[cfg(feature = "cookies")]
-        if let Some(cookie_store) = client.cookie_store.as_ref() {
-            if !headers.contains_key(crate::header::COOKIE) {
-                add_cookie_header(cookie_store, &mut headers, &url);
-            }
-        }
-
         // add accept-encoding header
         #[cfg(any(
             feature = "gzip",
@@ -1427,6 +3033,34 @@ impl Client {
             None => return Pending::new_err(error::url_bad_uri(url)),
         };
 
+        // serve the response from the cache, or revalidate a stale one
+        let stale = match client
+            .cache
+            .as_ref()
+            .map(|cache| cache.lookup(&method, &url, &headers))
+        {
+            Some(Lookup::Fresh(entry)) => {
+                let res = with_request_extensions(entry.into_response(), &extensions);
+                let max_size = client.max_response_size.fetch(&extensions).copied();
+                let res = Response::new(res, url, Vec::new(), client.accepts, None, None, max_size);
+                return Pending::new_layered(std::future::ready(Ok(res)));
+            }
+            Some(Lookup::Stale(entry)) => {
+                entry.set_conditions(&mut headers);
+                Some(entry)
+            }
+            Some(Lookup::Miss) | None => None,
+        };
+
+        // compress the body if requested
+        #[cfg(any(
+            feature = "gzip",
+            feature = "brotli",
+            feature = "zstd",
+            feature = "deflate"
+        ))]
+        let body = compress_body(body, &extensions, &mut headers);
+
         // reuse the body if possible
         let (reusable, body) = match body {
             Some(body) => {
@@ -1435,21 +3069,68 @@ impl Client {
             }
             None => (None, Body::empty()),
         };
+        let body = with_upload_progress(body, &extensions);
+
+        // sign the request as it is sent, now that its headers and body are final
+        if let Some(signer) = RequestConfig::<RequestAwsSigner>::get(&extensions) {
+            let payload = match reusable {
+                Some(Some(ref body)) => body.as_bytes(),
+                Some(None) => None,
+                None => Some(&[][..]),
+            };
+            if let Err(err) = signer.sign(&method, &url, &mut headers, payload, SystemTime::now()) {
+                return Pending::new_err(err);
+            }
+        }
+
+        // pick a proxy from the pool, unless the request has its own network scheme
+        let (network_scheme, proxy) = match client.pick_proxy(&uri, &network_scheme) {
+            Some((index, network_scheme)) => (network_scheme, Some(index)),
+            None => (network_scheme, None),
+        };
 
-        client.proxy_auth(&uri, &mut headers);
+        client.proxy_auth(&uri, &network_scheme, &mut headers);
 
         let headers_order = headers_order.or_else(|| client.headers_order.clone());
 
         let network_scheme = client.network_scheme(&uri, network_scheme);
 
+        // run the NTLM handshake on one connection, kept out of the pool
+        #[cfg(feature = "ntlm")]
+        let extensions = {
+            let mut extensions = extensions;
+            if RequestConfig::<RequestNtlmAuth>::get(&extensions).is_some() {
+                extensions.insert(PinnedConnection::<Body>::new());
+            }
+            extensions
+        };
+
+        // hold the body back until the server agrees to receive it
+        let mut sent_headers = headers.clone();
+        client.close_unpooled(&uri, &mut sent_headers);
+        let mut sent_extensions = extensions.clone();
+        let body = if client.expects_continue(&body, version, &extensions) {
+            let (body, on_informational) =
+                body.expect_continue(client.expect_continue_timeout, client.timer.clone());
+            sent_headers.insert(EXPECT, HeaderValue::from_static("100-continue"));
+            let on_informational = match sent_extensions.remove::<OnInformational>() {
+                Some(callback) => on_informational.then(callback),
+                None => on_informational,
+            };
+            sent_extensions.insert(on_informational);
+            body
+        } else {
+            body
+        };
+
         let in_flight = {
             let res = InnerRequest::builder()
                 .uri(uri)
                 .method(method.clone())
-                .headers(headers.clone())
+                .headers(sent_headers)
                 .headers_order(headers_order.as_deref())
                 .version(version)
-                .extensions(extensions.clone())
+                .extensions(sent_extensions)
                 .network_scheme(network_scheme.clone())
                 .body(body);
 
@@ -1459,14 +3140,34 @@ impl Client {
             }
         };
 
-        let total_timeout = client
-            .total_timeout
-            .fetch(&extensions)
-            .copied()
-            .map(tokio::time::sleep)
-            .map(Box::pin);
+        let timeout = client.total_timeout.fetch(&extensions).copied();
+        let deadline = match (timeout, RequestConfig::<RequestDeadline>::get(&extensions)) {
+            (Some(timeout), Some(&deadline)) => Some(deadline.min(Instant::now() + timeout)),
+            (Some(timeout), None) => Some(Instant::now() + timeout),
+            (None, deadline) => deadline.copied(),
+        };
+        let total_timeout = deadline.map(|deadline| client.timer.sleep_until(deadline));
         let read_timeout = client.read_timeout.fetch(&extensions).copied();
-        let read_timeout_fut = read_timeout.map(tokio::time::sleep).map(Box::pin);
+        let read_timeout_fut = read_timeout.map(|timeout| client.timer.sleep(timeout));
+
+        let span = RequestSpan::new(&method, &url);
+        span.send(&url);
+
+        let metrics = client.metrics.clone().map(|observer| {
+            let bytes_sent = match reusable {
+                Some(Some(ref body)) => body.as_bytes().map(|bytes| bytes.len() as u64),
+                Some(None) => None,
+                None => Some(0),
+            };
+            Recorder::new(observer, method.clone(), url.clone(), bytes_sent)
+        });
+        let har = client.har.clone().map(Recording::new);
+        let queued = client.limits.acquire(&url, &client.timer);
+        let throttle = client
+            .rate_limiter
+            .as_ref()
+            .and_then(|limiter| limiter.reserve(&url))
+            .map(|wait| client.timer.sleep(wait));
 
         Pending {
             inner: PendingInner::Request(PendingRequest {
@@ -1478,15 +3179,35 @@ impl Client {
                 version,
                 extensions,
                 urls: Vec::new(),
+                history: Vec::new(),
+                preview: None,
+                cookies: None,
+                handshake: None,
+                refresh: None,
                 http2_retry_count: 0,
                 http2_max_retry_count: client.http2_max_retry_count,
+                retries: 0,
+                retry_delay: None,
+                retry_after_count: 0,
+                retry_after_waited: Duration::ZERO,
                 redirect,
                 network_scheme,
+                proxy,
+                stale,
+                sent_at: SystemTime::now(),
                 client,
                 in_flight,
                 total_timeout,
                 read_timeout_fut,
                 read_timeout,
+                first_byte: None,
+                span,
+                metrics,
+                har,
+                throttle,
+                queued,
+                permits: None,
+                retrying: false,
             }),
         }
     }
@@ -1520,9 +3241,63 @@ impl Client {
             inner: self.inner.as_ref(),
             current: (**self.inner.load()).clone(),
             emulation: None,
+            rebuild_tls: false,
         }
     }
 
+    /// Replaces the certificate store used to verify servers.
+    ///
+    /// This is a shortcut for `client.update().cert_store(store).apply()`. See
+    /// [`ClientUpdate::cert_store`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rquest::tls::CertStore;
+    ///
+    /// let client = rquest::Client::new();
+    /// // ... later, once the CA has been rotated
+    /// client.update_cert_store(CertStore::default())?;
+    /// # Ok::<(), rquest::Error>(())
+    /// ```
+    pub fn update_cert_store(&self, store: CertStore) -> crate::Result<()> {
+        self.update().cert_store(store).apply()
+    }
+
+    /// Returns a snapshot of the connection pool statistics.
+    ///
+    /// This is useful to observe pool health in long-running programs, e.g. to tune
+    /// `pool_max_idle_per_host`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let client = rquest::Client::new();
+    /// let stats = client.pool_stats();
+    /// for host in stats.hosts() {
+    ///     println!("{}: {} idle, {} in flight", host.origin(), host.idle(), host.in_flight());
+    /// }
+    /// ```
+    pub fn pool_stats(&self) -> crate::PoolStats {
+        self.inner.load().hyper.pool_stats()
+    }
+
+    /// Clears the answers cached by the DNS resolver.
+    ///
+    /// New connections resolve their host again, e.g. after a failover changed
+    /// its addresses. Connections already in the pool are kept. The default
+    /// `getaddrinfo` resolver has no cache, so this does nothing for it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let client = rquest::Client::new();
+    /// client.clear_dns_cache();
+    /// ```
+    pub fn clear_dns_cache(&self) {
+        self.inner.load().resolver.clear_cache();
+    }
+
     /// Clones the `Client` into a new instance.
     ///
     /// This method creates a new instance of the `Client` by cloning its internal state.
@@ -1539,12 +3314,53 @@ impl Client {
     /// ```
     #[inline]
     pub fn cloned(&self) -> Self {
+        let mut client = (**self.inner.load()).clone();
         Self {
-            inner: Arc::new(ArcSwap::from_pointee((**self.inner.load()).clone())),
+            inner: Arc::new_cyclic(|weak| {
+                // point the layered service at the new client
+                client.service = client
+                    .layers
+                    .as_deref()
+                    .map(|layers| layered_service(weak, layers));
+                ArcSwap::from_pointee(client)
+            }),
+        }
+    }
+}
+
+/// The innermost service of [`ClientBuilder::layer`], which executes a request on the client.
+///
+/// It holds a weak reference, since the layered service is stored in the client itself.
+#[derive(Clone)]
+struct ClientService(Weak<ArcSwap<ClientRef>>);
+
+impl Service<Request> for ClientService {
+    type Response = Response;
+    type Error = Error;
+    type Future = Pending;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        match self.0.upgrade() {
+            Some(inner) => Client { inner }.execute_request_inner(req),
+            None => Pending::new_err(error::request("client has been dropped")),
         }
     }
 }
 
+fn layered_service(
+    client: &Weak<ArcSwap<ClientRef>>,
+    layers: &[BoxedClientLayer],
+) -> BoxedClientService {
+    layers.iter().fold(
+        BoxCloneSyncService::new(ClientService(client.clone())),
+        |service, layer| layer.layer(service),
+    )
+}
+
 impl tower_service::Service<Request> for Client {
     type Response = Response;
     type Error = Error;
@@ -1577,18 +3393,37 @@ impl tower_service::Service<Request> for &'_ Client {
 struct ClientRef {
     accepts: Accepts,
     #[cfg(feature = "cookies")]
-    cookie_store: Option<Arc<dyn cookie::CookieStore>>,
+    cookie_store: Option<Arc<dyn cookie::AsyncCookieStore>>,
+    bearer: Option<Arc<BearerAuth>>,
+    base_url: Option<Url>,
+    metrics: Option<Arc<dyn MetricsObserver>>,
+    har: Option<HarRecorder>,
+    limits: RequestLimits,
+    resolver: DynResolver,
     headers: HeaderMap,
     headers_order: Option<Cow<'static, [HeaderName]>>,
     hyper: HyperClient<Connector, super::Body>,
     redirect: redirect::Policy,
+    retry: retry::Policy,
+    respect_retry_after: bool,
+    max_retry_after_wait: Duration,
+    rate_limiter: Option<RateLimiter>,
+    cache: Option<CacheConfig>,
     referer: bool,
     total_timeout: RequestConfig<RequestTimeout>,
-    read_timeout: RequestConfig<RequestTimeout>,
+    read_timeout: RequestConfig<RequestReadTimeout>,
+    max_response_size: RequestConfig<RequestMaxResponseSize>,
+    unpooled_hosts: HashMap<String, ()>,
+    first_byte_timeout: Option<Duration>,
+    timer: RuntimeTimer,
+    expect_continue: Option<u64>,
+    expect_continue_timeout: Duration,
     https_only: bool,
     http2_max_retry_count: usize,
+    http2_options: Http2Options,
     proxies: Vec<Proxy>,
     proxies_maybe_http_auth: bool,
+    proxy_pool: Option<ProxyPool>,
     network_scheme: NetworkSchemeBuilder,
     alpn_protos: Option<AlpnProtos>,
     keylog: Option<KeyLogPolicy>,
@@ -1597,17 +3432,62 @@ struct ClientRef {
     identity: Option<Identity>,
     cert_store: Option<CertStore>,
     cert_verification: bool,
+    cert_pins: CertPins,
+    cert_verifier: Option<CertVerifier>,
+    ocsp_policy: Option<OcspPolicy>,
+    require_sct: bool,
+    tls_handshake_timeout: Option<Duration>,
+    tls_host_options: Vec<(String, TlsOptions)>,
+    tls_session_cache: TlsSessionCache,
+    tls_session_resumption: bool,
     min_tls_version: Option<TlsVersion>,
+    pq_key_share: Option<Option<PqKeyShare>>,
     max_tls_version: Option<TlsVersion>,
+    tls_config: TlsConfig,
+    layers: Option<Vec<BoxedClientLayer>>,
+    service: Option<BoxedClientService>,
 }
 
 impl ClientRef {
-    #[inline]
-    fn proxy_auth(&self, dst: &Uri, headers: &mut HeaderMap) {
-        if !self.proxies_maybe_http_auth {
+    /// Whether a request with `body` is sent with `Expect: 100-continue`.
+    fn expects_continue(
+        &self,
+        body: &Body,
+        version: Option<Version>,
+        extensions: &Extensions,
+    ) -> bool {
+        if body.is_end_stream() || version == Some(Version::HTTP_2) {
+            return false;
+        }
+        match RequestConfig::<RequestExpectContinue>::get(extensions) {
+            Some(&enabled) => enabled,
+            None => self
+                .expect_continue
+                .is_some_and(|min| body.size_hint().exact().is_none_or(|len| len >= min)),
+        }
+    }
+
+    /// Close the connection of a request to a host that pooling is disabled
+    /// for, so the request is sent on a new connection of its own.
+    fn close_unpooled(&self, dst: &Uri, headers: &mut HeaderMap) {
+        let Some(host) = dst.host() else {
             return;
+        };
+        if lookup_host(&self.unpooled_hosts, host).is_some() {
+            headers.insert(CONNECTION, HeaderValue::from_static("close"));
         }
+    }
+
+    /// The cookie store of a request, its own or else the client's.
+    #[cfg(feature = "cookies")]
+    fn cookie_store(&self, extensions: &Extensions) -> Option<Arc<dyn cookie::AsyncCookieStore>> {
+        RequestConfig::<RequestCookieJar>::get(extensions)
+            .map(|store| store.0.clone())
+            .or_else(|| self.cookie_store.clone())
+    }
 
+    #[inline]
+    fn proxy_auth(&self, dst: &Uri, network_scheme: &NetworkScheme, headers: &mut HeaderMap) {
         // Only set the header here if the destination scheme is 'http',
         // since otherwise, the header will be included in the CONNECT tunnel
         // request instead.
@@ -1619,6 +3499,24 @@ impl ClientRef {
             return;
         }
 
+        // A request-level network scheme replaces the client's proxies
+        if !matches!(network_scheme, NetworkScheme::Default) {
+            if let Some(proxy_scheme) = network_scheme.proxy_scheme() {
+                if let Some(header) = proxy_scheme.http_auth() {
+                    headers.insert(PROXY_AUTHORIZATION, header);
+                }
+
+                if let Some(http_headers) = proxy_scheme.maybe_http_headers() {
+                    headers.extend(http_headers.clone());
+                }
+            }
+            return;
+        }
+
+        if !self.proxies_maybe_http_auth {
+            return;
+        }
+
         // Find the first proxy that matches the destination URI
         // If a matching proxy provides an HTTP basic auth header, insert it into the headers
         for proxy in self.proxies.iter() {
@@ -1636,6 +3534,18 @@ impl ClientRef {
         }
     }
 
+    /// Pick a proxy from the pool, returning its index and the network scheme to use it.
+    fn pick_proxy(&self, uri: &Uri, default: &NetworkScheme) -> Option<(usize, NetworkScheme)> {
+        if !matches!(default, NetworkScheme::Default) {
+            return None;
+        }
+
+        let (index, proxy_scheme) = self.proxy_pool.as_ref()?.select(uri)?;
+        let mut builder = self.network_scheme.clone();
+        builder.proxy_scheme(proxy_scheme);
+        Some((index, builder.build()))
+    }
+
     #[inline]
     fn network_scheme(&self, uri: &Uri, default: NetworkScheme) -> NetworkScheme {
         if matches!(default, NetworkScheme::Default) {
@@ -1661,10 +3571,13 @@ impl_debug!(ClientRef,{
     headers_order,
     hyper,
     redirect,
+    retry,
+    cache,
     referer,
     https_only,
     http2_max_retry_count,
     proxies,
+    proxy_pool,
     network_scheme,
     cert_verification
 });
@@ -1677,6 +3590,7 @@ pub struct ClientUpdate<'c> {
     inner: &'c ArcSwap<ClientRef>,
     current: ClientRef,
     emulation: Option<EmulationProvider>,
+    rebuild_tls: bool,
 }
 
 impl<'c> ClientUpdate<'c> {
@@ -1705,7 +3619,7 @@ impl<'c> ClientUpdate<'c> {
     #[inline]
     pub fn cookie_provider<C>(mut self, cookie_store: Arc<C>) -> ClientUpdate<'c>
     where
-        C: cookie::CookieStore + 'static,
+        C: cookie::AsyncCookieStore + 'static,
     {
         std::mem::swap(&mut self.current.cookie_store, &mut Some(cookie_store as _));
         self
@@ -1782,6 +3696,18 @@ impl<'c> ClientUpdate<'c> {
         self
     }
 
+    /// Replaces the certificate store used to verify servers.
+    ///
+    /// New connections are verified against `store`, which lets long-running
+    /// services rotate private CAs without rebuilding their clients. Pooled
+    /// connections were verified when they were established, and are kept.
+    #[inline]
+    pub fn cert_store(mut self, store: CertStore) -> ClientUpdate<'c> {
+        self.current.cert_store = Some(store);
+        self.rebuild_tls = true;
+        self
+    }
+
     /// Configures the client to emulation the specified HTTP context.
     ///
     /// This method sets the necessary headers, HTTP/1 and HTTP/2 configurations, and TLS config
@@ -1804,6 +3730,7 @@ impl<'c> ClientUpdate<'c> {
     #[inline]
     pub fn apply(self) -> Result<(), Error> {
         let mut current = self.current;
+        let mut rebuild_tls = self.rebuild_tls;
 
         if let Some(emulation) = self.emulation {
             if let Some(mut headers) = emulation.default_headers {
@@ -1818,7 +3745,8 @@ impl<'c> ClientUpdate<'c> {
                 current.hyper.set_http1_config(http1_config);
             }
 
-            if let Some(http2_config) = emulation.http2_config {
+            if let Some(mut http2_config) = emulation.http2_config {
+                current.http2_options.apply(&mut http2_config);
                 current.hyper.set_http2_config(http2_config);
             }
 
@@ -1827,26 +3755,46 @@ impl<'c> ClientUpdate<'c> {
                     tls_config.alpn_protos = alpn_protos;
                 }
 
+                if current.tls_session_resumption {
+                    tls_config.pre_shared_key = true;
+                }
+
                 if current.min_tls_version.is_some() {
                     tls_config.min_tls_version = current.min_tls_version;
                 }
 
+                if current.pq_key_share.is_some() {
+                    tls_config.pq_key_share = current.pq_key_share;
+                }
+
                 if current.max_tls_version.is_some() {
                     tls_config.max_tls_version = current.max_tls_version;
                 }
 
-                let connector = TlsConnector::builder(tls_config)
-                    .keylog(current.keylog.clone())
-                    .identity(current.identity.clone())
-                    .cert_store(current.cert_store.clone())
-                    .cert_verification(current.cert_verification)
-                    .tls_sni(current.tls_sni)
-                    .verify_hostname(current.verify_hostname)
-                    .build()?;
-                current.hyper.connector_mut().set_tls_connector(connector);
+                current.tls_config = tls_config;
+                rebuild_tls = true;
             }
         }
 
+        if rebuild_tls {
+            let connector = TlsConnector::builder(current.tls_config.clone())
+                .keylog(current.keylog.clone())
+                .identity(current.identity.clone())
+                .cert_store(current.cert_store.clone())
+                .cert_verification(current.cert_verification)
+                .cert_pins(current.cert_pins.clone())
+                .cert_verifier(current.cert_verifier.clone())
+                .ocsp_policy(current.ocsp_policy)
+                .require_sct(current.require_sct)
+                .handshake_timeout(current.tls_handshake_timeout)
+                .host_options(current.tls_host_options.clone())
+                .session_cache(Some(current.tls_session_cache.clone()))
+                .tls_sni(current.tls_sni)
+                .verify_hostname(current.verify_hostname)
+                .build()?;
+            current.hyper.connector_mut().set_tls_connector(connector);
+        }
+
         self.inner.store(Arc::new(current));
         Ok(())
     }
@@ -1859,34 +3807,80 @@ pin_project! {
     }
 }
 
+type LayeredFuture = SyncWrapper<Pin<Box<dyn Future<Output = Result<Response, Error>> + Send>>>;
+
 #[allow(clippy::large_enum_variant)]
 enum PendingInner {
     Request(PendingRequest),
+    Layered(LayeredFuture),
     Error(Option<Error>),
 }
 
+type CookieFuture<T> = SyncWrapper<Pin<Box<dyn Future<Output = T> + Send>>>;
+
+/// A bearer token being refreshed after the request was rejected with it.
+type TokenRefresh = SyncWrapper<Pin<Box<dyn Future<Output = String> + Send>>>;
+
+/// A cookie store operation a request waits on.
+#[cfg_attr(not(feature = "cookies"), allow(dead_code))]
+enum CookieStep {
+    /// Storing the cookies of a response, before handling it.
+    Store(
+        CookieFuture<()>,
+        http::Response<ResponseBody>,
+        Option<Bytes>,
+    ),
+    /// Loading the cookies for a redirect, before sending it.
+    Load(CookieFuture<Option<Vec<HeaderValue>>>, Body),
+}
+
+/// The most times a request is sent again after a `Retry-After`.
+const MAX_RETRY_AFTER: usize = 10;
+
 pin_project! {
     struct PendingRequest {
         method: Method,
         url: Url,
         headers: HeaderMap,
         headers_order: Option<Cow<'static, [HeaderName]>>,
-        body: Option<Option<Bytes>>,
+        body: Option<Option<Body>>,
         version: Option<Version>,
         extensions: Extensions,
         urls: Vec<Url>,
+        history: Vec<redirect::RedirectRecord>,
+        preview: Option<RedirectPreview>,
+        cookies: Option<CookieStep>,
+        handshake: Option<(ResponseBody, Body)>,
+        refresh: Option<(TokenRefresh, Body)>,
         http2_retry_count: usize,
         http2_max_retry_count: usize,
+        retries: usize,
+        retry_delay: Option<Pin<Box<dyn Sleep>>>,
+        retry_after_count: usize,
+        retry_after_waited: Duration,
         redirect: Option<redirect::Policy>,
         network_scheme: NetworkScheme,
+        proxy: Option<usize>,
+        stale: Option<CachedResponse>,
+        sent_at: SystemTime,
         client: Guard<Arc<ClientRef>>,
         #[pin]
         in_flight: HyperResponseFuture,
         #[pin]
-        total_timeout: Option<Pin<Box<Sleep>>>,
+        total_timeout: Option<Pin<Box<dyn Sleep>>>,
         #[pin]
-        read_timeout_fut: Option<Pin<Box<Sleep>>>,
+        read_timeout_fut: Option<Pin<Box<dyn Sleep>>>,
         read_timeout: Option<Duration>,
+        // the time left for the response headers of the current attempt
+        first_byte: Option<Pin<Box<dyn Sleep>>>,
+        span: RequestSpan,
+        metrics: Option<Recorder>,
+        har: Option<Recording>,
+        throttle: Option<Pin<Box<dyn Sleep>>>,
+        queued: Option<Queued>,
+        permits: Option<Permits>,
+        // an attempt past its retry delay, waiting for the client's throttles
+        retrying: bool,
     }
 }
 
@@ -1897,12 +3891,12 @@ impl PendingRequest {
     }
 
     #[inline]
-    fn total_timeout(self: Pin<&mut Self>) -> Pin<&mut Option<Pin<Box<Sleep>>>> {
+    fn total_timeout(self: Pin<&mut Self>) -> Pin<&mut Option<Pin<Box<dyn Sleep>>>> {
         self.project().total_timeout
     }
 
     #[inline]
-    fn read_timeout(self: Pin<&mut Self>) -> Pin<&mut Option<Pin<Box<Sleep>>>> {
+    fn read_timeout(self: Pin<&mut Self>) -> Pin<&mut Option<Pin<Box<dyn Sleep>>>> {
         self.project().read_timeout_fut
     }
 
@@ -1923,13 +3917,12 @@ impl PendingRequest {
 
         trace!("can retry {:?}", err);
 
-        let body = match self.body {
-            Some(Some(ref body)) => Body::reusable(body.clone()),
-            Some(None) => {
+        let body = match self.replay_body() {
+            Some(body) => body,
+            None => {
                 debug!("error was retryable, but body not reusable");
                 return false;
             }
-            None => Body::empty(),
         };
 
         if self.http2_retry_count >= self.http2_max_retry_count {
@@ -1938,6 +3931,354 @@ impl PendingRequest {
         }
         self.http2_retry_count += 1;
 
+        self.resend(body)
+    }
+
+    /// Schedule another attempt according to the client's `retry::Policy`.
+    ///
+    /// `headers` are those of a response with a retryable status, if any.
+    fn retry_backoff(mut self: Pin<&mut Self>, headers: Option<&HeaderMap>) -> bool {
+        if self.retries >= self.client.retry.max_retries() {
+            trace!("retry policy attempts exhausted");
+            return false;
+        }
+
+        if let Some(None) = self.body {
+            debug!("request is retryable, but body not reusable");
+            return false;
+        }
+
+        let delay = match self.client.retry.delay(self.retries + 1, headers) {
+            Some(delay) => delay,
+            None => {
+                debug!("Retry-After exceeds the retry policy's maximum wait");
+                return false;
+            }
+        };
+
+        self.retries += 1;
+        trace!("retry {} for '{}' in {:?}", self.retries, self.url, delay);
+        self.wait_retry(delay);
+        true
+    }
+
+    /// Wait out the `Retry-After` of a `429` or `503` response, if the client
+    /// respects it, before sending the request again.
+    fn retry_after(mut self: Pin<&mut Self>, status: StatusCode, headers: &HeaderMap) -> bool {
+        if !self.client.respect_retry_after
+            || (status != StatusCode::TOO_MANY_REQUESTS
+                && status != StatusCode::SERVICE_UNAVAILABLE)
+        {
+            return false;
+        }
+
+        let Some(wait) = retry::retry_after(headers) else {
+            return false;
+        };
+
+        if self.retry_after_count >= MAX_RETRY_AFTER {
+            debug!("Retry-After attempts exhausted");
+            return false;
+        }
+
+        if self.retry_after_waited + wait > self.client.max_retry_after_wait {
+            debug!("Retry-After exceeds the maximum wait");
+            return false;
+        }
+
+        if let Some(None) = self.body {
+            debug!("Retry-After received, but body not reusable");
+            return false;
+        }
+
+        self.retry_after_count += 1;
+        self.retry_after_waited += wait;
+        self.span.retry_after(status, wait);
+        if let Some(ref mut metrics) = self.metrics {
+            metrics.retry_after(wait);
+        }
+        self.wait_retry(wait);
+        true
+    }
+
+    /// Send the request again after `delay`, giving back its slot under the
+    /// client's request limits in the meantime.
+    fn wait_retry(&mut self, delay: Duration) {
+        self.retry_delay = Some(self.client.timer.sleep(delay));
+        self.permits = None;
+    }
+
+    /// Queue the next attempt behind the client's rate limiter and request
+    /// limits, like the first one.
+    fn throttle_retry(&mut self) {
+        self.throttle = self
+            .client
+            .rate_limiter
+            .as_ref()
+            .and_then(|limiter| limiter.reserve(&self.url))
+            .map(|wait| self.client.timer.sleep(wait));
+        self.queued = self.client.limits.acquire(&self.url, &self.client.timer);
+        self.retrying = true;
+    }
+
+    /// Wait for the rate limiter of the host, then for a slot under the
+    /// client's request limits.
+    fn poll_throttle(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        if let Some(throttle) = self.throttle.as_mut() {
+            ready!(throttle.as_mut().poll(cx));
+            self.throttle = None;
+        }
+
+        if let Some(queued) = self.queued.as_mut() {
+            let permits = ready!(queued.get_mut().as_mut().poll(cx))?;
+            self.queued = None;
+            self.permits = Some(permits);
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    /// Answer the Digest challenge of a `401` response, if the request has
+    /// credentials for it and hasn't sent any yet.
+    fn retry_digest_auth(mut self: Pin<&mut Self>, headers: &HeaderMap) -> bool {
+        if self.headers.contains_key(AUTHORIZATION) {
+            return false;
+        }
+
+        let Some(auth) = RequestConfig::<RequestDigestAuth>::get(&self.extensions) else {
+            return false;
+        };
+        let Some(authorization) = auth.authorize(headers, &self.method, &self.url) else {
+            debug!("no supported Digest challenge in 401 response");
+            return false;
+        };
+        let Some(body) = self.replay_body() else {
+            debug!("Digest challenge received, but body not reusable");
+            return false;
+        };
+
+        self.headers.insert(AUTHORIZATION, authorization);
+        self.resend(body)
+    }
+
+    /// Answer the Digest challenge of a `407` response from a proxy the request
+    /// was forwarded to, if the proxy has Digest credentials and the request
+    /// hasn't sent any yet.
+    fn retry_proxy_digest_auth(mut self: Pin<&mut Self>, headers: &HeaderMap) -> bool {
+        if self.url.scheme() != "http" || self.headers.contains_key(PROXY_AUTHORIZATION) {
+            return false;
+        }
+
+        let Some(digest) = self
+            .network_scheme
+            .proxy_scheme()
+            .and_then(ProxyScheme::maybe_credentials)
+            .and_then(|credentials| credentials.digest_auth())
+        else {
+            return false;
+        };
+        // a forwarded request is sent in absolute form
+        let Some(authorization) = digest.authorize_proxy(headers, &self.method, self.url.as_str())
+        else {
+            debug!("no supported Digest challenge in 407 response");
+            return false;
+        };
+        let Some(body) = self.replay_body() else {
+            debug!("proxy Digest challenge received, but body not reusable");
+            return false;
+        };
+
+        self.headers.insert(PROXY_AUTHORIZATION, authorization);
+        self.resend(body)
+    }
+
+    /// Take the next step of the NTLM handshake answering a `401` response, if
+    /// the request has credentials for it, returning the body to send it with.
+    #[cfg(feature = "ntlm")]
+    fn retry_ntlm_auth(mut self: Pin<&mut Self>, headers: &HeaderMap) -> Option<Body> {
+        use crate::header::WWW_AUTHENTICATE;
+        use crate::ntlm::Scheme;
+
+        let credentials = RequestConfig::<RequestNtlmAuth>::get(&self.extensions)?;
+        let scheme = Scheme::offered(headers, &WWW_AUTHENTICATE)?;
+        let negotiate = credentials.negotiate(scheme);
+
+        let authorization = match self.headers.get(AUTHORIZATION) {
+            None => negotiate,
+            // the server answered the negotiate message with its challenge
+            Some(sent) if *sent == negotiate => {
+                let Some(authenticate) = credentials.authenticate(headers, &WWW_AUTHENTICATE)
+                else {
+                    debug!("no NTLM challenge in 401 response");
+                    return None;
+                };
+                authenticate
+            }
+            Some(_) => return None,
+        };
+        let Some(body) = self.replay_body() else {
+            debug!("NTLM challenge received, but body not reusable");
+            return None;
+        };
+
+        self.headers.insert(AUTHORIZATION, authorization);
+        Some(body)
+    }
+
+    /// Refresh the bearer token a `401` response rejected, if the client's
+    /// `TokenProvider` supplied it and the request hasn't been retried with a
+    /// new one yet.
+    fn retry_bearer_auth(mut self: Pin<&mut Self>) -> bool {
+        let Some(bearer) = self.client.bearer.clone() else {
+            return false;
+        };
+        let Some(token) = RequestConfig::<RequestBearerToken>::get_mut(&mut self.extensions).take()
+        else {
+            return false;
+        };
+
+        // the token may have been dropped on a redirect to another origin
+        let sent = self
+            .headers
+            .get(AUTHORIZATION)
+            .is_some_and(|value| value.as_bytes() == format!("Bearer {}", token).as_bytes());
+        if !sent {
+            return false;
+        }
+        let Some(body) = self.replay_body() else {
+            debug!("bearer token rejected, but body not reusable");
+            return false;
+        };
+
+        let fut = async move { bearer.refresh(&token).await };
+        self.refresh = Some((SyncWrapper::new(Box::pin(fut)), body));
+        true
+    }
+
+    /// Attach the attempt count to `err` if a retry policy is in use.
+    fn with_attempts(&self, err: Error) -> Error {
+        if self.client.retry.max_retries() > 0 {
+            err.with_attempts(self.retries + 1)
+        } else {
+            err
+        }
+    }
+
+    /// Take a proxy that failed to connect out of rotation, and switch to another
+    /// proxy from the pool in case the request is retried.
+    fn proxy_failed(mut self: Pin<&mut Self>) {
+        let Some(index) = self.proxy else {
+            return;
+        };
+
+        let client = Arc::clone(&self.client);
+        let Some(pool) = client.proxy_pool.as_ref() else {
+            return;
+        };
+        pool.report_failure(index);
+
+        let Some(uri) = try_uri(&self.url) else {
+            return;
+        };
+
+        if let Some((index, network_scheme)) = client.pick_proxy(&uri, &NetworkScheme::Default) {
+            // drop credentials meant for the previous proxy, which change with
+            // each request when they come from its credentials callback
+            let previous = self.network_scheme.proxy_scheme();
+            let stale = previous.and_then(|proxy| proxy.maybe_http_auth()).cloned();
+            let looked_up = previous.and_then(ProxyScheme::maybe_credentials).is_some();
+            if looked_up
+                || (stale.is_some() && self.headers.get(PROXY_AUTHORIZATION) == stale.as_ref())
+            {
+                self.headers.remove(PROXY_AUTHORIZATION);
+            }
+
+            client.proxy_auth(&uri, &network_scheme, &mut self.headers);
+            self.network_scheme = network_scheme;
+            self.proxy = Some(index);
+        }
+    }
+
+    /// Drive the cookie store operation the request waits on, if any.
+    ///
+    /// Returns the response whose cookies were stored, once they are.
+    #[cfg(feature = "cookies")]
+    fn poll_cookies(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<(http::Response<ResponseBody>, Option<Bytes>)>, Error>> {
+        match self.cookies.take() {
+            None => Poll::Ready(Ok(None)),
+            Some(CookieStep::Store(mut fut, res, preview)) => {
+                match fut.get_mut().as_mut().poll(cx) {
+                    Poll::Ready(()) => Poll::Ready(Ok(Some((res, preview)))),
+                    Poll::Pending => {
+                        self.cookies = Some(CookieStep::Store(fut, res, preview));
+                        Poll::Pending
+                    }
+                }
+            }
+            Some(CookieStep::Load(mut fut, body)) => match fut.get_mut().as_mut().poll(cx) {
+                Poll::Ready(cookies) => {
+                    for cookie in cookies.into_iter().flatten() {
+                        self.headers.append(crate::header::COOKIE, cookie);
+                    }
+                    if self.as_mut().resend(body) {
+                        Poll::Ready(Ok(None))
+                    } else {
+                        Poll::Ready(Err(error::request("failed to build request for redirect")))
+                    }
+                }
+                Poll::Pending => {
+                    self.cookies = Some(CookieStep::Load(fut, body));
+                    Poll::Pending
+                }
+            },
+        }
+    }
+
+    fn replay_body(&self) -> Option<Body> {
+        match self.body {
+            Some(Some(ref body)) => body.try_clone(),
+            Some(None) => None,
+            None => Some(Body::empty()),
+        }
+    }
+
+    /// Whether the response headers of the current attempt took longer than the
+    /// first byte timeout, which starts when the attempt is first polled.
+    fn first_byte_elapsed(&mut self, cx: &mut Context<'_>) -> bool {
+        let Some(timeout) = self.client.first_byte_timeout else {
+            return false;
+        };
+        let timer = &self.client.timer;
+        self.first_byte
+            .get_or_insert_with(|| timer.sleep(timeout))
+            .as_mut()
+            .poll(cx)
+            .is_ready()
+    }
+
+    /// Sign `headers` again for the current url and time, if the request is
+    /// signed with AWS SigV4 and still carries its signature.
+    fn resign(&self, headers: &mut HeaderMap) -> Result<(), Error> {
+        let Some(signer) = RequestConfig::<RequestAwsSigner>::get(&self.extensions) else {
+            return Ok(());
+        };
+        // the redirect policy removed it, e.g. on a redirect to another host
+        if !headers.contains_key(AUTHORIZATION) {
+            return Ok(());
+        }
+
+        let payload = match self.body {
+            Some(Some(ref body)) => body.as_bytes(),
+            Some(None) => None,
+            None => Some(&[][..]),
+        };
+        signer.sign(&self.method, &self.url, headers, payload, SystemTime::now())
+    }
+
+    fn resend(mut self: Pin<&mut Self>, body: Body) -> bool {
         let uri = match try_uri(&self.url) {
             Some(uri) => uri,
             None => {
@@ -1946,16 +4287,23 @@ impl PendingRequest {
             }
         };
 
+        let mut headers = self.headers.clone();
+        if let Err(err) = self.resign(&mut headers) {
+            debug!("failed to sign request again: {}", err);
+            return false;
+        }
+        self.client.close_unpooled(&uri, &mut headers);
+
         *self.as_mut().in_flight().get_mut() = {
             let res = InnerRequest::builder()
                 .uri(uri)
                 .method(self.method.clone())
-                .headers(self.headers.clone())
+                .headers(headers)
                 .headers_order(self.headers_order.as_deref())
                 .version(self.version)
                 .extensions(self.extensions.clone())
                 .network_scheme(self.network_scheme.clone())
-                .body(body);
+                .body(with_upload_progress(body, &self.extensions));
 
             if let Ok(req) = res {
                 self.client.hyper.request(req)
@@ -1964,11 +4312,43 @@ impl PendingRequest {
                 return false;
             }
         };
+        self.span.send(&self.url);
+        if let Some(ref mut metrics) = self.metrics {
+            metrics.resend();
+        }
+        if let Some(ref mut har) = self.har {
+            har.send();
+        }
 
         true
     }
 }
 
+fn bearer_header(token: &str) -> Result<HeaderValue, Error> {
+    let mut header = HeaderValue::try_from(format!("Bearer {}", token)).map_err(error::builder)?;
+    header.set_sensitive(true);
+    Ok(header)
+}
+
+/// Copy the request extensions to the response, keeping the response's own
+/// where both have a value of the same type.
+fn with_request_extensions<B>(
+    mut res: http::Response<B>,
+    extensions: &Extensions,
+) -> http::Response<B> {
+    let mut merged = extensions.clone();
+    merged.extend(std::mem::take(res.extensions_mut()));
+    *res.extensions_mut() = merged;
+    res
+}
+
+fn with_upload_progress(body: Body, extensions: &Extensions) -> Body {
+    match RequestConfig::<UploadProgress>::get(extensions) {
+        Some(progress) => body.with_progress(progress.clone()),
+        None => body,
+    }
+}
+
 impl Pending {
     pub(super) fn new_err(err: Error) -> Pending {
         Pending {
@@ -1976,6 +4356,15 @@ impl Pending {
         }
     }
 
+    fn new_layered<F>(fut: F) -> Pending
+    where
+        F: Future<Output = Result<Response, Error>> + Send + 'static,
+    {
+        Pending {
+            inner: PendingInner::Layered(SyncWrapper::new(Box::pin(fut))),
+        }
+    }
+
     fn inner(self: Pin<&mut Self>) -> Pin<&mut PendingInner> {
         self.project().inner
     }
@@ -1985,9 +4374,12 @@ impl Future for Pending {
     type Output = Result<Response, Error>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // the connections and timeouts of the request need a Tokio driver
+        let _enter = crate::rt::enter();
         let inner = self.inner();
         match inner.get_mut() {
             PendingInner::Request(req) => Pin::new(req).poll(cx),
+            PendingInner::Layered(fut) => fut.get_mut().as_mut().poll(cx),
             PendingInner::Error(err) => Poll::Ready(Err(err
                 .take()
                 .unwrap_or_else(|| error::request("Pending error polled more than once")))),
@@ -1999,11 +4391,35 @@ impl Future for PendingRequest {
     type Output = Result<Response, Error>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let span = self.span.clone();
+        let res = span.in_scope(|| self.as_mut().poll_response(cx));
+        if res.is_ready() {
+            if let Some(metrics) = self.metrics.take() {
+                metrics.failed(&self.url);
+            }
+        }
+        res
+    }
+}
+
+impl PendingRequest {
+    fn poll_response(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Response, Error>> {
         if let Some(delay) = self.as_mut().total_timeout().as_mut().as_pin_mut() {
             if let Poll::Ready(()) = delay.poll(cx) {
-                return Poll::Ready(Err(
-                    error::request(error::TimedOut).with_url(self.url.clone())
-                ));
+                let phase = if self.throttle.is_some() || self.queued.is_some() {
+                    TimeoutPhase::Queue
+                } else if self.retry_delay.is_some() {
+                    TimeoutPhase::Backoff
+                } else {
+                    TimeoutPhase::Request
+                };
+                let err = error::request(error::TimedOut)
+                    .with_url(self.url.clone())
+                    .with_timeout_phase(phase);
+                return Poll::Ready(Err(self.with_attempts(err)));
             }
         }
 
@@ -2015,35 +4431,258 @@ impl Future for PendingRequest {
             }
         }
 
+        if let Err(err) = ready!(self.poll_throttle(cx)) {
+            return Poll::Ready(Err(err));
+        }
+
         loop {
-            let res = {
-                let r = self.as_mut().in_flight().get_mut();
-                match Pin::new(r).poll(cx) {
+            // send the request again with the refreshed bearer token
+            if let Some((mut refresh, body)) = self.refresh.take() {
+                let token = match refresh.get_mut().as_mut().poll(cx) {
+                    Poll::Ready(token) => token,
+                    Poll::Pending => {
+                        self.refresh = Some((refresh, body));
+                        return Poll::Pending;
+                    }
+                };
+                let header = match bearer_header(&token) {
+                    Ok(header) => header,
+                    Err(err) => return Poll::Ready(Err(err.with_url(self.url.clone()))),
+                };
+                self.headers.insert(AUTHORIZATION, header);
+                *RequestConfig::<RequestBearerToken>::get_mut(&mut self.extensions) = None;
+                if !self.as_mut().resend(body) {
+                    let err = error::request("failed to rebuild request for bearer token");
+                    return Poll::Ready(Err(err.with_url(self.url.clone())));
+                }
+            }
+
+            // read the `401` response to its end, so the connection the handshake
+            // runs on goes back to the pool for the next request
+            #[cfg(feature = "ntlm")]
+            if let Some((mut unauthorized, body)) = self.handshake.take() {
+                use http_body::Body as _;
+
+                loop {
+                    match Pin::new(&mut unauthorized).poll_frame(cx) {
+                        Poll::Ready(Some(Ok(_))) => {}
+                        Poll::Ready(Some(Err(_)) | None) => break,
+                        Poll::Pending => {
+                            self.handshake = Some((unauthorized, body));
+                            return Poll::Pending;
+                        }
+                    }
+                }
+
+                if !self.as_mut().resend(body) {
+                    let err = error::request("failed to rebuild request for NTLM handshake");
+                    return Poll::Ready(Err(err.with_url(self.url.clone())));
+                }
+            }
+
+            #[cfg(feature = "cookies")]
+            let stored = match ready!(self.as_mut().poll_cookies(cx)) {
+                Ok(stored) => stored,
+                Err(err) => return Poll::Ready(Err(err)),
+            };
+            #[cfg(not(feature = "cookies"))]
+            let stored = None;
+            #[cfg(feature = "cookies")]
+            let fresh = stored.is_none();
+
+            let (res, preview) = match (stored, self.preview.take()) {
+                (Some(stored), _) => stored,
+                (None, Some(mut preview)) => match preview.poll_fill(cx) {
+                    Poll::Ready(Ok(())) => {
+                        let (res, prefix) = preview.finish();
+                        (res, Some(prefix))
+                    }
                     Poll::Ready(Err(e)) => {
-                        if self.as_mut().retry_error(&e) {
+                        return Poll::Ready(Err(error::body(e).with_url(self.url.clone())));
+                    }
+                    Poll::Pending => {
+                        self.preview = Some(preview);
+                        return Poll::Pending;
+                    }
+                },
+                (None, None) => {
+                    if let Some(delay) = self.retry_delay.as_mut() {
+                        ready!(delay.as_mut().poll(cx));
+                        self.retry_delay = None;
+                        self.throttle_retry();
+                    }
+
+                    if self.retrying {
+                        if let Err(err) = ready!(self.poll_throttle(cx)) {
+                            return Poll::Ready(Err(err));
+                        }
+                        self.retrying = false;
+
+                        let body = self.replay_body().unwrap_or_else(Body::empty);
+                        if !self.as_mut().resend(body) {
+                            let err = error::request("failed to rebuild request for retry");
+                            return Poll::Ready(Err(self.with_attempts(err)));
+                        }
+                    }
+
+                    let r = self.as_mut().in_flight().get_mut();
+                    let res = match Pin::new(r).poll(cx) {
+                        Poll::Ready(Err(e)) => {
+                            self.first_byte = None;
+                            if e.is_connect() {
+                                self.as_mut().proxy_failed();
+                            }
+                            if self.as_mut().retry_error(&e) {
+                                continue;
+                            }
+                            if self.client.retry.retries_error(&e)
+                                && self.as_mut().retry_backoff(None)
+                            {
+                                continue;
+                            }
+                            let err = error::request(e).with_url(self.url.clone());
+                            return Poll::Ready(Err(self.with_attempts(err)));
+                        }
+                        Poll::Ready(Ok(res)) => {
+                            self.first_byte = None;
+                            self.span.first_byte(&res);
+                            let this = &mut *self;
+                            if let Some(ref mut har) = this.har {
+                                har.response(
+                                    &this.method,
+                                    &this.url,
+                                    &this.headers,
+                                    this.body.as_ref(),
+                                    &res,
+                                );
+                            }
+                            if let Some(ref limiter) = self.client.rate_limiter {
+                                limiter.feedback(&self.url, res.status(), res.headers());
+                            }
+                            if let (Some(index), Some(pool)) =
+                                (self.proxy, self.client.proxy_pool.as_ref())
+                            {
+                                pool.report_success(index);
+                            }
+                            res.map(super::body::boxed)
+                        }
+                        Poll::Pending => {
+                            if self.first_byte_elapsed(cx) {
+                                let err =
+                                    error::request(error::TimedOut).with_url(self.url.clone());
+                                return Poll::Ready(Err(self.with_attempts(err)));
+                            }
+                            return Poll::Pending;
+                        }
+                    };
+
+                    if self.client.retry.retries_status(&self.method, res.status())
+                        && self.as_mut().retry_backoff(Some(res.headers()))
+                    {
+                        continue;
+                    }
+
+                    if self.as_mut().retry_after(res.status(), res.headers()) {
+                        continue;
+                    }
+
+                    if res.status() == StatusCode::UNAUTHORIZED
+                        && (self.as_mut().retry_digest_auth(res.headers())
+                            || self.as_mut().retry_bearer_auth())
+                    {
+                        continue;
+                    }
+
+                    if res.status() == StatusCode::PROXY_AUTHENTICATION_REQUIRED
+                        && self.as_mut().retry_proxy_digest_auth(res.headers())
+                    {
+                        continue;
+                    }
+
+                    #[cfg(feature = "ntlm")]
+                    if res.status() == StatusCode::UNAUTHORIZED {
+                        if let Some(body) = self.as_mut().retry_ntlm_auth(res.headers()) {
+                            self.handshake = Some((res.into_body(), body));
                             continue;
                         }
-                        return Poll::Ready(Err(error::request(e).with_url(self.url.clone())));
                     }
-                    Poll::Ready(Ok(res)) => res.map(super::body::boxed),
-                    Poll::Pending => return Poll::Pending,
+
+                    // buffer a prefix of the redirect body if the policy asks for it
+                    let policy = self.redirect.as_ref().unwrap_or(&self.client.redirect);
+                    let limit = policy.body_preview_limit();
+                    let follow_refresh = policy.follows_refresh();
+                    if limit > 0
+                        && res.status().is_redirection()
+                        && res.headers().contains_key(LOCATION)
+                    {
+                        self.preview = Some(RedirectPreview::new(res, limit));
+                        continue;
+                    }
+
+                    // decode and buffer the start of html pages to look for a meta refresh
+                    if follow_refresh
+                        && res.status() == StatusCode::OK
+                        && !res.headers().contains_key(REFRESH)
+                        && is_html(res.headers())
+                    {
+                        let (mut parts, body) = res.into_parts();
+                        let body = Decoder::detect(&mut parts.headers, body, self.client.accepts);
+                        let res = http::Response::from_parts(parts, super::body::boxed(body));
+                        self.preview = Some(RedirectPreview::new(
+                            res,
+                            limit.max(redirect::REFRESH_SCAN_LIMIT),
+                        ));
+                        continue;
+                    }
+
+                    (res, None)
                 }
             };
 
+            // store the cookies before going on, so they are sent along with the redirect
             #[cfg(feature = "cookies")]
-            {
-                if let Some(cookie_store) = self.client.cookie_store.as_ref() {
-                    let mut cookies =
-                        cookie::extract_response_cookie_headers(res.headers()).peekable();
-                    if cookies.peek().is_some() {
-                        cookie_store.set_cookies(&mut cookies, &self.url);
+            if fresh {
+                if let Some(cookie_store) = self.client.cookie_store(&self.extensions) {
+                    let cookies = cookie::extract_response_cookie_headers(res.headers())
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    if !cookies.is_empty() {
+                        let url = self.url.clone();
+                        let fut = async move { cookie_store.store_cookies(cookies, &url).await };
+                        let fut: CookieFuture<()> = SyncWrapper::new(Box::pin(fut));
+                        self.cookies = Some(CookieStep::Store(fut, res, preview));
+                        continue;
                     }
                 }
             }
 
             let previous_method = self.method.clone();
 
-            let should_redirect = match res.status() {
+            let refresh = if res.status() == StatusCode::OK
+                && self
+                    .redirect
+                    .as_ref()
+                    .unwrap_or(&self.client.redirect)
+                    .follows_refresh()
+            {
+                res.headers()
+                    .get(REFRESH)
+                    .and_then(|val| val.to_str().ok())
+                    .and_then(redirect::refresh_target)
+                    .map(ToOwned::to_owned)
+                    .or_else(|| preview.as_deref().and_then(redirect::meta_refresh))
+            } else {
+                None
+            };
+
+            // a refresh navigates like a `303 See Other`
+            let status = if refresh.is_some() {
+                StatusCode::SEE_OTHER
+            } else {
+                res.status()
+            };
+
+            let should_redirect = match status {
                 StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND | StatusCode::SEE_OTHER => {
                     self.body = None;
                     for header in &[
@@ -2073,14 +4712,16 @@ impl Future for PendingRequest {
             };
 
             if should_redirect {
-                let loc = res.headers().get(LOCATION).and_then(|val| {
+                let target = match refresh {
+                    Some(ref target) => Some(target.as_bytes()),
+                    None => res.headers().get(LOCATION).map(HeaderValue::as_bytes),
+                };
+                let loc = target.and_then(|val| {
                     let loc = (|| -> Option<Url> {
                         // Some sites may send a utf-8 Location header,
                         // even though we're supposed to treat those bytes
                         // as opaque, we'll check specifically for utf8.
-                        self.url
-                            .join(std::str::from_utf8(val.as_bytes()).ok()?)
-                            .ok()
+                        self.url.join(std::str::from_utf8(val).ok()?).ok()
                     })();
 
                     // Check that the `url` is also a valid `http::Uri`.
@@ -2095,10 +4736,15 @@ impl Future for PendingRequest {
                     });
 
                     if loc.is_none() {
-                        debug!("Location header had invalid URI: {:?}", val);
+                        debug!(
+                            "Location header had invalid URI: {:?}",
+                            String::from_utf8_lossy(val)
+                        );
                     }
                     loc
                 });
+                // a refresh to the same page is a reload, not a redirect
+                let loc = loc.filter(|loc| refresh.is_none() || *loc != self.url);
                 if let Some(loc) = loc {
                     if self.client.referer {
                         if let Some(referer) = make_referer(&loc, &self.url) {
@@ -2114,10 +4760,13 @@ impl Future for PendingRequest {
                         .unwrap_or(&self.client.redirect)
                         .check(
                             res.status(),
+                            res.headers(),
+                            preview.as_deref(),
                             &self.method,
                             &loc,
                             &previous_method,
                             &self.urls,
+                            &self.extensions,
                         );
 
                     match action {
@@ -2135,15 +4784,27 @@ impl Future for PendingRequest {
                                 )));
                             }
 
+                            let record = redirect::RedirectRecord::new(
+                                res.status(),
+                                self.url.clone(),
+                                loc.clone(),
+                                res.headers().clone(),
+                            );
+                            self.history.push(record);
+
                             self.url = loc;
                             let mut headers =
                                 std::mem::replace(self.as_mut().headers(), HeaderMap::new());
 
-                            redirect::Policy::remove_sensitive_headers(
-                                &mut headers,
-                                &self.url,
-                                &self.urls,
-                            );
+                            self.redirect
+                                .as_ref()
+                                .unwrap_or(&self.client.redirect)
+                                .remove_sensitive_headers(&mut headers, &self.url, &self.urls);
+
+                            // the stale response being revalidated was for the previous url
+                            if let Some(entry) = self.stale.take() {
+                                entry.clear_conditions(&mut headers);
+                            }
 
                             let uri = match try_uri(&self.url) {
                                 Some(uri) => uri,
@@ -2152,29 +4813,43 @@ impl Future for PendingRequest {
                                 }
                             };
 
-                            let body = match self.body {
-                                Some(Some(ref body)) => Body::reusable(body.clone()),
-                                _ => Body::empty(),
-                            };
+                            let body = self.replay_body().unwrap_or_else(Body::empty);
 
-                            // Add cookies from the cookie store.
+                            // Add cookies from the cookie store, once they are loaded.
                             #[cfg(feature = "cookies")]
-                            if let Some(cookie_store) = self.client.cookie_store.as_ref() {
-                                add_cookie_header(cookie_store, &mut headers, &self.url);
+                            if let Some(cookie_store) = self.client.cookie_store(&self.extensions) {
+                                *self.as_mut().headers() = headers;
+                                let url = self.url.clone();
+                                let fut = async move { cookie_store.load_cookies(&url).await };
+                                let fut: CookieFuture<Option<Vec<HeaderValue>>> =
+                                    SyncWrapper::new(Box::pin(fut));
+                                self.cookies = Some(CookieStep::Load(fut, body));
+                                continue;
                             }
 
+                            self.resign(&mut headers)?;
+                            let mut sent_headers = headers.clone();
+                            self.client.close_unpooled(&uri, &mut sent_headers);
+
                             *self.as_mut().in_flight().get_mut() = {
                                 let req = InnerRequest::builder()
                                     .uri(uri)
                                     .method(self.method.clone())
-                                    .headers(headers.clone())
+                                    .headers(sent_headers)
                                     .headers_order(self.headers_order.as_deref())
                                     .version(self.version)
                                     .extensions(self.extensions.clone())
                                     .network_scheme(self.network_scheme.clone())
-                                    .body(body)?;
+                                    .body(with_upload_progress(body, &self.extensions))?;
 
                                 std::mem::swap(self.as_mut().headers(), &mut headers);
+                                self.span.send(&self.url);
+                                if let Some(ref mut metrics) = self.metrics {
+                                    metrics.resend();
+                                }
+                                if let Some(ref mut har) = self.har {
+                                    har.send();
+                                }
                                 self.client.hyper.request(req)
                             };
 
@@ -2190,18 +4865,127 @@ impl Future for PendingRequest {
                 }
             }
 
+            let stale = self.stale.take();
+            let res = match self.client.cache {
+                Some(ref cache) => cache.on_response(
+                    &self.method,
+                    &self.url,
+                    &self.headers,
+                    stale,
+                    self.sent_at,
+                    res,
+                ),
+                None => res,
+            };
+
+            let res = match RequestConfig::<DownloadProgress>::get(&self.extensions) {
+                Some(progress) => {
+                    let total = res
+                        .headers()
+                        .get(CONTENT_LENGTH)
+                        .and_then(|len| len.to_str().ok())
+                        .and_then(|len| len.parse().ok());
+                    res.map(|body| {
+                        super::body::boxed(super::body::progress_body(
+                            body,
+                            progress.clone(),
+                            total,
+                        ))
+                    })
+                }
+                None => res,
+            };
+
+            let res = self.span.body(res);
+            let res = match self.metrics.take() {
+                Some(metrics) => metrics.response(&self.url, res),
+                None => res,
+            };
+            let res = match self.har.take() {
+                Some(har) => har.finish(res),
+                None => res,
+            };
+            let res = match self.permits.take() {
+                Some(permits) => permits.response(res),
+                None => res,
+            };
+
+            let res = with_request_extensions(res, &self.extensions);
+            let history = std::mem::take(&mut self.history);
+            let max_size = self
+                .client
+                .max_response_size
+                .fetch(&self.extensions)
+                .copied();
             let res = Response::new(
                 res,
                 self.url.clone(),
+                history,
                 self.client.accepts,
                 self.total_timeout.take(),
-                self.read_timeout,
+                self.read_timeout
+                    .map(|timeout| ReadTimeout::new(timeout, self.client.timer.clone())),
+                max_size,
             );
             return Poll::Ready(Ok(res));
         }
     }
 }
 
+/// A redirect response whose body prefix is being buffered for [`redirect::Attempt::body`].
+struct RedirectPreview {
+    res: http::Response<ResponseBody>,
+    buf: BytesMut,
+    limit: usize,
+}
+
+impl RedirectPreview {
+    fn new(res: http::Response<ResponseBody>, limit: usize) -> RedirectPreview {
+        RedirectPreview {
+            res,
+            buf: BytesMut::new(),
+            limit,
+        }
+    }
+
+    /// Read body frames until `limit` bytes are buffered or the body ends.
+    fn poll_fill(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), BoxError>> {
+        use http_body::Body as _;
+
+        while self.buf.len() < self.limit {
+            match ready!(Pin::new(self.res.body_mut()).poll_frame(cx)) {
+                Some(Ok(frame)) => {
+                    if let Ok(data) = frame.into_data() {
+                        self.buf.extend_from_slice(&data);
+                    }
+                }
+                Some(Err(err)) => return Poll::Ready(Err(err)),
+                None => break,
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    /// Restore the buffered bytes in front of the remaining body and return the preview.
+    fn finish(self) -> (http::Response<ResponseBody>, Bytes) {
+        let buf = self.buf.freeze();
+        let preview = buf.slice(..buf.len().min(self.limit));
+        let res = self
+            .res
+            .map(|body| super::body::boxed(super::body::prefixed(buf, body)));
+        (res, preview)
+    }
+}
+
+fn is_html(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|val| val.to_str().ok())
+        .and_then(|val| val.parse::<mime::Mime>().ok())
+        .is_some_and(|mime| mime.essence_str() == "text/html" || mime.subtype() == "xhtml")
+}
+
 fn is_retryable_error(err: &(dyn std::error::Error + 'static)) -> bool {
     // pop the legacy::Error
     let err = if let Some(err) = err.source() {
@@ -2243,16 +5027,27 @@ fn make_referer(next: &Url, previous: &Url) -> Option<HeaderValue> {
     referer.as_str().parse().ok()
 }
 
-#[cfg(feature = "cookies")]
-fn add_cookie_header(
-    cookie_store: &Arc<dyn cookie::CookieStore>,
+#[cfg(any(
+    feature = "gzip",
+    feature = "brotli",
+    feature = "zstd",
+    feature = "deflate"
+))]
+fn compress_body(
+    body: Option<Body>,
+    extensions: &Extensions,
     headers: &mut HeaderMap,
-    url: &Url,
-) {
-    if let Some(cookie_headers) = cookie_store.cookies(url) {
-        for header in cookie_headers {
-            headers.append(http::header::COOKIE, header);
+) -> Option<Body> {
+    match (body, RequestConfig::<RequestCompression>::get(extensions)) {
+        (Some(body), Some(encoding)) => {
+            headers.insert(
+                CONTENT_ENCODING,
+                HeaderValue::from_static(encoding.as_str()),
+            );
+            headers.remove(CONTENT_LENGTH);
+            Some(body.compress(*encoding))
         }
+        (body, _) => body,
     }
 }
 
@@ -2274,3 +5069,46 @@ fn add_accpet_encoding_header(accepts: &Accepts, headers: &mut HeaderMap) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> EmulationProvider {
+        EmulationProvider::builder()
+            .http2_config(
+                Http2Config::builder()
+                    .initial_stream_window_size(6291456)
+                    .header_table_size(65536)
+                    .build(),
+            )
+            .build()
+    }
+
+    #[test]
+    fn http2_config_after_emulation_replaces_profile() {
+        let builder = Client::builder()
+            .emulation(profile())
+            .http2_config(Http2Config::builder().max_frame_size(32768).build());
+
+        let h2 = &builder.config.http2_config.h2_builder;
+        assert_eq!(h2.max_frame_size, Some(32768));
+        assert_eq!(h2.header_table_size, None);
+        assert_eq!(
+            h2.initial_stream_window_size,
+            Http2Config::default().h2_builder.initial_stream_window_size
+        );
+    }
+
+    #[test]
+    fn http2_setters_hold_with_later_emulation() {
+        let builder = Client::builder()
+            .http2_initial_stream_window_size(1048576)
+            .emulation(profile());
+
+        let mut config = builder.config.http2_config;
+        builder.config.http2_options.apply(&mut config);
+        assert_eq!(config.h2_builder.initial_stream_window_size, 1048576);
+        assert_eq!(config.h2_builder.header_table_size, Some(65536));
+    }
+}
@@ -0,0 +1,144 @@
+use std::fmt::Write;
+
+use http::header::{ACCEPT, ACCEPT_ENCODING, PROXY_AUTHORIZATION, USER_AGENT};
+use http::{HeaderMap, HeaderName, HeaderValue, Method, Version};
+use url::Url;
+
+use crate::proxy::ProxyScheme;
+
+/// The parts of a request that make up its curl command.
+pub(super) struct Command<'a> {
+    pub(super) method: &'a Method,
+    pub(super) url: &'a Url,
+    pub(super) headers: &'a HeaderMap,
+    pub(super) body: Option<&'a [u8]>,
+    pub(super) version: Option<Version>,
+    pub(super) proxy: Option<&'a ProxyScheme>,
+    pub(super) insecure: bool,
+}
+
+impl Command<'_> {
+    /// Format the command, with each option on its own line.
+    pub(super) fn format(&self) -> String {
+        let mut cmd = String::from("curl");
+
+        match *self.method {
+            Method::GET => (),
+            Method::HEAD => cmd.push_str(" \\\n  --head"),
+            ref method => arg(&mut cmd, "-X", method.as_str()),
+        }
+
+        match self.version {
+            Some(Version::HTTP_10) => cmd.push_str(" \\\n  --http1.0"),
+            Some(Version::HTTP_11) => cmd.push_str(" \\\n  --http1.1"),
+            Some(Version::HTTP_2) if self.url.scheme() == "https" => cmd.push_str(" \\\n  --http2"),
+            Some(Version::HTTP_2) => cmd.push_str(" \\\n  --http2-prior-knowledge"),
+            _ => (),
+        }
+
+        if self.insecure {
+            cmd.push_str(" \\\n  --insecure");
+        }
+
+        for (name, value) in self.headers {
+            header(&mut cmd, "-H", name, value);
+        }
+
+        // curl adds these unless they are removed
+        for name in [ACCEPT, USER_AGENT] {
+            if !self.headers.contains_key(&name) {
+                arg(&mut cmd, "-H", &format!("{}:", name));
+            }
+        }
+
+        if self.headers.contains_key(ACCEPT_ENCODING) {
+            cmd.push_str(" \\\n  --compressed");
+        }
+
+        if let Some(proxy) = self.proxy {
+            arg(&mut cmd, "-x", &format!("{:?}", proxy));
+            if let Some(auth) = proxy.http_auth() {
+                header(&mut cmd, "--proxy-header", &PROXY_AUTHORIZATION, &auth);
+            }
+            for (name, value) in proxy
+                .maybe_http_headers()
+                .into_iter()
+                .flat_map(|h| h.iter())
+            {
+                header(&mut cmd, "--proxy-header", name, value);
+            }
+            #[cfg(feature = "socks")]
+            if let ProxyScheme::Socks5 {
+                auth: Some((user, pass)),
+                ..
+            } = proxy
+            {
+                arg(&mut cmd, "--proxy-user", &format!("{}:{}", user, pass));
+            }
+        }
+
+        if let Some(body) = self.body.filter(|body| !body.is_empty()) {
+            cmd.push_str(" \\\n  --data-binary ");
+            match std::str::from_utf8(body) {
+                Ok(text) => quote(&mut cmd, text),
+                Err(_) => ansi_c_quote(&mut cmd, body),
+            }
+        }
+
+        cmd.push_str(" \\\n  ");
+        quote(&mut cmd, self.url.as_str());
+        cmd
+    }
+}
+
+fn header(cmd: &mut String, flag: &str, name: &HeaderName, value: &HeaderValue) {
+    let line = format!("{}: {}", name, String::from_utf8_lossy(value.as_bytes()));
+    arg(cmd, flag, &line);
+}
+
+fn arg(cmd: &mut String, flag: &str, value: &str) {
+    let _ = write!(cmd, " \\\n  {} ", flag);
+    quote(cmd, value);
+}
+
+/// Quote `value` for a POSIX shell.
+fn quote(cmd: &mut String, value: &str) {
+    cmd.push('\'');
+    cmd.push_str(&value.replace('\'', r"'\''"));
+    cmd.push('\'');
+}
+
+/// Quote bytes that aren't UTF-8 as a `$'...'` string, as bash and zsh
+/// understand it.
+fn ansi_c_quote(cmd: &mut String, bytes: &[u8]) {
+    cmd.push_str("$'");
+    for &byte in bytes {
+        match byte {
+            b'\'' | b'\\' => {
+                cmd.push('\\');
+                cmd.push(byte as char);
+            }
+            0x20..=0x7e => cmd.push(byte as char),
+            _ => {
+                let _ = write!(cmd, "\\x{:02x}", byte);
+            }
+        }
+    }
+    cmd.push('\'');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_arguments() {
+        let mut cmd = String::new();
+        quote(&mut cmd, "it's");
+        assert_eq!(cmd, r"'it'\''s'");
+
+        let mut cmd = String::new();
+        ansi_c_quote(&mut cmd, b"a'\xff");
+        assert_eq!(cmd, r"$'a\'\xff'");
+    }
+}
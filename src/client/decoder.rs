@@ -27,7 +27,7 @@ use async_compression::tokio::bufread::BrotliDecoder;
 use async_compression::tokio::bufread::ZstdDecoder;
 
 #[cfg(feature = "deflate")]
-use async_compression::tokio::bufread::ZlibDecoder;
+use async_compression::tokio::bufread::{DeflateDecoder, ZlibDecoder};
 
 #[cfg(any(
     feature = "gzip",
@@ -69,13 +69,56 @@ pub(super) struct Accepts {
     pub(super) zstd: bool,
     #[cfg(feature = "deflate")]
     pub(super) deflate: bool,
+    #[cfg(any(
+        feature = "gzip",
+        feature = "brotli",
+        feature = "zstd",
+        feature = "deflate"
+    ))]
+    pub(super) limits: DecompressionLimits,
+}
+
+/// Limits on the decompressed bodies of responses, against decompression
+/// bombs.
+#[cfg(any(
+    feature = "gzip",
+    feature = "brotli",
+    feature = "zstd",
+    feature = "deflate"
+))]
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct DecompressionLimits {
+    pub(super) max_size: Option<u64>,
+    pub(super) max_ratio: Option<u32>,
 }
 
+/// The size a body must be decompressed to before its ratio is checked, as
+/// small bodies of repeated bytes can have a large one.
+#[cfg(any(
+    feature = "gzip",
+    feature = "brotli",
+    feature = "zstd",
+    feature = "deflate"
+))]
+const RATIO_MIN_SIZE: u64 = 64 * 1024;
+
 /// A response decompressor over a non-blocking stream of chunks.
 ///
 /// The inner decoder may be constructed asynchronously.
 pub(crate) struct Decoder {
     inner: Inner,
+    // the rest of a chunk that didn't fit the buffer of `Response::read_into`
+    unread: Bytes,
+    max_size: Option<u64>,
+    // the bytes of the body decoded so far, counted against the limits
+    decoded: u64,
+    #[cfg(any(
+        feature = "gzip",
+        feature = "brotli",
+        feature = "zstd",
+        feature = "deflate"
+    ))]
+    limits: DecompressionLimits,
 }
 
 #[cfg(any(
@@ -115,6 +158,11 @@ enum Inner {
     #[cfg(feature = "deflate")]
     Deflate(Pin<Box<Fuse<FramedRead<ZlibDecoder<PeekableIoStreamReader>, BytesCodec>>>>),
 
+    /// A `DeflateRaw` decoder will uncompress deflated response content sent without a
+    /// zlib header before returning it.
+    #[cfg(feature = "deflate")]
+    DeflateRaw(Pin<Box<Fuse<FramedRead<DeflateDecoder<PeekableIoStreamReader>, BytesCodec>>>>),
+
     /// A decoder that doesn't have a value yet.
     #[cfg(any(
         feature = "brotli",
@@ -140,7 +188,14 @@ struct Pending(PeekableIoStream, DecoderType);
     feature = "brotli",
     feature = "deflate",
 ))]
-pub(crate) struct IoStream<B = ResponseBody>(B);
+/// Converts a body into a `Stream` of its DATA frames, keeping its trailers,
+/// if any, to be yielded after the decompressed data.
+pub(crate) struct IoStream<B = ResponseBody> {
+    body: B,
+    trailers: Option<HeaderMap>,
+    // the bytes of DATA frames read, to check the ratio of a decompression
+    read: u64,
+}
 
 #[cfg(any(
     feature = "gzip",
@@ -166,13 +221,52 @@ impl fmt::Debug for Decoder {
 }
 
 impl Decoder {
+    fn new(inner: Inner) -> Decoder {
+        Decoder {
+            inner,
+            unread: Bytes::new(),
+            max_size: None,
+            decoded: 0,
+            #[cfg(any(
+                feature = "gzip",
+                feature = "brotli",
+                feature = "zstd",
+                feature = "deflate"
+            ))]
+            limits: DecompressionLimits::default(),
+        }
+    }
+
+    /// Fail a compressed body once it exceeds `limits`.
+    #[cfg(any(
+        feature = "gzip",
+        feature = "brotli",
+        feature = "zstd",
+        feature = "deflate"
+    ))]
+    fn decompression_limits(mut self, limits: DecompressionLimits) -> Decoder {
+        self.limits = limits;
+        self
+    }
+
+    /// Fail the body once more than `max_size` bytes of it were decoded.
+    pub(super) fn max_size(mut self, max_size: Option<u64>) -> Decoder {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Put back the rest of a chunk, to be returned before the rest of the
+    /// body.
+    pub(super) fn unread(&mut self, bytes: Bytes) {
+        debug_assert!(self.unread.is_empty());
+        self.unread = bytes;
+    }
+
     /// A plain text decoder.
     ///
     /// This decoder will emit the underlying chunks as-is.
     fn plain_text(body: ResponseBody) -> Decoder {
-        Decoder {
-            inner: Inner::PlainText(body),
-        }
+        Decoder::new(Inner::PlainText(body))
     }
 
     /// A gzip decoder.
@@ -182,12 +276,10 @@ impl Decoder {
     fn gzip(body: ResponseBody) -> Decoder {
         use futures_util::StreamExt;
 
-        Decoder {
-            inner: Inner::Pending(Box::pin(Pending(
-                IoStream(body).peekable(),
-                DecoderType::Gzip,
-            ))),
-        }
+        Decoder::new(Inner::Pending(Box::pin(Pending(
+            IoStream::new(body).peekable(),
+            DecoderType::Gzip,
+        ))))
     }
 
     /// A brotli decoder.
@@ -197,12 +289,10 @@ impl Decoder {
     fn brotli(body: ResponseBody) -> Decoder {
         use futures_util::StreamExt;
 
-        Decoder {
-            inner: Inner::Pending(Box::pin(Pending(
-                IoStream(body).peekable(),
-                DecoderType::Brotli,
-            ))),
-        }
+        Decoder::new(Inner::Pending(Box::pin(Pending(
+            IoStream::new(body).peekable(),
+            DecoderType::Brotli,
+        ))))
     }
 
     /// A zstd decoder.
@@ -212,12 +302,10 @@ impl Decoder {
     fn zstd(body: ResponseBody) -> Decoder {
         use futures_util::StreamExt;
 
-        Decoder {
-            inner: Inner::Pending(Box::pin(Pending(
-                IoStream(body).peekable(),
-                DecoderType::Zstd,
-            ))),
-        }
+        Decoder::new(Inner::Pending(Box::pin(Pending(
+            IoStream::new(body).peekable(),
+            DecoderType::Zstd,
+        ))))
     }
 
     /// A deflate decoder.
@@ -227,12 +315,10 @@ impl Decoder {
     fn deflate(body: ResponseBody) -> Decoder {
         use futures_util::StreamExt;
 
-        Decoder {
-            inner: Inner::Pending(Box::pin(Pending(
-                IoStream(body).peekable(),
-                DecoderType::Deflate,
-            ))),
-        }
+        Decoder::new(Inner::Pending(Box::pin(Pending(
+            IoStream::new(body).peekable(),
+            DecoderType::Deflate,
+        ))))
     }
 
     #[cfg(any(
@@ -283,28 +369,28 @@ impl Decoder {
         #[cfg(feature = "gzip")]
         {
             if _accepts.gzip && Decoder::detect_encoding(_headers, "gzip") {
-                return Decoder::gzip(body);
+                return Decoder::gzip(body).decompression_limits(_accepts.limits);
             }
         }
 
         #[cfg(feature = "brotli")]
         {
             if _accepts.brotli && Decoder::detect_encoding(_headers, "br") {
-                return Decoder::brotli(body);
+                return Decoder::brotli(body).decompression_limits(_accepts.limits);
             }
         }
 
         #[cfg(feature = "zstd")]
         {
             if _accepts.zstd && Decoder::detect_encoding(_headers, "zstd") {
-                return Decoder::zstd(body);
+                return Decoder::zstd(body).decompression_limits(_accepts.limits);
             }
         }
 
         #[cfg(feature = "deflate")]
         {
             if _accepts.deflate && Decoder::detect_encoding(_headers, "deflate") {
-                return Decoder::deflate(body);
+                return Decoder::deflate(body).decompression_limits(_accepts.limits);
             }
         }
 
@@ -312,14 +398,69 @@ impl Decoder {
     }
 }
 
-impl HttpBody for Decoder {
-    type Data = Bytes;
-    type Error = crate::Error;
+impl Decoder {
+    /// Drop the rest of the body, which resets its stream, or closes its
+    /// connection, and fail with `err`.
+    fn abort(&mut self, err: crate::Error) -> Poll<Option<Result<Frame<Bytes>, crate::Error>>> {
+        self.inner = Inner::PlainText(ResponseBody::default());
+        self.max_size = None;
+        Poll::Ready(Some(Err(err)))
+    }
 
-    fn poll_frame(
+    /// Returns `true` if a compressed body decompressed to more than its
+    /// limits allow.
+    #[cfg(any(
+        feature = "gzip",
+        feature = "brotli",
+        feature = "zstd",
+        feature = "deflate"
+    ))]
+    fn is_bomb(&self) -> bool {
+        let Some(read) = self.compressed_read() else {
+            return false;
+        };
+        if self.limits.max_size.is_some_and(|max| self.decoded > max) {
+            return true;
+        }
+        self.limits.max_ratio.is_some_and(|ratio| {
+            self.decoded > RATIO_MIN_SIZE && self.decoded > read.saturating_mul(u64::from(ratio))
+        })
+    }
+
+    /// The bytes of a compressed body read so far, or `None` if the body
+    /// isn't compressed.
+    #[cfg(any(
+        feature = "gzip",
+        feature = "brotli",
+        feature = "zstd",
+        feature = "deflate"
+    ))]
+    fn compressed_read(&self) -> Option<u64> {
+        let stream = match self.inner {
+            Inner::PlainText(_) => return None,
+            Inner::Pending(ref pending) => pending.0.get_ref(),
+            #[cfg(feature = "gzip")]
+            Inner::Gzip(ref decoder) => decoder.get_ref().get_ref().get_ref().get_ref().get_ref(),
+            #[cfg(feature = "brotli")]
+            Inner::Brotli(ref decoder) => decoder.get_ref().get_ref().get_ref().get_ref().get_ref(),
+            #[cfg(feature = "zstd")]
+            Inner::Zstd(ref decoder) => decoder.get_ref().get_ref().get_ref().get_ref().get_ref(),
+            #[cfg(feature = "deflate")]
+            Inner::Deflate(ref decoder) => {
+                decoder.get_ref().get_ref().get_ref().get_ref().get_ref()
+            }
+            #[cfg(feature = "deflate")]
+            Inner::DeflateRaw(ref decoder) => {
+                decoder.get_ref().get_ref().get_ref().get_ref().get_ref()
+            }
+        };
+        Some(stream.read)
+    }
+
+    fn poll_decoded(
         mut self: Pin<&mut Self>,
         cx: &mut Context,
-    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+    ) -> Poll<Option<Result<Frame<Bytes>, crate::Error>>> {
         match self.inner {
             #[cfg(any(
                 feature = "brotli",
@@ -330,7 +471,7 @@ impl HttpBody for Decoder {
             Inner::Pending(ref mut future) => match Pin::new(future).poll(cx) {
                 Poll::Ready(Ok(inner)) => {
                     self.inner = inner;
-                    self.poll_frame(cx)
+                    self.poll_decoded(cx)
                 }
                 Poll::Ready(Err(e)) => Poll::Ready(Some(Err(crate::error::decode_io(e)))),
                 Poll::Pending => Poll::Pending,
@@ -396,12 +537,103 @@ impl HttpBody for Decoder {
                     }
                 }
             }
+            #[cfg(feature = "deflate")]
+            Inner::DeflateRaw(ref mut decoder) => {
+                match ready!(Pin::new(&mut *decoder).poll_next(cx)) {
+                    Some(Ok(bytes)) => Poll::Ready(Some(Ok(Frame::data(bytes.freeze())))),
+                    Some(Err(err)) => Poll::Ready(Some(Err(crate::error::decode_io(err)))),
+                    None => {
+                        // poll inner connection until EOF after deflate stream is finished
+                        poll_inner_should_be_empty(
+                            decoder.get_mut().get_mut().get_mut().get_mut(),
+                            cx,
+                        )
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl HttpBody for Decoder {
+    type Data = Bytes;
+    type Error = crate::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        if !self.unread.is_empty() {
+            let bytes = std::mem::take(&mut self.unread);
+            return Poll::Ready(Some(Ok(Frame::data(bytes))));
+        }
+
+        // a body that tells its length fails before any of it is read
+        if let Some(max) = self.max_size {
+            let too_large = match self.inner {
+                Inner::PlainText(ref body) => self.decoded + body.size_hint().lower() > max,
+                #[cfg(any(
+                    feature = "brotli",
+                    feature = "zstd",
+                    feature = "gzip",
+                    feature = "deflate"
+                ))]
+                _ => false,
+            };
+            if too_large {
+                return self.abort(crate::error::body(crate::error::ResponseTooLarge::new(max)));
+            }
+        }
+
+        let frame = ready!(self.as_mut().poll_decoded(cx));
+        if let Some(Ok(ref frame)) = frame {
+            if let Some(data) = frame.data_ref() {
+                self.decoded += data.len() as u64;
+            }
+        }
+
+        if let Some(max) = self.max_size {
+            if self.decoded > max {
+                return self.abort(crate::error::body(crate::error::ResponseTooLarge::new(max)));
+            }
+        }
+        #[cfg(any(
+            feature = "gzip",
+            feature = "brotli",
+            feature = "zstd",
+            feature = "deflate"
+        ))]
+        if self.is_bomb() {
+            return self.abort(crate::error::decode(crate::error::DecompressionBomb));
+        }
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        match self.inner {
+            Inner::PlainText(ref body) => self.unread.is_empty() && body.is_end_stream(),
+            // the rest can't tell before their end is read
+            #[cfg(any(
+                feature = "brotli",
+                feature = "zstd",
+                feature = "gzip",
+                feature = "deflate"
+            ))]
+            _ => false,
         }
     }
 
     fn size_hint(&self) -> http_body::SizeHint {
         match self.inner {
-            Inner::PlainText(ref body) => HttpBody::size_hint(body),
+            Inner::PlainText(ref body) => {
+                let mut hint = HttpBody::size_hint(body);
+                let unread = self.unread.len() as u64;
+                if let Some(upper) = hint.upper() {
+                    hint.set_upper(upper + unread);
+                }
+                hint.set_lower(hint.lower() + unread);
+                hint
+            }
             // the rest are "unknown", so default
             #[cfg(any(
                 feature = "brotli",
@@ -437,7 +669,10 @@ fn poll_inner_should_be_empty(
                 ))));
             }
             Some(Err(err)) => return Poll::Ready(Some(Err(crate::error::decode_io(err)))),
-            None => return Poll::Ready(None),
+            None => {
+                let trailers = inner.get_mut().get_mut().trailers.take();
+                return Poll::Ready(trailers.map(|trailers| Ok(Frame::trailers(trailers))));
+            }
         }
     }
 }
@@ -478,7 +713,7 @@ impl Future for Pending {
             None => return Poll::Ready(Ok(Inner::PlainText(empty()))),
         };
 
-        let _body = std::mem::replace(&mut self.0, IoStream(empty()).peekable());
+        let _body = std::mem::replace(&mut self.0, IoStream::new(empty()).peekable());
 
         match self.1 {
             #[cfg(feature = "brotli")]
@@ -510,13 +745,63 @@ impl Future for Pending {
                 .fuse(),
             )))),
             #[cfg(feature = "deflate")]
-            DecoderType::Deflate => Poll::Ready(Ok(Inner::Deflate(Box::pin(
-                FramedRead::new(
-                    ZlibDecoder::new(StreamReader::new(_body)),
-                    BytesCodec::new(),
-                )
-                .fuse(),
-            )))),
+            DecoderType::Deflate => {
+                let mut body = _body;
+
+                // `deflate` content should be zlib-wrapped, but some servers send raw
+                // deflate data instead, which browsers accept too
+                let zlib = match Pin::new(&mut body).poll_peek(cx) {
+                    Poll::Ready(Some(Ok(chunk))) => is_zlib(chunk),
+                    _ => true,
+                };
+
+                if zlib {
+                    Poll::Ready(Ok(Inner::Deflate(Box::pin(
+                        FramedRead::new(
+                            ZlibDecoder::new(StreamReader::new(body)),
+                            BytesCodec::new(),
+                        )
+                        .fuse(),
+                    ))))
+                } else {
+                    Poll::Ready(Ok(Inner::DeflateRaw(Box::pin(
+                        FramedRead::new(
+                            DeflateDecoder::new(StreamReader::new(body)),
+                            BytesCodec::new(),
+                        )
+                        .fuse(),
+                    ))))
+                }
+            }
+        }
+    }
+}
+
+/// Whether deflated content starts with a zlib header ([RFC 1950](https://www.rfc-editor.org/rfc/rfc1950)).
+#[cfg(feature = "deflate")]
+fn is_zlib(chunk: &[u8]) -> bool {
+    // the compression method must be deflate with a window of at most 32K
+    let method = |cmf: u8| cmf & 0x0f == 8 && cmf >> 4 <= 7;
+
+    match *chunk {
+        [cmf, flg, ..] => method(cmf) && u16::from_be_bytes([cmf, flg]) % 31 == 0,
+        [cmf] => method(cmf),
+        [] => true,
+    }
+}
+
+#[cfg(any(
+    feature = "gzip",
+    feature = "zstd",
+    feature = "brotli",
+    feature = "deflate",
+))]
+impl<B> IoStream<B> {
+    pub(crate) fn new(body: B) -> IoStream<B> {
+        IoStream {
+            body,
+            trailers: None,
+            read: 0,
         }
     }
 }
@@ -536,15 +821,20 @@ where
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
         loop {
-            return match ready!(Pin::new(&mut self.0).poll_frame(cx)) {
-                Some(Ok(frame)) => {
-                    // skip non-data frames
-                    if let Ok(buf) = frame.into_data() {
+            return match ready!(Pin::new(&mut self.body).poll_frame(cx)) {
+                Some(Ok(frame)) => match frame.into_data() {
+                    Ok(buf) => {
+                        self.read += buf.len() as u64;
                         Poll::Ready(Some(Ok(buf)))
-                    } else {
+                    }
+                    Err(frame) => {
+                        // keep trailers, skip other non-data frames
+                        if let Ok(trailers) = frame.into_trailers() {
+                            self.trailers = Some(trailers);
+                        }
                         continue;
                     }
-                }
+                },
                 Some(Err(err)) => Poll::Ready(Some(Err(crate::error::into_io(err.into())))),
                 None => Poll::Ready(None),
             };
@@ -565,6 +855,13 @@ impl Accepts {
             zstd: false,
             #[cfg(feature = "deflate")]
             deflate: false,
+            #[cfg(any(
+                feature = "gzip",
+                feature = "brotli",
+                feature = "zstd",
+                feature = "deflate"
+            ))]
+            limits: DecompressionLimits::default(),
         }
     }
 }
@@ -577,46 +874,77 @@ impl Accepts {
     test
 ))]
 impl Accepts {
+    /// The `Accept-Encoding` value for the enabled encodings.
+    ///
+    /// Encodings are listed in the order browsers send them.
     pub(super) const fn as_str(&self) -> Option<&'static str> {
         match (
             self.is_gzip(),
+            self.is_deflate(),
             self.is_brotli(),
             self.is_zstd(),
-            self.is_deflate(),
         ) {
-            (true, true, true, true) => Some("gzip, br, zstd, deflate"),
-            (true, true, false, true) => Some("gzip, br, deflate"),
-            (true, true, true, false) => Some("gzip, br, zstd"),
-            (true, true, false, false) => Some("gzip, br"),
-            (true, false, true, true) => Some("gzip, zstd, deflate"),
-            (true, false, false, true) => Some("gzip, deflate"),
-            (false, true, true, true) => Some("br, zstd, deflate"),
-            (false, true, false, true) => Some("br, deflate"),
-            (true, false, true, false) => Some("gzip, zstd"),
+            (true, true, true, true) => Some("gzip, deflate, br, zstd"),
+            (true, true, true, false) => Some("gzip, deflate, br"),
+            (true, true, false, true) => Some("gzip, deflate, zstd"),
+            (true, true, false, false) => Some("gzip, deflate"),
+            (true, false, true, true) => Some("gzip, br, zstd"),
+            (true, false, true, false) => Some("gzip, br"),
+            (true, false, false, true) => Some("gzip, zstd"),
             (true, false, false, false) => Some("gzip"),
-            (false, true, true, false) => Some("br, zstd"),
-            (false, true, false, false) => Some("br"),
-            (false, false, true, true) => Some("zstd, deflate"),
-            (false, false, true, false) => Some("zstd"),
-            (false, false, false, true) => Some("deflate"),
+            (false, true, true, true) => Some("deflate, br, zstd"),
+            (false, true, true, false) => Some("deflate, br"),
+            (false, true, false, true) => Some("deflate, zstd"),
+            (false, true, false, false) => Some("deflate"),
+            (false, false, true, true) => Some("br, zstd"),
+            (false, false, true, false) => Some("br"),
+            (false, false, false, true) => Some("zstd"),
             (false, false, false, false) => None,
         }
     }
 
     const fn is_gzip(&self) -> bool {
-        cfg!(feature = "gzip")
+        #[cfg(feature = "gzip")]
+        {
+            self.gzip
+        }
+        #[cfg(not(feature = "gzip"))]
+        {
+            false
+        }
     }
 
     const fn is_brotli(&self) -> bool {
-        cfg!(feature = "brotli")
+        #[cfg(feature = "brotli")]
+        {
+            self.brotli
+        }
+        #[cfg(not(feature = "brotli"))]
+        {
+            false
+        }
     }
 
     const fn is_zstd(&self) -> bool {
-        cfg!(feature = "zstd")
+        #[cfg(feature = "zstd")]
+        {
+            self.zstd
+        }
+        #[cfg(not(feature = "zstd"))]
+        {
+            false
+        }
     }
 
     const fn is_deflate(&self) -> bool {
-        cfg!(feature = "deflate")
+        #[cfg(feature = "deflate")]
+        {
+            self.deflate
+        }
+        #[cfg(not(feature = "deflate"))]
+        {
+            false
+        }
     }
 }
 
@@ -632,6 +960,13 @@ impl Default for Accepts {
             zstd: true,
             #[cfg(feature = "deflate")]
             deflate: true,
+            #[cfg(any(
+                feature = "gzip",
+                feature = "brotli",
+                feature = "zstd",
+                feature = "deflate"
+            ))]
+            limits: DecompressionLimits::default(),
         }
     }
 }
@@ -640,6 +975,21 @@ impl Default for Accepts {
 mod tests {
     use super::*;
 
+    #[cfg(feature = "deflate")]
+    #[test]
+    fn detects_zlib_header() {
+        // zlib headers for each compression level
+        assert!(is_zlib(&[0x78, 0x01]));
+        assert!(is_zlib(&[0x78, 0x9c]));
+        assert!(is_zlib(&[0x78, 0xda]));
+        assert!(is_zlib(&[0x78]));
+
+        // raw deflate blocks
+        assert!(!is_zlib(&[0xcb, 0x48]));
+        assert!(!is_zlib(&[0xf3, 0x48]));
+        assert!(!is_zlib(&[0x78, 0x9d]));
+    }
+
     #[test]
     fn accepts_as_str() {
         fn format_accept_encoding(accepts: &Accepts) -> String {
@@ -647,15 +997,15 @@ mod tests {
             if accepts.is_gzip() {
                 encodings.push("gzip");
             }
+            if accepts.is_deflate() {
+                encodings.push("deflate");
+            }
             if accepts.is_brotli() {
                 encodings.push("br");
             }
             if accepts.is_zstd() {
                 encodings.push("zstd");
             }
-            if accepts.is_deflate() {
-                encodings.push("deflate");
-            }
             encodings.join(", ")
         }
 
@@ -676,6 +1026,13 @@ mod tests {
                             zstd,
                             #[cfg(feature = "deflate")]
                             deflate,
+                            #[cfg(any(
+                                feature = "gzip",
+                                feature = "brotli",
+                                feature = "zstd",
+                                feature = "deflate"
+                            ))]
+                            limits: DecompressionLimits::default(),
                         });
                     }
                 }
@@ -0,0 +1,324 @@
+//! HTTP Digest access authentication ([RFC 7616](https://www.rfc-editor.org/rfc/rfc7616))
+
+use std::fmt::{self, Write};
+
+use boring2::hash::{MessageDigest, hash};
+use http::Method;
+use url::Url;
+
+use crate::header::{HeaderMap, HeaderName, HeaderValue, PROXY_AUTHENTICATE, WWW_AUTHENTICATE};
+
+/// The username and password to answer a Digest challenge with.
+#[derive(Clone)]
+pub(crate) struct DigestAuth {
+    username: String,
+    password: String,
+}
+
+impl DigestAuth {
+    pub(crate) fn new(username: String, password: String) -> DigestAuth {
+        DigestAuth { username, password }
+    }
+
+    /// Build the `Authorization` header answering the strongest Digest challenge
+    /// of a `401 Unauthorized` response, if it has one this client supports.
+    pub(crate) fn authorize(
+        &self,
+        headers: &HeaderMap,
+        method: &Method,
+        url: &Url,
+    ) -> Option<HeaderValue> {
+        let uri = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_owned(),
+        };
+        self.answer(headers, &WWW_AUTHENTICATE, method, &uri)
+    }
+
+    /// Build the `Proxy-Authorization` header answering the strongest Digest
+    /// challenge of a `407 Proxy Authentication Required` response to a request
+    /// for `uri`, in the form it was sent to the proxy.
+    pub(crate) fn authorize_proxy(
+        &self,
+        headers: &HeaderMap,
+        method: &Method,
+        uri: &str,
+    ) -> Option<HeaderValue> {
+        self.answer(headers, &PROXY_AUTHENTICATE, method, uri)
+    }
+
+    fn answer(
+        &self,
+        headers: &HeaderMap,
+        name: &HeaderName,
+        method: &Method,
+        uri: &str,
+    ) -> Option<HeaderValue> {
+        let challenge = headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| Challenge::parse(value.to_str().ok()?))
+            .max_by_key(|challenge| challenge.algorithm.strength())?;
+
+        let mut cnonce = [0; 16];
+        boring2::rand::rand_bytes(&mut cnonce).ok()?;
+        self.respond(&challenge, method, uri, &hex(&cnonce))
+    }
+
+    fn respond(
+        &self,
+        challenge: &Challenge,
+        method: &Method,
+        uri: &str,
+        cnonce: &str,
+    ) -> Option<HeaderValue> {
+        let algorithm = challenge.algorithm;
+
+        let mut ha1 = algorithm.hash(&format!(
+            "{}:{}:{}",
+            self.username, challenge.realm, self.password
+        ))?;
+        if algorithm.session {
+            ha1 = algorithm.hash(&format!("{}:{}:{}", ha1, challenge.nonce, cnonce))?;
+        }
+        let ha2 = algorithm.hash(&format!("{}:{}", method, uri))?;
+
+        let mut header = format!(
+            "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm={}",
+            quote(&self.username),
+            quote(&challenge.realm),
+            quote(&challenge.nonce),
+            quote(uri),
+            algorithm,
+        );
+
+        let response = if challenge.qop_auth {
+            let nc = "00000001";
+            let _ = write!(header, ", qop=auth, nc={}, cnonce=\"{}\"", nc, cnonce);
+            algorithm.hash(&format!(
+                "{}:{}:{}:{}:auth:{}",
+                ha1, challenge.nonce, nc, cnonce, ha2
+            ))?
+        } else {
+            algorithm.hash(&format!("{}:{}:{}", ha1, challenge.nonce, ha2))?
+        };
+        let _ = write!(header, ", response=\"{}\"", response);
+
+        if let Some(ref opaque) = challenge.opaque {
+            let _ = write!(header, ", opaque=\"{}\"", quote(opaque));
+        }
+
+        let mut header = HeaderValue::from_str(&header).ok()?;
+        header.set_sensitive(true);
+        Some(header)
+    }
+}
+
+impl fmt::Debug for DigestAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DigestAuth")
+            .field("username", &self.username)
+            .finish()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Algorithm {
+    sha256: bool,
+    session: bool,
+}
+
+impl Algorithm {
+    fn parse(name: &str) -> Option<Algorithm> {
+        let (name, session) = match name.len().checked_sub(5) {
+            Some(at) if name[at..].eq_ignore_ascii_case("-sess") => (&name[..at], true),
+            _ => (name, false),
+        };
+        let sha256 = if name.eq_ignore_ascii_case("MD5") {
+            false
+        } else if name.eq_ignore_ascii_case("SHA-256") {
+            true
+        } else {
+            return None;
+        };
+        Some(Algorithm { sha256, session })
+    }
+
+    fn strength(&self) -> u8 {
+        u8::from(self.sha256)
+    }
+
+    fn hash(&self, data: &str) -> Option<String> {
+        let digest = if self.sha256 {
+            MessageDigest::sha256()
+        } else {
+            MessageDigest::md5()
+        };
+        hash(digest, data.as_bytes()).ok().map(|bytes| hex(&bytes))
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.sha256 { "SHA-256" } else { "MD5" })?;
+        if self.session {
+            f.write_str("-sess")?;
+        }
+        Ok(())
+    }
+}
+
+/// A `WWW-Authenticate: Digest` challenge.
+#[derive(Debug, PartialEq)]
+struct Challenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    algorithm: Algorithm,
+    qop_auth: bool,
+}
+
+impl Challenge {
+    fn parse(value: &str) -> Option<Challenge> {
+        let value = value.trim_start();
+        let (scheme, mut rest) = value.split_at_checked(6)?;
+        if !scheme.eq_ignore_ascii_case("Digest") || !rest.starts_with(' ') {
+            return None;
+        }
+
+        let mut realm = None;
+        let mut nonce = None;
+        let mut opaque = None;
+        let mut algorithm = Some(Algorithm {
+            sha256: false,
+            session: false,
+        });
+        let mut qop = None;
+
+        while let Some((name, value, next)) = param(rest) {
+            match name.to_ascii_lowercase().as_str() {
+                "realm" => realm = Some(value),
+                "nonce" => nonce = Some(value),
+                "opaque" => opaque = Some(value),
+                "algorithm" => algorithm = Algorithm::parse(&value),
+                "qop" => qop = Some(value),
+                _ => {}
+            }
+            rest = next;
+        }
+
+        // a challenge offering only `auth-int` can't be answered
+        let qop_auth = match qop {
+            Some(qop) => {
+                if !qop.split(',').any(|qop| qop.trim() == "auth") {
+                    return None;
+                }
+                true
+            }
+            None => false,
+        };
+
+        Some(Challenge {
+            realm: realm?,
+            nonce: nonce?,
+            opaque,
+            algorithm: algorithm?,
+            qop_auth,
+        })
+    }
+}
+
+/// Parse the next `name=value` parameter, returning it and the rest of the input.
+fn param(input: &str) -> Option<(&str, String, &str)> {
+    let input = input.trim_start_matches([' ', '\t', ',']);
+    let (name, rest) = input.split_once('=')?;
+    let name = name.trim();
+    let rest = rest.trim_start();
+
+    if let Some(quoted) = rest.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = quoted.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => value.push(chars.next()?.1),
+                '"' => return Some((name, value, &quoted[i + 1..])),
+                c => value.push(c),
+            }
+        }
+        None
+    } else {
+        let end = rest.find(',').unwrap_or(rest.len());
+        Some((name, rest[..end].trim().to_owned(), &rest[end..]))
+    }
+}
+
+/// Escape a value for a quoted-string.
+fn quote(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{:02x}", b);
+        hex
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the example of RFC 7616, section 3.9.1
+    const CHALLENGE: &str = "Digest realm=\"http-auth@example.org\", qop=\"auth, auth-int\", \
+        algorithm=SHA-256, nonce=\"7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v\", \
+        opaque=\"FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS\"";
+    const CNONCE: &str = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+
+    fn respond(challenge: &str) -> String {
+        let auth = DigestAuth::new("Mufasa".into(), "Circle of Life".into());
+        let challenge = Challenge::parse(challenge).unwrap();
+        auth.respond(&challenge, &Method::GET, "/dir/index.html", CNONCE)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+    }
+
+    #[test]
+    fn parses_challenge() {
+        let challenge = Challenge::parse(CHALLENGE).unwrap();
+        assert_eq!(challenge.realm, "http-auth@example.org");
+        assert_eq!(
+            challenge.nonce,
+            "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v"
+        );
+        assert!(challenge.algorithm.sha256);
+        assert!(!challenge.algorithm.session);
+        assert!(challenge.qop_auth);
+
+        assert!(Challenge::parse("Basic realm=\"x\"").is_none());
+        assert!(Challenge::parse("Digest realm=\"x\", nonce=\"y\", algorithm=SHA-512").is_none());
+        assert!(Challenge::parse("Digest realm=\"x\", nonce=\"y\", qop=\"auth-int\"").is_none());
+    }
+
+    #[test]
+    fn responds_with_sha256() {
+        let header = respond(CHALLENGE);
+        assert!(header.starts_with(
+            "Digest username=\"Mufasa\", realm=\"http-auth@example.org\", \
+             nonce=\"7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v\", \
+             uri=\"/dir/index.html\", algorithm=SHA-256, qop=auth, nc=00000001"
+        ));
+        assert!(header.contains(
+            "response=\"753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1\""
+        ));
+        assert!(header.ends_with("opaque=\"FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS\""));
+    }
+
+    #[test]
+    fn responds_with_md5() {
+        let header = respond(&CHALLENGE.replace("SHA-256", "MD5"));
+        assert!(header.contains("algorithm=MD5"));
+        assert!(header.contains("response=\"8ca523f5e9506fed4657c9700eebdbec\""));
+    }
+}
@@ -131,6 +131,16 @@ impl EmulationProvider {
             provider: EmulationProvider::default(),
         }
     }
+
+    /// Creates an `EmulationProviderBuilder` starting from this provider.
+    pub fn into_builder(self) -> EmulationProviderBuilder {
+        EmulationProviderBuilder { provider: self }
+    }
+
+    /// Returns the HTTP/2 configuration, if any.
+    pub fn http2_config(&self) -> Option<&Http2Config> {
+        self.http2_config.as_ref()
+    }
 }
 
 /// Implement `EmulationProviderFactory` for `EmulationProvider`.
@@ -0,0 +1,162 @@
+//! HTML documents
+//!
+//! [`Response::html`] parses a body into an [`Html`] document, which can be
+//! queried with CSS selectors.
+//!
+//! [`Response::html`]: crate::Response::html
+
+use std::fmt;
+
+use crate::error;
+
+/// A parsed HTML document.
+///
+/// # Example
+///
+/// ```
+/// use rquest::html::Html;
+///
+/// let doc = Html::parse(r#"<ul><li><a href="/a">A</a></li><li><a href="/b">B</a></li></ul>"#);
+/// let links = doc
+///     .select("li > a")?
+///     .iter()
+///     .filter_map(|a| a.attr("href"))
+///     .collect::<Vec<_>>();
+/// assert_eq!(links, ["/a", "/b"]);
+/// # Ok::<(), rquest::Error>(())
+/// ```
+pub struct Html {
+    document: scraper::Html,
+}
+
+impl Html {
+    /// Parse a whole HTML document.
+    ///
+    /// Parsing never fails: like a browser, the parser recovers from
+    /// malformed markup.
+    pub fn parse(text: &str) -> Html {
+        Html {
+            document: scraper::Html::parse_document(text),
+        }
+    }
+
+    /// Returns the elements matching the CSS `selector`, in document order.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if `selector` isn't a valid CSS selector.
+    pub fn select(&self, selector: &str) -> crate::Result<Vec<Element<'_>>> {
+        let selector = parse_selector(selector)?;
+        Ok(self.document.select(&selector).map(Element).collect())
+    }
+
+    /// Returns the first element matching the CSS `selector`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if `selector` isn't a valid CSS selector.
+    pub fn select_first(&self, selector: &str) -> crate::Result<Option<Element<'_>>> {
+        let selector = parse_selector(selector)?;
+        Ok(self.document.select(&selector).next().map(Element))
+    }
+
+    /// Returns the text of the `<title>` element, if there is one.
+    pub fn title(&self) -> Option<String> {
+        self.select_first("title")
+            .ok()
+            .flatten()
+            .map(|title| title.text())
+    }
+
+    /// Returns the underlying `scraper` document, for queries this type
+    /// doesn't cover.
+    pub fn as_scraper(&self) -> &scraper::Html {
+        &self.document
+    }
+}
+
+impl fmt::Debug for Html {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Html").finish()
+    }
+}
+
+/// An element of an [`Html`] document.
+#[derive(Clone, Copy)]
+pub struct Element<'a>(scraper::ElementRef<'a>);
+
+impl<'a> Element<'a> {
+    /// Returns the lowercase tag name, like `a`.
+    pub fn name(&self) -> &'a str {
+        self.0.value().name()
+    }
+
+    /// Returns the value of the attribute `name`.
+    pub fn attr(&self, name: &str) -> Option<&'a str> {
+        self.0.value().attr(name)
+    }
+
+    /// Returns the text of the element and its descendants, concatenated.
+    pub fn text(&self) -> String {
+        self.0.text().collect()
+    }
+
+    /// Returns the HTML of the element, including its own tag.
+    pub fn html(&self) -> String {
+        self.0.html()
+    }
+
+    /// Returns the HTML of the children of the element.
+    pub fn inner_html(&self) -> String {
+        self.0.inner_html()
+    }
+
+    /// Returns the descendants of the element matching the CSS `selector`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if `selector` isn't a valid CSS selector.
+    pub fn select(&self, selector: &str) -> crate::Result<Vec<Element<'a>>> {
+        let selector = parse_selector(selector)?;
+        Ok(self.0.select(&selector).map(Element).collect())
+    }
+
+    /// Returns the underlying `scraper` element.
+    pub fn as_scraper(&self) -> scraper::ElementRef<'a> {
+        self.0
+    }
+}
+
+impl fmt::Debug for Element<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Element").field(&self.0.html()).finish()
+    }
+}
+
+fn parse_selector(selector: &str) -> crate::Result<scraper::Selector> {
+    scraper::Selector::parse(selector)
+        .map_err(|e| error::builder(format!("invalid CSS selector {selector:?}: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selects_elements() {
+        let doc = Html::parse(
+            "<title>Shop</title><div class=item><span>A</span> <b>1</b></div><div class=item>B</div>",
+        );
+
+        assert_eq!(doc.title().as_deref(), Some("Shop"));
+
+        let items = doc.select("div.item").unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].text(), "A 1");
+        assert_eq!(items[0].select("b").unwrap()[0].inner_html(), "1");
+        assert_eq!(items[1].attr("class"), Some("item"));
+
+        assert!(doc.select("div[").unwrap_err().is_builder());
+        assert!(doc.select_first("table").unwrap().is_none());
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Weak};
+use std::task::{Context, Poll, ready};
+use std::time::Duration;
+
+use antidote::Mutex;
+use bytes::Bytes;
+use futures_util::future::{self, Either};
+use http_body::{Body, Frame, SizeHint};
+use pin_project_lite::pin_project;
+use sync_wrapper::SyncWrapper;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use url::Url;
+
+use super::body::ResponseBody;
+use crate::core::rt::Timer;
+use crate::error::{self, Error, TimeoutPhase};
+use crate::rt::RuntimeTimer;
+
+/// A request waiting for a free slot.
+pub(crate) type Queued = SyncWrapper<Pin<Box<dyn Future<Output = Result<Permits, Error>> + Send>>>;
+
+/// Caps the requests a client has in flight, in total and to each origin.
+///
+/// Waiting requests are served in the order they were sent.
+#[derive(Clone, Default)]
+pub(crate) struct RequestLimits {
+    total: Option<Arc<Semaphore>>,
+    per_host: Option<(usize, Arc<Mutex<HashMap<String, Weak<Semaphore>>>>)>,
+    queue_timeout: Option<Duration>,
+}
+
+/// The slots taken by a request, given back once its response body is finished.
+pub(crate) struct Permits {
+    _host: Option<OwnedSemaphorePermit>,
+    _total: Option<OwnedSemaphorePermit>,
+}
+
+impl RequestLimits {
+    pub(crate) fn new(
+        max_concurrent_requests: Option<usize>,
+        max_connections_per_host: Option<usize>,
+        queue_timeout: Option<Duration>,
+    ) -> RequestLimits {
+        RequestLimits {
+            total: max_concurrent_requests.map(|max| Arc::new(Semaphore::new(max))),
+            per_host: max_connections_per_host.map(|max| (max, Default::default())),
+            queue_timeout,
+        }
+    }
+
+    /// Wait for a slot to send a request to `url`, if the client has limits.
+    pub(crate) fn acquire(&self, url: &Url, timer: &RuntimeTimer) -> Option<Queued> {
+        if self.total.is_none() && self.per_host.is_none() {
+            return None;
+        }
+
+        let total = self.total.clone();
+        let host = self
+            .per_host
+            .as_ref()
+            .map(|(max, hosts)| host_semaphore(*max, hosts, url));
+        let queue_timeout = self.queue_timeout;
+        let timer = timer.clone();
+        let url = url.clone();
+
+        let acquire = async move {
+            // wait for the origin first, so a request to a busy origin doesn't
+            // hold a slot requests to other origins could use
+            let host = match host {
+                Some(host) => Some(acquire_owned(host).await),
+                None => None,
+            };
+            let total = match total {
+                Some(total) => Some(acquire_owned(total).await),
+                None => None,
+            };
+            Permits {
+                _host: host,
+                _total: total,
+            }
+        };
+
+        Some(SyncWrapper::new(Box::pin(async move {
+            let Some(timeout) = queue_timeout else {
+                return Ok(acquire.await);
+            };
+            match future::select(Box::pin(acquire), timer.sleep(timeout)).await {
+                Either::Left((permits, _)) => Ok(permits),
+                Either::Right(_) => Err(error::request(error::TimedOut)
+                    .with_url(url)
+                    .with_timeout_phase(TimeoutPhase::Queue)),
+            }
+        })))
+    }
+}
+
+impl Permits {
+    /// Hold the slots until the body of `res` is finished or dropped.
+    pub(crate) fn response(
+        self,
+        res: http::Response<ResponseBody>,
+    ) -> http::Response<ResponseBody> {
+        res.map(|body| {
+            super::body::boxed(PermitBody {
+                inner: body,
+                permits: Some(self),
+            })
+        })
+    }
+}
+
+fn host_semaphore(
+    max: usize,
+    hosts: &Mutex<HashMap<String, Weak<Semaphore>>>,
+    url: &Url,
+) -> Arc<Semaphore> {
+    let origin = url.origin().ascii_serialization();
+    let mut hosts = hosts.lock();
+    if let Some(semaphore) = hosts.get(&origin).and_then(Weak::upgrade) {
+        return semaphore;
+    }
+
+    // forget the origins no request is using anymore
+    hosts.retain(|_, semaphore| semaphore.strong_count() > 0);
+    let semaphore = Arc::new(Semaphore::new(max));
+    hosts.insert(origin, Arc::downgrade(&semaphore));
+    semaphore
+}
+
+async fn acquire_owned(semaphore: Arc<Semaphore>) -> OwnedSemaphorePermit {
+    semaphore
+        .acquire_owned()
+        .await
+        .expect("request limits are never closed")
+}
+
+pin_project! {
+    /// Gives back the slots of a request when its response body is finished.
+    struct PermitBody<B> {
+        #[pin]
+        inner: B,
+        permits: Option<Permits>,
+    }
+}
+
+impl<B> Body for PermitBody<B>
+where
+    B: Body<Data = Bytes>,
+{
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, B::Error>>> {
+        let this = self.project();
+        let item = ready!(this.inner.poll_frame(cx));
+        if !matches!(item, Some(Ok(_))) {
+            this.permits.take();
+        }
+        Poll::Ready(item)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
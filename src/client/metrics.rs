@@ -0,0 +1,293 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, ready};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use http::{Method, StatusCode, Uri};
+use http_body::{Body, Frame, SizeHint};
+use pin_project_lite::pin_project;
+use url::Url;
+
+/// Observes the requests and connections of a `Client`, e.g. to record
+/// latency histograms and byte counters.
+///
+/// The observer is called from the tasks running the requests, so it should
+/// only update its counters and return.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use rquest::{MetricsObserver, RequestMetrics};
+///
+/// #[derive(Default)]
+/// struct BytesReceived(AtomicU64);
+///
+/// impl MetricsObserver for BytesReceived {
+///     fn on_request(&self, metrics: &RequestMetrics) {
+///         self.0.fetch_add(metrics.bytes_received(), Ordering::Relaxed);
+///     }
+/// }
+///
+/// let client = rquest::Client::builder()
+///     .metrics(BytesReceived::default())
+///     .build()
+///     .unwrap();
+/// ```
+pub trait MetricsObserver: Send + Sync {
+    /// Called once for every request, when it is finished.
+    ///
+    /// A request is finished when its response body has been read to the end,
+    /// failed, or was dropped, or when it failed before a response arrived.
+    /// Requests that are dropped before a response arrives aren't observed.
+    fn on_request(&self, metrics: &RequestMetrics);
+
+    /// Called for every new connection, when it is established or failed.
+    ///
+    /// By default, this does nothing.
+    fn on_connect(&self, metrics: &ConnectMetrics) {
+        let _ = metrics;
+    }
+}
+
+/// The timings and byte counts of a finished request.
+#[derive(Debug, Clone)]
+pub struct RequestMetrics {
+    method: Method,
+    url: Url,
+    status: Option<StatusCode>,
+    attempts: usize,
+    bytes_sent: Option<u64>,
+    bytes_received: u64,
+    time_to_headers: Option<Duration>,
+    retry_after_wait: Duration,
+    duration: Duration,
+    failed: bool,
+}
+
+impl RequestMetrics {
+    /// The method of the request.
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// The final URL of the request, after any redirects.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// The status of the response, or `None` if there was no response.
+    pub fn status(&self) -> Option<StatusCode> {
+        self.status
+    }
+
+    /// The number of times the request was sent, counting redirects, retries
+    /// and authentication challenges.
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+
+    /// The size of the request body, or `None` if it was streamed.
+    pub fn bytes_sent(&self) -> Option<u64> {
+        self.bytes_sent
+    }
+
+    /// The bytes of the response body read, before any decompression.
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received
+    }
+
+    /// The time from sending the request until the headers of the final
+    /// response arrived, or `None` if there was no response.
+    pub fn time_to_headers(&self) -> Option<Duration> {
+        self.time_to_headers
+    }
+
+    /// The time the request waited on `Retry-After` headers, with
+    /// `ClientBuilder::respect_retry_after`.
+    pub fn retry_after_wait(&self) -> Duration {
+        self.retry_after_wait
+    }
+
+    /// The time from sending the request until it was finished.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Whether the request, or reading its response body, failed.
+    ///
+    /// A response with an error status doesn't fail the request.
+    pub fn failed(&self) -> bool {
+        self.failed
+    }
+}
+
+/// The timing of a new connection.
+#[derive(Debug, Clone)]
+pub struct ConnectMetrics {
+    uri: Uri,
+    duration: Duration,
+    failed: bool,
+}
+
+impl ConnectMetrics {
+    pub(crate) fn new(uri: Uri, duration: Duration, failed: bool) -> ConnectMetrics {
+        ConnectMetrics {
+            uri,
+            duration,
+            failed,
+        }
+    }
+
+    /// The URI the connection was made for.
+    pub fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    /// The time taken to resolve the host, connect and do any proxy and TLS
+    /// handshakes.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Whether the connection failed.
+    pub fn failed(&self) -> bool {
+        self.failed
+    }
+}
+
+/// The metrics of a request being sent, reported to the observer once.
+pub(crate) struct Recorder {
+    observer: Arc<dyn MetricsObserver>,
+    metrics: RequestMetrics,
+    start: Instant,
+}
+
+impl Recorder {
+    pub(crate) fn new(
+        observer: Arc<dyn MetricsObserver>,
+        method: Method,
+        url: Url,
+        bytes_sent: Option<u64>,
+    ) -> Recorder {
+        Recorder {
+            observer,
+            metrics: RequestMetrics {
+                method,
+                url,
+                status: None,
+                attempts: 1,
+                bytes_sent,
+                bytes_received: 0,
+                time_to_headers: None,
+                retry_after_wait: Duration::ZERO,
+                duration: Duration::ZERO,
+                failed: false,
+            },
+            start: Instant::now(),
+        }
+    }
+
+    /// Record the request being sent once more.
+    pub(crate) fn resend(&mut self) {
+        self.metrics.attempts += 1;
+    }
+
+    /// Record the request waiting `wait` for a `Retry-After`.
+    pub(crate) fn retry_after(&mut self, wait: Duration) {
+        self.metrics.retry_after_wait += wait;
+    }
+
+    /// Report a request that failed without a response.
+    pub(crate) fn failed(mut self, url: &Url) {
+        self.metrics.url = url.clone();
+        self.metrics.failed = true;
+        self.metrics.duration = self.start.elapsed();
+        self.observer.on_request(&self.metrics);
+    }
+
+    /// Record the final response, and report the request once its body is
+    /// finished.
+    pub(crate) fn response(
+        mut self,
+        url: &Url,
+        res: http::Response<super::body::ResponseBody>,
+    ) -> http::Response<super::body::ResponseBody> {
+        self.metrics.url = url.clone();
+        self.metrics.status = Some(res.status());
+        self.metrics.time_to_headers = Some(self.start.elapsed());
+        res.map(|body| {
+            super::body::boxed(MeteredBody {
+                inner: body,
+                recorder: Some(self),
+            })
+        })
+    }
+
+    fn finish(mut self, failed: bool) {
+        self.metrics.failed = failed;
+        self.metrics.duration = self.start.elapsed();
+        self.observer.on_request(&self.metrics);
+    }
+}
+
+pin_project! {
+    /// Counts the bytes of a response body, and reports the request when the
+    /// body is finished.
+    struct MeteredBody<B> {
+        #[pin]
+        inner: B,
+        recorder: Option<Recorder>,
+    }
+
+    impl<B> PinnedDrop for MeteredBody<B> {
+        fn drop(this: Pin<&mut Self>) {
+            if let Some(recorder) = this.project().recorder.take() {
+                recorder.finish(false);
+            }
+        }
+    }
+}
+
+impl<B> Body for MeteredBody<B>
+where
+    B: Body<Data = Bytes>,
+{
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, B::Error>>> {
+        let this = self.project();
+        let item = ready!(this.inner.poll_frame(cx));
+        match item {
+            Some(Ok(ref frame)) => {
+                if let (Some(data), Some(recorder)) = (frame.data_ref(), this.recorder.as_mut()) {
+                    recorder.metrics.bytes_received += data.len() as u64;
+                }
+            }
+            Some(Err(_)) => {
+                if let Some(recorder) = this.recorder.take() {
+                    recorder.finish(true);
+                }
+            }
+            None => {
+                if let Some(recorder) = this.recorder.take() {
+                    recorder.finish(false);
+                }
+            }
+        }
+        Poll::Ready(item)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
@@ -1,19 +1,50 @@
-pub use self::body::Body;
+pub use self::bearer::{TokenFuture, TokenProvider};
+#[cfg(any(
+    feature = "gzip",
+    feature = "brotli",
+    feature = "zstd",
+    feature = "deflate"
+))]
+pub use self::body::Encoding;
+pub use self::body::{Body, BodySender};
 pub use self::client::{Client, ClientBuilder, ClientUpdate};
 pub use self::emulation::{EmulationProvider, EmulationProviderFactory};
+pub use self::metrics::{ConnectMetrics, MetricsObserver, RequestMetrics};
+pub use self::params::Params;
 pub use self::request::{Request, RequestBuilder};
 pub use self::response::Response;
+#[cfg(feature = "cookies")]
+pub use self::session::Session;
+pub use self::sigv4::AwsCredentials;
+pub use self::timeouts::Timeouts;
 pub use self::upgrade::Upgraded;
+pub use self::verbose::VerboseConfig;
 
+mod bearer;
 pub mod body;
 #[allow(clippy::module_inception)]
 mod client;
+mod curl;
 pub mod decoder;
+pub(crate) mod digest;
 mod emulation;
+#[cfg(feature = "html")]
+pub mod html;
+mod limit;
+pub(crate) mod metrics;
 #[cfg(feature = "multipart")]
 pub mod multipart;
+mod params;
+pub mod push;
+pub mod range;
 pub(crate) mod request;
-mod response;
+pub(crate) mod response;
+#[cfg(feature = "cookies")]
+mod session;
+pub(crate) mod sigv4;
+pub mod sse;
+mod timeouts;
 mod upgrade;
+mod verbose;
 #[cfg(feature = "websocket")]
 pub mod websocket;
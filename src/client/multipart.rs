@@ -13,6 +13,10 @@ use mime_guess::Mime;
 use percent_encoding::{self, AsciiSet, NON_ALPHANUMERIC};
 #[cfg(feature = "stream")]
 use tokio::fs::File;
+#[cfg(feature = "stream")]
+use tokio::io::AsyncRead;
+#[cfg(feature = "stream")]
+use tokio_util::io::ReaderStream;
 
 use futures_util::{Stream, StreamExt, future, stream};
 
@@ -259,10 +263,51 @@ impl Part {
     /// Makes a new parameter from an arbitrary stream with a known length. This is particularly
     /// useful when adding something like file contents as a stream, where you can know the content
     /// length beforehand.
+    ///
+    /// When every part of a form has a known length, the request is sent with a
+    /// `Content-Length` header instead of chunked encoding.
     pub fn stream_with_length<T: Into<Body>>(value: T, length: u64) -> Part {
         Part::new(value.into(), Some(length))
     }
 
+    /// Makes a new parameter that streams from an `AsyncRead` source.
+    ///
+    /// The source is read as the request is sent, so it is never buffered whole.
+    #[cfg(feature = "stream")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    pub fn reader<R>(reader: R) -> Part
+    where
+        R: AsyncRead + Send + 'static,
+    {
+        Part::stream(Body::wrap_stream(ReaderStream::new(reader)))
+    }
+
+    /// Makes a new parameter that streams from an `AsyncRead` source of a known length.
+    ///
+    /// See [`Part::stream_with_length`] for how the length is used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn run() -> std::io::Result<()> {
+    /// use rquest::multipart::{Form, Part};
+    ///
+    /// let file = tokio::fs::File::open("backup.tar").await?;
+    /// let len = file.metadata().await?.len();
+    ///
+    /// let form = Form::new().part("backup", Part::reader_with_length(file, len));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "stream")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    pub fn reader_with_length<R>(reader: R, length: u64) -> Part
+    where
+        R: AsyncRead + Send + 'static,
+    {
+        Part::stream_with_length(Body::wrap_stream(ReaderStream::new(reader)), length)
+    }
+
     /// Makes a file parameter.
     ///
     /// # Errors
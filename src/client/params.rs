@@ -0,0 +1,157 @@
+use std::fmt::{self, Write};
+
+/// An ordered list of `application/x-www-form-urlencoded` pairs, for query
+/// strings and form bodies that serde can't express.
+///
+/// Pairs are encoded in the order they were added, keys may repeat, and the
+/// characters left unencoded can be chosen. Use it with
+/// [`RequestBuilder::query_params`] and [`RequestBuilder::form_params`].
+///
+/// [`RequestBuilder::query_params`]: crate::RequestBuilder::query_params
+/// [`RequestBuilder::form_params`]: crate::RequestBuilder::form_params
+///
+/// # Example
+///
+/// ```
+/// use rquest::Params;
+///
+/// let params = Params::new()
+///     .append("sort", "+date")
+///     .append_all("id", [1, 2])
+///     .keep("+");
+/// assert_eq!(params.encode(), "sort=+date&id=1&id=2");
+///
+/// let params = Params::new().append("q", "a b").space_as_plus(false);
+/// assert_eq!(params.encode(), "q=a%20b");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Params {
+    pairs: Vec<(String, String)>,
+    keep: Vec<u8>,
+    space_as_percent: bool,
+}
+
+impl Params {
+    /// Create an empty list of pairs.
+    pub fn new() -> Params {
+        Params::default()
+    }
+
+    /// Add a pair, after those already added.
+    pub fn append<K, V>(mut self, key: K, value: V) -> Params
+    where
+        K: Into<String>,
+        V: fmt::Display,
+    {
+        self.pairs.push((key.into(), value.to_string()));
+        self
+    }
+
+    /// Add a pair for each of `values`, all with the same key, like
+    /// `id=1&id=2`.
+    ///
+    /// For keys like `id[]`, include the brackets in `key`.
+    pub fn append_all<K, I>(mut self, key: K, values: I) -> Params
+    where
+        K: Into<String>,
+        I: IntoIterator,
+        I::Item: fmt::Display,
+    {
+        let key = key.into();
+        for value in values {
+            self.pairs.push((key.clone(), value.to_string()));
+        }
+        self
+    }
+
+    /// Leave the ASCII characters of `chars` unencoded, like the `+` some
+    /// endpoints expect literally, or the `,` of a list.
+    ///
+    /// Characters that can't appear in a query string, such as `#` and `&`,
+    /// shouldn't be kept.
+    pub fn keep(mut self, chars: &str) -> Params {
+        self.keep.extend(chars.bytes().filter(u8::is_ascii));
+        self
+    }
+
+    /// Set whether a space is encoded as `+`, rather than `%20`.
+    ///
+    /// Default is `true`, as in HTML forms.
+    pub fn space_as_plus(mut self, enabled: bool) -> Params {
+        self.space_as_percent = !enabled;
+        self
+    }
+
+    /// Returns the number of pairs.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns true if there are no pairs.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Encode the pairs, like `a=1&b=2`.
+    pub fn encode(&self) -> String {
+        let mut out = String::new();
+        for (i, (key, value)) in self.pairs.iter().enumerate() {
+            if i > 0 {
+                out.push('&');
+            }
+            self.encode_into(&mut out, key);
+            out.push('=');
+            self.encode_into(&mut out, value);
+        }
+        out
+    }
+
+    fn encode_into(&self, out: &mut String, s: &str) {
+        for &b in s.as_bytes() {
+            match b {
+                b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => {
+                    out.push(b as char)
+                }
+                _ if self.keep.contains(&b) => out.push(b as char),
+                b' ' if !self.space_as_percent => out.push('+'),
+                _ => {
+                    let _ = write!(out, "%{:02X}", b);
+                }
+            }
+        }
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for Params
+where
+    K: Into<String>,
+    V: fmt::Display,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Params {
+        iter.into_iter()
+            .fold(Params::new(), |params, (key, value)| {
+                params.append(key, value)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_pairs() {
+        let params: Params = [("b", "2"), ("a", "1"), ("b", "3")].into_iter().collect();
+        assert_eq!(params.encode(), "b=2&a=1&b=3");
+
+        let params = Params::new()
+            .append("list", "x,y")
+            .append("t", "1+1=2")
+            .append_all("k[]", ["é"]);
+        assert_eq!(params.encode(), "list=x%2Cy&t=1%2B1%3D2&k%5B%5D=%C3%A9");
+        assert_eq!(
+            params.keep(",[]").encode(),
+            "list=x,y&t=1%2B1%3D2&k[]=%C3%A9"
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! HTTP/2 server push
+//!
+//! When push is enabled with [`Http2ConfigBuilder::enable_push`], a server can
+//! push responses to requests it expects the client to make, along with the
+//! response to a request. [`Response::pushed`] returns a stream of these
+//! promised requests and the pushed responses to them.
+//!
+//! [`Http2ConfigBuilder::enable_push`]: crate::http2::Http2ConfigBuilder::enable_push
+//! [`Response::pushed`]: crate::Response::pushed
+
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use futures_util::Stream;
+use url::Url;
+
+use super::body::{ReadTimeout, boxed};
+use super::decoder::Accepts;
+use super::response::Response;
+use crate::core::ext::PushPromises;
+
+/// A stream of the requests a server promised, and the responses it pushed
+/// for them.
+///
+/// Created by [`Response::pushed`](crate::Response::pushed).
+pub struct PushStream {
+    promises: Option<Pushed>,
+}
+
+/// The push promises of a response, with what is needed to read the pushed
+/// responses like the response itself.
+#[derive(Clone)]
+pub(super) struct Pushed {
+    promises: PushPromises,
+    accepts: Accepts,
+    read_timeout: Option<ReadTimeout>,
+    max_size: Option<u64>,
+}
+
+impl Pushed {
+    pub(super) fn new(
+        promises: PushPromises,
+        accepts: Accepts,
+        read_timeout: Option<ReadTimeout>,
+        max_size: Option<u64>,
+    ) -> Pushed {
+        Pushed {
+            promises,
+            accepts,
+            read_timeout,
+            max_size,
+        }
+    }
+}
+
+impl PushStream {
+    pub(super) fn new(promises: Option<Pushed>) -> PushStream {
+        PushStream { promises }
+    }
+}
+
+impl Stream for PushStream {
+    type Item = crate::Result<(http::Request<()>, Response)>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let Some(ref pushed) = self.promises else {
+            return Poll::Ready(None);
+        };
+
+        let (req, res) = match ready!(pushed.promises.poll_next(cx)) {
+            Some(Ok(promise)) => promise,
+            Some(Err(err)) => return Poll::Ready(Some(Err(crate::error::request(err)))),
+            None => {
+                self.promises = None;
+                return Poll::Ready(None);
+            }
+        };
+
+        let url = match Url::parse(&req.uri().to_string()) {
+            Ok(url) => url,
+            Err(err) => return Poll::Ready(Some(Err(crate::error::request(err)))),
+        };
+        let res = Response::new(
+            res.map(boxed),
+            url,
+            Vec::new(),
+            pushed.accepts,
+            None,
+            pushed.read_timeout.clone(),
+            pushed.max_size,
+        );
+        Poll::Ready(Some(Ok((req, res))))
+    }
+}
+
+impl fmt::Debug for PushStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PushStream").finish()
+    }
+}
@@ -0,0 +1,263 @@
+//! Byte ranges
+//!
+//! [`RequestBuilder::range`] and [`RequestBuilder::ranges`] ask for parts of a
+//! resource, and [`Response::byte_ranges`] splits the `206 Partial Content`
+//! response, including a `multipart/byteranges` one, into its [`BytePart`]s.
+//!
+//! [`RequestBuilder::range`]: crate::RequestBuilder::range
+//! [`RequestBuilder::ranges`]: crate::RequestBuilder::ranges
+//! [`Response::byte_ranges`]: crate::Response::byte_ranges
+
+use std::fmt::Write;
+use std::ops::{Bound, Range, RangeBounds};
+
+use bytes::Bytes;
+use http::StatusCode;
+use http::header::{CONTENT_RANGE, CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue};
+
+use crate::error;
+
+/// A part of a resource, from a `206 Partial Content` response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BytePart {
+    range: Range<u64>,
+    complete_length: Option<u64>,
+    content_type: Option<HeaderValue>,
+    bytes: Bytes,
+}
+
+impl BytePart {
+    /// The offsets of the part in the resource, with an exclusive end.
+    pub fn range(&self) -> Range<u64> {
+        self.range.clone()
+    }
+
+    /// The length of the whole resource, if the server knows it.
+    pub fn complete_length(&self) -> Option<u64> {
+        self.complete_length
+    }
+
+    /// The content type of the part, if the server sent one.
+    pub fn content_type(&self) -> Option<&HeaderValue> {
+        self.content_type.as_ref()
+    }
+
+    /// The bytes of the part.
+    pub fn bytes(&self) -> &Bytes {
+        &self.bytes
+    }
+
+    /// Consume the part, returning its bytes.
+    pub fn into_bytes(self) -> Bytes {
+        self.bytes
+    }
+}
+
+/// Format a `Range` header asking for `ranges`.
+pub(crate) fn header_value<I, R>(ranges: I) -> crate::Result<HeaderValue>
+where
+    I: IntoIterator<Item = R>,
+    R: RangeBounds<u64>,
+{
+    let mut value = String::from("bytes=");
+    for (i, range) in ranges.into_iter().enumerate() {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.checked_add(1).ok_or_else(empty_range)?,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => Some(end),
+            Bound::Excluded(&end) => Some(end.checked_sub(1).ok_or_else(empty_range)?),
+            Bound::Unbounded => None,
+        };
+
+        if i > 0 {
+            value.push(',');
+        }
+        let _ = match end {
+            Some(end) if end < start => return Err(empty_range()),
+            Some(end) => write!(value, "{start}-{end}"),
+            None => write!(value, "{start}-"),
+        };
+    }
+
+    if value.len() == "bytes=".len() {
+        return Err(error::builder("no byte ranges requested"));
+    }
+    HeaderValue::from_str(&value).map_err(error::builder)
+}
+
+fn empty_range() -> crate::Error {
+    error::builder("empty byte range")
+}
+
+/// Parse a `Content-Range` header value, like `bytes 0-499/1234`.
+fn parse_content_range(value: &[u8]) -> Option<(Range<u64>, Option<u64>)> {
+    let value = std::str::from_utf8(value).ok()?.trim();
+    let (unit, rest) = value.split_once(' ')?;
+    if !unit.eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+    let (range, complete) = rest.trim().split_once('/')?;
+    let (start, end) = range.split_once('-')?;
+    let start = start.parse::<u64>().ok()?;
+    let end = end.parse::<u64>().ok()?.checked_add(1)?;
+    let complete = match complete {
+        "*" => None,
+        complete => Some(complete.parse().ok()?),
+    };
+    (start < end).then_some((start..end, complete))
+}
+
+/// Split the body of a response into the parts it holds.
+pub(super) fn parts(
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: Bytes,
+) -> crate::Result<Vec<BytePart>> {
+    let content_type = headers.get(CONTENT_TYPE);
+
+    if status == StatusCode::OK {
+        let len = body.len() as u64;
+        return Ok(vec![BytePart {
+            range: 0..len,
+            complete_length: Some(len),
+            content_type: content_type.cloned(),
+            bytes: body,
+        }]);
+    }
+
+    if status != StatusCode::PARTIAL_CONTENT {
+        return Err(error::decode(format!(
+            "expected a 206 Partial Content response, got {status}"
+        )));
+    }
+
+    if let Some(content_range) = headers.get(CONTENT_RANGE) {
+        return Ok(vec![part(content_range, content_type, body)?]);
+    }
+
+    let boundary = content_type
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<mime::Mime>().ok())
+        .filter(|mime| mime.type_() == mime::MULTIPART && mime.subtype() == "byteranges")
+        .and_then(|mime| mime.get_param(mime::BOUNDARY).map(|b| b.to_string()))
+        .ok_or_else(|| {
+            error::decode(
+                "206 response has neither a Content-Range nor a multipart/byteranges body",
+            )
+        })?;
+
+    multipart(&body, &boundary)
+}
+
+fn part(
+    content_range: &HeaderValue,
+    content_type: Option<&HeaderValue>,
+    bytes: Bytes,
+) -> crate::Result<BytePart> {
+    let (range, complete_length) = parse_content_range(content_range.as_bytes())
+        .ok_or_else(|| error::decode("invalid Content-Range header"))?;
+    if range.end - range.start != bytes.len() as u64 {
+        return Err(error::decode(
+            "byte range length doesn't match its Content-Range",
+        ));
+    }
+    Ok(BytePart {
+        range,
+        complete_length,
+        content_type: content_type.cloned(),
+        bytes,
+    })
+}
+
+/// Parse a `multipart/byteranges` body.
+fn multipart(body: &Bytes, boundary: &str) -> crate::Result<Vec<BytePart>> {
+    let malformed = || error::decode("malformed multipart/byteranges body");
+    let delimiter = format!("--{boundary}");
+    let delimiter = delimiter.as_bytes();
+
+    let mut pos = find(body, delimiter, 0).ok_or_else(malformed)? + delimiter.len();
+    let mut parts = Vec::new();
+    loop {
+        // the last delimiter is followed by `--`
+        if body[pos..].starts_with(b"--") {
+            return Ok(parts);
+        }
+        pos = find(body, b"\r\n", pos).ok_or_else(malformed)? + 2;
+
+        let mut headers = HeaderMap::new();
+        loop {
+            let end = find(body, b"\r\n", pos).ok_or_else(malformed)?;
+            let line = &body[pos..end];
+            pos = end + 2;
+            if line.is_empty() {
+                break;
+            }
+            let colon = line.iter().position(|&b| b == b':').ok_or_else(malformed)?;
+            let name = HeaderName::from_bytes(&line[..colon]).map_err(|_| malformed())?;
+            let value =
+                HeaderValue::from_bytes(line[colon + 1..].trim_ascii()).map_err(|_| malformed())?;
+            headers.append(name, value);
+        }
+
+        let end = find(body, &[b"\r\n", delimiter].concat(), pos).ok_or_else(malformed)?;
+        let content_range = headers.get(CONTENT_RANGE).ok_or_else(malformed)?;
+        parts.push(part(
+            content_range,
+            headers.get(CONTENT_TYPE),
+            body.slice(pos..end),
+        )?);
+        pos = end + 2 + delimiter.len();
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack[from..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|i| from + i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_range_header() {
+        assert_eq!(header_value([0..1024]).unwrap(), "bytes=0-1023");
+        assert_eq!(header_value([512..]).unwrap(), "bytes=512-");
+        assert_eq!(header_value([..=99]).unwrap(), "bytes=0-99");
+        assert_eq!(header_value([0..10, 20..30]).unwrap(), "bytes=0-9,20-29");
+        assert!(header_value([5..5]).unwrap_err().is_builder());
+        assert!(header_value(Vec::<Range<u64>>::new()).is_err());
+    }
+
+    #[test]
+    fn parses_content_range() {
+        assert_eq!(
+            parse_content_range(b"bytes 0-499/1234"),
+            Some((0..500, Some(1234)))
+        );
+        assert_eq!(parse_content_range(b"bytes 10-10/*"), Some((10..11, None)));
+        assert_eq!(parse_content_range(b"bytes */1234"), None);
+        assert_eq!(parse_content_range(b"items 0-1/2"), None);
+    }
+
+    #[test]
+    fn parses_multipart() {
+        let body = Bytes::from_static(
+            b"preamble\r\n--SEP\r\nContent-Type: text/plain\r\nContent-Range: bytes 0-4/20\r\n\r\nhello\r\n--SEP\r\nContent-Range: bytes 15-19/20\r\n\r\nworld\r\n--SEP--\r\n",
+        );
+        let parts = multipart(&body, "SEP").unwrap();
+
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].range(), 0..5);
+        assert_eq!(parts[0].bytes(), "hello");
+        assert_eq!(parts[0].content_type().unwrap(), "text/plain");
+        assert_eq!(parts[1].range(), 15..20);
+        assert_eq!(parts[1].complete_length(), Some(20));
+        assert_eq!(parts[1].bytes(), "world");
+    }
+}
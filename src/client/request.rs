@@ -3,21 +3,53 @@ use std::convert::TryFrom;
 use std::fmt;
 use std::future::Future;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
-use std::time::Duration;
+use std::ops::RangeBounds;
+use std::time::{Duration, Instant};
 
+use bytes::Bytes;
 use http::{Extensions, Request as HttpRequest, Version, request::Parts};
 use serde::Serialize;
 
-use super::body::Body;
+#[cfg(any(
+    feature = "gzip",
+    feature = "brotli",
+    feature = "zstd",
+    feature = "deflate"
+))]
+use super::body::Encoding;
+use super::body::{Body, Progress};
 use super::client::{Client, Pending};
+use super::digest::DigestAuth;
 #[cfg(feature = "multipart")]
 use super::multipart;
+use super::params::Params;
 use super::response::Response;
-use crate::config::{RequestConfig, RequestTimeout};
+use super::sigv4::{AwsCredentials, AwsSigner};
+use super::sse::EventSource;
+#[cfg(any(
+    feature = "gzip",
+    feature = "brotli",
+    feature = "zstd",
+    feature = "deflate"
+))]
+use crate::config::RequestCompression;
+#[cfg(feature = "cookies")]
+use crate::config::RequestCookieJar;
+#[cfg(feature = "ntlm")]
+use crate::config::RequestNtlmAuth;
+use crate::config::{
+    DownloadProgress, RequestAwsSigner, RequestConfig, RequestDeadline, RequestDigestAuth,
+    RequestExpectContinue, RequestMaxResponseSize, RequestReadTimeout, RequestTimeout,
+    UploadProgress,
+};
 use crate::core::client::{NetworkScheme, NetworkSchemeBuilder};
-use crate::header::{CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue};
+use crate::core::ext::{HeaderCaseMap, OnInformational};
+use crate::header::{CONNECTION, CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue};
 use crate::proxy::IntoProxy;
-use crate::{Method, Url, redirect};
+use crate::{Method, StatusCode, Url, redirect};
+
+/// The `Priority` header of [RFC 9218](https://www.rfc-editor.org/rfc/rfc9218.html).
+const PRIORITY: HeaderName = HeaderName::from_static("priority");
 
 /// A request which can be executed with `Client::execute()`.
 pub struct Request {
@@ -164,13 +196,25 @@ impl Request {
     /// Get the read timeout.
     #[inline]
     pub fn read_timeout(&self) -> Option<&Duration> {
-        RequestConfig::<RequestTimeout>::get(&self.extensions)
+        RequestConfig::<RequestReadTimeout>::get(&self.extensions)
     }
 
     /// Get a mutable reference to the read timeout.
     #[inline]
     pub fn read_timeout_mut(&mut self) -> &mut Option<Duration> {
-        RequestConfig::<RequestTimeout>::get_mut(&mut self.extensions)
+        RequestConfig::<RequestReadTimeout>::get_mut(&mut self.extensions)
+    }
+
+    /// Get the deadline.
+    #[inline]
+    pub fn deadline(&self) -> Option<&Instant> {
+        RequestConfig::<RequestDeadline>::get(&self.extensions)
+    }
+
+    /// Get a mutable reference to the deadline.
+    #[inline]
+    pub fn deadline_mut(&mut self) -> &mut Option<Instant> {
+        RequestConfig::<RequestDeadline>::get_mut(&mut self.extensions)
     }
 
     /// Get the http version.
@@ -187,19 +231,23 @@ impl Request {
 
     /// Get the extensions.
     #[inline]
-    pub(crate) fn extensions(&self) -> &Extensions {
+    pub fn extensions(&self) -> &Extensions {
         &self.extensions
     }
 
     /// Get a mutable reference to the extensions.
+    ///
+    /// Extensions carry per-request values, like a correlation ID, to
+    /// middleware and redirect policies, and are copied to the response.
     #[inline]
-    pub(crate) fn extensions_mut(&mut self) -> &mut Extensions {
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
         &mut self.extensions
     }
 
     /// Attempt to clone the request.
     ///
-    /// `None` is returned if the request can not be cloned, i.e. if the body is a stream.
+    /// `None` is returned if the request can not be cloned, i.e. if the body is a stream
+    /// that can't be created again, unlike those of [`Body::from_fn`] and [`Body::file`].
     pub fn try_clone(&self) -> Option<Request> {
         let body = match self.body.as_ref() {
             Some(body) => Some(body.try_clone()?),
@@ -379,6 +427,53 @@ impl RequestBuilder {
         self
     }
 
+    /// Set the order and the HTTP/1 casing of the headers.
+    ///
+    /// Headers are written in the given order, like with
+    /// [`headers_order`](Self::headers_order), and HTTP/1 requests spell their
+    /// names exactly as given, e.g. `X-Requested-With`. HTTP/2 always sends header
+    /// names in lowercase.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), rquest::Error> {
+    /// let res = rquest::Client::new()
+    ///     .get("http://httpbin.org/headers")
+    ///     .header("x-requested-with", "XMLHttpRequest")
+    ///     .headers_order_cased(["Host", "X-Requested-With", "User-Agent", "Accept"])
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn headers_order_cased<I, S>(mut self, names: I) -> RequestBuilder
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        if let Ok(ref mut req) = self.request {
+            let mut order = Vec::new();
+            let mut case = HeaderCaseMap::default();
+            for name in names {
+                let name = name.as_ref();
+                match HeaderName::from_bytes(name.as_bytes()) {
+                    Ok(key) => {
+                        case.append(&key, Bytes::copy_from_slice(name.as_bytes()));
+                        order.push(key);
+                    }
+                    Err(e) => {
+                        self.request = Err(crate::error::builder(e));
+                        return self;
+                    }
+                }
+            }
+            *req.headers_order_mut() = Some(order.into());
+            req.extensions_mut().insert(case);
+        }
+        self
+    }
+
     /// Enable HTTP authentication.
     pub fn auth<V>(self, value: V) -> RequestBuilder
     where
@@ -417,6 +512,166 @@ impl RequestBuilder {
         )
     }
 
+    /// Ask for a range of bytes of the resource, with a `Range` header.
+    ///
+    /// `0..1024` asks for the first 1024 bytes, and `1024..` for all bytes
+    /// after them. Use [`Response::byte_ranges`](crate::Response::byte_ranges)
+    /// to read the response.
+    ///
+    /// ```rust
+    /// # use rquest::Error;
+    ///
+    /// # async fn run() -> Result<(), Error> {
+    /// let parts = rquest::Client::new()
+    ///     .get("http://httpbin.org/range/4096")
+    ///     .range(0..1024)
+    ///     .send()
+    ///     .await?
+    ///     .byte_ranges()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// The request fails to build if the range is empty.
+    pub fn range<R: RangeBounds<u64>>(self, range: R) -> RequestBuilder {
+        self.ranges([range])
+    }
+
+    /// Ask for several ranges of bytes of the resource at once.
+    ///
+    /// The server may answer with a `multipart/byteranges` body, holding each
+    /// range it sends as a part. See [`RequestBuilder::range`].
+    pub fn ranges<I, R>(mut self, ranges: I) -> RequestBuilder
+    where
+        I: IntoIterator<Item = R>,
+        R: RangeBounds<u64>,
+    {
+        match super::range::header_value(ranges) {
+            Ok(value) => self.header_operation(crate::header::RANGE, value, false, true, false),
+            Err(err) => {
+                self.request = Err(err);
+                self
+            }
+        }
+    }
+
+    /// Enable HTTP digest authentication.
+    ///
+    /// The request is first sent without credentials. If the server answers
+    /// with `401 Unauthorized` and a `WWW-Authenticate: Digest` challenge, the
+    /// request is sent once more with an `Authorization` header answering it.
+    /// The `MD5` and `SHA-256` algorithms, their `-sess` variants, and the
+    /// `auth` quality of protection are supported.
+    ///
+    /// A request whose body can't be sent twice, such as a stream, is not
+    /// retried, and its `401` response is returned instead.
+    ///
+    /// ```rust
+    /// # use rquest::Error;
+    ///
+    /// # async fn run() -> Result<(), Error> {
+    /// let client = rquest::Client::new();
+    /// let resp = client.get("http://httpbin.org/digest-auth/auth/user/passwd")
+    ///     .digest_auth("user", "passwd")
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn digest_auth<U, P>(mut self, username: U, password: P) -> RequestBuilder
+    where
+        U: fmt::Display,
+        P: fmt::Display,
+    {
+        if let Ok(ref mut req) = self.request {
+            *RequestConfig::<RequestDigestAuth>::get_mut(req.extensions_mut()) =
+                Some(DigestAuth::new(username.to_string(), password.to_string()));
+        }
+        self
+    }
+
+    /// Sign the request with AWS Signature Version 4.
+    ///
+    /// The request is signed as it is sent, after the client's default headers
+    /// are added, so the signature covers its final url, headers and body. The
+    /// `Authorization`, `x-amz-date` and, for temporary credentials,
+    /// `x-amz-security-token` headers are added. A streaming body is not
+    /// hashed, and is sent as `UNSIGNED-PAYLOAD` in the
+    /// `x-amz-content-sha256` header, which S3 accepts.
+    ///
+    /// ```rust
+    /// # use rquest::Error;
+    ///
+    /// # async fn run() -> Result<(), Error> {
+    /// let credentials = rquest::AwsCredentials::new("AKIDEXAMPLE", "secret");
+    /// let resp = rquest::Client::new()
+    ///     .put("https://bucket.s3.us-east-1.amazonaws.com/hello.txt")
+    ///     .body("hello")
+    ///     .sign_aws_v4(credentials, "us-east-1", "s3")
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sign_aws_v4<R, S>(
+        mut self,
+        credentials: AwsCredentials,
+        region: R,
+        service: S,
+    ) -> RequestBuilder
+    where
+        R: Into<String>,
+        S: Into<String>,
+    {
+        if let Ok(ref mut req) = self.request {
+            *RequestConfig::<RequestAwsSigner>::get_mut(req.extensions_mut()) =
+                Some(AwsSigner::new(credentials, region.into(), service.into()));
+        }
+        self
+    }
+
+    /// Enable NTLM authentication.
+    ///
+    /// The request is first sent without credentials. If the server answers
+    /// with `401 Unauthorized` and a `WWW-Authenticate: NTLM` or `Negotiate`
+    /// challenge, the NTLM handshake runs on the same connection: the request
+    /// is sent again with the negotiate message, then once more answering the
+    /// server's challenge with an NTLMv2 response. `Negotiate` is answered with
+    /// NTLM tokens as well, since Kerberos is not supported.
+    ///
+    /// A request whose body can't be sent twice, such as a stream, is not
+    /// retried, and its `401` response is returned instead.
+    ///
+    /// ```rust
+    /// # use rquest::Error;
+    ///
+    /// # async fn run() -> Result<(), Error> {
+    /// let client = rquest::Client::new();
+    /// let resp = client.get("http://intranet.corp.example/")
+    ///     .ntlm_auth("CORP", "user", "passwd")
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `ntlm` feature to be enabled.
+    #[cfg(feature = "ntlm")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ntlm")))]
+    pub fn ntlm_auth(mut self, domain: &str, username: &str, password: &str) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            *RequestConfig::<RequestNtlmAuth>::get_mut(req.extensions_mut()) = Some(
+                crate::ntlm::NtlmCredentials::new(domain, username, password),
+            );
+        }
+        self
+    }
+
     /// Enable HTTP bearer authentication.
     pub fn bearer_auth<T>(self, token: T) -> RequestBuilder
     where
@@ -452,11 +707,45 @@ impl RequestBuilder {
         self
     }
 
+    /// Sets a deadline for the whole request.
+    ///
+    /// Connecting, redirects, retries and their backoff, and reading the response
+    /// body must all finish before `deadline`, or the request fails with a timeout
+    /// error. [`Error::timeout_phase`](crate::Error::timeout_phase) tells which
+    /// phase was in progress. If a [`timeout`](RequestBuilder::timeout) is set as
+    /// well, whichever ends first applies.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # async fn run() -> Result<(), rquest::Error> {
+    /// use std::time::{Duration, Instant};
+    ///
+    /// // the budget left to serve the incoming request
+    /// let deadline = Instant::now() + Duration::from_millis(800);
+    ///
+    /// let res = rquest::Client::new()
+    ///     .get("https://hyper.rs")
+    ///     .deadline(deadline)
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn deadline(mut self, deadline: Instant) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            *req.deadline_mut() = Some(deadline);
+        }
+        self
+    }
+
     /// Enables a read timeout.
     ///
-    /// The read timeout is applied from when the response body starts being read
-    /// until the response body has finished. It affects only this request and
-    /// overrides the read timeout configured using `ClientBuilder::read_timeout()`.
+    /// The read timeout is applied to each read of the response body: if no data
+    /// arrives for this long, e.g. because the server stalls in the middle of
+    /// the body, reading it (including through `Response::bytes_stream()`)
+    /// fails with a timeout error. It affects only this request and overrides
+    /// the read timeout configured using `ClientBuilder::read_timeout()`.
     pub fn read_timeout(mut self, timeout: Duration) -> RequestBuilder {
         if let Ok(ref mut req) = self.request {
             *req.read_timeout_mut() = Some(timeout);
@@ -464,6 +753,192 @@ impl RequestBuilder {
         self
     }
 
+    /// Limit the size of the response body to `bytes`.
+    ///
+    /// It affects only this request and overrides the limit configured using
+    /// [`ClientBuilder::max_response_size`](crate::ClientBuilder::max_response_size),
+    /// which describes what happens to a larger body.
+    pub fn max_response_size(mut self, bytes: u64) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            *RequestConfig::<RequestMaxResponseSize>::get_mut(req.extensions_mut()) = Some(bytes);
+        }
+        self
+    }
+
+    /// Send the request on a new connection, and close it after the response.
+    ///
+    /// This sets the `Connection: close` header, which keeps the request from
+    /// taking an idle connection from the pool, and the connection it opens from
+    /// being reused. Over HTTP/2, the header isn't sent, but the connection is
+    /// still its own. Passing `false` removes the header.
+    ///
+    /// See [`ClientBuilder::pool_disable_for_host`](crate::ClientBuilder::pool_disable_for_host)
+    /// to do this for every request to a host.
+    pub fn close_connection(mut self, close: bool) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            if close {
+                req.headers_mut()
+                    .insert(CONNECTION, HeaderValue::from_static("close"));
+            } else {
+                req.headers_mut().remove(CONNECTION);
+            }
+        }
+        self
+    }
+
+    /// Compress the request body with `encoding` as it is sent.
+    ///
+    /// The `Content-Encoding` header is set, and any `Content-Length` header is
+    /// removed since the compressed length isn't known in advance. The server must
+    /// accept compressed request bodies.
+    ///
+    /// A compressed body is streamed, so it is not resent on redirects or retries.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "gzip")]
+    /// # async fn run() -> Result<(), rquest::Error> {
+    /// let res = rquest::Client::new()
+    ///     .post("http://httpbin.org/post")
+    ///     .body("a large payload")
+    ///     .compress_body(rquest::Encoding::Gzip)
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Optional
+    ///
+    /// This requires at least one of the `gzip`, `brotli`, `zstd` or `deflate`
+    /// features to be enabled.
+    #[cfg(any(
+        feature = "gzip",
+        feature = "brotli",
+        feature = "zstd",
+        feature = "deflate"
+    ))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            feature = "gzip",
+            feature = "brotli",
+            feature = "zstd",
+            feature = "deflate"
+        )))
+    )]
+    pub fn compress_body(mut self, encoding: Encoding) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            *RequestConfig::<RequestCompression>::get_mut(req.extensions_mut()) = Some(encoding);
+        }
+        self
+    }
+
+    /// Calls `f` as the request body is sent.
+    ///
+    /// `f` receives the number of body bytes sent so far and the length of the
+    /// body, if known. If the request is resent, e.g. after a redirect or a retry,
+    /// the count starts again from zero.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), rquest::Error> {
+    /// let res = rquest::Client::new()
+    ///     .post("http://httpbin.org/post")
+    ///     .body(vec![0u8; 1024 * 1024])
+    ///     .on_upload_progress(|sent, total| {
+    ///         println!("sent {} of {:?} bytes", sent, total);
+    ///     })
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_upload_progress<F>(mut self, f: F) -> RequestBuilder
+    where
+        F: Fn(u64, Option<u64>) + Send + Sync + 'static,
+    {
+        if let Ok(ref mut req) = self.request {
+            *RequestConfig::<UploadProgress>::get_mut(req.extensions_mut()) =
+                Some(Progress::new(f));
+        }
+        self
+    }
+
+    /// Calls `f` as the response body is received.
+    ///
+    /// `f` receives the number of body bytes received so far and the value of the
+    /// `Content-Length` header, if any. Bytes are counted as they arrive, before
+    /// any decompression, so the count can be compared with the `Content-Length`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), rquest::Error> {
+    /// let bytes = rquest::Client::new()
+    ///     .get("http://httpbin.org/bytes/65536")
+    ///     .on_download_progress(|received, total| match total {
+    ///         Some(total) => println!("{}%", received * 100 / total.max(1)),
+    ///         None => println!("{} bytes", received),
+    ///     })
+    ///     .send()
+    ///     .await?
+    ///     .bytes()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_download_progress<F>(mut self, f: F) -> RequestBuilder
+    where
+        F: Fn(u64, Option<u64>) + Send + Sync + 'static,
+    {
+        if let Ok(ref mut req) = self.request {
+            *RequestConfig::<DownloadProgress>::get_mut(req.extensions_mut()) =
+                Some(Progress::new(f));
+        }
+        self
+    }
+
+    /// Calls `f` with the status and headers of each informational (`1xx`)
+    /// response received before the final response, like `103 Early Hints`.
+    ///
+    /// This is only supported over HTTP/1, and `101 Switching Protocols` is
+    /// never passed to `f`. If the request is resent, e.g. after a redirect,
+    /// `f` is called for the informational responses to each attempt.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), rquest::Error> {
+    /// let res = rquest::Client::new()
+    ///     .get("https://example.com/")
+    ///     .on_informational(|status, headers| {
+    ///         if status == rquest::StatusCode::EARLY_HINTS {
+    ///             for link in headers.get_all(rquest::header::LINK) {
+    ///                 println!("preload {:?}", link);
+    ///             }
+    ///         }
+    ///     })
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_informational<F>(mut self, f: F) -> RequestBuilder
+    where
+        F: Fn(StatusCode, &HeaderMap) + Send + Sync + 'static,
+    {
+        if let Ok(ref mut req) = self.request {
+            req.extensions_mut()
+                .insert(OnInformational::new(move |res| {
+                    f(res.status(), res.headers())
+                }));
+        }
+        self
+    }
+
     /// Sends a multipart/form-data body.
     ///
     /// ```
@@ -545,6 +1020,41 @@ impl RequestBuilder {
         self
     }
 
+    /// Modify the query string of the URL, appending `params` exactly as
+    /// they encode.
+    ///
+    /// Unlike [`RequestBuilder::query`], the pairs keep their order and
+    /// duplicate keys, and [`Params::keep`] can leave characters unencoded.
+    ///
+    /// ```rust
+    /// use rquest::Params;
+    ///
+    /// let req = rquest::Client::new()
+    ///     .get("http://httpbin.org/get?page=1")
+    ///     .query_params(
+    ///         &Params::new()
+    ///             .append_all("tag", ["a", "b"])
+    ///             .append("fields", "id,name")
+    ///             .keep(","),
+    ///     )
+    ///     .build()?;
+    /// assert_eq!(req.url().query(), Some("page=1&tag=a&tag=b&fields=id,name"));
+    /// # Ok::<(), rquest::Error>(())
+    /// ```
+    pub fn query_params(mut self, params: &Params) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            if !params.is_empty() {
+                let encoded = params.encode();
+                let query = match req.url().query() {
+                    Some(query) if !query.is_empty() => format!("{query}&{encoded}"),
+                    _ => encoded,
+                };
+                req.url_mut().set_query(Some(&query));
+            }
+        }
+        self
+    }
+
     /// Set HTTP version
     pub fn version(mut self, version: Version) -> RequestBuilder {
         if let Ok(ref mut req) = self.request {
@@ -553,6 +1063,53 @@ impl RequestBuilder {
         self
     }
 
+    /// Set the priority of this request, as defined by [RFC 9218].
+    ///
+    /// `urgency` ranges from `0`, the most urgent, to `7`, and is `3` by default.
+    /// An `incremental` response can be used as it arrives, like a progressive
+    /// image, so the server can interleave it with other responses.
+    ///
+    /// The priority is sent in the `Priority` header, leaving out the default
+    /// parameters as browsers do. The priorities of RFC 7540 that HTTP/2
+    /// requests carry in their `HEADERS` frame are configured by the
+    /// [`Http2Config`](crate::http2::Http2Config) instead.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `urgency` is greater than `7`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn run() -> Result<(), rquest::Error> {
+    /// let req = rquest::Client::new()
+    ///     .get("https://example.com/style.css")
+    ///     .priority(0, false)
+    ///     .build()?;
+    ///
+    /// assert_eq!(req.headers()["priority"], "u=0");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [RFC 9218]: https://www.rfc-editor.org/rfc/rfc9218.html
+    pub fn priority(mut self, urgency: u8, incremental: bool) -> RequestBuilder {
+        if urgency > 7 {
+            if self.request.is_ok() {
+                self.request = Err(crate::error::builder("priority urgency must be at most 7"));
+            }
+            return self;
+        }
+
+        let value = match (urgency, incremental) {
+            (3, false) => "u=3".to_owned(),
+            (3, true) => "i".to_owned(),
+            (urgency, false) => format!("u={urgency}"),
+            (urgency, true) => format!("u={urgency}, i"),
+        };
+        self.header_operation(PRIORITY, value, false, true, false)
+    }
+
     /// Set the redirect policy for this request.
     pub fn redirect(mut self, policy: redirect::Policy) -> RequestBuilder {
         if let Ok(ref mut req) = self.request {
@@ -561,6 +1118,53 @@ impl RequestBuilder {
         self
     }
 
+    /// Set whether this request is sent with `Expect: 100-continue`, so its
+    /// body is only sent once the server answers `100 Continue`.
+    ///
+    /// This overrides the client's
+    /// [`expect_continue_threshold`](crate::ClientBuilder::expect_continue_threshold).
+    /// A request without a body, or sent over HTTP/2, is never sent with it.
+    pub fn expect_continue(mut self, enable: bool) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            *RequestConfig::<RequestExpectContinue>::get_mut(req.extensions_mut()) = Some(enable);
+        }
+        self
+    }
+
+    /// Attach a value of type `T` to this request, replacing any previous
+    /// value of that type.
+    ///
+    /// Extensions aren't sent to the server. They can be read by middleware
+    /// from [`Request::extensions`], by a custom redirect policy from
+    /// [`redirect::Attempt::extensions`], and afterwards from
+    /// [`Response::extensions`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// #[derive(Clone)]
+    /// struct CorrelationId(u64);
+    ///
+    /// # async fn run() -> Result<(), rquest::Error> {
+    /// let res = rquest::Client::new()
+    ///     .get("https://hyper.rs")
+    ///     .extension(CorrelationId(42))
+    ///     .send()
+    ///     .await?;
+    /// assert_eq!(res.extensions().get::<CorrelationId>().map(|id| id.0), Some(42));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn extension<T>(mut self, value: T) -> RequestBuilder
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        if let Ok(ref mut req) = self.request {
+            req.extensions_mut().insert(value);
+        }
+        self
+    }
+
     /// Sets if this request will announce that it accepts compression.
     ///
     /// This value defaults to true. Note that this only lets the browser know that this request supports
@@ -578,8 +1182,52 @@ impl RequestBuilder {
         self
     }
 
+    /// Set the cookie store for this request.
+    ///
+    /// The store replaces the client's cookie store for this request only, and
+    /// for the redirects it follows, while the connection pool and TLS
+    /// configuration are still shared. This allows one client to keep several
+    /// isolated sessions, such as one for each account.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `cookies` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use rquest::cookie::Jar;
+    ///
+    /// # async fn run() -> Result<(), rquest::Error> {
+    /// let client = rquest::Client::new();
+    /// let alice = Arc::new(Jar::default());
+    /// let bob = Arc::new(Jar::default());
+    ///
+    /// client.get("https://example.com/login").cookie_jar(alice.clone()).send().await?;
+    /// client.get("https://example.com/login").cookie_jar(bob.clone()).send().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "cookies")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cookies")))]
+    pub fn cookie_jar<C>(mut self, cookie_store: std::sync::Arc<C>) -> RequestBuilder
+    where
+        C: crate::cookie::AsyncCookieStore + 'static,
+    {
+        if let Ok(ref mut req) = self.request {
+            *RequestConfig::<RequestCookieJar>::get_mut(req.extensions_mut()) =
+                Some(crate::cookie::RequestCookieStore(cookie_store));
+        }
+        self
+    }
+
     /// Set the proxy for this request.
     ///
+    /// The proxy replaces any proxies configured on the `Client` for this request only,
+    /// while the client's cookie store and connection pool are still shared. If the
+    /// proxy does not intercept the request URL, the client's proxies are used.
+    ///
     /// # Examples
     ///
     /// ```
@@ -620,7 +1268,49 @@ impl RequestBuilder {
         self
     }
 
+    /// Send this request directly, ignoring any proxies configured on the `Client`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rquest::Client;
+    ///
+    /// let client = Client::builder().proxy("http://hyper.rs/prox").build()?;
+    ///
+    /// let resp = client
+    ///     .get("https://tls.peet.ws/api/all")
+    ///     .no_proxy()
+    ///     .send()
+    ///     .await?;
+    /// ```
+    pub fn no_proxy(mut self) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            req.network_scheme.no_proxy();
+        }
+        self
+    }
+
     /// Set the local address for this request.
+    ///
+    /// This overrides the client's [`local_address`](crate::ClientBuilder::local_address),
+    /// e.g. to rotate the egress IP of a multi-homed host between requests. Pooled
+    /// connections are only reused by requests bound to the same address.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # async fn run() -> Result<(), rquest::Error> {
+    /// use std::net::IpAddr;
+    ///
+    /// let client = rquest::Client::new();
+    /// let res = client
+    ///     .get("http://httpbin.org/ip")
+    ///     .local_address(IpAddr::from([192, 168, 1, 226]))
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn local_address<V>(mut self, local_address: V) -> RequestBuilder
     where
         V: Into<Option<IpAddr>>,
@@ -644,6 +1334,8 @@ impl RequestBuilder {
     }
 
     /// Set the interface for this request.
+    ///
+    /// This overrides the client's [`interface`](crate::ClientBuilder::interface).
     #[cfg(any(
         target_os = "android",
         target_os = "fuchsia",
@@ -725,6 +1417,24 @@ impl RequestBuilder {
         self
     }
 
+    /// Send a form body of `params`, exactly as they encode.
+    ///
+    /// Unlike [`RequestBuilder::form`], the pairs keep their order and
+    /// duplicate keys, and [`Params::keep`] can leave characters unencoded.
+    /// The `Content-Type: application/x-www-form-urlencoded` header is set
+    /// too, unless the request already has a content type.
+    pub fn form_params(mut self, params: &Params) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            req.headers_mut()
+                .entry(CONTENT_TYPE)
+                .or_insert(HeaderValue::from_static(
+                    "application/x-www-form-urlencoded",
+                ));
+            *req.body_mut() = Some(params.encode().into());
+        }
+        self
+    }
+
     /// Send a JSON body.
     ///
     /// # Optional
@@ -764,10 +1474,45 @@ impl RequestBuilder {
     ///
     /// This is similar to [`RequestBuilder::build()`], but also returns the
     /// embedded `Client`.
+    ///
+    /// The two parts can be reused: pass the request to
+    /// [`Client::execute_ref()`] to send it as many times as needed, without
+    /// building it again.
     pub fn build_split(self) -> (Client, crate::Result<Request>) {
         (self.client, self.request)
     }
 
+    /// Format a curl command that sends the same request, to share a
+    /// reproduction with someone not using Rust.
+    ///
+    /// The command has the method, the headers in the order they would be
+    /// sent, including the client's default headers, the body, and the proxy
+    /// the request would use. Cookies, authentication challenges and the
+    /// TLS fingerprint of the client aren't part of it. Header values are
+    /// included as they are, even sensitive ones.
+    ///
+    /// Returns `None` if the request failed to build, or if its body is a
+    /// stream.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let curl = rquest::Client::new()
+    ///     .post("http://httpbin.org/post")
+    ///     .header("x-trace", "1")
+    ///     .body("hello")
+    ///     .to_curl()
+    ///     .unwrap();
+    /// println!("{curl}");
+    /// ```
+    pub fn to_curl(&self) -> Option<String> {
+        let req = self.request.as_ref().ok()?;
+        if req.body().is_some_and(|body| body.as_bytes().is_none()) {
+            return None;
+        }
+        Some(self.client.curl(req.try_clone()?))
+    }
+
     /// Constructs the Request and sends it to the target URL, returning a
     /// future Response.
     ///
@@ -796,10 +1541,36 @@ impl RequestBuilder {
         }
     }
 
+    /// Send the request as a server-sent events subscription.
+    ///
+    /// Returns an [`EventSource`] that sends the request when first polled, and sends
+    /// it again with a `Last-Event-ID` header whenever the connection is lost. The
+    /// request body must be replayable.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures_util::StreamExt;
+    ///
+    /// # async fn run() -> Result<(), rquest::Error> {
+    /// let mut events = rquest::Client::new()
+    ///     .get("https://example.com/events")
+    ///     .event_source();
+    ///
+    /// while let Some(event) = events.next().await {
+    ///     println!("{}", event?.data());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn event_source(self) -> EventSource {
+        EventSource::new(self.client, self.request)
+    }
+
     /// Attempt to clone the RequestBuilder.
     ///
     /// `None` is returned if the RequestBuilder can not be cloned,
-    /// i.e. if the request body is a stream.
+    /// i.e. if the request body is a stream that can't be created again.
     ///
     /// # Examples
     ///
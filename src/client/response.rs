@@ -1,20 +1,25 @@
 use std::fmt;
 use std::net::SocketAddr;
 use std::pin::Pin;
-use std::time::Duration;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use crate::core::client::connect::HttpInfo;
+use crate::core::rt::Sleep;
 use crate::core::{HeaderMap, StatusCode, Version};
-use bytes::Bytes;
+use bytes::{Buf, BufMut, Bytes};
 use http_body_util::BodyExt;
 #[cfg(feature = "json")]
 use serde::de::DeserializeOwned;
-use tokio::time::Sleep;
 use url::Url;
 
 use super::body::Body;
-use super::body::ResponseBody;
+use super::body::{Progress, ReadTimeout, ResponseBody};
 use super::decoder::{Accepts, Decoder};
+use super::push::{PushStream, Pushed};
+use crate::core::ext::PushPromises;
+use crate::redirect::RedirectRecord;
+use crate::tls::TlsInfo;
 
 #[cfg(feature = "cookies")]
 use crate::cookie;
@@ -30,27 +35,42 @@ pub struct Response {
     // Boxed to save space (11 words to 1 word), and it's not accessed
     // frequently internally.
     url: Box<Url>,
+    history: Vec<RedirectRecord>,
+    trailers: Option<HeaderMap>,
+    // the bytes of the body received so far, before decompression
+    received: Arc<AtomicU64>,
 }
 
 impl Response {
     pub(super) fn new(
         res: crate::core::Response<ResponseBody>,
         url: Url,
+        history: Vec<RedirectRecord>,
         accepts: Accepts,
-        total_timeout: Option<Pin<Box<Sleep>>>,
-        read_timeout: Option<Duration>,
+        total_timeout: Option<Pin<Box<dyn Sleep>>>,
+        read_timeout: Option<ReadTimeout>,
+        max_size: Option<u64>,
     ) -> Response {
         let (mut parts, body) = res.into_parts();
+        let (body, received) = count_received(body);
+        if let Some(promises) = parts.extensions.remove::<PushPromises>() {
+            let pushed = Pushed::new(promises, accepts, read_timeout.clone(), max_size);
+            parts.extensions.insert(pushed);
+        }
         let decoder = Decoder::detect(
             &mut parts.headers,
             super::body::response(body, total_timeout, read_timeout),
             accepts,
-        );
+        )
+        .max_size(max_size);
         let res = crate::core::Response::from_parts(parts, decoder);
 
         Response {
             res,
             url: Box::new(url),
+            history,
+            trailers: None,
+            received,
         }
     }
 
@@ -96,6 +116,42 @@ impl Response {
         Body::size_hint(self.res.body()).exact()
     }
 
+    /// Get the bounds on the length of the rest of the response body.
+    ///
+    /// This returns the lower bound, and the upper bound if there is one,
+    /// like [`Iterator::size_hint`]. As with [`Response::content_length`],
+    /// the bounds are of the body after decompression, and so are unknown
+    /// for a compressed response.
+    pub fn content_length_hint(&self) -> (u64, Option<u64>) {
+        use crate::core::body::Body;
+
+        let hint = Body::size_hint(self.res.body());
+        (hint.lower(), hint.upper())
+    }
+
+    /// Returns `true` if the whole response body is known to have been read.
+    ///
+    /// This is always `true` after a read of the body returned its end, and
+    /// may already be before, like once as many bytes as the
+    /// `Content-Length` were read. A `false` doesn't mean more of the body is
+    /// coming.
+    pub fn is_end_stream(&self) -> bool {
+        use crate::core::body::Body;
+
+        Body::is_end_stream(self.res.body())
+    }
+
+    /// Get the number of bytes of the response body received so far.
+    ///
+    /// These are counted before the body is decompressed, so they can be
+    /// compared with the `Content-Length` header. A body that ended before
+    /// all of them were received fails with an error for which
+    /// [`Error::is_incomplete_body`](crate::Error::is_incomplete_body)
+    /// returns `true`, rather than ending as if it were complete.
+    pub fn bytes_received(&self) -> u64 {
+        self.received.load(Ordering::Relaxed)
+    }
+
     /// Retrieve the cookies contained in the response.
     ///
     /// Note that invalid 'Set-Cookie' headers will be ignored.
@@ -118,6 +174,16 @@ impl Response {
         &self.url
     }
 
+    /// Get the redirect hops that were followed to get this `Response`.
+    ///
+    /// Each record holds the URL, status code and headers of an intermediate
+    /// redirect response, in the order they were followed. The slice is empty
+    /// if no redirect was followed.
+    #[inline]
+    pub fn redirect_history(&self) -> &[RedirectRecord] {
+        &self.history
+    }
+
     /// Get the remote address used to get this `Response`.
     pub fn remote_addr(&self) -> Option<SocketAddr> {
         self.res
@@ -126,7 +192,18 @@ impl Response {
             .map(|info| info.remote_addr())
     }
 
+    /// Get the TLS information of the connection used to get this `Response`.
+    ///
+    /// This is only available if [`ClientBuilder::tls_info`](crate::ClientBuilder::tls_info)
+    /// was enabled, and the response came over TLS.
+    pub fn tls_info(&self) -> Option<&TlsInfo> {
+        self.res.extensions().get::<TlsInfo>()
+    }
+
     /// Returns a reference to the associated extensions.
+    ///
+    /// This includes the extensions of the request, such as those set with
+    /// [`RequestBuilder::extension`](crate::RequestBuilder::extension).
     pub fn extensions(&self) -> &http::Extensions {
         self.res.extensions()
     }
@@ -187,7 +264,9 @@ impl Response {
     /// and with malformed sequences replaced with the
     /// [`char::REPLACEMENT_CHARACTER`].
     /// You can provide a default encoding for decoding the raw message, while the
-    /// `charset` parameter of `Content-Type` header is still prioritized. For more information
+    /// `charset` parameter of `Content-Type` header is still prioritized. Without
+    /// that parameter, an HTML body is checked for a `<meta charset>` tag in its
+    /// first 1024 bytes before the default is used. For more information
     /// about the possible encoding name, please go to [`encoding_rs`] docs.
     ///
     /// Note that the BOM is stripped from the returned String.
@@ -216,21 +295,123 @@ impl Response {
     #[cfg(feature = "charset")]
     #[cfg_attr(docsrs, doc(cfg(feature = "charset")))]
     pub async fn text_with_charset(self, default_encoding: &str) -> crate::Result<String> {
+        let (label, html) = self.charset();
+        let full = self.bytes().await?;
+
+        let encoding = label
+            .or_else(|| html.then(|| meta_charset(&full)).flatten())
+            .or_else(|| Encoding::for_label(default_encoding.as_bytes()))
+            .unwrap_or(UTF_8);
+
+        let (text, _, _) = encoding.decode(&full);
+        Ok(text.into_owned())
+    }
+
+    /// Get the full response text, guessing its encoding from the body.
+    ///
+    /// This is for sites that label their text with the wrong charset, or
+    /// none at all, as legacy sites serving GBK or Shift_JIS often do. The
+    /// encoding is guessed from the bytes of the body and the top-level
+    /// domain of the URL. Only when the guess is uncertain are the `charset`
+    /// of the `Content-Type` header and a `<meta charset>` tag used. A BOM
+    /// always wins, and is stripped from the returned String.
+    ///
+    /// Malformed sequences are replaced with the [`char::REPLACEMENT_CHARACTER`].
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `charset-detection` feature enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let content = rquest::Client::new()
+    ///     .get("http://legacy.example.jp/")
+    ///     .send()
+    ///     .await?
+    ///     .text_with_charset_detection()
+    ///     .await?;
+    ///
+    /// println!("text: {content:?}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "charset-detection")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "charset-detection")))]
+    pub async fn text_with_charset_detection(self) -> crate::Result<String> {
+        let (label, html) = self.charset();
+        let tld = self
+            .url()
+            .domain()
+            .and_then(|domain| domain.rsplit('.').next())
+            .map(str::to_ascii_lowercase);
+        let full = self.bytes().await?;
+
+        let mut detector = chardetng::EncodingDetector::new();
+        detector.feed(&full, true);
+        let (guess, confident) = detector.guess_assess(tld.as_deref().map(str::as_bytes), true);
+        let encoding = if confident {
+            guess
+        } else {
+            label
+                .or_else(|| html.then(|| meta_charset(&full)).flatten())
+                .unwrap_or(guess)
+        };
+
+        let (text, _, _) = encoding.decode(&full);
+        Ok(text.into_owned())
+    }
+
+    /// The encoding named by the `charset` of the `Content-Type` header, and
+    /// whether the body may be HTML.
+    #[cfg(feature = "charset")]
+    fn charset(&self) -> (Option<&'static Encoding>, bool) {
         let content_type = self
             .headers()
             .get(crate::header::CONTENT_TYPE)
             .and_then(|value| value.to_str().ok())
             .and_then(|value| value.parse::<Mime>().ok());
-        let encoding_name = content_type
+        let label = content_type
             .as_ref()
-            .and_then(|mime| mime.get_param("charset").map(|charset| charset.as_str()))
-            .unwrap_or(default_encoding);
-        let encoding = Encoding::for_label(encoding_name.as_bytes()).unwrap_or(UTF_8);
-
-        let full = self.bytes().await?;
+            .and_then(|mime| mime.get_param("charset"))
+            .and_then(|charset| Encoding::for_label(charset.as_str().as_bytes()));
+        let html = content_type
+            .as_ref()
+            .is_none_or(|mime| mime.subtype() == mime::HTML || mime.subtype() == "xhtml");
+        (label, html)
+    }
 
-        let (text, _, _) = encoding.decode(&full);
-        Ok(text.into_owned())
+    /// Parse the response body as an HTML document.
+    ///
+    /// The body is decoded like [`Response::text`].
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `html` feature enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn run() -> Result<(), rquest::Error> {
+    /// let doc = rquest::Client::new()
+    ///     .get("https://www.rust-lang.org")
+    ///     .send()
+    ///     .await?
+    ///     .html()
+    ///     .await?;
+    ///
+    /// for link in doc.select("nav a")? {
+    ///     println!("{:?}: {}", link.attr("href"), link.text());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "html")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "html")))]
+    pub async fn html(self) -> crate::Result<super::html::Html> {
+        let text = self.text().await?;
+        Ok(super::html::Html::parse(&text))
     }
 
     /// Try to deserialize the response body as JSON.
@@ -275,13 +456,17 @@ impl Response {
     /// or it cannot be properly deserialized to target type `T`. For more
     /// details please see [`serde_json::from_reader`].
     ///
+    /// The error names the path of the value that failed to deserialize, see
+    /// [`Error::json_path`](crate::Error::json_path), and includes the first
+    /// bytes of the body.
+    ///
     /// [`serde_json::from_reader`]: https://docs.serde.rs/serde_json/fn.from_reader.html
     #[cfg(feature = "json")]
     #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
     pub async fn json<T: DeserializeOwned>(self) -> crate::Result<T> {
         let full = self.bytes().await?;
 
-        serde_json::from_slice(&full).map_err(crate::error::decode)
+        from_json(&full)
     }
 
     /// Get the full response body as `Bytes`.
@@ -332,8 +517,13 @@ impl Response {
         loop {
             if let Some(res) = self.res.body_mut().frame().await {
                 let frame = res?;
-                if let Ok(buf) = frame.into_data() {
-                    return Ok(Some(buf));
+                match frame.into_data() {
+                    Ok(buf) => return Ok(Some(buf)),
+                    Err(frame) => {
+                        if let Ok(trailers) = frame.into_trailers() {
+                            self.trailers = Some(trailers);
+                        }
+                    }
                 }
                 // else continue
             } else {
@@ -342,6 +532,84 @@ impl Response {
         }
     }
 
+    /// Copy the next bytes of the response body into `buf`, returning how
+    /// many were copied.
+    ///
+    /// At most as many bytes as `buf` has room for are copied; the rest of
+    /// the chunk received is kept, and returned first by the next read of
+    /// the body. Reading into a buffer that is reused, like a `BytesMut`
+    /// that is cleared between reads, avoids holding a `Bytes` for every
+    /// chunk.
+    ///
+    /// When the response body has been exhausted, or `buf` has no room
+    /// left, this will return `0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut res = rquest::Client::new().get("https://hyper.rs").send().await?;
+    /// let mut buf = bytes::BytesMut::with_capacity(64 * 1024);
+    ///
+    /// loop {
+    ///     buf.clear();
+    ///     if res.read_into(&mut buf).await? == 0 {
+    ///         break;
+    ///     }
+    ///     println!("Read: {}", buf.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_into<B: BufMut>(&mut self, buf: &mut B) -> crate::Result<usize> {
+        if !buf.has_remaining_mut() {
+            return Ok(0);
+        }
+
+        let mut chunk = loop {
+            match self.chunk().await? {
+                Some(chunk) if chunk.is_empty() => continue,
+                Some(chunk) => break chunk,
+                None => return Ok(0),
+            }
+        };
+
+        let n = chunk.len().min(buf.remaining_mut());
+        buf.put_slice(&chunk[..n]);
+        chunk.advance(n);
+        if !chunk.is_empty() {
+            self.res.body_mut().unread(chunk);
+        }
+        Ok(n)
+    }
+
+    /// Get the trailers of the response, sent after its body.
+    ///
+    /// Any of the body not yet read with [`Response::chunk`] is discarded.
+    /// When the server sent no trailers, this will return `None`. Over
+    /// HTTP/1.1, servers usually only send trailers to requests with a
+    /// `TE: trailers` header.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut res = rquest::Client::new().get("https://hyper.rs").send().await?;
+    ///
+    /// while let Some(chunk) = res.chunk().await? {
+    ///     println!("Chunk: {chunk:?}");
+    /// }
+    /// if let Some(trailers) = res.trailers().await? {
+    ///     println!("Trailers: {trailers:?}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn trailers(&mut self) -> crate::Result<Option<HeaderMap>> {
+        while self.chunk().await?.is_some() {}
+        Ok(self.trailers.take())
+    }
+
     /// Convert the response into a `Stream` of `Bytes` from the body.
     ///
     /// # Example
@@ -372,6 +640,235 @@ impl Response {
         super::body::DataStream(self.res.into_body())
     }
 
+    /// Convert the response into a `Stream` of `Bytes` from the body, copied
+    /// into chunks of `capacity` bytes.
+    ///
+    /// The chunks received are gathered into a buffer of `capacity` bytes,
+    /// and each item is a part of that buffer, of at most `capacity` bytes.
+    /// An item is yielded early when no more of the body has arrived yet.
+    /// Once every item yielded from a buffer has been dropped, the buffer is
+    /// reused for the next ones, so a consumer that doesn't hold on to the
+    /// items reads the whole body with a single allocation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures_util::StreamExt;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut stream = rquest::Client::new()
+    ///     .get("http://httpbin.org/bytes/1048576")
+    ///     .send()
+    ///     .await?
+    ///     .bytes_stream_with_capacity(64 * 1024);
+    ///
+    /// while let Some(item) = stream.next().await {
+    ///     println!("Chunk: {}", item?.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `capacity` is zero.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `stream` feature to be enabled.
+    #[cfg(feature = "stream")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    pub fn bytes_stream_with_capacity(
+        self,
+        capacity: usize,
+    ) -> impl futures_core::Stream<Item = crate::Result<Bytes>> {
+        assert!(capacity > 0, "capacity must be greater than zero");
+        Chunked {
+            body: super::body::DataStream(self.res.into_body()),
+            buf: bytes::BytesMut::with_capacity(capacity),
+            capacity,
+            done: false,
+        }
+    }
+
+    /// Convert the response into a `Stream` of the text of the body, decoded
+    /// as each chunk arrives.
+    ///
+    /// The body is decoded like [`Response::text`], except that an HTML body
+    /// without a `charset` isn't checked for a `<meta charset>` tag. A
+    /// character whose bytes are split between chunks is held back until
+    /// the rest of it has arrived, so it's never replaced. Chunks that hold
+    /// no complete character yield nothing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures_util::StreamExt;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut stream = rquest::Client::new()
+    ///     .get("http://httpbin.org/stream/20")
+    ///     .send()
+    ///     .await?
+    ///     .text_stream();
+    ///
+    /// while let Some(text) = stream.next().await {
+    ///     print!("{}", text?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `stream` feature to be enabled.
+    #[cfg(feature = "stream")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    pub fn text_stream(self) -> impl futures_core::Stream<Item = crate::Result<String>> {
+        #[cfg(feature = "charset")]
+        let decoder = self
+            .charset()
+            .0
+            .unwrap_or(UTF_8)
+            .new_decoder_with_bom_removal();
+
+        TextStream {
+            body: super::body::DataStream(self.res.into_body()),
+            #[cfg(feature = "charset")]
+            decoder,
+            #[cfg(not(feature = "charset"))]
+            partial: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Convert the response into a `Stream` of values parsed from a body of
+    /// newline-delimited JSON, like `application/x-ndjson` or JSON Lines.
+    ///
+    /// Each line is parsed as soon as it has arrived, so the body is never
+    /// buffered as a whole. Blank lines are skipped. A line that fails to
+    /// parse yields an error, like [`Response::json`] does, and the stream
+    /// goes on with the next line.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures_util::StreamExt;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut rows = rquest::Client::new()
+    ///     .get("https://example.com/export.ndjson")
+    ///     .send()
+    ///     .await?
+    ///     .json_lines::<serde_json::Value>();
+    ///
+    /// while let Some(row) = rows.next().await {
+    ///     println!("{}", row?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `json` and `stream` features to be enabled.
+    #[cfg(all(feature = "json", feature = "stream"))]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "json", feature = "stream"))))]
+    pub fn json_lines<T: DeserializeOwned>(
+        self,
+    ) -> impl futures_core::Stream<Item = crate::Result<T>> {
+        JsonLines {
+            body: super::body::DataStream(self.res.into_body()),
+            buf: bytes::BytesMut::new(),
+            scanned: 0,
+            done: false,
+            _value: std::marker::PhantomData,
+        }
+    }
+
+    /// Get the parts of the resource in the response, after asking for them
+    /// with [`RequestBuilder::range`] or [`RequestBuilder::ranges`].
+    ///
+    /// A `206 Partial Content` response holds one part, described by its
+    /// `Content-Range` header, or several in a `multipart/byteranges` body. A
+    /// server that ignores the `Range` header answers with `200 OK`, whose
+    /// whole body is returned as a single part.
+    ///
+    /// [`RequestBuilder::range`]: crate::RequestBuilder::range
+    /// [`RequestBuilder::ranges`]: crate::RequestBuilder::ranges
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the response has another status, or its parts
+    /// can't be parsed.
+    pub async fn byte_ranges(self) -> crate::Result<Vec<super::range::BytePart>> {
+        let status = self.status();
+        let headers = self.headers().clone();
+        let body = self.bytes().await?;
+        super::range::parts(status, &headers, body)
+    }
+
+    /// Convert the response into a `Stream` of server-sent events.
+    ///
+    /// The body is parsed incrementally as a `text/event-stream`. The stream ends
+    /// when the body does; use [`RequestBuilder::event_source`] to reconnect
+    /// automatically.
+    ///
+    /// [`RequestBuilder::event_source`]: crate::RequestBuilder::event_source
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures_util::StreamExt;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut events = rquest::Client::new()
+    ///     .get("https://example.com/events")
+    ///     .send()
+    ///     .await?
+    ///     .events();
+    ///
+    /// while let Some(event) = events.next().await {
+    ///     println!("{}", event?.data());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn events(self) -> super::sse::SseStream {
+        super::sse::SseStream::new(self)
+    }
+
+    /// Get a stream of the requests the server promised along with this
+    /// response, and the responses it pushed for them.
+    ///
+    /// Pushed responses are only read when push is enabled with
+    /// [`Http2ConfigBuilder::enable_push`], and the response was received
+    /// over HTTP/2. Otherwise, the stream is empty. It ends once this response
+    /// has been received entirely, so the body should be read concurrently.
+    ///
+    /// [`Http2ConfigBuilder::enable_push`]: crate::http2::Http2ConfigBuilder::enable_push
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures_util::StreamExt;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut res = rquest::Client::new().get("https://example.com/").send().await?;
+    /// let mut pushed = res.pushed();
+    /// let body = res.bytes().await?;
+    ///
+    /// while let Some(push) = pushed.next().await {
+    ///     let (req, res) = push?;
+    ///     println!("{} pushed with {}", req.uri(), res.status());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pushed(&mut self) -> PushStream {
+        PushStream::new(self.res.extensions_mut().remove::<Pushed>())
+    }
+
     // util methods
 
     /// Turn a response into an error if the server returned an error.
@@ -453,11 +950,8 @@ impl<T: Into<Body>> From<http::Response<T>> for Response {
 
         let (mut parts, body) = r.into_parts();
         let body: super::body::Body = body.into();
-        let decoder = Decoder::detect(
-            &mut parts.headers,
-            ResponseBody::new(body.map_err(Into::into)),
-            Accepts::none(),
-        );
+        let (body, received) = count_received(ResponseBody::new(body.map_err(Into::into)));
+        let decoder = Decoder::detect(&mut parts.headers, body, Accepts::none());
         let url = parts
             .extensions
             .remove::<ResponseUrl>()
@@ -467,6 +961,9 @@ impl<T: Into<Body>> From<http::Response<T>> for Response {
         Response {
             res,
             url: Box::new(url),
+            history: Vec::new(),
+            trailers: None,
+            received,
         }
     }
 }
@@ -488,6 +985,300 @@ impl From<Response> for Body {
     }
 }
 
+/// Count the bytes of `body` as they are received.
+fn count_received(body: ResponseBody) -> (ResponseBody, Arc<AtomicU64>) {
+    let received = Arc::new(AtomicU64::new(0));
+    let progress = Progress::new({
+        let received = received.clone();
+        move |transferred, _| received.store(transferred, Ordering::Relaxed)
+    });
+    let body = super::body::boxed(super::body::progress_body(body, progress, None));
+    (body, received)
+}
+
+/// Deserialize a JSON document, with the path of the value that failed in the
+/// error.
+#[cfg(feature = "json")]
+pub(crate) fn from_json<T: DeserializeOwned>(bytes: &[u8]) -> crate::Result<T> {
+    let de = &mut serde_json::Deserializer::from_slice(bytes);
+    let value = serde_path_to_error::deserialize(&mut *de).map_err(|e| {
+        let path = e.path().to_string();
+        crate::error::decode(crate::error::JsonError::new(path, bytes, e.into_inner()))
+    })?;
+    de.end().map_err(crate::error::decode)?;
+    Ok(value)
+}
+
+/// A stream of the chunks of a body, copied into a reused buffer.
+#[cfg(feature = "stream")]
+struct Chunked {
+    body: super::body::DataStream<Decoder>,
+    buf: bytes::BytesMut,
+    capacity: usize,
+    done: bool,
+}
+
+#[cfg(feature = "stream")]
+impl futures_core::Stream for Chunked {
+    type Item = crate::Result<Bytes>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        use futures_core::Stream;
+        use std::task::Poll;
+
+        loop {
+            if self.buf.len() >= self.capacity {
+                let capacity = self.capacity;
+                return Poll::Ready(Some(Ok(self.buf.split_to(capacity).freeze())));
+            }
+            if self.done {
+                if self.buf.is_empty() {
+                    return Poll::Ready(None);
+                }
+                return Poll::Ready(Some(Ok(self.buf.split().freeze())));
+            }
+
+            match Pin::new(&mut self.body).poll_next(cx) {
+                // reserving takes back the allocation once the items split
+                // from it are dropped, and allocates `capacity` otherwise
+                Poll::Ready(Some(Ok(chunk))) => self.buf.extend_from_slice(&chunk),
+                Poll::Ready(Some(Err(err))) => {
+                    self.done = true;
+                    self.buf.clear();
+                    return Poll::Ready(Some(Err(err)));
+                }
+                Poll::Ready(None) => self.done = true,
+                Poll::Pending if !self.buf.is_empty() => {
+                    return Poll::Ready(Some(Ok(self.buf.split().freeze())));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// A stream of the text of a body, decoded chunk by chunk.
+#[cfg(feature = "stream")]
+struct TextStream {
+    body: super::body::DataStream<Decoder>,
+    #[cfg(feature = "charset")]
+    decoder: encoding_rs::Decoder,
+    // the start of a UTF-8 character whose rest is in the next chunk
+    #[cfg(not(feature = "charset"))]
+    partial: Vec<u8>,
+    done: bool,
+}
+
+#[cfg(feature = "stream")]
+impl TextStream {
+    #[cfg(feature = "charset")]
+    fn decode(&mut self, bytes: &[u8], last: bool) -> String {
+        let len = self
+            .decoder
+            .max_utf8_buffer_length(bytes.len())
+            .expect("chunk length overflows usize");
+        let mut text = String::with_capacity(len);
+        let _ = self.decoder.decode_to_string(bytes, &mut text, last);
+        text
+    }
+
+    #[cfg(not(feature = "charset"))]
+    fn decode(&mut self, bytes: &[u8], last: bool) -> String {
+        self.partial.extend_from_slice(bytes);
+
+        let mut text = String::with_capacity(self.partial.len());
+        let mut rest = &self.partial[..];
+        loop {
+            match std::str::from_utf8(rest) {
+                Ok(valid) => {
+                    text.push_str(valid);
+                    rest = &[];
+                    break;
+                }
+                Err(err) => {
+                    let (valid, after) = rest.split_at(err.valid_up_to());
+                    text.push_str(std::str::from_utf8(valid).expect("valid up to here"));
+                    match err.error_len() {
+                        Some(len) => {
+                            text.push(char::REPLACEMENT_CHARACTER);
+                            rest = &after[len..];
+                        }
+                        // the character may end in the next chunk
+                        None if !last => {
+                            rest = after;
+                            break;
+                        }
+                        None => {
+                            text.push(char::REPLACEMENT_CHARACTER);
+                            rest = &[];
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        let kept = rest.len();
+        let start = self.partial.len() - kept;
+        self.partial.drain(..start);
+        text
+    }
+}
+
+#[cfg(feature = "stream")]
+impl futures_core::Stream for TextStream {
+    type Item = crate::Result<String>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        use futures_core::Stream;
+        use std::task::Poll;
+
+        loop {
+            if self.done {
+                return Poll::Ready(None);
+            }
+
+            let text = match std::task::ready!(Pin::new(&mut self.body).poll_next(cx)) {
+                Some(Ok(chunk)) => self.decode(&chunk, false),
+                Some(Err(err)) => {
+                    self.done = true;
+                    return Poll::Ready(Some(Err(err)));
+                }
+                None => {
+                    self.done = true;
+                    self.decode(&[], true)
+                }
+            };
+            if !text.is_empty() {
+                return Poll::Ready(Some(Ok(text)));
+            }
+        }
+    }
+}
+
+/// A stream of values parsed from the lines of a body.
+#[cfg(all(feature = "json", feature = "stream"))]
+struct JsonLines<T> {
+    body: super::body::DataStream<Decoder>,
+    buf: bytes::BytesMut,
+    // the bytes of `buf` already searched for a newline
+    scanned: usize,
+    done: bool,
+    _value: std::marker::PhantomData<fn() -> T>,
+}
+
+#[cfg(all(feature = "json", feature = "stream"))]
+impl<T: DeserializeOwned> JsonLines<T> {
+    /// Take the next non-blank line, or the rest of the buffer once the body
+    /// has ended.
+    fn next_line(&mut self) -> Option<Bytes> {
+        loop {
+            let line = match self.buf[self.scanned..].iter().position(|&b| b == b'\n') {
+                Some(pos) => {
+                    let line = self.buf.split_to(self.scanned + pos + 1);
+                    self.scanned = 0;
+                    line.freeze()
+                }
+                None if self.done && !self.buf.is_empty() => {
+                    self.scanned = 0;
+                    self.buf.split().freeze()
+                }
+                None => {
+                    self.scanned = self.buf.len();
+                    return None;
+                }
+            };
+
+            if !line.trim_ascii().is_empty() {
+                return Some(line);
+            }
+        }
+    }
+}
+
+#[cfg(all(feature = "json", feature = "stream"))]
+impl<T: DeserializeOwned> futures_core::Stream for JsonLines<T> {
+    type Item = crate::Result<T>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        use futures_core::Stream;
+        use std::task::Poll;
+
+        loop {
+            if let Some(line) = self.next_line() {
+                return Poll::Ready(Some(from_json(line.trim_ascii())));
+            }
+            if self.done {
+                return Poll::Ready(None);
+            }
+
+            match std::task::ready!(Pin::new(&mut self.body).poll_next(cx)) {
+                Some(Ok(chunk)) => self.buf.extend_from_slice(&chunk),
+                Some(Err(err)) => {
+                    self.done = true;
+                    self.buf.clear();
+                    return Poll::Ready(Some(Err(err)));
+                }
+                None => self.done = true,
+            }
+        }
+    }
+}
+
+/// Find the encoding of a `<meta charset>` or `<meta http-equiv>` tag in the
+/// first 1024 bytes of an HTML document.
+#[cfg(feature = "charset")]
+fn meta_charset(body: &[u8]) -> Option<&'static Encoding> {
+    let head = body[..body.len().min(1024)].to_ascii_lowercase();
+    let mut rest = &head[..];
+
+    while let Some(start) = find(rest, b"<meta") {
+        let tag = &rest[start..];
+        let tag = &tag[..find(tag, b">").unwrap_or(tag.len())];
+        rest = &rest[start + tag.len()..];
+
+        let Some(pos) = find(tag, b"charset") else {
+            continue;
+        };
+        let value = tag[pos + b"charset".len()..].trim_ascii_start();
+        let Some(value) = value.strip_prefix(b"=") else {
+            continue;
+        };
+        let value = value.trim_ascii_start();
+        let value = value
+            .strip_prefix(b"\"")
+            .or_else(|| value.strip_prefix(b"'"))
+            .unwrap_or(value);
+        let end = value
+            .iter()
+            .position(|b| matches!(b, b'"' | b'\'' | b';' | b'/') || b.is_ascii_whitespace())
+            .unwrap_or(value.len());
+
+        // UTF-16 can't be declared from inside the document itself
+        if let Some(encoding) = Encoding::for_label(&value[..end]) {
+            return Some(encoding.output_encoding());
+        }
+    }
+
+    None
+}
+
+#[cfg(feature = "charset")]
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
 #[cfg(test)]
 mod tests {
     use super::Response;
@@ -508,4 +1299,28 @@ mod tests {
         assert_eq!(response.status(), 200);
         assert_eq!(*response.url(), url);
     }
+
+    #[cfg(feature = "charset")]
+    #[test]
+    fn sniffs_meta_charset() {
+        use super::meta_charset;
+
+        assert_eq!(
+            meta_charset(b"<html><head><meta charset=\"GBK\">").map(|e| e.name()),
+            Some("GBK")
+        );
+        assert_eq!(
+            meta_charset(
+                b"<meta name=viewport><META HTTP-EQUIV=Content-Type CONTENT='text/html; charset=Shift_JIS'>"
+            )
+            .map(|e| e.name()),
+            Some("Shift_JIS")
+        );
+        assert_eq!(
+            meta_charset(b"<meta charset=utf-16le>").map(|e| e.name()),
+            Some("UTF-8")
+        );
+        assert_eq!(meta_charset(b"<meta charset=bogus><p>"), None);
+        assert_eq!(meta_charset(b"<p>no meta</p>"), None);
+    }
 }
@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use http::Method;
+
+use super::client::Client;
+use super::request::RequestBuilder;
+use crate::IntoUrl;
+use crate::cookie::Jar;
+use crate::header::HeaderMap;
+use crate::proxy::{IntoProxy, Proxy};
+
+/// An independent browsing session over a shared `Client`.
+///
+/// Each session has its own cookie jar, default headers and, optionally, its
+/// own proxy, while the connection pool, TLS configuration and emulation of the
+/// `Client` are shared by all sessions. Sessions are cheap to create and to
+/// clone, so one client can act as many independent users.
+///
+/// Created with [`Client::session`].
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn run() -> Result<(), rquest::Error> {
+/// let client = rquest::Client::new();
+///
+/// let alice = client.session();
+/// let bob = client.session().proxy("http://bob.proxy:8080")?;
+///
+/// alice.post("https://example.com/login").form(&[("user", "alice")]).send().await?;
+/// bob.post("https://example.com/login").form(&[("user", "bob")]).send().await?;
+///
+/// // each session sends only its own cookies
+/// let inbox = alice.get("https://example.com/inbox").send().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Session {
+    client: Client,
+    jar: Arc<Jar>,
+    headers: HeaderMap,
+    proxy: Option<Proxy>,
+}
+
+impl Session {
+    pub(super) fn new(client: Client) -> Session {
+        Session {
+            client,
+            jar: Arc::new(Jar::default()),
+            headers: HeaderMap::new(),
+            proxy: None,
+        }
+    }
+
+    /// Set the default headers for every request of this session.
+    ///
+    /// They are sent in addition to the default headers of the `Client`, and
+    /// replace those with the same name. Headers set on a request replace them
+    /// in turn.
+    pub fn headers(mut self, headers: HeaderMap) -> Session {
+        crate::util::replace_headers(&mut self.headers, headers);
+        self
+    }
+
+    /// Set the proxy for every request of this session.
+    ///
+    /// Like [`RequestBuilder::proxy`], the client's proxies are used for the
+    /// urls the proxy does not intercept.
+    pub fn proxy<P: IntoProxy>(mut self, proxy: P) -> crate::Result<Session> {
+        self.proxy = Some(proxy.into_proxy()?);
+        Ok(self)
+    }
+
+    /// Use `jar` as the cookie jar of this session, for instance to resume a
+    /// saved session.
+    pub fn cookie_jar(mut self, jar: Arc<Jar>) -> Session {
+        self.jar = jar;
+        self
+    }
+
+    /// Get the cookie jar of this session.
+    pub fn jar(&self) -> &Arc<Jar> {
+        &self.jar
+    }
+
+    /// Get the `Client` this session sends its requests with.
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Convenience method to make a `GET` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever the supplied `Url` cannot be parsed.
+    pub fn get<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::GET, url)
+    }
+
+    /// Convenience method to make a `POST` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever the supplied `Url` cannot be parsed.
+    pub fn post<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::POST, url)
+    }
+
+    /// Convenience method to make a `PUT` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever the supplied `Url` cannot be parsed.
+    pub fn put<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::PUT, url)
+    }
+
+    /// Convenience method to make a `PATCH` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever the supplied `Url` cannot be parsed.
+    pub fn patch<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::PATCH, url)
+    }
+
+    /// Convenience method to make a `DELETE` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever the supplied `Url` cannot be parsed.
+    pub fn delete<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::DELETE, url)
+    }
+
+    /// Convenience method to make a `HEAD` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever the supplied `Url` cannot be parsed.
+    pub fn head<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::HEAD, url)
+    }
+
+    /// Start building a `Request` of this session with the `Method` and `Url`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever the supplied `Url` cannot be parsed.
+    pub fn request<U: IntoUrl>(&self, method: Method, url: U) -> RequestBuilder {
+        let mut builder = self
+            .client
+            .request(method, url)
+            .cookie_jar(self.jar.clone())
+            .headers(self.headers.clone());
+        if let Some(ref proxy) = self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        builder
+    }
+}
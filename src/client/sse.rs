@@ -0,0 +1,497 @@
+//! Server-Sent Events
+//!
+//! [`Response::events`] parses a `text/event-stream` body into a stream of
+//! [`SseEvent`]s. [`RequestBuilder::event_source`] additionally reconnects when the
+//! stream ends, resuming from the last event id with the `Last-Event-ID` header.
+//!
+//! [`Response::events`]: crate::Response::events
+//! [`RequestBuilder::event_source`]: crate::RequestBuilder::event_source
+
+use std::fmt;
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+use std::time::Duration;
+
+use bytes::{Buf, Bytes, BytesMut};
+use futures_util::Stream;
+use http::header::{ACCEPT, CACHE_CONTROL, CONTENT_TYPE, HeaderName, HeaderValue};
+
+use super::body::DataStream;
+use super::decoder::Decoder;
+use super::{Client, Request, Response};
+use crate::StatusCode;
+use crate::core::rt::{Sleep, Timer};
+use crate::error::{self, Error};
+
+const LAST_EVENT_ID: HeaderName = HeaderName::from_static("last-event-id");
+
+const DEFAULT_RETRY: Duration = Duration::from_secs(3);
+
+/// A single event received from a server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SseEvent {
+    event: String,
+    data: String,
+    id: Option<String>,
+    retry: Option<Duration>,
+}
+
+impl SseEvent {
+    /// The event type, `"message"` unless the server set an `event` field.
+    pub fn event(&self) -> &str {
+        &self.event
+    }
+
+    /// The event data, with the lines of multi-line data joined by `\n`.
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    /// The last event id set by the server, if any.
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// The reconnection time set by the server in this event, if any.
+    pub fn retry(&self) -> Option<Duration> {
+        self.retry
+    }
+}
+
+/// A stream of [`SseEvent`]s parsed from a response body.
+///
+/// Created by [`Response::events`](crate::Response::events).
+pub struct SseStream {
+    body: DataStream<Decoder>,
+    parser: Parser,
+}
+
+impl SseStream {
+    pub(super) fn new(res: Response) -> SseStream {
+        SseStream {
+            body: DataStream(res.res.into_body()),
+            parser: Parser::default(),
+        }
+    }
+
+    /// The id of the last event received, used to resume the stream.
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.parser.last_id.as_deref()
+    }
+
+    /// The reconnection time most recently set by the server.
+    pub fn retry(&self) -> Option<Duration> {
+        self.parser.retry
+    }
+}
+
+impl Stream for SseStream {
+    type Item = crate::Result<SseEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(event) = self.parser.next_event() {
+                return Poll::Ready(Some(Ok(event)));
+            }
+
+            match ready!(Pin::new(&mut self.body).poll_next(cx)) {
+                Some(Ok(chunk)) => self.parser.feed(&chunk),
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+impl fmt::Debug for SseStream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SseStream")
+            .field("last_event_id", &self.parser.last_id)
+            .finish()
+    }
+}
+
+/// A stream of [`SseEvent`]s that reconnects when the connection is lost.
+///
+/// Created by [`RequestBuilder::event_source`](crate::RequestBuilder::event_source).
+///
+/// When the response body ends or fails, the request is sent again after the
+/// reconnection time, with a `Last-Event-ID` header if the server sent an event id.
+/// Errors are yielded from the stream without ending it, except for responses with
+/// a status other than `200 OK` or a content type other than `text/event-stream`,
+/// which end the stream after the error.
+///
+/// # Example
+///
+/// ```
+/// use futures_util::StreamExt;
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut events = rquest::Client::new()
+///     .get("https://example.com/events")
+///     .event_source();
+///
+/// while let Some(event) = events.next().await {
+///     let event = event?;
+///     println!("{}: {}", event.event(), event.data());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct EventSource {
+    client: Client,
+    request: Option<Request>,
+    last_event_id: Option<String>,
+    retry: Duration,
+    state: State,
+}
+
+enum State {
+    Idle,
+    Connecting(Pin<Box<dyn Future<Output = crate::Result<Response>> + Send>>),
+    Open(SseStream),
+    Waiting(Pin<Box<dyn Sleep>>),
+    Failed(Error),
+    Closed,
+}
+
+impl EventSource {
+    pub(super) fn new(client: Client, request: crate::Result<Request>) -> EventSource {
+        let (request, state) = match request {
+            Ok(mut request) => {
+                let headers = request.headers_mut();
+                headers
+                    .entry(ACCEPT)
+                    .or_insert(HeaderValue::from_static("text/event-stream"));
+                headers
+                    .entry(CACHE_CONTROL)
+                    .or_insert(HeaderValue::from_static("no-cache"));
+                (Some(request), State::Idle)
+            }
+            Err(err) => (None, State::Failed(err)),
+        };
+
+        EventSource {
+            client,
+            request,
+            last_event_id: None,
+            retry: DEFAULT_RETRY,
+            state,
+        }
+    }
+
+    /// Set the reconnection time used until the server sets one.
+    ///
+    /// Default is 3 seconds.
+    pub fn retry(mut self, retry: Duration) -> EventSource {
+        self.retry = retry;
+        self
+    }
+
+    /// The id of the last event received, sent as `Last-Event-ID` when reconnecting.
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.last_event_id.as_deref()
+    }
+
+    /// Stop the stream, closing the connection.
+    pub fn close(&mut self) {
+        self.state = State::Closed;
+    }
+
+    fn connect(&mut self) -> Option<Error> {
+        let mut request = match self.request.as_ref().map(Request::try_clone) {
+            Some(Some(request)) => request,
+            _ => {
+                return Some(error::builder(
+                    "event source request body is not replayable",
+                ));
+            }
+        };
+
+        if let Some(ref id) = self.last_event_id {
+            match HeaderValue::from_str(id) {
+                Ok(value) => {
+                    request.headers_mut().insert(LAST_EVENT_ID, value);
+                }
+                Err(err) => return Some(error::builder(err)),
+            }
+        }
+
+        self.state = State::Connecting(Box::pin(self.client.execute(request)));
+        None
+    }
+
+    fn reconnect_later(&mut self) {
+        self.state = State::Waiting(self.client.timer().sleep(self.retry));
+    }
+}
+
+impl Stream for EventSource {
+    type Item = crate::Result<SseEvent>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match this.state {
+                State::Waiting(ref mut delay) => {
+                    ready!(delay.as_mut().poll(cx));
+                    this.state = State::Idle;
+                }
+                State::Idle => {
+                    if let Some(err) = this.connect() {
+                        this.state = State::Closed;
+                        return Poll::Ready(Some(Err(err)));
+                    }
+                }
+                State::Connecting(ref mut pending) => match ready!(pending.as_mut().poll(cx)) {
+                    Ok(res) => {
+                        if let Err(err) = check_response(&res) {
+                            this.state = State::Closed;
+                            return Poll::Ready(Some(Err(err)));
+                        }
+                        let mut stream = SseStream::new(res);
+                        stream.parser.last_id = this.last_event_id.clone();
+                        this.state = State::Open(stream);
+                    }
+                    Err(err) => {
+                        this.reconnect_later();
+                        return Poll::Ready(Some(Err(err)));
+                    }
+                },
+                State::Open(ref mut stream) => {
+                    let next = ready!(Pin::new(&mut *stream).poll_next(cx));
+                    this.last_event_id = stream.parser.last_id.clone();
+                    if let Some(retry) = stream.parser.retry {
+                        this.retry = retry;
+                    }
+
+                    match next {
+                        Some(Ok(event)) => return Poll::Ready(Some(Ok(event))),
+                        Some(Err(err)) => {
+                            this.reconnect_later();
+                            return Poll::Ready(Some(Err(err)));
+                        }
+                        None => this.reconnect_later(),
+                    }
+                }
+                State::Failed(_) => match mem::replace(&mut this.state, State::Closed) {
+                    State::Failed(err) => return Poll::Ready(Some(Err(err))),
+                    _ => unreachable!(),
+                },
+                State::Closed => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+impl fmt::Debug for EventSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EventSource")
+            .field("url", &self.request.as_ref().map(Request::url))
+            .field("last_event_id", &self.last_event_id)
+            .field("retry", &self.retry)
+            .finish()
+    }
+}
+
+fn check_response(res: &Response) -> crate::Result<()> {
+    if res.status() != StatusCode::OK {
+        return Err(error::status_code(res.url().clone(), res.status()));
+    }
+
+    let is_event_stream = res
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<mime::Mime>().ok())
+        .is_some_and(|mime| mime.essence_str() == "text/event-stream");
+
+    if !is_event_stream {
+        return Err(error::decode("response is not a text/event-stream"));
+    }
+
+    Ok(())
+}
+
+/// An incremental `text/event-stream` parser.
+#[derive(Default)]
+struct Parser {
+    buf: BytesMut,
+    started: bool,
+    skip_lf: bool,
+    event: String,
+    data: String,
+    has_data: bool,
+    last_id: Option<String>,
+    retry: Option<Duration>,
+    event_retry: Option<Duration>,
+}
+
+impl Parser {
+    fn feed(&mut self, chunk: &[u8]) {
+        self.buf.extend_from_slice(chunk);
+    }
+
+    fn next_event(&mut self) -> Option<SseEvent> {
+        while let Some(line) = self.next_line() {
+            if let Some(event) = self.process_line(&line) {
+                return Some(event);
+            }
+        }
+        None
+    }
+
+    fn next_line(&mut self) -> Option<Bytes> {
+        if self.skip_lf && !self.buf.is_empty() {
+            if self.buf[0] == b'\n' {
+                self.buf.advance(1);
+            }
+            self.skip_lf = false;
+        }
+
+        if !self.started {
+            if self.buf.len() < 3 && b"\xEF\xBB\xBF".starts_with(&self.buf) {
+                return None;
+            }
+            if self.buf.starts_with(b"\xEF\xBB\xBF") {
+                self.buf.advance(3);
+            }
+            self.started = true;
+        }
+
+        let end = self.buf.iter().position(|&b| b == b'\n' || b == b'\r')?;
+        let line = self.buf.split_to(end).freeze();
+        if self.buf[0] == b'\r' {
+            self.skip_lf = true;
+        }
+        self.buf.advance(1);
+        Some(line)
+    }
+
+    fn process_line(&mut self, line: &[u8]) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+
+        if line[0] == b':' {
+            return None;
+        }
+
+        let (field, value) = match line.iter().position(|&b| b == b':') {
+            Some(colon) => {
+                let value = &line[colon + 1..];
+                (&line[..colon], value.strip_prefix(b" ").unwrap_or(value))
+            }
+            None => (line, &b""[..]),
+        };
+        let value = String::from_utf8_lossy(value);
+
+        match field {
+            b"event" => self.event = value.into_owned(),
+            b"data" => {
+                if self.has_data {
+                    self.data.push('\n');
+                }
+                self.data.push_str(&value);
+                self.has_data = true;
+            }
+            b"id" if !value.contains('\0') => self.last_id = Some(value.into_owned()),
+            b"retry" => {
+                if let Ok(ms) = value.parse::<u64>() {
+                    let retry = Duration::from_millis(ms);
+                    self.retry = Some(retry);
+                    self.event_retry = Some(retry);
+                }
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event = mem::take(&mut self.event);
+        let retry = self.event_retry.take();
+        if !mem::take(&mut self.has_data) {
+            return None;
+        }
+
+        Some(SseEvent {
+            event: if event.is_empty() {
+                "message".to_owned()
+            } else {
+                event
+            },
+            data: mem::take(&mut self.data),
+            id: self.last_id.clone(),
+            retry,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(chunks: &[&str]) -> Vec<SseEvent> {
+        let mut parser = Parser::default();
+        let mut events = Vec::new();
+        for chunk in chunks {
+            parser.feed(chunk.as_bytes());
+            while let Some(event) = parser.next_event() {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn parses_fields() {
+        let events = parse(&[
+            ": comment\n",
+            "event: update\nid: 7\nretry: 1500\ndata: first\ndata:second\n\n",
+            "data: plain\n\n",
+        ]);
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event(), "update");
+        assert_eq!(events[0].data(), "first\nsecond");
+        assert_eq!(events[0].id(), Some("7"));
+        assert_eq!(events[0].retry(), Some(Duration::from_millis(1500)));
+
+        assert_eq!(events[1].event(), "message");
+        assert_eq!(events[1].data(), "plain");
+        assert_eq!(events[1].id(), Some("7"));
+        assert_eq!(events[1].retry(), None);
+    }
+
+    #[test]
+    fn handles_split_chunks_and_line_endings() {
+        let events = parse(&["\u{feff}da", "ta: a\r", "\ndata: b\r\r", "data: c\n", "\n"]);
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].data(), "a\nb");
+        assert_eq!(events[1].data(), "c");
+    }
+
+    #[test]
+    fn skips_events_without_data() {
+        let events = parse(&["event: ping\n\nid: 3\n\ndata\n\n", "data: partial"]);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event(), "message");
+        assert_eq!(events[0].data(), "");
+        assert_eq!(events[0].id(), Some("3"));
+    }
+
+    #[test]
+    fn ignores_invalid_fields() {
+        let events = parse(&["retry: soon\nid: a\0b\nfoo: bar\ndata: x\n\n"]);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id(), None);
+        assert_eq!(events[0].retry(), None);
+    }
+}
@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+/// Timeouts for the separate phases of a request.
+///
+/// Where [`ClientBuilder::timeout`](crate::ClientBuilder::timeout) caps a whole
+/// request, these time out the phase that stalls, e.g. a server that accepts
+/// the connection but sends its response one byte at a time. Phases left unset
+/// are not timed out, except by the other timeouts of the client.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use rquest::Timeouts;
+///
+/// let client = rquest::Client::builder()
+///     .timeouts(
+///         Timeouts::new()
+///             .dns(Duration::from_secs(2))
+///             .connect(Duration::from_secs(3))
+///             .tls_handshake(Duration::from_secs(3))
+///             .first_byte(Duration::from_secs(10))
+///             .read(Duration::from_secs(5)),
+///     )
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Timeouts {
+    pub(crate) dns: Option<Duration>,
+    pub(crate) connect: Option<Duration>,
+    pub(crate) tls_handshake: Option<Duration>,
+    pub(crate) write: Option<Duration>,
+    pub(crate) first_byte: Option<Duration>,
+    pub(crate) read: Option<Duration>,
+}
+
+impl Timeouts {
+    /// Create timeouts that change nothing.
+    pub fn new() -> Timeouts {
+        Timeouts::default()
+    }
+
+    /// Time out resolving the host name of a new connection.
+    pub fn dns(mut self, timeout: Duration) -> Timeouts {
+        self.dns = Some(timeout);
+        self
+    }
+
+    /// Time out establishing the TCP connection.
+    ///
+    /// If the host resolves to several addresses, the timeout is divided evenly
+    /// between them. Unlike [`ClientBuilder::connect_timeout`](crate::ClientBuilder::connect_timeout),
+    /// this doesn't include resolving the host, the TLS handshake or a proxy tunnel.
+    pub fn connect(mut self, timeout: Duration) -> Timeouts {
+        self.connect = Some(timeout);
+        self
+    }
+
+    /// Time out the TLS handshake with the server or an HTTPS proxy.
+    pub fn tls_handshake(mut self, timeout: Duration) -> Timeouts {
+        self.tls_handshake = Some(timeout);
+        self
+    }
+
+    /// Time out a write to the connection that makes no progress, e.g. because
+    /// the server stopped reading the request body.
+    pub fn write(mut self, timeout: Duration) -> Timeouts {
+        self.write = Some(timeout);
+        self
+    }
+
+    /// Time out waiting for the response headers.
+    ///
+    /// The time is counted from when each attempt of a request (e.g. after a
+    /// redirect or a retry) starts, so it includes getting a connection and
+    /// sending the request.
+    pub fn first_byte(mut self, timeout: Duration) -> Timeouts {
+        self.first_byte = Some(timeout);
+        self
+    }
+
+    /// Time out reading the response body when no data arrives for this long.
+    ///
+    /// This is the same as [`ClientBuilder::read_timeout`](crate::ClientBuilder::read_timeout).
+    pub fn read(mut self, timeout: Duration) -> Timeouts {
+        self.read = Some(timeout);
+        self
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt::{self, Write};
+use std::sync::Arc;
+use std::sync::mpsc;
+
+use http::{HeaderMap, Version};
+
+/// Where a `Client` dumps the heads of the requests it sends and the
+/// responses it receives, like `curl --verbose`.
+///
+/// Each head is dumped as a single block of text, after everything the client
+/// changes about the request: its emulation headers in their order, cookies,
+/// authentication, and the `Host` header and request target of HTTP/1. A
+/// request is dumped for each time it is sent, so redirects and retries show
+/// up as well.
+///
+/// ```text
+/// > GET / HTTP/1.1
+/// > user-agent: rquest
+/// > accept: */*
+/// > host: example.com
+/// >
+/// < HTTP/1.1 200 OK
+/// < content-type: text/html
+/// <
+/// ```
+///
+/// Header names are shown in lowercase, as HTTP/2 sends them; an HTTP/1
+/// connection may send them with another case. Framing headers the
+/// connection adds, like `content-length`, and bodies aren't dumped.
+///
+/// # Example
+///
+/// ```
+/// use rquest::VerboseConfig;
+///
+/// let (verbose, heads) = VerboseConfig::channel();
+/// let client = rquest::Client::builder()
+///     .verbose(verbose)
+///     .build()
+///     .unwrap();
+/// # drop((client, heads));
+/// ```
+#[derive(Clone)]
+pub struct VerboseConfig {
+    sink: Sink,
+    redact_sensitive: bool,
+}
+
+#[derive(Clone)]
+enum Sink {
+    Stderr,
+    Callback(Arc<dyn Fn(&str) + Send + Sync>),
+    Channel(mpsc::Sender<String>),
+}
+
+impl VerboseConfig {
+    /// Print the heads to standard error.
+    pub fn stderr() -> VerboseConfig {
+        VerboseConfig::with_sink(Sink::Stderr)
+    }
+
+    /// Pass the heads to `callback`.
+    pub fn callback<F>(callback: F) -> VerboseConfig
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        VerboseConfig::with_sink(Sink::Callback(Arc::new(callback)))
+    }
+
+    /// Send the heads to the returned receiver.
+    ///
+    /// Heads are dropped once the receiver is.
+    pub fn channel() -> (VerboseConfig, mpsc::Receiver<String>) {
+        let (tx, rx) = mpsc::channel();
+        (VerboseConfig::with_sink(Sink::Channel(tx)), rx)
+    }
+
+    /// Set whether the values of sensitive headers, like those set by
+    /// `RequestBuilder::basic_auth`, are replaced with `[redacted]`.
+    ///
+    /// Default is `true`.
+    pub fn redact_sensitive(mut self, enabled: bool) -> VerboseConfig {
+        self.redact_sensitive = enabled;
+        self
+    }
+
+    fn with_sink(sink: Sink) -> VerboseConfig {
+        VerboseConfig {
+            sink,
+            redact_sensitive: true,
+        }
+    }
+
+    /// Dump the head of a request about to be sent with `version`.
+    pub(crate) fn request<B>(&self, req: &http::Request<B>, version: Version) {
+        let mut head = String::new();
+        let _ = writeln!(head, "> {} {} {:?}", req.method(), req.uri(), version);
+        self.headers(&mut head, '>', req.headers());
+        self.emit(head);
+    }
+
+    /// Dump the head of a response.
+    pub(crate) fn response<B>(&self, res: &http::Response<B>) {
+        let mut head = String::new();
+        let _ = write!(head, "< {:?} {}", res.version(), res.status().as_u16());
+        if let Some(reason) = res.status().canonical_reason() {
+            let _ = write!(head, " {}", reason);
+        }
+        head.push('\n');
+        self.headers(&mut head, '<', res.headers());
+        self.emit(head);
+    }
+
+    fn headers(&self, head: &mut String, prefix: char, headers: &HeaderMap) {
+        for (name, value) in headers {
+            let _ = if value.is_sensitive() && self.redact_sensitive {
+                writeln!(head, "{} {}: [redacted]", prefix, name)
+            } else {
+                writeln!(
+                    head,
+                    "{} {}: {}",
+                    prefix,
+                    name,
+                    String::from_utf8_lossy(value.as_bytes())
+                )
+            };
+        }
+        head.push(prefix);
+    }
+
+    fn emit(&self, head: String) {
+        match self.sink {
+            Sink::Stderr => eprintln!("{}", head),
+            Sink::Callback(ref callback) => callback(&head),
+            Sink::Channel(ref tx) => {
+                let _ = tx.send(head);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for VerboseConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sink = match self.sink {
+            Sink::Stderr => "stderr",
+            Sink::Callback(_) => "callback",
+            Sink::Channel(_) => "channel",
+        };
+        f.debug_struct("VerboseConfig")
+            .field("sink", &sink)
+            .field("redact_sensitive", &self.redact_sensitive)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dumps_heads() {
+        let (verbose, rx) = VerboseConfig::channel();
+
+        let mut auth = http::HeaderValue::from_static("Basic c2VjcmV0");
+        auth.set_sensitive(true);
+        let req = http::Request::builder()
+            .uri("/path?q=1")
+            .header("user-agent", "rquest")
+            .header("authorization", auth)
+            .body(())
+            .unwrap();
+        verbose.request(&req, Version::HTTP_11);
+
+        let res = http::Response::builder()
+            .status(404)
+            .header("content-type", "text/plain")
+            .body(())
+            .unwrap();
+        verbose.response(&res);
+
+        assert_eq!(
+            rx.recv().unwrap(),
+            "> GET /path?q=1 HTTP/1.1\n> user-agent: rquest\n> authorization: [redacted]\n>"
+        );
+        assert_eq!(
+            rx.recv().unwrap(),
+            "< HTTP/1.1 404 Not Found\n< content-type: text/plain\n<"
+        );
+    }
+}
@@ -227,6 +227,12 @@ impl WebSocketRequestBuilder {
         self
     }
 
+    /// Connect directly, ignoring any proxies configured on the `Client`.
+    pub fn no_proxy(mut self) -> Self {
+        self.inner = self.inner.no_proxy();
+        self
+    }
+
     /// Set the local address for this request.
     pub fn local_address<V>(mut self, local_address: V) -> Self
     where
@@ -25,10 +25,12 @@
 //! to set or retrieve config values for each key type in a uniform way.
 
 use std::fmt::Debug;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use http::Extensions;
 
+use crate::client::body::Progress;
+
 /// This trait is empty and is only used to associate a configuration key type with its
 /// corresponding value type.
 pub(crate) trait RequestConfigValue: Copy + Clone + 'static {
@@ -98,3 +100,104 @@ pub(crate) struct RequestTimeout;
 impl RequestConfigValue for RequestTimeout {
     type Value = Duration;
 }
+
+#[derive(Clone, Copy)]
+pub(crate) struct RequestReadTimeout;
+
+impl RequestConfigValue for RequestReadTimeout {
+    type Value = Duration;
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct RequestDeadline;
+
+impl RequestConfigValue for RequestDeadline {
+    type Value = Instant;
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct RequestMaxResponseSize;
+
+impl RequestConfigValue for RequestMaxResponseSize {
+    type Value = u64;
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct UploadProgress;
+
+impl RequestConfigValue for UploadProgress {
+    type Value = Progress;
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct DownloadProgress;
+
+impl RequestConfigValue for DownloadProgress {
+    type Value = Progress;
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct RequestDigestAuth;
+
+impl RequestConfigValue for RequestDigestAuth {
+    type Value = crate::client::digest::DigestAuth;
+}
+
+/// The bearer token of the client's `TokenProvider` a request was sent with.
+#[derive(Clone, Copy)]
+pub(crate) struct RequestBearerToken;
+
+impl RequestConfigValue for RequestBearerToken {
+    type Value = String;
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct RequestExpectContinue;
+
+impl RequestConfigValue for RequestExpectContinue {
+    type Value = bool;
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct RequestAwsSigner;
+
+impl RequestConfigValue for RequestAwsSigner {
+    type Value = crate::client::sigv4::AwsSigner;
+}
+
+#[cfg(feature = "ntlm")]
+#[derive(Clone, Copy)]
+pub(crate) struct RequestNtlmAuth;
+
+#[cfg(feature = "ntlm")]
+impl RequestConfigValue for RequestNtlmAuth {
+    type Value = crate::ntlm::NtlmCredentials;
+}
+
+#[cfg(any(
+    feature = "gzip",
+    feature = "brotli",
+    feature = "zstd",
+    feature = "deflate"
+))]
+#[derive(Clone, Copy)]
+pub(crate) struct RequestCompression;
+
+#[cfg(any(
+    feature = "gzip",
+    feature = "brotli",
+    feature = "zstd",
+    feature = "deflate"
+))]
+impl RequestConfigValue for RequestCompression {
+    type Value = crate::client::body::Encoding;
+}
+
+#[cfg(feature = "cookies")]
+#[derive(Clone, Copy)]
+pub(crate) struct RequestCookieJar;
+
+#[cfg(feature = "cookies")]
+impl RequestConfigValue for RequestCookieJar {
+    type Value = crate::cookie::RequestCookieStore;
+}
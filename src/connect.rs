@@ -22,11 +22,15 @@ use tower::{ServiceBuilder, timeout::TimeoutLayer, util::BoxCloneSyncService};
 use tower_service::Service;
 
 use std::io::{self, IoSlice};
+#[cfg(unix)]
+use std::path::Path;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::{future::Future, ops::Deref};
 
+use crate::client::metrics::{ConnectMetrics, MetricsObserver};
 use crate::dns::DynResolver;
 use crate::error::{BoxError, cast_to_internal_error};
 use crate::proxy::ProxyScheme;
@@ -38,13 +42,105 @@ pub(crate) type BoxedConnectorService = BoxCloneSyncService<Unnameable, Conn, Bo
 pub(crate) type BoxedConnectorLayer =
     BoxCloneSyncServiceLayer<BoxedConnectorService, Unnameable, Conn, BoxError>;
 
+/// TCP socket options of the connections a `Client` opens.
+///
+/// Options left unset keep the client's defaults, or else the operating system's.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use rquest::TcpOptions;
+///
+/// let client = rquest::Client::builder()
+///     .tcp_options(
+///         TcpOptions::new()
+///             .keepalive(Duration::from_secs(60))
+///             .keepalive_interval(Duration::from_secs(10))
+///             .keepalive_retries(3)
+///             .recv_buffer_size(4 * 1024 * 1024),
+///     )
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TcpOptions {
+    pub(crate) nodelay: Option<bool>,
+    pub(crate) keepalive: Option<Duration>,
+    pub(crate) keepalive_interval: Option<Duration>,
+    pub(crate) keepalive_retries: Option<u32>,
+    pub(crate) send_buffer_size: Option<usize>,
+    pub(crate) recv_buffer_size: Option<usize>,
+    #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+    pub(crate) user_timeout: Option<Duration>,
+}
+
+impl TcpOptions {
+    /// Create options that change nothing.
+    pub fn new() -> TcpOptions {
+        TcpOptions::default()
+    }
+
+    /// Set whether sockets have `TCP_NODELAY` enabled.
+    pub fn nodelay(mut self, enabled: bool) -> TcpOptions {
+        self.nodelay = Some(enabled);
+        self
+    }
+
+    /// Enable `SO_KEEPALIVE`, with the idle time before the first probe.
+    pub fn keepalive(mut self, time: Duration) -> TcpOptions {
+        self.keepalive = Some(time);
+        self
+    }
+
+    /// Set the time between keepalive probes.
+    pub fn keepalive_interval(mut self, interval: Duration) -> TcpOptions {
+        self.keepalive_interval = Some(interval);
+        self
+    }
+
+    /// Set the number of unanswered keepalive probes before the connection is dropped.
+    pub fn keepalive_retries(mut self, retries: u32) -> TcpOptions {
+        self.keepalive_retries = Some(retries);
+        self
+    }
+
+    /// Set the size of the socket send buffer (`SO_SNDBUF`).
+    pub fn send_buffer_size(mut self, size: usize) -> TcpOptions {
+        self.send_buffer_size = Some(size);
+        self
+    }
+
+    /// Set the size of the socket receive buffer (`SO_RCVBUF`).
+    pub fn recv_buffer_size(mut self, size: usize) -> TcpOptions {
+        self.recv_buffer_size = Some(size);
+        self
+    }
+
+    /// Set how long sent data may stay unacknowledged before the connection is
+    /// dropped (`TCP_USER_TIMEOUT`).
+    #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux")))
+    )]
+    pub fn user_timeout(mut self, time: Duration) -> TcpOptions {
+        self.user_timeout = Some(time);
+        self
+    }
+}
+
 pub(crate) struct ConnectorBuilder {
     http: HttpConnector,
     tls: TlsConnector,
     verbose: verbose::Wrapper,
     timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    metrics: Option<Arc<dyn MetricsObserver>>,
     nodelay: bool,
     tls_info: bool,
+    #[cfg(unix)]
+    unix_socket: Option<Arc<Path>>,
     #[cfg(feature = "socks")]
     resolver: Option<DynResolver>,
 }
@@ -61,8 +157,14 @@ impl ConnectorBuilder {
             nodelay: self.nodelay,
             tls_info: self.tls_info,
             timeout: self.timeout,
+            write_timeout: self.write_timeout,
+            metrics: self.metrics,
+            #[cfg(unix)]
+            unix_socket: self.unix_socket,
             #[cfg(feature = "socks")]
             resolver: self.resolver.unwrap_or_else(DynResolver::gai),
+            #[cfg(feature = "tracing")]
+            conn_id: None,
         };
 
         match layers.into() {
@@ -144,12 +246,31 @@ impl ConnectorBuilder {
         self
     }
 
+    #[inline]
+    pub(crate) fn write_timeout(mut self, timeout: Option<Duration>) -> ConnectorBuilder {
+        self.write_timeout = timeout;
+        self
+    }
+
+    #[inline]
+    pub(crate) fn metrics(mut self, metrics: Option<Arc<dyn MetricsObserver>>) -> ConnectorBuilder {
+        self.metrics = metrics;
+        self
+    }
+
     #[inline]
     pub(crate) fn verbose(mut self, enabled: bool) -> ConnectorBuilder {
         self.verbose.0 = enabled;
         self
     }
 
+    #[cfg(unix)]
+    #[inline]
+    pub(crate) fn unix_socket(mut self, path: Option<Arc<Path>>) -> ConnectorBuilder {
+        self.unix_socket = path;
+        self
+    }
+
     #[cfg(feature = "socks")]
     #[inline]
     pub(crate) fn socks_resolver<R>(mut self, resolver: R) -> ConnectorBuilder
@@ -187,8 +308,12 @@ impl Connector {
             tls,
             verbose: verbose::OFF,
             timeout: None,
+            write_timeout: None,
+            metrics: None,
             nodelay,
             tls_info,
+            #[cfg(unix)]
+            unix_socket: None,
             #[cfg(feature = "socks")]
             resolver: None,
         }
@@ -211,8 +336,13 @@ impl Connector {
                     base_service.tls_info,
                 )
                 .timeout(base_service.timeout)
+                .write_timeout(base_service.write_timeout)
+                .metrics(base_service.metrics.clone())
                 .verbose(base_service.verbose.0);
 
+                #[cfg(unix)]
+                let builder = builder.unix_socket(base_service.unix_socket.clone());
+
                 let mut connector = {
                     #[cfg(feature = "socks")]
                     {
@@ -262,13 +392,48 @@ pub(crate) struct ConnectorService {
     /// This lets us avoid an extra `Box::pin` indirection layer
     /// since `tokio::time::Timeout` is `Unpin`
     timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    metrics: Option<Arc<dyn MetricsObserver>>,
     nodelay: bool,
     tls_info: bool,
+    #[cfg(unix)]
+    unix_socket: Option<Arc<Path>>,
     #[cfg(feature = "socks")]
     resolver: DynResolver,
+    /// The connection being made, set by each call.
+    #[cfg(feature = "tracing")]
+    conn_id: Option<crate::trace::ConnectionId>,
 }
 
 impl ConnectorService {
+    /// Box a connection, with the wrappers this connector is configured with.
+    fn wrap<T: AsyncConnWithInfo>(&self, conn: T) -> BoxConn {
+        #[cfg(feature = "tracing")]
+        let conn = traced::Traced::new(conn, self.conn_id);
+        match self.write_timeout {
+            Some(timeout) => self
+                .verbose
+                .wrap(write_timeout::WriteTimeout::new(conn, timeout)),
+            None => self.verbose.wrap(conn),
+        }
+    }
+
+    #[cfg(unix)]
+    async fn connect_unix(self, dst: Dst, path: Arc<Path>) -> Result<Conn, BoxError> {
+        if dst.scheme() == Some(&Scheme::HTTPS) {
+            return Err("HTTPS is not supported over a unix socket".into());
+        }
+
+        trace!("connecting to unix socket {:?}", path);
+        let io = tokio::net::UnixStream::connect(&*path).await?;
+
+        Ok(Conn {
+            inner: self.wrap(TokioIo::new(io)),
+            is_proxy: false,
+            tls_info: false,
+        })
+    }
+
     #[cfg(feature = "socks")]
     async fn connect_socks(&self, mut dst: Dst, proxy: ProxyScheme) -> Result<Conn, BoxError> {
         let dns = match proxy {
@@ -299,7 +464,7 @@ impl ConnectorService {
             let io = http.connect(&dst, host, TokioIo::new(conn)).await?;
 
             return Ok(Conn {
-                inner: self.verbose.wrap(BoringTlsConn {
+                inner: self.wrap(BoringTlsConn {
                     inner: TokioIo::new(io),
                 }),
                 is_proxy: false,
@@ -310,7 +475,7 @@ impl ConnectorService {
         socks::connect(proxy, &dst, dns, &self.resolver)
             .await
             .map(|tcp| Conn {
-                inner: self.verbose.wrap(TokioIo::new(tcp)),
+                inner: self.wrap(TokioIo::new(tcp)),
                 is_proxy: false,
                 tls_info: false,
             })
@@ -344,13 +509,13 @@ impl ConnectorService {
                     .set_nodelay(false)?;
             }
             Ok(Conn {
-                inner: self.verbose.wrap(BoringTlsConn { inner: stream }),
+                inner: self.wrap(BoringTlsConn { inner: stream }),
                 is_proxy,
                 tls_info: self.tls_info,
             })
         } else {
             Ok(Conn {
-                inner: self.verbose.wrap(io),
+                inner: self.wrap(io),
                 is_proxy,
                 tls_info: self.tls_info,
             })
@@ -364,17 +529,41 @@ impl ConnectorService {
     ) -> Result<Conn, BoxError> {
         debug!("proxy({:?}) intercepts '{:?}'", proxy_scheme, dst.uri());
 
-        let (proxy_dst, auth, headers) = match proxy_scheme {
+        #[cfg(feature = "ntlm")]
+        let ntlm = match proxy_scheme {
+            ProxyScheme::Http { ref ntlm, .. } | ProxyScheme::Https { ref ntlm, .. } => {
+                ntlm.clone()
+            }
+            #[cfg(feature = "socks")]
+            _ => None,
+        };
+
+        let credentials = proxy_scheme.maybe_credentials().cloned();
+        let (proxy_dst, auth, headers, connect_headers) = match proxy_scheme {
             ProxyScheme::Http {
                 host,
                 auth,
                 headers,
-            } => (into_uri(Scheme::HTTP, host)?, auth, headers),
+                connect_headers,
+                ..
+            } => (
+                into_uri(Scheme::HTTP, host)?,
+                auth,
+                headers,
+                connect_headers,
+            ),
             ProxyScheme::Https {
                 host,
                 auth,
                 headers,
-            } => (into_uri(Scheme::HTTPS, host)?, auth, headers),
+                connect_headers,
+                ..
+            } => (
+                into_uri(Scheme::HTTPS, host)?,
+                auth,
+                headers,
+                connect_headers,
+            ),
             #[cfg(feature = "socks")]
             ProxyScheme::Socks4 { .. } | ProxyScheme::Socks5 { .. } => {
                 return self.connect_socks(dst, proxy_scheme).await;
@@ -391,10 +580,29 @@ impl ConnectorService {
                 tunnel = tunnel.with_auth(auth);
             }
 
+            // the credentials are looked up for each connection, so they can rotate
+            if let Some(credentials) = credentials {
+                if let Some(auth) = credentials.basic_auth() {
+                    tunnel = tunnel.with_auth(auth);
+                }
+                if let Some(digest) = credentials.digest_auth() {
+                    tunnel = tunnel.with_digest(digest);
+                }
+            }
+
             if let Some(headers) = headers {
                 tunnel = tunnel.with_headers((*headers).clone());
             }
 
+            if let Some(headers) = connect_headers {
+                tunnel = tunnel.with_headers((*headers).clone());
+            }
+
+            #[cfg(feature = "ntlm")]
+            if let Some(ntlm) = ntlm {
+                tunnel = tunnel.with_ntlm(ntlm);
+            }
+
             // We don't wrap this again in an HttpsConnector since that uses Maybe,
             // and we know this is definitely HTTPS.
             let tunneled = tunnel.call(dst.deref().clone()).await?;
@@ -403,7 +611,7 @@ impl ConnectorService {
             let io = http.connect(&dst, host, tunneled).await?;
 
             return Ok(Conn {
-                inner: self.verbose.wrap(BoringTlsConn {
+                inner: self.wrap(BoringTlsConn {
                     inner: TokioIo::new(io),
                 }),
                 is_proxy: false,
@@ -451,19 +659,64 @@ impl Service<Dst> for ConnectorService {
         Poll::Ready(Ok(()))
     }
 
-    fn call(&mut self, mut dst: Dst) -> Self::Future {
+    fn call(&mut self, dst: Dst) -> Self::Future {
         debug!("starting new connection: {:?}", dst.uri());
 
+        #[cfg(feature = "tracing")]
+        {
+            use tracing::Instrument;
+
+            let id = crate::trace::ConnectionId::next();
+            let span = tracing::debug_span!(
+                "connect",
+                conn.id = id.0,
+                server.address = %dst.uri(),
+            );
+            let mut this = self.clone();
+            this.conn_id = Some(id);
+            Box::pin(this.connect(dst).instrument(span))
+        }
+        #[cfg(not(feature = "tracing"))]
+        self.clone().connect(dst)
+    }
+}
+
+impl ConnectorService {
+    fn connect(self, dst: Dst) -> Connecting {
+        match self.metrics.clone() {
+            Some(metrics) => {
+                let uri = dst.uri().clone();
+                let start = Instant::now();
+                let connecting = self.connect_inner(dst);
+                Box::pin(async move {
+                    let res = connecting.await;
+                    metrics.on_connect(&ConnectMetrics::new(uri, start.elapsed(), res.is_err()));
+                    res
+                })
+            }
+            None => self.connect_inner(dst),
+        }
+    }
+
+    fn connect_inner(self, mut dst: Dst) -> Connecting {
+        #[cfg(unix)]
+        if let Some(path) = self.unix_socket.clone() {
+            let timeout = self.timeout;
+            return Box::pin(with_timeout(self.connect_unix(dst, path), timeout));
+        }
+
         if let Some(proxy_scheme) = dst.take_proxy_scheme() {
+            let timeout = self.timeout;
             return Box::pin(with_timeout(
-                self.clone().connect_via_proxy(dst, proxy_scheme),
-                self.timeout,
+                self.connect_via_proxy(dst, proxy_scheme),
+                timeout,
             ));
         }
 
+        let timeout = self.timeout;
         Box::pin(with_timeout(
-            self.clone().connect_with_maybe_proxy(dst, false),
-            self.timeout,
+            self.connect_with_maybe_proxy(dst, false),
+            timeout,
         ))
     }
 }
@@ -478,6 +731,20 @@ impl TlsInfoFactory for tokio::net::TcpStream {
     }
 }
 
+#[cfg(unix)]
+impl TlsInfoFactory for tokio::net::UnixStream {
+    fn tls_info(&self) -> Option<crate::tls::TlsInfo> {
+        None
+    }
+}
+
+#[cfg(unix)]
+impl Connection for tokio::net::UnixStream {
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+
 impl<T: TlsInfoFactory> TlsInfoFactory for TokioIo<T> {
     fn tls_info(&self) -> Option<crate::tls::TlsInfo> {
         self.inner().tls_info()
@@ -486,12 +753,7 @@ impl<T: TlsInfoFactory> TlsInfoFactory for TokioIo<T> {
 
 impl TlsInfoFactory for SslStream<TokioIo<TokioIo<tokio::net::TcpStream>>> {
     fn tls_info(&self) -> Option<crate::tls::TlsInfo> {
-        self.ssl()
-            .peer_certificate()
-            .and_then(|c| c.to_der().ok())
-            .map(|c| crate::tls::TlsInfo {
-                peer_certificate: Some(c),
-            })
+        Some(crate::tls::TlsInfo::from_ssl(self.ssl()))
     }
 }
 
@@ -725,6 +987,7 @@ mod socks {
     use tokio_socks::tcp::{Socks4Stream, Socks5Stream};
 
     use super::{BoxError, Scheme};
+    use crate::error::ProxyError;
     use crate::{dns::DynResolver, proxy::ProxyScheme};
 
     pub(super) enum DnsResolve {
@@ -760,7 +1023,7 @@ mod socks {
             ProxyScheme::Socks4 { addr, .. } => {
                 let stream = Socks4Stream::connect(addr, (host.as_str(), port))
                     .await
-                    .map_err(|e| format!("socks connect error: {e}"))?;
+                    .map_err(|e| ProxyError::new(format!("socks connect error: {e}")))?;
                 Ok(stream.into_inner())
             }
             ProxyScheme::Socks5 { addr, ref auth, .. } => {
@@ -772,11 +1035,11 @@ mod socks {
                         password,
                     )
                     .await
-                    .map_err(|e| format!("socks connect error: {e}"))?
+                    .map_err(|e| ProxyError::new(format!("socks connect error: {e}")))?
                 } else {
                     Socks5Stream::connect(addr, (host.as_str(), port))
                         .await
-                        .map_err(|e| format!("socks connect error: {e}"))?
+                        .map_err(|e| ProxyError::new(format!("socks connect error: {e}")))?
                 };
 
                 Ok(stream.into_inner())
@@ -964,3 +1227,209 @@ mod verbose {
         }
     }
 }
+
+mod write_timeout {
+    use std::future::Future;
+    use std::io::{self, IoSlice};
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use std::time::Duration;
+
+    use tokio::time::Sleep;
+
+    use crate::core::client::connect::{Connected, Connection};
+    use crate::core::rt::{Read, ReadBufCursor, Write};
+
+    /// Fails writes to a connection that make no progress for `timeout`.
+    pub(super) struct WriteTimeout<T> {
+        inner: T,
+        timeout: Duration,
+        sleep: Option<Pin<Box<Sleep>>>,
+    }
+
+    impl<T> WriteTimeout<T> {
+        pub(super) fn new(inner: T, timeout: Duration) -> Self {
+            WriteTimeout {
+                inner,
+                timeout,
+                sleep: None,
+            }
+        }
+
+        /// Start the timer when a write is pending, and reset it when it isn't.
+        fn check<R>(
+            &mut self,
+            cx: &mut Context<'_>,
+            res: Poll<io::Result<R>>,
+        ) -> Poll<io::Result<R>> {
+            if res.is_ready() {
+                self.sleep = None;
+                return res;
+            }
+
+            let timeout = self.timeout;
+            let sleep = self
+                .sleep
+                .get_or_insert_with(|| Box::pin(tokio::time::sleep(timeout)));
+            match sleep.as_mut().poll(cx) {
+                Poll::Ready(()) => {
+                    self.sleep = None;
+                    Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        "write timed out",
+                    )))
+                }
+                Poll::Pending => Poll::Pending,
+            }
+        }
+    }
+
+    impl<T: Connection> Connection for WriteTimeout<T> {
+        fn connected(&self) -> Connected {
+            self.inner.connected()
+        }
+    }
+
+    impl<T: Read + Unpin> Read for WriteTimeout<T> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context,
+            buf: ReadBufCursor<'_>,
+        ) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.inner).poll_read(cx, buf)
+        }
+    }
+
+    impl<T: Write + Unpin> Write for WriteTimeout<T> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            let this = self.get_mut();
+            let res = Pin::new(&mut this.inner).poll_write(cx, buf);
+            this.check(cx, res)
+        }
+
+        fn poll_write_vectored(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &[IoSlice<'_>],
+        ) -> Poll<io::Result<usize>> {
+            let this = self.get_mut();
+            let res = Pin::new(&mut this.inner).poll_write_vectored(cx, bufs);
+            this.check(cx, res)
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            self.inner.is_write_vectored()
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+            let this = self.get_mut();
+            let res = Pin::new(&mut this.inner).poll_flush(cx);
+            this.check(cx, res)
+        }
+
+        fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.inner).poll_shutdown(cx)
+        }
+    }
+
+    impl<T: super::TlsInfoFactory> super::TlsInfoFactory for WriteTimeout<T> {
+        fn tls_info(&self) -> Option<crate::tls::TlsInfo> {
+            self.inner.tls_info()
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+mod traced {
+    use std::io::{self, IoSlice};
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use crate::core::client::connect::{Connected, Connection};
+    use crate::core::rt::{Read, ReadBufCursor, Write};
+    use crate::trace::ConnectionId;
+
+    /// Tags the responses received on a connection with its `ConnectionId`,
+    /// and traces the connection closing.
+    pub(super) struct Traced<T> {
+        inner: T,
+        id: Option<ConnectionId>,
+    }
+
+    impl<T> Traced<T> {
+        pub(super) fn new(inner: T, id: Option<ConnectionId>) -> Self {
+            if let Some(id) = id {
+                tracing::debug!(conn.id = id.0, "connection established");
+            }
+            Traced { inner, id }
+        }
+    }
+
+    impl<T> Drop for Traced<T> {
+        fn drop(&mut self) {
+            if let Some(id) = self.id {
+                tracing::debug!(conn.id = id.0, "connection closed");
+            }
+        }
+    }
+
+    impl<T: Connection> Connection for Traced<T> {
+        fn connected(&self) -> Connected {
+            let connected = self.inner.connected();
+            match self.id {
+                Some(id) => connected.extra(id),
+                None => connected,
+            }
+        }
+    }
+
+    impl<T: Read + Unpin> Read for Traced<T> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context,
+            buf: ReadBufCursor<'_>,
+        ) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.inner).poll_read(cx, buf)
+        }
+    }
+
+    impl<T: Write + Unpin> Write for Traced<T> {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            Pin::new(&mut self.inner).poll_write(cx, buf)
+        }
+
+        fn poll_write_vectored(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &[IoSlice<'_>],
+        ) -> Poll<io::Result<usize>> {
+            Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            self.inner.is_write_vectored()
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.inner).poll_flush(cx)
+        }
+
+        fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.inner).poll_shutdown(cx)
+        }
+    }
+
+    impl<T: super::TlsInfoFactory> super::TlsInfoFactory for Traced<T> {
+        fn tls_info(&self) -> Option<crate::tls::TlsInfo> {
+            self.inner.tls_info()
+        }
+    }
+}
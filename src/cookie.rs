@@ -3,6 +3,9 @@
 use antidote::RwLock;
 use bytes::BufMut;
 use std::fmt;
+use std::future::{self, Future};
+use std::pin::Pin;
+use std::sync::Arc;
 use std::time::SystemTime;
 use std::{borrow::Cow, convert::TryInto};
 
@@ -18,6 +21,45 @@ pub trait CookieStore: Send + Sync {
     fn cookies(&self, url: &url::Url) -> Option<Vec<HeaderValue>>;
 }
 
+/// A future returned by an [`AsyncCookieStore`].
+pub type CookieFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Actions for a cookie store that is accessed asynchronously.
+///
+/// This allows the cookies of a `Client` to be kept in a shared database, such
+/// as Redis or SQLite, that several clients or processes use at once. Every
+/// [`CookieStore`] is also an `AsyncCookieStore`.
+pub trait AsyncCookieStore: Send + Sync {
+    /// Store all Set-Cookie header values of a response received from `url`.
+    ///
+    /// This is called once for every response that sets cookies, including the
+    /// responses to redirects, and before the response is handled further. It
+    /// is therefore also the place to observe which cookies each response changes.
+    fn store_cookies<'a>(
+        &'a self,
+        cookie_headers: Vec<HeaderValue>,
+        url: &'a url::Url,
+    ) -> CookieFuture<'a, ()>;
+
+    /// Get all Cookie values in the store for `url`.
+    fn load_cookies<'a>(&'a self, url: &'a url::Url) -> CookieFuture<'a, Option<Vec<HeaderValue>>>;
+}
+
+impl<T: CookieStore + ?Sized> AsyncCookieStore for T {
+    fn store_cookies<'a>(
+        &'a self,
+        cookie_headers: Vec<HeaderValue>,
+        url: &'a url::Url,
+    ) -> CookieFuture<'a, ()> {
+        self.set_cookies(&mut cookie_headers.iter(), url);
+        Box::pin(future::ready(()))
+    }
+
+    fn load_cookies<'a>(&'a self, url: &'a url::Url) -> CookieFuture<'a, Option<Vec<HeaderValue>>> {
+        Box::pin(future::ready(self.cookies(url)))
+    }
+}
+
 /// A single HTTP cookie.
 #[derive(Debug, Clone)]
 pub struct Cookie<'a>(cookie_crate::Cookie<'a>);
@@ -26,6 +68,24 @@ pub struct Cookie<'a>(cookie_crate::Cookie<'a>);
 #[derive(Debug, Clone)]
 pub struct CookieBuilder<'a>(cookie_crate::CookieBuilder<'a>);
 
+/// A list of public suffixes, such as `com` or `co.uk`, under which anyone can
+/// register a domain.
+///
+/// A [`Jar`] with a list rejects cookies set for a whole public suffix, as
+/// browsers do, so that one site can't set cookies for all others.
+///
+/// # Optional
+///
+/// This requires the optional `cookies-psl` feature to be enabled.
+#[cfg(feature = "cookies-psl")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cookies-psl")))]
+#[derive(Debug, Clone)]
+pub struct PublicSuffixList(publicsuffix::List);
+
+/// A cookie store set for a single request.
+#[derive(Clone)]
+pub(crate) struct RequestCookieStore(pub(crate) Arc<dyn AsyncCookieStore>);
+
 /// A good default `CookieStore` implementation.
 ///
 /// This is the implementation used when simply calling `cookie_store(true)`.
@@ -100,6 +160,12 @@ impl<'a> Cookie<'a> {
         self.0.same_site() == Some(cookie_crate::SameSite::Strict)
     }
 
+    /// Returns the 'SameSite' directive of the cookie, if set.
+    #[inline]
+    pub fn same_site(&self) -> Option<SameSite> {
+        self.0.same_site()
+    }
+
     /// Returns the path directive of the cookie, if set.
     #[inline]
     pub fn path(&self) -> Option<&str> {
@@ -246,8 +312,68 @@ impl fmt::Display for CookieParseError {
 
 impl std::error::Error for CookieParseError {}
 
+impl fmt::Debug for RequestCookieStore {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("RequestCookieStore").finish()
+    }
+}
+
+// ===== impl PublicSuffixList =====
+#[cfg(feature = "cookies-psl")]
+impl PublicSuffixList {
+    /// Parse a list in the format of
+    /// [public_suffix_list.dat](https://publicsuffix.org/list/public_suffix_list.dat).
+    pub fn from_bytes(bytes: &[u8]) -> crate::Result<PublicSuffixList> {
+        publicsuffix::List::from_bytes(bytes)
+            .map(PublicSuffixList)
+            .map_err(crate::error::builder)
+    }
+
+    /// The copy of the list embedded in this crate.
+    ///
+    /// It's as current as the version of this crate, so long-running
+    /// programs should prefer loading a fresh list with
+    /// [`PublicSuffixList::from_bytes`].
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `cookies-psl-embedded` feature to be enabled.
+    #[cfg(feature = "cookies-psl-embedded")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cookies-psl-embedded")))]
+    pub fn embedded() -> PublicSuffixList {
+        static EMBEDDED: std::sync::LazyLock<PublicSuffixList> = std::sync::LazyLock::new(|| {
+            PublicSuffixList::from_bytes(include_bytes!("../data/public_suffix_list.dat"))
+                .expect("embedded public suffix list is valid")
+        });
+        EMBEDDED.clone()
+    }
+}
+
 // ===== impl Jar =====
 impl Jar {
+    /// Create an empty jar that rejects cookies for the public suffixes in `list`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `cookies-psl` feature to be enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rquest::cookie::{Jar, PublicSuffixList};
+    ///
+    /// # fn run() -> rquest::Result<()> {
+    /// let list = std::fs::read("public_suffix_list.dat").unwrap();
+    /// let jar = Jar::with_public_suffix_list(PublicSuffixList::from_bytes(&list)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "cookies-psl")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cookies-psl")))]
+    pub fn with_public_suffix_list(list: PublicSuffixList) -> Jar {
+        Jar(RwLock::new(cookie_store::CookieStore::new(Some(list.0))))
+    }
+
     /// Add a cookie str to this jar.
     ///
     /// # Example
@@ -291,8 +417,62 @@ impl Jar {
         let _ = self.0.write().insert_raw(&cookie.0, url);
     }
 
+    /// Get the cookie named `name` that would be sent to `url`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rquest::{cookie::Jar, Url};
+    ///
+    /// let url = "https://yolo.local/account".parse::<Url>().unwrap();
+    /// let jar = Jar::default();
+    /// jar.add_cookie_str("session=abc; Secure; HttpOnly", &url);
+    ///
+    /// let session = jar.get(&url, "session").unwrap();
+    /// assert_eq!(session.value(), "abc");
+    /// assert!(session.http_only());
+    /// ```
+    pub fn get(&self, url: &url::Url, name: &str) -> Option<Cookie<'static>> {
+        self.0
+            .read()
+            .matches(url)
+            .into_iter()
+            .find(|cookie| cookie.name() == name)
+            .map(stored_cookie)
+    }
+
+    /// Get all unexpired cookies in the jar, whatever the url they are for.
+    ///
+    /// The cookies are copied out of the jar, so it can be changed while
+    /// iterating.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rquest::{cookie::Jar, Url};
+    ///
+    /// let url = "https://yolo.local".parse::<Url>().unwrap();
+    /// let jar = Jar::default();
+    /// jar.add_cookie_str("foo=bar", &url);
+    ///
+    /// for cookie in jar.iter() {
+    ///     println!("{}={} expires {:?}", cookie.name(), cookie.value(), cookie.expires());
+    /// }
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = Cookie<'static>> {
+        self.0
+            .read()
+            .iter_unexpired()
+            .map(stored_cookie)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
     /// Removes a `Cookie` from the store, returning the `Cookie` if it was in the jar.
     ///
+    /// The cookie is looked up by the host and path of `url`, which must be
+    /// those the cookie was stored for.
+    ///
     /// # Example
     ///
     /// ```
@@ -305,11 +485,52 @@ impl Jar {
     /// jar.add_cookie_str(cookie, &url);
     ///
     /// // remove the cookie
-    /// jar.remove("foo", &url);
+    /// let removed = jar.remove("foo", &url);
+    /// assert_eq!(removed.unwrap().value(), "bar");
+    /// ```
+    pub fn remove(&self, name: &str, url: &url::Url) -> Option<Cookie<'static>> {
+        let domain = url.host_str()?;
+        self.0
+            .write()
+            .remove(domain, url.path(), name)
+            .as_ref()
+            .map(stored_cookie)
+    }
+
+    /// Remove all cookies of `domain` and its subdomains.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rquest::{cookie::Jar, Url};
+    ///
+    /// let jar = Jar::default();
+    /// jar.add_cookie_str("foo=bar", &"https://www.yolo.local".parse::<Url>().unwrap());
+    /// jar.add_cookie_str("foo=bar", &"https://example.com".parse::<Url>().unwrap());
+    ///
+    /// jar.clear_domain("yolo.local");
+    /// assert_eq!(jar.iter().count(), 1);
     /// ```
-    pub fn remove(&self, name: &str, url: &url::Url) {
-        if let Some(domain) = url.host_str() {
-            self.0.write().remove(domain, url.path(), name);
+    pub fn clear_domain(&self, domain: &str) {
+        let domain = domain.trim_start_matches('.').to_ascii_lowercase();
+        let mut store = self.0.write();
+        let kept = store
+            .iter_any()
+            .filter(|cookie| {
+                !cookie.domain.as_cow().is_some_and(|cookie_domain| {
+                    let cookie_domain = cookie_domain.to_ascii_lowercase();
+                    cookie_domain == domain
+                        || cookie_domain
+                            .strip_suffix(&domain)
+                            .is_some_and(|sub| sub.ends_with('.'))
+                })
+            })
+            .cloned()
+            .map(Ok::<_, std::convert::Infallible>)
+            .collect::<Vec<_>>();
+
+        if let Ok(kept) = cookie_store::CookieStore::from_cookies(kept, true) {
+            *store = kept;
         }
     }
 
@@ -331,6 +552,67 @@ impl Jar {
     pub fn clear(&self) {
         self.0.write().clear();
     }
+
+    /// Write the cookies of this jar as JSON, so they can be restored with
+    /// [`Jar::load_json`].
+    ///
+    /// Each cookie is saved with its domain, path, expiry and flags. Session
+    /// cookies are included, so a saved session survives a process restart.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `json` feature to be enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rquest::cookie::Jar;
+    /// use std::fs::File;
+    /// use std::io::BufReader;
+    ///
+    /// # fn run() -> std::io::Result<()> {
+    /// let jar = Jar::load_json(BufReader::new(File::open("cookies.json")?))?;
+    /// // ... use the jar in a `Client`
+    /// jar.save_json(&mut File::create("cookies.json")?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn save_json<W>(&self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        cookie_store::serde::json::save_incl_expired_and_nonpersistent(&self.0.read(), writer)
+            .map_err(std::io::Error::other)
+    }
+
+    /// Read a jar from cookies written by [`Jar::save_json`].
+    ///
+    /// Cookies that have expired since they were saved are skipped.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `json` feature to be enabled.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn load_json<R>(reader: R) -> std::io::Result<Jar>
+    where
+        R: std::io::BufRead,
+    {
+        cookie_store::serde::json::load(reader)
+            .map(|store| Jar(RwLock::new(store)))
+            .map_err(std::io::Error::other)
+    }
+}
+
+/// Copy a cookie out of the jar, with its expiry as an absolute time.
+fn stored_cookie(cookie: &cookie_store::Cookie<'static>) -> Cookie<'static> {
+    let mut raw = RawCookie::clone(cookie);
+    if let cookie_store::CookieExpiration::AtUtc(at) = cookie.expires {
+        raw.set_expires(at);
+    }
+    Cookie(raw)
 }
 
 impl CookieStore for Jar {
@@ -363,7 +645,18 @@ impl CookieStore for Jar {
 }
 
 impl Default for Jar {
+    /// Create an empty jar.
+    ///
+    /// With the `cookies-psl-embedded` feature, the jar rejects cookies for the
+    /// public suffixes of the embedded list.
     fn default() -> Self {
-        Self(RwLock::new(cookie_store::CookieStore::default()))
+        #[cfg(feature = "cookies-psl-embedded")]
+        {
+            Self::with_public_suffix_list(PublicSuffixList::embedded())
+        }
+        #[cfg(not(feature = "cookies-psl-embedded"))]
+        {
+            Self(RwLock::new(cookie_store::CookieStore::default()))
+        }
     }
 }
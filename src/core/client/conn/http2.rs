@@ -193,6 +193,12 @@ where
         }
     }
 
+    /// Provide an executor to execute background HTTP2 tasks.
+    pub fn executor(&mut self, exec: Ex) -> &mut Builder<Ex> {
+        self.exec = exec;
+        self
+    }
+
     /// Provide a timer to execute background HTTP2 tasks.
     pub fn timer<M>(&mut self, timer: M) -> &mut Builder<Ex>
     where
@@ -183,6 +183,22 @@ impl fmt::Debug for GaiAddrs {
     }
 }
 
+/// The IP versions of the addresses to connect to, and which is tried first.
+///
+/// By default, both are used and IPv6 addresses are tried first, falling back
+/// to IPv4 as described by [RFC 6555 (Happy Eyeballs)](https://tools.ietf.org/html/rfc6555).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpStrategy {
+    /// Only connect to IPv4 addresses.
+    Ipv4Only,
+    /// Only connect to IPv6 addresses.
+    Ipv6Only,
+    /// Connect to IPv4 addresses first, falling back to IPv6.
+    Ipv4ThenIpv6,
+    /// Connect to IPv6 addresses first, falling back to IPv4.
+    Ipv6ThenIpv4,
+}
+
 pub(super) struct SocketAddrs {
     iter: vec::IntoIter<SocketAddr>,
 }
@@ -215,6 +231,23 @@ impl SocketAddrs {
         SocketAddrs::new(self.iter.filter(predicate).collect())
     }
 
+    /// Keep and order the addresses allowed by `strategy`.
+    pub(super) fn with_strategy(self, strategy: IpStrategy) -> SocketAddrs {
+        match strategy {
+            IpStrategy::Ipv4Only => self.filter(SocketAddr::is_ipv4),
+            IpStrategy::Ipv6Only => self.filter(SocketAddr::is_ipv6),
+            IpStrategy::Ipv4ThenIpv6 | IpStrategy::Ipv6ThenIpv4 => {
+                let first = match strategy {
+                    IpStrategy::Ipv4ThenIpv6 => SocketAddr::is_ipv4,
+                    _ => SocketAddr::is_ipv6,
+                };
+                let (mut addrs, rest) = self.iter.partition::<Vec<_>, _>(first);
+                addrs.extend(rest);
+                SocketAddrs::new(addrs)
+            }
+        }
+    }
+
     pub(super) fn split_by_preference(
         self,
         local_addr_ipv4: Option<Ipv4Addr>,
@@ -351,6 +384,27 @@ mod tests {
         assert!(fallback.is_empty());
     }
 
+    #[test]
+    fn test_ip_addrs_with_strategy() {
+        let v4_addr: SocketAddr = (Ipv4Addr::new(127, 0, 0, 1), 80).into();
+        let v6_addr: SocketAddr = (Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1), 80).into();
+        let addrs = || SocketAddrs::new(vec![v6_addr, v4_addr, v6_addr]);
+
+        let only: Vec<_> = addrs().with_strategy(IpStrategy::Ipv4Only).collect();
+        assert_eq!(only, [v4_addr]);
+
+        let only: Vec<_> = addrs().with_strategy(IpStrategy::Ipv6Only).collect();
+        assert_eq!(only, [v6_addr, v6_addr]);
+
+        let ordered: Vec<_> = addrs().with_strategy(IpStrategy::Ipv4ThenIpv6).collect();
+        assert_eq!(ordered, [v4_addr, v6_addr, v6_addr]);
+
+        let ordered: Vec<_> = SocketAddrs::new(vec![v4_addr, v6_addr])
+            .with_strategy(IpStrategy::Ipv6ThenIpv4)
+            .collect();
+        assert_eq!(ordered, [v6_addr, v4_addr]);
+    }
+
     #[test]
     fn test_name_from_str() {
         const DOMAIN: &str = "test.example.com";
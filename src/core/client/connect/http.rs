@@ -16,7 +16,7 @@ use socket2::TcpKeepalive;
 use tokio::net::{TcpSocket, TcpStream};
 use tokio::time::Sleep;
 
-use super::dns::{self, GaiResolver, Resolve, resolve};
+use super::dns::{self, GaiResolver, IpStrategy, Resolve, resolve};
 use super::{Connected, Connection};
 use crate::core::rt::TokioIo;
 
@@ -66,8 +66,10 @@ pub struct HttpInfo {
 #[derive(Clone)]
 struct Config {
     connect_timeout: Option<Duration>,
+    dns_timeout: Option<Duration>,
     enforce_http: bool,
     happy_eyeballs_timeout: Option<Duration>,
+    ip_strategy: Option<IpStrategy>,
     tcp_keepalive_config: TcpKeepaliveConfig,
     local_address_ipv4: Option<Ipv4Addr>,
     local_address_ipv6: Option<Ipv6Addr>,
@@ -228,8 +230,10 @@ impl<R> HttpConnector<R> {
         HttpConnector {
             config: Arc::new(Config {
                 connect_timeout: None,
+                dns_timeout: None,
                 enforce_http: true,
                 happy_eyeballs_timeout: Some(Duration::from_millis(300)),
+                ip_strategy: None,
                 tcp_keepalive_config: TcpKeepaliveConfig::default(),
                 local_address_ipv4: None,
                 local_address_ipv6: None,
@@ -349,6 +353,14 @@ impl<R> HttpConnector<R> {
         self.config_mut().connect_timeout = dur;
     }
 
+    /// Set the timeout for resolving the host name.
+    ///
+    /// Default is `None`.
+    #[inline]
+    pub fn set_dns_timeout(&mut self, dur: Option<Duration>) {
+        self.config_mut().dns_timeout = dur;
+    }
+
     /// Set timeout for [RFC 6555 (Happy Eyeballs)][RFC 6555] algorithm.
     ///
     /// If hostname resolves to both IPv4 and IPv6 addresses and connection
@@ -366,6 +378,16 @@ impl<R> HttpConnector<R> {
         self.config_mut().happy_eyeballs_timeout = dur;
     }
 
+    /// Set the IP versions of the addresses to connect to, and which is tried first.
+    ///
+    /// If `None`, IPv6 addresses are preferred and IPv4 addresses are the fallback.
+    ///
+    /// Default is `None`.
+    #[inline]
+    pub fn set_ip_strategy(&mut self, strategy: Option<IpStrategy>) {
+        self.config_mut().ip_strategy = strategy;
+    }
+
     /// Set that all socket have `SO_REUSEADDR` set to the supplied value `reuse_address`.
     ///
     /// Default is `false`.
@@ -540,9 +562,24 @@ where
         let addrs = if let Some(addrs) = dns::SocketAddrs::try_parse(host, port) {
             addrs
         } else {
-            let addrs = resolve(&mut self.resolver, dns::Name::new(host.into()))
-                .await
-                .map_err(ConnectError::dns)?;
+            let resolving = resolve(&mut self.resolver, dns::Name::new(host.into()));
+            #[cfg(feature = "tracing")]
+            let resolving = tracing::Instrument::instrument(
+                resolving,
+                tracing::debug_span!("dns", server.address = host),
+            );
+            let addrs = match config.dns_timeout {
+                Some(timeout) => tokio::time::timeout(timeout, resolving)
+                    .await
+                    .map_err(|_| {
+                        ConnectError::dns(io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            "dns resolution timed out",
+                        ))
+                    })?,
+                None => resolving.await,
+            }
+            .map_err(ConnectError::dns)?;
             let addrs = addrs
                 .map(|mut addr| {
                     set_port(&mut addr, port, dst.port().is_some());
@@ -553,9 +590,29 @@ where
             dns::SocketAddrs::new(addrs)
         };
 
+        let addrs = match config.ip_strategy {
+            Some(strategy) => {
+                let addrs = addrs.with_strategy(strategy);
+                if addrs.is_empty() {
+                    return Err(ConnectError::dns(format!(
+                        "{} has no address allowed by {:?}",
+                        host, strategy
+                    )));
+                }
+                addrs
+            }
+            None => addrs,
+        };
+
         let c = ConnectingTcp::new(addrs, config);
 
+        #[cfg(feature = "tracing")]
+        let sock =
+            tracing::Instrument::instrument(c.connect(), tracing::debug_span!("tcp_connect"))
+                .await?;
+        #[cfg(not(feature = "tracing"))]
         let sock = c.connect().await?;
+        debug!("connected to {:?}", sock.peer_addr().ok());
 
         if let Err(e) = sock.set_nodelay(config.nodelay) {
             warn!("tcp set_nodelay error: {}", e);
@@ -653,6 +710,11 @@ impl ConnectError {
         ConnectError::new("dns error", cause)
     }
 
+    /// Whether resolving the host failed.
+    pub(crate) fn is_dns(&self) -> bool {
+        &*self.msg == "dns error"
+    }
+
     fn m<S, E>(msg: S) -> impl FnOnce(E) -> ConnectError
     where
         S: Into<Box<str>>,
@@ -702,8 +764,11 @@ struct ConnectingTcp<'a> {
 impl<'a> ConnectingTcp<'a> {
     fn new(remote_addrs: dns::SocketAddrs, config: &'a Config) -> Self {
         if let Some(fallback_timeout) = config.happy_eyeballs_timeout {
-            let (preferred_addrs, fallback_addrs) = remote_addrs
+            let (mut preferred_addrs, mut fallback_addrs) = remote_addrs
                 .split_by_preference(config.local_address_ipv4, config.local_address_ipv6);
+            if config.ip_strategy == Some(IpStrategy::Ipv4ThenIpv6) {
+                std::mem::swap(&mut preferred_addrs, &mut fallback_addrs);
+            }
             if fallback_addrs.is_empty() {
                 return ConnectingTcp {
                     preferred: ConnectingTcpRemote::new(preferred_addrs, config.connect_timeout),
@@ -73,6 +73,7 @@ use std::{
 
 use ::http::Extensions;
 
+pub(crate) use self::http::ConnectError;
 pub use self::http::{HttpConnector, HttpInfo};
 
 pub mod dns;
@@ -3,7 +3,7 @@ mod socks;
 mod tunnel;
 
 pub use self::socks::{SocksV4, SocksV5};
-pub use self::tunnel::Tunnel;
+pub use self::tunnel::{Tunnel, TunnelError};
 
 mod tests {
     use tokio::io::{AsyncReadExt, AsyncWriteExt};
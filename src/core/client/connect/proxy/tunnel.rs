@@ -4,8 +4,13 @@ use std::marker::{PhantomData, Unpin};
 use std::pin::Pin;
 use std::task::{self, Poll};
 
+use crate::client::digest::DigestAuth;
 use crate::core::rt::{Read, Write};
-use http::{HeaderMap, HeaderValue, Uri};
+#[cfg(feature = "ntlm")]
+use crate::ntlm::{self, NtlmCredentials};
+use crate::proxy::ConnectResponse;
+use http::header::HeaderName;
+use http::{HeaderMap, HeaderValue, Method, StatusCode, Uri};
 use pin_project_lite::pin_project;
 use tower_service::Service;
 
@@ -19,6 +24,9 @@ pub struct Tunnel<C> {
     headers: Headers,
     inner: C,
     proxy_dst: Uri,
+    digest: Option<DigestAuth>,
+    #[cfg(feature = "ntlm")]
+    ntlm: Option<NtlmCredentials>,
 }
 
 #[derive(Clone, Debug)]
@@ -33,10 +41,10 @@ pub enum TunnelError {
     ConnectFailed(Box<dyn StdError + Send + Sync>),
     Io(std::io::Error),
     MissingHost,
-    ProxyAuthRequired,
+    ProxyAuthRequired(Option<ConnectResponse>),
     ProxyHeadersTooLong,
     TunnelUnexpectedEof,
-    TunnelUnsuccessful,
+    TunnelUnsuccessful(Option<ConnectResponse>),
 }
 
 pin_project! {
@@ -70,6 +78,9 @@ impl<C> Tunnel<C> {
             headers: Headers::Empty,
             inner: connector,
             proxy_dst,
+            digest: None,
+            #[cfg(feature = "ntlm")]
+            ntlm: None,
         }
     }
 
@@ -77,18 +88,22 @@ impl<C> Tunnel<C> {
     pub fn with_auth(mut self, mut auth: HeaderValue) -> Self {
         // just in case the user forgot
         auth.set_sensitive(true);
-        match self.headers {
-            Headers::Empty => {
-                self.headers = Headers::Auth(auth);
-            }
-            Headers::Auth(ref mut existing) => {
-                *existing = auth;
-            }
-            Headers::Extra(ref mut extra) => {
-                extra.insert(http::header::PROXY_AUTHORIZATION, auth);
-            }
-        }
+        self.headers.set_auth(auth);
+        self
+    }
+
+    /// Authenticate to the proxy with Digest, answering its challenge on the
+    /// connection of the CONNECT request.
+    pub fn with_digest(mut self, digest: DigestAuth) -> Self {
+        self.digest = Some(digest);
+        self
+    }
 
+    /// Authenticate to the proxy with NTLM, answering its challenge on the
+    /// connection of the CONNECT request.
+    #[cfg(feature = "ntlm")]
+    pub fn with_ntlm(mut self, credentials: NtlmCredentials) -> Self {
+        self.ntlm = Some(credentials);
         self
     }
 
@@ -135,19 +150,28 @@ where
     fn call(&mut self, dst: Uri) -> Self::Future {
         let connecting = self.inner.call(self.proxy_dst.clone());
         let headers = self.headers.clone();
+        let digest = self.digest.clone();
+        #[cfg(feature = "ntlm")]
+        let ntlm = self.ntlm.clone();
 
         Tunneling {
             fut: Box::pin(async move {
                 let conn = connecting
                     .await
                     .map_err(|e| TunnelError::ConnectFailed(e.into()))?;
-                tunnel(
-                    conn,
-                    dst.host().ok_or(TunnelError::MissingHost)?,
-                    dst.port().map(|p| p.as_u16()).unwrap_or(443),
-                    &headers,
-                )
-                .await
+                let host = dst.host().ok_or(TunnelError::MissingHost)?;
+                let port = dst.port().map(|p| p.as_u16()).unwrap_or(443);
+
+                #[cfg(feature = "ntlm")]
+                if let Some(credentials) = ntlm {
+                    return ntlm_tunnel(conn, host, port, &headers, &credentials).await;
+                }
+
+                if let Some(digest) = digest {
+                    return digest_tunnel(conn, host, port, &headers, &digest).await;
+                }
+
+                tunnel(conn, host, port, &headers).await
             }),
             _marker: PhantomData,
         }
@@ -165,7 +189,209 @@ where
     }
 }
 
+impl Headers {
+    fn set_auth(&mut self, auth: HeaderValue) {
+        match self {
+            Headers::Empty => {
+                *self = Headers::Auth(auth);
+            }
+            Headers::Auth(existing) => {
+                *existing = auth;
+            }
+            Headers::Extra(extra) => {
+                extra.insert(http::header::PROXY_AUTHORIZATION, auth);
+            }
+        }
+    }
+}
+
 async fn tunnel<T>(mut conn: T, host: &str, port: u16, headers: &Headers) -> Result<T, TunnelError>
+where
+    T: Read + Write + Unpin,
+{
+    write_connect(&mut conn, host, port, headers).await?;
+
+    let mut buf = [0; 8192];
+    let (status, headers, _) = read_head(&mut conn, &mut buf).await?;
+    match status {
+        200 => Ok(conn),
+        407 => Err(TunnelError::ProxyAuthRequired(rejected(status, headers))),
+        _ => Err(TunnelError::TunnelUnsuccessful(rejected(status, headers))),
+    }
+}
+
+/// Keep the response of a proxy that refused to open the tunnel, for the error.
+fn rejected(status: u16, headers: HeaderMap) -> Option<ConnectResponse> {
+    StatusCode::from_u16(status)
+        .ok()
+        .map(|status| ConnectResponse::new(status, headers))
+}
+
+/// Open the tunnel answering a Digest challenge: a CONNECT request without
+/// credentials, and, when the proxy asks for them, another one on the same
+/// connection answering its challenge.
+async fn digest_tunnel<T>(
+    mut conn: T,
+    host: &str,
+    port: u16,
+    headers: &Headers,
+    digest: &DigestAuth,
+) -> Result<T, TunnelError>
+where
+    T: Read + Write + Unpin,
+{
+    write_connect(&mut conn, host, port, headers).await?;
+
+    let challenge = match read_response(&mut conn).await? {
+        (200, _) => return Ok(conn),
+        (407, challenge) => challenge,
+        (status, headers) => {
+            return Err(TunnelError::TunnelUnsuccessful(rejected(status, headers)));
+        }
+    };
+
+    let uri = format!("{host}:{port}");
+    let Some(auth) = digest.authorize_proxy(&challenge, &Method::CONNECT, &uri) else {
+        return Err(TunnelError::ProxyAuthRequired(rejected(407, challenge)));
+    };
+
+    let mut authenticate = headers.clone();
+    authenticate.set_auth(auth);
+    tunnel(conn, host, port, &authenticate).await
+}
+
+/// Open the tunnel with the NTLM handshake: a CONNECT request carrying the
+/// negotiate message, and, when the proxy answers with its challenge, another
+/// one on the same connection carrying the authenticate message.
+#[cfg(feature = "ntlm")]
+async fn ntlm_tunnel<T>(
+    mut conn: T,
+    host: &str,
+    port: u16,
+    headers: &Headers,
+    credentials: &NtlmCredentials,
+) -> Result<T, TunnelError>
+where
+    T: Read + Write + Unpin,
+{
+    use http::header::PROXY_AUTHENTICATE;
+
+    let mut scheme = ntlm::Scheme::Ntlm;
+    loop {
+        let mut negotiate = headers.clone();
+        negotiate.set_auth(credentials.negotiate(scheme));
+        write_connect(&mut conn, host, port, &negotiate).await?;
+
+        let challenge = match read_response(&mut conn).await? {
+            (200, _) => return Ok(conn),
+            (407, challenge) => challenge,
+            (status, headers) => {
+                return Err(TunnelError::TunnelUnsuccessful(rejected(status, headers)));
+            }
+        };
+
+        if let Some(auth) = credentials.authenticate(&challenge, &PROXY_AUTHENTICATE) {
+            let mut authenticate = headers.clone();
+            authenticate.set_auth(auth);
+            return tunnel(conn, host, port, &authenticate).await;
+        }
+
+        // the proxy only takes the tokens under the other scheme
+        match ntlm::Scheme::offered(&challenge, &PROXY_AUTHENTICATE) {
+            Some(offered) if offered != scheme => scheme = offered,
+            _ => return Err(TunnelError::ProxyAuthRequired(rejected(407, challenge))),
+        }
+    }
+}
+
+/// Read the response to a CONNECT request, returning its status and headers.
+///
+/// The body of a `407` response is read too, so the connection can carry the
+/// next request of the handshake.
+async fn read_response<T>(conn: &mut T) -> Result<(u16, HeaderMap), TunnelError>
+where
+    T: Read + Write + Unpin,
+{
+    use http::header::{CONNECTION, CONTENT_LENGTH, TRANSFER_ENCODING};
+
+    let mut buf = [0; 8192];
+    let (status, headers, mut body) = read_head(conn, &mut buf).await?;
+
+    if status != 407 {
+        return Ok((status, headers));
+    }
+
+    // the handshake authenticates a connection, so it can't go on without one
+    let closing = headers
+        .get(CONNECTION)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("close"));
+    if closing || headers.contains_key(TRANSFER_ENCODING) {
+        return Err(TunnelError::ProxyAuthRequired(rejected(status, headers)));
+    }
+
+    let len = headers
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok()?.parse::<usize>().ok())
+        .unwrap_or(0);
+    while body < len {
+        let n = crate::core::rt::read(conn, &mut buf)
+            .await
+            .map_err(TunnelError::Io)?;
+        if n == 0 {
+            return Err(TunnelError::TunnelUnexpectedEof);
+        }
+        body += n;
+    }
+
+    Ok((status, headers))
+}
+
+/// Read the head of the response to a CONNECT request into `buf`, returning
+/// its status, its headers, and how many bytes of its body were read along.
+async fn read_head<T>(conn: &mut T, buf: &mut [u8]) -> Result<(u16, HeaderMap, usize), TunnelError>
+where
+    T: Read + Write + Unpin,
+{
+    let mut pos = 0;
+
+    loop {
+        let n = crate::core::rt::read(conn, &mut buf[pos..])
+            .await
+            .map_err(TunnelError::Io)?;
+        if n == 0 {
+            return Err(TunnelError::TunnelUnexpectedEof);
+        }
+        pos += n;
+
+        let mut parsed = [httparse::EMPTY_HEADER; 64];
+        let mut response = httparse::Response::new(&mut parsed);
+        match response.parse(&buf[..pos]) {
+            Ok(httparse::Status::Complete(len)) => {
+                let status = response.code.ok_or(TunnelError::TunnelUnsuccessful(None))?;
+                let mut headers = HeaderMap::new();
+                for header in response.headers.iter() {
+                    let name = HeaderName::from_bytes(header.name.as_bytes());
+                    let value = HeaderValue::from_bytes(header.value);
+                    if let (Ok(name), Ok(value)) = (name, value) {
+                        headers.append(name, value);
+                    }
+                }
+                return Ok((status, headers, pos - len));
+            }
+            Ok(httparse::Status::Partial) if pos < buf.len() => {}
+            Ok(httparse::Status::Partial) => return Err(TunnelError::ProxyHeadersTooLong),
+            Err(_) => return Err(TunnelError::TunnelUnsuccessful(None)),
+        }
+    }
+}
+
+async fn write_connect<T>(
+    conn: &mut T,
+    host: &str,
+    port: u16,
+    headers: &Headers,
+) -> Result<(), TunnelError>
 where
     T: Read + Write + Unpin,
 {
@@ -197,38 +423,9 @@ where
     // headers end
     buf.extend_from_slice(b"\r\n");
 
-    crate::core::rt::write_all(&mut conn, &buf)
+    crate::core::rt::write_all(conn, &buf)
         .await
-        .map_err(TunnelError::Io)?;
-
-    let mut buf = [0; 8192];
-    let mut pos = 0;
-
-    loop {
-        let n = crate::core::rt::read(&mut conn, &mut buf[pos..])
-            .await
-            .map_err(TunnelError::Io)?;
-
-        if n == 0 {
-            return Err(TunnelError::TunnelUnexpectedEof);
-        }
-        pos += n;
-
-        let recvd = &buf[..pos];
-        if recvd.starts_with(b"HTTP/1.1 200") || recvd.starts_with(b"HTTP/1.0 200") {
-            if recvd.ends_with(b"\r\n\r\n") {
-                return Ok(conn);
-            }
-            if pos == buf.len() {
-                return Err(TunnelError::ProxyHeadersTooLong);
-            }
-        // else read more
-        } else if recvd.starts_with(b"HTTP/1.1 407") {
-            return Err(TunnelError::ProxyAuthRequired);
-        } else {
-            return Err(TunnelError::TunnelUnsuccessful);
-        }
-    }
+        .map_err(TunnelError::Io)
 }
 
 impl std::fmt::Display for TunnelError {
@@ -237,13 +434,29 @@ impl std::fmt::Display for TunnelError {
 
         f.write_str(match self {
             TunnelError::MissingHost => "missing destination host",
-            TunnelError::ProxyAuthRequired => "proxy authorization required",
+            TunnelError::ProxyAuthRequired(_) => "proxy authorization required",
             TunnelError::ProxyHeadersTooLong => "proxy response headers too long",
             TunnelError::TunnelUnexpectedEof => "unexpected end of file",
-            TunnelError::TunnelUnsuccessful => "unsuccessful",
+            TunnelError::TunnelUnsuccessful(_) => "unsuccessful",
             TunnelError::ConnectFailed(_) => "failed to create underlying connection",
             TunnelError::Io(_) => "io error establishing tunnel",
-        })
+        })?;
+
+        if let Some(response) = self.response() {
+            write!(f, " ({})", response.status())?;
+        }
+        Ok(())
+    }
+}
+
+impl TunnelError {
+    /// The response of the proxy, if it refused to open the tunnel.
+    pub(crate) fn response(&self) -> Option<&ConnectResponse> {
+        match self {
+            TunnelError::ProxyAuthRequired(response)
+            | TunnelError::TunnelUnsuccessful(response) => response.as_ref(),
+            _ => None,
+        }
     }
 }
 
@@ -13,24 +13,28 @@ mod network;
 // designed.
 mod pool;
 mod request;
+mod stats;
 
 use std::error::Error as StdError;
 use std::fmt;
 use std::future::Future;
 use std::num::NonZeroUsize;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{self, Poll};
 use std::time::Duration;
 
+use crate::client::VerboseConfig;
 use crate::core::client::conn::TrySendError as ConnTrySendError;
 use crate::core::common;
-use crate::core::header::{HOST, HeaderValue};
+use crate::core::header::{CONNECTION, HOST, HeaderValue};
 use crate::core::rt::Timer;
 use crate::core::{Method, Request, Response, Uri, Version, body::Body};
 use crate::http1::Http1Config;
 use crate::http2::Http2Config;
 use crate::tls::AlpnProtos;
 
+use antidote::Mutex;
 use futures_util::future::{self, Either, FutureExt, TryFutureExt};
 use http::uri::Scheme;
 use sync_wrapper::SyncWrapper;
@@ -44,6 +48,8 @@ use common::{Exec, Lazy, lazy as hyper_lazy, timer};
 pub use dst::Dst;
 pub use network::{NetworkScheme, NetworkSchemeBuilder};
 pub use request::InnerRequest;
+pub(crate) use request::sort_headers;
+pub use stats::{EvictReason, HostPoolStats, PoolEvent, PoolEventKind, PoolStats};
 
 type BoxSendFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
 
@@ -55,6 +61,7 @@ pub struct Client<C, B> {
     config: Config,
     connector: C,
     exec: Exec,
+    verbose: Option<VerboseConfig>,
     h1_builder: crate::core::client::conn::http1::Builder,
     h2_builder: crate::core::client::conn::http2::Builder<Exec>,
     pool: pool::Pool<PoolClient<B>, PoolKey>,
@@ -136,6 +143,43 @@ struct PoolKey {
     network: NetworkScheme,
 }
 
+/// A connection held out of the pool for all the requests of a handshake.
+///
+/// Connection-based authentication, such as NTLM, authenticates the
+/// connection rather than the request, so each step of the handshake must be
+/// sent on the connection that started it. The first request carrying this
+/// extension opens a connection of its own, and the next ones take it over
+/// once it is idle again. The connection is never returned to the pool, and
+/// closes when the last request or response holding the extension is dropped.
+pub(crate) struct PinnedConnection<B: Send + 'static>(
+    Arc<Mutex<Option<pool::Pooled<PoolClient<B>, PoolKey>>>>,
+);
+
+impl<B: Send + 'static> PinnedConnection<B> {
+    #[cfg_attr(not(feature = "ntlm"), allow(dead_code))]
+    pub(crate) fn new() -> Self {
+        PinnedConnection(Arc::new(Mutex::new(None)))
+    }
+
+    /// Take the pinned connection, if it leads to `dst`.
+    fn take(&self, dst: &Dst) -> Option<pool::Pooled<PoolClient<B>, PoolKey>> {
+        self.0
+            .lock()
+            .take()
+            .filter(|pooled| pooled.key() == dst.pool_key())
+    }
+
+    fn put(&self, pooled: pool::Pooled<PoolClient<B>, PoolKey>) {
+        *self.0.lock() = Some(pooled);
+    }
+}
+
+impl<B: Send + 'static> Clone for PinnedConnection<B> {
+    fn clone(&self) -> Self {
+        PinnedConnection(self.0.clone())
+    }
+}
+
 #[allow(clippy::large_enum_variant)]
 enum TrySendError<B> {
     Retryable {
@@ -246,6 +290,24 @@ where
         ResponseFuture::new(self.clone().send_request(req, ctx))
     }
 
+    /// Establishes a connection to the origin of `uri`, and puts it in the
+    /// pool, for a later request to reuse.
+    pub(crate) async fn preconnect(
+        &self,
+        mut uri: Uri,
+        network_scheme: NetworkScheme,
+    ) -> Result<(), Error> {
+        let dst = Dst::new(&mut uri, false, network_scheme, None)?;
+        let mut pooled = self.connection_for(dst).await?;
+
+        // An HTTP/1 connection is only put back in the pool once it's ready
+        // for a request.
+        if !pooled.is_http2() && pooled.is_pool_enabled() && !pooled.is_ready() {
+            std::future::poll_fn(|cx| pooled.poll_ready(cx)).await?;
+        }
+        Ok(())
+    }
+
     async fn send_request(
         self,
         mut req: Request<B>,
@@ -284,9 +346,24 @@ where
         mut req: Request<B>,
         dst: Dst,
     ) -> Result<Response<crate::core::body::Incoming>, TrySendError<B>> {
-        let mut pooled = self
-            .connection_for(dst)
-            .await
+        // A request closing its connection gets one of its own, rather than
+        // taking an idle one from the pool that could serve other requests.
+        let close = req
+            .headers()
+            .get(CONNECTION)
+            .is_some_and(crate::core::headers::connection_close);
+        //
+        // A pinned request does too, or goes on the connection its handshake
+        // started on.
+        let pinned = req.extensions().get::<PinnedConnection<B>>().cloned();
+        let connection = match pinned.as_ref().and_then(|pinned| pinned.take(&dst)) {
+            Some(mut pooled) => std::future::poll_fn(|cx| pooled.poll_ready(cx))
+                .await
+                .map(|()| pooled),
+            None if close || pinned.is_some() => self.connect_to(dst, false).await,
+            None => self.connection_for(dst).await,
+        };
+        let mut pooled = connection
             // `connection_for` already retries checkout errors, so if
             // it returns an error, there's not much else to retry
             .map_err(TrySendError::Nope)?;
@@ -329,6 +406,16 @@ where
             authority_form(req.uri_mut());
         }
 
+        if let Some(ref verbose) = self.verbose {
+            let version = if pooled.is_http2() {
+                Version::HTTP_2
+            } else {
+                req.version()
+            };
+            verbose.request(&req, version);
+        }
+
+        pooled.usage.record_request();
         let mut res = match pooled.try_send_request(req).await {
             Ok(res) => res,
             Err(mut err) => {
@@ -353,6 +440,16 @@ where
             extra.set(res.extensions_mut());
         }
 
+        if let Some(ref verbose) = self.verbose {
+            verbose.response(&res);
+        }
+
+        // A pinned connection waits for the next request of its handshake.
+        if let Some(pinned) = pinned {
+            pinned.put(pooled);
+            return Ok(res);
+        }
+
         // If pooled is HTTP/2, we can toss this reference immediately.
         //
         // when pooled is dropped, it will try to insert back into the
@@ -416,7 +513,7 @@ where
         // Return a single connection if pooling is not enabled
         if !self.pool.is_enabled() {
             return self
-                .connect_to(dst)
+                .connect_to(dst, true)
                 .await
                 .map_err(ClientConnectError::Normal);
         }
@@ -435,7 +532,7 @@ where
         //   connection future is spawned into the runtime to complete,
         //   and then be inserted into the pool as an idle connection.
         let checkout = self.pool.checkout(dst.pool_key().clone());
-        let connect = self.connect_to(dst);
+        let connect = self.connect_to(dst, true);
         let is_ver_h2 = self.config.ver == Ver::Http2;
 
         // The order of the `select` is depended on below...
@@ -501,9 +598,12 @@ where
         }
     }
 
+    /// Connects to `dst`, for the pool unless `pooled` is false, in which case
+    /// the connection is closed once the request it was made for completes.
     fn connect_to(
         &self,
         dst: Dst,
+        pooled: bool,
     ) -> impl Lazy<Output = Result<pool::Pooled<PoolClient<B>, PoolKey>, Error>> + Send + Unpin + 'static
     {
         let executor = self.exec.clone();
@@ -524,7 +624,9 @@ where
             // If the pool_key is for HTTP/2, and there is already a
             // connection being established, then this can't take a
             // second lock. The "connect_to" future is Canceled.
-            let connecting = match pool.connecting(dst.pool_key(), ver) {
+            // An unpooled connection doesn't take the HTTP/2 connecting lock.
+            let lock_ver = if pooled { ver } else { Ver::Auto };
+            let connecting = match pool.connecting(dst.pool_key(), lock_ver) {
                 Some(lock) => lock,
                 None => {
                     let canceled = e!(Canceled);
@@ -542,7 +644,7 @@ where
                         // If ALPN is h2 and we aren't http2_only already,
                         // then we need to convert our pool checkout into
                         // a single HTTP2 one.
-                        let connecting = if connected.alpn == Alpn::H2 && !is_ver_h2 {
+                        let connecting = if connected.alpn == Alpn::H2 && !is_ver_h2 && pooled {
                             match connecting.alpn_h2(&pool) {
                                 Some(lock) => {
                                     trace!("ALPN negotiated h2, updating pool");
@@ -601,19 +703,27 @@ where
                                 }
                             };
 
-                            Ok(pool.pooled(
-                                connecting,
-                                PoolClient {
-                                    conn_info: connected,
-                                    tx,
-                                },
-                            ))
+                            let client = PoolClient {
+                                conn_info: connected,
+                                tx,
+                                usage: Arc::new(pool::Usage::default()),
+                            };
+                            Ok(if pooled {
+                                pool.pooled(connecting, client)
+                            } else {
+                                pool.unpooled(connecting, client)
+                            })
                         }))
                     }),
             )
         })
     }
 
+    /// Take a snapshot of the connection pool statistics.
+    pub(crate) fn pool_stats(&self) -> PoolStats {
+        PoolStats::new(self.pool.stats())
+    }
+
     #[inline]
     pub(crate) fn connector_mut(&mut self) -> &mut C {
         &mut self.connector
@@ -680,6 +790,7 @@ impl<C: Clone, B> Clone for Client<C, B> {
             h2_builder: self.h2_builder.clone(),
             connector: self.connector.clone(),
             pool: self.pool.clone(),
+            verbose: self.verbose.clone(),
         }
     }
 }
@@ -729,6 +840,7 @@ impl Future for ResponseFuture {
 struct PoolClient<B> {
     conn_info: Connected,
     tx: PoolTx<B>,
+    usage: Arc<pool::Usage>,
 }
 
 enum PoolTx<B> {
@@ -802,16 +914,19 @@ where
             PoolTx::Http1(tx) => pool::Reservation::Unique(PoolClient {
                 conn_info: self.conn_info,
                 tx: PoolTx::Http1(tx),
+                usage: self.usage,
             }),
 
             PoolTx::Http2(tx) => {
                 let b = PoolClient {
                     conn_info: self.conn_info.clone(),
                     tx: PoolTx::Http2(tx.clone()),
+                    usage: self.usage.clone(),
                 };
                 let a = PoolClient {
                     conn_info: self.conn_info,
                     tx: PoolTx::Http2(tx),
+                    usage: self.usage,
                 };
                 pool::Reservation::Shared(a, b)
             }
@@ -821,6 +936,10 @@ where
     fn can_share(&self) -> bool {
         self.is_http2()
     }
+
+    fn usage(&self) -> &pool::Usage {
+        &self.usage
+    }
 }
 
 enum ClientConnectError {
@@ -932,7 +1051,9 @@ pub struct Builder {
     h1_builder: crate::core::client::conn::http1::Builder,
     h2_builder: crate::core::client::conn::http2::Builder<Exec>,
     pool_config: pool::Config,
+    pool_events: Option<Arc<dyn Fn(&PoolEvent) + Send + Sync>>,
     pool_timer: Option<timer::Timer>,
+    verbose: Option<VerboseConfig>,
 }
 
 impl Builder {
@@ -956,8 +1077,12 @@ impl Builder {
                 idle_timeout: Some(Duration::from_secs(90)),
                 max_idle_per_host: usize::MAX,
                 max_pool_size: None,
+                max_lifetime: None,
+                max_requests: None,
             },
+            pool_events: None,
             pool_timer: None,
+            verbose: None,
         }
     }
     /// Set an optional timeout for idle sockets being kept-alive.
@@ -1009,6 +1134,36 @@ impl Builder {
         self
     }
 
+    /// Sets the maximum age of a pooled connection.
+    ///
+    /// Older connections finish their current request, and are then closed
+    /// instead of being reused.
+    ///
+    /// Default is `None` (no limit).
+    pub fn pool_max_lifetime(&mut self, max_lifetime: Option<Duration>) -> &mut Self {
+        self.pool_config.max_lifetime = max_lifetime;
+        self
+    }
+
+    /// Sets the maximum number of requests sent on a pooled connection.
+    ///
+    /// Default is `None` (no limit).
+    pub fn pool_max_requests(&mut self, max_requests: Option<NonZeroUsize>) -> &mut Self {
+        self.pool_config.max_requests = max_requests;
+        self
+    }
+
+    /// Set a callback notified when a connection is created, reused, evicted or closed.
+    ///
+    /// Default is `None`.
+    pub fn pool_events(
+        &mut self,
+        on_event: Option<Arc<dyn Fn(&PoolEvent) + Send + Sync>>,
+    ) -> &mut Self {
+        self.pool_events = on_event;
+        self
+    }
+
     /// Set whether the connection **must** use HTTP/2.
     ///
     /// The destination must either allow HTTP2 Prior Knowledge, or the
@@ -1050,6 +1205,16 @@ impl Builder {
         self
     }
 
+    /// Provide an executor to spawn the background tasks of connections on.
+    pub fn executor<E>(&mut self, executor: E) -> &mut Self
+    where
+        E: crate::core::rt::Executor<BoxSendFuture> + Send + Sync + Clone + 'static,
+    {
+        self.exec = Exec::new(executor);
+        self.h2_builder.executor(self.exec.clone());
+        self
+    }
+
     /// Provide a timer to be used for timeouts and intervals in connection pools.
     pub fn pool_timer<M>(&mut self, timer: M) -> &mut Self
     where
@@ -1076,6 +1241,15 @@ impl Builder {
         self
     }
 
+    /// Dump the heads of the requests sent and the responses received.
+    ///
+    /// Default is `None`.
+    #[inline]
+    pub fn verbose(&mut self, verbose: Option<VerboseConfig>) -> &mut Self {
+        self.verbose = verbose;
+        self
+    }
+
     /// Set whether to automatically add the `Host` header to requests.
     ///
     /// If true, and a request does not include a `Host` header, one will be
@@ -1097,6 +1271,11 @@ impl Builder {
     {
         let exec = self.exec.clone();
         let timer = self.pool_timer.clone();
+        let on_event = self.pool_events.clone().map(|on_event| {
+            Arc::new(move |key: &PoolKey, usage: &pool::Usage, kind| {
+                on_event(&PoolEvent::new(key, usage, kind))
+            }) as pool::OnEvent<PoolKey>
+        });
         Client {
             config: self.client_config,
             exec: exec.clone(),
@@ -1104,7 +1283,8 @@ impl Builder {
             h1_builder: self.h1_builder.clone(),
             h2_builder: self.h2_builder.clone(),
             connector,
-            pool: pool::Pool::new(self.pool_config, exec, timer),
+            pool: pool::Pool::new(self.pool_config, exec, timer, on_event),
+            verbose: self.verbose.clone(),
         }
     }
 }
@@ -58,6 +58,14 @@ impl NetworkScheme {
         NetworkSchemeBuilder::default()
     }
 
+    #[inline(always)]
+    pub fn proxy_scheme(&self) -> Option<&ProxyScheme> {
+        match self {
+            NetworkScheme::Scheme { proxy_scheme, .. } => proxy_scheme.as_ref(),
+            _ => None,
+        }
+    }
+
     #[inline(always)]
     pub fn take_proxy_scheme(&mut self) -> Option<ProxyScheme> {
         match self {
@@ -175,6 +183,7 @@ pub struct NetworkSchemeBuilder {
     interface: Option<std::borrow::Cow<'static, str>>,
     addresses: (Option<Ipv4Addr>, Option<Ipv6Addr>),
     proxy_scheme: Option<ProxyScheme>,
+    no_proxy: bool,
 }
 
 /// ==== impl NetworkSchemeBuilder ====
@@ -223,6 +232,15 @@ impl NetworkSchemeBuilder {
     #[inline]
     pub fn proxy_scheme(&mut self, proxy: impl Into<Option<ProxyScheme>>) -> &mut Self {
         self.proxy_scheme = proxy.into();
+        self.no_proxy = false;
+        self
+    }
+
+    /// Connect directly, ignoring any proxies configured on the client.
+    #[inline]
+    pub fn no_proxy(&mut self) -> &mut Self {
+        self.proxy_scheme = None;
+        self.no_proxy = true;
         self
     }
 
@@ -241,10 +259,12 @@ impl NetworkSchemeBuilder {
             target_os = "watchos",
         ))]
         {
-            if matches!(
-                (&self.proxy_scheme, &self.addresses, &self.interface),
-                (None, (None, None), None)
-            ) {
+            if !self.no_proxy
+                && matches!(
+                    (&self.proxy_scheme, &self.addresses, &self.interface),
+                    (None, (None, None), None)
+                )
+            {
                 return NetworkScheme::Default;
             }
 
@@ -268,7 +288,9 @@ impl NetworkSchemeBuilder {
             target_os = "watchos",
         )))]
         {
-            if matches!((&self.proxy_scheme, &self.addresses), (None, (None, None))) {
+            if !self.no_proxy
+                && matches!((&self.proxy_scheme, &self.addresses), (None, (None, None)))
+            {
                 return NetworkScheme::Default;
             }
 
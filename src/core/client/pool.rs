@@ -7,6 +7,7 @@ use std::hash::Hash;
 use std::num::NonZero;
 use std::ops::{Deref, DerefMut};
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Weak};
 use std::task::{self, Poll, ready};
 use std::time::{Duration, Instant};
@@ -14,6 +15,7 @@ use std::time::{Duration, Instant};
 use antidote::Mutex;
 use lru::LruCache;
 
+use super::stats::{EvictReason, PoolEventKind};
 use crate::core::common::{exec, exec::Exec, timer::Timer};
 use crate::core::rt::Sleep;
 use crate::core::rt::Timer as _;
@@ -24,6 +26,7 @@ use tokio::sync::oneshot;
 pub struct Pool<T, K: Key> {
     // If the pool is disabled, this is None.
     inner: Option<Arc<Mutex<PoolInner<T, K>>>>,
+    stats: Arc<Stats<K>>,
 }
 
 // Before using a pooled connection, make sure the sender is not dead.
@@ -38,6 +41,8 @@ pub trait Poolable: Unpin + Send + Sized + 'static {
     /// Allows for HTTP/2 to return a shared reservation.
     fn reserve(self) -> Reservation<Self>;
     fn can_share(&self) -> bool;
+    /// The age and request count of the underlying connection.
+    fn usage(&self) -> &Usage;
 }
 
 pub trait Key: Eq + Hash + Clone + Debug + Unpin + Send + 'static {}
@@ -68,6 +73,39 @@ pub enum Reservation<T> {
     Unique(T),
 }
 
+/// The age and request count of a connection, shared by all of its handles.
+pub struct Usage {
+    created_at: Instant,
+    requests: AtomicUsize,
+}
+
+impl Default for Usage {
+    fn default() -> Usage {
+        Usage {
+            created_at: Instant::now(),
+            requests: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl Usage {
+    /// Count a request sent on this connection.
+    pub fn record_request(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn age(&self) -> Duration {
+        self.created_at.elapsed()
+    }
+
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::Relaxed)
+    }
+}
+
+/// A callback notified when a pooled connection is created, reused, evicted or closed.
+pub(crate) type OnEvent<K> = Arc<dyn Fn(&K, &Usage, PoolEventKind) + Send + Sync>;
+
 /// Simple type alias in case the key type needs to be adjusted.
 // pub type Key = (http::uri::Scheme, http::uri::Authority); //Arc<String>;
 struct PoolInner<T, K: Eq + Hash> {
@@ -95,6 +133,28 @@ struct PoolInner<T, K: Eq + Hash> {
     exec: Exec,
     timer: Option<Timer>,
     timeout: Option<Duration>,
+    retirement: Retirement,
+    stats: Arc<Stats<K>>,
+}
+
+/// Counters shared by a pool and the connections it hands out.
+///
+/// These live outside of `PoolInner` so they are kept when the pool is disabled.
+struct Stats<K> {
+    created: AtomicU64,
+    reused: AtomicU64,
+    evicted: AtomicU64,
+    in_flight: Mutex<HashMap<K, usize>>,
+    on_event: Option<OnEvent<K>>,
+}
+
+/// A point-in-time copy of a pool's state.
+pub(crate) struct Snapshot<K> {
+    /// Idle and in-flight counts for each key.
+    pub(crate) keys: Vec<(K, usize, usize)>,
+    pub(crate) created: u64,
+    pub(crate) reused: u64,
+    pub(crate) evicted: u64,
 }
 
 // This is because `Weak::new()` *allocates* space for `T`, even if it
@@ -106,6 +166,8 @@ pub struct Config {
     pub idle_timeout: Option<Duration>,
     pub max_idle_per_host: usize,
     pub max_pool_size: Option<NonZero<usize>>,
+    pub max_lifetime: Option<Duration>,
+    pub max_requests: Option<NonZero<usize>>,
 }
 
 impl Config {
@@ -114,8 +176,36 @@ impl Config {
     }
 }
 
+/// Limits after which a connection is no longer handed out by the pool.
+#[derive(Clone, Copy)]
+struct Retirement {
+    max_lifetime: Option<Duration>,
+    max_requests: Option<NonZero<usize>>,
+}
+
+impl Retirement {
+    fn reason(&self, usage: &Usage) -> Option<EvictReason> {
+        if let Some(max) = self.max_requests {
+            if usage.requests() >= max.get() {
+                return Some(EvictReason::MaxRequests);
+            }
+        }
+        if let Some(max) = self.max_lifetime {
+            if usage.age() >= max {
+                return Some(EvictReason::MaxLifetime);
+            }
+        }
+        None
+    }
+}
+
 impl<T, K: Key> Pool<T, K> {
-    pub fn new<E, M>(config: Config, executor: E, timer: Option<M>) -> Pool<T, K>
+    pub fn new<E, M>(
+        config: Config,
+        executor: E,
+        timer: Option<M>,
+        on_event: Option<OnEvent<K>>,
+    ) -> Pool<T, K>
     where
         E: crate::core::rt::Executor<exec::BoxSendFuture> + Send + Sync + Clone + 'static,
         M: crate::core::rt::Timer + Send + Sync + Clone + 'static,
@@ -126,6 +216,13 @@ impl<T, K: Key> Pool<T, K> {
             Some(max_size) => LruCache::new(max_size),
             None => LruCache::unbounded(),
         };
+        let stats = Arc::new(Stats {
+            created: AtomicU64::new(0),
+            reused: AtomicU64::new(0),
+            evicted: AtomicU64::new(0),
+            in_flight: Mutex::new(HashMap::new()),
+            on_event,
+        });
         let inner = if config.is_enabled() {
             Some(Arc::new(Mutex::new(PoolInner {
                 connecting: HashSet::new(),
@@ -136,17 +233,75 @@ impl<T, K: Key> Pool<T, K> {
                 exec,
                 timer,
                 timeout: config.idle_timeout,
+                retirement: Retirement {
+                    max_lifetime: config.max_lifetime,
+                    max_requests: config.max_requests,
+                },
+                stats: stats.clone(),
             })))
         } else {
             None
         };
 
-        Pool { inner }
+        Pool { inner, stats }
     }
 
     pub(crate) fn is_enabled(&self) -> bool {
         self.inner.is_some()
     }
+
+    /// Take a snapshot of the idle and in-flight connections of this pool.
+    pub(crate) fn stats(&self) -> Snapshot<K> {
+        let mut keys: HashMap<K, (usize, usize)> = HashMap::new();
+
+        if let Some(ref enabled) = self.inner {
+            let inner = enabled.lock();
+            for (key, list) in inner.idle.iter() {
+                keys.entry(key.clone()).or_default().0 += list.len();
+            }
+        }
+
+        for (key, count) in self.stats.in_flight.lock().iter() {
+            keys.entry(key.clone()).or_default().1 += count;
+        }
+
+        Snapshot {
+            keys: keys
+                .into_iter()
+                .map(|(key, (idle, in_flight))| (key, idle, in_flight))
+                .collect(),
+            created: self.stats.created.load(Ordering::Relaxed),
+            reused: self.stats.reused.load(Ordering::Relaxed),
+            evicted: self.stats.evicted.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl<K: Key> Stats<K> {
+    fn acquire(&self, key: &K) {
+        *self.in_flight.lock().entry(key.clone()).or_insert(0) += 1;
+    }
+
+    fn release(&self, key: &K) {
+        let mut in_flight = self.in_flight.lock();
+        if let Some(count) = in_flight.get_mut(key) {
+            *count -= 1;
+            if *count == 0 {
+                in_flight.remove(key);
+            }
+        }
+    }
+
+    fn emit(&self, key: &K, usage: &Usage, kind: PoolEventKind) {
+        if let Some(ref on_event) = self.on_event {
+            on_event(key, usage, kind);
+        }
+    }
+
+    fn evict(&self, key: &K, usage: &Usage, kind: PoolEventKind) {
+        self.evicted.fetch_add(1, Ordering::Relaxed);
+        self.emit(key, usage, kind);
+    }
 }
 
 impl<T: Poolable, K: Key> Pool<T, K> {
@@ -189,6 +344,8 @@ impl<T: Poolable, K: Key> Pool<T, K> {
     }
 
     pub fn pooled(&self, mut connecting: Connecting<T, K>, value: T) -> Pooled<T, K> {
+        self.stats
+            .emit(&connecting.key, value.usage(), PoolEventKind::Created);
         let (value, pool_ref) = if let Some(ref enabled) = self.inner {
             match value.reserve() {
                 Reservation::Shared(to_insert, to_return) => {
@@ -220,10 +377,32 @@ impl<T: Poolable, K: Key> Pool<T, K> {
 
             (value, WeakOpt::none())
         };
+        self.stats.created.fetch_add(1, Ordering::Relaxed);
+        self.stats.acquire(&connecting.key);
         Pooled {
             key: connecting.key.clone(),
             is_reused: false,
             pool: pool_ref,
+            stats: self.stats.clone(),
+            value: Some(value),
+        }
+    }
+
+    /// Wraps a new connection that is never put in the pool, so it's only used
+    /// by the request it was made for.
+    pub fn unpooled(&self, connecting: Connecting<T, K>, value: T) -> Pooled<T, K> {
+        // An unpooled connection never takes a connecting lock.
+        debug_assert!(connecting.pool.upgrade().is_none());
+
+        self.stats
+            .emit(&connecting.key, value.usage(), PoolEventKind::Created);
+        self.stats.created.fetch_add(1, Ordering::Relaxed);
+        self.stats.acquire(&connecting.key);
+        Pooled {
+            key: connecting.key.clone(),
+            is_reused: false,
+            pool: WeakOpt::none(),
+            stats: self.stats.clone(),
             value: Some(value),
         }
     }
@@ -245,10 +424,14 @@ impl<T: Poolable, K: Key> Pool<T, K> {
             }
         }
 
+        self.stats.reused.fetch_add(1, Ordering::Relaxed);
+        self.stats.emit(key, value.usage(), PoolEventKind::Reused);
+        self.stats.acquire(key);
         Pooled {
             is_reused: true,
             key: key.clone(),
             pool: pool_ref,
+            stats: self.stats.clone(),
             value: Some(value),
         }
     }
@@ -256,18 +439,21 @@ impl<T: Poolable, K: Key> Pool<T, K> {
 
 /// Pop off this list, looking for a usable connection that hasn't expired.
 struct IdlePopper<'a, T, K> {
-    #[allow(dead_code)]
     key: &'a K,
     list: &'a mut Vec<Idle<T>>,
+    retirement: Retirement,
+    stats: &'a Stats<K>,
 }
 
-impl<'a, T: Poolable + 'a, K: Debug> IdlePopper<'a, T, K> {
+impl<'a, T: Poolable + 'a, K: Key> IdlePopper<'a, T, K> {
     fn pop(self, expiration: &Expiration) -> Option<Idle<T>> {
         while let Some(entry) = self.list.pop() {
             // If the connection has been closed, or is older than our idle
             // timeout, simply drop it and keep looking...
             if !entry.value.is_open() {
                 trace!("removing closed connection for {:?}", self.key);
+                self.stats
+                    .evict(self.key, entry.value.usage(), PoolEventKind::Closed);
                 continue;
             }
             // TODO: Actually, since the `idle` list is pushed to the end always,
@@ -278,6 +464,20 @@ impl<'a, T: Poolable + 'a, K: Debug> IdlePopper<'a, T, K> {
             // whole list...
             if expiration.expires(entry.idle_at) {
                 trace!("removing expired connection for {:?}", self.key);
+                self.stats.evict(
+                    self.key,
+                    entry.value.usage(),
+                    PoolEventKind::Evicted(EvictReason::IdleTimeout),
+                );
+                continue;
+            }
+            if let Some(reason) = self.retirement.reason(entry.value.usage()) {
+                trace!("removing retired connection for {:?}", self.key);
+                self.stats.evict(
+                    self.key,
+                    entry.value.usage(),
+                    PoolEventKind::Evicted(reason),
+                );
                 continue;
             }
 
@@ -308,6 +508,12 @@ impl<T: Poolable, K: Key> PoolInner<T, K> {
             trace!("put; existing idle HTTP/2 connection for {:?}", key);
             return;
         }
+        if let Some(reason) = self.retirement.reason(value.usage()) {
+            trace!("put; retiring connection for {:?}", key);
+            self.stats
+                .evict(&key, value.usage(), PoolEventKind::Evicted(reason));
+            return;
+        }
         trace!("put; add idle connection for {:?}", key);
         let mut remove_waiters = false;
         let mut value = Some(value);
@@ -352,6 +558,11 @@ impl<T: Poolable, K: Key> PoolInner<T, K> {
                     .get_or_insert_mut(key.clone(), Vec::<Idle<T>>::default);
                 if self.max_idle_per_host <= idle_list.len() {
                     trace!("max idle per host for {:?}, dropping connection", key);
+                    self.stats.evict(
+                        &key,
+                        value.usage(),
+                        PoolEventKind::Evicted(EvictReason::MaxIdlePerHost),
+                    );
                     return;
                 }
 
@@ -434,17 +645,31 @@ impl<T: Poolable, K: Key> PoolInner<T, K> {
         let now = Instant::now();
         //self.last_idle_check_at = now;
 
+        let stats = &self.stats;
+        let retirement = self.retirement;
         let mut keys_to_remove = Vec::new();
         self.idle.iter_mut().for_each(|(key, values)| {
             values.retain(|entry| {
                 if !entry.value.is_open() {
                     trace!("idle interval evicting closed for {:?}", key);
+                    stats.evict(key, entry.value.usage(), PoolEventKind::Closed);
                     return false;
                 }
 
                 // Avoid `Instant::sub` to avoid issues like rust-lang/rust#86470.
                 if now.saturating_duration_since(entry.idle_at) > dur {
                     trace!("idle interval evicting expired for {:?}", key);
+                    stats.evict(
+                        key,
+                        entry.value.usage(),
+                        PoolEventKind::Evicted(EvictReason::IdleTimeout),
+                    );
+                    return false;
+                }
+
+                if let Some(reason) = retirement.reason(entry.value.usage()) {
+                    trace!("idle interval evicting retired for {:?}", key);
+                    stats.evict(key, entry.value.usage(), PoolEventKind::Evicted(reason));
                     return false;
                 }
 
@@ -467,6 +692,7 @@ impl<T, K: Key> Clone for Pool<T, K> {
     fn clone(&self) -> Pool<T, K> {
         Pool {
             inner: self.inner.clone(),
+            stats: self.stats.clone(),
         }
     }
 }
@@ -478,6 +704,7 @@ pub struct Pooled<T: Poolable, K: Key> {
     is_reused: bool,
     key: K,
     pool: WeakOpt<Mutex<PoolInner<T, K>>>,
+    stats: Arc<Stats<K>>,
 }
 
 impl<T: Poolable, K: Key> Pooled<T, K> {
@@ -489,6 +716,10 @@ impl<T: Poolable, K: Key> Pooled<T, K> {
         self.pool.0.is_some()
     }
 
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
     fn as_ref(&self) -> &T {
         self.value.as_ref().expect("not dropped")
     }
@@ -513,10 +744,18 @@ impl<T: Poolable, K: Key> DerefMut for Pooled<T, K> {
 
 impl<T: Poolable, K: Key> Drop for Pooled<T, K> {
     fn drop(&mut self) {
+        self.stats.release(&self.key);
+
         if let Some(value) = self.value.take() {
             if !value.is_open() {
                 // If we *already* know the connection is done here,
                 // it shouldn't be re-inserted back into the pool.
+                //
+                // HTTP/2 handles are shared, the copy in the pool reports it.
+                if !value.can_share() {
+                    self.stats
+                        .emit(&self.key, value.usage(), PoolEventKind::Closed);
+                }
                 return;
             }
 
@@ -608,6 +847,7 @@ impl<T: Poolable, K: Key> Checkout<T, K> {
         let entry = {
             let mut inner = self.pool.inner.as_ref()?.lock();
             let expiration = Expiration::new(inner.timeout);
+            let retirement = inner.retirement;
             let maybe_entry = inner.idle.get_mut(&self.key).and_then(|list| {
                 trace!("take? {:?}: expiration = {:?}", self.key, expiration.0);
                 // A block to end the mutable borrow on list,
@@ -616,6 +856,8 @@ impl<T: Poolable, K: Key> Checkout<T, K> {
                     let popper = IdlePopper {
                         key: &self.key,
                         list,
+                        retirement,
+                        stats: &self.pool.stats,
                     };
                     popper.pop(&expiration)
                 }
@@ -291,7 +291,7 @@ fn method_has_defined_payload_semantics(method: Method) -> bool {
 /// Headers in `headers_order` are sorted to the front, preserving their order.
 /// Remaining headers are appended in their original order.
 #[inline]
-fn sort_headers(headers: &mut HeaderMap, headers_order: &[HeaderName]) {
+pub(crate) fn sort_headers(headers: &mut HeaderMap, headers_order: &[HeaderName]) {
     if headers.len() <= 1 {
         return;
     }
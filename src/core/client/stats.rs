@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use http::Uri;
+
+use super::PoolKey;
+use super::pool::{Snapshot, Usage};
+
+/// Statistics of a client's connection pool.
+///
+/// This is a snapshot taken by [`Client::pool_stats`](crate::Client::pool_stats).
+#[derive(Debug, Clone, Default)]
+pub struct PoolStats {
+    hosts: Vec<HostPoolStats>,
+    connections_created: u64,
+    connections_reused: u64,
+    evictions: u64,
+}
+
+/// Connection pool statistics for a single origin.
+#[derive(Debug, Clone)]
+pub struct HostPoolStats {
+    origin: Uri,
+    idle: usize,
+    in_flight: usize,
+}
+
+impl PoolStats {
+    pub(super) fn new(snapshot: Snapshot<PoolKey>) -> PoolStats {
+        // connections to the same origin may differ by ALPN or proxy, merge those
+        let mut hosts: BTreeMap<String, HostPoolStats> = BTreeMap::new();
+        for (key, idle, in_flight) in snapshot.keys {
+            let host = hosts
+                .entry(key.uri.to_string())
+                .or_insert_with(|| HostPoolStats {
+                    origin: key.uri.clone(),
+                    idle: 0,
+                    in_flight: 0,
+                });
+            host.idle += idle;
+            host.in_flight += in_flight;
+        }
+
+        PoolStats {
+            hosts: hosts.into_values().collect(),
+            connections_created: snapshot.created,
+            connections_reused: snapshot.reused,
+            evictions: snapshot.evicted,
+        }
+    }
+
+    /// Get the statistics of each origin with idle or in-flight connections.
+    pub fn hosts(&self) -> &[HostPoolStats] {
+        &self.hosts
+    }
+
+    /// Get the total number of connections established.
+    pub fn connections_created(&self) -> u64 {
+        self.connections_created
+    }
+
+    /// Get the total number of times an idle connection was reused.
+    ///
+    /// Each request multiplexed over an existing HTTP/2 connection counts as a reuse.
+    pub fn connections_reused(&self) -> u64 {
+        self.connections_reused
+    }
+
+    /// Get the total number of connections dropped because they were closed,
+    /// expired, or exceeded `pool_max_idle_per_host`, `pool_max_lifetime` or
+    /// `pool_max_requests`.
+    pub fn evictions(&self) -> u64 {
+        self.evictions
+    }
+}
+
+impl HostPoolStats {
+    /// Get the origin (scheme and authority) of these connections.
+    pub fn origin(&self) -> &Uri {
+        &self.origin
+    }
+
+    /// Get the number of idle connections.
+    pub fn idle(&self) -> usize {
+        self.idle
+    }
+
+    /// Get the number of connections currently checked out for a request.
+    ///
+    /// For HTTP/2, this counts requests in flight on shared connections.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight
+    }
+}
+
+/// A change in the lifecycle of a pooled connection.
+///
+/// Passed to the callback set by [`ClientBuilder::pool_events`](crate::ClientBuilder::pool_events).
+#[derive(Debug, Clone)]
+pub struct PoolEvent {
+    origin: Uri,
+    kind: PoolEventKind,
+    age: Duration,
+    requests: usize,
+}
+
+/// What happened to a pooled connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PoolEventKind {
+    /// A new connection was established.
+    Created,
+    /// An idle connection was checked out for another request.
+    Reused,
+    /// A usable connection was dropped by the pool.
+    Evicted(EvictReason),
+    /// A connection was found closed, by the peer or after an error.
+    Closed,
+}
+
+/// Why the pool dropped a connection that was still usable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EvictReason {
+    /// The connection sat idle longer than `pool_idle_timeout`.
+    IdleTimeout,
+    /// The connection is older than `pool_max_lifetime`.
+    MaxLifetime,
+    /// The connection has served `pool_max_requests` requests.
+    MaxRequests,
+    /// The origin already had `pool_max_idle_per_host` idle connections.
+    MaxIdlePerHost,
+}
+
+impl PoolEvent {
+    pub(super) fn new(key: &PoolKey, usage: &Usage, kind: PoolEventKind) -> PoolEvent {
+        PoolEvent {
+            origin: key.uri.clone(),
+            kind,
+            age: usage.age(),
+            requests: usage.requests(),
+        }
+    }
+
+    /// Get the origin (scheme and authority) of the connection.
+    pub fn origin(&self) -> &Uri {
+        &self.origin
+    }
+
+    /// Get what happened to the connection.
+    pub fn kind(&self) -> PoolEventKind {
+        self.kind
+    }
+
+    /// Get the time since the connection was established.
+    pub fn age(&self) -> Duration {
+        self.age
+    }
+
+    /// Get the number of requests sent on the connection so far.
+    pub fn requests(&self) -> usize {
+        self.requests
+    }
+}
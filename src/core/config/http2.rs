@@ -1,5 +1,7 @@
 //! Re-export the `http2` module for HTTP/2 frame types and utilities.
 
+use std::time::Duration;
+
 use crate::core::proto::{self, h2::client::Config};
 use http2::frame::ExperimentalSettings;
 pub use http2::frame::{
@@ -23,6 +25,46 @@ pub struct Http2Config {
     pub(crate) h2_builder: Config,
 }
 
+/// Flow control and keep-alive settings of a client, applied on top of its
+/// [`Http2Config`], so they hold with any emulation profile.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Http2Options {
+    pub(crate) adaptive_window: Option<bool>,
+    pub(crate) initial_stream_window_size: Option<u32>,
+    pub(crate) initial_connection_window_size: Option<u32>,
+    pub(crate) keep_alive_interval: Option<Duration>,
+    pub(crate) keep_alive_timeout: Option<Duration>,
+    pub(crate) keep_alive_while_idle: Option<bool>,
+}
+
+impl Http2Options {
+    /// Applies these settings to `config`.
+    ///
+    /// Unlike [`Http2ConfigBuilder::adaptive_window`], enabling the adaptive
+    /// window keeps the initial window sizes, so the SETTINGS frame is unchanged.
+    pub(crate) fn apply(&self, config: &mut Http2Config) {
+        let h2_builder = &mut config.h2_builder;
+        if let Some(sz) = self.initial_stream_window_size {
+            h2_builder.initial_stream_window_size = sz;
+        }
+        if let Some(sz) = self.initial_connection_window_size {
+            h2_builder.initial_conn_window_size = sz;
+        }
+        if let Some(enabled) = self.adaptive_window {
+            h2_builder.adaptive_window = enabled;
+        }
+        if let Some(interval) = self.keep_alive_interval {
+            h2_builder.keep_alive_interval = Some(interval);
+        }
+        if let Some(timeout) = self.keep_alive_timeout {
+            h2_builder.keep_alive_timeout = timeout;
+        }
+        if let Some(enabled) = self.keep_alive_while_idle {
+            h2_builder.keep_alive_while_idle = enabled;
+        }
+    }
+}
+
 impl Http2ConfigBuilder {
     /// Sets the [`SETTINGS_INITIAL_WINDOW_SIZE`][spec] option for HTTP2
     /// stream-level flow control.
@@ -150,7 +192,8 @@ impl Http2ConfigBuilder {
 
     /// Enables and disables the push feature for HTTP2.
     ///
-    /// Passing `None` will do nothing.
+    /// When enabled, the responses a server pushes along with a response can be
+    /// read with [`Response::pushed`](crate::Response::pushed).
     pub fn enable_push(mut self, opt: bool) -> Self {
         self.config.h2_builder.enable_push = Some(opt);
         self
@@ -284,4 +327,48 @@ impl Http2Config {
             config: Http2Config::default(),
         }
     }
+
+    /// Creates a `Http2ConfigBuilder` starting from this configuration.
+    ///
+    /// This allows adjusting the settings of an emulation profile.
+    pub fn into_builder(self) -> Http2ConfigBuilder {
+        Http2ConfigBuilder { config: self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EmulationProvider;
+
+    #[test]
+    fn into_builder_keeps_profile_settings() {
+        let profile = EmulationProvider::builder()
+            .http2_config(
+                Http2Config::builder()
+                    .initial_stream_window_size(6291456)
+                    .initial_connection_window_size(15728640)
+                    .max_frame_size(16384)
+                    .header_table_size(65536)
+                    .enable_push(false)
+                    .build(),
+            )
+            .build();
+
+        let config = profile
+            .http2_config()
+            .cloned()
+            .unwrap()
+            .into_builder()
+            .max_header_list_size(262144)
+            .build();
+
+        let h2 = &config.h2_builder;
+        assert_eq!(h2.initial_stream_window_size, 6291456);
+        assert_eq!(h2.initial_conn_window_size, 15728640);
+        assert_eq!(h2.max_frame_size, Some(16384));
+        assert_eq!(h2.header_table_size, Some(65536));
+        assert_eq!(h2.enable_push, Some(false));
+        assert_eq!(h2.max_header_list_size, Some(262144));
+    }
 }
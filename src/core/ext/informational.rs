@@ -0,0 +1,37 @@
+use std::fmt;
+use std::sync::Arc;
+
+/// A callback for the informational (`1xx`) responses received before the
+/// final response to an HTTP/1 request.
+///
+/// Set in the extensions of a request, it is called with each `1xx`
+/// response other than `101 Switching Protocols`, in the order they arrive.
+#[derive(Clone)]
+pub(crate) struct OnInformational(Arc<dyn Fn(&http::Response<()>) + Send + Sync>);
+
+impl OnInformational {
+    pub(crate) fn new<F>(callback: F) -> Self
+    where
+        F: Fn(&http::Response<()>) + Send + Sync + 'static,
+    {
+        OnInformational(Arc::new(callback))
+    }
+
+    pub(crate) fn call(&self, res: &http::Response<()>) {
+        (self.0)(res)
+    }
+
+    /// Returns a callback calling this one, then `next`.
+    pub(crate) fn then(self, next: OnInformational) -> Self {
+        OnInformational::new(move |res| {
+            self.call(res);
+            next.call(res);
+        })
+    }
+}
+
+impl fmt::Debug for OnInformational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OnInformational").finish()
+    }
+}
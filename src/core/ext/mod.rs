@@ -6,7 +6,10 @@ use http::header::{HeaderMap, IntoHeaderName, ValueIter};
 use std::fmt;
 
 mod h1_reason_phrase;
+mod informational;
+pub(crate) use crate::core::proto::h2::client::PushPromises;
 pub use h1_reason_phrase::ReasonPhrase;
+pub(crate) use informational::OnInformational;
 
 /// Represents the `:protocol` pseudo-header used by
 /// the [Extended CONNECT Protocol].
@@ -76,15 +79,6 @@ impl fmt::Debug for Protocol {
 pub(crate) struct HeaderCaseMap(HeaderMap<Bytes>);
 
 impl HeaderCaseMap {
-    /// Returns a view of all spellings associated with that header name,
-    /// in the order they were found.
-    pub(crate) fn get_all<'a>(
-        &'a self,
-        name: &HeaderName,
-    ) -> impl Iterator<Item = impl AsRef<[u8]> + 'a> + 'a {
-        self.get_all_internal(name)
-    }
-
     /// Returns a view of all spellings associated with that header name,
     /// in the order they were found.
     pub(crate) fn get_all_internal(&self, name: &HeaderName) -> ValueIter<'_, Bytes> {
@@ -6,7 +6,7 @@ use std::task::{Context, Poll, ready};
 
 use crate::core::rt::{Read, Write};
 use bytes::{Buf, Bytes};
-use http::header::{CONNECTION, HeaderValue, TE};
+use http::header::{CONNECTION, EXPECT, HeaderValue, TE};
 use http::{HeaderMap, Method, Version};
 use http_body::Frame;
 use httparse::ParserConfig;
@@ -14,6 +14,7 @@ use httparse::ParserConfig;
 use super::io::Buffered;
 use super::{Decoder, Encode, EncodedBuf, Encoder, Http1Transaction, ParseContext, Wants};
 use crate::core::body::DecodedLength;
+use crate::core::ext::OnInformational;
 use crate::core::headers;
 use crate::core::proto::{BodyLength, MessageHead};
 
@@ -60,6 +61,8 @@ where
                 // If they tell us otherwise, we'll downgrade in `read_head`.
                 version: Version::HTTP_11,
                 allow_trailer_fields: false,
+                on_informational: None,
+                expect_continue: false,
             },
             _marker: PhantomData,
         }
@@ -163,6 +166,7 @@ where
                 h1_max_headers: self.state.h1_max_headers,
                 preserve_header_case: self.state.preserve_header_case,
                 h09_responses: self.state.h09_responses,
+                on_informational: self.state.on_informational.as_ref(),
             },
         ) {
             Poll::Ready(Ok(msg)) => msg,
@@ -184,6 +188,17 @@ where
         self.state.keep_alive &= msg.keep_alive;
         self.state.version = msg.head.version;
 
+        // A final response instead of a `100 Continue` means the server
+        // doesn't want the body, which is then left unsent.
+        if self.state.expect_continue {
+            self.state.expect_continue = false;
+            if let Writing::Body(..) = self.state.writing {
+                debug!("response received before 100 Continue, not sending the body");
+                self.state.close_write();
+            }
+        }
+        self.state.on_informational = None;
+
         let mut wants = if msg.wants_upgrade {
             Wants::UPGRADE
         } else {
@@ -509,6 +524,12 @@ where
 
         if !T::should_read_first() {
             self.state.busy();
+            self.state.on_informational = head.extensions.remove::<OnInformational>();
+            self.state.expect_continue = body.is_some()
+                && head
+                    .headers
+                    .get(EXPECT)
+                    .is_some_and(|value| value.as_bytes().eq_ignore_ascii_case(b"100-continue"));
         }
 
         self.enforce_version(&mut head);
@@ -591,6 +612,7 @@ where
         debug_assert!(self.can_write_body() && self.can_buffer_body());
         // empty chunks should be discarded at Dispatcher level
         debug_assert!(chunk.remaining() != 0);
+        self.state.expect_continue = false;
 
         let state = match self.state.writing {
             Writing::Body(ref mut encoder) => {
@@ -641,6 +663,7 @@ where
         debug_assert!(self.can_write_body() && self.can_buffer_body());
         // empty chunks should be discarded at Dispatcher level
         debug_assert!(chunk.remaining() != 0);
+        self.state.expect_continue = false;
 
         let state = match self.state.writing {
             Writing::Body(ref encoder) => {
@@ -816,6 +839,11 @@ struct State {
     version: Version,
     /// Flag to track if trailer fields are allowed to be sent
     allow_trailer_fields: bool,
+    /// Called with the informational responses to the current request.
+    on_informational: Option<OnInformational>,
+    /// If the current request was sent with `Expect: 100-continue`, and
+    /// none of its body has been written yet.
+    expect_continue: bool,
 }
 
 #[derive(Debug)]
@@ -169,6 +169,7 @@ where
                     h1_max_headers: parse_ctx.h1_max_headers,
                     preserve_header_case: parse_ctx.preserve_header_case,
                     h09_responses: parse_ctx.h09_responses,
+                    on_informational: parse_ctx.on_informational,
                 },
             )? {
                 Some(msg) => {
@@ -683,6 +684,7 @@ mod tests {
                 h1_max_headers: None,
                 preserve_header_case: false,
                 h09_responses: false,
+                on_informational: None,
             };
             assert!(
                 buffered
@@ -3,6 +3,7 @@ use http::{HeaderMap, Method};
 use httparse::ParserConfig;
 
 use crate::core::body::DecodedLength;
+use crate::core::ext::OnInformational;
 use crate::core::proto::{BodyLength, MessageHead};
 
 pub(crate) use self::conn::Conn;
@@ -69,6 +70,7 @@ pub(crate) struct ParseContext<'a> {
     h1_max_headers: Option<usize>,
     preserve_header_case: bool,
     h09_responses: bool,
+    on_informational: Option<&'a OnInformational>,
 }
 
 /// Passed to Http1Transaction::encode
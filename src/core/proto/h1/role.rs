@@ -253,6 +253,10 @@ impl Http1Transaction for Client {
                 }));
             }
 
+            if let Some(callback) = ctx.on_informational {
+                callback.call(&head.into_response(()));
+            }
+
             // Parsing a 1xx response could have consumed the buffer, check if
             // it is empty now...
             if buf.is_empty() {
@@ -682,11 +686,14 @@ fn write_headers_original_case(
     //
     // TODO: consider adding http::HeaderMap::entries() iterator
     for name in headers.keys() {
-        let mut names = orig_case.get_all(name);
+        let mut names = orig_case.get_all_internal(name);
+        let mut last = None;
 
         for value in headers.get_all(name) {
-            if let Some(orig_name) = names.next() {
+            // a repeated header keeps the last known spelling of its name
+            if let Some(orig_name) = names.next().or(last) {
                 extend(dst, orig_name.as_ref());
+                last = Some(orig_name);
             } else if title_case_headers {
                 title_case(dst, name.as_str().as_bytes());
             } else {
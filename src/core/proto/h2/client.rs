@@ -1,8 +1,10 @@
 use std::{
     convert::Infallible,
+    fmt,
     future::Future,
     marker::PhantomData,
     pin::Pin,
+    sync::{Arc, Mutex},
     task::{Context, Poll, ready},
     time::Duration,
 };
@@ -35,7 +37,7 @@ use crate::core::rt::bounds::Http2ClientConnExec;
 use crate::core::upgrade::Upgraded;
 use crate::core::{Request, Response};
 
-use http2::client::ResponseFuture;
+use http2::client::{PushedResponseFuture, ResponseFuture};
 use http2::frame::{PseudoOrder, SettingsOrder, StreamDependency};
 
 type ClientRx<B> = crate::core::client::dispatch::Receiver<Request<B>, Response<IncomingBody>>;
@@ -243,6 +245,7 @@ where
         h2_tx,
         req_rx,
         fut_ctx: None,
+        enable_push: config.enable_push == Some(true),
         marker: PhantomData,
     })
 }
@@ -475,6 +478,7 @@ where
     h2_tx: SendRequest<SendBuf<B::Data>>,
     req_rx: ClientRx<B>,
     fut_ctx: Option<FutCtx<B>>,
+    enable_push: bool,
     marker: PhantomData<T>,
 }
 
@@ -525,8 +529,13 @@ where
     B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
     T: Read + Write + Unpin,
 {
-    fn poll_pipe(&mut self, f: FutCtx<B>, cx: &mut Context<'_>) {
+    fn poll_pipe(&mut self, mut f: FutCtx<B>, cx: &mut Context<'_>) {
         let ping = self.ping.clone();
+        let push_promises = if self.enable_push && !f.is_connect {
+            Some(f.fut.push_promises())
+        } else {
+            None
+        };
 
         let send_stream = if !f.is_connect {
             if !f.eos {
@@ -566,6 +575,7 @@ where
                     fut: f.fut,
                     ping: Some(ping),
                     send_stream: Some(send_stream),
+                    push_promises,
                 },
                 call_back: Some(f.cb),
             },
@@ -585,6 +595,7 @@ pin_project! {
         ping: Option<Recorder>,
         #[pin]
         send_stream: Option<Option<SendStream<SendBuf<<B as Body>::Data>>>>,
+        push_promises: Option<http2::client::PushPromises>,
     }
 }
 
@@ -636,10 +647,17 @@ where
 
                     Poll::Ready(Ok(res))
                 } else {
-                    let res = res.map(|stream| {
+                    let push_promises = this
+                        .push_promises
+                        .take()
+                        .map(|promises| PushPromises::new(promises, ping.clone()));
+                    let mut res = res.map(|stream| {
                         let ping = ping.for_stream(&stream);
                         IncomingBody::h2(stream, content_length.into(), ping)
                     });
+                    if let Some(push_promises) = push_promises {
+                        res.extensions_mut().insert(push_promises);
+                    }
                     Poll::Ready(Ok(res))
                 }
             }
@@ -653,6 +671,66 @@ where
     }
 }
 
+/// The streams a server promised to push along with a response.
+///
+/// Set in the extensions of an HTTP/2 response, when push is enabled.
+#[derive(Clone)]
+pub(crate) struct PushPromises(Arc<Mutex<Pushed>>);
+
+struct Pushed {
+    promises: http2::client::PushPromises,
+    pending: Option<(Request<()>, PushedResponseFuture)>,
+    ping: Recorder,
+}
+
+impl PushPromises {
+    fn new(promises: http2::client::PushPromises, ping: Recorder) -> Self {
+        PushPromises(Arc::new(Mutex::new(Pushed {
+            promises,
+            pending: None,
+            ping,
+        })))
+    }
+
+    /// Polls for the next promised request, and the pushed response to it.
+    pub(crate) fn poll_next(
+        &self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<crate::core::Result<(Request<()>, Response<IncomingBody>)>>> {
+        let mut pushed = self.0.lock().unwrap();
+        loop {
+            if let Some((_, ref mut fut)) = pushed.pending {
+                let result = ready!(Pin::new(fut).poll(cx));
+                let (req, _) = pushed.pending.take().expect("pending push");
+                let res = match result {
+                    Ok(res) => res,
+                    Err(err) => return Poll::Ready(Some(Err(crate::core::Error::new_h2(err)))),
+                };
+
+                pushed.ping.record_non_data();
+                let content_length = headers::content_length_parse_all(res.headers());
+                let res = res.map(|stream| {
+                    let ping = pushed.ping.clone().for_stream(&stream);
+                    IncomingBody::h2(stream, content_length.into(), ping)
+                });
+                return Poll::Ready(Some(Ok((req, res))));
+            }
+
+            match ready!(pushed.promises.poll_push_promise(cx)) {
+                Some(Ok(promise)) => pushed.pending = Some(promise.into_parts()),
+                Some(Err(err)) => return Poll::Ready(Some(Err(crate::core::Error::new_h2(err)))),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+impl fmt::Debug for PushPromises {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PushPromises").finish()
+    }
+}
+
 impl<B, E, T> Future for ClientTask<B, E, T>
 where
     B: Body + 'static + Unpin,
@@ -0,0 +1,361 @@
+//! DNS resolution over HTTPS ([RFC 8484](https://www.rfc-editor.org/rfc/rfc8484))
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use antidote::Mutex;
+use lru::LruCache;
+
+use super::gai::GaiResolver;
+use super::{Addrs, Name, Resolve, Resolving};
+use crate::error::BoxError;
+use crate::header::{ACCEPT, CONTENT_TYPE, HeaderValue};
+use crate::into_url::{IntoUrl, IntoUrlSealed};
+use crate::{Client, Url};
+
+const DNS_MESSAGE: &str = "application/dns-message";
+
+const TYPE_A: u16 = 1;
+const TYPE_AAAA: u16 = 28;
+const CLASS_IN: u16 = 1;
+
+const CACHE_SIZE: usize = 1024;
+const MAX_TTL: u32 = 24 * 60 * 60;
+
+/// A resolver that sends DNS queries over HTTPS.
+///
+/// Queries for `A` and `AAAA` records are sent in parallel, and answers are cached for
+/// their TTL. If the DoH server can't be reached or sends an unusable response, names
+/// are resolved with the system resolver instead, unless [`DohResolver::fallback`]
+/// disables it. A name the server answered as nonexistent, or without addresses, is
+/// never looked up with the system resolver.
+///
+/// The endpoint itself is resolved with the system resolver, so an endpoint given by
+/// IP address (e.g. `https://1.1.1.1/dns-query`) avoids plaintext DNS entirely.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use rquest::dns::DohResolver;
+///
+/// let resolver = DohResolver::new("https://1.1.1.1/dns-query").unwrap();
+/// let client = rquest::Client::builder()
+///     .dns_resolver(Arc::new(resolver))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct DohResolver {
+    endpoint: Url,
+    client: Client,
+    cache: Arc<Mutex<LruCache<String, Cached>>>,
+    fallback: Option<Arc<GaiResolver>>,
+}
+
+struct Cached {
+    addrs: Vec<IpAddr>,
+    expires_at: Instant,
+}
+
+impl DohResolver {
+    /// Create a resolver that queries the given DoH endpoint.
+    pub fn new<U: IntoUrl>(endpoint: U) -> crate::Result<DohResolver> {
+        let endpoint = endpoint.into_url()?;
+        let client = Client::builder().build()?;
+
+        Ok(DohResolver {
+            endpoint,
+            client,
+            cache: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(CACHE_SIZE).expect("cache size is non-zero"),
+            ))),
+            fallback: Some(Arc::new(GaiResolver::new())),
+        })
+    }
+
+    /// Set the `Client` used to send queries.
+    ///
+    /// This can be used to route queries through a proxy or to emulate a browser.
+    /// The client must not use this resolver itself.
+    pub fn with_client(mut self, client: Client) -> DohResolver {
+        self.client = client;
+        self
+    }
+
+    /// Enable or disable falling back to the system resolver when the DoH server
+    /// can't answer a query.
+    ///
+    /// Default is `true`.
+    pub fn fallback(mut self, enable: bool) -> DohResolver {
+        self.fallback = enable.then(|| Arc::new(GaiResolver::new()));
+        self
+    }
+
+    async fn lookup(&self, host: &str) -> Result<Vec<IpAddr>, BoxError> {
+        if let Some(addrs) = self.cached(host) {
+            return Ok(addrs);
+        }
+
+        let (v4, v6) =
+            futures_util::future::join(self.query(host, TYPE_A), self.query(host, TYPE_AAAA)).await;
+
+        let (addrs, ttl) = match (v4, v6) {
+            (Ok((mut v4, ttl4)), Ok((v6, ttl6))) => {
+                v4.extend(v6);
+                (v4, ttl4.min(ttl6))
+            }
+            (Ok(answer), Err(_err)) | (Err(_err), Ok(answer)) => {
+                debug!("DoH query for {} partially failed: {}", host, _err);
+                answer
+            }
+            (Err(err), Err(_)) => return Err(err),
+        };
+
+        if addrs.is_empty() {
+            return Err(Box::new(NoAddresses(host.to_owned())));
+        }
+
+        self.cache.lock().put(
+            host.to_owned(),
+            Cached {
+                addrs: addrs.clone(),
+                expires_at: Instant::now() + Duration::from_secs(ttl.min(MAX_TTL).into()),
+            },
+        );
+
+        Ok(addrs)
+    }
+
+    fn cached(&self, host: &str) -> Option<Vec<IpAddr>> {
+        let mut cache = self.cache.lock();
+        match cache.get(host) {
+            Some(cached) if cached.expires_at > Instant::now() => Some(cached.addrs.clone()),
+            Some(_) => {
+                cache.pop(host);
+                None
+            }
+            None => None,
+        }
+    }
+
+    async fn query(&self, host: &str, qtype: u16) -> Result<(Vec<IpAddr>, u32), BoxError> {
+        let res = self
+            .client
+            .post(self.endpoint.clone())
+            .header(CONTENT_TYPE, HeaderValue::from_static(DNS_MESSAGE))
+            .header(ACCEPT, HeaderValue::from_static(DNS_MESSAGE))
+            .body(encode_query(host, qtype)?)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let body = res.bytes().await?;
+        parse_answer(&body, qtype)
+    }
+}
+
+impl Resolve for DohResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+        Box::pin(async move {
+            match resolver.lookup(name.as_str()).await {
+                Ok(addrs) => {
+                    let addrs: Addrs = Box::new(addrs.into_iter().map(|ip| SocketAddr::new(ip, 0)));
+                    Ok(addrs)
+                }
+                Err(err) => match resolver.fallback {
+                    // the server answered, so there is nothing to fall back from
+                    Some(_) if is_answer(&*err) => Err(err),
+                    Some(ref fallback) => {
+                        debug!(
+                            "DoH lookup for {} failed, using system DNS: {}",
+                            name.as_str(),
+                            err
+                        );
+                        fallback.resolve(name).await
+                    }
+                    None => Err(err),
+                },
+            }
+        })
+    }
+
+    fn clear_cache(&self) {
+        self.cache.lock().clear();
+    }
+}
+
+impl std::fmt::Debug for DohResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DohResolver")
+            .field("endpoint", &self.endpoint.as_str())
+            .field("fallback", &self.fallback.is_some())
+            .finish()
+    }
+}
+
+/// The DoH server answered that a name has no addresses.
+#[derive(Debug)]
+struct NoAddresses(String);
+
+impl std::fmt::Display for NoAddresses {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no addresses found for {}", self.0)
+    }
+}
+
+impl std::error::Error for NoAddresses {}
+
+/// The DoH server answered a query with an error, such as NXDOMAIN.
+#[derive(Debug)]
+struct ResponseCode(u16);
+
+impl std::fmt::Display for ResponseCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            3 => f.write_str("DNS name does not exist"),
+            rcode => write!(f, "DNS server returned error code {}", rcode),
+        }
+    }
+}
+
+impl std::error::Error for ResponseCode {}
+
+/// Whether a lookup failed because of the DoH server's answer, rather than
+/// because it couldn't be reached or sent an unusable response.
+fn is_answer(err: &(dyn std::error::Error + 'static)) -> bool {
+    err.is::<NoAddresses>() || err.is::<ResponseCode>()
+}
+
+/// Encode a recursive query for `host` in DNS wire format.
+fn encode_query(host: &str, qtype: u16) -> Result<Vec<u8>, BoxError> {
+    // the id is 0 to keep queries cacheable, as recommended by RFC 8484
+    let mut buf = vec![0, 0, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+
+    for label in host.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(format!("invalid DNS name: {}", host).into());
+        }
+        buf.push(label.len() as u8);
+        buf.extend_from_slice(label.as_bytes());
+    }
+    buf.push(0);
+
+    buf.extend_from_slice(&qtype.to_be_bytes());
+    buf.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Ok(buf)
+}
+
+/// Parse the addresses and the lowest TTL of the answers to a query of `qtype`.
+fn parse_answer(msg: &[u8], qtype: u16) -> Result<(Vec<IpAddr>, u32), BoxError> {
+    fn truncated() -> BoxError {
+        "truncated DNS message".into()
+    }
+
+    let header = msg.get(..12).ok_or_else(truncated)?;
+    let flags = u16::from_be_bytes([header[2], header[3]]);
+    if flags & 0x8000 == 0 {
+        return Err("DNS message is not a response".into());
+    }
+    match flags & 0x000f {
+        0 => {}
+        rcode => return Err(Box::new(ResponseCode(rcode))),
+    }
+
+    let questions = u16::from_be_bytes([header[4], header[5]]);
+    let answers = u16::from_be_bytes([header[6], header[7]]);
+
+    let mut pos = 12;
+    for _ in 0..questions {
+        pos = skip_name(msg, pos).ok_or_else(truncated)? + 4;
+    }
+
+    let mut addrs = Vec::new();
+    let mut ttl = MAX_TTL;
+    for _ in 0..answers {
+        pos = skip_name(msg, pos).ok_or_else(truncated)?;
+        let record = msg.get(pos..pos + 10).ok_or_else(truncated)?;
+        let rtype = u16::from_be_bytes([record[0], record[1]]);
+        let rttl = u32::from_be_bytes([record[4], record[5], record[6], record[7]]);
+        let len = u16::from_be_bytes([record[8], record[9]]) as usize;
+        pos += 10;
+
+        let data = msg.get(pos..pos + len).ok_or_else(truncated)?;
+        pos += len;
+
+        // skip CNAMEs and anything else that isn't the requested type
+        if rtype != qtype {
+            continue;
+        }
+
+        let addr = match (rtype, <[u8; 4]>::try_from(data), <[u8; 16]>::try_from(data)) {
+            (TYPE_A, Ok(octets), _) => IpAddr::V4(Ipv4Addr::from(octets)),
+            (TYPE_AAAA, _, Ok(octets)) => IpAddr::V6(Ipv6Addr::from(octets)),
+            _ => return Err("malformed address record".into()),
+        };
+        addrs.push(addr);
+        ttl = ttl.min(rttl);
+    }
+
+    Ok((addrs, ttl))
+}
+
+/// Skip over a possibly compressed name, returning the position after it.
+fn skip_name(msg: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        let len = *msg.get(pos)?;
+        match len {
+            0 => return Some(pos + 1),
+            // a compression pointer ends the name
+            len if len & 0xc0 == 0xc0 => {
+                msg.get(pos + 1)?;
+                return Some(pos + 2);
+            }
+            len => pos += 1 + len as usize,
+        }
+    }
+}
+
+#[test]
+fn test_encode_query() {
+    let query = encode_query("example.com.", TYPE_AAAA).unwrap();
+    assert_eq!(
+        query,
+        [
+            0, 0, 1, 0, 0, 1, 0, 0, 0, 0, 0, 0, // header
+            7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o', b'm', 0, // name
+            0, 28, 0, 1, // type and class
+        ]
+    );
+
+    assert!(encode_query("a..b", TYPE_A).is_err());
+}
+
+#[test]
+fn test_parse_answer() {
+    let mut msg = encode_query("example.com", TYPE_A).unwrap();
+    // mark as a response with two answers
+    msg[2] = 0x81;
+    msg[3] = 0x80;
+    msg[7] = 2;
+
+    // a CNAME pointing at the question name, then an A record
+    msg.extend_from_slice(&[0xc0, 12, 0, 5, 0, 1, 0, 0, 0, 30, 0, 2, 0xc0, 12]);
+    msg.extend_from_slice(&[0xc0, 12, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 93, 184, 216, 34]);
+
+    let (addrs, ttl) = parse_answer(&msg, TYPE_A).unwrap();
+    assert_eq!(addrs, [IpAddr::V4(Ipv4Addr::new(93, 184, 216, 34))]);
+    assert_eq!(ttl, 60);
+
+    // NXDOMAIN
+    msg[3] = 0x83;
+    assert!(is_answer(&*parse_answer(&msg, TYPE_A).unwrap_err()));
+
+    // truncated
+    msg[3] = 0x80;
+    let err = parse_answer(&msg[..msg.len() - 2], TYPE_A).unwrap_err();
+    assert!(!is_answer(&*err));
+}
@@ -7,6 +7,7 @@ use hickory_resolver::name_server::TokioConnectionProvider;
 use hickory_resolver::{TokioResolver, lookup_ip::LookupIpIntoIter};
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Wrapper around an `AsyncResolver`, which implements the `Resolve` trait.
 #[derive(Debug, Clone)]
@@ -23,6 +24,15 @@ impl HickoryDnsResolver {
     /// overriden to look up for both IPv4 and IPv6 addresses
     /// to work with "happy eyeballs" algorithm.
     pub fn new<S>(strategy: S) -> crate::Result<Self>
+    where
+        S: Into<Option<LookupIpStrategy>>,
+    {
+        Self::with_cache(strategy, DnsCache::default())
+    }
+
+    /// Create a new resolver like [`HickoryDnsResolver::new`], caching answers
+    /// as configured by `cache`.
+    pub fn with_cache<S>(strategy: S, cache: DnsCache) -> crate::Result<Self>
     where
         S: Into<Option<LookupIpStrategy>>,
     {
@@ -37,8 +47,15 @@ impl HickoryDnsResolver {
             }
         };
 
-        resolver.options_mut().ip_strategy =
-            strategy.into().unwrap_or(LookupIpStrategy::Ipv4AndIpv6);
+        let options = resolver.options_mut();
+        options.ip_strategy = strategy.into().unwrap_or(LookupIpStrategy::Ipv4AndIpv6);
+        if let Some(size) = cache.size {
+            options.cache_size = size;
+        }
+        options.positive_min_ttl = cache.positive_min_ttl;
+        options.positive_max_ttl = cache.positive_max_ttl;
+        options.negative_min_ttl = cache.negative_min_ttl;
+        options.negative_max_ttl = cache.negative_max_ttl;
 
         Ok(Self {
             state: Arc::new(resolver.build()),
@@ -46,6 +63,61 @@ impl HickoryDnsResolver {
     }
 }
 
+/// Cache settings of a [`HickoryDnsResolver`].
+///
+/// Answers are cached for the TTL sent by the name server, clamped to the
+/// bounds set here. Failed lookups (e.g. `NXDOMAIN`) are cached too, for the
+/// TTL of the zone's `SOA` record, clamped to the negative bounds.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use rquest::dns::DnsCache;
+///
+/// let cache = DnsCache::new()
+///     .size(256)
+///     .ttl(Duration::from_secs(30), Duration::from_secs(300))
+///     .negative_ttl(Duration::ZERO, Duration::from_secs(5));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DnsCache {
+    size: Option<u64>,
+    positive_min_ttl: Option<Duration>,
+    positive_max_ttl: Option<Duration>,
+    negative_min_ttl: Option<Duration>,
+    negative_max_ttl: Option<Duration>,
+}
+
+impl DnsCache {
+    /// Create the default cache settings of hickory-dns.
+    pub fn new() -> DnsCache {
+        DnsCache::default()
+    }
+
+    /// Set the maximum number of cached answers.
+    pub fn size(mut self, size: u64) -> DnsCache {
+        self.size = Some(size);
+        self
+    }
+
+    /// Clamp the TTL of successful answers between `min` and `max`.
+    pub fn ttl(mut self, min: Duration, max: Duration) -> DnsCache {
+        self.positive_min_ttl = Some(min);
+        self.positive_max_ttl = Some(max);
+        self
+    }
+
+    /// Clamp the TTL of failed lookups between `min` and `max`.
+    ///
+    /// A `max` of zero disables negative caching.
+    pub fn negative_ttl(mut self, min: Duration, max: Duration) -> DnsCache {
+        self.negative_min_ttl = Some(min);
+        self.negative_max_ttl = Some(max);
+        self
+    }
+}
+
 struct SocketAddrs {
     iter: LookupIpIntoIter,
 }
@@ -61,6 +133,10 @@ impl Resolve for HickoryDnsResolver {
             Ok(addrs)
         })
     }
+
+    fn clear_cache(&self) {
+        self.state.clear_cache();
+    }
 }
 
 impl Iterator for SocketAddrs {
@@ -0,0 +1,69 @@
+//! Parsing of `/etc/hosts`-style files
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+
+/// Parse the lines of a hosts file into the addresses of each host name.
+///
+/// Each line holds an IP address followed by one or more host names, and
+/// everything after a `#` is a comment. Lines with an invalid address are
+/// skipped. Host names are matched case-insensitively, so they are lowercased.
+pub(crate) fn parse(contents: &str) -> HashMap<String, Vec<SocketAddr>> {
+    let mut hosts: HashMap<String, Vec<SocketAddr>> = HashMap::new();
+
+    for line in contents.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        let mut fields = line.split_whitespace();
+
+        let ip = match fields.next().map(str::parse::<IpAddr>) {
+            Some(Ok(ip)) => ip,
+            Some(Err(_)) => {
+                debug!("skipping hosts line with an invalid address: {}", line);
+                continue;
+            }
+            None => continue,
+        };
+
+        for name in fields {
+            let addrs = hosts.entry(name.to_ascii_lowercase()).or_default();
+            let addr = SocketAddr::new(ip, 0);
+            if !addrs.contains(&addr) {
+                addrs.push(addr);
+            }
+        }
+    }
+
+    hosts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_names_and_aliases() {
+        let hosts = parse(
+            "# static hosts\n\
+             127.0.0.1\tlocalhost\n\
+             ::1 localhost ip6-localhost  # loopback\n\
+             10.0.0.5 Api.Internal api\n\
+             not-an-ip broken.example\n\
+             \n\
+             10.0.0.6 api.internal\n",
+        );
+
+        let addrs = |name: &str| -> Vec<String> {
+            hosts[name]
+                .iter()
+                .map(|addr| addr.ip().to_string())
+                .collect()
+        };
+
+        assert_eq!(addrs("localhost"), ["127.0.0.1", "::1"]);
+        assert_eq!(addrs("ip6-localhost"), ["::1"]);
+        assert_eq!(addrs("api.internal"), ["10.0.0.5", "10.0.0.6"]);
+        assert_eq!(addrs("api"), ["10.0.0.5"]);
+        assert!(!hosts.contains_key("broken.example"));
+        assert!(hosts["api"].iter().all(|addr| addr.port() == 0));
+    }
+}
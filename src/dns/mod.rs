@@ -1,11 +1,15 @@
 //! DNS resolution
 
+pub use crate::core::client::connect::dns::IpStrategy;
+pub use doh::DohResolver;
 #[cfg(feature = "hickory-dns")]
-pub use hickory::{HickoryDnsResolver, LookupIpStrategy};
+pub use hickory::{DnsCache, HickoryDnsResolver, LookupIpStrategy};
 pub use resolve::{Addrs, Name, Resolve, Resolving};
 pub(crate) use resolve::{DnsResolverWithOverrides, DynResolver};
 
+pub(crate) mod doh;
 pub(crate) mod gai;
 #[cfg(feature = "hickory-dns")]
 pub(crate) mod hickory;
+pub(crate) mod hosts;
 pub(crate) mod resolve;
@@ -31,6 +31,12 @@ pub trait Resolve: Send + Sync {
     /// Explicitly specified port in the URL will override any port in the resolved `SocketAddr`s.
     /// Otherwise, port `0` will be replaced by the conventional port for the given scheme (e.g. 80 for http).
     fn resolve(&self, name: Name) -> Resolving;
+
+    /// Clears the answers cached by this resolver, if it caches any.
+    ///
+    /// This is called by [`Client::clear_dns_cache`](crate::Client::clear_dns_cache).
+    /// The default implementation does nothing.
+    fn clear_cache(&self) {}
 }
 
 /// A name that must be resolved to addresses.
@@ -64,6 +70,10 @@ impl DynResolver {
         Self { resolver }
     }
 
+    pub(crate) fn clear_cache(&self) {
+        self.resolver.clear_cache();
+    }
+
     #[cfg(feature = "socks")]
     pub(crate) fn gai() -> Self {
         Self::new(Arc::new(super::gai::GaiResolver::new()))
@@ -141,6 +151,10 @@ impl Resolve for DnsResolverWithOverrides {
             None => self.dns_resolver.resolve(name),
         }
     }
+
+    fn clear_cache(&self) {
+        self.dns_resolver.clear_cache();
+    }
 }
 
 mod sealed {
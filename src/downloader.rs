@@ -0,0 +1,327 @@
+//! Segmented downloads
+//!
+//! A [`Downloader`] fetches a large resource as several ranged requests sent
+//! at once over a `Client`, and writes each segment at its offset in a file
+//! sized up front. A segment that fails is resumed from where it stopped.
+//!
+//! Servers that don't support ranges, or don't tell the length of the
+//! resource, answer the first request with the whole body, which is then
+//! written as a single segment.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use rquest::downloader::Downloader;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let len = Downloader::new(rquest::Client::new())
+//!     .segments(8)
+//!     .on_progress(|progress| {
+//!         println!("{}/{:?}", progress.downloaded(), progress.total());
+//!     })
+//!     .download("https://example.com/large.iso", "large.iso")
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::io::SeekFrom;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use futures_util::future::try_join_all;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+use crate::error;
+use crate::header::{CONTENT_RANGE, ETAG, HeaderValue, IF_RANGE, LAST_MODIFIED};
+use crate::{Client, IntoUrl, Response, StatusCode, Url};
+
+/// Downloads a resource to a file as several ranged requests sent at once.
+#[derive(Clone)]
+pub struct Downloader {
+    client: Client,
+    segments: usize,
+    min_segment_size: u64,
+    retries: usize,
+    on_progress: Option<Arc<dyn Fn(&Progress) + Send + Sync>>,
+}
+
+/// The progress of a download, passed to [`Downloader::on_progress`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+impl Progress {
+    /// The bytes written so far, across all segments.
+    pub fn downloaded(&self) -> u64 {
+        self.downloaded
+    }
+
+    /// The length of the resource, if the server told it.
+    pub fn total(&self) -> Option<u64> {
+        self.total
+    }
+}
+
+impl Downloader {
+    /// Create a downloader sending its requests with `client`.
+    ///
+    /// By default, a resource is split into up to 4 segments of at least
+    /// 1 MiB, and each segment is resumed up to 3 times.
+    pub fn new(client: Client) -> Downloader {
+        Downloader {
+            client,
+            segments: 4,
+            min_segment_size: 1024 * 1024,
+            retries: 3,
+            on_progress: None,
+        }
+    }
+
+    /// Set the most segments a resource is split into.
+    pub fn segments(mut self, segments: usize) -> Downloader {
+        self.segments = segments.max(1);
+        self
+    }
+
+    /// Set the smallest segment, so small resources aren't split into many
+    /// tiny requests.
+    pub fn min_segment_size(mut self, bytes: u64) -> Downloader {
+        self.min_segment_size = bytes.max(1);
+        self
+    }
+
+    /// Set how many times a failed segment is resumed before the download
+    /// fails.
+    pub fn retries(mut self, retries: usize) -> Downloader {
+        self.retries = retries;
+        self
+    }
+
+    /// Call `callback` each time a chunk of the resource has been written.
+    ///
+    /// The callback is called from the task running the download, so it
+    /// should return quickly.
+    pub fn on_progress<F>(mut self, callback: F) -> Downloader
+    where
+        F: Fn(&Progress) + Send + Sync + 'static,
+    {
+        self.on_progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// Download the resource at `url` to the file at `path`, returning its
+    /// length.
+    ///
+    /// The file is created, or truncated if it exists.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if a request fails, a segment still fails after
+    /// its retries, the resource changes while it is downloaded, or the file
+    /// can't be written. The file is left partially written.
+    pub async fn download<U: IntoUrl>(&self, url: U, path: impl AsRef<Path>) -> crate::Result<u64> {
+        let url = url.into_url()?;
+        let path = path.as_ref();
+
+        // ask for the first byte, to learn the length and whether ranges work
+        let res = self
+            .client
+            .get(url.clone())
+            .range(0..1)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let file = File::create(path).await.map_err(error::body)?;
+
+        let total = match probe(&res) {
+            Some(total) => total,
+            None => {
+                let progress = Counter::new(self, None);
+                let (written, result) = write_segment(file, 0, res, &progress).await;
+                return result.map(|()| written);
+            }
+        };
+        file.set_len(total).await.map_err(error::body)?;
+        drop(file);
+
+        // a changed resource is sent whole instead of the range
+        let validator = res
+            .headers()
+            .get(ETAG)
+            .filter(|etag| !etag.as_bytes().starts_with(b"W/"))
+            .or_else(|| res.headers().get(LAST_MODIFIED))
+            .cloned();
+        drop(res);
+
+        let progress = Counter::new(self, Some(total));
+        let segments = self.split(total);
+        try_join_all(
+            segments
+                .into_iter()
+                .map(|range| self.segment(&url, path, range, validator.clone(), &progress)),
+        )
+        .await?;
+
+        Ok(total)
+    }
+
+    /// Split `total` bytes into segments.
+    fn split(&self, total: u64) -> Vec<Range<u64>> {
+        let count = total
+            .div_ceil(self.min_segment_size)
+            .clamp(1, self.segments as u64);
+        let size = total.div_ceil(count);
+        (0..count)
+            .map(|i| i * size..((i + 1) * size).min(total))
+            .filter(|range| !range.is_empty())
+            .collect()
+    }
+
+    /// Download one segment, resuming it after a failure.
+    async fn segment(
+        &self,
+        url: &Url,
+        path: &Path,
+        range: Range<u64>,
+        validator: Option<HeaderValue>,
+        progress: &Counter<'_>,
+    ) -> crate::Result<()> {
+        let mut start = range.start;
+        let mut retries = self.retries;
+        loop {
+            let (written, result) = self
+                .fetch(url, path, start..range.end, validator.as_ref(), progress)
+                .await;
+            start += written;
+
+            let err = match result {
+                Ok(()) if start >= range.end => return Ok(()),
+                Ok(()) => error::body("the segment ended early").with_url(url.clone()),
+                // the resource changed, so resuming won't help
+                Err(err) if err.is_decode() => return Err(err),
+                Err(err) => err,
+            };
+            if retries == 0 {
+                return Err(err);
+            }
+            retries -= 1;
+        }
+    }
+
+    /// Request `range`, and write it to the file, returning the bytes written.
+    async fn fetch(
+        &self,
+        url: &Url,
+        path: &Path,
+        range: Range<u64>,
+        validator: Option<&HeaderValue>,
+        progress: &Counter<'_>,
+    ) -> (u64, crate::Result<()>) {
+        let mut req = self.client.get(url.clone()).range(range.clone());
+        if let Some(validator) = validator {
+            req = req.header(IF_RANGE, validator.clone());
+        }
+        let res = match req.send().await.and_then(Response::error_for_status) {
+            Ok(res) => res,
+            Err(err) => return (0, Err(err)),
+        };
+
+        if res.status() != StatusCode::PARTIAL_CONTENT
+            || content_range_start(&res) != Some(range.start)
+        {
+            let err = error::decode("the server didn't send the requested range");
+            return (0, Err(err.with_url(url.clone())));
+        }
+
+        match OpenOptions::new().write(true).open(path).await {
+            Ok(file) => write_segment(file, range.start, res, progress).await,
+            Err(err) => (0, Err(error::body(err))),
+        }
+    }
+}
+
+impl fmt::Debug for Downloader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Downloader")
+            .field("segments", &self.segments)
+            .field("min_segment_size", &self.min_segment_size)
+            .field("retries", &self.retries)
+            .finish()
+    }
+}
+
+/// The length of the resource, if the response to the probe is a range.
+fn probe(res: &Response) -> Option<u64> {
+    if res.status() != StatusCode::PARTIAL_CONTENT {
+        return None;
+    }
+    let value = res.headers().get(CONTENT_RANGE)?.to_str().ok()?;
+    let (_, total) = value.rsplit_once('/')?;
+    total.trim().parse().ok().filter(|&total| total > 0)
+}
+
+fn content_range_start(res: &Response) -> Option<u64> {
+    let value = res.headers().get(CONTENT_RANGE)?.to_str().ok()?;
+    let range = value.trim().strip_prefix("bytes ")?;
+    range.split_once('-')?.0.parse().ok()
+}
+
+/// Write the body of `res` at `offset` in `file`, returning the bytes
+/// written, even when writing the rest failed.
+async fn write_segment(
+    mut file: File,
+    offset: u64,
+    mut res: Response,
+    progress: &Counter<'_>,
+) -> (u64, crate::Result<()>) {
+    let mut written = 0;
+    let result = async {
+        file.seek(SeekFrom::Start(offset))
+            .await
+            .map_err(error::body)?;
+        while let Some(chunk) = res.chunk().await? {
+            file.write_all(&chunk).await.map_err(error::body)?;
+            written += chunk.len() as u64;
+            progress.add(chunk.len() as u64);
+        }
+        file.flush().await.map_err(error::body)
+    }
+    .await;
+
+    (written, result)
+}
+
+/// Counts the bytes written by all segments.
+struct Counter<'a> {
+    downloader: &'a Downloader,
+    total: Option<u64>,
+    downloaded: AtomicU64,
+}
+
+impl<'a> Counter<'a> {
+    fn new(downloader: &'a Downloader, total: Option<u64>) -> Counter<'a> {
+        Counter {
+            downloader,
+            total,
+            downloaded: AtomicU64::new(0),
+        }
+    }
+
+    fn add(&self, bytes: u64) {
+        let downloaded = self.downloaded.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        if let Some(ref callback) = self.downloader.on_progress {
+            callback(&Progress {
+                downloaded,
+                total: self.total,
+            });
+        }
+    }
+}
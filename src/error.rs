@@ -22,6 +22,62 @@ struct Inner {
     kind: Kind,
     source: Option<BoxError>,
     url: Option<Url>,
+    attempts: Option<usize>,
+    timeout_phase: Option<TimeoutPhase>,
+}
+
+/// The phase a request was in when its timeout or deadline passed.
+///
+/// See [`Error::timeout_phase`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TimeoutPhase {
+    /// Connecting, sending the request, or waiting for the response headers.
+    Request,
+    /// Waiting to retry the request.
+    Backoff,
+    /// Reading the response body.
+    Body,
+    /// Waiting for a free slot under the client's request limits, or for its
+    /// rate limiter.
+    Queue,
+}
+
+/// The kind of an [`Error`], for handling failures without matching on
+/// messages or downcasting sources.
+///
+/// See [`Error::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A builder, like `RequestBuilder` or `ClientBuilder`, was misconfigured.
+    Builder,
+    /// Resolving the host failed.
+    Dns,
+    /// Connecting to the host failed.
+    Connect,
+    /// The TLS handshake failed, or the server's certificate was rejected.
+    TlsHandshake,
+    /// Connecting through a proxy failed.
+    Proxy,
+    /// A timeout or deadline passed.
+    Timeout {
+        /// The phase the request was in, if the total timeout or deadline
+        /// passed.
+        phase: Option<TimeoutPhase>,
+    },
+    /// Sending the request, or receiving its response, failed.
+    Request,
+    /// Following a redirect failed.
+    Redirect,
+    /// The response had an error status, see [`Response::error_for_status`](crate::Response::error_for_status).
+    Status(StatusCode),
+    /// Reading or writing a body failed.
+    Body,
+    /// Decoding the response body failed.
+    Decode,
+    /// Upgrading the connection failed.
+    Upgrade,
 }
 
 impl Error {
@@ -34,6 +90,8 @@ impl Error {
                 kind,
                 source: source.map(Into::into),
                 url: None,
+                attempts: None,
+                timeout_phase: None,
             }),
         }
     }
@@ -81,6 +139,120 @@ impl Error {
         self
     }
 
+    /// Returns the number of times the request was sent, if a retry policy was in use.
+    ///
+    /// See [`ClientBuilder::retry`](crate::ClientBuilder::retry).
+    pub fn attempts(&self) -> Option<usize> {
+        self.inner.attempts
+    }
+
+    pub(crate) fn with_attempts(mut self, attempts: usize) -> Self {
+        self.inner.attempts = Some(attempts);
+        self
+    }
+
+    /// Returns the phase the request was in, if the error is from its total
+    /// timeout or deadline passing.
+    ///
+    /// See [`RequestBuilder::deadline`](crate::RequestBuilder::deadline).
+    pub fn timeout_phase(&self) -> Option<TimeoutPhase> {
+        self.inner.timeout_phase
+    }
+
+    pub(crate) fn with_timeout_phase(mut self, phase: TimeoutPhase) -> Self {
+        self.inner.timeout_phase = Some(phase);
+        self
+    }
+
+    /// Returns the kind of the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rquest::ErrorKind;
+    ///
+    /// # async fn run() {
+    /// let res = rquest::Client::new()
+    ///     .get("https://unreachable.example")
+    ///     .send()
+    ///     .await;
+    /// if let Err(e) = res {
+    ///     match e.kind() {
+    ///         ErrorKind::Dns | ErrorKind::Connect => println!("host is down: {e}"),
+    ///         ErrorKind::Timeout { .. } => println!("too slow: {e}"),
+    ///         _ => println!("failed: {e}"),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        match self.inner.kind {
+            Kind::Builder => return ErrorKind::Builder,
+            _ if self.is_timeout() => {
+                return ErrorKind::Timeout {
+                    phase: self.inner.timeout_phase,
+                };
+            }
+            Kind::Request => (),
+            Kind::Redirect => return ErrorKind::Redirect,
+            Kind::Status(code) => return ErrorKind::Status(code),
+            Kind::Body => return ErrorKind::Body,
+            Kind::Decode => return ErrorKind::Decode,
+            Kind::Upgrade => return ErrorKind::Upgrade,
+        }
+
+        // The outermost cause wins, so a proxy that can't be resolved is
+        // a proxy error rather than a DNS one.
+        let mut source = self.source();
+        while let Some(err) = source {
+            if err.is::<ProxyError>() {
+                return ErrorKind::Proxy;
+            }
+            if err.is::<TlsHandshakeError>() {
+                return ErrorKind::TlsHandshake;
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                if err.is::<crate::core::client::connect::proxy::TunnelError>() {
+                    return ErrorKind::Proxy;
+                }
+                if err
+                    .downcast_ref::<crate::core::client::connect::ConnectError>()
+                    .is_some_and(|e| e.is_dns())
+                {
+                    return ErrorKind::Dns;
+                }
+                if err.is::<crate::tls::PinMismatch>() || err.is::<crate::tls::CertificateRevoked>()
+                {
+                    return ErrorKind::TlsHandshake;
+                }
+            }
+            source = err.source();
+        }
+
+        if self.is_connect() {
+            ErrorKind::Connect
+        } else {
+            ErrorKind::Request
+        }
+    }
+
+    /// Returns true if the request failed before any of it reached the
+    /// server, so sending it again can't make the server handle it twice.
+    ///
+    /// This is the case for failures to resolve the host, connect to it or
+    /// its proxy, and complete the TLS handshake, including timeouts while
+    /// connecting.
+    pub fn is_retry_safe(&self) -> bool {
+        match self.kind() {
+            ErrorKind::Dns | ErrorKind::Connect | ErrorKind::TlsHandshake | ErrorKind::Proxy => {
+                true
+            }
+            ErrorKind::Timeout { .. } => self.is_connect(),
+            _ => false,
+        }
+    }
+
     /// Returns true if the error is from a type Builder.
     pub fn is_builder(&self) -> bool {
         matches!(self.inner.kind, Kind::Builder)
@@ -125,6 +297,7 @@ impl Error {
         let mut source = self.source();
 
         while let Some(err) = source {
+            #[cfg(not(target_arch = "wasm32"))]
             if let Some(hyper_err) = err.downcast_ref::<crate::core::client::Error>() {
                 if hyper_err.is_connect() {
                     return true;
@@ -153,6 +326,127 @@ impl Error {
         false
     }
 
+    /// Returns true if the response body was larger than the limit set with
+    /// [`ClientBuilder::max_response_size`](crate::ClientBuilder::max_response_size)
+    /// or [`RequestBuilder::max_response_size`](crate::RequestBuilder::max_response_size).
+    pub fn is_response_too_large(&self) -> bool {
+        let mut source = self.source();
+
+        while let Some(err) = source {
+            if err.is::<ResponseTooLarge>() {
+                return true;
+            }
+            source = err.source();
+        }
+
+        false
+    }
+
+    /// Returns true if a compressed response body was stopped for
+    /// decompressing to more than the limits set with
+    /// [`ClientBuilder::max_decompressed_size`](crate::ClientBuilder::max_decompressed_size)
+    /// or [`ClientBuilder::max_decompression_ratio`](crate::ClientBuilder::max_decompression_ratio).
+    ///
+    /// # Optional
+    ///
+    /// This requires at least one of the optional `gzip`, `brotli`, `zstd`
+    /// or `deflate` features to be enabled.
+    #[cfg(any(
+        feature = "gzip",
+        feature = "brotli",
+        feature = "zstd",
+        feature = "deflate"
+    ))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            feature = "gzip",
+            feature = "brotli",
+            feature = "zstd",
+            feature = "deflate"
+        )))
+    )]
+    pub fn is_decompression_bomb(&self) -> bool {
+        let mut source = self.source();
+
+        while let Some(err) = source {
+            if err.is::<DecompressionBomb>() {
+                return true;
+            }
+            source = err.source();
+        }
+
+        false
+    }
+
+    /// Returns true if the body of a response ended before all of it was
+    /// received, like when the connection closed before as many bytes as its
+    /// `Content-Length`, or before the last chunk of a chunked body.
+    pub fn is_incomplete_body(&self) -> bool {
+        let mut source = self.source();
+
+        while let Some(err) = source {
+            if let Some(io) = err.downcast_ref::<io::Error>() {
+                if io.kind() == io::ErrorKind::UnexpectedEof {
+                    return true;
+                }
+            }
+            source = err.source();
+        }
+
+        false
+    }
+
+    /// Returns true if the server's certificate chain matched none of the keys
+    /// pinned with [`ClientBuilder::pin_certificates`](crate::ClientBuilder::pin_certificates).
+    pub fn is_certificate_pin_mismatch(&self) -> bool {
+        let mut source = self.source();
+
+        while let Some(err) = source {
+            #[cfg(not(target_arch = "wasm32"))]
+            if err.is::<crate::tls::PinMismatch>() {
+                return true;
+            }
+            source = err.source();
+        }
+
+        false
+    }
+
+    /// Returns true if a stapled OCSP response reported the server's certificate
+    /// as revoked. See [`ClientBuilder::ocsp_stapling`](crate::ClientBuilder::ocsp_stapling).
+    pub fn is_certificate_revoked(&self) -> bool {
+        let mut source = self.source();
+
+        while let Some(err) = source {
+            #[cfg(not(target_arch = "wasm32"))]
+            if err.is::<crate::tls::CertificateRevoked>() {
+                return true;
+            }
+            source = err.source();
+        }
+
+        false
+    }
+
+    /// Returns the response of a proxy that refused to open a tunnel with
+    /// `CONNECT`, such as a `407 Proxy Authentication Required`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn proxy_connect_response(&self) -> Option<&crate::proxy::ConnectResponse> {
+        let mut source = self.source();
+
+        while let Some(err) = source {
+            if let Some(err) =
+                err.downcast_ref::<crate::core::client::connect::proxy::TunnelError>()
+            {
+                return err.response();
+            }
+            source = err.source();
+        }
+
+        None
+    }
+
     /// Returns true if the error is related to the request or response body
     pub fn is_body(&self) -> bool {
         matches!(self.inner.kind, Kind::Body)
@@ -168,6 +462,18 @@ impl Error {
         matches!(self.inner.kind, Kind::Upgrade)
     }
 
+    /// Returns the path of the JSON value that failed to deserialize, like
+    /// `items[2].id`, if the error is from [`Response::json`](crate::Response::json).
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn json_path(&self) -> Option<&str> {
+        self.inner
+            .source
+            .as_ref()?
+            .downcast_ref::<JsonError>()
+            .map(|e| e.path.as_str())
+    }
+
     /// Returns the status code, if the error was generated from a response.
     pub fn status(&self) -> Option<StatusCode> {
         match self.inner.kind {
@@ -205,6 +511,12 @@ impl fmt::Debug for Error {
         if let Some(ref url) = self.inner.url {
             builder.field("url", &url.as_str());
         }
+        if let Some(attempts) = self.inner.attempts {
+            builder.field("attempts", &attempts);
+        }
+        if let Some(phase) = self.inner.timeout_phase {
+            builder.field("timeout_phase", &phase);
+        }
         if let Some(ref source) = self.inner.source {
             builder.field("source", source);
         }
@@ -251,7 +563,7 @@ impl StdError for Error {
     }
 }
 
-#[cfg(feature = "websocket")]
+#[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
 impl From<tokio_tungstenite::tungstenite::Error> for Error {
     fn from(err: tokio_tungstenite::tungstenite::Error) -> Error {
         Error::new(Kind::Upgrade, Some(err))
@@ -278,6 +590,7 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl From<boring2::error::ErrorStack> for Error {
     fn from(err: boring2::error::ErrorStack) -> Error {
         Error::new(Kind::Builder, Some(format!("boring tls error: {:?}", err)))
@@ -290,7 +603,7 @@ impl From<http::Error> for Error {
     }
 }
 
-#[cfg(feature = "hickory-dns")]
+#[cfg(all(feature = "hickory-dns", not(target_arch = "wasm32")))]
 impl From<hickory_resolver::ResolveError> for Error {
     fn from(err: hickory_resolver::ResolveError) -> Error {
         Error::new(Kind::Builder, Some(err))
@@ -350,6 +663,11 @@ pub(crate) fn uri_bad_host() -> Error {
     Error::new(Kind::Builder, Some("no host in url"))
 }
 
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn wasm(js_val: wasm_bindgen::JsValue) -> BoxError {
+    format!("{js_val:?}").into()
+}
+
 // io::Error helpers
 
 #[cfg(any(
@@ -387,6 +705,100 @@ impl fmt::Display for TimedOut {
 
 impl StdError for TimedOut {}
 
+/// A TLS handshake that failed.
+#[derive(Debug)]
+pub(crate) struct TlsHandshakeError(BoxError);
+
+impl TlsHandshakeError {
+    pub(crate) fn new<E: Into<BoxError>>(e: E) -> Self {
+        TlsHandshakeError(e.into())
+    }
+}
+
+impl fmt::Display for TlsHandshakeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TLS handshake failed: {}", self.0)
+    }
+}
+
+impl StdError for TlsHandshakeError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&*self.0)
+    }
+}
+
+/// A failure to connect through a proxy.
+#[derive(Debug)]
+pub(crate) struct ProxyError(BoxError);
+
+impl ProxyError {
+    pub(crate) fn new<E: Into<BoxError>>(e: E) -> Self {
+        ProxyError(e.into())
+    }
+}
+
+impl fmt::Display for ProxyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl StdError for ProxyError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.0.source()
+    }
+}
+
+/// A response body larger than the limit of its request.
+#[derive(Debug)]
+pub(crate) struct ResponseTooLarge {
+    limit: u64,
+}
+
+impl ResponseTooLarge {
+    pub(crate) fn new(limit: u64) -> Self {
+        ResponseTooLarge { limit }
+    }
+}
+
+impl fmt::Display for ResponseTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "response body is larger than {} bytes", self.limit)
+    }
+}
+
+impl StdError for ResponseTooLarge {}
+
+/// A compressed response body that decompressed to more than its limits.
+#[cfg(any(
+    feature = "gzip",
+    feature = "brotli",
+    feature = "zstd",
+    feature = "deflate"
+))]
+#[derive(Debug)]
+pub(crate) struct DecompressionBomb;
+
+#[cfg(any(
+    feature = "gzip",
+    feature = "brotli",
+    feature = "zstd",
+    feature = "deflate"
+))]
+impl fmt::Display for DecompressionBomb {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("decompressed body exceeds the decompression limits")
+    }
+}
+
+#[cfg(any(
+    feature = "gzip",
+    feature = "brotli",
+    feature = "zstd",
+    feature = "deflate"
+))]
+impl StdError for DecompressionBomb {}
+
 #[derive(Debug)]
 pub(crate) struct BadScheme;
 
@@ -398,6 +810,53 @@ impl fmt::Display for BadScheme {
 
 impl StdError for BadScheme {}
 
+/// A JSON body that failed to deserialize, with the path of the value that
+/// failed and the start of the body.
+#[cfg(feature = "json")]
+#[derive(Debug)]
+pub(crate) struct JsonError {
+    path: String,
+    body: String,
+    source: serde_json::Error,
+}
+
+#[cfg(feature = "json")]
+impl JsonError {
+    /// The number of body bytes included in the error.
+    const MAX_BODY_LEN: usize = 256;
+
+    pub(crate) fn new(path: String, body: &[u8], source: serde_json::Error) -> Self {
+        let mut snippet =
+            String::from_utf8_lossy(&body[..body.len().min(Self::MAX_BODY_LEN)]).into_owned();
+        if body.len() > Self::MAX_BODY_LEN {
+            snippet.push_str("...");
+        }
+
+        JsonError {
+            path,
+            body: snippet,
+            source,
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.path != "." {
+            write!(f, "{}: ", self.path)?;
+        }
+        write!(f, "{} (body: {:?})", self.source, self.body)
+    }
+}
+
+#[cfg(feature = "json")]
+impl StdError for JsonError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&self.source)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -416,6 +875,26 @@ mod tests {
         assert_sync::<Error>();
     }
 
+    #[test]
+    fn kind_from_sources() {
+        assert_eq!(super::decode("bad").kind(), ErrorKind::Decode);
+        assert_eq!(
+            super::request(TimedOut).kind(),
+            ErrorKind::Timeout { phase: None }
+        );
+        assert_eq!(
+            super::request(TlsHandshakeError::new("bad record")).kind(),
+            ErrorKind::TlsHandshake
+        );
+        assert_eq!(
+            super::request(ProxyError::new("socks connect error")).kind(),
+            ErrorKind::Proxy
+        );
+        assert_eq!(super::request("reset").kind(), ErrorKind::Request);
+        assert!(super::request(ProxyError::new("refused")).is_retry_safe());
+        assert!(!super::request("reset").is_retry_safe());
+    }
+
     #[test]
     fn mem_size_of() {
         use std::mem::size_of;
@@ -0,0 +1,622 @@
+//! TLS and HTTP fingerprints
+//!
+//! [`Client::fingerprint`](crate::Client::fingerprint) computes the [JA3], [JA4] and
+//! [JA4H] fingerprints of the requests a `Client` sends, so a configuration can be
+//! checked against the browser it emulates without contacting a fingerprinting
+//! service.
+//!
+//! The fingerprints are taken from a real ClientHello and a real request, sent by
+//! the client to a listener on `localhost`. The TLS handshake is aborted once the
+//! ClientHello has been read.
+//!
+//! Browsers that permute their TLS extensions, like the client does with
+//! [`TlsConfigBuilder::permute_extensions`](crate::tls::TlsConfigBuilder::permute_extensions), produce a different JA3
+//! for each connection. JA4 sorts extensions and is stable.
+//!
+//! [JA3]: https://github.com/salesforce/ja3
+//! [JA4]: https://github.com/FoxIO-LLC/ja4/blob/main/technical_details/JA4.md
+//! [JA4H]: https://github.com/FoxIO-LLC/ja4/blob/main/technical_details/JA4H.md
+
+use std::fmt::Write;
+use std::io;
+use std::net::Ipv4Addr;
+use std::pin::pin;
+use std::time::Duration;
+
+use boring2::hash::MessageDigest;
+use futures_util::future::{Either, select};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::{Client, error};
+
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_CAPTURE_SIZE: usize = 64 * 1024;
+
+const EXT_SERVER_NAME: u16 = 0x0000;
+const EXT_SUPPORTED_GROUPS: u16 = 0x000a;
+const EXT_EC_POINT_FORMATS: u16 = 0x000b;
+const EXT_SIGNATURE_ALGORITHMS: u16 = 0x000d;
+const EXT_ALPN: u16 = 0x0010;
+const EXT_SUPPORTED_VERSIONS: u16 = 0x002b;
+
+/// The fingerprints of the requests sent by a `Client`.
+#[derive(Clone, Debug)]
+pub struct FingerprintReport {
+    ja3: String,
+    ja3_hash: String,
+    ja4: String,
+    ja4_r: String,
+    ja4h: String,
+}
+
+impl FingerprintReport {
+    /// Returns the JA3 string, e.g. `771,4865-4866-4867,...`.
+    pub fn ja3(&self) -> &str {
+        &self.ja3
+    }
+
+    /// Returns the MD5 hash of the JA3 string, as reported by most services.
+    pub fn ja3_hash(&self) -> &str {
+        &self.ja3_hash
+    }
+
+    /// Returns the JA4 fingerprint, e.g. `t13d1516h2_8daaf6152771_02713d6af862`.
+    pub fn ja4(&self) -> &str {
+        &self.ja4
+    }
+
+    /// Returns the raw JA4 fingerprint, with the cipher, extension and signature
+    /// algorithm lists instead of their hashes.
+    pub fn ja4_r(&self) -> &str {
+        &self.ja4_r
+    }
+
+    /// Returns the JA4H fingerprint of a `GET` request.
+    ///
+    /// The request is captured over HTTP/1.1, so the version part is always `11`.
+    pub fn ja4h(&self) -> &str {
+        &self.ja4h
+    }
+}
+
+/// Capture a ClientHello and a request sent by `client`, and fingerprint them.
+pub(crate) async fn fingerprint(client: &Client) -> crate::Result<FingerprintReport> {
+    let hello = capture(client, "https", client_hello).await?;
+    let hello = ClientHello::parse(&hello).ok_or_else(|| error::decode("invalid ClientHello"))?;
+
+    let head = capture(client, "http", request_head).await?;
+    let head = RequestHead::parse(&head).ok_or_else(|| error::decode("invalid request head"))?;
+
+    let ja3 = hello.ja3();
+    let ja3_hash = boring2::hash::hash(MessageDigest::md5(), ja3.as_bytes())
+        .map(|digest| hex(&digest))
+        .map_err(error::decode)?;
+
+    Ok(FingerprintReport {
+        ja4: hello.ja4(false),
+        ja4_r: hello.ja4(true),
+        ja3,
+        ja3_hash,
+        ja4h: head.ja4h(),
+    })
+}
+
+/// Send a request to a local listener, and read what the client sends until
+/// `parse` returns a complete message.
+async fn capture(
+    client: &Client,
+    scheme: &str,
+    parse: fn(&[u8]) -> io::Result<Option<Vec<u8>>>,
+) -> crate::Result<Vec<u8>> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .map_err(error::request)?;
+    let port = listener.local_addr().map_err(error::request)?.port();
+
+    let read = pin!(async {
+        let (stream, _) = listener.accept().await?;
+        read_message(&stream, parse).await
+    });
+    let send = pin!(
+        client
+            .get(format!("{}://localhost:{}/", scheme, port))
+            .no_proxy()
+            .send()
+    );
+
+    // the request fails once the listener has what it needs and closes the connection
+    match tokio::time::timeout(CAPTURE_TIMEOUT, select(read, send)).await {
+        Ok(Either::Left((message, _))) => message.map_err(error::request),
+        Ok(Either::Right((Err(err), _))) => Err(err),
+        Ok(Either::Right((Ok(_), _))) => Err(error::request("request completed unexpectedly")),
+        Err(_) => Err(error::request(error::TimedOut)),
+    }
+}
+
+async fn read_message(
+    stream: &TcpStream,
+    parse: fn(&[u8]) -> io::Result<Option<Vec<u8>>>,
+) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0; 4096];
+    loop {
+        if let Some(message) = parse(&buf)? {
+            return Ok(message);
+        }
+        if buf.len() > MAX_CAPTURE_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "message too large",
+            ));
+        }
+
+        stream.readable().await?;
+        match stream.try_read(&mut chunk) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => continue,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Reassemble the first handshake message from TLS records.
+fn client_hello(records: &[u8]) -> io::Result<Option<Vec<u8>>> {
+    let mut message = Vec::new();
+    let mut records = records;
+
+    while let [content_type, _, _, len_hi, len_lo, rest @ ..] = records {
+        if *content_type != 0x16 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "expected a TLS handshake record",
+            ));
+        }
+        let len = usize::from(u16::from_be_bytes([*len_hi, *len_lo]));
+        let Some(payload) = rest.get(..len) else {
+            break;
+        };
+        message.extend_from_slice(payload);
+        records = &rest[len..];
+
+        if let [_, a, b, c, ..] = message[..] {
+            let len = 4 + u32::from_be_bytes([0, a, b, c]) as usize;
+            if message.len() >= len {
+                message.truncate(len);
+                return Ok(Some(message));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Find the head of an HTTP/1 request.
+fn request_head(buf: &[u8]) -> io::Result<Option<Vec<u8>>> {
+    Ok(buf
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .map(|end| buf[..end].to_vec()))
+}
+
+/// The parts of a ClientHello that are fingerprinted.
+#[derive(Debug, Default)]
+struct ClientHello {
+    version: u16,
+    ciphers: Vec<u16>,
+    extensions: Vec<u16>,
+    curves: Vec<u16>,
+    point_formats: Vec<u8>,
+    sigalgs: Vec<u16>,
+    alpn: Option<Vec<u8>>,
+    supported_versions: Vec<u16>,
+}
+
+impl ClientHello {
+    /// Parse a ClientHello handshake message.
+    fn parse(message: &[u8]) -> Option<ClientHello> {
+        let mut r = Reader(message);
+        if r.u8()? != 0x01 {
+            return None;
+        }
+        let mut r = Reader(r.take(r.u24()?)?);
+
+        let mut hello = ClientHello {
+            version: r.u16()?,
+            ..Default::default()
+        };
+        r.take(32)?;
+        r.vec8()?;
+        hello.ciphers = r.vec16()?.u16s()?;
+        r.vec8()?;
+
+        let mut extensions = r.vec16()?;
+        while !extensions.0.is_empty() {
+            let ty = extensions.u16()?;
+            let mut data = extensions.vec16()?;
+            hello.extensions.push(ty);
+
+            match ty {
+                EXT_SUPPORTED_GROUPS => hello.curves = data.vec16()?.u16s()?,
+                EXT_EC_POINT_FORMATS => hello.point_formats = data.vec8()?.0.to_vec(),
+                EXT_SIGNATURE_ALGORITHMS => hello.sigalgs = data.vec16()?.u16s()?,
+                EXT_SUPPORTED_VERSIONS => hello.supported_versions = data.vec8()?.u16s()?,
+                EXT_ALPN => {
+                    let mut protos = data.vec16()?;
+                    hello.alpn = Some(protos.vec8()?.0.to_vec());
+                }
+                _ => {}
+            }
+        }
+
+        Some(hello)
+    }
+
+    fn ja3(&self) -> String {
+        fn join<T: ToString>(values: impl Iterator<Item = T>) -> String {
+            values.map(|v| v.to_string()).collect::<Vec<_>>().join("-")
+        }
+
+        format!(
+            "{},{},{},{},{}",
+            self.version,
+            join(not_grease(&self.ciphers)),
+            join(not_grease(&self.extensions)),
+            join(not_grease(&self.curves)),
+            join(self.point_formats.iter()),
+        )
+    }
+
+    fn ja4(&self, raw: bool) -> String {
+        let version = not_grease(&self.supported_versions)
+            .max()
+            .unwrap_or(self.version);
+        let version = match version {
+            0x0304 => "13",
+            0x0303 => "12",
+            0x0302 => "11",
+            0x0301 => "10",
+            0x0300 => "s3",
+            _ => "00",
+        };
+        let sni = if self.extensions.contains(&EXT_SERVER_NAME) {
+            'd'
+        } else {
+            'i'
+        };
+
+        let mut ciphers = not_grease(&self.ciphers).collect::<Vec<_>>();
+        let mut extensions = not_grease(&self.extensions).collect::<Vec<_>>();
+
+        let a = format!(
+            "t{}{}{:02}{:02}{}",
+            version,
+            sni,
+            ciphers.len().min(99),
+            extensions.len().min(99),
+            alpn_chars(self.alpn.as_deref()),
+        );
+
+        ciphers.sort_unstable();
+        extensions.retain(|&ext| ext != EXT_SERVER_NAME && ext != EXT_ALPN);
+        extensions.sort_unstable();
+
+        let ciphers = hex_list(ciphers.into_iter());
+        let mut extensions = hex_list(extensions.into_iter());
+        let sigalgs = hex_list(self.sigalgs.iter().copied());
+
+        if raw {
+            return format!("{}_{}_{}_{}", a, ciphers, extensions, sigalgs);
+        }
+
+        if !sigalgs.is_empty() {
+            extensions.push('_');
+            extensions.push_str(&sigalgs);
+        }
+        format!(
+            "{}_{}_{}",
+            a,
+            truncated_sha256(&ciphers),
+            truncated_sha256(&extensions)
+        )
+    }
+}
+
+/// The parts of a request head that are fingerprinted.
+#[derive(Debug)]
+struct RequestHead {
+    method: String,
+    version: &'static str,
+    headers: Vec<(String, String)>,
+}
+
+impl RequestHead {
+    fn parse(head: &[u8]) -> Option<RequestHead> {
+        let head = std::str::from_utf8(head).ok()?;
+        let mut lines = head.split("\r\n");
+
+        let mut request_line = lines.next()?.split(' ');
+        let method = request_line.next()?.to_owned();
+        let version = match request_line.nth(1)? {
+            "HTTP/1.0" => "10",
+            "HTTP/1.1" => "11",
+            _ => return None,
+        };
+
+        let headers = lines
+            .map(|line| {
+                let (name, value) = line.split_once(':')?;
+                Some((name.to_owned(), value.trim().to_owned()))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(RequestHead {
+            method,
+            version,
+            headers,
+        })
+    }
+
+    fn ja4h(&self) -> String {
+        let is = |name: &str, expected: &str| name.eq_ignore_ascii_case(expected);
+
+        let names = self
+            .headers
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .filter(|name| !is(name, "cookie") && !is(name, "referer"))
+            .collect::<Vec<_>>();
+        let cookies = self
+            .headers
+            .iter()
+            .filter(|(name, _)| is(name, "cookie"))
+            .flat_map(|(_, value)| value.split(';'))
+            .map(str::trim)
+            .filter(|cookie| !cookie.is_empty())
+            .collect::<Vec<_>>();
+        let referer = self.headers.iter().any(|(name, _)| is(name, "referer"));
+
+        let language = self
+            .headers
+            .iter()
+            .find(|(name, _)| is(name, "accept-language"))
+            .map(|(_, value)| {
+                let value = value
+                    .replace('-', "")
+                    .replace(';', ",")
+                    .to_ascii_lowercase();
+                let first = value.split(',').next().unwrap_or_default();
+                format!("{:0<4}", first.chars().take(4).collect::<String>())
+            })
+            .unwrap_or_else(|| "0000".to_owned());
+
+        let a = format!(
+            "{}{}{}{}{:02}{}",
+            self.method
+                .to_ascii_lowercase()
+                .chars()
+                .take(2)
+                .collect::<String>(),
+            self.version,
+            if cookies.is_empty() { 'n' } else { 'c' },
+            if referer { 'r' } else { 'n' },
+            names.len().min(99),
+            language,
+        );
+
+        let mut fields = cookies
+            .iter()
+            .map(|cookie| cookie.split_once('=').map_or(*cookie, |(name, _)| name))
+            .collect::<Vec<_>>();
+        fields.sort_unstable();
+        let mut values = cookies.clone();
+        values.sort_unstable();
+
+        format!(
+            "{}_{}_{}_{}",
+            a,
+            truncated_sha256(&names.join(",")),
+            truncated_sha256(&fields.join(",")),
+            truncated_sha256(&values.join(",")),
+        )
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        let (head, rest) = self.0.split_at_checked(n)?;
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let b = self.take(2)?;
+        Some(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u24(&mut self) -> Option<usize> {
+        let b = self.take(3)?;
+        Some(u32::from_be_bytes([0, b[0], b[1], b[2]]) as usize)
+    }
+
+    fn vec8(&mut self) -> Option<Reader<'a>> {
+        let len = self.u8()?;
+        self.take(len.into()).map(Reader)
+    }
+
+    fn vec16(&mut self) -> Option<Reader<'a>> {
+        let len = self.u16()?;
+        self.take(len.into()).map(Reader)
+    }
+
+    fn u16s(mut self) -> Option<Vec<u16>> {
+        let mut values = Vec::with_capacity(self.0.len() / 2);
+        while !self.0.is_empty() {
+            values.push(self.u16()?);
+        }
+        Some(values)
+    }
+}
+
+/// GREASE values ([RFC 8701](https://www.rfc-editor.org/rfc/rfc8701)) are left out of fingerprints.
+fn not_grease(values: &[u16]) -> impl Iterator<Item = u16> + '_ {
+    values
+        .iter()
+        .copied()
+        .filter(|&v| v & 0x0f0f != 0x0a0a || v >> 8 != v & 0xff)
+}
+
+/// The first and last characters of the first ALPN protocol.
+fn alpn_chars(alpn: Option<&[u8]>) -> String {
+    match alpn {
+        Some([first, .., last])
+            if first.is_ascii_alphanumeric() && last.is_ascii_alphanumeric() =>
+        {
+            format!("{}{}", *first as char, *last as char)
+        }
+        Some([only]) if only.is_ascii_alphanumeric() => format!("{0}{0}", *only as char),
+        Some([first, .., last]) => format!("{:x}{:x}", first >> 4, last & 0x0f),
+        Some([only]) => format!("{:x}{:x}", only >> 4, only & 0x0f),
+        _ => "00".to_owned(),
+    }
+}
+
+fn hex_list(values: impl Iterator<Item = u16>) -> String {
+    values
+        .map(|v| format!("{:04x}", v))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn truncated_sha256(value: &str) -> String {
+    if value.is_empty() {
+        return "000000000000".to_owned();
+    }
+    let mut digest = hex(&boring2::sha::sha256(value.as_bytes()));
+    digest.truncate(12);
+    digest
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut s, b| {
+        let _ = write!(s, "{:02x}", b);
+        s
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client_hello_message() -> Vec<u8> {
+        fn ext(ty: u16, data: &[u8]) -> Vec<u8> {
+            let mut ext = ty.to_be_bytes().to_vec();
+            ext.extend_from_slice(&(data.len() as u16).to_be_bytes());
+            ext.extend_from_slice(data);
+            ext
+        }
+
+        let mut extensions = Vec::new();
+        extensions.extend(ext(0x0a0a, &[]));
+        extensions.extend(ext(EXT_SERVER_NAME, &[0, 6, 0, 0, 3, b'a', b'.', b'b']));
+        extensions.extend(ext(
+            EXT_SUPPORTED_GROUPS,
+            &[0, 6, 0x2a, 0x2a, 0, 0x1d, 0, 0x17],
+        ));
+        extensions.extend(ext(EXT_EC_POINT_FORMATS, &[1, 0]));
+        extensions.extend(ext(EXT_SIGNATURE_ALGORITHMS, &[0, 4, 4, 3, 8, 4]));
+        extensions.extend(ext(
+            EXT_ALPN,
+            &[
+                0, 12, 2, b'h', b'2', 8, b'h', b't', b't', b'p', b'/', b'1', b'.', b'1',
+            ],
+        ));
+        extensions.extend(ext(EXT_SUPPORTED_VERSIONS, &[4, 0x3a, 0x3a, 3, 4]));
+
+        let mut body = vec![3, 3];
+        body.extend_from_slice(&[0; 32]);
+        body.push(0);
+        body.extend_from_slice(&[0, 6, 0x1a, 0x1a, 0x13, 0x01, 0xc0, 0x2b]);
+        body.extend_from_slice(&[1, 0]);
+        body.extend_from_slice(&(extensions.len() as u16).to_be_bytes());
+        body.extend(extensions);
+
+        let mut message = vec![1];
+        message.extend_from_slice(&(body.len() as u32).to_be_bytes()[1..]);
+        message.extend(body);
+        message
+    }
+
+    #[test]
+    fn reassembles_client_hello_records() {
+        let message = client_hello_message();
+        let (first, second) = message.split_at(10);
+
+        let mut records = Vec::new();
+        for part in [first, second] {
+            records.extend_from_slice(&[0x16, 3, 1]);
+            records.extend_from_slice(&(part.len() as u16).to_be_bytes());
+            records.extend_from_slice(part);
+        }
+
+        assert_eq!(client_hello(&records[..12]).unwrap(), None);
+        assert_eq!(client_hello(&records).unwrap(), Some(message));
+        assert!(client_hello(&[0x17, 3, 3, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn ja3_and_ja4() {
+        let hello = ClientHello::parse(&client_hello_message()).unwrap();
+
+        assert_eq!(hello.ja3(), "771,4865-49195,0-10-11-13-16-43,29-23,0");
+        assert_eq!(
+            hello.ja4(true),
+            "t13d0206h2_1301,c02b_000a,000b,000d,002b_0403,0804"
+        );
+
+        let ja4 = hello.ja4(false);
+        assert!(ja4.starts_with("t13d0206h2_"));
+        assert_eq!(ja4.len(), "t13d0206h2_".len() + 12 + 1 + 12);
+    }
+
+    #[test]
+    fn ja4_alpn_chars() {
+        assert_eq!(alpn_chars(Some(b"h2")), "h2");
+        assert_eq!(alpn_chars(Some(b"http/1.1")), "h1");
+        assert_eq!(alpn_chars(Some(&[0xab, 0xcd])), "ad");
+        assert_eq!(alpn_chars(None), "00");
+    }
+
+    #[test]
+    fn ja4h() {
+        let head = RequestHead::parse(
+            b"GET / HTTP/1.1\r\n\
+              Host: localhost\r\n\
+              User-Agent: test\r\n\
+              Accept-Language: en-US,en;q=0.9\r\n\
+              Cookie: b=2; a=1\r\n\
+              Referer: http://localhost/",
+        )
+        .unwrap();
+
+        let ja4h = head.ja4h();
+        let parts = ja4h.split('_').collect::<Vec<_>>();
+        assert_eq!(parts[0], "ge11cr03enus");
+        assert_eq!(
+            parts[1],
+            truncated_sha256("Host,User-Agent,Accept-Language")
+        );
+        assert_eq!(parts[2], truncated_sha256("a,b"));
+        assert_eq!(parts[3], truncated_sha256("a=1,b=2"));
+
+        let head = RequestHead::parse(b"POST / HTTP/1.0\r\nHost: localhost").unwrap();
+        assert!(head.ja4h().starts_with("po10nn010000_"));
+        assert!(head.ja4h().ends_with("_000000000000_000000000000"));
+    }
+}
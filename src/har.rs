@@ -0,0 +1,594 @@
+//! HTTP Archive (HAR) recording
+//!
+//! A [`HarRecorder`] set on a `ClientBuilder` records every response the
+//! client receives, together with the request it answers, as an entry of an
+//! [HTTP Archive](http://www.softwareishard.com/blog/har-12-spec/). The
+//! archive can be opened in the network panel of browser developer tools, or
+//! compared with a browser's own recording to find why a site treats the
+//! client differently.
+//!
+//! Each redirect and retry is an entry of its own. A response is recorded once
+//! its body has been read to the end or dropped, and requests that fail
+//! without a response aren't recorded. The headers are those of the request
+//! as built by the client; headers added while sending it, like `Host`, are
+//! left out.
+//!
+//! Bodies are only recorded when enabled with
+//! [`HarRecorder::record_bodies`], as they were received, before any
+//! decompression.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use rquest::har::HarRecorder;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let har = HarRecorder::new().record_bodies(64 * 1024);
+//! let client = rquest::Client::builder().har(har.clone()).build()?;
+//!
+//! client.get("https://example.com").send().await?.text().await?;
+//!
+//! # #[cfg(feature = "json")]
+//! har.archive().save("example.har")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, ready};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use antidote::Mutex;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use bytes::{Bytes, BytesMut};
+use http_body::Body as HttpBody;
+use pin_project_lite::pin_project;
+use serde::Serialize;
+
+use crate::client::body::ResponseBody;
+use crate::core::client::connect::HttpInfo;
+use crate::header::{CONTENT_ENCODING, CONTENT_TYPE, COOKIE, HeaderMap, LOCATION, SET_COOKIE};
+use crate::{Method, Url, Version};
+
+/// Records the traffic of a `Client` as an HTTP Archive.
+///
+/// Clones of a recorder share its entries, so one clone can be set on a
+/// `ClientBuilder` and another used to read the archive.
+#[derive(Clone, Default)]
+pub struct HarRecorder {
+    entries: Arc<Mutex<Vec<Entry>>>,
+    max_body_size: Option<usize>,
+}
+
+impl HarRecorder {
+    /// Create a recorder, without bodies.
+    pub fn new() -> HarRecorder {
+        HarRecorder::default()
+    }
+
+    /// Record request and response bodies, up to `max_size` bytes each.
+    ///
+    /// A body larger than that is recorded with its size only.
+    pub fn record_bodies(mut self, max_size: usize) -> HarRecorder {
+        self.max_body_size = Some(max_size);
+        self
+    }
+
+    /// Get the archive of the traffic recorded so far.
+    pub fn archive(&self) -> Har {
+        Har {
+            log: Log {
+                version: "1.2",
+                creator: Creator {
+                    name: "rquest",
+                    version: env!("CARGO_PKG_VERSION"),
+                },
+                entries: self.entries.lock().clone(),
+            },
+        }
+    }
+
+    /// Get the number of entries recorded.
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    /// Check whether no entries were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.lock().is_empty()
+    }
+
+    /// Remove the entries recorded so far.
+    pub fn clear(&self) {
+        self.entries.lock().clear();
+    }
+
+    fn push(&self, entry: Entry) {
+        self.entries.lock().push(entry);
+    }
+}
+
+impl fmt::Debug for HarRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HarRecorder")
+            .field("entries", &self.len())
+            .field("max_body_size", &self.max_body_size)
+            .finish()
+    }
+}
+
+/// An HTTP Archive, taken by [`HarRecorder::archive`].
+///
+/// The archive implements `serde::Serialize` in the HAR 1.2 format.
+#[derive(Debug, Clone, Serialize)]
+pub struct Har {
+    log: Log,
+}
+
+impl Har {
+    /// Get the number of entries in the archive.
+    pub fn len(&self) -> usize {
+        self.log.entries.len()
+    }
+
+    /// Check whether the archive has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.log.entries.is_empty()
+    }
+
+    /// Serialize the archive as JSON.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `json` feature to be enabled.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a HAR log serializes to JSON")
+    }
+
+    /// Write the archive as JSON to the file at `path`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `json` feature to be enabled.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Log {
+    version: &'static str,
+    creator: Creator,
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Creator {
+    name: &'static str,
+    version: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Entry {
+    started_date_time: String,
+    time: f64,
+    request: Request,
+    response: Response,
+    cache: Cache,
+    timings: Timings,
+    #[serde(rename = "serverIPAddress", skip_serializing_if = "Option::is_none")]
+    server_ip_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    connection: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Request {
+    method: String,
+    url: String,
+    http_version: String,
+    cookies: Vec<Cookie>,
+    headers: Vec<NameValue>,
+    query_string: Vec<NameValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    post_data: Option<PostData>,
+    headers_size: i64,
+    body_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Response {
+    status: u16,
+    status_text: String,
+    http_version: String,
+    cookies: Vec<Cookie>,
+    headers: Vec<NameValue>,
+    content: Content,
+    #[serde(rename = "redirectURL")]
+    redirect_url: String,
+    headers_size: i64,
+    body_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct NameValue {
+    name: String,
+    value: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Cookie {
+    name: String,
+    value: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PostData {
+    mime_type: String,
+    text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Content {
+    size: i64,
+    mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encoding: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Cache {}
+
+#[derive(Debug, Clone, Serialize)]
+struct Timings {
+    blocked: f64,
+    dns: f64,
+    connect: f64,
+    send: f64,
+    wait: f64,
+    receive: f64,
+    ssl: f64,
+}
+
+/// The recording of a request being sent, with the entry of its latest
+/// response until the next attempt or its body is finished.
+pub(crate) struct Recording {
+    recorder: HarRecorder,
+    started: SystemTime,
+    start: Instant,
+    wait: Duration,
+    entry: Option<Entry>,
+}
+
+impl Recording {
+    pub(crate) fn new(recorder: HarRecorder) -> Recording {
+        Recording {
+            recorder,
+            started: SystemTime::now(),
+            start: Instant::now(),
+            wait: Duration::ZERO,
+            entry: None,
+        }
+    }
+
+    /// Record the request being sent once more, after the previous response
+    /// turned out not to be the final one.
+    pub(crate) fn send(&mut self) {
+        if let Some(entry) = self.entry.take() {
+            self.recorder.push(entry);
+        }
+        self.started = SystemTime::now();
+        self.start = Instant::now();
+    }
+
+    /// Record the response headers of the current attempt.
+    pub(crate) fn response<B>(
+        &mut self,
+        method: &Method,
+        url: &Url,
+        headers: &HeaderMap,
+        body: Option<&Option<Bytes>>,
+        res: &http::Response<B>,
+    ) {
+        self.wait = self.start.elapsed();
+
+        let http_version = version(res.version());
+        let post_data = match (body, self.recorder.max_body_size) {
+            (Some(Some(bytes)), Some(max)) if bytes.len() <= max => Some(PostData {
+                mime_type: header_str(headers, &CONTENT_TYPE),
+                text: String::from_utf8_lossy(bytes).into_owned(),
+            }),
+            _ => None,
+        };
+        let request = Request {
+            method: method.to_string(),
+            url: url.to_string(),
+            http_version: http_version.clone(),
+            cookies: request_cookies(headers),
+            headers: name_values(headers),
+            query_string: url
+                .query_pairs()
+                .map(|(name, value)| NameValue {
+                    name: name.into_owned(),
+                    value: value.into_owned(),
+                })
+                .collect(),
+            post_data,
+            headers_size: -1,
+            body_size: match body {
+                Some(Some(bytes)) => bytes.len() as i64,
+                Some(None) => -1,
+                None => 0,
+            },
+        };
+
+        let response = Response {
+            status: res.status().as_u16(),
+            status_text: res.status().canonical_reason().unwrap_or("").to_owned(),
+            http_version,
+            cookies: response_cookies(res.headers()),
+            headers: name_values(res.headers()),
+            content: Content {
+                size: -1,
+                mime_type: header_str(res.headers(), &CONTENT_TYPE),
+                text: None,
+                encoding: None,
+            },
+            redirect_url: header_str(res.headers(), &LOCATION),
+            headers_size: -1,
+            body_size: -1,
+        };
+
+        let info = res.extensions().get::<HttpInfo>();
+        self.entry = Some(Entry {
+            started_date_time: iso_date(self.started),
+            time: millis(self.wait),
+            request,
+            response,
+            cache: Cache {},
+            timings: Timings {
+                blocked: -1.0,
+                dns: -1.0,
+                connect: -1.0,
+                send: 0.0,
+                wait: millis(self.wait),
+                receive: 0.0,
+                ssl: -1.0,
+            },
+            server_ip_address: info.map(|info| info.remote_addr().ip().to_string()),
+            connection: info.map(|info| info.local_addr().port().to_string()),
+        });
+    }
+
+    /// Record the body of the final response as it is read.
+    pub(crate) fn finish(
+        mut self,
+        res: http::Response<ResponseBody>,
+    ) -> http::Response<ResponseBody> {
+        let Some(entry) = self.entry.take() else {
+            return res;
+        };
+        let encoded = res.headers().contains_key(CONTENT_ENCODING);
+        let recorder = self.recorder.clone();
+        let headers_at = self.start + self.wait;
+        res.map(|body| {
+            crate::client::body::boxed(RecordingBody {
+                inner: body,
+                entry: Some(entry),
+                recorder,
+                headers_at,
+                size: 0,
+                body: Some(BytesMut::new()),
+                encoded,
+            })
+        })
+    }
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        if let Some(entry) = self.entry.take() {
+            self.recorder.push(entry);
+        }
+    }
+}
+
+pin_project! {
+    #[project = RecordingBodyProj]
+    /// Completes the entry of the final response when its body is finished.
+    struct RecordingBody<B> {
+        #[pin]
+        inner: B,
+        entry: Option<Entry>,
+        recorder: HarRecorder,
+        headers_at: Instant,
+        size: u64,
+        // the body so far, while it fits the recorder's maximum body size
+        body: Option<BytesMut>,
+        encoded: bool,
+    }
+
+    impl<B> PinnedDrop for RecordingBody<B> {
+        fn drop(this: Pin<&mut Self>) {
+            this.project().finish();
+        }
+    }
+}
+
+impl<B> RecordingBodyProj<'_, B> {
+    fn finish(&mut self) {
+        let Some(mut entry) = self.entry.take() else {
+            return;
+        };
+
+        let receive = millis(self.headers_at.elapsed());
+        entry.timings.receive = receive;
+        entry.time += receive;
+        entry.response.body_size = *self.size as i64;
+        entry.response.content.size = *self.size as i64;
+
+        if let (Some(body), Some(_)) = (self.body.take(), self.recorder.max_body_size) {
+            match std::str::from_utf8(&body) {
+                Ok(text) if !*self.encoded => entry.response.content.text = Some(text.to_owned()),
+                _ => {
+                    entry.response.content.text = Some(BASE64_STANDARD.encode(&body));
+                    entry.response.content.encoding = Some("base64");
+                }
+            }
+        }
+
+        self.recorder.push(entry);
+    }
+}
+
+impl<B> HttpBody for RecordingBody<B>
+where
+    B: HttpBody<Data = Bytes>,
+{
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Bytes>, B::Error>>> {
+        let mut this = self.project();
+        let item = ready!(this.inner.as_mut().poll_frame(cx));
+        match item {
+            Some(Ok(ref frame)) => {
+                if let Some(data) = frame.data_ref() {
+                    *this.size += data.len() as u64;
+                    let max = this.recorder.max_body_size.unwrap_or(0);
+                    if *this.size > max as u64 {
+                        *this.body = None;
+                    } else if let Some(body) = this.body.as_mut() {
+                        body.extend_from_slice(data);
+                    }
+                }
+            }
+            Some(Err(_)) | None => this.finish(),
+        }
+        Poll::Ready(item)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+fn version(version: Version) -> String {
+    format!("{:?}", version)
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn header_str(headers: &HeaderMap, name: &crate::header::HeaderName) -> String {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("")
+        .to_owned()
+}
+
+fn name_values(headers: &HeaderMap) -> Vec<NameValue> {
+    headers
+        .iter()
+        .map(|(name, value)| NameValue {
+            name: name.as_str().to_owned(),
+            value: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+        })
+        .collect()
+}
+
+fn request_cookies(headers: &HeaderMap) -> Vec<Cookie> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .map(|(name, value)| Cookie {
+            name: name.to_owned(),
+            value: value.to_owned(),
+        })
+        .collect()
+}
+
+fn response_cookies(headers: &HeaderMap) -> Vec<Cookie> {
+    headers
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|value| value.split(';').next()?.trim().split_once('='))
+        .map(|(name, value)| Cookie {
+            name: name.to_owned(),
+            value: value.to_owned(),
+        })
+        .collect()
+}
+
+/// Format `time` as an ISO 8601 date and time in UTC, with milliseconds.
+fn iso_date(time: SystemTime) -> String {
+    let since = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since.as_secs();
+    let (year, month, day) = crate::util::civil_from_days(secs / 86_400);
+    let secs = secs % 86_400;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs / 3_600,
+        secs % 3_600 / 60,
+        secs % 60,
+        since.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_iso_dates() {
+        let time = UNIX_EPOCH + Duration::from_millis(784_111_777_042);
+        assert_eq!(iso_date(time), "1994-11-06T08:49:37.042Z");
+    }
+
+    #[test]
+    fn parses_cookies() {
+        let mut headers = HeaderMap::new();
+        headers.insert(COOKIE, "a=1; b=2".parse().unwrap());
+        headers.append(SET_COOKIE, "c=3; Path=/; HttpOnly".parse().unwrap());
+
+        let names = |cookies: Vec<Cookie>| {
+            cookies
+                .into_iter()
+                .map(|c| format!("{}={}", c.name, c.value))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(request_cookies(&headers)), ["a=1", "b=2"]);
+        assert_eq!(names(response_cookies(&headers)), ["c=3"]);
+    }
+}
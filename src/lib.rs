@@ -189,6 +189,12 @@
 //! By default, the client does not handle HTTP redirects.
 //! To customize this behavior, you can use [`redirect::Policy`][redirect] with ClientBuilder.
 //!
+//! ## Caching
+//!
+//! Responses can be cached and revalidated following HTTP caching rules by setting
+//! a [`cache::CacheConfig`][cache] with the [`cache`][ClientBuilder::cache] method
+//! on `ClientBuilder`.
+//!
 //! ## Cookies
 //!
 //! The automatic storing and sending of session cookies can be enabled with
@@ -239,6 +245,14 @@
 //!
 //! Custom Certificate Store verification supports Root CA certificates, peer certificates, and self-signed certificate SSL pinning.
 //!
+//! ## WASM
+//!
+//! On `wasm32-unknown-unknown`, the `Client` sends requests with the `fetch` API
+//! of the browser or worker it runs in. The browser makes the connections, so
+//! there is no TLS or HTTP/2 emulation, and only the part of the API that maps
+//! onto `fetch` is available. See the `Client` documentation on that target for
+//! the details.
+//!
 //! ## Optional Features
 //!
 //! The following are a list of [Cargo features][cargo-features] that can be
@@ -247,19 +261,32 @@
 //! - **full**: Enables all optional features.
 //! - **websocket**: Provides websocket support.
 //! - **cookies**: Provides cookie session support.
+//! - **cookies-psl**: Rejects cookies set for public suffixes, such as `co.uk`,
+//!   with a public suffix list loaded at runtime.
+//! - **cookies-psl-embedded**: Embeds a copy of the public suffix list, used by
+//!   the default cookie jar.
 //! - **gzip**: Provides response body gzip decompression.
 //! - **brotli**: Provides response body brotli decompression.
 //! - **zstd**: Provides response body zstd decompression.
 //! - **deflate**: Provides response body deflate decompression.
 //! - **json**: Provides serialization and deserialization for JSON bodies.
+//! - **html**: Provides `Response::html`, to query documents with CSS selectors.
+//! - **charset-detection**: Provides `Response::text_with_charset_detection`, to guess
+//!   the encoding of text with a wrong or missing charset.
 //! - **multipart**: Provides functionality for multipart forms.
 //! - **stream**: Adds support for `futures::Stream`.
 //! - **socks**: Provides SOCKS5 proxy support.
+//! - **ntlm**: Provides NTLM authentication to servers and proxies.
+//! - **testing**: Provides a layer to record HTTP traffic and play it back in tests.
+//! - **blocking**: Provides the `blocking` client API, for programs that don't use async.
 //! - **hickory-dns**: Enables a hickory-dns async resolver instead of default
 //!   threadpool using `getaddrinfo`.
 //! - **native-roots**: Use the native system root certificate store.
+//! - **native-trust**: Provides `ClientBuilder::native_trust`, to verify certificates
+//!   with the system verifier on macOS and Windows.
 //! - **webpki-roots**: Use the webpki-roots crate for root certificates.
-//! - **tracing**: Enable tracing.
+//! - **tracing**: Enable tracing, with spans for the requests and the DNS, connect and TLS
+//!   handshake phases of their connections.
 //! - **internal_proxy_sys_no_cache**: Use the internal proxy system with no cache.
 //!
 //! [hyper]: http://hyper.rs
@@ -272,6 +299,20 @@
 //! [Proxy]: ./struct.Proxy.html
 //! [cargo-features]: https://doc.rust-lang.org/stable/cargo/reference/manifest.html#the-features-section
 
+macro_rules! if_wasm {
+    ($($item:item)*) => {$(
+        #[cfg(target_arch = "wasm32")]
+        $item
+    )*}
+}
+
+macro_rules! if_native {
+    ($($item:item)*) => {$(
+        #[cfg(not(target_arch = "wasm32"))]
+        $item
+    )*}
+}
+
 #[macro_use]
 mod trace;
 
@@ -280,65 +321,110 @@ pub use http::header;
 pub use http::{StatusCode, Version};
 pub use url::Url;
 
-mod config;
 mod error;
 mod into_url;
 mod response;
 
-pub use self::error::{Error, Result};
+pub use self::error::{Error, ErrorKind, Result, TimeoutPhase};
 pub use self::into_url::IntoUrl;
 pub use self::response::ResponseBuilderExt;
 
-fn _assert_impls() {
-    fn assert_send<T: Send>() {}
-    fn assert_sync<T: Sync>() {}
-    fn assert_clone<T: Clone>() {}
+mod util;
 
-    assert_send::<Client>();
-    assert_sync::<Client>();
-    assert_clone::<Client>();
+if_native! {
+    fn _assert_impls() {
+        fn assert_send<T: Send>() {}
+        fn assert_sync<T: Sync>() {}
+        fn assert_clone<T: Clone>() {}
 
-    assert_send::<Request>();
-    assert_send::<RequestBuilder>();
-    #[cfg(feature = "websocket")]
-    assert_send::<websocket::WebSocketRequestBuilder>();
+        assert_send::<Client>();
+        assert_sync::<Client>();
+        assert_clone::<Client>();
 
-    assert_send::<Response>();
-    #[cfg(feature = "websocket")]
-    assert_send::<websocket::WebSocketResponse>();
-    #[cfg(feature = "websocket")]
-    assert_send::<websocket::WebSocket>();
+        assert_send::<Request>();
+        assert_send::<RequestBuilder>();
+        #[cfg(feature = "websocket")]
+        assert_send::<websocket::WebSocketRequestBuilder>();
 
-    assert_send::<Error>();
-    assert_sync::<Error>();
-}
+        assert_send::<Response>();
+        #[cfg(feature = "websocket")]
+        assert_send::<websocket::WebSocketResponse>();
+        #[cfg(feature = "websocket")]
+        assert_send::<websocket::WebSocket>();
 
-#[cfg(test)]
-doc_comment::doctest!("../README.md");
+        assert_send::<Error>();
+        assert_sync::<Error>();
+    }
 
-#[cfg(feature = "multipart")]
-pub use self::client::multipart;
-#[cfg(feature = "websocket")]
-pub use self::client::websocket;
+    #[cfg(test)]
+    doc_comment::doctest!("../README.md");
 
-pub use self::client::{
-    Body, Client, ClientBuilder, ClientUpdate, EmulationProvider, EmulationProviderFactory,
-    Request, RequestBuilder, Response, Upgraded,
-};
-pub use self::core::client::Dst;
-pub use self::core::config::{http1, http2};
-pub use self::proxy::{NoProxy, Proxy};
+    #[cfg(any(
+        feature = "gzip",
+        feature = "brotli",
+        feature = "zstd",
+        feature = "deflate"
+    ))]
+    pub use self::client::Encoding;
+    #[cfg(feature = "cookies")]
+    pub use self::client::Session;
+    #[cfg(feature = "html")]
+    pub use self::client::html;
+    #[cfg(feature = "multipart")]
+    pub use self::client::multipart;
+    pub use self::client::push;
+    pub use self::client::range;
+    pub use self::client::sse;
+    #[cfg(feature = "websocket")]
+    pub use self::client::websocket;
 
-mod client;
-mod connect;
-#[cfg(feature = "cookies")]
-pub mod cookie;
+    pub use self::client::{
+        AwsCredentials, Body, BodySender, Client, ClientBuilder, ClientUpdate, ConnectMetrics,
+        EmulationProvider, EmulationProviderFactory, MetricsObserver, Params, Request,
+        RequestBuilder, RequestMetrics, Response, Timeouts, TokenFuture, TokenProvider, Upgraded,
+        VerboseConfig,
+    };
+    pub use self::connect::TcpOptions;
+    pub use self::core::client::{
+        Dst, EvictReason, HostPoolStats, PoolEvent, PoolEventKind, PoolStats,
+    };
+    pub use self::core::config::{http1, http2};
+    pub use self::proxy::{ConnectResponse, NoProxy, Proxy, ProxyPool, ProxyStrategy};
 
-mod core;
-pub mod dns;
-mod proxy;
+    #[cfg(feature = "blocking")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
+    pub mod blocking;
+    pub mod cache;
+    mod client;
+    mod config;
+    mod connect;
+    #[cfg(feature = "cookies")]
+    pub mod cookie;
 
-pub mod redirect;
+    mod core;
+    pub mod dns;
+    #[cfg(feature = "stream")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    pub mod downloader;
+    pub mod fingerprint;
+    pub mod har;
+    #[cfg(feature = "ntlm")]
+    mod ntlm;
+    mod proxy;
 
-pub mod tls;
-mod util;
+    pub mod ratelimit;
+    pub mod redirect;
+    pub mod retry;
+    pub mod rt;
+
+    #[cfg(feature = "testing")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+    pub mod testing;
+    pub mod tls;
+}
+
+if_wasm! {
+    mod wasm;
+
+    pub use self::wasm::{Body, Client, ClientBuilder, Request, RequestBuilder, Response};
+}
@@ -0,0 +1,452 @@
+//! NTLM authentication ([MS-NLMP](https://learn.microsoft.com/openspecs/windows_protocols/ms-nlmp))
+//!
+//! Only NTLMv2 responses are sent. Servers and proxies offering `Negotiate`
+//! are answered with NTLM tokens too, which Windows accepts in place of
+//! Kerberos.
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use boring2::hash::{MessageDigest, hash};
+
+use crate::header::{HeaderMap, HeaderName, HeaderValue};
+
+const SIGNATURE: &[u8; 8] = b"NTLMSSP\0";
+
+const NEGOTIATE_UNICODE: u32 = 0x0000_0001;
+const NEGOTIATE_OEM: u32 = 0x0000_0002;
+const REQUEST_TARGET: u32 = 0x0000_0004;
+const NEGOTIATE_NTLM: u32 = 0x0000_0200;
+const NEGOTIATE_ALWAYS_SIGN: u32 = 0x0000_8000;
+const NEGOTIATE_EXTENDED_SESSIONSECURITY: u32 = 0x0008_0000;
+
+const NEGOTIATE_FLAGS: u32 = NEGOTIATE_UNICODE
+    | NEGOTIATE_OEM
+    | REQUEST_TARGET
+    | NEGOTIATE_NTLM
+    | NEGOTIATE_ALWAYS_SIGN
+    | NEGOTIATE_EXTENDED_SESSIONSECURITY;
+
+const AV_EOL: u16 = 0;
+const AV_TIMESTAMP: u16 = 7;
+
+/// Seconds between 1601-01-01, the epoch of Windows file times, and 1970-01-01.
+const FILETIME_UNIX_EPOCH: u64 = 11_644_473_600;
+
+/// The account to authenticate with NTLM.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) struct NtlmCredentials {
+    domain: String,
+    username: String,
+    password: String,
+}
+
+impl NtlmCredentials {
+    pub(crate) fn new(domain: &str, username: &str, password: &str) -> NtlmCredentials {
+        NtlmCredentials {
+            domain: domain.to_owned(),
+            username: username.to_owned(),
+            password: password.to_owned(),
+        }
+    }
+
+    /// The header value starting the handshake, in the given auth `scheme`.
+    pub(crate) fn negotiate(&self, scheme: Scheme) -> HeaderValue {
+        header(scheme, &negotiate_message())
+    }
+
+    /// The header value answering the challenge in `headers`, the
+    /// `WWW-Authenticate` or `Proxy-Authenticate` headers named `name`.
+    pub(crate) fn authenticate(
+        &self,
+        headers: &HeaderMap,
+        name: &HeaderName,
+    ) -> Option<HeaderValue> {
+        let (scheme, challenge) = headers.get_all(name).iter().find_map(|value| {
+            let (scheme, token) = value.to_str().ok()?.split_once(' ')?;
+            let scheme = Scheme::parse(scheme)?;
+            Some((scheme, BASE64_STANDARD.decode(token.trim()).ok()?))
+        })?;
+        let challenge = Challenge::parse(&challenge)?;
+
+        let mut client_challenge = [0; 8];
+        boring2::rand::rand_bytes(&mut client_challenge).ok()?;
+        let message = self.authenticate_message(&challenge, client_challenge, filetime_now())?;
+        Some(header(scheme, &message))
+    }
+
+    fn authenticate_message(
+        &self,
+        challenge: &Challenge,
+        client_challenge: [u8; 8],
+        now: u64,
+    ) -> Option<Vec<u8>> {
+        let nt_hash = md4(&utf16(&self.password));
+        let user_domain = utf16(&(self.username.to_uppercase() + &self.domain));
+        let ntowf = hmac_md5(&nt_hash, &user_domain)?;
+
+        // prefer the server's clock, as the server compares it with its own
+        let timestamp = challenge.timestamp.unwrap_or(now);
+
+        let mut blob = vec![1, 1, 0, 0, 0, 0, 0, 0];
+        blob.extend_from_slice(&timestamp.to_le_bytes());
+        blob.extend_from_slice(&client_challenge);
+        blob.extend_from_slice(&[0; 4]);
+        blob.extend_from_slice(&challenge.target_info);
+        blob.extend_from_slice(&[0; 4]);
+
+        let nt_proof = hmac_md5(&ntowf, &[&challenge.server_challenge[..], &blob].concat())?;
+        let nt_response = [&nt_proof[..], &blob].concat();
+
+        let lm_response = if challenge.timestamp.is_some() {
+            vec![0; 24]
+        } else {
+            let proof = hmac_md5(
+                &ntowf,
+                &[challenge.server_challenge, client_challenge].concat(),
+            )?;
+            [&proof[..], &client_challenge].concat()
+        };
+
+        let flags = challenge.flags & NEGOTIATE_FLAGS | NEGOTIATE_UNICODE;
+        let fields = [
+            lm_response,
+            nt_response,
+            utf16(&self.domain),
+            utf16(&self.username),
+            // workstation
+            Vec::new(),
+            // session key
+            Vec::new(),
+        ];
+
+        let mut message = SIGNATURE.to_vec();
+        message.extend_from_slice(&3u32.to_le_bytes());
+        let mut offset = 64;
+        for field in &fields {
+            let len = u16::try_from(field.len()).ok()?;
+            message.extend_from_slice(&len.to_le_bytes());
+            message.extend_from_slice(&len.to_le_bytes());
+            message.extend_from_slice(&(offset as u32).to_le_bytes());
+            offset += field.len();
+        }
+        message.extend_from_slice(&flags.to_le_bytes());
+        for field in fields {
+            message.extend(field);
+        }
+        Some(message)
+    }
+}
+
+impl fmt::Debug for NtlmCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NtlmCredentials")
+            .field("domain", &self.domain)
+            .field("username", &self.username)
+            .finish()
+    }
+}
+
+/// The auth scheme carrying NTLM tokens.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Scheme {
+    Ntlm,
+    Negotiate,
+}
+
+impl Scheme {
+    /// The scheme to answer the challenges in `headers` with, if any.
+    pub(crate) fn offered(headers: &HeaderMap, name: &HeaderName) -> Option<Scheme> {
+        let offered = headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| Scheme::parse(value.split(' ').next()?))
+            .collect::<Vec<_>>();
+
+        // plain NTLM is the more likely to work, as Negotiate may expect Kerberos
+        [Scheme::Ntlm, Scheme::Negotiate]
+            .into_iter()
+            .find(|scheme| offered.contains(scheme))
+    }
+
+    fn parse(scheme: &str) -> Option<Scheme> {
+        if scheme.eq_ignore_ascii_case("NTLM") {
+            Some(Scheme::Ntlm)
+        } else if scheme.eq_ignore_ascii_case("Negotiate") {
+            Some(Scheme::Negotiate)
+        } else {
+            None
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Scheme::Ntlm => "NTLM",
+            Scheme::Negotiate => "Negotiate",
+        }
+    }
+}
+
+/// A `CHALLENGE_MESSAGE`.
+#[derive(Debug)]
+struct Challenge {
+    flags: u32,
+    server_challenge: [u8; 8],
+    target_info: Vec<u8>,
+    timestamp: Option<u64>,
+}
+
+impl Challenge {
+    fn parse(message: &[u8]) -> Option<Challenge> {
+        if message.get(..8)? != SIGNATURE || u32_at(message, 8)? != 2 {
+            return None;
+        }
+        let flags = u32_at(message, 20)?;
+        let server_challenge = message.get(24..32)?.try_into().ok()?;
+
+        let target_info = match (u16_at(message, 40), u32_at(message, 44)) {
+            (Some(len), Some(offset)) if len > 0 => message
+                .get(offset as usize..offset as usize + len as usize)?
+                .to_vec(),
+            _ => Vec::new(),
+        };
+
+        let mut timestamp = None;
+        let mut pairs = &target_info[..];
+        while pairs.len() >= 4 {
+            let id = u16_at(pairs, 0)?;
+            let len = u16_at(pairs, 2)? as usize;
+            let value = pairs.get(4..4 + len)?;
+            match id {
+                AV_EOL => break,
+                AV_TIMESTAMP => timestamp = Some(u64::from_le_bytes(value.try_into().ok()?)),
+                _ => {}
+            }
+            pairs = &pairs[4 + len..];
+        }
+
+        Some(Challenge {
+            flags,
+            server_challenge,
+            target_info,
+            timestamp,
+        })
+    }
+}
+
+fn negotiate_message() -> Vec<u8> {
+    let mut message = SIGNATURE.to_vec();
+    message.extend_from_slice(&1u32.to_le_bytes());
+    message.extend_from_slice(&NEGOTIATE_FLAGS.to_le_bytes());
+    // no domain or workstation
+    message.extend_from_slice(&[0; 16]);
+    message
+}
+
+fn header(scheme: Scheme, message: &[u8]) -> HeaderValue {
+    let value = format!("{} {}", scheme.as_str(), BASE64_STANDARD.encode(message));
+    let mut header = HeaderValue::try_from(value).expect("base64 is always valid HeaderValue");
+    header.set_sensitive(true);
+    header
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn utf16(s: &str) -> Vec<u8> {
+    s.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
+
+fn filetime_now() -> u64 {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    (since_epoch.as_secs() + FILETIME_UNIX_EPOCH) * 10_000_000
+        + u64::from(since_epoch.subsec_nanos() / 100)
+}
+
+fn hmac_md5(key: &[u8], data: &[u8]) -> Option<[u8; 16]> {
+    const BLOCK: usize = 64;
+
+    let mut block = [0; BLOCK];
+    if key.len() > BLOCK {
+        block[..16].copy_from_slice(&hash(MessageDigest::md5(), key).ok()?);
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let inner = block.iter().map(|b| b ^ 0x36).chain(data.iter().copied());
+    let inner = hash(MessageDigest::md5(), &inner.collect::<Vec<_>>()).ok()?;
+    let outer = block.iter().map(|b| b ^ 0x5c).chain(inner.iter().copied());
+    let outer = hash(MessageDigest::md5(), &outer.collect::<Vec<_>>()).ok()?;
+    outer.as_ref().try_into().ok()
+}
+
+/// MD4 ([RFC 1320](https://www.rfc-editor.org/rfc/rfc1320)), which NTLM hashes
+/// passwords with.
+fn md4(data: &[u8]) -> [u8; 16] {
+    // the word order and shifts of the rounds
+    const ORDER: [[usize; 16]; 3] = [
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+        [0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15],
+        [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15],
+    ];
+    const SHIFT: [[u32; 4]; 3] = [[3, 7, 11, 19], [3, 5, 9, 13], [3, 9, 11, 15]];
+    const CONSTANT: [u32; 3] = [0, 0x5a82_7999, 0x6ed9_eba1];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_le_bytes());
+
+    let mut state: [u32; 4] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+    for chunk in message.chunks_exact(64) {
+        let mut x = [0u32; 16];
+        for (word, bytes) in x.iter_mut().zip(chunk.chunks_exact(4)) {
+            *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+
+        let mut v = state;
+        for round in 0..3 {
+            for step in 0..16 {
+                // the registers rotate each step: a, d, c, b
+                let [a, b, c, d] = [0, 1, 2, 3].map(|i| (i + 4 - step % 4) % 4);
+                let mix = match round {
+                    0 => (v[b] & v[c]) | (!v[b] & v[d]),
+                    1 => (v[b] & v[c]) | (v[b] & v[d]) | (v[c] & v[d]),
+                    _ => v[b] ^ v[c] ^ v[d],
+                };
+                v[a] = v[a]
+                    .wrapping_add(mix)
+                    .wrapping_add(x[ORDER[round][step]])
+                    .wrapping_add(CONSTANT[round])
+                    .rotate_left(SHIFT[round][step % 4]);
+            }
+        }
+
+        for (word, v) in state.iter_mut().zip(v) {
+            *word = word.wrapping_add(v);
+        }
+    }
+
+    let mut digest = [0; 16];
+    for (out, word) in digest.chunks_exact_mut(4).zip(state) {
+        out.copy_from_slice(&word.to_le_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn md4_test_vectors() {
+        assert_eq!(hex(&md4(b"")), "31d6cfe0d16ae931b73c59d7e0c089c0");
+        assert_eq!(hex(&md4(b"abc")), "a448017aaf21d8525fc10ae87aa6729d");
+        assert_eq!(
+            hex(&md4(
+                b"12345678901234567890123456789012345678901234567890123456789012345678901234567890"
+            )),
+            "e33b4ddc9c38f2199c3e7b164fcc0536"
+        );
+    }
+
+    // the NTLMv2 example of MS-NLMP, section 4.2.4
+    #[test]
+    fn ntlmv2_response() {
+        let target_info = [
+            &[0x02, 0x00, 0x0c, 0x00][..],
+            &utf16("Domain"),
+            &[0x01, 0x00, 0x0c, 0x00],
+            &utf16("Server"),
+            &[0x00, 0x00, 0x00, 0x00],
+        ]
+        .concat();
+        let challenge = Challenge {
+            flags: 0xe28a_8233,
+            server_challenge: [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef],
+            target_info,
+            timestamp: None,
+        };
+
+        let credentials = NtlmCredentials::new("Domain", "User", "Password");
+        assert_eq!(
+            hex(&md4(&utf16("Password"))),
+            "a4f49c406510bdcab6824ee7c30fd852"
+        );
+
+        let message = credentials
+            .authenticate_message(&challenge, [0xaa; 8], 0)
+            .unwrap();
+        assert_eq!(&message[..8], SIGNATURE);
+        assert_eq!(u32_at(&message, 8), Some(3));
+
+        let nt_len = u16_at(&message, 20).unwrap() as usize;
+        let nt_offset = u32_at(&message, 24).unwrap() as usize;
+        let nt_response = &message[nt_offset..nt_offset + nt_len];
+        assert_eq!(hex(&nt_response[..16]), "68cd0ab851e51c96aabc927bebef6a1c");
+
+        let lm_offset = u32_at(&message, 16).unwrap() as usize;
+        assert_eq!(
+            hex(&message[lm_offset..lm_offset + 24]),
+            "86c35097ac9cec102554764a57cccc19aaaaaaaaaaaaaaaa"
+        );
+    }
+
+    #[test]
+    fn parses_challenge() {
+        let mut message = SIGNATURE.to_vec();
+        message.extend_from_slice(&2u32.to_le_bytes());
+        message.extend_from_slice(&[0; 8]);
+        message.extend_from_slice(&0x0000_8205u32.to_le_bytes());
+        message.extend_from_slice(&[7; 8]);
+        message.extend_from_slice(&[0; 8]);
+        let pairs = [
+            &[0x07, 0x00, 0x08, 0x00][..],
+            &42u64.to_le_bytes(),
+            &[0x00, 0x00, 0x00, 0x00],
+        ]
+        .concat();
+        message.extend_from_slice(&(pairs.len() as u16).to_le_bytes());
+        message.extend_from_slice(&(pairs.len() as u16).to_le_bytes());
+        message.extend_from_slice(&48u32.to_le_bytes());
+        message.extend_from_slice(&pairs);
+
+        let challenge = Challenge::parse(&message).unwrap();
+        assert_eq!(challenge.flags, 0x0000_8205);
+        assert_eq!(challenge.server_challenge, [7; 8]);
+        assert_eq!(challenge.target_info, pairs);
+        assert_eq!(challenge.timestamp, Some(42));
+
+        assert!(Challenge::parse(&message[..30]).is_none());
+        assert!(Challenge::parse(&negotiate_message()).is_none());
+    }
+
+    #[test]
+    fn prefers_ntlm_scheme() {
+        let name = crate::header::WWW_AUTHENTICATE;
+        let mut headers = HeaderMap::new();
+        headers.append(&name, HeaderValue::from_static("Negotiate"));
+        assert_eq!(Scheme::offered(&headers, &name), Some(Scheme::Negotiate));
+        headers.append(&name, HeaderValue::from_static("NTLM"));
+        assert_eq!(Scheme::offered(&headers, &name), Some(Scheme::Ntlm));
+        headers.clear();
+        headers.append(&name, HeaderValue::from_static("Basic realm=\"x\""));
+        assert_eq!(Scheme::offered(&headers, &name), None);
+    }
+}
@@ -5,9 +5,12 @@ use std::net::SocketAddr;
 use std::sync::{Arc, LazyLock};
 
 use crate::Url;
+use crate::client::digest::DigestAuth;
 use crate::into_url::{IntoUrl, IntoUrlSealed};
+#[cfg(feature = "ntlm")]
+use crate::ntlm::NtlmCredentials;
 
-use http::HeaderMap;
+use http::{HeaderMap, StatusCode};
 use http::{Uri, header::HeaderValue};
 use ipnet::IpNet;
 use percent_encoding::percent_decode;
@@ -35,6 +38,10 @@ use system_configuration::{
 #[cfg(target_os = "windows")]
 use windows_registry::CURRENT_USER;
 
+pub use self::pool::{ProxyPool, ProxyStrategy};
+
+mod pool;
+
 /// Configuration of a proxy that a `Client` should pass requests to.
 ///
 /// A `Proxy` has a couple pieces to it:
@@ -97,6 +104,86 @@ pub struct NoProxy {
     domains: DomainMatcher,
 }
 
+/// The response of a proxy that refused to open a tunnel with `CONNECT`.
+///
+/// Proxies often explain the refusal in the status and headers, such as a
+/// `402 Payment Required` or an `X-Proxy-Error` header. It's available from a
+/// failed request with [`Error::proxy_connect_response`](crate::Error::proxy_connect_response).
+#[derive(Clone, Debug)]
+pub struct ConnectResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+}
+
+impl ConnectResponse {
+    pub(crate) fn new(status: StatusCode, headers: HeaderMap) -> ConnectResponse {
+        ConnectResponse { status, headers }
+    }
+
+    /// The status code of the response.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// The headers of the response.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+}
+
+/// The username and password to authenticate to a proxy with, looked up again
+/// for each connection.
+#[derive(Clone)]
+pub(crate) struct ProxyCredentials {
+    get: Arc<dyn Fn() -> (String, String) + Send + Sync>,
+    digest: bool,
+}
+
+impl ProxyCredentials {
+    /// The `Proxy-Authorization` header to send up front, for Basic credentials.
+    pub(crate) fn basic_auth(&self) -> Option<HeaderValue> {
+        if self.digest {
+            return None;
+        }
+        let (username, password) = (self.get)();
+        Some(encode_basic_auth(&username, &password))
+    }
+
+    /// The credentials to answer a Digest challenge of the proxy with.
+    pub(crate) fn digest_auth(&self) -> Option<DigestAuth> {
+        if !self.digest {
+            return None;
+        }
+        let (username, password) = (self.get)();
+        Some(DigestAuth::new(username, password))
+    }
+}
+
+// Connections are pooled by proxy, so credentials compare by identity: two
+// clones of one callback share connections, two separate callbacks don't.
+impl PartialEq for ProxyCredentials {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.get, &other.get) && self.digest == other.digest
+    }
+}
+
+impl Eq for ProxyCredentials {}
+
+impl Hash for ProxyCredentials {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (Arc::as_ptr(&self.get) as *const () as usize).hash(state);
+        self.digest.hash(state);
+    }
+}
+
+impl fmt::Debug for ProxyCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProxyCredentials")
+            .field("digest", &self.digest)
+            .finish_non_exhaustive()
+    }
+}
+
 /// A particular scheme used for proxying requests.
 ///
 /// For example, HTTP vs SOCKS5
@@ -106,11 +193,19 @@ pub enum ProxyScheme {
         auth: Option<HeaderValue>,
         host: http::uri::Authority,
         headers: Option<Arc<HeaderMap>>,
+        connect_headers: Option<Arc<HeaderMap>>,
+        credentials: Option<ProxyCredentials>,
+        #[cfg(feature = "ntlm")]
+        ntlm: Option<NtlmCredentials>,
     },
     Https {
         auth: Option<HeaderValue>,
         host: http::uri::Authority,
         headers: Option<Arc<HeaderMap>>,
+        connect_headers: Option<Arc<HeaderMap>>,
+        credentials: Option<ProxyCredentials>,
+        #[cfg(feature = "ntlm")]
+        ntlm: Option<NtlmCredentials>,
     },
     #[cfg(feature = "socks")]
     Socks4 { addr: SocketAddr, remote_dns: bool },
@@ -123,7 +218,7 @@ pub enum ProxyScheme {
 }
 
 impl ProxyScheme {
-    fn maybe_http_auth(&self) -> Option<&HeaderValue> {
+    pub(crate) fn maybe_http_auth(&self) -> Option<&HeaderValue> {
         match self {
             ProxyScheme::Http { auth, .. } | ProxyScheme::Https { auth, .. } => auth.as_ref(),
             #[cfg(feature = "socks")]
@@ -131,7 +226,7 @@ impl ProxyScheme {
         }
     }
 
-    fn maybe_http_headers(&self) -> Option<&HeaderMap> {
+    pub(crate) fn maybe_http_headers(&self) -> Option<&HeaderMap> {
         match self {
             ProxyScheme::Http { headers, .. } | ProxyScheme::Https { headers, .. } => {
                 headers.as_deref()
@@ -140,6 +235,62 @@ impl ProxyScheme {
             _ => None,
         }
     }
+
+    /// Whether requests forwarded to the proxy carry a `Proxy-Authorization`
+    /// header, without calling a credentials callback.
+    pub(crate) fn has_http_auth(&self) -> bool {
+        match self {
+            ProxyScheme::Http {
+                auth, credentials, ..
+            }
+            | ProxyScheme::Https {
+                auth, credentials, ..
+            } => auth.is_some() || credentials.as_ref().is_some_and(|c| !c.digest),
+            #[cfg(feature = "socks")]
+            _ => false,
+        }
+    }
+
+    /// The `Proxy-Authorization` header to send to the proxy, from its
+    /// credentials callback if it has one.
+    pub(crate) fn http_auth(&self) -> Option<HeaderValue> {
+        match self {
+            ProxyScheme::Http {
+                auth, credentials, ..
+            }
+            | ProxyScheme::Https {
+                auth, credentials, ..
+            } => match credentials {
+                Some(credentials) => credentials.basic_auth(),
+                None => auth.clone(),
+            },
+            #[cfg(feature = "socks")]
+            _ => None,
+        }
+    }
+
+    pub(crate) fn maybe_credentials(&self) -> Option<&ProxyCredentials> {
+        match self {
+            ProxyScheme::Http { credentials, .. } | ProxyScheme::Https { credentials, .. } => {
+                credentials.as_ref()
+            }
+            #[cfg(feature = "socks")]
+            _ => None,
+        }
+    }
+
+    pub(crate) fn maybe_connect_headers(&self) -> Option<&HeaderMap> {
+        match self {
+            ProxyScheme::Http {
+                connect_headers, ..
+            }
+            | ProxyScheme::Https {
+                connect_headers, ..
+            } => connect_headers.as_deref(),
+            #[cfg(feature = "socks")]
+            _ => None,
+        }
+    }
 }
 
 /// This implementation of `Hash` for `ProxyScheme` was improved based on the code
@@ -153,11 +304,12 @@ impl ProxyScheme {
 /// the discriminant of the enum to distinguish between different variants. Then, it hashes
 /// the fields of the specific variant.
 ///
-/// The `Http` and `Https` variants include optional `auth`, `host`, and `headers` fields.
+/// The `Http` and `Https` variants include optional `auth`, `host`, `headers`,
+/// `connect_headers` and `credentials` fields.
 /// The `Socks4` and `Socks5` variants include `addr`, `auth`, and `remote_dns` fields.
 ///
-/// The `headers` field, which is an `Option<HeaderMap>`, is hashed by iterating over its
-/// key-value pairs and hashing each pair.
+/// The `headers` and `connect_headers` fields, which are `Option<HeaderMap>`s, are hashed by
+/// iterating over their key-value pairs and hashing each pair.
 impl Hash for ProxyScheme {
     #[inline]
     fn hash<H: Hasher>(&self, state: &mut H) {
@@ -168,10 +320,17 @@ impl Hash for ProxyScheme {
                 auth,
                 host,
                 headers,
+                connect_headers,
+                credentials,
+                #[cfg(feature = "ntlm")]
+                ntlm,
             } => {
                 Hash::hash(auth, state);
                 Hash::hash(host, state);
-                if let Some(headers) = headers {
+                Hash::hash(credentials, state);
+                #[cfg(feature = "ntlm")]
+                Hash::hash(ntlm, state);
+                for headers in [headers, connect_headers].into_iter().flatten() {
                     state.write_usize(headers.len());
                     for (key, value) in headers.iter() {
                         Hash::hash(key, state);
@@ -183,10 +342,17 @@ impl Hash for ProxyScheme {
                 auth,
                 host,
                 headers,
+                connect_headers,
+                credentials,
+                #[cfg(feature = "ntlm")]
+                ntlm,
             } => {
                 Hash::hash(auth, state);
                 Hash::hash(host, state);
-                if let Some(headers) = headers {
+                Hash::hash(credentials, state);
+                #[cfg(feature = "ntlm")]
+                Hash::hash(ntlm, state);
+                for headers in [headers, connect_headers].into_iter().flatten() {
                     state.write_usize(headers.len());
                     for (key, value) in headers.iter() {
                         Hash::hash(key, state);
@@ -443,6 +609,68 @@ impl Proxy {
         self
     }
 
+    /// Set a callback returning the username and password to authenticate to
+    /// the proxy with, using Basic auth.
+    ///
+    /// Unlike [`Proxy::basic_auth`], the callback is called again for each new
+    /// connection to the proxy, and for each `http` request forwarded to it
+    /// without a tunnel, so credentials can rotate without rebuilding the client.
+    /// Rotating proxies often pick the exit node from a session in the username.
+    /// Connections already open keep the credentials they were opened with. The
+    /// callback takes precedence over `basic_auth`, and replaces
+    /// [`Proxy::digest_auth`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session = AtomicUsize::new(0);
+    /// let proxy = rquest::Proxy::all("http://localhost:1234")?.credentials_fn(move || {
+    ///     let session = session.fetch_add(1, Ordering::Relaxed);
+    ///     (format!("user-session-{}", session), "secret".to_owned())
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn credentials_fn<F>(mut self, credentials: F) -> Proxy
+    where
+        F: Fn() -> (String, String) + Send + Sync + 'static,
+    {
+        self.intercept.set_credentials(ProxyCredentials {
+            get: Arc::new(credentials),
+            digest: false,
+        });
+        self
+    }
+
+    /// Authenticate to the proxy with Digest auth.
+    ///
+    /// Unlike Basic auth, the credentials are only sent in answer to a `407 Proxy
+    /// Authentication Required` challenge for `Digest`: on the connection of the
+    /// `CONNECT` request that opens the tunnel of an `https` request, or by
+    /// sending an `http` request forwarded to the proxy again, if its body can be
+    /// sent again. This replaces credentials set with [`Proxy::credentials_fn`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let proxy = rquest::Proxy::all("http://localhost:1234")?
+    ///     .digest_auth("Aladdin", "open sesame");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn digest_auth(mut self, username: &str, password: &str) -> Proxy {
+        let credentials = (username.to_owned(), password.to_owned());
+        self.intercept.set_credentials(ProxyCredentials {
+            get: Arc::new(move || credentials.clone()),
+            digest: true,
+        });
+        self
+    }
+
     /// Set the `Proxy-Authorization` header to a specified value.
     ///
     /// # Example
@@ -462,6 +690,36 @@ impl Proxy {
         self
     }
 
+    /// Authenticate to the proxy with NTLM.
+    ///
+    /// The handshake runs when the tunnel for an `https` request is opened,
+    /// answering a `407 Proxy Authentication Required` challenge for `NTLM` or
+    /// `Negotiate` on the same connection. Requests forwarded to the proxy
+    /// without a tunnel are not authenticated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate rquest;
+    /// # fn run() -> Result<(), Box<std::error::Error>> {
+    /// let proxy = rquest::Proxy::https("http://localhost:1234")?
+    ///     .ntlm_auth("CORP", "Aladdin", "open sesame");
+    /// # Ok(())
+    /// # }
+    /// # fn main() {}
+    /// ```
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `ntlm` feature to be enabled.
+    #[cfg(feature = "ntlm")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ntlm")))]
+    pub fn ntlm_auth(mut self, domain: &str, username: &str, password: &str) -> Proxy {
+        self.intercept
+            .set_ntlm_auth(NtlmCredentials::new(domain, username, password));
+        self
+    }
+
     /// Set custom HTTP headers to be used with the proxy.
     ///
     /// This method allows you to specify custom HTTP headers that will be included
@@ -498,6 +756,32 @@ impl Proxy {
         self
     }
 
+    /// Set custom HTTP headers to be sent only with the `CONNECT` request that
+    /// opens a tunnel through the proxy for an `https` request.
+    ///
+    /// Unlike [`Proxy::custom_http_headers`], these aren't added to `http`
+    /// requests forwarded to the proxy. Rotating proxies often take options,
+    /// such as a session or a country, this way. They are sent after the headers
+    /// of `custom_http_headers`, replacing any of the same name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rquest::header::{HeaderMap, HeaderValue};
+    /// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("X-Proxy-Session", HeaderValue::from_static("abc123"));
+    ///
+    /// let proxy = rquest::Proxy::all("http://localhost:1234")?
+    ///     .custom_connect_headers(headers);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn custom_connect_headers(mut self, headers: HeaderMap) -> Proxy {
+        self.intercept.set_custom_connect_headers(headers);
+        self
+    }
+
     /// Adds a `No Proxy` exclusion list to this Proxy
     ///
     /// # Example
@@ -518,26 +802,19 @@ impl Proxy {
 
     pub(crate) fn maybe_has_http_auth(&self) -> bool {
         match &self.intercept {
-            Intercept::All(p) | Intercept::Http(p) => p.maybe_http_auth().is_some(),
+            Intercept::All(p) | Intercept::Http(p) => p.has_http_auth(),
             // Custom *may* match 'http', so assume so.
             Intercept::Custom(_) => true,
-            Intercept::System(system) => system
-                .get("http")
-                .and_then(|s| s.maybe_http_auth())
-                .is_some(),
+            Intercept::System(system) => system.get("http").is_some_and(|s| s.has_http_auth()),
             Intercept::Https(_) => false,
         }
     }
 
     pub(crate) fn http_basic_auth<D: Dst>(&self, uri: &D) -> Option<HeaderValue> {
         match &self.intercept {
-            Intercept::All(p) | Intercept::Http(p) => p.maybe_http_auth().cloned(),
-            Intercept::System(system) => system
-                .get("http")
-                .and_then(|s| s.maybe_http_auth().cloned()),
-            Intercept::Custom(custom) => {
-                custom.call(uri).and_then(|s| s.maybe_http_auth().cloned())
-            }
+            Intercept::All(p) | Intercept::Http(p) => p.http_auth(),
+            Intercept::System(system) => system.get("http").and_then(|s| s.http_auth()),
+            Intercept::Custom(custom) => custom.call(uri).and_then(|s| s.http_auth()),
             Intercept::Https(_) => None,
         }
     }
@@ -742,6 +1019,10 @@ impl ProxyScheme {
             auth: None,
             host: host.parse().map_err(crate::error::builder)?,
             headers: None,
+            connect_headers: None,
+            credentials: None,
+            #[cfg(feature = "ntlm")]
+            ntlm: None,
         })
     }
 
@@ -751,6 +1032,10 @@ impl ProxyScheme {
             auth: None,
             host: host.parse().map_err(crate::error::builder)?,
             headers: None,
+            connect_headers: None,
+            credentials: None,
+            #[cfg(feature = "ntlm")]
+            ntlm: None,
         })
     }
 
@@ -862,6 +1147,23 @@ impl ProxyScheme {
         }
     }
 
+    #[cfg(feature = "ntlm")]
+    fn set_ntlm_auth(&mut self, credentials: NtlmCredentials) {
+        match *self {
+            ProxyScheme::Http { ref mut ntlm, .. } | ProxyScheme::Https { ref mut ntlm, .. } => {
+                *ntlm = Some(credentials);
+            }
+            #[cfg(feature = "socks")]
+            ProxyScheme::Socks4 { .. } => {
+                panic!("Socks4 is not supported for this method")
+            }
+            #[cfg(feature = "socks")]
+            ProxyScheme::Socks5 { .. } => {
+                panic!("Socks5 is not supported for this method")
+            }
+        }
+    }
+
     fn set_custom_http_headers(&mut self, custom_headers: HeaderMap) {
         match *self {
             ProxyScheme::Http {
@@ -885,6 +1187,52 @@ impl ProxyScheme {
         }
     }
 
+    fn set_credentials(&mut self, update: ProxyCredentials) {
+        match *self {
+            ProxyScheme::Http {
+                ref mut credentials,
+                ..
+            }
+            | ProxyScheme::Https {
+                ref mut credentials,
+                ..
+            } => {
+                *credentials = Some(update);
+            }
+            #[cfg(feature = "socks")]
+            ProxyScheme::Socks4 { .. } => {
+                panic!("Socks4 is not supported for this method")
+            }
+            #[cfg(feature = "socks")]
+            ProxyScheme::Socks5 { .. } => {
+                panic!("Socks5 is not supported for this method")
+            }
+        }
+    }
+
+    fn set_custom_connect_headers(&mut self, custom_headers: HeaderMap) {
+        match *self {
+            ProxyScheme::Http {
+                ref mut connect_headers,
+                ..
+            }
+            | ProxyScheme::Https {
+                ref mut connect_headers,
+                ..
+            } => {
+                *connect_headers = Some(Arc::new(custom_headers));
+            }
+            #[cfg(feature = "socks")]
+            ProxyScheme::Socks4 { .. } => {
+                panic!("Socks4 is not supported for this method")
+            }
+            #[cfg(feature = "socks")]
+            ProxyScheme::Socks5 { .. } => {
+                panic!("Socks5 is not supported for this method")
+            }
+        }
+    }
+
     fn if_no_auth(mut self, update: &Option<HeaderValue>) -> Self {
         match self {
             ProxyScheme::Http { ref mut auth, .. } => {
@@ -1036,6 +1384,17 @@ impl Intercept {
         }
     }
 
+    #[cfg(feature = "ntlm")]
+    fn set_ntlm_auth(&mut self, credentials: NtlmCredentials) {
+        match self {
+            Intercept::All(s) | Intercept::Http(s) | Intercept::Https(s) => {
+                s.set_ntlm_auth(credentials)
+            }
+            Intercept::System(_) => unimplemented!(),
+            Intercept::Custom(_) => {}
+        }
+    }
+
     fn set_custom_http_headers(&mut self, headers: HeaderMap) {
         match self {
             Intercept::All(s) | Intercept::Http(s) | Intercept::Https(s) => {
@@ -1045,6 +1404,26 @@ impl Intercept {
             Intercept::Custom(_) => {}
         }
     }
+
+    fn set_credentials(&mut self, credentials: ProxyCredentials) {
+        match self {
+            Intercept::All(s) | Intercept::Http(s) | Intercept::Https(s) => {
+                s.set_credentials(credentials)
+            }
+            Intercept::System(_) => unimplemented!(),
+            Intercept::Custom(_) => {}
+        }
+    }
+
+    fn set_custom_connect_headers(&mut self, headers: HeaderMap) {
+        match self {
+            Intercept::All(s) | Intercept::Http(s) | Intercept::Https(s) => {
+                s.set_custom_connect_headers(headers)
+            }
+            Intercept::System(_) => unimplemented!(),
+            Intercept::Custom(_) => {}
+        }
+    }
 }
 
 type ProxyFunc = Arc<dyn Fn(&Url) -> Option<crate::Result<ProxyScheme>> + Send + Sync + 'static>;
@@ -1943,6 +2322,10 @@ mod tests {
                 auth: Some(HeaderValue::from_static("auth1")),
                 host: http::uri::Authority::from_static("authority"),
                 headers: None,
+                connect_headers: None,
+                credentials: None,
+                #[cfg(feature = "ntlm")]
+                ntlm: None,
             }),
             no_proxy: None,
         };
@@ -1957,6 +2340,10 @@ mod tests {
                 auth: None,
                 host: http::uri::Authority::from_static("authority"),
                 headers: None,
+                connect_headers: None,
+                credentials: None,
+                #[cfg(feature = "ntlm")]
+                ntlm: None,
             }),
             no_proxy: None,
         };
@@ -1971,6 +2358,10 @@ mod tests {
                 auth: Some(HeaderValue::from_static("auth2")),
                 host: http::uri::Authority::from_static("authority"),
                 headers: None,
+                connect_headers: None,
+                credentials: None,
+                #[cfg(feature = "ntlm")]
+                ntlm: None,
             }),
             no_proxy: None,
         };
@@ -1985,6 +2376,10 @@ mod tests {
                 auth: Some(HeaderValue::from_static("auth3")),
                 host: http::uri::Authority::from_static("authority"),
                 headers: None,
+                connect_headers: None,
+                credentials: None,
+                #[cfg(feature = "ntlm")]
+                ntlm: None,
             }),
             no_proxy: None,
         };
@@ -1999,6 +2394,10 @@ mod tests {
                 auth: Some(HeaderValue::from_static("auth4")),
                 host: http::uri::Authority::from_static("authority"),
                 headers: None,
+                connect_headers: None,
+                credentials: None,
+                #[cfg(feature = "ntlm")]
+                ntlm: None,
             }),
             no_proxy: None,
         };
@@ -2013,6 +2412,10 @@ mod tests {
                 auth: None,
                 host: http::uri::Authority::from_static("authority"),
                 headers: None,
+                connect_headers: None,
+                credentials: None,
+                #[cfg(feature = "ntlm")]
+                ntlm: None,
             }),
             no_proxy: None,
         };
@@ -2031,6 +2434,10 @@ mod tests {
                         auth: Some(HeaderValue::from_static("auth5")),
                         host: http::uri::Authority::from_static("authority"),
                         headers: None,
+                        connect_headers: None,
+                        credentials: None,
+                        #[cfg(feature = "ntlm")]
+                        ntlm: None,
                     },
                 );
                 m
@@ -2052,6 +2459,10 @@ mod tests {
                         auth: Some(HeaderValue::from_static("auth6")),
                         host: http::uri::Authority::from_static("authority"),
                         headers: None,
+                        connect_headers: None,
+                        credentials: None,
+                        #[cfg(feature = "ntlm")]
+                        ntlm: None,
                     },
                 );
                 m
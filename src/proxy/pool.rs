@@ -0,0 +1,338 @@
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use antidote::Mutex;
+use lru::LruCache;
+
+use super::{Dst, Proxy, ProxyScheme};
+
+const STICKY_HOSTS: usize = 1024;
+
+/// How a [`ProxyPool`] picks a proxy for each request.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProxyStrategy {
+    /// Use each proxy in turn.
+    #[default]
+    RoundRobin,
+    /// Pick a proxy at random.
+    Random,
+    /// Pick the proxy with the fewest connection errors so far.
+    LeastErrors,
+    /// Keep sending requests for the same host through the same proxy.
+    StickyPerHost,
+}
+
+/// A set of proxies that a `Client` rotates through.
+///
+/// Each request that isn't given its own proxy is sent through one proxy of the
+/// pool, chosen by the pool's [`ProxyStrategy`]. Connections are pooled per proxy,
+/// so requests through the same proxy reuse connections.
+///
+/// A proxy that fails to connect is taken out of rotation for a cooldown period.
+/// If every matching proxy is cooling down, they are all used again rather than
+/// failing the request.
+///
+/// Clones of a `ProxyPool` share their rotation and health state.
+///
+/// # Example
+///
+/// ```
+/// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::time::Duration;
+/// use rquest::{Proxy, ProxyPool, ProxyStrategy};
+///
+/// let pool = ProxyPool::new([
+///     Proxy::all("http://proxy-a.local:8080")?,
+///     Proxy::all("http://proxy-b.local:8080")?,
+/// ])
+/// .strategy(ProxyStrategy::StickyPerHost)
+/// .cooldown(Duration::from_secs(60));
+///
+/// let client = rquest::Client::builder().proxy_pool(pool).build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ProxyPool {
+    proxies: Arc<[Proxy]>,
+    strategy: ProxyStrategy,
+    cooldown: Duration,
+    state: Arc<State>,
+}
+
+struct State {
+    next: AtomicUsize,
+    health: Mutex<Vec<Health>>,
+    sticky: Mutex<LruCache<String, usize>>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Health {
+    errors: usize,
+    down_until: Option<Instant>,
+}
+
+impl ProxyPool {
+    /// Create a pool of the given proxies.
+    ///
+    /// The pool uses [`ProxyStrategy::RoundRobin`] and a cooldown of 30 seconds by default.
+    pub fn new<I>(proxies: I) -> ProxyPool
+    where
+        I: IntoIterator<Item = Proxy>,
+    {
+        let proxies: Arc<[Proxy]> = proxies.into_iter().collect();
+        let health = vec![Health::default(); proxies.len()];
+
+        ProxyPool {
+            proxies,
+            strategy: ProxyStrategy::default(),
+            cooldown: Duration::from_secs(30),
+            state: Arc::new(State {
+                next: AtomicUsize::new(0),
+                health: Mutex::new(health),
+                sticky: Mutex::new(LruCache::new(
+                    NonZeroUsize::new(STICKY_HOSTS).expect("sticky host capacity is non-zero"),
+                )),
+            }),
+        }
+    }
+
+    /// Set how a proxy is picked for each request.
+    pub fn strategy(mut self, strategy: ProxyStrategy) -> ProxyPool {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Set how long a proxy is taken out of rotation after a connection error.
+    ///
+    /// Default is 30 seconds.
+    pub fn cooldown(mut self, cooldown: Duration) -> ProxyPool {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Returns the number of proxies in the pool.
+    pub fn len(&self) -> usize {
+        self.proxies.len()
+    }
+
+    /// Returns true if the pool has no proxies.
+    pub fn is_empty(&self) -> bool {
+        self.proxies.is_empty()
+    }
+
+    /// Returns the number of proxies currently taken out of rotation.
+    pub fn unhealthy(&self) -> usize {
+        let now = Instant::now();
+        self.state
+            .health
+            .lock()
+            .iter()
+            .filter(|health| health.is_down(now))
+            .count()
+    }
+
+    /// Pick a proxy for `dst`, returning its index in the pool and its scheme.
+    pub(crate) fn select<D: Dst>(&self, dst: &D) -> Option<(usize, ProxyScheme)> {
+        let candidates = self.candidates(dst);
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let pick = match self.strategy {
+            ProxyStrategy::RoundRobin => self.round_robin(&candidates),
+            ProxyStrategy::Random => {
+                candidates[crate::util::fast_random() as usize % candidates.len()].0
+            }
+            ProxyStrategy::LeastErrors => {
+                let health = self.state.health.lock();
+                let start = self.state.next.fetch_add(1, Ordering::Relaxed);
+                (0..candidates.len())
+                    .map(|i| candidates[(start + i) % candidates.len()].0)
+                    .min_by_key(|&index| health[index].errors)
+                    .expect("candidates are not empty")
+            }
+            ProxyStrategy::StickyPerHost => {
+                let mut sticky = self.state.sticky.lock();
+                match sticky.get(dst.host()) {
+                    Some(&index) if candidates.iter().any(|(i, _)| *i == index) => index,
+                    _ => {
+                        let index = self.round_robin(&candidates);
+                        sticky.put(dst.host().to_owned(), index);
+                        index
+                    }
+                }
+            }
+        };
+
+        candidates.into_iter().find(|(index, _)| *index == pick)
+    }
+
+    /// Record a connection error through the proxy at `index`.
+    pub(crate) fn report_failure(&self, index: usize) {
+        if let Some(health) = self.state.health.lock().get_mut(index) {
+            health.errors += 1;
+            health.down_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+
+    /// Record a response received through the proxy at `index`.
+    pub(crate) fn report_success(&self, index: usize) {
+        if let Some(health) = self.state.health.lock().get_mut(index) {
+            health.down_until = None;
+        }
+    }
+
+    /// The proxies that intercept `dst`, leaving out those cooling down unless all are.
+    fn candidates<D: Dst>(&self, dst: &D) -> Vec<(usize, ProxyScheme)> {
+        let matching = self
+            .proxies
+            .iter()
+            .enumerate()
+            .filter_map(|(index, proxy)| proxy.intercept(dst).map(|scheme| (index, scheme)))
+            .collect::<Vec<_>>();
+
+        let now = Instant::now();
+        let health = self.state.health.lock();
+        let healthy = matching
+            .iter()
+            .filter(|(index, _)| !health[*index].is_down(now))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if healthy.is_empty() {
+            matching
+        } else {
+            healthy
+        }
+    }
+
+    fn round_robin(&self, candidates: &[(usize, ProxyScheme)]) -> usize {
+        let next = self.state.next.fetch_add(1, Ordering::Relaxed);
+        candidates[next % candidates.len()].0
+    }
+}
+
+impl Health {
+    fn is_down(&self, now: Instant) -> bool {
+        self.down_until.is_some_and(|until| until > now)
+    }
+}
+
+impl fmt::Debug for ProxyPool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ProxyPool")
+            .field("proxies", &self.proxies)
+            .field("strategy", &self.strategy)
+            .field("cooldown", &self.cooldown)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Url;
+
+    fn pool(strategy: ProxyStrategy) -> ProxyPool {
+        ProxyPool::new([
+            Proxy::all("http://a.local:8080").unwrap(),
+            Proxy::all("http://b.local:8080").unwrap(),
+            Proxy::all("http://c.local:8080").unwrap(),
+        ])
+        .strategy(strategy)
+    }
+
+    fn url(s: &str) -> Url {
+        s.parse().unwrap()
+    }
+
+    fn pick(pool: &ProxyPool, dst: &str) -> usize {
+        pool.select(&url(dst)).unwrap().0
+    }
+
+    #[test]
+    fn round_robin_rotates() {
+        let pool = pool(ProxyStrategy::RoundRobin);
+        let picks = (0..6)
+            .map(|_| pick(&pool, "http://example.local"))
+            .collect::<Vec<_>>();
+        assert_eq!(picks, [0, 1, 2, 0, 1, 2]);
+    }
+
+    #[test]
+    fn failed_proxy_cools_down() {
+        let pool = pool(ProxyStrategy::RoundRobin);
+        pool.report_failure(1);
+        assert_eq!(pool.unhealthy(), 1);
+
+        for _ in 0..6 {
+            assert_ne!(pick(&pool, "http://example.local"), 1);
+        }
+
+        pool.report_success(1);
+        assert_eq!(pool.unhealthy(), 0);
+    }
+
+    #[test]
+    fn all_down_uses_all() {
+        let pool = pool(ProxyStrategy::RoundRobin);
+        for index in 0..3 {
+            pool.report_failure(index);
+        }
+        assert!(pool.select(&url("http://example.local")).is_some());
+    }
+
+    #[test]
+    fn expired_cooldown_returns_to_rotation() {
+        let pool = pool(ProxyStrategy::LeastErrors).cooldown(Duration::ZERO);
+        pool.report_failure(0);
+        assert_eq!(pool.unhealthy(), 0);
+        assert_ne!(pick(&pool, "http://example.local"), 0);
+    }
+
+    #[test]
+    fn least_errors_prefers_reliable_proxies() {
+        let pool = pool(ProxyStrategy::LeastErrors).cooldown(Duration::ZERO);
+        pool.report_failure(0);
+        pool.report_failure(0);
+        pool.report_failure(2);
+
+        for _ in 0..6 {
+            assert_eq!(pick(&pool, "http://example.local"), 1);
+        }
+    }
+
+    #[test]
+    fn sticky_per_host() {
+        let pool = pool(ProxyStrategy::StickyPerHost);
+        let first = pick(&pool, "http://one.local/a");
+        let second = pick(&pool, "http://two.local/a");
+        assert_ne!(first, second);
+
+        for _ in 0..4 {
+            assert_eq!(pick(&pool, "http://one.local/b"), first);
+            assert_eq!(pick(&pool, "http://two.local/b"), second);
+        }
+
+        // a host moves to another proxy once its proxy fails
+        pool.report_failure(first);
+        assert_ne!(pick(&pool, "http://one.local/c"), first);
+    }
+
+    #[test]
+    fn random_picks_matching_proxies() {
+        let pool = ProxyPool::new([
+            Proxy::http("http://a.local:8080").unwrap(),
+            Proxy::https("http://b.local:8080").unwrap(),
+        ])
+        .strategy(ProxyStrategy::Random);
+
+        for _ in 0..8 {
+            assert_eq!(pick(&pool, "https://example.local"), 1);
+        }
+    }
+}
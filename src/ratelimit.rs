@@ -0,0 +1,294 @@
+//! Rate Limiting
+//!
+//! A `RateLimiter` spaces out the requests a `Client` sends to each host with
+//! a token bucket: a host may receive a burst of requests at once, and then
+//! one request every `interval / requests`. Requests over the limit wait
+//! before they are sent, in the order they were made.
+//!
+//! When enabled with [`RateLimiter::respect_retry_after`], a
+//! `429 Too Many Requests` response empties the bucket of its host, and a
+//! `Retry-After` header on it holds back further requests until it passes.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use antidote::Mutex;
+use url::Url;
+
+use crate::StatusCode;
+use crate::header::HeaderMap;
+
+/// A number of requests allowed per interval.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use rquest::ratelimit::Rate;
+///
+/// // 2 requests per second, with bursts of up to 10 requests
+/// let rate = Rate::per_second(2).burst(10);
+///
+/// // 100 requests per 10 minutes
+/// let rate = Rate::new(100, Duration::from_secs(600));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rate {
+    requests: u32,
+    interval: Duration,
+    burst: u32,
+}
+
+impl Rate {
+    /// Allow `requests` requests per `interval`.
+    ///
+    /// The burst defaults to `requests`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests` is zero or `interval` is zero.
+    pub fn new(requests: u32, interval: Duration) -> Rate {
+        assert!(requests > 0, "rate must allow at least 1 request");
+        assert!(!interval.is_zero(), "rate interval must not be zero");
+        Rate {
+            requests,
+            interval,
+            burst: requests,
+        }
+    }
+
+    /// Allow `requests` requests per second.
+    pub fn per_second(requests: u32) -> Rate {
+        Rate::new(requests, Duration::from_secs(1))
+    }
+
+    /// Allow `requests` requests per minute.
+    pub fn per_minute(requests: u32) -> Rate {
+        Rate::new(requests, Duration::from_secs(60))
+    }
+
+    /// Set the number of requests that may be sent at once after a quiet period.
+    ///
+    /// A burst of `0` is treated as `1`.
+    pub fn burst(mut self, burst: u32) -> Rate {
+        self.burst = burst.max(1);
+        self
+    }
+
+    fn per_sec(&self) -> f64 {
+        f64::from(self.requests) / self.interval.as_secs_f64()
+    }
+}
+
+/// Limits the rate of requests a `Client` sends to each host.
+///
+/// Clones of a `RateLimiter` share their state, so one limiter can be set on
+/// several clients to limit them together.
+///
+/// # Example
+///
+/// ```rust
+/// use rquest::ratelimit::{Rate, RateLimiter};
+///
+/// let limiter = RateLimiter::new()
+///     .host("api.example.com", Rate::per_second(5))
+///     .host("*.example.org", Rate::per_minute(30).burst(5))
+///     .default_rate(Rate::per_second(10))
+///     .respect_retry_after(true);
+///
+/// let client = rquest::Client::builder()
+///     .rate_limiter(limiter)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Default)]
+pub struct RateLimiter {
+    hosts: HashMap<String, Rate>,
+    default: Option<Rate>,
+    respect_retry_after: bool,
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+}
+
+/// The tokens left for a host.
+struct Bucket {
+    rate: Rate,
+    // may go negative, for requests that reserved a token they wait for
+    tokens: f64,
+    updated_at: Instant,
+    blocked_until: Option<Instant>,
+}
+
+impl RateLimiter {
+    /// Create a `RateLimiter` without any limits.
+    pub fn new() -> RateLimiter {
+        RateLimiter::default()
+    }
+
+    /// Limit the requests to `host` to `rate`.
+    ///
+    /// A host of the form `*.example.com` applies to every subdomain of
+    /// `example.com`, unless a subdomain has a rate of its own. Each host
+    /// matching the pattern gets a bucket of its own.
+    pub fn host(mut self, host: &str, rate: Rate) -> RateLimiter {
+        self.hosts.insert(host.to_ascii_lowercase(), rate);
+        self
+    }
+
+    /// Limit the requests to hosts without a rate of their own to `rate`.
+    ///
+    /// By default, these hosts aren't limited.
+    pub fn default_rate(mut self, rate: Rate) -> RateLimiter {
+        self.default = Some(rate);
+        self
+    }
+
+    /// Set whether a `429 Too Many Requests` response slows down the requests to
+    /// its host.
+    ///
+    /// Default is `false`.
+    pub fn respect_retry_after(mut self, enable: bool) -> RateLimiter {
+        self.respect_retry_after = enable;
+        self
+    }
+
+    fn rate(&self, host: &str) -> Option<Rate> {
+        if let Some(rate) = self.hosts.get(host) {
+            return Some(*rate);
+        }
+
+        let mut parent = host;
+        while let Some((_, rest)) = parent.split_once('.') {
+            if let Some(rate) = self.hosts.get(&format!("*.{rest}")) {
+                return Some(*rate);
+            }
+            parent = rest;
+        }
+
+        self.default
+    }
+
+    /// Take a token to send a request to `url`, returning how long the request
+    /// must wait first.
+    pub(crate) fn reserve(&self, url: &Url) -> Option<Duration> {
+        self.reserve_at(url.host_str()?, Instant::now())
+    }
+
+    fn reserve_at(&self, host: &str, now: Instant) -> Option<Duration> {
+        let host = host.to_ascii_lowercase();
+        let rate = self.rate(&host)?;
+
+        let mut buckets = self.buckets.lock();
+        let bucket = buckets.entry(host).or_insert_with(|| Bucket {
+            rate,
+            tokens: f64::from(rate.burst),
+            updated_at: now,
+            blocked_until: None,
+        });
+        bucket.refill(now);
+        bucket.tokens -= 1.0;
+
+        let mut wait = if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / bucket.rate.per_sec())
+        };
+        if let Some(blocked_until) = bucket.blocked_until {
+            wait = wait.max(blocked_until.saturating_duration_since(now));
+        }
+
+        (!wait.is_zero()).then_some(wait)
+    }
+
+    /// Slow down the requests to the host of `url` after it answered
+    /// `429 Too Many Requests`.
+    pub(crate) fn feedback(&self, url: &Url, status: StatusCode, headers: &HeaderMap) {
+        if !self.respect_retry_after || status != StatusCode::TOO_MANY_REQUESTS {
+            return;
+        }
+        if let Some(host) = url.host_str() {
+            self.throttle_at(host, crate::retry::retry_after(headers), Instant::now());
+        }
+    }
+
+    fn throttle_at(&self, host: &str, retry_after: Option<Duration>, now: Instant) {
+        let host = host.to_ascii_lowercase();
+        let mut buckets = self.buckets.lock();
+        if let Some(bucket) = buckets.get_mut(&host) {
+            bucket.refill(now);
+            bucket.tokens = bucket.tokens.min(0.0);
+            if let Some(retry_after) = retry_after {
+                let until = now + retry_after;
+                bucket.blocked_until = Some(bucket.blocked_until.map_or(until, |b| b.max(until)));
+            }
+        }
+    }
+}
+
+impl Bucket {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate.per_sec())
+            .min(f64::from(self.rate.burst));
+        self.updated_at = now;
+        if self.blocked_until.is_some_and(|until| until <= now) {
+            self.blocked_until = None;
+        }
+    }
+}
+
+impl fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("hosts", &self.hosts)
+            .field("default", &self.default)
+            .field("respect_retry_after", &self.respect_retry_after)
+            .finish()
+    }
+}
+
+#[test]
+fn test_rate_limiter_burst() {
+    let limiter = RateLimiter::new().host("example.com", Rate::per_second(2).burst(2));
+    let now = Instant::now();
+
+    assert_eq!(limiter.reserve_at("example.com", now), None);
+    assert_eq!(limiter.reserve_at("example.com", now), None);
+    assert_eq!(
+        limiter.reserve_at("example.com", now),
+        Some(Duration::from_millis(500))
+    );
+    assert_eq!(
+        limiter.reserve_at("example.com", now),
+        Some(Duration::from_secs(1))
+    );
+    assert_eq!(limiter.reserve_at("other.com", now), None);
+}
+
+#[test]
+fn test_rate_limiter_host_patterns() {
+    let limiter = RateLimiter::new()
+        .host("*.example.com", Rate::per_minute(1))
+        .host("api.example.com", Rate::per_second(10))
+        .default_rate(Rate::per_second(1));
+
+    assert_eq!(limiter.rate("a.b.example.com"), Some(Rate::per_minute(1)));
+    assert_eq!(limiter.rate("api.example.com"), Some(Rate::per_second(10)));
+    assert_eq!(limiter.rate("example.com"), Some(Rate::per_second(1)));
+}
+
+#[test]
+fn test_rate_limiter_retry_after() {
+    let limiter = RateLimiter::new()
+        .host("example.com", Rate::per_second(10))
+        .respect_retry_after(true);
+    let now = Instant::now();
+
+    assert_eq!(limiter.reserve_at("example.com", now), None);
+    limiter.throttle_at("example.com", Some(Duration::from_secs(3)), now);
+    assert_eq!(
+        limiter.reserve_at("example.com", now),
+        Some(Duration::from_secs(3))
+    );
+}
@@ -4,12 +4,15 @@
 //! maximum redirect chain of 10 hops. To customize this behavior, a
 //! `redirect::Policy` can be used with a `ClientBuilder`.
 
+use std::borrow::Cow;
 use std::fmt;
 use std::{error::Error as StdError, sync::Arc};
 
 use crate::core::StatusCode;
-use crate::header::{AUTHORIZATION, COOKIE, HeaderMap, PROXY_AUTHORIZATION, WWW_AUTHENTICATE};
-use http::Method;
+use crate::header::{
+    AUTHORIZATION, COOKIE, HeaderMap, HeaderName, PROXY_AUTHORIZATION, WWW_AUTHENTICATE,
+};
+use http::{Extensions, Method};
 
 use crate::Url;
 
@@ -25,17 +28,33 @@ use crate::Url;
 #[derive(Clone)]
 pub struct Policy {
     inner: PolicyKind,
+    body_preview: usize,
+    follow_refresh: bool,
+    sensitive_headers: Cow<'static, [HeaderName]>,
+    trusted_domains: Vec<Cow<'static, str>>,
 }
 
+/// Headers removed by default when a redirect crosses to another host.
+static DEFAULT_SENSITIVE_HEADERS: [HeaderName; 5] = [
+    AUTHORIZATION,
+    COOKIE,
+    HeaderName::from_static("cookie2"),
+    PROXY_AUTHORIZATION,
+    WWW_AUTHENTICATE,
+];
+
 /// A type that holds information on the next request and previous requests
 /// in redirect chain.
 #[derive(Debug)]
 pub struct Attempt<'a> {
     status: StatusCode,
+    headers: &'a HeaderMap,
+    body: Option<&'a [u8]>,
     next_method: &'a Method,
     next: &'a Url,
     previous_method: &'a Method,
     previous: &'a [Url],
+    extensions: &'a Extensions,
 }
 
 /// An action to perform when a redirect status code is found.
@@ -44,6 +63,18 @@ pub struct Action {
     inner: ActionKind,
 }
 
+/// A record of a single redirect hop followed while executing a request.
+///
+/// The history of a request can be obtained from
+/// [`Response::redirect_history`](crate::Response::redirect_history).
+#[derive(Debug, Clone)]
+pub struct RedirectRecord {
+    status: StatusCode,
+    url: Url,
+    next: Url,
+    headers: HeaderMap,
+}
+
 impl Policy {
     /// Create a `Policy` with a maximum number of redirects.
     ///
@@ -51,6 +82,10 @@ impl Policy {
     pub fn limited(max: usize) -> Self {
         Self {
             inner: PolicyKind::Limit(max),
+            body_preview: 0,
+            follow_refresh: false,
+            sensitive_headers: Cow::Borrowed(&DEFAULT_SENSITIVE_HEADERS),
+            trusted_domains: Vec::new(),
         }
     }
 
@@ -58,6 +93,10 @@ impl Policy {
     pub fn none() -> Self {
         Self {
             inner: PolicyKind::None,
+            body_preview: 0,
+            follow_refresh: false,
+            sensitive_headers: Cow::Borrowed(&DEFAULT_SENSITIVE_HEADERS),
+            trusted_domains: Vec::new(),
         }
     }
 
@@ -105,9 +144,86 @@ impl Policy {
     {
         Self {
             inner: PolicyKind::Custom(Arc::new(policy)),
+            body_preview: 0,
+            follow_refresh: false,
+            sensitive_headers: Cow::Borrowed(&DEFAULT_SENSITIVE_HEADERS),
+            trusted_domains: Vec::new(),
         }
     }
 
+    /// Buffer up to `max` bytes of the redirect response body before applying this policy.
+    ///
+    /// The buffered prefix is available through [`Attempt::body`], which lets a custom
+    /// policy inspect HTML content (e.g. a meta-refresh tag) before deciding to follow.
+    /// If the redirect is not followed, the complete body is still available on the
+    /// returned `Response`.
+    ///
+    /// Default is `0`, which disables buffering.
+    pub fn body_preview(mut self, max: usize) -> Self {
+        self.body_preview = max;
+        self
+    }
+
+    /// Treat `Refresh` headers and `<meta http-equiv="refresh">` tags on `200 OK`
+    /// responses as redirects.
+    ///
+    /// Refresh redirects are followed immediately, regardless of the delay, and are
+    /// subject to this policy like any `303 See Other`. HTML bodies are scanned for a
+    /// meta tag within the first 8 KiB after decompression. A refresh that points back
+    /// to the same URL is treated as a page reload and not followed.
+    ///
+    /// Default is `false`.
+    pub fn follow_refresh(mut self, enable: bool) -> Self {
+        self.follow_refresh = enable;
+        self
+    }
+
+    /// Set the headers that are removed when a redirect crosses to another host.
+    ///
+    /// This replaces the default list, which is `Authorization`, `Cookie`, `Cookie2`,
+    /// `Proxy-Authorization` and `WWW-Authenticate`. Include the defaults you want to keep
+    /// when adding custom headers such as `X-Api-Key`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rquest::header::{AUTHORIZATION, COOKIE, HeaderName};
+    /// use rquest::redirect;
+    ///
+    /// let policy = redirect::Policy::default().sensitive_headers([
+    ///     AUTHORIZATION,
+    ///     COOKIE,
+    ///     HeaderName::from_static("x-api-key"),
+    /// ]);
+    /// ```
+    pub fn sensitive_headers<I>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = HeaderName>,
+    {
+        self.sensitive_headers = Cow::Owned(headers.into_iter().collect());
+        self
+    }
+
+    /// Add a domain whose hosts are trusted to receive sensitive headers.
+    ///
+    /// A redirect between two hosts that both equal, or are subdomains of, the same
+    /// trusted domain keeps the headers set by [`Policy::sensitive_headers`], unless it
+    /// downgrades from `https` to `http`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// // keep `Authorization` when redirecting from api.example.com to auth.example.com
+    /// let policy = rquest::redirect::Policy::default().trusted_domain("example.com");
+    /// ```
+    pub fn trusted_domain<D>(mut self, domain: D) -> Self
+    where
+        D: Into<Cow<'static, str>>,
+    {
+        self.trusted_domains.push(domain.into());
+        self
+    }
+
     /// Apply this policy to a given [`Attempt`] to produce a [`Action`].
     ///
     /// # Note
@@ -143,37 +259,71 @@ impl Policy {
         }
     }
 
+    #[inline]
+    pub(crate) fn body_preview_limit(&self) -> usize {
+        self.body_preview
+    }
+
+    #[inline]
+    pub(crate) fn follows_refresh(&self) -> bool {
+        self.follow_refresh
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn check(
         &self,
         status: StatusCode,
+        headers: &HeaderMap,
+        body: Option<&[u8]>,
         next_method: &Method,
         next: &Url,
         previous_method: &Method,
         previous: &[Url],
+        extensions: &Extensions,
     ) -> ActionKind {
         self.redirect(Attempt {
             status,
+            headers,
+            body,
             next_method,
             next,
             previous_method,
             previous,
+            extensions,
         })
         .inner
     }
 
-    pub(crate) fn remove_sensitive_headers(headers: &mut HeaderMap, next: &Url, previous: &[Url]) {
+    pub(crate) fn remove_sensitive_headers(
+        &self,
+        headers: &mut HeaderMap,
+        next: &Url,
+        previous: &[Url],
+    ) {
         if let Some(previous) = previous.last() {
             let cross_host = next.host_str() != previous.host_str()
                 || next.port_or_known_default() != previous.port_or_known_default();
-            if cross_host {
-                headers.remove(AUTHORIZATION);
-                headers.remove(COOKIE);
-                headers.remove("cookie2");
-                headers.remove(PROXY_AUTHORIZATION);
-                headers.remove(WWW_AUTHENTICATE);
+            if cross_host && !self.is_trusted(next, previous) {
+                for header in self.sensitive_headers.iter() {
+                    headers.remove(header);
+                }
             }
         }
     }
+
+    fn is_trusted(&self, next: &Url, previous: &Url) -> bool {
+        if next.scheme() != previous.scheme() && next.scheme() != "https" {
+            return false;
+        }
+
+        match (next.host_str(), previous.host_str()) {
+            (Some(next), Some(previous)) => self
+                .trusted_domains
+                .iter()
+                .any(|domain| domain_matches(next, domain) && domain_matches(previous, domain)),
+            _ => false,
+        }
+    }
 }
 
 impl Default for Policy {
@@ -189,6 +339,19 @@ impl Attempt<'_> {
         self.status
     }
 
+    /// Get the headers of the redirect response.
+    pub fn headers(&self) -> &HeaderMap {
+        self.headers
+    }
+
+    /// Get the buffered prefix of the redirect response body.
+    ///
+    /// This is `None` unless the policy was configured with [`Policy::body_preview`].
+    /// The slice holds at most that many bytes, and may be shorter if the body ended early.
+    pub fn body(&self) -> Option<&[u8]> {
+        self.body
+    }
+
     /// Get the method for the next request, after applying redirection logic.
     pub fn next_method(&self) -> &Method {
         self.next_method
@@ -208,6 +371,15 @@ impl Attempt<'_> {
     pub fn previous(&self) -> &[Url] {
         self.previous
     }
+
+    /// Get the extensions of the request being redirected.
+    ///
+    /// Values set with [`RequestBuilder::extension`](crate::RequestBuilder::extension)
+    /// can be read here, to decide per request.
+    pub fn extensions(&self) -> &Extensions {
+        self.extensions
+    }
+
     /// Returns an action meaning rquest should follow the next URL.
     pub fn follow(self) -> Action {
         Action {
@@ -234,6 +406,55 @@ impl Attempt<'_> {
     }
 }
 
+impl RedirectRecord {
+    pub(crate) fn new(status: StatusCode, url: Url, next: Url, headers: HeaderMap) -> Self {
+        RedirectRecord {
+            status,
+            url,
+            next,
+            headers,
+        }
+    }
+
+    /// Get the status code of the redirect response.
+    #[inline]
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Get the URL that returned the redirect response.
+    #[inline]
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Get the URL the client was redirected to.
+    #[inline]
+    pub fn next(&self) -> &Url {
+        &self.next
+    }
+
+    /// Get the headers of the redirect response.
+    ///
+    /// This includes any `Set-Cookie` headers sent by this hop.
+    #[inline]
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+}
+
+/// Returns true if `host` equals `domain` or is one of its subdomains.
+fn domain_matches(host: &str, domain: &str) -> bool {
+    let domain = domain.trim_start_matches('.');
+    if host.eq_ignore_ascii_case(domain) {
+        return true;
+    }
+
+    host.len() > domain.len()
+        && host.as_bytes()[host.len() - domain.len() - 1] == b'.'
+        && host[host.len() - domain.len()..].eq_ignore_ascii_case(domain)
+}
+
 #[derive(Clone)]
 enum PolicyKind {
     Custom(Arc<dyn Fn(Attempt) -> Action + Send + Sync + 'static>),
@@ -243,7 +464,13 @@ enum PolicyKind {
 
 impl fmt::Debug for Policy {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.debug_tuple("Policy").field(&self.inner).finish()
+        f.debug_struct("Policy")
+            .field("inner", &self.inner)
+            .field("body_preview", &self.body_preview)
+            .field("follow_refresh", &self.follow_refresh)
+            .field("sensitive_headers", &self.sensitive_headers)
+            .field("trusted_domains", &self.trusted_domains)
+            .finish()
     }
 }
 
@@ -259,6 +486,103 @@ impl fmt::Debug for PolicyKind {
 
 // pub(crate)
 
+/// The number of body bytes scanned for a `<meta http-equiv="refresh">` tag.
+pub(crate) const REFRESH_SCAN_LIMIT: usize = 8 * 1024;
+
+/// Extract the target URL of a refresh directive, e.g. `0; url=https://example.com/`.
+///
+/// Returns `None` for a plain reload without a URL.
+pub(crate) fn refresh_target(value: &str) -> Option<&str> {
+    let (_delay, rest) = value.split_once([';', ','])?;
+    let rest = rest.trim_start();
+
+    let rest = match rest.get(..3) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("url") => {
+            let after = rest[3..].trim_start();
+            after.strip_prefix('=').map_or(rest, str::trim_start)
+        }
+        _ => rest,
+    };
+
+    let target = rest.trim_end();
+    let target = match target.as_bytes().first() {
+        Some(&quote @ (b'"' | b'\'')) => {
+            let inner = &target[1..];
+            inner.find(quote as char).map_or(inner, |end| &inner[..end])
+        }
+        _ => target,
+    };
+
+    Some(target.trim()).filter(|target| !target.is_empty())
+}
+
+/// Find the target URL of the first `<meta http-equiv="refresh">` tag in an HTML prefix.
+pub(crate) fn meta_refresh(html: &[u8]) -> Option<String> {
+    let html = String::from_utf8_lossy(html);
+    let lower = html.to_ascii_lowercase();
+
+    let mut pos = 0;
+    while let Some(start) = lower[pos..].find("<meta") {
+        let start = pos + start;
+        let end = lower[start..]
+            .find('>')
+            .map_or(lower.len(), |end| start + end);
+        let tag = &lower[start..end];
+
+        if tag_attr(tag, "http-equiv").is_some_and(|(value, _)| value == "refresh") {
+            let (_, range) = tag_attr(tag, "content")?;
+            let content = &html[start + range.start..start + range.end];
+            return refresh_target(content).map(|target| target.replace("&amp;", "&"));
+        }
+
+        pos = end;
+    }
+
+    None
+}
+
+/// Look up an attribute in a lowercased tag, returning its value and byte range within the tag.
+fn tag_attr<'a>(tag: &'a str, name: &str) -> Option<(&'a str, std::ops::Range<usize>)> {
+    let bytes = tag.as_bytes();
+    let mut pos = 0;
+
+    while let Some(found) = tag[pos..].find(name) {
+        let start = pos + found;
+        pos = start + name.len();
+
+        if !start
+            .checked_sub(1)
+            .is_some_and(|prev| bytes[prev].is_ascii_whitespace())
+        {
+            continue;
+        }
+
+        let rest = tag[pos..].trim_start();
+        let Some(rest) = rest.strip_prefix('=') else {
+            continue;
+        };
+        let rest = rest.trim_start();
+        let offset = tag.len() - rest.len();
+
+        let range = match rest.as_bytes().first() {
+            Some(&quote @ (b'"' | b'\'')) => {
+                let len = rest[1..].find(quote as char).unwrap_or(rest.len() - 1);
+                offset + 1..offset + 1 + len
+            }
+            _ => {
+                let len = rest
+                    .find(|c: char| c.is_ascii_whitespace() || c == '/')
+                    .unwrap_or(rest.len());
+                offset..offset + len
+            }
+        };
+
+        return Some((&tag[range.clone()], range));
+    }
+
+    None
+}
+
 #[derive(Debug)]
 pub(crate) enum ActionKind {
     Follow,
@@ -287,10 +611,13 @@ fn test_redirect_policy_limit() {
 
     match policy.check(
         StatusCode::FOUND,
+        &HeaderMap::new(),
+        None,
         &Method::GET,
         &next,
         &Method::GET,
         &previous,
+        &Extensions::new(),
     ) {
         ActionKind::Follow => (),
         other => panic!("unexpected {:?}", other),
@@ -300,10 +627,13 @@ fn test_redirect_policy_limit() {
 
     match policy.check(
         StatusCode::FOUND,
+        &HeaderMap::new(),
+        None,
         &Method::GET,
         &next,
         &Method::GET,
         &previous,
+        &Extensions::new(),
     ) {
         ActionKind::Error(err) if err.is::<TooManyRedirects>() => (),
         other => panic!("unexpected {:?}", other),
@@ -318,10 +648,13 @@ fn test_redirect_policy_limit_to_0() {
 
     match policy.check(
         StatusCode::FOUND,
+        &HeaderMap::new(),
+        None,
         &Method::GET,
         &next,
         &Method::GET,
         &previous,
+        &Extensions::new(),
     ) {
         ActionKind::Error(err) if err.is::<TooManyRedirects>() => (),
         other => panic!("unexpected {:?}", other),
@@ -339,13 +672,31 @@ fn test_redirect_policy_custom() {
     });
 
     let next = Url::parse("http://bar/baz").unwrap();
-    match policy.check(StatusCode::FOUND, &Method::GET, &next, &Method::GET, &[]) {
+    match policy.check(
+        StatusCode::FOUND,
+        &HeaderMap::new(),
+        None,
+        &Method::GET,
+        &next,
+        &Method::GET,
+        &[],
+        &Extensions::new(),
+    ) {
         ActionKind::Follow => (),
         other => panic!("unexpected {:?}", other),
     }
 
     let next = Url::parse("http://foo/baz").unwrap();
-    match policy.check(StatusCode::FOUND, &Method::GET, &next, &Method::GET, &[]) {
+    match policy.check(
+        StatusCode::FOUND,
+        &HeaderMap::new(),
+        None,
+        &Method::GET,
+        &next,
+        &Method::GET,
+        &[],
+        &Extensions::new(),
+    ) {
         ActionKind::Stop => (),
         other => panic!("unexpected {:?}", other),
     }
@@ -366,8 +717,60 @@ fn test_redirect_custom_policy_methods() {
     });
 
     let next = Url::parse("http://bar/baz").unwrap();
-    let res = policy.check(StatusCode::FOUND, &Method::HEAD, &next, &Method::PUT, &[]);
+    let res = policy.check(
+        StatusCode::FOUND,
+        &HeaderMap::new(),
+        None,
+        &Method::HEAD,
+        &next,
+        &Method::PUT,
+        &[],
+        &Extensions::new(),
+    );
+    assert!(matches!(res, ActionKind::Stop));
+}
+
+#[test]
+fn test_redirect_policy_attempt_response() {
+    use crate::core::header::{HeaderValue, SET_COOKIE};
+
+    let policy = Policy::custom(|attempt| {
+        assert_eq!(attempt.headers()[SET_COOKIE], "session=1");
+        match attempt.body() {
+            Some(body) if body.starts_with(b"<html>") => attempt.stop(),
+            _ => attempt.follow(),
+        }
+    })
+    .body_preview(6);
+    assert_eq!(policy.body_preview_limit(), 6);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(SET_COOKIE, HeaderValue::from_static("session=1"));
+
+    let next = Url::parse("http://bar/baz").unwrap();
+    let res = policy.check(
+        StatusCode::FOUND,
+        &headers,
+        Some(b"<html>"),
+        &Method::GET,
+        &next,
+        &Method::GET,
+        &[],
+        &Extensions::new(),
+    );
     assert!(matches!(res, ActionKind::Stop));
+
+    let res = policy.check(
+        StatusCode::FOUND,
+        &headers,
+        None,
+        &Method::GET,
+        &next,
+        &Method::GET,
+        &[],
+        &Extensions::new(),
+    );
+    assert!(matches!(res, ActionKind::Follow));
 }
 
 #[test]
@@ -383,13 +786,92 @@ fn test_remove_sensitive_headers() {
     let mut prev = vec![Url::parse("http://initial-domain.com/new_path").unwrap()];
     let mut filtered_headers = headers.clone();
 
-    Policy::remove_sensitive_headers(&mut headers, &next, &prev);
+    let policy = Policy::default();
+    policy.remove_sensitive_headers(&mut headers, &next, &prev);
     assert_eq!(headers, filtered_headers);
 
     prev.push(Url::parse("http://new-domain.com/path").unwrap());
     filtered_headers.remove(AUTHORIZATION);
     filtered_headers.remove(COOKIE);
 
-    Policy::remove_sensitive_headers(&mut headers, &next, &prev);
+    policy.remove_sensitive_headers(&mut headers, &next, &prev);
     assert_eq!(headers, filtered_headers);
 }
+
+#[test]
+fn test_remove_custom_sensitive_headers() {
+    use crate::core::header::{AUTHORIZATION, COOKIE, HeaderValue};
+
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_static("let me in"));
+    headers.insert(COOKIE, HeaderValue::from_static("foo=bar"));
+    headers.insert("x-api-key", HeaderValue::from_static("secret"));
+
+    let policy =
+        Policy::default().sensitive_headers([COOKIE, HeaderName::from_static("x-api-key")]);
+
+    let next = Url::parse("http://new-domain.com/path").unwrap();
+    let prev = vec![Url::parse("http://initial-domain.com/path").unwrap()];
+    policy.remove_sensitive_headers(&mut headers, &next, &prev);
+
+    assert_eq!(headers[AUTHORIZATION], "let me in");
+    assert!(!headers.contains_key(COOKIE));
+    assert!(!headers.contains_key("x-api-key"));
+}
+
+#[test]
+fn test_trusted_domain_keeps_sensitive_headers() {
+    use crate::core::header::{AUTHORIZATION, HeaderValue};
+
+    let policy = Policy::default().trusted_domain("example.com");
+
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_static("let me in"));
+
+    let prev = vec![Url::parse("https://api.example.com/path").unwrap()];
+    let next = Url::parse("https://auth.example.com/path").unwrap();
+    policy.remove_sensitive_headers(&mut headers, &next, &prev);
+    assert_eq!(headers[AUTHORIZATION], "let me in");
+
+    // downgrading to http is never trusted
+    let next = Url::parse("http://auth.example.com/path").unwrap();
+    policy.remove_sensitive_headers(&mut headers, &next, &prev);
+    assert!(!headers.contains_key(AUTHORIZATION));
+
+    // lookalike domains are not subdomains
+    headers.insert(AUTHORIZATION, HeaderValue::from_static("let me in"));
+    let next = Url::parse("https://notexample.com/path").unwrap();
+    policy.remove_sensitive_headers(&mut headers, &next, &prev);
+    assert!(!headers.contains_key(AUTHORIZATION));
+}
+
+#[test]
+fn test_refresh_target() {
+    assert_eq!(refresh_target("0; url=/next"), Some("/next"));
+    assert_eq!(
+        refresh_target("5;URL='https://example.com/a b'"),
+        Some("https://example.com/a b")
+    );
+    assert_eq!(refresh_target("0, url = \"/quoted\""), Some("/quoted"));
+    assert_eq!(refresh_target("3; /bare"), Some("/bare"));
+    assert_eq!(refresh_target("10"), None);
+    assert_eq!(refresh_target("0; url="), None);
+}
+
+#[test]
+fn test_meta_refresh() {
+    let html = br#"<html><head>
+        <meta charset="utf-8">
+        <META HTTP-EQUIV="Refresh" CONTENT="0; URL=/Next?a=1&amp;b=2">
+        </head></html>"#;
+    assert_eq!(meta_refresh(html).as_deref(), Some("/Next?a=1&b=2"));
+
+    let html = b"<meta http-equiv=refresh content='1;url=/unquoted'/>";
+    assert_eq!(meta_refresh(html).as_deref(), Some("/unquoted"));
+
+    let html = br#"<meta name="refresh" content="0; url=/nope">"#;
+    assert_eq!(meta_refresh(html), None);
+
+    let html = br#"<meta http-equiv="refresh" content="30">"#;
+    assert_eq!(meta_refresh(html), None);
+}
@@ -0,0 +1,376 @@
+//! Retry Handling
+//!
+//! By default, a `Client` does not retry failed requests, apart from the
+//! transparent retries of HTTP/2 requests refused by the server. A
+//! `retry::Policy` can be set on a `ClientBuilder` to retry connection
+//! errors and retryable status codes with exponential backoff.
+//!
+//! Only requests whose body can be replayed are retried. Bodies created from
+//! bytes or strings are replayable, as are those created with
+//! `Body::from_fn` and `Body::file`. Other streaming bodies are not.
+//!
+//! A retryable status is only retried for requests with an idempotent method,
+//! unless [`Policy::retry_non_idempotent`] is enabled: the server may already
+//! have processed a `POST` it answered with `503`.
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::header::{HeaderMap, RETRY_AFTER};
+use crate::{Method, StatusCode};
+
+/// A type that controls whether and when failed requests are retried.
+///
+/// The default policy does not retry. Use [`Policy::limited`] to enable retries.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use rquest::retry;
+///
+/// let policy = retry::Policy::limited(3)
+///     .backoff(Duration::from_millis(200), Duration::from_secs(5))
+///     .max_retry_after(Duration::from_secs(30));
+///
+/// let client = rquest::Client::builder().retry(policy).build().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct Policy {
+    max_retries: usize,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    connect_errors: bool,
+    non_idempotent: bool,
+    status: Option<Arc<dyn Fn(StatusCode) -> bool + Send + Sync>>,
+    max_retry_after: Duration,
+}
+
+impl Policy {
+    /// Create a `Policy` that retries a request up to `max` times.
+    ///
+    /// A request is therefore sent at most `max + 1` times. Connection errors
+    /// are retried, as are `429 Too Many Requests` and `5xx` responses to
+    /// requests with an idempotent method.
+    pub fn limited(max: usize) -> Self {
+        Self {
+            max_retries: max,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            connect_errors: true,
+            non_idempotent: false,
+            status: None,
+            max_retry_after: Duration::from_secs(60),
+        }
+    }
+
+    /// Create a `Policy` that does not retry.
+    pub fn none() -> Self {
+        Self::limited(0)
+    }
+
+    /// Set the exponential backoff between attempts.
+    ///
+    /// The delay before the `n`th retry is `base * 2^(n - 1)`, capped at `max`.
+    ///
+    /// Default is 100 milliseconds, capped at 30 seconds.
+    pub fn backoff(mut self, base: Duration, max: Duration) -> Self {
+        self.base_delay = base;
+        self.max_delay = max;
+        self
+    }
+
+    /// Enable or disable randomizing backoff delays.
+    ///
+    /// With jitter, each delay is picked between half and all of the computed backoff,
+    /// which avoids many clients retrying in lockstep.
+    ///
+    /// Default is `true`.
+    pub fn jitter(mut self, enable: bool) -> Self {
+        self.jitter = enable;
+        self
+    }
+
+    /// Enable or disable retrying requests that failed to connect.
+    ///
+    /// Default is `true`.
+    pub fn retry_connect_errors(mut self, enable: bool) -> Self {
+        self.connect_errors = enable;
+        self
+    }
+
+    /// Enable or disable retrying the responses of requests whose method isn't
+    /// idempotent, such as `POST` and `PATCH`.
+    ///
+    /// The server may have processed such a request before answering with a
+    /// retryable status, so sending it again could apply it twice. Requests
+    /// that failed to connect are retried regardless, as they were never sent.
+    ///
+    /// Default is `false`.
+    pub fn retry_non_idempotent(mut self, enable: bool) -> Self {
+        self.non_idempotent = enable;
+        self
+    }
+
+    /// Set which response statuses are retried.
+    ///
+    /// Default retries `429 Too Many Requests` and every `5xx` status.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rquest::{StatusCode, retry};
+    ///
+    /// let policy = retry::Policy::limited(2)
+    ///     .retry_status(|status| status == StatusCode::SERVICE_UNAVAILABLE);
+    /// ```
+    pub fn retry_status<F>(mut self, f: F) -> Self
+    where
+        F: Fn(StatusCode) -> bool + Send + Sync + 'static,
+    {
+        self.status = Some(Arc::new(f));
+        self
+    }
+
+    /// Set the longest `Retry-After` delay that will be waited for.
+    ///
+    /// Responses asking to wait longer than this are returned instead of retried.
+    ///
+    /// Default is 60 seconds.
+    pub fn max_retry_after(mut self, max: Duration) -> Self {
+        self.max_retry_after = max;
+        self
+    }
+
+    #[inline]
+    pub(crate) fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+
+    pub(crate) fn retries_status(&self, method: &Method, status: StatusCode) -> bool {
+        if !self.non_idempotent && !is_idempotent(method) {
+            return false;
+        }
+
+        match self.status {
+            Some(ref f) => f(status),
+            None => status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error(),
+        }
+    }
+
+    pub(crate) fn retries_error(&self, err: &(dyn StdError + 'static)) -> bool {
+        if !self.connect_errors {
+            return false;
+        }
+
+        let mut source = Some(err);
+        while let Some(err) = source {
+            if let Some(err) = err.downcast_ref::<crate::core::client::Error>() {
+                if err.is_connect() {
+                    return true;
+                }
+            }
+            source = err.source();
+        }
+
+        false
+    }
+
+    /// Compute the delay before retry number `retry` (starting at 1).
+    ///
+    /// Returns `None` if the response asked to wait longer than `max_retry_after`.
+    pub(crate) fn delay(&self, retry: usize, headers: Option<&HeaderMap>) -> Option<Duration> {
+        if let Some(wait) = headers.and_then(retry_after) {
+            return (wait <= self.max_retry_after).then_some(wait);
+        }
+
+        let exp = u32::try_from(retry.saturating_sub(1)).unwrap_or(u32::MAX);
+        let delay = self
+            .base_delay
+            .checked_mul(2u32.saturating_pow(exp))
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay));
+
+        if self.jitter {
+            let half = delay / 2;
+            let nanos = u64::try_from(half.as_nanos()).unwrap_or(u64::MAX);
+            let extra = crate::util::fast_random() % nanos.saturating_add(1);
+            return Some(half + Duration::from_nanos(extra));
+        }
+
+        Some(delay)
+    }
+}
+
+impl Default for Policy {
+    fn default() -> Policy {
+        Policy::none()
+    }
+}
+
+impl fmt::Debug for Policy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Policy")
+            .field("max_retries", &self.max_retries)
+            .field("base_delay", &self.base_delay)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .field("connect_errors", &self.connect_errors)
+            .field("non_idempotent", &self.non_idempotent)
+            .field("status", &self.status.as_ref().map(|_| "Custom"))
+            .field("max_retry_after", &self.max_retry_after)
+            .finish()
+    }
+}
+
+/// Whether sending a request with `method` twice has the same effect as once.
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS | Method::TRACE
+    )
+}
+
+/// Parse a `Retry-After` header given in seconds or as an HTTP-date.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = parse_http_date(value)?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Parse an IMF-fixdate, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+pub(crate) fn parse_http_date(value: &str) -> Option<SystemTime> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let (_weekday, rest) = value.split_once(", ")?;
+    let mut parts = rest.split(' ');
+    let day: u64 = parts.next()?.parse().ok()?;
+    let month = parts.next()?;
+    let month = MONTHS.iter().position(|m| *m == month)? as u64 + 1;
+    let year: u64 = parts.next()?.parse().ok()?;
+
+    let mut time = parts.next()?.split(':');
+    let hour: u64 = time.next()?.parse().ok()?;
+    let minute: u64 = time.next()?.parse().ok()?;
+    let second: u64 = time.next()?.parse().ok()?;
+
+    if parts.next()? != "GMT"
+        || parts.next().is_some()
+        || year < 1970
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    // days since the unix epoch of a civil date
+    let (y, m) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = y / 400;
+    let yoe = y % 400;
+    let doy = (153 * m + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = (era * 146097 + doe).checked_sub(719468)?;
+
+    let secs = days * 86400 + hour * 3600 + minute * 60 + second;
+    UNIX_EPOCH.checked_add(Duration::from_secs(secs))
+}
+
+#[test]
+fn test_policy_delay_backoff() {
+    let policy = Policy::limited(5)
+        .backoff(Duration::from_millis(100), Duration::from_millis(300))
+        .jitter(false);
+
+    assert_eq!(policy.delay(1, None), Some(Duration::from_millis(100)));
+    assert_eq!(policy.delay(2, None), Some(Duration::from_millis(200)));
+    assert_eq!(policy.delay(3, None), Some(Duration::from_millis(300)));
+    assert_eq!(policy.delay(64, None), Some(Duration::from_millis(300)));
+}
+
+#[test]
+fn test_policy_delay_jitter() {
+    let policy = Policy::limited(5).backoff(Duration::from_millis(100), Duration::from_secs(1));
+
+    for _ in 0..32 {
+        let delay = policy.delay(2, None).unwrap();
+        assert!(delay >= Duration::from_millis(100));
+        assert!(delay <= Duration::from_millis(200));
+    }
+}
+
+#[test]
+fn test_policy_delay_retry_after() {
+    let policy = Policy::limited(1).max_retry_after(Duration::from_secs(10));
+
+    let mut headers = HeaderMap::new();
+    headers.insert(RETRY_AFTER, "3".parse().unwrap());
+    assert_eq!(
+        policy.delay(1, Some(&headers)),
+        Some(Duration::from_secs(3))
+    );
+
+    headers.insert(RETRY_AFTER, "11".parse().unwrap());
+    assert_eq!(policy.delay(1, Some(&headers)), None);
+
+    headers.insert(
+        RETRY_AFTER,
+        "Sun, 06 Nov 1994 08:49:37 GMT".parse().unwrap(),
+    );
+    assert_eq!(policy.delay(1, Some(&headers)), Some(Duration::ZERO));
+}
+
+#[test]
+fn test_policy_retries_status() {
+    let policy = Policy::limited(1);
+    assert!(policy.retries_status(&Method::GET, StatusCode::TOO_MANY_REQUESTS));
+    assert!(policy.retries_status(&Method::GET, StatusCode::BAD_GATEWAY));
+    assert!(!policy.retries_status(&Method::GET, StatusCode::NOT_FOUND));
+
+    let policy = policy.retry_status(|status| status == StatusCode::NOT_FOUND);
+    assert!(policy.retries_status(&Method::GET, StatusCode::NOT_FOUND));
+    assert!(!policy.retries_status(&Method::GET, StatusCode::BAD_GATEWAY));
+}
+
+#[test]
+fn test_policy_retries_status_idempotent_only() {
+    let policy = Policy::limited(1);
+    assert!(policy.retries_status(&Method::PUT, StatusCode::BAD_GATEWAY));
+    assert!(!policy.retries_status(&Method::POST, StatusCode::BAD_GATEWAY));
+    assert!(!policy.retries_status(&Method::PATCH, StatusCode::TOO_MANY_REQUESTS));
+
+    let policy = policy.retry_non_idempotent(true);
+    assert!(policy.retries_status(&Method::POST, StatusCode::BAD_GATEWAY));
+}
+
+#[test]
+fn test_parse_http_date() {
+    assert_eq!(
+        parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+        Some(UNIX_EPOCH + Duration::from_secs(784111777))
+    );
+    assert_eq!(
+        parse_http_date("Thu, 01 Jan 1970 00:00:00 GMT"),
+        Some(UNIX_EPOCH)
+    );
+    assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), None);
+    assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 PST"), None);
+}
@@ -0,0 +1,219 @@
+//! Async runtime integration
+//!
+//! By default, a `Client` spawns its background tasks, such as the
+//! connections it keeps open, with `tokio::spawn`, and waits with Tokio's
+//! timers. [`ClientBuilder::runtime`](crate::ClientBuilder::runtime) hands them
+//! to another [`Runtime`] instead, so a client can live inside an application
+//! built on smol or async-std. The timeouts, backoffs and rate limits of its
+//! requests then sleep with [`Runtime::sleep`].
+//!
+//! # Tokio is still required
+//!
+//! A `Runtime` replaces Tokio's executor and the timers of requests, not
+//! Tokio's reactor. Sockets, including their TLS streams, are registered with
+//! Tokio's I/O driver, and the connect and TLS handshake timeouts use Tokio's
+//! timers. So a process running a client on another runtime still runs a
+//! Tokio reactor next to it:
+//!
+//! - The first client built with a `Runtime` starts a current-thread Tokio
+//!   runtime with [`Runtime::spawn_blocking`], which by default is a dedicated
+//!   thread named `rquest-driver`. It is shared by every client in the process.
+//! - It only drives I/O and the connect and handshake timeouts; no task of a
+//!   client runs on it.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use std::future::Future;
+//! use std::pin::Pin;
+//! use std::time::Duration;
+//!
+//! use rquest::rt::Runtime;
+//!
+//! struct Smol;
+//!
+//! impl Runtime for Smol {
+//!     fn spawn(&self, fut: Pin<Box<dyn Future<Output = ()> + Send>>) {
+//!         smol::spawn(fut).detach();
+//!     }
+//!
+//!     fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + Sync>> {
+//!         Box::pin(async move {
+//!             smol::Timer::after(duration).await;
+//!         })
+//!     }
+//! }
+//!
+//! smol::block_on(async {
+//!     let client = rquest::Client::builder().runtime(Smol).build()?;
+//!     let body = client.get("https://example.com").send().await?.text().await?;
+//!     # Ok::<_, rquest::Error>(())
+//! });
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use pin_project_lite::pin_project;
+use tokio::runtime::{EnterGuard, Handle};
+
+/// An async runtime a `Client` spawns its background tasks on.
+pub trait Runtime: Send + Sync + 'static {
+    /// Run `fut` in the background.
+    fn spawn(&self, fut: Pin<Box<dyn Future<Output = ()> + Send>>);
+
+    /// Return a future that resolves after `duration`.
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + Sync>>;
+
+    /// Run `f`, which blocks for as long as the process lives, off the
+    /// runtime's executor.
+    ///
+    /// rquest runs the Tokio driver its sockets are registered with this way.
+    /// The default implementation starts a thread for it; runtimes with a pool
+    /// for blocking work can run it there instead.
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) {
+        thread::Builder::new()
+            .name("rquest-driver".into())
+            .spawn(f)
+            .expect("spawn rquest runtime driver thread");
+    }
+}
+
+/// The Tokio runtime, which a `Client` uses by default.
+///
+/// Tasks are spawned with `tokio::spawn`, so they run on the Tokio runtime
+/// the request is sent from.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TokioRuntime;
+
+impl Runtime for TokioRuntime {
+    fn spawn(&self, fut: Pin<Box<dyn Future<Output = ()> + Send>>) {
+        let _enter = enter();
+        tokio::spawn(fut);
+    }
+
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + Sync>> {
+        Box::pin(sleep(duration))
+    }
+}
+
+/// Enter the runtime driver of rquest, unless the current thread already is
+/// in a Tokio runtime.
+pub(crate) fn enter() -> Option<EnterGuard<'static>> {
+    if Handle::try_current().is_ok() {
+        return None;
+    }
+    Some(driver(&TokioRuntime).enter())
+}
+
+/// Start the runtime driver of rquest on `runtime`, unless it is running
+/// already.
+pub(crate) fn start(runtime: &dyn Runtime) {
+    driver(runtime);
+}
+
+/// A Tokio sleep, which works outside of a Tokio runtime too.
+pub(crate) fn sleep(duration: Duration) -> tokio::time::Sleep {
+    let _enter = enter();
+    tokio::time::sleep(duration)
+}
+
+fn driver(runtime: &dyn Runtime) -> &'static Handle {
+    static DRIVER: OnceLock<Handle> = OnceLock::new();
+
+    DRIVER.get_or_init(|| {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("rquest runtime driver");
+        let handle = rt.handle().clone();
+        runtime.spawn_blocking(Box::new(move || rt.block_on(std::future::pending::<()>())));
+        handle
+    })
+}
+
+/// Spawns the background tasks of a client on a [`Runtime`].
+#[derive(Clone)]
+pub(crate) struct RuntimeExecutor(Arc<dyn Runtime>);
+
+impl RuntimeExecutor {
+    pub(crate) fn new(runtime: Arc<dyn Runtime>) -> RuntimeExecutor {
+        RuntimeExecutor(runtime)
+    }
+}
+
+impl<F> crate::core::rt::Executor<F> for RuntimeExecutor
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    fn execute(&self, fut: F) {
+        self.0.spawn(Box::pin(Entered { inner: fut }));
+    }
+}
+
+impl fmt::Debug for RuntimeExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RuntimeExecutor").finish()
+    }
+}
+
+/// Times the connections and requests of a client with a [`Runtime`].
+#[derive(Clone)]
+pub(crate) struct RuntimeTimer(Arc<dyn Runtime>);
+
+impl RuntimeTimer {
+    pub(crate) fn new(runtime: Arc<dyn Runtime>) -> RuntimeTimer {
+        RuntimeTimer(runtime)
+    }
+}
+
+impl crate::core::rt::Timer for RuntimeTimer {
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn crate::core::rt::Sleep>> {
+        Box::pin(RuntimeSleep(self.0.sleep(duration)))
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> Pin<Box<dyn crate::core::rt::Sleep>> {
+        self.sleep(deadline.saturating_duration_since(Instant::now()))
+    }
+}
+
+impl fmt::Debug for RuntimeTimer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RuntimeTimer").finish()
+    }
+}
+
+struct RuntimeSleep(Pin<Box<dyn Future<Output = ()> + Send + Sync>>);
+
+impl Future for RuntimeSleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.0.as_mut().poll(cx)
+    }
+}
+
+impl crate::core::rt::Sleep for RuntimeSleep {}
+
+pin_project! {
+    /// Polls a background task with the runtime driver entered, for the
+    /// sockets and timers it creates.
+    struct Entered<F> {
+        #[pin]
+        inner: F,
+    }
+}
+
+impl<F: Future> Future for Entered<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let _enter = enter();
+        self.project().inner.poll(cx)
+    }
+}
@@ -0,0 +1,469 @@
+//! Recording and playback of HTTP traffic for tests
+//!
+//! A [`VcrLayer`] set on a `ClientBuilder` with
+//! [`layer`](crate::ClientBuilder::layer) either records the requests a client
+//! sends, with the responses they received, into a [`Cassette`], or plays the
+//! responses of a cassette back without touching the network. A cassette is
+//! saved to and loaded from a JSON file, so tests of code built on rquest can
+//! record real traffic once and run deterministically afterwards.
+//!
+//! Requests are matched to recorded interactions by method and URL, and
+//! optionally by some of their headers and their body. Identical requests are
+//! answered in the order they were recorded, and the last of them is repeated
+//! once all were played.
+//!
+//! For unit tests that don't need recorded traffic,
+//! [`ClientBuilder::mock`](crate::ClientBuilder::mock) answers every request
+//! with a function instead.
+//!
+//! Response bodies are recorded after decompression, so the
+//! `Content-Encoding` and `Content-Length` headers are left out. Streaming
+//! request bodies are recorded as empty.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use rquest::testing::{Cassette, VcrLayer};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! // record the traffic once...
+//! let cassette = Cassette::new();
+//! let client = rquest::Client::builder()
+//!     .layer(VcrLayer::record(cassette.clone()))
+//!     .build()?;
+//! client.get("https://example.com").send().await?.text().await?;
+//! cassette.save("tests/fixtures/example.json")?;
+//!
+//! // ...and play it back in tests
+//! let cassette = Cassette::load("tests/fixtures/example.json")?;
+//! let client = rquest::Client::builder()
+//!     .layer(VcrLayer::playback(cassette))
+//!     .build()?;
+//! let body = client.get("https://example.com").send().await?.text().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use antidote::Mutex;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use serde::{Deserialize, Serialize};
+use tower::{Layer, Service};
+
+use crate::header::{CONTENT_ENCODING, CONTENT_LENGTH, HeaderMap, HeaderName, HeaderValue};
+use crate::response::{ResponseBuilderExt, ResponseUrl};
+use crate::{Body, Error, Request, Response, StatusCode, Url, error};
+
+/// A list of recorded requests and the responses they received.
+///
+/// Clones of a cassette share their interactions, so one clone can be given to
+/// a `VcrLayer` and another used to save the recording.
+#[derive(Clone, Default)]
+pub struct Cassette {
+    interactions: Arc<Mutex<Vec<Interaction>>>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+    #[serde(skip)]
+    played: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: RecordedBody,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: RecordedBody,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum RecordedBody {
+    Text(String),
+    Base64(String),
+}
+
+impl Cassette {
+    /// Create an empty cassette.
+    pub fn new() -> Cassette {
+        Cassette::default()
+    }
+
+    /// Load a cassette saved with [`Cassette::save`].
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Cassette> {
+        let json = std::fs::read(path)?;
+        let interactions = serde_json::from_slice(&json)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Cassette {
+            interactions: Arc::new(Mutex::new(interactions)),
+        })
+    }
+
+    /// Save the interactions recorded so far to `path`, as JSON.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(&*self.interactions.lock())
+            .expect("a cassette serializes to JSON");
+        std::fs::write(path, json)
+    }
+
+    /// Get the number of recorded interactions.
+    pub fn len(&self) -> usize {
+        self.interactions.lock().len()
+    }
+
+    /// Check whether no interactions were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.interactions.lock().is_empty()
+    }
+
+    fn push(&self, interaction: Interaction) {
+        self.interactions.lock().push(interaction);
+    }
+
+    /// Take the response of the first interaction matching `req` that wasn't
+    /// played yet, or else of the last interaction matching it.
+    fn play(&self, req: &Request, matcher: &Matcher) -> Option<RecordedResponse> {
+        let mut interactions = self.interactions.lock();
+        let mut matching = interactions
+            .iter_mut()
+            .filter(|interaction| matcher.matches(req, &interaction.request))
+            .collect::<Vec<_>>();
+
+        let index = match matching.iter().position(|interaction| !interaction.played) {
+            Some(index) => index,
+            None => matching.len().checked_sub(1)?,
+        };
+        let interaction = &mut matching[index];
+        interaction.played = true;
+        Some(interaction.response.clone())
+    }
+}
+
+impl fmt::Debug for Cassette {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cassette")
+            .field("interactions", &self.len())
+            .finish()
+    }
+}
+
+/// How requests are matched to recorded interactions.
+#[derive(Clone, Default)]
+struct Matcher {
+    headers: Vec<HeaderName>,
+    body: bool,
+}
+
+impl Matcher {
+    fn matches(&self, req: &Request, recorded: &RecordedRequest) -> bool {
+        if req.method().as_str() != recorded.method || req.url().as_str() != recorded.url {
+            return false;
+        }
+
+        let headers_match = self.headers.iter().all(|name| {
+            let sent = req.headers().get_all(name).iter().map(|v| v.as_bytes());
+            let recorded = recorded
+                .headers
+                .iter()
+                .filter(|(n, _)| name.as_str().eq_ignore_ascii_case(n))
+                .map(|(_, v)| v.as_bytes());
+            sent.eq(recorded)
+        });
+
+        headers_match && (!self.body || request_body(req) == recorded.body)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    Record,
+    Playback,
+}
+
+/// A `tower::Layer` that records the traffic of a client into a [`Cassette`],
+/// or plays a cassette back.
+///
+/// See the [module documentation](self) for an example.
+#[derive(Clone)]
+pub struct VcrLayer {
+    cassette: Cassette,
+    mode: Mode,
+    matcher: Matcher,
+}
+
+impl VcrLayer {
+    /// Send requests, and record them with their responses into `cassette`.
+    pub fn record(cassette: Cassette) -> VcrLayer {
+        VcrLayer {
+            cassette,
+            mode: Mode::Record,
+            matcher: Matcher::default(),
+        }
+    }
+
+    /// Answer requests with the responses recorded in `cassette`, without
+    /// sending them.
+    ///
+    /// A request no interaction matches fails with an error.
+    pub fn playback(cassette: Cassette) -> VcrLayer {
+        VcrLayer {
+            cassette,
+            mode: Mode::Playback,
+            matcher: Matcher::default(),
+        }
+    }
+
+    /// Also match requests by the values of the header `name`.
+    pub fn match_header(mut self, name: HeaderName) -> VcrLayer {
+        self.matcher.headers.push(name);
+        self
+    }
+
+    /// Set whether requests are also matched by their body.
+    ///
+    /// Default is `false`.
+    pub fn match_body(mut self, enable: bool) -> VcrLayer {
+        self.matcher.body = enable;
+        self
+    }
+}
+
+impl fmt::Debug for VcrLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VcrLayer")
+            .field("cassette", &self.cassette)
+            .field("mode", &self.mode)
+            .finish()
+    }
+}
+
+impl<S> Layer<S> for VcrLayer {
+    type Service = Vcr<S>;
+
+    fn layer(&self, inner: S) -> Vcr<S> {
+        Vcr {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// The service created by a [`VcrLayer`].
+#[derive(Clone)]
+pub struct Vcr<S> {
+    inner: S,
+    layer: VcrLayer,
+}
+
+impl<S> fmt::Debug for Vcr<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Vcr").field("layer", &self.layer).finish()
+    }
+}
+
+impl<S> Service<Request> for Vcr<S>
+where
+    S: Service<Request, Response = Response, Error = Error> + Send,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        match self.layer.mode {
+            Mode::Record => self.inner.poll_ready(cx),
+            Mode::Playback => Poll::Ready(Ok(())),
+        }
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        match self.layer.mode {
+            Mode::Playback => {
+                let res = match self.layer.cassette.play(&req, &self.layer.matcher) {
+                    Some(recorded) => recorded.into_response(req.url().clone()),
+                    None => Err(error::request(format!(
+                        "no recorded interaction matches {} {}",
+                        req.method(),
+                        req.url()
+                    ))),
+                };
+                Box::pin(std::future::ready(res))
+            }
+            Mode::Record => {
+                let recorded = RecordedRequest {
+                    method: req.method().to_string(),
+                    url: req.url().to_string(),
+                    headers: header_pairs(req.headers()),
+                    body: request_body(&req),
+                };
+                let cassette = self.layer.cassette.clone();
+                let fut = self.inner.call(req);
+                Box::pin(async move {
+                    let res = fut.await?;
+                    let status = res.status();
+                    let url = res.url().clone();
+                    let mut headers = res.headers().clone();
+                    headers.remove(CONTENT_ENCODING);
+                    headers.remove(CONTENT_LENGTH);
+                    let body = res.bytes().await?;
+
+                    let response = RecordedResponse {
+                        status: status.as_u16(),
+                        headers: header_pairs(&headers),
+                        body: RecordedBody::new(&body),
+                    };
+                    cassette.push(Interaction {
+                        request: recorded,
+                        response: response.clone(),
+                        played: false,
+                    });
+                    response.into_response(url)
+                })
+            }
+        }
+    }
+}
+
+impl RecordedResponse {
+    fn into_response(self, url: Url) -> Result<Response, Error> {
+        let mut builder = http::Response::builder()
+            .status(StatusCode::from_u16(self.status).map_err(error::decode)?)
+            .url(url);
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(error::decode)?;
+            let value = HeaderValue::from_str(value).map_err(error::decode)?;
+            builder = builder.header(name, value);
+        }
+        let res = builder
+            .body(self.body.into_bytes()?)
+            .map_err(error::decode)?;
+        Ok(Response::from(res))
+    }
+}
+
+impl RecordedBody {
+    fn new(bytes: &[u8]) -> RecordedBody {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => RecordedBody::Text(text.to_owned()),
+            Err(_) => RecordedBody::Base64(BASE64_STANDARD.encode(bytes)),
+        }
+    }
+
+    fn into_bytes(self) -> Result<Vec<u8>, Error> {
+        match self {
+            RecordedBody::Text(text) => Ok(text.into_bytes()),
+            RecordedBody::Base64(data) => BASE64_STANDARD.decode(data).map_err(error::decode),
+        }
+    }
+}
+
+fn request_body(req: &Request) -> RecordedBody {
+    RecordedBody::new(
+        req.body()
+            .and_then(|body| body.as_bytes())
+            .unwrap_or_default(),
+    )
+}
+
+fn header_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| {
+            let value = value.to_str().ok()?;
+            Some((name.as_str().to_owned(), value.to_owned()))
+        })
+        .collect()
+}
+
+type MockFn = Arc<dyn Fn(Request) -> http::Response<Body> + Send + Sync>;
+
+/// A `tower::Layer` that answers every request with a function, without
+/// sending it.
+///
+/// Set with [`ClientBuilder::mock`](crate::ClientBuilder::mock).
+#[derive(Clone)]
+pub struct MockLayer {
+    respond: MockFn,
+}
+
+impl MockLayer {
+    /// Answer every request with the response returned by `respond`.
+    ///
+    /// A response without a URL set with `ResponseBuilderExt::url` gets the
+    /// URL of its request.
+    pub fn new<F, B>(respond: F) -> MockLayer
+    where
+        F: Fn(Request) -> http::Response<B> + Send + Sync + 'static,
+        B: Into<Body>,
+    {
+        MockLayer {
+            respond: Arc::new(move |req| respond(req).map(Into::into)),
+        }
+    }
+}
+
+impl fmt::Debug for MockLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockLayer").finish()
+    }
+}
+
+impl<S> Layer<S> for MockLayer {
+    type Service = Mock;
+
+    fn layer(&self, _inner: S) -> Mock {
+        Mock {
+            respond: self.respond.clone(),
+        }
+    }
+}
+
+/// The service created by a [`MockLayer`].
+#[derive(Clone)]
+pub struct Mock {
+    respond: MockFn,
+}
+
+impl fmt::Debug for Mock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mock").finish()
+    }
+}
+
+impl Service<Request> for Mock {
+    type Response = Response;
+    type Error = Error;
+    type Future = std::future::Ready<Result<Response, Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let url = req.url().clone();
+        let mut res = (self.respond)(req);
+        if res.extensions().get::<ResponseUrl>().is_none() {
+            res.extensions_mut().insert(ResponseUrl(url));
+        }
+        std::future::ready(Ok(Response::from(res)))
+    }
+}
@@ -1,8 +1,46 @@
-use super::{AlpnProtos, AlpsProtos, TlsVersion};
+use super::{AlpnProtos, AlpsProtos, CertStore, Identity, TlsVersion};
 use boring2::ssl::{CertCompressionAlgorithm, ExtensionType};
 use std::borrow::Cow;
 
-/// Builder for `[`TlsConfig`]`.
+/// Builder for [`TlsConfig`].
+///
+/// Every part of the ClientHello that BoringSSL lets a client choose can be set
+/// here, so the builder can reproduce clients that have no emulation profile.
+/// Lists are sent in the order they are given.
+///
+/// Curve and signature algorithm names that BoringSSL doesn't recognize are
+/// reported as an error when the `Client` is built. Unknown cipher names are
+/// skipped, as BoringSSL does for cipher lists, but a cipher list that selects
+/// no cipher is an error too.
+///
+/// # Example
+///
+/// A ClientHello resembling curl's:
+///
+/// ```
+/// use rquest::tls::{AlpnProtos, TlsConfig, TlsVersion};
+///
+/// let tls = TlsConfig::builder()
+///     .ciphers([
+///         "TLS_AES_128_GCM_SHA256",
+///         "TLS_AES_256_GCM_SHA384",
+///         "TLS_CHACHA20_POLY1305_SHA256",
+///         "TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256",
+///         "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256",
+///     ])
+///     .curves(["X25519", "P-256", "P-384"])
+///     .signature_algorithms([
+///         "ecdsa_secp256r1_sha256",
+///         "rsa_pss_rsae_sha256",
+///         "rsa_pkcs1_sha256",
+///     ])
+///     .alpn_protos(AlpnProtos::ALL)
+///     .grease_enabled(false)
+///     .permute_extensions(false)
+///     .key_shares_limit(1)
+///     .min_tls_version(TlsVersion::TLS_1_2)
+///     .build();
+/// ```
 #[must_use]
 #[derive(Debug)]
 pub struct TlsConfigBuilder {
@@ -12,7 +50,7 @@ pub struct TlsConfigBuilder {
 /// Configuration settings for TLS connections.
 ///
 /// This struct defines various parameters to fine-tune the behavior of a TLS connection,
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TlsConfig {
     pub(crate) alpn_protos: AlpnProtos,
     pub(crate) alps_protos: Option<AlpsProtos>,
@@ -33,6 +71,7 @@ pub struct TlsConfig {
     pub(crate) renegotiation: bool,
     pub(crate) delegated_credentials: Option<Cow<'static, str>>,
     pub(crate) curves_list: Option<Cow<'static, str>>,
+    pub(crate) pq_key_share: Option<Option<PqKeyShare>>,
     pub(crate) cipher_list: Option<Cow<'static, str>>,
     pub(crate) sigalgs_list: Option<Cow<'static, str>>,
     pub(crate) cert_compression_algorithm: Option<Cow<'static, [CertCompressionAlgorithm]>>,
@@ -63,6 +102,7 @@ impl Default for TlsConfig {
             renegotiation: true,
             delegated_credentials: None,
             curves_list: None,
+            pq_key_share: None,
             cipher_list: None,
             sigalgs_list: None,
             cert_compression_algorithm: None,
@@ -178,6 +218,9 @@ impl TlsConfigBuilder {
     }
 
     /// Sets the key shares length limit.
+    ///
+    /// Key shares are sent for the first `limit` curves of the
+    /// [`curves`](Self::curves) list.
     pub fn key_shares_limit<T>(mut self, limit: T) -> Self
     where
         T: Into<Option<u8>>,
@@ -207,6 +250,18 @@ impl TlsConfigBuilder {
         self
     }
 
+    /// Sets the supported curves, in order of preference.
+    ///
+    /// This is the same as [`curves_list`](Self::curves_list), with the curves given
+    /// separately instead of joined by `:`.
+    pub fn curves<I, S>(self, curves: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.curves_list(join_list(curves))
+    }
+
     /// Sets the supported curves list.
     pub fn curves_list<T>(mut self, curves: T) -> Self
     where
@@ -216,6 +271,34 @@ impl TlsConfigBuilder {
         self
     }
 
+    /// Sets the post-quantum hybrid key share, or leaves it out with `None`.
+    ///
+    /// The key exchange is added in front of the [`curves`](Self::curves), or of
+    /// BoringSSL's default curves if none are set, and any other post-quantum key
+    /// exchange is removed from them. Browsers also send an X25519 key share, so
+    /// [`key_shares_limit`](Self::key_shares_limit) should be at least 2.
+    ///
+    /// If not set, the curves are used as given.
+    pub fn pq_key_share<T>(mut self, key_share: T) -> Self
+    where
+        T: Into<Option<PqKeyShare>>,
+    {
+        self.config.pq_key_share = Some(key_share.into());
+        self
+    }
+
+    /// Sets the cipher suites, in the order they are sent.
+    ///
+    /// This is the same as [`cipher_list`](Self::cipher_list), with the cipher
+    /// suites given separately instead of joined by `:`.
+    pub fn ciphers<I, S>(self, ciphers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.cipher_list(join_list(ciphers))
+    }
+
     /// Sets the cipher list.
     pub fn cipher_list<T>(mut self, ciphers: T) -> Self
     where
@@ -225,6 +308,18 @@ impl TlsConfigBuilder {
         self
     }
 
+    /// Sets the signature algorithms, in the order they are sent.
+    ///
+    /// This is the same as [`sigalgs_list`](Self::sigalgs_list), with the
+    /// algorithms given separately instead of joined by `:`.
+    pub fn signature_algorithms<I, S>(self, sigalgs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.sigalgs_list(join_list(sigalgs))
+    }
+
     /// Sets the supported signature algorithms.
     pub fn sigalgs_list<T>(mut self, sigalgs: T) -> Self
     where
@@ -244,6 +339,9 @@ impl TlsConfigBuilder {
     }
 
     /// Sets the extension permutation.
+    ///
+    /// Extensions are sent in the given order. This shouldn't be combined with
+    /// [`permute_extensions`](Self::permute_extensions).
     pub fn extension_permutation<T>(mut self, permutation: T) -> Self
     where
         T: Into<Cow<'static, [ExtensionType]>>,
@@ -275,4 +373,195 @@ impl TlsConfig {
             config: TlsConfig::default(),
         }
     }
+
+    /// The curves list to configure, with the post-quantum key share applied.
+    pub(crate) fn effective_curves_list(&self) -> Option<Cow<'static, str>> {
+        let Some(pq_key_share) = self.pq_key_share else {
+            return self.curves_list.clone();
+        };
+
+        let curves = self.curves_list.as_deref().unwrap_or(DEFAULT_CURVES);
+        let curves = pq_key_share
+            .map(PqKeyShare::as_str)
+            .into_iter()
+            .chain(
+                curves
+                    .split(':')
+                    .filter(|curve| !PqKeyShare::ALL.iter().any(|pq| pq.as_str() == *curve)),
+            )
+            .collect::<Vec<_>>();
+        Some(curves.join(":").into())
+    }
+}
+
+/// BoringSSL's curves when none are configured.
+const DEFAULT_CURVES: &str = "X25519:P-256:P-384";
+
+/// A post-quantum hybrid key exchange.
+///
+/// These combine X25519 with a post-quantum key encapsulation mechanism, so the
+/// shared secret stays safe if either is broken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PqKeyShare {
+    /// X25519 with ML-KEM-768, sent by Chrome 131 and later.
+    X25519MlKem768,
+    /// X25519 with the draft version of Kyber768, sent by Chrome 124 to 130.
+    X25519Kyber768Draft00,
+}
+
+impl PqKeyShare {
+    const ALL: [PqKeyShare; 2] = [
+        PqKeyShare::X25519MlKem768,
+        PqKeyShare::X25519Kyber768Draft00,
+    ];
+
+    /// The name of the key exchange in BoringSSL's curves list.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PqKeyShare::X25519MlKem768 => "X25519MLKEM768",
+            PqKeyShare::X25519Kyber768Draft00 => "X25519Kyber768Draft00",
+        }
+    }
+}
+
+/// TLS settings that replace the client's own for specific hosts.
+///
+/// Settings that aren't set are taken from the client. See
+/// [`ClientBuilder::tls_for_host`](crate::ClientBuilder::tls_for_host).
+///
+/// # Example
+///
+/// ```
+/// use rquest::tls::{TlsOptions, TlsVersion};
+///
+/// let client = rquest::Client::builder()
+///     .min_tls_version(TlsVersion::TLS_1_2)
+///     .tls_for_host(
+///         "legacy.internal.corp",
+///         TlsOptions::new()
+///             .cert_verification(false)
+///             .min_tls_version(TlsVersion::TLS_1_0),
+///     )
+///     .build()
+///     .unwrap();
+/// ```
+#[must_use]
+#[derive(Clone, Debug, Default)]
+pub struct TlsOptions {
+    pub(crate) cert_verification: Option<bool>,
+    pub(crate) verify_hostname: Option<bool>,
+    pub(crate) cert_store: Option<CertStore>,
+    pub(crate) identity: Option<Identity>,
+    pub(crate) min_tls_version: Option<TlsVersion>,
+    pub(crate) max_tls_version: Option<TlsVersion>,
+}
+
+impl TlsOptions {
+    /// Creates `TlsOptions` that keep all of the client's settings.
+    pub fn new() -> TlsOptions {
+        TlsOptions::default()
+    }
+
+    /// Controls the use of certificate validation.
+    ///
+    /// # Warning
+    ///
+    /// If invalid certificates are trusted, *any* certificate will be trusted for
+    /// the host, including expired certificates.
+    pub fn cert_verification(mut self, enabled: bool) -> TlsOptions {
+        self.cert_verification = Some(enabled);
+        self
+    }
+
+    /// Controls the use of hostname verification.
+    pub fn verify_hostname(mut self, enabled: bool) -> TlsOptions {
+        self.verify_hostname = Some(enabled);
+        self
+    }
+
+    /// Sets the certificate store used to verify the host's certificates.
+    pub fn cert_store(mut self, store: CertStore) -> TlsOptions {
+        self.cert_store = Some(store);
+        self
+    }
+
+    /// Sets the identity sent to the host for client certificate authentication.
+    pub fn identity(mut self, identity: Identity) -> TlsOptions {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Sets the minimum TLS version.
+    pub fn min_tls_version(mut self, version: TlsVersion) -> TlsOptions {
+        self.min_tls_version = Some(version);
+        self
+    }
+
+    /// Sets the maximum TLS version.
+    pub fn max_tls_version(mut self, version: TlsVersion) -> TlsOptions {
+        self.max_tls_version = Some(version);
+        self
+    }
+}
+
+fn join_list<I, S>(items: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    items.into_iter().fold(String::new(), |mut list, item| {
+        if !list.is_empty() {
+            list.push(':');
+        }
+        list.push_str(item.as_ref());
+        list
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curves(builder: TlsConfigBuilder) -> Option<String> {
+        builder.build().effective_curves_list().map(Cow::into_owned)
+    }
+
+    #[test]
+    fn join_list_keeps_order() {
+        assert_eq!(join_list(Vec::<&str>::new()), "");
+        assert_eq!(join_list(["X25519"]), "X25519");
+        assert_eq!(
+            join_list(["P-256", "X25519", "P-384"]),
+            "P-256:X25519:P-384"
+        );
+        assert_eq!(join_list(vec![String::from("b"), String::from("a")]), "b:a");
+    }
+
+    #[test]
+    fn pq_key_share_curves() {
+        assert_eq!(curves(TlsConfig::builder()), None);
+        assert_eq!(
+            curves(TlsConfig::builder().curves(["X25519", "P-256"])).as_deref(),
+            Some("X25519:P-256")
+        );
+
+        assert_eq!(
+            curves(TlsConfig::builder().pq_key_share(PqKeyShare::X25519MlKem768)).as_deref(),
+            Some("X25519MLKEM768:X25519:P-256:P-384")
+        );
+
+        // replaces the profile's key share
+        let profile = TlsConfig::builder().curves(["X25519Kyber768Draft00", "X25519", "P-256"]);
+        assert_eq!(
+            curves(profile.pq_key_share(PqKeyShare::X25519MlKem768)).as_deref(),
+            Some("X25519MLKEM768:X25519:P-256")
+        );
+
+        let profile = TlsConfig::builder().curves(["X25519MLKEM768", "X25519", "P-256"]);
+        assert_eq!(
+            curves(profile.pq_key_share(None)).as_deref(),
+            Some("X25519:P-256")
+        );
+    }
 }
@@ -1,6 +1,6 @@
 //! backport: <https://github.com/cloudflare/boring/blob/master/hyper-boring/src/lib.rs>
 
-use super::cache::{SessionCache, SessionKey};
+use super::cache::{SessionCache, SessionKey, TlsSessionCache};
 use super::ext::{ConnectConfigurationExt, SslConnectorBuilderExt, SslRefExt};
 use super::{HandshakeSettings, MaybeHttpsStream, key_index};
 
@@ -9,7 +9,10 @@ use crate::connect::HttpConnector;
 use crate::core::client::connect::Connection;
 use crate::core::rt::TokioIo;
 use crate::error::BoxError;
-use crate::tls::{CertStore, Identity, KeyLogPolicy, TlsConfig};
+use crate::tls::{
+    CertPins, CertStore, CertVerifier, Identity, KeyLogPolicy, OcspPolicy, TlsConfig, TlsOptions,
+    VerifiedChain,
+};
 
 use crate::core::rt::{Read, Write};
 use antidote::Mutex;
@@ -24,6 +27,7 @@ use http::uri::Scheme;
 use tokio_boring2::SslStream;
 use tower_service::Service;
 
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::Debug;
 use std::future::Future;
@@ -31,6 +35,7 @@ use std::net::{IpAddr, Ipv6Addr};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 /// A Connector using BoringSSL to support `http` and `https` schemes.
 #[derive(Clone)]
@@ -128,12 +133,20 @@ pub struct TlsConnector {
 }
 
 /// A builder for creating a `TlsConnector`.
+#[derive(Clone)]
 pub struct TlsConnectorBuilder {
     config: TlsConfig,
     keylog_policy: Option<KeyLogPolicy>,
     identity: Option<Identity>,
     cert_store: Option<CertStore>,
     cert_verification: bool,
+    cert_pins: CertPins,
+    cert_verifier: Option<CertVerifier>,
+    ocsp_policy: Option<OcspPolicy>,
+    require_sct: bool,
+    handshake_timeout: Option<Duration>,
+    host_options: Vec<(String, TlsOptions)>,
+    session_cache: Option<TlsSessionCache>,
     tls_sni: bool,
     verify_hostname: bool,
 }
@@ -145,6 +158,12 @@ struct Inner {
     callback: Option<Callback>,
     ssl_callback: Option<SslCallback>,
     skip_session_ticket: bool,
+    cert_pins: CertPins,
+    cert_verifier: Option<CertVerifier>,
+    ocsp_policy: Option<OcspPolicy>,
+    require_sct: bool,
+    handshake_timeout: Option<Duration>,
+    hosts: Arc<HashMap<String, Inner>>,
 }
 
 type Callback =
@@ -160,6 +179,13 @@ impl TlsConnector {
             identity: None,
             cert_store: None,
             cert_verification: true,
+            cert_pins: CertPins::default(),
+            cert_verifier: None,
+            ocsp_policy: None,
+            require_sct: false,
+            handshake_timeout: None,
+            host_options: Vec::new(),
+            session_cache: None,
             tls_sni: true,
             verify_hostname: true,
         }
@@ -169,12 +195,14 @@ impl TlsConnector {
     fn with_connector_and_settings(
         mut ssl: SslConnectorBuilder,
         settings: HandshakeSettings,
+        session_cache: Option<TlsSessionCache>,
+        cert_pins: CertPins,
     ) -> TlsConnector {
         // If the session cache is disabled, we don't need to set up any callbacks.
         let cache = if settings.session_cache {
-            let cache = Arc::new(Mutex::new(SessionCache::with_capacity(
-                settings.session_cache_capacity,
-            )));
+            let cache = session_cache
+                .unwrap_or_else(|| TlsSessionCache::with_capacity(settings.session_cache_capacity))
+                .0;
 
             ssl.set_session_cache_mode(SslSessionCacheMode::CLIENT);
 
@@ -218,6 +246,12 @@ impl TlsConnector {
                 callback: Some(callback),
                 ssl_callback: None,
                 skip_session_ticket: settings.skip_session_ticket,
+                cert_pins,
+                cert_verifier: None,
+                ocsp_policy: None,
+                require_sct: false,
+                handshake_timeout: None,
+                hosts: Arc::default(),
             },
         }
     }
@@ -255,6 +289,55 @@ impl TlsConnectorBuilder {
         self
     }
 
+    /// Sets the pinned keys checked after the handshake.
+    #[inline]
+    pub fn cert_pins(mut self, cert_pins: CertPins) -> Self {
+        self.cert_pins = cert_pins;
+        self
+    }
+
+    /// Sets the verifier that replaces the built-in certificate verification.
+    #[inline]
+    pub fn cert_verifier(mut self, verifier: Option<CertVerifier>) -> Self {
+        self.cert_verifier = verifier;
+        self
+    }
+
+    /// Sets how stapled OCSP responses are checked after the handshake.
+    #[inline]
+    pub fn ocsp_policy(mut self, policy: Option<OcspPolicy>) -> Self {
+        self.ocsp_policy = policy;
+        self
+    }
+
+    /// Sets whether the server's certificate must carry enough SCTs.
+    #[inline]
+    pub fn require_sct(mut self, enabled: bool) -> Self {
+        self.require_sct = enabled;
+        self
+    }
+
+    /// Sets the timeout of the TLS handshake.
+    #[inline]
+    pub fn handshake_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.handshake_timeout = timeout;
+        self
+    }
+
+    /// Sets the options that replace this builder's settings for specific hosts.
+    #[inline]
+    pub fn host_options(mut self, host_options: Vec<(String, TlsOptions)>) -> Self {
+        self.host_options = host_options;
+        self
+    }
+
+    /// Sets the cache that stores sessions for resumption, if it is enabled.
+    #[inline]
+    pub fn session_cache(mut self, cache: Option<TlsSessionCache>) -> Self {
+        self.session_cache = cache;
+        self
+    }
+
     /// Sets the Server Name Indication (SNI) flag.
     #[inline]
     pub fn tls_sni(mut self, enabled: bool) -> Self {
@@ -270,18 +353,53 @@ impl TlsConnectorBuilder {
     }
 
     /// Build the `TlsConnector` with the provided configuration.
-    pub fn build(self) -> crate::Result<TlsConnector> {
+    pub fn build(mut self) -> crate::Result<TlsConnector> {
+        // Each host with its own options gets a connector of its own.
+        let mut hosts = HashMap::with_capacity(self.host_options.len());
+        for (host, options) in std::mem::take(&mut self.host_options) {
+            let connector = self.clone().with_options(options).build()?;
+            hosts.insert(host.to_ascii_lowercase(), connector.inner);
+        }
+
+        let mut connector = self.build_connector()?;
+        connector.inner.hosts = Arc::new(hosts);
+        Ok(connector)
+    }
+
+    fn with_options(mut self, options: TlsOptions) -> Self {
+        if let Some(cert_verification) = options.cert_verification {
+            self.cert_verification = cert_verification;
+        }
+        if let Some(verify_hostname) = options.verify_hostname {
+            self.verify_hostname = verify_hostname;
+        }
+        if options.cert_store.is_some() {
+            self.cert_store = options.cert_store;
+        }
+        if options.identity.is_some() {
+            self.identity = options.identity;
+        }
+        if options.min_tls_version.is_some() {
+            self.config.min_tls_version = options.min_tls_version;
+        }
+        if options.max_tls_version.is_some() {
+            self.config.max_tls_version = options.max_tls_version;
+        }
+        self
+    }
+
+    fn build_connector(self) -> crate::Result<TlsConnector> {
         let config = self.config;
 
         let mut connector = SslConnector::no_default_verify_builder(SslMethod::tls_client())?
             .cert_store(self.cert_store)?
-            .cert_verification(self.cert_verification)?
+            .cert_verification(self.cert_verification && self.cert_verifier.is_none())?
             .identity(self.identity)?
             .alpn_protos(config.alpn_protos)?
             .min_tls_version(config.min_tls_version)?
             .max_tls_version(config.max_tls_version)?;
 
-        if config.enable_ocsp_stapling {
+        if config.enable_ocsp_stapling || self.ocsp_policy.is_some() {
             connector.enable_ocsp_stapling();
         }
 
@@ -309,8 +427,8 @@ impl TlsConnectorBuilder {
             connector.set_permute_extensions(permute_extensions);
         }
 
-        if let Some(curves_list) = config.curves_list.as_deref() {
-            connector.set_curves_list(curves_list)?;
+        if let Some(curves_list) = config.effective_curves_list() {
+            connector.set_curves_list(&curves_list)?;
         }
 
         if let Some(sigalgs_list) = config.sigalgs_list.as_deref() {
@@ -347,12 +465,20 @@ impl TlsConnectorBuilder {
             connector.set_aes_hw_override(aes_hw_override);
         }
 
-        if let Some(policy) = self.keylog_policy {
-            let handle = policy.open_handle().map_err(crate::error::builder)?;
-            connector.set_keylog_callback(move |_, line| {
-                let line = format!("{}\n", line);
-                handle.write_log_line(line);
-            });
+        match self.keylog_policy {
+            Some(KeyLogPolicy::Callback(callback)) => {
+                connector.set_keylog_callback(move |_, line| callback(line));
+            }
+            // Without `SSLKEYLOGFILE`, nothing is logged.
+            Some(KeyLogPolicy::Environment) if std::env::var_os("SSLKEYLOGFILE").is_none() => {}
+            Some(policy) => {
+                let handle = policy.open_handle().map_err(crate::error::builder)?;
+                connector.set_keylog_callback(move |_, line| {
+                    let line = format!("{}\n", line);
+                    handle.write_log_line(line);
+                });
+            }
+            None => {}
         }
 
         // Create the `HandshakeSettings` with the default session cache capacity.
@@ -368,9 +494,17 @@ impl TlsConnectorBuilder {
             .random_aes_hw_override(config.random_aes_hw_override)
             .build();
 
-        Ok(TlsConnector::with_connector_and_settings(
-            connector, settings,
-        ))
+        let mut connector = TlsConnector::with_connector_and_settings(
+            connector,
+            settings,
+            self.session_cache,
+            self.cert_pins,
+        );
+        connector.inner.cert_verifier = self.cert_verifier;
+        connector.inner.ocsp_policy = self.ocsp_policy;
+        connector.inner.require_sct = self.require_sct;
+        connector.inner.handshake_timeout = self.handshake_timeout;
+        Ok(connector)
     }
 }
 
@@ -387,22 +521,27 @@ impl Inner {
     where
         A: Read + Write + Unpin + Send + Sync + Debug + 'static,
     {
-        let mut conf = self.ssl.configure()?;
+        // The per-connection callback set on the `HttpsConnector` always applies,
+        // whichever host's connector is used.
+        let ssl_callback = self.ssl_callback.as_ref();
+        let this = crate::tls::lookup_host(&self.hosts, host).unwrap_or(self);
+
+        let mut conf = this.ssl.configure()?;
 
-        if let Some(ref callback) = self.callback {
+        if let Some(ref callback) = this.callback {
             callback(&mut conf, uri)?;
         }
 
         if let Some(authority) = uri.authority() {
             let key = SessionKey(authority.clone());
 
-            if let Some(ref cache) = self.cache {
+            if let Some(ref cache) = this.cache {
                 if let Some(session) = cache.lock().get(&key) {
                     unsafe {
                         conf.set_session(&session)?;
                     }
 
-                    if self.skip_session_ticket {
+                    if this.skip_session_ticket {
                         conf.skip_session_ticket()?;
                     }
                 }
@@ -414,14 +553,60 @@ impl Inner {
 
         let mut ssl = conf.into_ssl(host)?;
 
-        if let Some(ref ssl_callback) = self.ssl_callback {
+        // Pins and OCSP responses are checked against the chain verification
+        // builds, never against certificates the server merely presented.
+        let verified = VerifiedChain::default();
+        let check_chain = !this.cert_pins.is_empty() || this.ocsp_policy.is_some();
+        if check_chain {
+            verified.record(&mut ssl);
+        }
+
+        if let Some(ssl_callback) = ssl_callback {
             ssl_callback(&mut ssl, uri)?;
         }
 
-        tokio_boring2::SslStreamBuilder::new(ssl, TokioIo::new(conn))
-            .connect()
-            .await
-            .map_err(Into::into)
+        let handshake = tokio_boring2::SslStreamBuilder::new(ssl, TokioIo::new(conn)).connect();
+        #[cfg(feature = "tracing")]
+        let handshake = tracing::Instrument::instrument(
+            handshake,
+            tracing::debug_span!("tls_handshake", server.address = host),
+        );
+        let stream = match self.handshake_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, handshake)
+                .await
+                .map_err(|_| crate::error::TimedOut)?
+                .map_err(crate::error::TlsHandshakeError::new)?,
+            None => handshake
+                .await
+                .map_err(crate::error::TlsHandshakeError::new)?,
+        };
+
+        // A custom verifier makes the trust decision the handshake skipped.
+        if let Some(ref verifier) = this.cert_verifier {
+            verifier.verify(host, stream.ssl())?;
+        }
+
+        let chain = if check_chain {
+            verified.certs(stream.ssl())
+        } else {
+            Vec::new()
+        };
+
+        // Pins are checked before the connection is handed out, so nothing is
+        // ever sent to a server whose keys don't match.
+        if !this.cert_pins.is_empty() {
+            this.cert_pins.verify(host, &chain)?;
+        }
+
+        if let Some(policy) = this.ocsp_policy {
+            crate::tls::ocsp::check(policy, stream.ssl(), &chain)?;
+        }
+
+        if this.require_sct {
+            crate::tls::sct::check(stream.ssl())?;
+        }
+
+        Ok(stream)
     }
 }
 
@@ -485,3 +670,133 @@ where
         Box::pin(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tls::{Certificate, PinMismatch, Sha256Pin};
+    use boring2::asn1::Asn1Time;
+    use boring2::bn::BigNum;
+    use boring2::ec::{EcGroup, EcKey};
+    use boring2::hash::MessageDigest;
+    use boring2::nid::Nid;
+    use boring2::pkey::{PKey, Private};
+    use boring2::ssl::SslAcceptor;
+    use boring2::x509::extension::{BasicConstraints, KeyUsage, SubjectAlternativeName};
+    use boring2::x509::{X509, X509Builder, X509NameBuilder};
+    use tokio::net::{TcpListener, TcpStream};
+
+    type Issued = (X509, PKey<Private>);
+
+    /// Issue a certificate for `name`, or a self-signed CA certificate if there
+    /// is no `issuer`.
+    fn issue(name: &str, issuer: Option<&Issued>) -> Issued {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+
+        let mut subject = X509NameBuilder::new().unwrap();
+        subject.append_entry_by_text("CN", name).unwrap();
+        let subject = subject.build();
+
+        let mut cert = X509Builder::new().unwrap();
+        let serial = BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap();
+        cert.set_version(2).unwrap();
+        cert.set_serial_number(&serial).unwrap();
+        cert.set_subject_name(&subject).unwrap();
+        cert.set_pubkey(&key).unwrap();
+        cert.set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        cert.set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+
+        match issuer {
+            Some((issuer_cert, issuer_key)) => {
+                let san = SubjectAlternativeName::new()
+                    .dns(name)
+                    .build(&cert.x509v3_context(Some(issuer_cert), None))
+                    .unwrap();
+                cert.set_issuer_name(issuer_cert.subject_name()).unwrap();
+                cert.append_extension(san).unwrap();
+                cert.sign(issuer_key, MessageDigest::sha256()).unwrap();
+            }
+            None => {
+                let constraints = BasicConstraints::new().critical().ca().build().unwrap();
+                let usage = KeyUsage::new().critical().key_cert_sign().build().unwrap();
+                cert.set_issuer_name(&subject).unwrap();
+                cert.append_extension(constraints).unwrap();
+                cert.append_extension(usage).unwrap();
+                cert.sign(&key, MessageDigest::sha256()).unwrap();
+            }
+        }
+
+        (cert.build(), key)
+    }
+
+    fn pin(cert: &X509) -> Sha256Pin {
+        Sha256Pin::from_spki_der(&cert.public_key().unwrap().public_key_to_der().unwrap())
+    }
+
+    /// Accept one TLS connection presenting `leaf`, followed by `extra`.
+    async fn serve(leaf: Issued, extra: Vec<X509>) -> u16 {
+        let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        acceptor.set_certificate(&leaf.0).unwrap();
+        acceptor.set_private_key(&leaf.1).unwrap();
+        for cert in extra {
+            acceptor.add_extra_chain_cert(cert).unwrap();
+        }
+        let acceptor = acceptor.build();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let _ = tokio_boring2::accept(&acceptor, stream).await;
+        });
+        port
+    }
+
+    async fn connect(connector: TlsConnector, port: u16) -> Result<(), BoxError> {
+        let uri = format!("https://localhost:{}/", port).parse::<Uri>()?;
+        let tcp = TcpStream::connect(("127.0.0.1", port)).await?;
+        connector
+            .inner
+            .connect(&uri, "localhost", TokioIo::new(tcp))
+            .await
+            .map(drop)
+    }
+
+    fn pinned_connector(ca: &X509, pins: Sha256Pin) -> TlsConnector {
+        let mut cert_pins = CertPins::default();
+        cert_pins.add("localhost", [pins]);
+        TlsConnector::builder(TlsConfig::default())
+            .cert_store(CertStore::from_der_certs([Certificate(ca.clone())]).unwrap())
+            .cert_pins(cert_pins)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn pins_match_verified_chain() {
+        let ca = issue("Test CA", None);
+        let leaf = issue("localhost", Some(&ca));
+
+        let port = serve(leaf, Vec::new()).await;
+        connect(pinned_connector(&ca.0, pin(&ca.0)), port)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn pins_ignore_presented_certificates() {
+        let ca = issue("Test CA", None);
+        let leaf = issue("localhost", Some(&ca));
+        // a pinned certificate the server has no key for, appended to its chain
+        let pinned = issue("Pinned CA", None);
+
+        let port = serve(leaf, vec![pinned.0.clone()]).await;
+        let err = connect(pinned_connector(&ca.0, pin(&pinned.0)), port)
+            .await
+            .unwrap_err();
+        assert!(err.is::<PinMismatch>(), "{}", err);
+    }
+}
@@ -1,11 +1,18 @@
 /// backport: https://github.com/cloudflare/boring/blob/master/hyper-boring/src/cache.rs
+use antidote::Mutex;
 use boring2::ssl::SslVersion;
 use boring2::ssl::{SslSession, SslSessionRef};
 use http::uri::Authority;
 use linked_hash_set::LinkedHashSet;
 use std::borrow::Borrow;
 use std::collections::hash_map::{Entry, HashMap};
+use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Version of the format written by [`TlsSessionCache::export`].
+const EXPORT_VERSION: u8 = 1;
 
 #[derive(Hash, PartialEq, Eq, Clone)]
 pub struct SessionKey(pub Authority);
@@ -98,3 +105,174 @@ impl SessionCache {
         }
     }
 }
+
+/// A cache of TLS sessions that can be saved and restored.
+///
+/// A `Client` keeps the sessions it establishes so later connections to the same
+/// host can resume them, which is faster and, for emulation profiles that send a
+/// pre-shared key, matches the ClientHello of a returning browser. Sessions are
+/// only kept in memory, so they are lost when the process exits.
+///
+/// [`export`](TlsSessionCache::export) serializes the sessions, and
+/// [`import`](TlsSessionCache::import) restores them in a new process to be
+/// given to [`ClientBuilder::tls_session_cache`](crate::ClientBuilder::tls_session_cache).
+///
+/// Clones of a `TlsSessionCache` share their sessions, and one cache can be used
+/// by several clients.
+///
+/// # Security
+///
+/// An exported cache contains session secrets. Anyone who can read it can resume
+/// the sessions and decrypt traffic recorded from them, so it should be stored as
+/// carefully as a private key.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// use rquest::tls::TlsSessionCache;
+///
+/// let cache = match std::fs::read("sessions.bin") {
+///     Ok(saved) => TlsSessionCache::import(&saved)?,
+///     Err(_) => TlsSessionCache::new(),
+/// };
+///
+/// let client = rquest::Client::builder()
+///     .tls_session_cache(cache)
+///     .build()?;
+/// client.get("https://example.com").send().await?;
+///
+/// std::fs::write("sessions.bin", client.export_tls_sessions())?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct TlsSessionCache(pub(crate) Arc<Mutex<SessionCache>>);
+
+impl TlsSessionCache {
+    /// Creates an empty cache.
+    pub fn new() -> TlsSessionCache {
+        TlsSessionCache::with_capacity(8)
+    }
+
+    /// Creates an empty cache keeping up to `capacity` sessions per host.
+    pub(crate) fn with_capacity(capacity: usize) -> TlsSessionCache {
+        TlsSessionCache(Arc::new(Mutex::new(SessionCache::with_capacity(capacity))))
+    }
+
+    /// Returns the number of cached sessions.
+    pub fn len(&self) -> usize {
+        self.0.lock().reverse.len()
+    }
+
+    /// Returns true if no session is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Serializes the cached sessions.
+    pub fn export(&self) -> Vec<u8> {
+        let cache = self.0.lock();
+        let mut buf = vec![EXPORT_VERSION];
+
+        for (key, sessions) in &cache.sessions {
+            let authority = key.0.as_str().as_bytes();
+            for session in sessions {
+                let Ok(der) = session.0.to_der() else {
+                    continue;
+                };
+                buf.extend_from_slice(&(authority.len() as u16).to_be_bytes());
+                buf.extend_from_slice(authority);
+                buf.extend_from_slice(&(der.len() as u32).to_be_bytes());
+                buf.extend_from_slice(&der);
+            }
+        }
+
+        buf
+    }
+
+    /// Restores sessions serialized by [`export`](TlsSessionCache::export).
+    ///
+    /// Sessions that have expired since they were exported are left out.
+    pub fn import(data: &[u8]) -> crate::Result<TlsSessionCache> {
+        fn invalid() -> crate::Error {
+            crate::error::builder("invalid TLS session cache data")
+        }
+
+        fn take<'a>(data: &mut &'a [u8], n: usize) -> crate::Result<&'a [u8]> {
+            let (head, rest) = data.split_at_checked(n).ok_or_else(invalid)?;
+            *data = rest;
+            Ok(head)
+        }
+
+        let (&version, mut data) = data.split_first().ok_or_else(invalid)?;
+        if version != EXPORT_VERSION {
+            return Err(crate::error::builder(
+                "unsupported TLS session cache data version",
+            ));
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+
+        let cache = TlsSessionCache::new();
+        {
+            let mut sessions = cache.0.lock();
+            while !data.is_empty() {
+                let len =
+                    u16::from_be_bytes(take(&mut data, 2)?.try_into().map_err(|_| invalid())?);
+                let authority = Authority::try_from(take(&mut data, len.into())?)
+                    .map_err(crate::error::builder)?;
+                let len =
+                    u32::from_be_bytes(take(&mut data, 4)?.try_into().map_err(|_| invalid())?);
+                let session = SslSession::from_der(take(&mut data, len as usize)?)?;
+
+                if session.time() + u64::from(session.timeout()) > now {
+                    sessions.insert(SessionKey(authority), session);
+                }
+            }
+        }
+
+        Ok(cache)
+    }
+}
+
+impl Default for TlsSessionCache {
+    fn default() -> TlsSessionCache {
+        TlsSessionCache::new()
+    }
+}
+
+impl fmt::Debug for TlsSessionCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsSessionCache")
+            .field("sessions", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_cache_round_trip() {
+        let cache = TlsSessionCache::new();
+        assert!(cache.is_empty());
+
+        let data = cache.export();
+        assert_eq!(data, [EXPORT_VERSION]);
+        assert!(TlsSessionCache::import(&data).unwrap().is_empty());
+    }
+
+    #[test]
+    fn import_rejects_invalid_data() {
+        assert!(TlsSessionCache::import(b"").is_err());
+        assert!(TlsSessionCache::import(&[EXPORT_VERSION + 1]).is_err());
+        // truncated entry
+        assert!(TlsSessionCache::import(&[EXPORT_VERSION, 0, 11, b'e']).is_err());
+        // not a session
+        assert!(TlsSessionCache::import(&[EXPORT_VERSION, 0, 1, b'e', 0, 0, 0, 1, 0]).is_err());
+    }
+}
@@ -21,6 +21,7 @@ use tokio::io;
 use tokio_boring2::SslStream;
 
 pub use self::boring::{HttpsConnector, TlsConnector};
+pub use self::cache::TlsSessionCache;
 
 fn key_index() -> Result<Index<Ssl, SessionKey>, ErrorStack> {
     static IDX: LazyLock<Result<Index<Ssl, SessionKey>, ErrorStack>> =
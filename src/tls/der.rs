@@ -0,0 +1,119 @@
+//! A minimal DER reader, for the parts of certificates and OCSP responses that
+//! BoringSSL doesn't expose.
+
+pub(crate) const TAG_BOOLEAN: u8 = 0x01;
+pub(crate) const TAG_INTEGER: u8 = 0x02;
+pub(crate) const TAG_BIT_STRING: u8 = 0x03;
+pub(crate) const TAG_OCTET_STRING: u8 = 0x04;
+pub(crate) const TAG_OID: u8 = 0x06;
+pub(crate) const TAG_ENUMERATED: u8 = 0x0a;
+pub(crate) const TAG_GENERALIZED_TIME: u8 = 0x18;
+pub(crate) const TAG_SEQUENCE: u8 = 0x30;
+
+/// The remaining elements of a DER encoding.
+#[derive(Clone, Copy)]
+pub(crate) struct Der<'a>(pub(crate) &'a [u8]);
+
+impl<'a> Der<'a> {
+    /// Read the next element, returning its tag and contents.
+    pub(crate) fn read(&mut self) -> Option<(u8, Der<'a>)> {
+        let (tag, _, contents) = self.read_element()?;
+        Some((tag, Der(contents)))
+    }
+
+    /// Read the next element, returning its tag and full encoding.
+    pub(crate) fn read_raw(&mut self) -> Option<(u8, &'a [u8])> {
+        let start = self.0;
+        let (tag, len, _) = self.read_element()?;
+        Some((tag, &start[..len]))
+    }
+
+    fn read_element(&mut self) -> Option<(u8, usize, &'a [u8])> {
+        let (&tag, rest) = self.0.split_first()?;
+        let (&first, mut rest) = rest.split_first()?;
+
+        let len = if first < 0x80 {
+            usize::from(first)
+        } else {
+            let n = usize::from(first & 0x7f);
+            if n == 0 || n > 4 {
+                return None;
+            }
+            let (bytes, after) = rest.split_at_checked(n)?;
+            rest = after;
+            bytes.iter().fold(0, |len, &b| len << 8 | usize::from(b))
+        };
+
+        let header = self.0.len() - rest.len();
+        let (contents, after) = rest.split_at_checked(len)?;
+        self.0 = after;
+        Some((tag, header + len, contents))
+    }
+
+    /// Read the next element, which must have the given tag.
+    pub(crate) fn expect(&mut self, tag: u8) -> Option<Der<'a>> {
+        match self.read()? {
+            (t, contents) if t == tag => Some(contents),
+            _ => None,
+        }
+    }
+
+    /// Read the next element if it has the given tag.
+    pub(crate) fn optional(&mut self, tag: u8) -> Option<Der<'a>> {
+        if self.0.first() == Some(&tag) {
+            self.expect(tag)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// The value of the extension `oid` of a DER encoded certificate.
+pub(crate) fn certificate_extension<'a>(cert: &'a [u8], oid: &[u8]) -> Option<&'a [u8]> {
+    let mut tbs = Der(cert).expect(TAG_SEQUENCE)?.expect(TAG_SEQUENCE)?;
+    tbs.optional(0xa0);
+    // serial, signature, issuer, validity, subject, public key
+    for _ in 0..6 {
+        tbs.read()?;
+    }
+    tbs.optional(0xa1);
+    tbs.optional(0xa2);
+
+    let mut extensions = tbs.expect(0xa3)?.expect(TAG_SEQUENCE)?;
+    while !extensions.is_empty() {
+        let mut extension = extensions.expect(TAG_SEQUENCE)?;
+        if extension.expect(TAG_OID)?.0 == oid {
+            extension.optional(TAG_BOOLEAN);
+            return Some(extension.expect(TAG_OCTET_STRING)?.0);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_elements() {
+        // SEQUENCE { INTEGER 5, OCTET STRING (200 bytes) }
+        let mut der = vec![0x30, 0x81, 0xcd, 0x02, 0x01, 0x05, 0x04, 0x81, 0xc8];
+        der.extend_from_slice(&[0xab; 200]);
+
+        let mut seq = Der(&der).expect(TAG_SEQUENCE).unwrap();
+        assert_eq!(seq.expect(TAG_INTEGER).unwrap().0, [5]);
+        assert!(seq.optional(TAG_BOOLEAN).is_none());
+        assert_eq!(seq.expect(TAG_OCTET_STRING).unwrap().0, [0xab; 200]);
+        assert!(seq.is_empty());
+
+        let mut raw = Der(&der);
+        assert_eq!(raw.read_raw().unwrap(), (TAG_SEQUENCE, &der[..]));
+
+        // truncated
+        assert!(Der(&der[..20]).expect(TAG_SEQUENCE).is_none());
+    }
+}
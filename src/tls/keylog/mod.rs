@@ -5,16 +5,20 @@ pub use handle::KeyLogHandle;
 use std::{
     borrow::Cow,
     collections::{HashMap, hash_map::Entry},
+    fmt,
     io::{Error, ErrorKind, Result},
     path::{Component, Path, PathBuf},
-    sync::OnceLock,
+    sync::{Arc, OnceLock},
 };
 
 static GLOBAL_KEYLOG_FILE_MAPPING: OnceLock<RwLock<HashMap<PathBuf, KeyLogHandle>>> =
     OnceLock::new();
 
 /// Specifies the intent for a (TLS) keylogger to be used in a client or server configuration.
-#[derive(Debug, Clone)]
+///
+/// Keys are logged in the NSS key log format, which Wireshark can use to decrypt
+/// captured traffic.
+#[derive(Clone)]
 pub enum KeyLogPolicy {
     /// Uses the default behavior, respecting the `SSLKEYLOGFILE` environment variable.
     ///
@@ -28,10 +32,25 @@ pub enum KeyLogPolicy {
     /// manipulated and queried. This is useful for operations that require reading from or
     /// writing to the file system.
     File(PathBuf),
+
+    /// Passes each key log line, without a trailing newline, to a callback.
+    ///
+    /// See [`KeyLogPolicy::callback`].
+    Callback(Arc<dyn Fn(&str) + Send + Sync>),
 }
 
 impl KeyLogPolicy {
+    /// Creates a policy that passes each key log line to `callback`.
+    pub fn callback<F>(callback: F) -> KeyLogPolicy
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        KeyLogPolicy::Callback(Arc::new(callback))
+    }
+
     /// Creates a new key log file handle based on the policy.
+    ///
+    /// Fails for [`KeyLogPolicy::Callback`], which doesn't log to a file.
     pub fn open_handle(self) -> Result<KeyLogHandle> {
         let path = match self {
             KeyLogPolicy::Environment => std::env::var("SSLKEYLOGFILE")
@@ -47,6 +66,12 @@ impl KeyLogPolicy {
                     )
                 })?,
             KeyLogPolicy::File(keylog_filename) => normalize_path(keylog_filename),
+            KeyLogPolicy::Callback(_) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "KeyLogPolicy: callback policy has no key log file",
+                ));
+            }
         };
 
         let mapping = GLOBAL_KEYLOG_FILE_MAPPING.get_or_init(|| RwLock::new(HashMap::new()));
@@ -66,6 +91,16 @@ impl KeyLogPolicy {
     }
 }
 
+impl fmt::Debug for KeyLogPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyLogPolicy::Environment => f.write_str("Environment"),
+            KeyLogPolicy::File(path) => f.debug_tuple("File").field(path).finish(),
+            KeyLogPolicy::Callback(_) => f.write_str("Callback"),
+        }
+    }
+}
+
 pub fn normalize_path<'a, P>(path: P) -> PathBuf
 where
     P: Into<Cow<'a, Path>>,
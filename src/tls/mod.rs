@@ -7,16 +7,50 @@
 
 mod config;
 mod conn;
+mod der;
 mod keylog;
+#[cfg(feature = "native-trust")]
+pub(crate) mod native;
+mod ocsp;
+mod pin;
+mod sct;
+mod verify;
 mod x509;
 
+pub use self::conn::TlsSessionCache;
 pub(crate) use self::conn::{HttpsConnector, MaybeHttpsStream, TlsConnector};
+pub(crate) use self::ocsp::CertificateRevoked;
+pub(crate) use self::pin::{CertPins, PinMismatch};
+pub(crate) use self::verify::{CertVerifier, VerifiedChain};
 pub use self::{
-    config::TlsConfig,
+    config::{PqKeyShare, TlsConfig, TlsConfigBuilder, TlsOptions},
     keylog::KeyLogPolicy,
+    ocsp::OcspPolicy,
+    pin::Sha256Pin,
+    sct::SignedCertificateTimestamp,
+    verify::CertChain,
     x509::{CertStore, CertStoreBuilder, Certificate, CertificateInput, Identity},
 };
 pub use boring2::ssl::{CertCompressionAlgorithm, ExtensionType};
+use std::collections::HashMap;
+
+/// Look up the entry for `host`, falling back to a `*.` wildcard entry of one of
+/// its parent domains. Keys are expected in lowercase.
+pub(crate) fn lookup_host<'a, T>(map: &'a HashMap<String, T>, host: &str) -> Option<&'a T> {
+    let host = host.to_ascii_lowercase();
+    if let Some(value) = map.get(&host) {
+        return Some(value);
+    }
+
+    let mut parent = host.as_str();
+    while let Some((_, rest)) = parent.split_once('.') {
+        if let Some(value) = map.get(&format!("*.{}", rest)) {
+            return Some(value);
+        }
+        parent = rest;
+    }
+    None
+}
 
 /// A TLS protocol version.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -70,12 +104,76 @@ impl AlpsProtos {
 /// Made available to clients on responses when `tls_info` is set.
 #[derive(Debug, Clone)]
 pub struct TlsInfo {
+    pub(crate) version: Option<TlsVersion>,
+    pub(crate) cipher: Option<&'static str>,
+    pub(crate) alpn_protocol: Option<Vec<u8>>,
+    pub(crate) session_resumed: bool,
     pub(crate) peer_certificate: Option<Vec<u8>>,
+    pub(crate) peer_cert_chain: Vec<Vec<u8>>,
+    pub(crate) signed_certificate_timestamps: Vec<SignedCertificateTimestamp>,
 }
 
 impl TlsInfo {
+    /// Collect the information of an established connection.
+    pub(crate) fn from_ssl(ssl: &boring2::ssl::SslRef) -> TlsInfo {
+        let peer_certificate = ssl.peer_certificate().and_then(|cert| cert.to_der().ok());
+        let peer_cert_chain = ssl
+            .peer_cert_chain()
+            .into_iter()
+            .flatten()
+            .filter_map(|cert| cert.to_der().ok())
+            .collect();
+
+        TlsInfo {
+            version: ssl.version2().map(TlsVersion),
+            cipher: ssl
+                .current_cipher()
+                .map(|cipher| cipher.standard_name().unwrap_or_else(|| cipher.name())),
+            alpn_protocol: ssl.selected_alpn_protocol().map(ToOwned::to_owned),
+            session_resumed: ssl.session_reused(),
+            signed_certificate_timestamps: peer_certificate
+                .as_deref()
+                .map(sct::embedded)
+                .unwrap_or_default(),
+            peer_certificate,
+            peer_cert_chain,
+        }
+    }
+
+    /// Get the negotiated TLS protocol version.
+    pub fn version(&self) -> Option<TlsVersion> {
+        self.version
+    }
+
+    /// Get the standard name of the negotiated cipher suite, such as
+    /// `TLS_AES_128_GCM_SHA256`.
+    pub fn cipher(&self) -> Option<&str> {
+        self.cipher
+    }
+
+    /// Get the protocol negotiated with ALPN, such as `b"h2"`.
+    pub fn alpn_protocol(&self) -> Option<&[u8]> {
+        self.alpn_protocol.as_deref()
+    }
+
+    /// Returns true if the connection resumed a previous TLS session.
+    pub fn session_resumed(&self) -> bool {
+        self.session_resumed
+    }
+
     /// Get the DER encoded leaf certificate of the peer.
     pub fn peer_certificate(&self) -> Option<&[u8]> {
         self.peer_certificate.as_ref().map(|der| &der[..])
     }
+
+    /// Get the DER encoded certificate chain sent by the peer, starting with its
+    /// leaf certificate.
+    pub fn peer_cert_chain(&self) -> impl Iterator<Item = &[u8]> {
+        self.peer_cert_chain.iter().map(|der| &der[..])
+    }
+
+    /// Get the signed certificate timestamps embedded in the peer's leaf certificate.
+    pub fn signed_certificate_timestamps(&self) -> &[SignedCertificateTimestamp] {
+        &self.signed_certificate_timestamps
+    }
 }
@@ -0,0 +1,156 @@
+//! Certificate verification by the operating system.
+//!
+//! On macOS the chain is evaluated by Security.framework, and on Windows by
+//! CryptoAPI, so that enterprise-deployed roots and intermediates, and trust
+//! policies, apply exactly as they do for the system browsers. Other platforms
+//! have no system verifier, and keep the built-in verification.
+
+use super::{CertChain, CertVerifier};
+use crate::error::BoxError;
+
+pub(crate) fn verifier() -> CertVerifier {
+    CertVerifier::new(verify)
+}
+
+#[cfg(target_os = "macos")]
+fn verify(chain: &CertChain, host: &str) -> Result<(), BoxError> {
+    use security_framework::certificate::SecCertificate;
+    use security_framework::policy::SecPolicy;
+    use security_framework::secure_transport::SslProtocolSide;
+    use security_framework::trust::SecTrust;
+
+    let certs = chain
+        .iter()
+        .map(SecCertificate::from_der)
+        .collect::<Result<Vec<_>, _>>()?;
+    let policy = SecPolicy::create_ssl(SslProtocolSide::CLIENT, Some(host));
+    let trust = SecTrust::create_with_certificates(&certs, &[policy])?;
+
+    trust
+        .evaluate_with_error()
+        .map_err(|err| format!("system verifier rejected certificate: {}", err).into())
+}
+
+#[cfg(windows)]
+fn verify(chain: &CertChain, host: &str) -> Result<(), BoxError> {
+    use std::{io, mem, ptr};
+    use windows_sys::Win32::Security::Cryptography::*;
+
+    struct Store(HCERTSTORE);
+
+    impl Drop for Store {
+        fn drop(&mut self) {
+            unsafe { CertCloseStore(self.0, 0) };
+        }
+    }
+
+    struct Cert(*mut CERT_CONTEXT);
+
+    impl Drop for Cert {
+        fn drop(&mut self) {
+            unsafe { CertFreeCertificateContext(self.0) };
+        }
+    }
+
+    struct Chain(*mut CERT_CHAIN_CONTEXT);
+
+    impl Drop for Chain {
+        fn drop(&mut self) {
+            unsafe { CertFreeCertificateChain(self.0) };
+        }
+    }
+
+    let mut server_name = host.encode_utf16().chain(Some(0)).collect::<Vec<u16>>();
+
+    unsafe {
+        // The intermediates sent by the server, to build the chain with.
+        let store = CertOpenStore(CERT_STORE_PROV_MEMORY, 0, 0, 0, ptr::null());
+        if store.is_null() {
+            return Err(io::Error::last_os_error().into());
+        }
+        let store = Store(store);
+
+        let mut leaf = Cert(ptr::null_mut());
+        for (index, der) in chain.iter().enumerate() {
+            let context = if index == 0 {
+                &mut leaf.0 as *mut _
+            } else {
+                ptr::null_mut()
+            };
+            let added = CertAddEncodedCertificateToStore(
+                store.0,
+                X509_ASN_ENCODING | PKCS_7_ASN_ENCODING,
+                der.as_ptr(),
+                der.len() as u32,
+                CERT_STORE_ADD_ALWAYS,
+                context,
+            );
+            if added == 0 {
+                return Err(io::Error::last_os_error().into());
+            }
+        }
+        if leaf.0.is_null() {
+            return Err("server sent no certificate".into());
+        }
+
+        let mut server_auth = [szOID_PKIX_KP_SERVER_AUTH as *mut u8];
+        let mut chain_para: CERT_CHAIN_PARA = mem::zeroed();
+        chain_para.cbSize = mem::size_of::<CERT_CHAIN_PARA>() as u32;
+        chain_para.RequestedUsage.dwType = USAGE_MATCH_TYPE_AND;
+        chain_para.RequestedUsage.Usage.cUsageIdentifier = 1;
+        chain_para.RequestedUsage.Usage.rgpszUsageIdentifier = server_auth.as_mut_ptr();
+
+        let mut context = ptr::null_mut();
+        let built = CertGetCertificateChain(
+            ptr::null_mut(),
+            leaf.0,
+            ptr::null(),
+            store.0,
+            &chain_para,
+            CERT_CHAIN_REVOCATION_CHECK_CHAIN_EXCLUDE_ROOT,
+            ptr::null(),
+            &mut context,
+        );
+        if built == 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        let context = Chain(context);
+
+        let mut extra: HTTPSPolicyCallbackData = mem::zeroed();
+        extra.Anonymous.cbSize = mem::size_of::<HTTPSPolicyCallbackData>() as u32;
+        extra.dwAuthType = AUTHTYPE_SERVER;
+        extra.pwszServerName = server_name.as_mut_ptr();
+
+        let mut policy_para: CERT_CHAIN_POLICY_PARA = mem::zeroed();
+        policy_para.cbSize = mem::size_of::<CERT_CHAIN_POLICY_PARA>() as u32;
+        policy_para.pvExtraPolicyPara = &mut extra as *mut _ as *mut _;
+
+        let mut status: CERT_CHAIN_POLICY_STATUS = mem::zeroed();
+        status.cbSize = mem::size_of::<CERT_CHAIN_POLICY_STATUS>() as u32;
+
+        let checked = CertVerifyCertificateChainPolicy(
+            CERT_CHAIN_POLICY_SSL,
+            context.0,
+            &policy_para,
+            &mut status,
+        );
+        if checked == 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        if status.dwError != 0 {
+            let err = io::Error::from_raw_os_error(status.dwError as i32);
+            return Err(format!("system verifier rejected certificate: {}", err).into());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn verify(chain: &CertChain, _host: &str) -> Result<(), BoxError> {
+    if chain.is_trusted() {
+        Ok(())
+    } else {
+        Err("certificate verification failed".into())
+    }
+}
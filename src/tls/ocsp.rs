@@ -0,0 +1,369 @@
+//! Validation of stapled OCSP responses ([RFC 6960](https://www.rfc-editor.org/rfc/rfc6960))
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use boring2::hash::{MessageDigest, hash};
+use boring2::pkey::{PKeyRef, Public};
+use boring2::sign::Verifier;
+use boring2::ssl::SslRef;
+use boring2::x509::{X509, X509Ref};
+
+use super::der::{
+    Der, TAG_BIT_STRING, TAG_ENUMERATED, TAG_GENERALIZED_TIME, TAG_INTEGER, TAG_OCTET_STRING,
+    TAG_OID, TAG_SEQUENCE, certificate_extension,
+};
+use crate::error::BoxError;
+
+const OID_OCSP_BASIC: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x30, 0x01, 0x01];
+const OID_EXT_KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x25];
+const OID_OCSP_SIGNING: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x09];
+
+/// Allowed clock difference with the responder, in seconds.
+const CLOCK_SKEW: u64 = 5 * 60;
+
+/// How stapled OCSP responses are checked.
+///
+/// With either policy, the client asks servers to staple an OCSP response to the
+/// handshake. A stapled response is checked to be signed by the certificate's
+/// issuer, or by a responder the issuer delegated to, and to be current.
+///
+/// Certificate revocation lists are not fetched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OcspPolicy {
+    /// Reject certificates that a valid stapled response reports as revoked.
+    ///
+    /// Connections to servers that staple no response, or a response that can't
+    /// be validated, are allowed.
+    Soft,
+    /// Require a valid stapled response reporting the certificate as good.
+    Require,
+}
+
+/// Check the stapled OCSP response of a connection with the verified `chain`.
+pub(crate) fn check(policy: OcspPolicy, ssl: &SslRef, chain: &[X509]) -> Result<(), BoxError> {
+    let status = match ssl.ocsp_status() {
+        Some(staple) => status(chain, staple),
+        None => Err("server didn't staple an OCSP response".into()),
+    };
+
+    match (status, policy) {
+        (Ok(CertStatus::Good), _) => Ok(()),
+        (Ok(CertStatus::Revoked), _) => Err(Box::new(CertificateRevoked)),
+        (Ok(CertStatus::Unknown), OcspPolicy::Require) => {
+            Err("OCSP responder doesn't know the certificate".into())
+        }
+        (Err(err), OcspPolicy::Require) => Err(err),
+        (_, OcspPolicy::Soft) => Ok(()),
+    }
+}
+
+/// The server's certificate was revoked by its issuer.
+#[derive(Debug)]
+pub(crate) struct CertificateRevoked;
+
+impl fmt::Display for CertificateRevoked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("server certificate has been revoked")
+    }
+}
+
+impl std::error::Error for CertificateRevoked {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CertStatus {
+    Good,
+    Revoked,
+    Unknown,
+}
+
+/// The status of the server's certificate in a stapled response.
+///
+/// The issuer is taken from the verified chain: one the server merely presented
+/// could be forged with the right name to vouch for its own response.
+fn status(chain: &[X509], staple: &[u8]) -> Result<CertStatus, BoxError> {
+    let [leaf, issuer, ..] = chain else {
+        return Err("no verified issuer certificate".into());
+    };
+
+    let response = BasicResponse::parse(staple).ok_or("malformed OCSP response")?;
+    response.verify_signer(issuer)?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let serial = leaf.serial_number().to_bn()?.to_vec();
+
+    for single in &response.responses {
+        if !single.matches(&serial, issuer)? {
+            continue;
+        }
+        if single.this_update > now + CLOCK_SKEW {
+            return Err("OCSP response is not yet valid".into());
+        }
+        if single
+            .next_update
+            .is_some_and(|next| next + CLOCK_SKEW < now)
+        {
+            return Err("OCSP response has expired".into());
+        }
+        return Ok(single.status);
+    }
+
+    Err("OCSP response doesn't cover the server certificate".into())
+}
+
+struct BasicResponse<'a> {
+    tbs: &'a [u8],
+    signature_algorithm: &'a [u8],
+    signature: &'a [u8],
+    certs: Vec<&'a [u8]>,
+    responses: Vec<SingleResponse<'a>>,
+}
+
+struct SingleResponse<'a> {
+    hash_algorithm: &'a [u8],
+    issuer_name_hash: &'a [u8],
+    issuer_key_hash: &'a [u8],
+    serial: &'a [u8],
+    status: CertStatus,
+    this_update: u64,
+    next_update: Option<u64>,
+}
+
+impl<'a> BasicResponse<'a> {
+    fn parse(der: &'a [u8]) -> Option<BasicResponse<'a>> {
+        let mut response = Der(der).expect(TAG_SEQUENCE)?;
+        // only "successful" responses have a body
+        if response.expect(TAG_ENUMERATED)? != [0] {
+            return None;
+        }
+        let mut bytes = response.expect(0xa0)?.expect(TAG_SEQUENCE)?;
+        if bytes.expect(TAG_OID)?.0 != OID_OCSP_BASIC {
+            return None;
+        }
+
+        let mut basic = Der(bytes.expect(TAG_OCTET_STRING)?.0).expect(TAG_SEQUENCE)?;
+        let (_, tbs) = basic.read_raw()?;
+        let signature_algorithm = basic.expect(TAG_SEQUENCE)?.expect(TAG_OID)?.0;
+        let signature = basic.expect(TAG_BIT_STRING)?.0.split_first()?.1;
+
+        let mut certs = Vec::new();
+        if let Some(mut list) = basic.optional(0xa0) {
+            let mut list = list.expect(TAG_SEQUENCE)?;
+            while !list.is_empty() {
+                certs.push(list.read_raw()?.1);
+            }
+        }
+
+        let mut data = Der(tbs).expect(TAG_SEQUENCE)?;
+        data.optional(0xa0);
+        data.read()?; // responder ID
+        data.expect(TAG_GENERALIZED_TIME)?;
+
+        let mut list = data.expect(TAG_SEQUENCE)?;
+        let mut responses = Vec::new();
+        while !list.is_empty() {
+            responses.push(SingleResponse::parse(list.expect(TAG_SEQUENCE)?)?);
+        }
+
+        Some(BasicResponse {
+            tbs,
+            signature_algorithm,
+            signature,
+            certs,
+            responses,
+        })
+    }
+
+    /// Check that the response is signed by `issuer` or by a responder it delegated to.
+    fn verify_signer(&self, issuer: &X509Ref) -> Result<(), BoxError> {
+        let issuer_key = issuer.public_key()?;
+        if self.verify_signature(&issuer_key)? {
+            return Ok(());
+        }
+
+        for der in &self.certs {
+            let responder = boring2::x509::X509::from_der(der)?;
+            if responder.verify(&issuer_key)?
+                && is_ocsp_signer(der)
+                && self.verify_signature(&responder.public_key()?)?
+            {
+                return Ok(());
+            }
+        }
+
+        Err("OCSP response is not signed by the certificate issuer".into())
+    }
+
+    fn verify_signature(&self, key: &PKeyRef<Public>) -> Result<bool, BoxError> {
+        let digest =
+            signature_digest(self.signature_algorithm).ok_or("unsupported OCSP signature")?;
+        let mut verifier = Verifier::new(digest, key)?;
+        verifier.update(self.tbs)?;
+        Ok(verifier.verify(self.signature).unwrap_or(false))
+    }
+}
+
+impl<'a> SingleResponse<'a> {
+    fn parse(mut der: Der<'a>) -> Option<SingleResponse<'a>> {
+        let mut cert_id = der.expect(TAG_SEQUENCE)?;
+        let hash_algorithm = cert_id.expect(TAG_SEQUENCE)?.expect(TAG_OID)?.0;
+        let issuer_name_hash = cert_id.expect(TAG_OCTET_STRING)?.0;
+        let issuer_key_hash = cert_id.expect(TAG_OCTET_STRING)?.0;
+        let serial = cert_id.expect(TAG_INTEGER)?.0;
+
+        let status = match der.read()?.0 {
+            0x80 => CertStatus::Good,
+            0xa1 => CertStatus::Revoked,
+            0x82 => CertStatus::Unknown,
+            _ => return None,
+        };
+        let this_update = parse_time(der.expect(TAG_GENERALIZED_TIME)?.0)?;
+        let next_update = match der.optional(0xa0) {
+            Some(mut next) => Some(parse_time(next.expect(TAG_GENERALIZED_TIME)?.0)?),
+            None => None,
+        };
+
+        Some(SingleResponse {
+            hash_algorithm,
+            issuer_name_hash,
+            issuer_key_hash,
+            serial,
+            status,
+            this_update,
+            next_update,
+        })
+    }
+
+    /// Whether this response is about the certificate `serial` issued by `issuer`.
+    fn matches(&self, serial: &[u8], issuer: &X509Ref) -> Result<bool, BoxError> {
+        let Some(digest) = cert_id_digest(self.hash_algorithm) else {
+            return Ok(false);
+        };
+        if strip_leading_zeros(self.serial) != strip_leading_zeros(serial) {
+            return Ok(false);
+        }
+
+        let name_hash = hash(digest, &issuer.subject_name().to_der()?)?;
+        let spki = issuer.public_key()?.public_key_to_der()?;
+        let key = subject_public_key(&spki).ok_or("malformed issuer public key")?;
+        let key_hash = hash(digest, key)?;
+
+        Ok(*name_hash == *self.issuer_name_hash && *key_hash == *self.issuer_key_hash)
+    }
+}
+
+/// Whether a certificate allows signing OCSP responses.
+fn is_ocsp_signer(cert: &[u8]) -> bool {
+    let Some(usages) = certificate_extension(cert, OID_EXT_KEY_USAGE) else {
+        return false;
+    };
+    let Some(mut usages) = Der(usages).expect(TAG_SEQUENCE) else {
+        return false;
+    };
+    while let Some(usage) = usages.expect(TAG_OID) {
+        if usage.0 == OID_OCSP_SIGNING {
+            return true;
+        }
+    }
+    false
+}
+
+/// The key bits of a DER encoded SubjectPublicKeyInfo.
+fn subject_public_key(spki: &[u8]) -> Option<&[u8]> {
+    let mut spki = Der(spki).expect(TAG_SEQUENCE)?;
+    spki.expect(TAG_SEQUENCE)?;
+    let (unused_bits, key) = spki.expect(TAG_BIT_STRING)?.0.split_first()?;
+    (*unused_bits == 0).then_some(key)
+}
+
+fn cert_id_digest(oid: &[u8]) -> Option<MessageDigest> {
+    match oid {
+        [0x2b, 0x0e, 0x03, 0x02, 0x1a] => Some(MessageDigest::sha1()),
+        [0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01] => Some(MessageDigest::sha256()),
+        [0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02] => Some(MessageDigest::sha384()),
+        [0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03] => Some(MessageDigest::sha512()),
+        _ => None,
+    }
+}
+
+fn signature_digest(oid: &[u8]) -> Option<MessageDigest> {
+    match oid {
+        // sha*WithRSAEncryption
+        [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x05] => Some(MessageDigest::sha1()),
+        [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b] => Some(MessageDigest::sha256()),
+        [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0c] => Some(MessageDigest::sha384()),
+        [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0d] => Some(MessageDigest::sha512()),
+        // ecdsa-with-SHA*
+        [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02] => Some(MessageDigest::sha256()),
+        [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03] => Some(MessageDigest::sha384()),
+        [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x04] => Some(MessageDigest::sha512()),
+        _ => None,
+    }
+}
+
+fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+/// Parse a `YYYYMMDDHHMMSSZ` GeneralizedTime into seconds since the Unix epoch.
+fn parse_time(time: &[u8]) -> Option<u64> {
+    let time = std::str::from_utf8(time).ok()?;
+    let time = time.strip_suffix('Z')?;
+    // fractional seconds are allowed, but not needed
+    let time = time.split('.').next()?;
+    if time.len() != 14 || !time.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let field = |range: std::ops::Range<usize>| time[range].parse::<u64>().ok();
+    let (year, month, day) = (field(0..4)?, field(4..6)?, field(6..8)?);
+    let (hour, minute, second) = (field(8..10)?, field(10..12)?, field(12..14)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || year < 1970 {
+        return None;
+    }
+
+    // days since the epoch, from Howard Hinnant's `days_from_civil`
+    let (y, m) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = y / 400;
+    let yoe = y % 400;
+    let doy = (153 * m + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    Some(days * 86400 + hour * 3600 + minute * 60 + second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_generalized_time() {
+        assert_eq!(parse_time(b"19700101000000Z"), Some(0));
+        assert_eq!(parse_time(b"20240229123045Z"), Some(1709209845));
+        assert_eq!(parse_time(b"20240229123045.123Z"), Some(1709209845));
+        assert_eq!(parse_time(b"20241301000000Z"), None);
+        assert_eq!(parse_time(b"20240101000000"), None);
+    }
+
+    #[test]
+    fn rejects_unsuccessful_responses() {
+        // OCSPResponse { responseStatus tryLater(3) }
+        assert!(BasicResponse::parse(&[0x30, 0x03, 0x0a, 0x01, 0x03]).is_none());
+        assert!(BasicResponse::parse(b"not der").is_none());
+    }
+
+    #[test]
+    fn strips_serial_zeros() {
+        assert_eq!(strip_leading_zeros(&[0, 0x80, 1]), [0x80, 1]);
+        assert_eq!(strip_leading_zeros(&[0x7f]), [0x7f]);
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use boring2::x509::X509;
+
+use super::Certificate;
+use crate::error::BoxError;
+
+/// A SHA-256 hash of a certificate's SubjectPublicKeyInfo, used to pin it.
+///
+/// The textual form is the one used by HPKP and most pinning tools:
+/// `sha256/` followed by the base64 encoded hash. It can be computed with
+///
+/// ```text
+/// openssl x509 -in cert.pem -pubkey -noout \
+///     | openssl pkey -pubin -outform der \
+///     | openssl dgst -sha256 -binary | base64
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sha256Pin([u8; 32]);
+
+impl Sha256Pin {
+    /// Create a pin from a raw SHA-256 hash.
+    pub fn new(hash: [u8; 32]) -> Sha256Pin {
+        Sha256Pin(hash)
+    }
+
+    /// Parse a base64 encoded hash, with or without the `sha256/` prefix.
+    pub fn from_base64(pin: &str) -> crate::Result<Sha256Pin> {
+        let pin = pin.strip_prefix("sha256/").unwrap_or(pin);
+        let hash = BASE64_STANDARD.decode(pin).map_err(crate::error::builder)?;
+        <[u8; 32]>::try_from(hash)
+            .map(Sha256Pin)
+            .map_err(|_| crate::error::builder("SHA-256 pin must be 32 bytes"))
+    }
+
+    /// Compute the pin of a DER encoded SubjectPublicKeyInfo.
+    pub fn from_spki_der(spki: &[u8]) -> Sha256Pin {
+        Sha256Pin(boring2::sha::sha256(spki))
+    }
+
+    /// Compute the pin of a certificate's public key.
+    pub fn from_certificate(cert: &Certificate) -> crate::Result<Sha256Pin> {
+        let spki = cert.0.public_key()?.public_key_to_der()?;
+        Ok(Sha256Pin::from_spki_der(&spki))
+    }
+}
+
+impl fmt::Display for Sha256Pin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sha256/{}", BASE64_STANDARD.encode(self.0))
+    }
+}
+
+impl fmt::Debug for Sha256Pin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// The pinned keys of each host.
+///
+/// A host given as `*.example.com` pins every subdomain of `example.com`. An exact
+/// host takes precedence over a wildcard.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CertPins(Arc<HashMap<String, Vec<Sha256Pin>>>);
+
+impl CertPins {
+    pub(crate) fn add<I>(&mut self, host: &str, pins: I)
+    where
+        I: IntoIterator<Item = Sha256Pin>,
+    {
+        Arc::make_mut(&mut self.0)
+            .entry(host.to_ascii_lowercase())
+            .or_default()
+            .extend(pins);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn get(&self, host: &str) -> Option<&[Sha256Pin]> {
+        super::lookup_host(&self.0, host).map(Vec::as_slice)
+    }
+
+    /// Check that a key of the verified `chain` of a connection to `host` is pinned.
+    pub(crate) fn verify(&self, host: &str, chain: &[X509]) -> Result<(), BoxError> {
+        let Some(pins) = self.get(host) else {
+            return Ok(());
+        };
+
+        let pinned = chain
+            .iter()
+            .filter_map(|cert| cert.public_key().ok()?.public_key_to_der().ok())
+            .any(|spki| pins.contains(&Sha256Pin::from_spki_der(&spki)));
+
+        if pinned {
+            Ok(())
+        } else {
+            Err(Box::new(PinMismatch {
+                host: host.to_owned(),
+            }))
+        }
+    }
+}
+
+/// The certificate chain of a host matched none of its pins.
+#[derive(Debug)]
+pub(crate) struct PinMismatch {
+    host: String,
+}
+
+impl fmt::Display for PinMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "certificate chain of {} matches no pinned key",
+            self.host
+        )
+    }
+}
+
+impl std::error::Error for PinMismatch {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pin_base64_round_trip() {
+        let pin = Sha256Pin::from_spki_der(b"spki");
+        let text = pin.to_string();
+        assert!(text.starts_with("sha256/"));
+
+        assert_eq!(Sha256Pin::from_base64(&text).unwrap(), pin);
+        assert_eq!(
+            Sha256Pin::from_base64(&text["sha256/".len()..]).unwrap(),
+            pin
+        );
+
+        assert!(Sha256Pin::from_base64("sha256/not base64").is_err());
+        assert!(Sha256Pin::from_base64("c2hvcnQ=").is_err());
+    }
+
+    #[test]
+    fn pins_match_hosts() {
+        let exact = Sha256Pin::new([1; 32]);
+        let wildcard = Sha256Pin::new([2; 32]);
+
+        let mut pins = CertPins::default();
+        pins.add("API.example.com", [exact]);
+        pins.add("*.example.com", [wildcard]);
+
+        assert_eq!(pins.get("api.example.com"), Some(&[exact][..]));
+        assert_eq!(pins.get("www.example.com"), Some(&[wildcard][..]));
+        assert_eq!(pins.get("a.b.example.com"), Some(&[wildcard][..]));
+        assert_eq!(pins.get("example.com"), None);
+        assert_eq!(pins.get("example.org"), None);
+    }
+}
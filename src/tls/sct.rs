@@ -0,0 +1,184 @@
+//! Signed certificate timestamps ([RFC 6962](https://www.rfc-editor.org/rfc/rfc6962))
+
+use std::collections::HashSet;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use boring2::ssl::SslRef;
+
+use super::der::{Der, TAG_OCTET_STRING, certificate_extension};
+use crate::error::BoxError;
+
+const OID_EMBEDDED_SCTS: &[u8] = &[0x2b, 0x06, 0x01, 0x04, 0x01, 0xd6, 0x79, 0x02, 0x04, 0x02];
+
+/// Certificates valid for longer than this need one more SCT.
+const SHORT_LIVED_DAYS: i32 = 180;
+
+/// A promise by a Certificate Transparency log to publish a certificate.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SignedCertificateTimestamp {
+    version: u8,
+    log_id: [u8; 32],
+    timestamp: u64,
+}
+
+impl SignedCertificateTimestamp {
+    /// The SCT version, `0` for version 1.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// The SHA-256 hash of the public key of the log that issued the SCT.
+    pub fn log_id(&self) -> &[u8; 32] {
+        &self.log_id
+    }
+
+    /// When the log issued the SCT.
+    pub fn timestamp(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.timestamp)
+    }
+}
+
+impl fmt::Debug for SignedCertificateTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let log_id = self
+            .log_id
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+        f.debug_struct("SignedCertificateTimestamp")
+            .field("version", &self.version)
+            .field("log_id", &log_id)
+            .field("timestamp", &self.timestamp())
+            .finish()
+    }
+}
+
+/// The SCTs embedded in a DER encoded certificate.
+pub(crate) fn embedded(cert: &[u8]) -> Vec<SignedCertificateTimestamp> {
+    certificate_extension(cert, OID_EMBEDDED_SCTS)
+        .and_then(|value| Der(value).expect(TAG_OCTET_STRING))
+        .and_then(|list| parse_list(list.0))
+        .unwrap_or_default()
+}
+
+/// Check that the server's certificate was logged as browsers require.
+///
+/// Like Chrome's policy, this asks for SCTs from two distinct logs, or three if
+/// the certificate is valid for more than 180 days.
+pub(crate) fn check(ssl: &SslRef) -> Result<(), BoxError> {
+    let leaf = ssl.peer_certificate().ok_or("server sent no certificate")?;
+    let scts = embedded(&leaf.to_der()?);
+
+    let lifetime = leaf.not_before().diff(leaf.not_after())?;
+    let required = if lifetime.days > SHORT_LIVED_DAYS
+        || (lifetime.days == SHORT_LIVED_DAYS && lifetime.secs > 0)
+    {
+        3
+    } else {
+        2
+    };
+
+    let logs = scts.iter().map(|sct| sct.log_id).collect::<HashSet<_>>();
+    if logs.len() < required {
+        return Err(format!(
+            "server certificate has SCTs from {} logs, {} are required",
+            logs.len(),
+            required
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Parse a `SignedCertificateTimestampList`.
+fn parse_list(bytes: &[u8]) -> Option<Vec<SignedCertificateTimestamp>> {
+    let mut list = Reader(bytes).vec16()?;
+    let mut scts = Vec::new();
+    while !list.0.is_empty() {
+        let mut sct = list.vec16()?;
+        let version = sct.u8()?;
+        if version != 0 {
+            // unknown versions are skipped, as RFC 6962 asks
+            continue;
+        }
+        let log_id = sct.take(32)?.try_into().ok()?;
+        let timestamp = u64::from_be_bytes(sct.take(8)?.try_into().ok()?);
+        scts.push(SignedCertificateTimestamp {
+            version,
+            log_id,
+            timestamp,
+        });
+    }
+    Some(scts)
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        let (head, rest) = self.0.split_at_checked(n)?;
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn vec16(&mut self) -> Option<Reader<'a>> {
+        let len = u16::from_be_bytes(self.take(2)?.try_into().ok()?);
+        self.take(usize::from(len)).map(Reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sct(version: u8, log: u8, timestamp: u64) -> Vec<u8> {
+        let mut sct = vec![version];
+        sct.extend_from_slice(&[log; 32]);
+        sct.extend_from_slice(&timestamp.to_be_bytes());
+        // no extensions, an empty ECDSA/SHA-256 signature
+        sct.extend_from_slice(&[0, 0, 4, 3, 0, 0]);
+
+        let mut serialized = (sct.len() as u16).to_be_bytes().to_vec();
+        serialized.extend(sct);
+        serialized
+    }
+
+    fn list(scts: &[Vec<u8>]) -> Vec<u8> {
+        let body = scts.concat();
+        let mut list = (body.len() as u16).to_be_bytes().to_vec();
+        list.extend(body);
+        list
+    }
+
+    #[test]
+    fn parses_sct_list() {
+        let scts = parse_list(&list(&[sct(0, 1, 1_700_000_000_000), sct(0, 2, 0)])).unwrap();
+        assert_eq!(scts.len(), 2);
+        assert_eq!(scts[0].version(), 0);
+        assert_eq!(scts[0].log_id(), &[1; 32]);
+        assert_eq!(
+            scts[0].timestamp(),
+            UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+        );
+        assert_eq!(scts[1].log_id(), &[2; 32]);
+    }
+
+    #[test]
+    fn skips_unknown_versions() {
+        let scts = parse_list(&list(&[sct(1, 1, 0), sct(0, 2, 0)])).unwrap();
+        assert_eq!(scts.len(), 1);
+        assert_eq!(scts[0].log_id(), &[2; 32]);
+    }
+
+    #[test]
+    fn rejects_truncated_list() {
+        let list = list(&[sct(0, 1, 0)]);
+        assert!(parse_list(&list[..list.len() - 1]).is_none());
+        assert!(parse_list(&[]).is_none());
+    }
+}
@@ -0,0 +1,128 @@
+use std::fmt;
+use std::sync::Arc;
+
+use antidote::Mutex;
+use boring2::ssl::SslRef;
+use boring2::x509::{X509, X509VerifyResult};
+
+use crate::error::BoxError;
+
+/// The certificate chain a server presented during the handshake.
+///
+/// Passed to the verifier set with
+/// [`ClientBuilder::cert_verifier`](crate::ClientBuilder::cert_verifier).
+#[derive(Clone)]
+pub struct CertChain {
+    certs: Vec<Vec<u8>>,
+    trusted: bool,
+}
+
+impl CertChain {
+    pub(crate) fn from_ssl(ssl: &SslRef) -> CertChain {
+        let certs = ssl
+            .peer_cert_chain()
+            .into_iter()
+            .flatten()
+            .filter_map(|cert| cert.to_der().ok())
+            .collect();
+
+        CertChain {
+            certs,
+            trusted: ssl.verify_result() == X509VerifyResult::OK,
+        }
+    }
+
+    /// Get the DER encoded leaf certificate of the server.
+    pub fn leaf(&self) -> Option<&[u8]> {
+        self.certs.first().map(|der| &der[..])
+    }
+
+    /// Get the DER encoded certificates of the chain, starting with the leaf.
+    pub fn iter(&self) -> impl Iterator<Item = &[u8]> {
+        self.certs.iter().map(|der| &der[..])
+    }
+
+    /// Returns the number of certificates in the chain.
+    pub fn len(&self) -> usize {
+        self.certs.len()
+    }
+
+    /// Returns true if the server sent no certificate.
+    pub fn is_empty(&self) -> bool {
+        self.certs.is_empty()
+    }
+
+    /// Returns true if the chain passed the client's built-in verification,
+    /// against its certificate store and for the requested host.
+    pub fn is_trusted(&self) -> bool {
+        self.trusted
+    }
+}
+
+impl fmt::Debug for CertChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CertChain")
+            .field("len", &self.certs.len())
+            .field("trusted", &self.trusted)
+            .finish()
+    }
+}
+
+type VerifyFn = dyn Fn(&CertChain, &str) -> Result<(), BoxError> + Send + Sync;
+
+/// A custom trust decision, made after the handshake.
+#[derive(Clone)]
+pub(crate) struct CertVerifier(Arc<VerifyFn>);
+
+impl CertVerifier {
+    pub(crate) fn new<F>(verify: F) -> CertVerifier
+    where
+        F: Fn(&CertChain, &str) -> Result<(), BoxError> + Send + Sync + 'static,
+    {
+        CertVerifier(Arc::new(verify))
+    }
+
+    pub(crate) fn verify(&self, host: &str, ssl: &SslRef) -> Result<(), BoxError> {
+        (self.0)(&CertChain::from_ssl(ssl), host)
+    }
+}
+
+impl fmt::Debug for CertVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CertVerifier")
+    }
+}
+
+/// The chain built by the handshake's certificate verification.
+///
+/// Unlike the certificates the server presented, which can include anything it
+/// chooses to append, each certificate of this chain is signed by the next one,
+/// up to a root of the certificate store.
+#[derive(Clone, Default)]
+pub(crate) struct VerifiedChain(Arc<Mutex<Vec<X509>>>);
+
+impl VerifiedChain {
+    /// Record the chain that verification of `ssl` builds.
+    pub(crate) fn record(&self, ssl: &mut SslRef) {
+        let chain = self.0.clone();
+        ssl.set_verify_callback(ssl.verify_mode(), move |ok, ctx| {
+            if let Some(certs) = ctx.chain() {
+                *chain.lock() = certs.iter().map(ToOwned::to_owned).collect();
+            }
+            ok
+        });
+    }
+
+    /// The certificates of the verified chain of `ssl`, starting with the leaf.
+    ///
+    /// If verification failed or was disabled, this is only the leaf: the server
+    /// proved it holds the leaf's key, but nothing vouches for the rest.
+    pub(crate) fn certs(&self, ssl: &SslRef) -> Vec<X509> {
+        let chain = std::mem::take(&mut *self.0.lock());
+        if ssl.verify_result() == X509VerifyResult::OK && !chain.is_empty() {
+            chain
+        } else {
+            ssl.peer_certificate().into_iter().collect()
+        }
+    }
+}
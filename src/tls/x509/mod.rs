@@ -41,7 +41,7 @@ impl<'c, T: AsRef<[u8]> + ?Sized + 'c> From<&'c T> for CertificateInput<'c> {
 
 /// A certificate.
 #[derive(Clone)]
-pub struct Certificate(X509);
+pub struct Certificate(pub(crate) X509);
 
 impl Certificate {
     /// Parse a certificate from DER data.
@@ -62,4 +62,11 @@ impl Certificate {
         let certs = X509::stack_from_pem(cert.as_ref())?;
         Ok(certs.into_iter().map(Self).collect())
     }
+
+    /// Get the hash of the certificate's subject name, as used by OpenSSL's
+    /// `c_rehash` to name files in a certificate directory.
+    #[inline]
+    pub fn subject_name_hash(&self) -> u32 {
+        self.0.subject_name_hash()
+    }
 }
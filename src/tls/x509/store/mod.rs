@@ -10,8 +10,27 @@ use std::{fmt::Debug, path::Path};
 /// This builder provides methods to add certificates to the store from various formats,
 /// and to set default paths for the certificate store. Once all desired certificates
 /// have been added, the `build` method can be used to create the `CertStore`.
+///
+/// Certificates from several sources can be merged, and removed again by subject
+/// before the store is built.
+///
+/// # Example
+///
+/// ```no_run
+/// use rquest::tls::{CertStore, Certificate};
+///
+/// # fn run(distrusted: &Certificate) -> rquest::Result<()> {
+/// let store = CertStore::builder()
+///     .add_webpki_roots()
+///     .add_dir_certs("/etc/ssl/private-ca")
+///     .remove_subject_hash(distrusted.subject_name_hash())
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
 pub struct CertStoreBuilder {
-    builder: crate::Result<X509StoreBuilder>,
+    certs: crate::Result<Vec<Certificate>>,
+    default_paths: bool,
 }
 
 impl CertStoreBuilder {
@@ -58,12 +77,10 @@ impl CertStoreBuilder {
     where
         C: AsRef<[u8]>,
     {
-        if let Ok(ref mut builder) = self.builder {
-            let result = Certificate::stack_from_pem(certs.as_ref())
-                .and_then(|certs| process_certs(certs.into_iter(), builder));
-
-            if let Err(err) = result {
-                self.builder = Err(err);
+        if let Ok(ref mut store) = self.certs {
+            match Certificate::stack_from_pem(certs.as_ref()) {
+                Ok(certs) => store.extend(certs),
+                Err(err) => self.certs = Err(err),
             }
         }
         self
@@ -80,23 +97,73 @@ impl CertStoreBuilder {
         match std::fs::read(path) {
             Ok(data) => return self.add_stack_pem_certs(data),
             Err(err) => {
-                self.builder = Err(crate::error::builder(err));
+                self.certs = Err(crate::error::builder(err));
+            }
+        }
+        self
+    }
+
+    /// Adds the certificates of every file in a directory, like OpenSSL's `CApath`.
+    ///
+    /// Files may hold a PEM-encoded certificate stack or a single DER-encoded
+    /// certificate. Files holding neither, and subdirectories, are skipped.
+    pub fn add_dir_certs<P>(mut self, path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        if let Ok(ref mut store) = self.certs {
+            if let Err(err) = read_dir_certs(path.as_ref(), store) {
+                self.certs = Err(crate::error::builder(err));
             }
         }
         self
     }
 
+    /// Adds the Mozilla root certificates bundled by `webpki-root-certs`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `webpki-roots` feature to be enabled.
+    #[cfg(feature = "webpki-roots")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "webpki-roots")))]
+    pub fn add_webpki_roots(self) -> Self {
+        self.add_der_certs(webpki_root_certs::TLS_SERVER_ROOT_CERTS)
+    }
+
+    /// Adds the root certificates of the operating system's certificate store.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `native-roots` feature to be enabled.
+    #[cfg(feature = "native-roots")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "native-roots")))]
+    pub fn add_native_roots(self) -> Self {
+        let native = rustls_native_certs::load_native_certs();
+        for _err in &native.errors {
+            warn!("tls failed to load native certificate: {}", _err);
+        }
+        self.add_der_certs(&native.certs)
+    }
+
+    /// Removes the certificates added so far whose subject name has the given
+    /// hash, as computed by [`Certificate::subject_name_hash`].
+    ///
+    /// Certificates loaded with [`CertStoreBuilder::set_default_paths`] are not
+    /// affected.
+    pub fn remove_subject_hash(mut self, hash: u32) -> Self {
+        if let Ok(ref mut store) = self.certs {
+            store.retain(|cert| cert.subject_name_hash() != hash);
+        }
+        self
+    }
+
     /// Load certificates from their default locations.
     ///
     /// These locations are read from the `SSL_CERT_FILE` and `SSL_CERT_DIR`
     /// environment variables if present, or defaults specified at OpenSSL
     /// build time otherwise.
     pub fn set_default_paths(mut self) -> Self {
-        if let Ok(ref mut builder) = self.builder {
-            if let Err(err) = builder.set_default_paths() {
-                self.builder = Err(err.into());
-            }
-        }
+        self.default_paths = true;
         self
     }
 
@@ -105,7 +172,12 @@ impl CertStoreBuilder {
     /// This method finalizes the builder and constructs the `CertStore`
     /// containing all the added certificates.
     pub fn build(self) -> crate::Result<CertStore> {
-        let builder = self.builder?;
+        let certs = self.certs?;
+        let mut builder = X509StoreBuilder::new()?;
+        if self.default_paths {
+            builder.set_default_paths()?;
+        }
+        process_certs(certs.into_iter(), &mut builder)?;
         Ok(CertStore(builder.build()))
     }
 
@@ -114,14 +186,10 @@ impl CertStoreBuilder {
         C: Into<CertificateInput<'c>>,
         P: Fn(&'c [u8]) -> crate::Result<Certificate>,
     {
-        if let Ok(ref mut builder) = self.builder {
-            let input = cert.into();
-            let result = input
-                .with_parser(parser)
-                .and_then(|cert| builder.add_cert(cert.0).map_err(Into::into));
-
-            if let Err(err) = result {
-                self.builder = Err(err);
+        if let Ok(ref mut store) = self.certs {
+            match cert.into().with_parser(parser) {
+                Ok(cert) => store.push(cert),
+                Err(err) => self.certs = Err(err),
             }
         }
         self
@@ -136,16 +204,34 @@ impl CertStoreBuilder {
         I: IntoIterator,
         I::Item: Into<CertificateInput<'c>>,
     {
-        if let Ok(ref mut builder) = self.builder {
-            let certs = filter_map_certs(certs, parser);
-            if let Err(err) = process_certs(certs, builder) {
-                self.builder = Err(err);
-            }
+        if let Ok(ref mut store) = self.certs {
+            store.extend(filter_map_certs(certs, parser));
         }
         self
     }
 }
 
+fn read_dir_certs(dir: &Path, store: &mut Vec<Certificate>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let data = std::fs::read(&path)?;
+        match Certificate::stack_from_pem(&data) {
+            Ok(certs) if !certs.is_empty() => store.extend(certs),
+            _ => match Certificate::from_der(&data) {
+                Ok(cert) => store.push(cert),
+                Err(_err) => {
+                    trace!("tls skipped {:?}, which holds no certificate", path);
+                }
+            },
+        }
+    }
+    Ok(())
+}
+
 /// A collection of certificates Store.
 #[derive(Clone)]
 pub struct CertStore(X509Store);
@@ -193,7 +279,8 @@ impl CertStore {
     #[inline]
     pub fn builder() -> CertStoreBuilder {
         CertStoreBuilder {
-            builder: X509StoreBuilder::new().map_err(crate::error::builder),
+            certs: Ok(Vec::new()),
+            default_paths: false,
         }
     }
 
@@ -246,3 +333,67 @@ impl CertStore {
         tls.set_cert_store(self.0);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn self_signed(cn: &str) -> Certificate {
+        use boring2::asn1::Asn1Time;
+        use boring2::ec::{EcGroup, EcKey};
+        use boring2::hash::MessageDigest;
+        use boring2::nid::Nid;
+        use boring2::pkey::PKey;
+        use boring2::x509::{X509, X509NameBuilder};
+
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let pkey = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", cn).unwrap();
+        let name = name.build();
+
+        let mut cert = X509::builder().unwrap();
+        cert.set_version(2).unwrap();
+        cert.set_subject_name(&name).unwrap();
+        cert.set_issuer_name(&name).unwrap();
+        cert.set_pubkey(&pkey).unwrap();
+        cert.set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        cert.set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        cert.sign(&pkey, MessageDigest::sha256()).unwrap();
+        Certificate(cert.build())
+    }
+
+    #[test]
+    fn builder_loads_dir_and_removes_by_subject() {
+        let a = self_signed("a.local");
+        let b = self_signed("b.local");
+
+        let dir = std::env::temp_dir().join(format!("rquest-certs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.pem"), a.0.to_pem().unwrap()).unwrap();
+        std::fs::write(dir.join("b.der"), b.0.to_der().unwrap()).unwrap();
+        std::fs::write(dir.join("README"), "not a certificate").unwrap();
+
+        let builder = CertStore::builder().add_dir_certs(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(builder.certs.as_ref().unwrap().len(), 2);
+
+        let builder = builder.remove_subject_hash(a.subject_name_hash());
+        let certs = builder.certs.as_ref().unwrap();
+        assert_eq!(certs.len(), 1);
+        assert_eq!(certs[0].subject_name_hash(), b.subject_name_hash());
+
+        builder.build().unwrap();
+    }
+
+    #[test]
+    fn builder_missing_dir() {
+        CertStore::builder()
+            .add_dir_certs("/nonexistent/rquest/certs")
+            .build()
+            .unwrap_err();
+    }
+}
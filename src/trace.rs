@@ -53,3 +53,199 @@ macro_rules! error {
         }
     }
 }
+
+/// The span a request is traced in, when the `tracing` feature is enabled.
+///
+/// The span is named `request`, and records the status code, HTTP version and
+/// connection of the response. The connections a request opens are traced in
+/// `connect` spans under it, with `dns`, `tcp_connect` and `tls_handshake`
+/// spans for their phases.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone)]
+pub(crate) struct RequestSpan {
+    #[cfg(feature = "tracing")]
+    span: ::tracing::Span,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl RequestSpan {
+    pub(crate) fn new(_method: &http::Method, _url: &url::Url) -> RequestSpan {
+        RequestSpan {
+            #[cfg(feature = "tracing")]
+            span: ::tracing::debug_span!(
+                "request",
+                http.request.method = %_method,
+                url.full = %_url,
+                http.response.status_code = ::tracing::field::Empty,
+                network.protocol.version = ::tracing::field::Empty,
+                conn.id = ::tracing::field::Empty,
+            ),
+        }
+    }
+
+    /// Run `f` inside the span.
+    #[inline]
+    pub(crate) fn in_scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        #[cfg(feature = "tracing")]
+        {
+            self.span.in_scope(f)
+        }
+        #[cfg(not(feature = "tracing"))]
+        {
+            f()
+        }
+    }
+
+    /// Record an attempt of the request being sent to `_url`.
+    pub(crate) fn send(&self, _url: &url::Url) {
+        #[cfg(feature = "tracing")]
+        ::tracing::debug!(parent: &self.span, url = %_url, "sending request");
+    }
+
+    /// Record the response headers of an attempt arriving.
+    pub(crate) fn first_byte<B>(&self, _res: &http::Response<B>) {
+        #[cfg(feature = "tracing")]
+        {
+            let conn = _res.extensions().get::<ConnectionId>().map(|id| id.0);
+            self.span
+                .record("http.response.status_code", _res.status().as_u16());
+            self.span.record(
+                "network.protocol.version",
+                ::tracing::field::debug(_res.version()),
+            );
+            if let Some(conn) = conn {
+                self.span.record("conn.id", conn);
+            }
+            ::tracing::debug!(
+                parent: &self.span,
+                status = _res.status().as_u16(),
+                conn.id = conn,
+                "received response headers"
+            );
+        }
+    }
+
+    /// Record the request waiting out the `Retry-After` of a response.
+    pub(crate) fn retry_after(&self, _status: http::StatusCode, _wait: std::time::Duration) {
+        #[cfg(feature = "tracing")]
+        ::tracing::debug!(
+            parent: &self.span,
+            status = _status.as_u16(),
+            wait = ?_wait,
+            "retry_after"
+        );
+    }
+
+    /// Trace the end of the response body, if tracing is enabled.
+    pub(crate) fn body(
+        &self,
+        res: http::Response<crate::client::body::ResponseBody>,
+    ) -> http::Response<crate::client::body::ResponseBody> {
+        #[cfg(feature = "tracing")]
+        {
+            let span = self.span.clone();
+            res.map(|body| crate::client::body::boxed(body::TracedBody::new(body, span)))
+        }
+        #[cfg(not(feature = "tracing"))]
+        {
+            res
+        }
+    }
+}
+
+/// The identifier of a connection in the spans and events of the `tracing`
+/// feature, set as an extension of the responses received on it.
+#[cfg(all(feature = "tracing", not(target_arch = "wasm32")))]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ConnectionId(pub(crate) u64);
+
+#[cfg(all(feature = "tracing", not(target_arch = "wasm32")))]
+impl ConnectionId {
+    pub(crate) fn next() -> ConnectionId {
+        static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+        ConnectionId(NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+    }
+}
+
+#[cfg(all(feature = "tracing", not(target_arch = "wasm32")))]
+mod body {
+    use std::pin::Pin;
+    use std::task::{Context, Poll, ready};
+
+    use bytes::Bytes;
+    use http_body::{Body, Frame, SizeHint};
+    use pin_project_lite::pin_project;
+
+    pin_project! {
+        /// Emits an event in the request span when the response body ends.
+        pub(crate) struct TracedBody<B> {
+            #[pin]
+            inner: B,
+            span: ::tracing::Span,
+            received: u64,
+            done: bool,
+        }
+    }
+
+    impl<B> TracedBody<B> {
+        pub(crate) fn new(inner: B, span: ::tracing::Span) -> TracedBody<B> {
+            TracedBody {
+                inner,
+                span,
+                received: 0,
+                done: false,
+            }
+        }
+    }
+
+    impl<B> Body for TracedBody<B>
+    where
+        B: Body<Data = Bytes>,
+        B::Error: std::fmt::Display,
+    {
+        type Data = Bytes;
+        type Error = B::Error;
+
+        fn poll_frame(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Frame<Bytes>, B::Error>>> {
+            let this = self.project();
+            let item = ready!(this.inner.poll_frame(cx));
+            match item {
+                Some(Ok(ref frame)) => {
+                    if let Some(data) = frame.data_ref() {
+                        *this.received += data.len() as u64;
+                    }
+                }
+                Some(Err(ref err)) if !*this.done => {
+                    *this.done = true;
+                    ::tracing::debug!(
+                        parent: &*this.span,
+                        bytes = *this.received,
+                        error = %err,
+                        "response body failed"
+                    );
+                }
+                None if !*this.done => {
+                    *this.done = true;
+                    ::tracing::debug!(
+                        parent: &*this.span,
+                        bytes = *this.received,
+                        "response body complete"
+                    );
+                }
+                _ => {}
+            }
+            Poll::Ready(item)
+        }
+
+        fn is_end_stream(&self) -> bool {
+            self.inner.is_end_stream()
+        }
+
+        fn size_hint(&self) -> SizeHint {
+            self.inner.size_hint()
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt;
+
+use bytes::Bytes;
+use js_sys::Uint8Array;
+use wasm_bindgen::JsValue;
+
+/// The body of a `Request`.
+///
+/// In most cases, this is not needed directly, as the
+/// [`RequestBuilder.body`][builder] method uses `Into<Body>`, which allows
+/// passing many things (like a string or vector of bytes).
+///
+/// Streaming bodies aren't supported by `fetch` in every browser, so a body is
+/// always sent whole.
+///
+/// [builder]: ./struct.RequestBuilder.html#method.body
+pub struct Body {
+    inner: Inner,
+}
+
+enum Inner {
+    Bytes(Bytes),
+    Text(String),
+}
+
+impl Body {
+    /// Returns a reference to the internal data of the `Body`.
+    ///
+    /// `None` is returned, if the underlying data is a string.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match &self.inner {
+            Inner::Bytes(bytes) => Some(bytes.as_ref()),
+            Inner::Text(_) => None,
+        }
+    }
+
+    pub(crate) fn to_js_value(&self) -> JsValue {
+        match &self.inner {
+            Inner::Bytes(bytes) => {
+                let body_bytes: &[u8] = bytes.as_ref();
+                let body_array: Uint8Array = body_bytes.into();
+                body_array.into()
+            }
+            Inner::Text(text) => JsValue::from_str(text),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        match &self.inner {
+            Inner::Bytes(bytes) => bytes.is_empty(),
+            Inner::Text(text) => text.is_empty(),
+        }
+    }
+
+    pub(crate) fn try_clone(&self) -> Option<Body> {
+        let inner = match &self.inner {
+            Inner::Bytes(bytes) => Inner::Bytes(bytes.clone()),
+            Inner::Text(text) => Inner::Text(text.clone()),
+        };
+        Some(Body { inner })
+    }
+}
+
+impl From<Bytes> for Body {
+    #[inline]
+    fn from(bytes: Bytes) -> Body {
+        Body {
+            inner: Inner::Bytes(bytes),
+        }
+    }
+}
+
+impl From<Vec<u8>> for Body {
+    #[inline]
+    fn from(vec: Vec<u8>) -> Body {
+        Body::from(Bytes::from(vec))
+    }
+}
+
+impl From<&'static [u8]> for Body {
+    #[inline]
+    fn from(s: &'static [u8]) -> Body {
+        Body::from(Bytes::from_static(s))
+    }
+}
+
+impl From<String> for Body {
+    #[inline]
+    fn from(s: String) -> Body {
+        Body {
+            inner: Inner::Text(s),
+        }
+    }
+}
+
+impl From<&'static str> for Body {
+    #[inline]
+    fn from(s: &'static str) -> Body {
+        Body::from(s.to_owned())
+    }
+}
+
+impl fmt::Debug for Body {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Body").finish()
+    }
+}
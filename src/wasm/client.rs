@@ -0,0 +1,332 @@
+use std::convert::TryInto;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use http::header::{ACCEPT, HeaderValue, USER_AGENT};
+use http::{HeaderMap, Method};
+use js_sys::{Promise, Reflect};
+use url::Url;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{RequestInit, RequestMode};
+
+use super::{AbortGuard, Request, RequestBuilder, Response};
+use crate::IntoUrl;
+
+/// A `Client` to make Requests with the browser's `fetch`.
+///
+/// The browser makes the connections, so nothing of the TLS and HTTP/2
+/// configuration of the native client applies: there is no emulation, header
+/// order, proxy, certificate or DNS setting, and servers see the browser's own
+/// fingerprint. Redirects, cookies and decompression are left to the browser
+/// as well.
+#[derive(Clone)]
+pub struct Client {
+    config: Arc<Config>,
+}
+
+/// A `ClientBuilder` can be used to create a `Client` with custom configuration.
+#[must_use]
+pub struct ClientBuilder {
+    config: Config,
+}
+
+#[derive(Debug)]
+struct Config {
+    headers: HeaderMap,
+    timeout: Option<Duration>,
+    error: Option<crate::Error>,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClientBuilder {
+    /// Constructs a new `ClientBuilder`.
+    ///
+    /// This is the same as `Client::builder()`.
+    pub fn new() -> ClientBuilder {
+        ClientBuilder {
+            config: Config {
+                headers: HeaderMap::new(),
+                timeout: None,
+                error: None,
+            },
+        }
+    }
+
+    /// Returns a `Client` that uses this `ClientBuilder` configuration.
+    pub fn build(self) -> crate::Result<Client> {
+        let mut config = self.config;
+        if let Some(err) = config.error.take() {
+            return Err(err);
+        }
+
+        Ok(Client {
+            config: Arc::new(config),
+        })
+    }
+
+    /// Sets the `User-Agent` header to be used by this client.
+    ///
+    /// Browsers may ignore it, as `User-Agent` is a forbidden header name in
+    /// some of them.
+    pub fn user_agent<V>(mut self, value: V) -> ClientBuilder
+    where
+        V: TryInto<HeaderValue>,
+        V::Error: Into<http::Error>,
+    {
+        match value.try_into() {
+            Ok(value) => {
+                self.config.headers.insert(USER_AGENT, value);
+            }
+            Err(e) => {
+                self.config.error = Some(crate::error::builder(e.into()));
+            }
+        }
+        self
+    }
+
+    /// Sets the default headers for every request.
+    pub fn default_headers(mut self, headers: HeaderMap) -> ClientBuilder {
+        crate::util::replace_headers(&mut self.config.headers, headers);
+        self
+    }
+
+    /// Enables a total request timeout.
+    ///
+    /// The timeout is applied from when the request is handed to `fetch` until
+    /// the response body has finished.
+    ///
+    /// Default is no timeout.
+    pub fn timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.config.timeout = Some(timeout);
+        self
+    }
+}
+
+impl fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ClientBuilder")
+            .field("headers", &self.config.headers)
+            .field("timeout", &self.config.timeout)
+            .finish()
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Client {
+    /// Constructs a new `Client`.
+    pub fn new() -> Self {
+        Client::builder().build().expect("Client::new()")
+    }
+
+    /// Creates a `ClientBuilder` to configure a `Client`.
+    ///
+    /// This is the same as `ClientBuilder::new()`.
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    /// Convenience method to make a `GET` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever supplied `Url` cannot be parsed.
+    pub fn get<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::GET, url)
+    }
+
+    /// Convenience method to make a `POST` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever supplied `Url` cannot be parsed.
+    pub fn post<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::POST, url)
+    }
+
+    /// Convenience method to make a `PUT` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever supplied `Url` cannot be parsed.
+    pub fn put<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::PUT, url)
+    }
+
+    /// Convenience method to make a `PATCH` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever supplied `Url` cannot be parsed.
+    pub fn patch<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::PATCH, url)
+    }
+
+    /// Convenience method to make a `DELETE` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever supplied `Url` cannot be parsed.
+    pub fn delete<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::DELETE, url)
+    }
+
+    /// Convenience method to make a `HEAD` request to a URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever supplied `Url` cannot be parsed.
+    pub fn head<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.request(Method::HEAD, url)
+    }
+
+    /// Start building a `Request` with the `Method` and `Url`.
+    ///
+    /// Returns a `RequestBuilder`, which will allow setting headers and
+    /// request body before sending.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever supplied `Url` cannot be parsed.
+    pub fn request<U: IntoUrl>(&self, method: Method, url: U) -> RequestBuilder {
+        let req = url.into_url().map(move |url| Request::new(method, url));
+        RequestBuilder::new(self.clone(), req)
+    }
+
+    /// Executes a `Request`.
+    ///
+    /// A `Request` can be built manually with `Request::new()` or obtained
+    /// from a RequestBuilder with `RequestBuilder::build()`.
+    ///
+    /// You should prefer to use the `RequestBuilder` and
+    /// `RequestBuilder::send()`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if there was an error while sending request.
+    pub async fn execute(&self, request: Request) -> crate::Result<Response> {
+        self.execute_request(request).await
+    }
+
+    pub(super) async fn execute_request(&self, mut req: Request) -> crate::Result<Response> {
+        // the request's headers win over the client's defaults
+        for (name, value) in self.config.headers.iter() {
+            if !req.headers().contains_key(name) {
+                req.headers_mut().insert(name, value.clone());
+            }
+        }
+        if !req.headers().contains_key(ACCEPT) {
+            req.headers_mut()
+                .insert(ACCEPT, HeaderValue::from_static("*/*"));
+        }
+        if req.timeout().is_none() {
+            *req.timeout_mut() = self.config.timeout;
+        }
+
+        fetch(req).await
+    }
+}
+
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("headers", &self.config.headers)
+            .field("timeout", &self.config.timeout)
+            .finish()
+    }
+}
+
+async fn fetch(req: Request) -> crate::Result<Response> {
+    let url = req.url().clone();
+    let wrap = |e: crate::error::BoxError| crate::error::request(e).with_url(url.clone());
+
+    let headers = web_sys::Headers::new().map_err(|e| wrap(crate::error::wasm(e)))?;
+    for (name, value) in req.headers() {
+        headers
+            .append(
+                name.as_str(),
+                value
+                    .to_str()
+                    .map_err(|e| crate::error::builder(e).with_url(url.clone()))?,
+            )
+            .map_err(|e| wrap(crate::error::wasm(e)))?;
+    }
+
+    let init = RequestInit::new();
+    init.set_method(req.method().as_str());
+    init.set_headers(&headers.into());
+
+    // the browser keeps its own cookies, and sends them only if told to
+    if let Some(creds) = req.credentials() {
+        init.set_credentials(creds);
+    }
+    if req.no_cors() {
+        init.set_mode(RequestMode::NoCors);
+    }
+    if let Some(body) = req.body() {
+        if !body.is_empty() {
+            init.set_body(&body.to_js_value());
+        }
+    }
+
+    let mut abort = AbortGuard::new()?;
+    if let Some(timeout) = req.timeout() {
+        abort.timeout(*timeout);
+    }
+    init.set_signal(Some(&abort.signal()));
+
+    let js_req = web_sys::Request::new_with_str_and_init(url.as_str(), &init)
+        .map_err(|e| crate::error::builder(crate::error::wasm(e)).with_url(url.clone()))?;
+
+    let js_resp = match super::promise::<web_sys::Response>(fetch_with_request(&js_req)).await {
+        Ok(js_resp) => js_resp,
+        Err(_) if abort.timed_out() => {
+            return Err(wrap(Box::new(crate::error::TimedOut)));
+        }
+        Err(e) => return Err(wrap(e)),
+    };
+
+    let mut resp = http::Response::builder().status(js_resp.status());
+    let js_headers = js_resp.headers();
+    let js_iter = js_sys::try_iter(&js_headers)
+        .expect("headers try_iter")
+        .expect("headers have an iterator");
+    for item in js_iter {
+        // `Headers` yields `[name, value]` pairs
+        let pair = js_sys::Array::from(&item.expect("headers iterator doesn't throw"));
+        if let (Some(name), Some(value)) = (pair.get(0).as_string(), pair.get(1).as_string()) {
+            resp = resp.header(name, value);
+        }
+    }
+
+    let url = Url::parse(&js_resp.url()).unwrap_or(url);
+    resp.body(js_resp)
+        .map(|resp| Response::new(resp, url, abort))
+        .map_err(crate::error::request)
+}
+
+fn fetch_with_request(request: &web_sys::Request) -> Promise {
+    let global = js_sys::global();
+
+    if let Ok(true) = Reflect::has(&global, &JsValue::from_str("ServiceWorkerGlobalScope")) {
+        global
+            .unchecked_into::<web_sys::ServiceWorkerGlobalScope>()
+            .fetch_with_request(request)
+    } else if let Some(worker) = global.dyn_ref::<web_sys::WorkerGlobalScope>() {
+        worker.fetch_with_request(request)
+    } else {
+        global
+            .unchecked_into::<web_sys::Window>()
+            .fetch_with_request(request)
+    }
+}
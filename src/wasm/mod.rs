@@ -0,0 +1,105 @@
+//! A `Client` for `wasm32-unknown-unknown`, backed by the `fetch` API of the
+//! browser or worker it runs in.
+//!
+//! The browser makes the connections, so nothing of the TLS and HTTP/2
+//! configuration of the native client applies: there is no emulation,
+//! header order, proxy, certificate or DNS setting, and the fingerprint seen
+//! by servers is the browser's own. Redirects, cookies and decompression are
+//! handled by the browser as well, and some headers, such as `User-Agent` in
+//! some browsers, can't be set at all.
+//!
+//! The `Client`, `RequestBuilder` and `Response` types offer the subset of
+//! the native API that maps onto `fetch`, so code written against it
+//! compiles for both targets.
+
+use std::convert::TryInto;
+use std::time::Duration;
+
+use js_sys::Function;
+use wasm_bindgen::prelude::{Closure, wasm_bindgen};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{AbortController, AbortSignal};
+
+mod body;
+mod client;
+mod request;
+mod response;
+
+pub use self::body::Body;
+pub use self::client::{Client, ClientBuilder};
+pub use self::request::{Request, RequestBuilder};
+pub use self::response::Response;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = "setTimeout")]
+    fn set_timeout(handler: &Function, timeout: i32) -> JsValue;
+
+    #[wasm_bindgen(js_name = "clearTimeout")]
+    fn clear_timeout(handle: JsValue) -> JsValue;
+}
+
+async fn promise<T>(promise: js_sys::Promise) -> Result<T, crate::error::BoxError>
+where
+    T: JsCast,
+{
+    use wasm_bindgen_futures::JsFuture;
+
+    let js_val = JsFuture::from(promise).await.map_err(crate::error::wasm)?;
+
+    js_val
+        .dyn_into::<T>()
+        .map_err(|_js_val| "promise resolved to unexpected type".into())
+}
+
+/// Aborts a `fetch` when dropped, or once its timeout passes.
+///
+/// The guard is kept alive until the response body is read, so dropping a
+/// request or response cancels what the browser still has in flight.
+#[derive(Debug)]
+struct AbortGuard {
+    ctrl: AbortController,
+    timeout: Option<(JsValue, Closure<dyn FnMut()>)>,
+}
+
+impl AbortGuard {
+    fn new() -> crate::Result<Self> {
+        Ok(AbortGuard {
+            ctrl: AbortController::new()
+                .map_err(crate::error::wasm)
+                .map_err(crate::error::builder)?,
+            timeout: None,
+        })
+    }
+
+    fn signal(&self) -> AbortSignal {
+        self.ctrl.signal()
+    }
+
+    /// Whether the timeout aborted the `fetch`, as nothing else aborts it
+    /// while the guard is alive.
+    fn timed_out(&self) -> bool {
+        self.ctrl.signal().aborted()
+    }
+
+    fn timeout(&mut self, timeout: Duration) {
+        let ctrl = self.ctrl.clone();
+        let abort = Closure::once(move || ctrl.abort());
+        let timeout = set_timeout(
+            abort.as_ref().unchecked_ref::<Function>(),
+            timeout.as_millis().try_into().expect("timeout"),
+        );
+        if let Some((id, _)) = self.timeout.replace((timeout, abort)) {
+            clear_timeout(id);
+        }
+    }
+}
+
+impl Drop for AbortGuard {
+    fn drop(&mut self) {
+        self.ctrl.abort();
+        if let Some((id, _)) = self.timeout.take() {
+            clear_timeout(id);
+        }
+    }
+}
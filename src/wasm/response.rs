@@ -0,0 +1,181 @@
+use std::fmt;
+
+use bytes::Bytes;
+use http::{HeaderMap, StatusCode, Version};
+use js_sys::Uint8Array;
+#[cfg(feature = "json")]
+use serde::de::DeserializeOwned;
+use url::Url;
+
+use super::AbortGuard;
+
+/// A Response to a submitted `Request`.
+pub struct Response {
+    http: http::Response<web_sys::Response>,
+    url: Box<Url>,
+    // aborts the fetch if the response is dropped before its body is read
+    _abort: AbortGuard,
+    // set once `chunk` has returned the whole body
+    body_done: bool,
+}
+
+impl Response {
+    pub(super) fn new(
+        res: http::Response<web_sys::Response>,
+        url: Url,
+        abort: AbortGuard,
+    ) -> Response {
+        Response {
+            http: res,
+            url: Box::new(url),
+            _abort: abort,
+            body_done: false,
+        }
+    }
+
+    /// Get the `StatusCode` of this `Response`.
+    #[inline]
+    pub fn status(&self) -> StatusCode {
+        self.http.status()
+    }
+
+    /// Get the HTTP `Version` of this `Response`.
+    ///
+    /// `fetch` doesn't tell the version a response used, so this is always
+    /// HTTP/1.1.
+    #[inline]
+    pub fn version(&self) -> Version {
+        self.http.version()
+    }
+
+    /// Get the `Headers` of this `Response`.
+    #[inline]
+    pub fn headers(&self) -> &HeaderMap {
+        self.http.headers()
+    }
+
+    /// Get a mutable reference to the `Headers` of this `Response`.
+    #[inline]
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        self.http.headers_mut()
+    }
+
+    /// Get the content length of the response, if it is known.
+    ///
+    /// This is read from the `Content-Length` header, when the browser lets
+    /// the page see it.
+    pub fn content_length(&self) -> Option<u64> {
+        self.headers()
+            .get(http::header::CONTENT_LENGTH)?
+            .to_str()
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    /// Get the final `Url` of this `Response`.
+    #[inline]
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Get the full response text.
+    ///
+    /// The browser decodes the body as UTF-8.
+    pub async fn text(self) -> crate::Result<String> {
+        let p = self
+            .http
+            .body()
+            .text()
+            .map_err(crate::error::wasm)
+            .map_err(crate::error::decode)?;
+        let js_val = super::promise::<wasm_bindgen::JsValue>(p)
+            .await
+            .map_err(crate::error::decode)?;
+        if let Some(s) = js_val.as_string() {
+            Ok(s)
+        } else {
+            Err(crate::error::decode("response.text isn't string"))
+        }
+    }
+
+    /// Try to deserialize the response body as JSON.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `json` feature enabled.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub async fn json<T: DeserializeOwned>(self) -> crate::Result<T> {
+        let full = self.bytes().await?;
+
+        serde_json::from_slice(&full).map_err(crate::error::decode)
+    }
+
+    /// Get the full response body as `Bytes`.
+    pub async fn bytes(self) -> crate::Result<Bytes> {
+        self.read_body().await
+    }
+
+    /// Get the next chunk of the response body.
+    ///
+    /// Bodies aren't streamed from `fetch`, so the whole body is returned as
+    /// one chunk. When the response body has been exhausted, this will return
+    /// `None`.
+    pub async fn chunk(&mut self) -> crate::Result<Option<Bytes>> {
+        if self.body_done {
+            return Ok(None);
+        }
+
+        let bytes = self.read_body().await?;
+        self.body_done = true;
+        Ok(Some(bytes))
+    }
+
+    async fn read_body(&self) -> crate::Result<Bytes> {
+        let p = self
+            .http
+            .body()
+            .array_buffer()
+            .map_err(crate::error::wasm)
+            .map_err(crate::error::decode)?;
+        let buf_js = super::promise::<wasm_bindgen::JsValue>(p)
+            .await
+            .map_err(crate::error::decode)?;
+
+        let buffer = Uint8Array::new(&buf_js);
+        let mut bytes = vec![0; buffer.length() as usize];
+        buffer.copy_to(&mut bytes);
+        Ok(bytes.into())
+    }
+
+    /// Turn a response into an error if the server returned an error.
+    pub fn error_for_status(self) -> crate::Result<Self> {
+        let status = self.status();
+        if status.is_client_error() || status.is_server_error() {
+            Err(crate::error::status_code(*self.url, status))
+        } else {
+            Ok(self)
+        }
+    }
+
+    /// Turn a reference to a response into an error if the server returned an error.
+    pub fn error_for_status_ref(&self) -> crate::Result<&Self> {
+        let status = self.status();
+        if status.is_client_error() || status.is_server_error() {
+            Err(crate::error::status_code(*self.url.clone(), status))
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl fmt::Debug for Response {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Response")
+            .field("url", &self.url().as_str())
+            .field("status", &self.status())
+            .field("headers", self.headers())
+            .finish()
+    }
+}
@@ -1,6 +1,10 @@
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use rquest::tls::{AlpsProtos, TlsConfig, TlsInfo, TlsVersion};
+use rquest::tls::{
+    AlpsProtos, CertStore, KeyLogPolicy, TlsConfig, TlsInfo, TlsOptions, TlsSessionCache,
+    TlsVersion,
+};
 use rquest::{Client, EmulationProvider};
 
 macro_rules! join {
@@ -45,6 +49,76 @@ async fn test_badssl_self_signed() {
 
     assert!(!text.is_empty());
 }
+
+#[tokio::test]
+async fn test_badssl_self_signed_for_host() {
+    let client = rquest::Client::builder()
+        .tls_for_host(
+            "self-signed.badssl.com",
+            TlsOptions::new().cert_verification(false),
+        )
+        .connect_timeout(Duration::from_secs(360))
+        .no_proxy()
+        .build()
+        .unwrap();
+
+    let text = client
+        .get("https://self-signed.badssl.com/")
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(!text.is_empty());
+
+    // other hosts are still verified
+    let err = client
+        .get("https://untrusted-root.badssl.com/")
+        .send()
+        .await
+        .unwrap_err();
+    assert!(err.is_connect());
+}
+
+#[tokio::test]
+async fn test_badssl_export_tls_sessions() {
+    let client = rquest::Client::builder()
+        .tls_session_cache(TlsSessionCache::new())
+        .connect_timeout(Duration::from_secs(360))
+        .no_proxy()
+        .build()
+        .unwrap();
+
+    client
+        .get("https://mozilla-modern.badssl.com/")
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+
+    let cache = TlsSessionCache::import(&client.export_tls_sessions()).unwrap();
+    assert!(!cache.is_empty());
+
+    // a new client resumes the imported sessions
+    let text = rquest::Client::builder()
+        .tls_session_cache(cache)
+        .connect_timeout(Duration::from_secs(360))
+        .no_proxy()
+        .build()
+        .unwrap()
+        .get("https://mozilla-modern.badssl.com/")
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(!text.is_empty());
+}
+
 const CURVES_LIST: &str = join!(
     ":",
     "X25519",
@@ -252,3 +326,90 @@ async fn ssl_pinning() {
     let res = client.get("https://www.google.com").send().await;
     assert!(res.is_err());
 }
+
+#[tokio::test]
+async fn tls_keylog_callback() {
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let client = rquest::Client::builder()
+        .tls_keylog(KeyLogPolicy::callback({
+            let lines = lines.clone();
+            move |line| lines.lock().unwrap().push(line.to_owned())
+        }))
+        .connect_timeout(Duration::from_secs(360))
+        .build()
+        .unwrap();
+
+    let resp = client
+        .get("https://tls-v1-2.badssl.com:1012/")
+        .send()
+        .await
+        .unwrap();
+    assert!(resp.status().is_success());
+
+    let lines = lines.lock().unwrap();
+    assert!(lines.iter().any(|line| line.starts_with("CLIENT_RANDOM ")));
+}
+
+#[tokio::test]
+async fn response_tls_info() {
+    let resp = rquest::Client::builder()
+        .tls_info(true)
+        .connect_timeout(Duration::from_secs(360))
+        .build()
+        .unwrap()
+        .get("https://tls-v1-2.badssl.com:1012/")
+        .send()
+        .await
+        .unwrap();
+
+    let info = resp.tls_info().unwrap();
+    assert_eq!(info.version(), Some(TlsVersion::TLS_1_2));
+    assert!(info.cipher().is_some());
+    assert!(!info.session_resumed());
+    assert_eq!(info.peer_cert_chain().next(), info.peer_certificate());
+}
+
+#[tokio::test]
+async fn cert_verifier() {
+    let client = rquest::Client::builder()
+        .cert_verifier(|chain, host| {
+            if chain.is_trusted() || host == "self-signed.badssl.com" {
+                Ok(())
+            } else {
+                Err("untrusted".into())
+            }
+        })
+        .connect_timeout(Duration::from_secs(360))
+        .build()
+        .unwrap();
+
+    let resp = client
+        .get("https://self-signed.badssl.com/")
+        .send()
+        .await
+        .unwrap();
+    assert!(resp.status().is_success());
+
+    let res = client
+        .get("https://untrusted-root.badssl.com/")
+        .send()
+        .await;
+    assert!(res.is_err());
+}
+
+#[tokio::test]
+async fn update_cert_store() -> Result<(), rquest::Error> {
+    let client = rquest::Client::builder()
+        .cert_store(CertStore::builder().build()?)
+        .connect_timeout(Duration::from_secs(360))
+        .build()?;
+
+    let res = client.get("https://badssl.com/").send().await;
+    assert!(res.is_err());
+
+    client.update_cert_store(CertStore::default())?;
+
+    let resp = client.get("https://badssl.com/").send().await?;
+    assert!(resp.status().is_success());
+    Ok(())
+}
@@ -0,0 +1,137 @@
+mod support;
+use std::io::Read;
+use std::time::Duration;
+
+use support::server;
+
+#[test]
+fn test_response_text() {
+    let server = server::http(move |_req| async { http::Response::new("Hello".into()) });
+
+    let url = format!("http://{}/text", server.addr());
+    let res = rquest::blocking::get(&url).unwrap();
+    assert_eq!(res.url().as_str(), &url);
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+    assert_eq!(res.content_length(), Some(5));
+
+    let body = res.text().unwrap();
+    assert_eq!(b"Hello", body.as_bytes());
+}
+
+#[test]
+fn test_post_and_read() {
+    let server = server::http(move |req| async move {
+        assert_eq!(req.method(), "POST");
+        assert_eq!(req.headers()["x-custom"], "yes");
+
+        let body = http_body_util::BodyExt::collect(req.into_body())
+            .await
+            .unwrap()
+            .to_bytes();
+        http::Response::new(body.into())
+    });
+
+    let client = rquest::blocking::Client::new();
+    let mut res = client
+        .post(format!("http://{}/echo", server.addr()))
+        .header("x-custom", "yes")
+        .body("echo this body")
+        .send()
+        .unwrap();
+
+    let mut start = [0; 5];
+    res.read_exact(&mut start).unwrap();
+    assert_eq!(&start, b"echo ");
+
+    let mut rest = Vec::new();
+    res.copy_to(&mut rest).unwrap();
+    assert_eq!(rest, b"this body");
+}
+
+#[test]
+fn test_error_for_status() {
+    let server = server::http(move |_req| async {
+        http::Response::builder()
+            .status(500)
+            .body(Default::default())
+            .unwrap()
+    });
+
+    let url = format!("http://{}/err", server.addr());
+    let err = rquest::blocking::get(&url)
+        .unwrap()
+        .error_for_status()
+        .unwrap_err();
+    assert!(err.is_status());
+    assert_eq!(
+        err.status(),
+        Some(rquest::StatusCode::INTERNAL_SERVER_ERROR)
+    );
+}
+
+#[test]
+fn test_client_shared_across_threads() {
+    let server = server::http(move |_req| async { http::Response::new("shared".into()) });
+    let url = format!("http://{}/", server.addr());
+
+    let client = rquest::blocking::Client::new();
+    let handles = (0..4)
+        .map(|_| {
+            let client = client.clone();
+            let url = url.clone();
+            std::thread::spawn(move || client.get(&url).send().unwrap().text().unwrap())
+        })
+        .collect::<Vec<_>>();
+    drop(client);
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), "shared");
+    }
+}
+
+#[test]
+fn test_timeout() {
+    let server = server::http(move |_req| async {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        http::Response::default()
+    });
+
+    let client = rquest::blocking::Client::builder()
+        .timeout(Duration::from_millis(100))
+        .no_proxy()
+        .build()
+        .unwrap();
+
+    let url = format!("http://{}/slow", server.addr());
+    let err = client.get(&url).send().unwrap_err();
+
+    assert!(err.is_timeout());
+    assert_eq!(err.url().map(|u| u.as_str()), Some(url.as_str()));
+}
+
+#[test]
+fn test_read_timeout() {
+    let server = server::http(move |_req| async {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        http::Response::default()
+    });
+
+    let client = rquest::blocking::Client::builder()
+        .read_timeout(Duration::from_millis(100))
+        .no_proxy()
+        .build()
+        .unwrap();
+
+    let url = format!("http://{}/slow", server.addr());
+    let request = client.get(&url).build().unwrap();
+    let err = client.execute(request).unwrap_err();
+
+    assert!(err.is_timeout());
+}
+
+#[tokio::test]
+#[should_panic]
+async fn test_send_in_async_context_panics() {
+    let client = rquest::blocking::Client::new();
+    let _ = client.get("http://localhost/").send();
+}
@@ -0,0 +1,167 @@
+mod support;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rquest::cache::CacheConfig;
+use rquest::{Body, StatusCode};
+use support::server;
+
+#[tokio::test]
+async fn fresh_response_is_served_from_cache() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let server = server::http(move |_req| {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            http::Response::builder()
+                .header("cache-control", "max-age=60")
+                .body(Body::from("cached"))
+                .unwrap()
+        }
+    });
+
+    let client = rquest::Client::builder()
+        .cache(CacheConfig::memory(16))
+        .no_proxy()
+        .build()
+        .unwrap();
+    let url = format!("http://{}/fresh", server.addr());
+
+    for _ in 0..3 {
+        let res = client.get(&url).send().await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.text().await.unwrap(), "cached");
+    }
+
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn stale_response_is_revalidated() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let server = server::http(move |req| {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            if req
+                .headers()
+                .get("if-none-match")
+                .is_some_and(|v| v == "\"v1\"")
+            {
+                return http::Response::builder()
+                    .status(StatusCode::NOT_MODIFIED)
+                    .header("etag", "\"v1\"")
+                    .body(Body::default())
+                    .unwrap();
+            }
+            http::Response::builder()
+                .header("cache-control", "no-cache")
+                .header("etag", "\"v1\"")
+                .body(Body::from("revalidated"))
+                .unwrap()
+        }
+    });
+
+    let client = rquest::Client::builder()
+        .cache(CacheConfig::memory(16))
+        .no_proxy()
+        .build()
+        .unwrap();
+    let url = format!("http://{}/stale", server.addr());
+
+    for _ in 0..2 {
+        let res = client.get(&url).send().await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.text().await.unwrap(), "revalidated");
+    }
+
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn unsafe_request_invalidates_cache() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let server = server::http(move |_req| {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            http::Response::builder()
+                .header("cache-control", "max-age=60")
+                .body(Body::default())
+                .unwrap()
+        }
+    });
+
+    let client = rquest::Client::builder()
+        .cache(CacheConfig::memory(16))
+        .no_proxy()
+        .build()
+        .unwrap();
+    let url = format!("http://{}/resource", server.addr());
+
+    client
+        .get(&url)
+        .send()
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+    client
+        .post(&url)
+        .send()
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+    client
+        .get(&url)
+        .send()
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+
+    assert_eq!(hits.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn no_store_is_not_cached() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let server = server::http(move |_req| {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            http::Response::builder()
+                .header("cache-control", "no-store, max-age=60")
+                .body(Body::from("secret"))
+                .unwrap()
+        }
+    });
+
+    let client = rquest::Client::builder()
+        .cache(CacheConfig::memory(16))
+        .no_proxy()
+        .build()
+        .unwrap();
+    let url = format!("http://{}/private", server.addr());
+
+    for _ in 0..2 {
+        client
+            .get(&url)
+            .send()
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+}
@@ -228,6 +228,48 @@ async fn test_headers_order_with_request() {
     assert_eq!(res.status(), rquest::StatusCode::OK);
 }
 
+#[tokio::test]
+async fn test_headers_order_cased_with_request() {
+    use tokio::io::AsyncWriteExt;
+
+    // echo the raw request head back as the response body
+    let server = server::low_level_with_response(|raw_request, client_socket| {
+        Box::new(async move {
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n",
+                raw_request.len()
+            );
+            client_socket
+                .write_all(&[head.as_bytes(), raw_request].concat())
+                .await
+                .expect("response write_all failed");
+        })
+    });
+
+    let res = Client::builder()
+        .no_proxy()
+        .build()
+        .unwrap()
+        .get(format!("http://{}/", server.addr()))
+        .header("x-requested-with", "XMLHttpRequest")
+        .header("accept", "*/*")
+        .header("x-custom", "a")
+        .header("x-custom-2", "b")
+        .headers_order_cased(["X-Custom", "Accept", "X-REQUESTED-WITH"])
+        .send()
+        .await
+        .unwrap();
+
+    let text = res.text().await.unwrap();
+    let x_custom = text.find("\r\nX-Custom: a\r\n").expect("cased x-custom");
+    let accept = text.find("\r\nAccept: */*\r\n").expect("cased accept");
+    let requested_with = text
+        .find("\r\nX-REQUESTED-WITH: XMLHttpRequest\r\n")
+        .expect("cased x-requested-with");
+    assert!(x_custom < accept && accept < requested_with);
+    assert!(text.contains("\r\nx-custom-2: b\r\n"));
+}
+
 #[tokio::test]
 async fn donot_set_content_length_0_if_have_no_body() {
     let server = server::http(move |req| async move {
@@ -326,6 +368,92 @@ async fn response_json() {
     assert_eq!("Hello", text);
 }
 
+#[tokio::test]
+#[cfg(feature = "charset")]
+async fn response_text_meta_charset() {
+    let _ = env_logger::try_init();
+
+    let server = server::http(move |_req| async {
+        // "你好" in GBK
+        let mut body = b"<html><head><meta charset=\"gbk\"></head><body>".to_vec();
+        body.extend_from_slice(&[0xc4, 0xe3, 0xba, 0xc3]);
+        http::Response::builder()
+            .header("content-type", "text/html")
+            .body(body.into())
+            .unwrap()
+    });
+
+    let text = Client::new()
+        .get(format!("http://{}/gbk", server.addr()))
+        .send()
+        .await
+        .expect("Failed to get")
+        .text()
+        .await
+        .expect("Failed to get text");
+    assert!(text.ends_with("<body>你好"), "{}", text);
+}
+
+#[tokio::test]
+#[cfg(feature = "charset-detection")]
+async fn response_text_charset_detection() {
+    let _ = env_logger::try_init();
+
+    let server = server::http(move |_req| async {
+        http::Response::builder()
+            .header("content-type", "text/plain; charset=iso-8859-1")
+            .body("こんにちは、世界".into())
+            .unwrap()
+    });
+
+    let text = Client::new()
+        .get(format!("http://{}/mislabeled", server.addr()))
+        .send()
+        .await
+        .expect("Failed to get")
+        .text_with_charset_detection()
+        .await
+        .expect("Failed to get text");
+    assert_eq!(text, "こんにちは、世界");
+}
+
+#[tokio::test]
+#[cfg(feature = "json")]
+async fn response_json_error_has_path() {
+    #[derive(Debug, serde::Deserialize)]
+    #[allow(dead_code)]
+    struct Item {
+        id: u64,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    #[allow(dead_code)]
+    struct Page {
+        items: Vec<Item>,
+    }
+
+    let _ = env_logger::try_init();
+
+    let server = server::http(move |_req| async {
+        http::Response::new(r#"{"items":[{"id":1},{"id":"2"}]}"#.into())
+    });
+
+    let err = Client::new()
+        .get(format!("http://{}/json", server.addr()))
+        .send()
+        .await
+        .expect("Failed to get")
+        .json::<Page>()
+        .await
+        .unwrap_err();
+
+    assert!(err.is_decode());
+    assert_eq!(err.json_path(), Some("items[1].id"));
+    let msg = err.to_string();
+    assert!(msg.contains("items[1].id: invalid type"), "{}", msg);
+    assert!(msg.contains(r#"{\"items\":"#), "{}", msg);
+}
+
 #[tokio::test]
 async fn body_pipe_response() {
     use http_body_util::BodyExt;
@@ -397,6 +525,41 @@ async fn overridden_dns_resolution_with_gai() {
     assert_eq!("Hello", text);
 }
 
+#[tokio::test]
+async fn overridden_dns_resolution_with_hosts_file() {
+    let server = server::http(move |req| async move {
+        assert_eq!(req.headers()["host"], "staging.rust-lang.org");
+        http::Response::new("Hello".into())
+    });
+
+    let path = std::env::temp_dir().join(format!("rquest-hosts-{}", server.addr().port()));
+    std::fs::write(
+        &path,
+        "# staging\n127.0.0.1 staging.rust-lang.org api.rust-lang.org\n",
+    )
+    .unwrap();
+
+    let client = rquest::Client::builder()
+        .no_proxy()
+        .hosts_file(&path)
+        .build()
+        .expect("client builder");
+    let _ = std::fs::remove_file(&path);
+
+    let url = format!(
+        "http://staging.rust-lang.org:{}/domain_override",
+        server.addr().port()
+    );
+    let res = client.get(&url).send().await.expect("request");
+    assert_eq!(res.text().await.expect("Failed to get text"), "Hello");
+
+    let err = rquest::Client::builder()
+        .hosts_file("/nonexistent/hosts")
+        .build()
+        .unwrap_err();
+    assert!(err.is_builder());
+}
+
 #[tokio::test]
 async fn overridden_dns_resolution_with_gai_multiple() {
     let _ = env_logger::builder().is_test(true).try_init();
@@ -718,3 +881,2051 @@ async fn connection_pool_cache() {
     assert_eq!(resp.status(), rquest::StatusCode::OK);
     assert_eq!(resp.version(), http::Version::HTTP_2);
 }
+
+#[tokio::test]
+async fn client_layer_wraps_requests() {
+    use tower::util::{MapRequestLayer, MapResponseLayer};
+
+    let server = server::http(move |req| async move {
+        assert_eq!(req.headers()["x-signature"], "signed");
+        http::Response::default()
+    });
+
+    let client = Client::builder()
+        .layer(MapRequestLayer::new(|mut req: rquest::Request| {
+            req.headers_mut()
+                .insert("x-signature", http::HeaderValue::from_static("signed"));
+            req
+        }))
+        .layer(MapResponseLayer::new(|mut res: rquest::Response| {
+            res.extensions_mut().insert("seen");
+            res
+        }))
+        .build()
+        .unwrap();
+
+    let url = format!("http://{}/layer", server.addr());
+
+    let res = client.get(&url).send().await.unwrap();
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+    assert_eq!(res.extensions().get::<&str>(), Some(&"seen"));
+
+    // a cloned client keeps its layers
+    let res = client.cloned().get(&url).send().await.unwrap();
+    assert_eq!(res.extensions().get::<&str>(), Some(&"seen"));
+}
+
+#[tokio::test]
+async fn client_layer_outlives_temporary_client() {
+    use tower::util::MapRequestLayer;
+
+    let server = server::http(move |_req| async move { http::Response::new("ok".into()) });
+    let url = format!("http://{}/layer", server.addr());
+
+    // the client is dropped once the request is built, before it is polled
+    let pending = Client::builder()
+        .layer(MapRequestLayer::new(|req: rquest::Request| req))
+        .build()
+        .unwrap()
+        .get(&url)
+        .send();
+
+    let res = pending.await.unwrap();
+    assert_eq!(res.text().await.unwrap(), "ok");
+}
+
+#[tokio::test]
+async fn pool_stats_track_connections() {
+    let server = server::http(move |_req| async move { http::Response::new("ok".into()) });
+
+    let client = Client::builder().no_proxy().build().unwrap();
+    let url = format!("http://{}/", server.addr());
+
+    let stats = client.pool_stats();
+    assert!(stats.hosts().is_empty());
+    assert_eq!(stats.connections_created(), 0);
+
+    for _ in 0..2 {
+        let res = client.get(&url).send().await.unwrap();
+        assert_eq!(res.text().await.unwrap(), "ok");
+        // give the connection time to return to the pool
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+
+    let stats = client.pool_stats();
+    assert_eq!(stats.connections_created(), 1);
+    assert_eq!(stats.connections_reused(), 1);
+    assert_eq!(stats.hosts().len(), 1);
+
+    let host = &stats.hosts()[0];
+    assert_eq!(host.origin().port_u16(), Some(server.addr().port()));
+    assert_eq!(host.idle(), 1);
+    assert_eq!(host.in_flight(), 0);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn unix_socket_transport() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let path = std::env::temp_dir().join(format!("rquest-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = tokio::net::UnixListener::bind(&path).unwrap();
+
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0; 4096];
+        let n = stream.read(&mut buf).await.unwrap();
+        let req = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\nok")
+            .await
+            .unwrap();
+        req
+    });
+
+    let res = rquest::Client::builder()
+        .unix_socket(&path)
+        .build()
+        .unwrap()
+        .get("http://docker.local/version")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+    assert_eq!(res.text().await.unwrap(), "ok");
+
+    let req = server.await.unwrap();
+    assert!(req.starts_with("get /version http/1.1\r\n"));
+    assert!(req.contains("host: docker.local\r\n"));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn upload_and_download_progress() {
+    use std::sync::{Arc, Mutex};
+
+    let server = server::http(move |req| async move {
+        let body = req.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body.len(), 1000);
+
+        http::Response::new(rquest::Body::from(vec![b'x'; 4096]))
+    });
+
+    let uploaded = Arc::new(Mutex::new(Vec::new()));
+    let downloaded = Arc::new(Mutex::new(Vec::new()));
+
+    let res = Client::builder()
+        .no_proxy()
+        .build()
+        .unwrap()
+        .post(format!("http://{}/progress", server.addr()))
+        .body(vec![b'a'; 1000])
+        .on_upload_progress({
+            let uploaded = uploaded.clone();
+            move |sent, total| uploaded.lock().unwrap().push((sent, total))
+        })
+        .on_download_progress({
+            let downloaded = downloaded.clone();
+            move |received, total| downloaded.lock().unwrap().push((received, total))
+        })
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.bytes().await.unwrap().len(), 4096);
+
+    assert_eq!(uploaded.lock().unwrap().last(), Some(&(1000, Some(1000))));
+
+    let downloaded = downloaded.lock().unwrap();
+    assert_eq!(downloaded.last(), Some(&(4096, Some(4096))));
+    assert!(downloaded.windows(2).all(|w| w[0].0 < w[1].0));
+}
+
+#[tokio::test]
+async fn digest_auth_answers_challenge() {
+    let server = server::http(move |req| async move {
+        match req.headers().get("authorization") {
+            None => http::Response::builder()
+                .status(401)
+                .header(
+                    "www-authenticate",
+                    "Digest realm=\"test\", qop=\"auth\", nonce=\"abc\", opaque=\"xyz\"",
+                )
+                .body(Default::default())
+                .unwrap(),
+            Some(auth) => {
+                let auth = auth.to_str().unwrap();
+                assert!(auth.starts_with("Digest username=\"user\", realm=\"test\""));
+                assert!(auth.contains("uri=\"/secret?page=1\""));
+                assert!(auth.contains("qop=auth, nc=00000001"));
+                assert!(auth.contains("opaque=\"xyz\""));
+                http::Response::new("ok".into())
+            }
+        }
+    });
+
+    let res = Client::builder()
+        .no_proxy()
+        .build()
+        .unwrap()
+        .post(format!("http://{}/secret?page=1", server.addr()))
+        .body("data")
+        .digest_auth("user", "passwd")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+    assert_eq!(res.text().await.unwrap(), "ok");
+}
+
+#[tokio::test]
+async fn token_provider_refreshes_rejected_token() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Provider(AtomicUsize);
+
+    impl rquest::TokenProvider for Provider {
+        fn token(&self) -> rquest::TokenFuture<'_> {
+            Box::pin(async { "expired".to_owned() })
+        }
+
+        fn refresh(&self) -> rquest::TokenFuture<'_> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { "fresh".to_owned() })
+        }
+    }
+
+    let server = server::http(move |req| async move {
+        match req.headers()["authorization"].to_str().unwrap() {
+            "Bearer fresh" => http::Response::new("ok".into()),
+            _ => http::Response::builder()
+                .status(401)
+                .body(Default::default())
+                .unwrap(),
+        }
+    });
+
+    let provider = Arc::new(Provider(AtomicUsize::new(0)));
+    let client = Client::builder()
+        .no_proxy()
+        .token_provider(provider.clone())
+        .build()
+        .unwrap();
+
+    let url = format!("http://{}/", server.addr());
+    for _ in 0..2 {
+        let res = client.get(&url).send().await.unwrap();
+        assert_eq!(res.status(), rquest::StatusCode::OK);
+    }
+    assert_eq!(provider.0.load(Ordering::SeqCst), 1);
+
+    // a request with its own authorization is left alone
+    let res = client
+        .get(&url)
+        .header(AUTHORIZATION, "Bearer mine")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), rquest::StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn sign_aws_v4_signs_final_request() {
+    let server = server::http(move |req| async move {
+        let auth = req.headers()["authorization"].to_str().unwrap();
+        assert!(auth.starts_with("AWS4-HMAC-SHA256 Credential=AKID/"));
+        assert!(auth.contains("/us-east-1/s3/aws4_request, "));
+        // the client's default header is signed too
+        assert!(auth.contains("SignedHeaders=host;x-amz-content-sha256;x-amz-date;x-custom,"));
+        assert_eq!(
+            req.headers()["x-amz-content-sha256"],
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        http::Response::default()
+    });
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-custom", "1".parse().unwrap());
+    let res = Client::builder()
+        .no_proxy()
+        .default_headers(headers)
+        .build()
+        .unwrap()
+        .put(format!("http://{}/bucket/key", server.addr()))
+        .body("hello")
+        .sign_aws_v4(
+            rquest::AwsCredentials::new("AKID", "secret"),
+            "us-east-1",
+            "s3",
+        )
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+}
+
+#[tokio::test]
+async fn sign_aws_v4_signs_redirected_request_again() {
+    let signatures = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let seen = signatures.clone();
+    let server = server::http(move |req| {
+        let path = req.uri().path().to_owned();
+        let auth = req.headers()["authorization"].to_str().unwrap().to_owned();
+        seen.lock().unwrap().push((path, auth));
+        async move {
+            if req.uri().path() == "/bucket/old" {
+                http::Response::builder()
+                    .status(307)
+                    .header("location", "/bucket/new")
+                    .body(Default::default())
+                    .unwrap()
+            } else {
+                http::Response::default()
+            }
+        }
+    });
+
+    let res = Client::builder()
+        .no_proxy()
+        .build()
+        .unwrap()
+        .put(format!("http://{}/bucket/old", server.addr()))
+        .body("hello")
+        .sign_aws_v4(
+            rquest::AwsCredentials::new("AKID", "secret"),
+            "us-east-1",
+            "s3",
+        )
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+
+    let signatures = signatures.lock().unwrap();
+    let [(old, old_auth), (new, new_auth)] = &signatures[..] else {
+        panic!("expected two requests, got {:?}", signatures);
+    };
+    assert_eq!(old, "/bucket/old");
+    assert_eq!(new, "/bucket/new");
+    // the signature covers the path, so the redirected request has its own
+    assert!(new_auth.starts_with("AWS4-HMAC-SHA256 Credential=AKID/"));
+    assert_ne!(old_auth, new_auth);
+}
+
+#[test]
+fn unknown_tls_names_fail_build() {
+    use rquest::EmulationProvider;
+    use rquest::tls::TlsConfig;
+
+    let build = |tls: TlsConfig| {
+        Client::builder()
+            .emulation(EmulationProvider::builder().tls_config(tls).build())
+            .build()
+    };
+
+    let tls = TlsConfig::builder()
+        .ciphers(["TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256"])
+        .curves(["X25519", "P-256"])
+        .signature_algorithms(["ecdsa_secp256r1_sha256"])
+        .build();
+    assert!(build(tls).is_ok());
+
+    let tls = TlsConfig::builder().curves(["X25519", "P-999"]).build();
+    assert!(build(tls).unwrap_err().is_builder());
+
+    let tls = TlsConfig::builder().ciphers(["TLS_NOT_A_CIPHER"]).build();
+    assert!(build(tls).unwrap_err().is_builder());
+
+    let tls = TlsConfig::builder()
+        .signature_algorithms(["not_a_sigalg"])
+        .build();
+    assert!(build(tls).unwrap_err().is_builder());
+}
+
+#[cfg(feature = "ntlm")]
+#[tokio::test]
+async fn ntlm_auth_runs_handshake() {
+    // a challenge with the server challenge 0102030405060708 and no target info pairs
+    const CHALLENGE: &str =
+        "NTLM TlRMTVNTUAACAAAAAAAAADAAAAAFgggAAQIDBAUGBwgAAAAAAAAAAAQABAAwAAAAAAAAAA==";
+
+    let server = server::http(move |req| async move {
+        let auth = req
+            .headers()
+            .get("authorization")
+            .map(|auth| auth.to_str().unwrap().to_owned());
+        match auth.as_deref() {
+            None => http::Response::builder()
+                .status(401)
+                .header("www-authenticate", "Negotiate")
+                .header("www-authenticate", "NTLM")
+                .body("denied".into())
+                .unwrap(),
+            // the negotiate message
+            Some(auth) if auth.starts_with("NTLM TlRMTVNTUAABAA") => http::Response::builder()
+                .status(401)
+                .header("www-authenticate", CHALLENGE)
+                .body("denied".into())
+                .unwrap(),
+            // the authenticate message
+            Some(auth) if auth.starts_with("NTLM TlRMTVNTUAADAA") => {
+                http::Response::new("ok".into())
+            }
+            Some(auth) => panic!("unexpected authorization: {}", auth),
+        }
+    });
+
+    let res = Client::builder()
+        .no_proxy()
+        .build()
+        .unwrap()
+        .post(format!("http://{}/secret", server.addr()))
+        .body("data")
+        .ntlm_auth("CORP", "user", "passwd")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+    assert_eq!(res.text().await.unwrap(), "ok");
+}
+
+#[cfg(feature = "ntlm")]
+#[tokio::test]
+async fn ntlm_auth_handshake_stays_on_one_connection() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    const CHALLENGE: &str =
+        "NTLM TlRMTVNTUAACAAAAAAAAADAAAAAFgggAAQIDBAUGBwgAAAAAAAAAAAQABAAwAAAAAAAAAA==";
+
+    // whether a connection received the negotiate message; the server clones
+    // its handler for each connection, and each clone starts over
+    struct Negotiated(Arc<AtomicBool>);
+
+    impl Clone for Negotiated {
+        fn clone(&self) -> Self {
+            Negotiated(Arc::new(AtomicBool::new(false)))
+        }
+    }
+
+    let negotiated = Negotiated(Arc::new(AtomicBool::new(false)));
+    let server = server::http(move |req| {
+        let negotiated = negotiated.0.clone();
+        async move {
+            let auth = req
+                .headers()
+                .get("authorization")
+                .map(|auth| auth.to_str().unwrap().to_owned());
+            // keep the handshakes overlapping
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            match auth.as_deref() {
+                None => http::Response::builder()
+                    .status(401)
+                    .header("www-authenticate", "NTLM")
+                    .body("denied".into())
+                    .unwrap(),
+                Some(auth) if auth.starts_with("NTLM TlRMTVNTUAABAA") => {
+                    negotiated.store(true, Ordering::SeqCst);
+                    http::Response::builder()
+                        .status(401)
+                        .header("www-authenticate", CHALLENGE)
+                        .body("denied".into())
+                        .unwrap()
+                }
+                Some(auth) if auth.starts_with("NTLM TlRMTVNTUAADAA") => {
+                    if negotiated.load(Ordering::SeqCst) {
+                        http::Response::new("ok".into())
+                    } else {
+                        http::Response::builder()
+                            .status(400)
+                            .body("authenticated on another connection".into())
+                            .unwrap()
+                    }
+                }
+                Some(auth) => panic!("unexpected authorization: {}", auth),
+            }
+        }
+    });
+
+    let client = Client::builder().no_proxy().build().unwrap();
+    let url = format!("http://{}/secret", server.addr());
+    let futs = (0..8).map(|_| async {
+        let res = client
+            .get(&url)
+            .ntlm_auth("CORP", "user", "passwd")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), rquest::StatusCode::OK);
+        assert_eq!(res.text().await.unwrap(), "ok");
+    });
+    futures_util::future::join_all(futs).await;
+}
+
+#[tokio::test]
+async fn clear_dns_cache_clears_resolver() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use rquest::dns::{Name, Resolve, Resolving};
+
+    #[derive(Default)]
+    struct Counting {
+        clears: AtomicUsize,
+    }
+
+    impl Resolve for Counting {
+        fn resolve(&self, _: Name) -> Resolving {
+            Box::pin(async { Err("unused".into()) })
+        }
+
+        fn clear_cache(&self) {
+            self.clears.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let resolver = Arc::new(Counting::default());
+    let client = Client::builder()
+        .dns_resolver(resolver.clone())
+        // the overrides wrap the resolver, and pass the call on
+        .resolve("example.com", "127.0.0.1:80".parse().unwrap())
+        .build()
+        .unwrap();
+
+    client.clear_dns_cache();
+    client.cloned().clear_dns_cache();
+
+    assert_eq!(resolver.clears.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn ip_strategy_filters_addresses() {
+    use rquest::dns::IpStrategy;
+
+    let server = server::http(move |_req| async { http::Response::new("Hello".into()) });
+    let url = format!("http://localhost:{}/", server.addr().port());
+    let loopback = |ip: &str| std::net::SocketAddr::new(ip.parse().unwrap(), 0);
+
+    let client = Client::builder()
+        .no_proxy()
+        .resolve_to_addrs("localhost", &[loopback("::1"), loopback("127.0.0.1")])
+        .ip_strategy(IpStrategy::Ipv4Only)
+        .build()
+        .unwrap();
+    let res = client.get(&url).send().await.unwrap();
+    assert_eq!(res.remote_addr(), Some(server.addr()));
+
+    let err = Client::builder()
+        .no_proxy()
+        .resolve("localhost", loopback("127.0.0.1"))
+        .ip_strategy(IpStrategy::Ipv6Only)
+        .build()
+        .unwrap()
+        .get(&url)
+        .send()
+        .await
+        .unwrap_err();
+    assert!(err.is_connect());
+}
+
+#[tokio::test]
+async fn request_local_address_overrides_client() {
+    let server = server::http(move |_req| async { http::Response::new("Hello".into()) });
+    let url = format!("http://{}/", server.addr());
+
+    let client = Client::builder()
+        .no_proxy()
+        .local_address(std::net::IpAddr::from([127, 0, 0, 1]))
+        .build()
+        .unwrap();
+
+    let res = client.get(&url).send().await.unwrap();
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+
+    // 192.0.2.0/24 is reserved for documentation, so it can't be bound, and the
+    // pooled connection bound to 127.0.0.1 must not be reused
+    let err = client
+        .get(&url)
+        .local_address(std::net::IpAddr::from([192, 0, 2, 1]))
+        .send()
+        .await
+        .unwrap_err();
+    assert!(err.is_connect());
+}
+
+#[tokio::test]
+async fn tcp_options_apply_to_connections() {
+    use std::time::Duration;
+
+    let server = server::http(move |_req| async { http::Response::new("Hello".into()) });
+
+    let client = Client::builder()
+        .no_proxy()
+        .tcp_options(
+            rquest::TcpOptions::new()
+                .nodelay(false)
+                .keepalive(Duration::from_secs(60))
+                .keepalive_interval(Duration::from_secs(10))
+                .keepalive_retries(3)
+                .send_buffer_size(64 * 1024)
+                .recv_buffer_size(64 * 1024),
+        )
+        .build()
+        .unwrap();
+
+    let res = client
+        .get(format!("http://{}/", server.addr()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.text().await.unwrap(), "Hello");
+}
+
+#[tokio::test]
+async fn metrics_observe_requests_and_connections() {
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Observed {
+        requests: Arc<Mutex<Vec<rquest::RequestMetrics>>>,
+        connections: Arc<Mutex<Vec<rquest::ConnectMetrics>>>,
+    }
+
+    impl rquest::MetricsObserver for Observed {
+        fn on_request(&self, metrics: &rquest::RequestMetrics) {
+            self.requests.lock().unwrap().push(metrics.clone());
+        }
+
+        fn on_connect(&self, metrics: &rquest::ConnectMetrics) {
+            self.connections.lock().unwrap().push(metrics.clone());
+        }
+    }
+
+    let server = server::http(move |req| async move {
+        if req.uri().path() == "/redirect" {
+            http::Response::builder()
+                .status(302)
+                .header("location", "/body")
+                .body(Default::default())
+                .unwrap()
+        } else {
+            http::Response::new("Hello".into())
+        }
+    });
+
+    let observed = Observed::default();
+    let client = Client::builder()
+        .no_proxy()
+        .metrics(observed.clone())
+        .build()
+        .unwrap();
+
+    let url = format!("http://{}/redirect", server.addr());
+    let res = client.post(&url).body("ping").send().await.unwrap();
+    assert!(observed.requests.lock().unwrap().is_empty());
+    assert_eq!(res.text().await.unwrap(), "Hello");
+
+    let requests = observed.requests.lock().unwrap().clone();
+    assert_eq!(requests.len(), 1);
+    let metrics = &requests[0];
+    assert_eq!(metrics.method(), "POST");
+    assert_eq!(metrics.url().path(), "/body");
+    assert_eq!(metrics.status(), Some(rquest::StatusCode::OK));
+    assert_eq!(metrics.attempts(), 2);
+    assert_eq!(metrics.bytes_sent(), Some(4));
+    assert_eq!(metrics.bytes_received(), 5);
+    assert!(metrics.time_to_headers().unwrap() <= metrics.duration());
+    assert!(!metrics.failed());
+
+    let connections = observed.connections.lock().unwrap().clone();
+    assert_eq!(connections.len(), 1);
+    assert!(!connections[0].failed());
+
+    // a request failing to connect is observed without a response
+    let err = client.get("http://127.0.0.1:1/").send().await.unwrap_err();
+    assert!(err.is_connect());
+
+    let requests = observed.requests.lock().unwrap().clone();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1].status(), None);
+    assert!(requests[1].failed());
+    assert!(observed.connections.lock().unwrap()[1].failed());
+}
+
+#[tokio::test]
+async fn har_records_each_response() {
+    let server = server::http(move |req| async move {
+        if req.uri().path() == "/redirect" {
+            http::Response::builder()
+                .status(302)
+                .header("location", "/body")
+                .body(Default::default())
+                .unwrap()
+        } else {
+            http::Response::builder()
+                .header("content-type", "text/plain")
+                .body("Hello".into())
+                .unwrap()
+        }
+    });
+
+    let har = rquest::har::HarRecorder::new().record_bodies(1024);
+    let client = Client::builder()
+        .no_proxy()
+        .har(har.clone())
+        .build()
+        .unwrap();
+
+    let url = format!("http://{}/redirect?q=1", server.addr());
+    let res = client.get(&url).send().await.unwrap();
+    // the final response is recorded once its body is read
+    assert_eq!(har.len(), 1);
+    assert_eq!(res.text().await.unwrap(), "Hello");
+    assert_eq!(har.len(), 2);
+
+    #[cfg(feature = "json")]
+    {
+        let json = har.archive().to_json();
+        assert!(json.contains(r#""version": "1.2""#));
+        assert!(json.contains(r#""redirectURL": "/body""#));
+        assert!(json.contains(r#""name": "q","#));
+        assert!(json.contains(r#""text": "Hello""#));
+        assert!(json.contains(r#""status": 302"#));
+        assert!(json.contains(r#""status": 200"#));
+    }
+
+    har.clear();
+    assert!(har.archive().is_empty());
+}
+
+#[tokio::test]
+async fn verbose_dumps_request_and_response_heads() {
+    let server = server::http(move |_req| async move {
+        http::Response::builder()
+            .header("x-reply", "pong")
+            .body("Hello".into())
+            .unwrap()
+    });
+
+    let (verbose, heads) = rquest::VerboseConfig::channel();
+    let client = Client::builder()
+        .no_proxy()
+        .verbose(verbose)
+        .build()
+        .unwrap();
+
+    let url = format!("http://{}/dump?q=1", server.addr());
+    let res = client
+        .get(&url)
+        .header("x-first", "1")
+        .header("x-second", "2")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+
+    let request = heads.try_recv().unwrap();
+    assert!(
+        request.starts_with("> GET /dump?q=1 HTTP/1.1\n"),
+        "{}",
+        request
+    );
+    let first = request.find("> x-first: 1\n").unwrap();
+    let second = request.find("> x-second: 2\n").unwrap();
+    assert!(first < second);
+    assert!(request.contains(&format!("> host: {}\n", server.addr())));
+
+    let response = heads.try_recv().unwrap();
+    assert!(response.starts_with("< HTTP/1.1 200 OK\n"), "{}", response);
+    assert!(response.contains("< x-reply: pong\n"));
+}
+
+#[test]
+fn to_curl_reproduces_request() {
+    use rquest::header::{HeaderMap, HeaderName, HeaderValue};
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-default", HeaderValue::from_static("client"));
+    let client = rquest::Client::builder()
+        .default_headers(headers)
+        .proxy(
+            rquest::Proxy::all("http://proxy.local:8080")
+                .unwrap()
+                .basic_auth("user", "pass"),
+        )
+        .build()
+        .unwrap();
+
+    let curl = client
+        .post("http://example.com/echo?q=1")
+        .header("x-first", "it's")
+        .header("x-second", "2")
+        .headers_order(vec![
+            HeaderName::from_static("x-second"),
+            HeaderName::from_static("x-default"),
+            HeaderName::from_static("x-first"),
+        ])
+        .body("{\"a\":1}")
+        .to_curl()
+        .unwrap();
+
+    assert!(curl.starts_with("curl \\\n  -X 'POST'"), "{}", curl);
+    let second = curl.find("-H 'x-second: 2'").unwrap();
+    let default = curl.find("-H 'x-default: client'").unwrap();
+    let first = curl.find(r"-H 'x-first: it'\''s'").unwrap();
+    assert!(second < default && default < first, "{}", curl);
+    assert!(curl.contains("-x 'http://proxy.local:8080'"), "{}", curl);
+    assert!(
+        curl.contains("--proxy-header 'proxy-authorization: Basic dXNlcjpwYXNz'"),
+        "{}",
+        curl
+    );
+    assert!(curl.contains("--data-binary '{\"a\":1}'"), "{}", curl);
+    assert!(curl.ends_with("'http://example.com/echo?q=1'"), "{}", curl);
+}
+
+#[cfg(feature = "stream")]
+#[test]
+fn to_curl_needs_reusable_body() {
+    let stream = futures_util::stream::once(async { Ok::<_, std::io::Error>("hello") });
+    let builder = rquest::Client::new()
+        .post("http://example.com/")
+        .body(rquest::Body::wrap_stream(stream));
+
+    assert!(builder.to_curl().is_none());
+}
+
+#[tokio::test]
+async fn error_kind_classifies_failures() {
+    use std::sync::Arc;
+
+    use rquest::ErrorKind;
+    use rquest::dns::{Name, Resolve, Resolving};
+
+    struct Failing;
+
+    impl Resolve for Failing {
+        fn resolve(&self, _: Name) -> Resolving {
+            Box::pin(async { Err("no such host".into()) })
+        }
+    }
+
+    let client = Client::builder()
+        .dns_resolver(Arc::new(Failing))
+        .no_proxy()
+        .build()
+        .unwrap();
+    let err = client
+        .get("http://missing.example/")
+        .send()
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Dns);
+    assert!(err.is_retry_safe());
+
+    // bind and drop a listener to find a port nothing listens on
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let client = Client::builder().no_proxy().build().unwrap();
+    let err = client
+        .get(format!("http://{addr}/"))
+        .send()
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Connect);
+    assert!(err.is_retry_safe());
+
+    let server = server::http(move |_req| async {
+        http::Response::builder()
+            .status(503)
+            .body(Default::default())
+            .unwrap()
+    });
+    let err = client
+        .get(format!("http://{}/", server.addr()))
+        .send()
+        .await
+        .unwrap()
+        .error_for_status()
+        .unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::Status(rquest::StatusCode::SERVICE_UNAVAILABLE)
+    );
+    assert!(!err.is_retry_safe());
+}
+
+#[tokio::test]
+#[cfg(feature = "html")]
+async fn response_html() {
+    let _ = env_logger::try_init();
+
+    let server = server::http(move |_req| async {
+        http::Response::builder()
+            .header("content-type", "text/html; charset=utf-8")
+            .body(
+                r#"<html><head><title>Items</title></head>
+                <body><a class="item" href="/1">One</a><a class="item" href="/2">Two</a></body></html>"#
+                    .into(),
+            )
+            .unwrap()
+    });
+
+    let doc = Client::new()
+        .get(format!("http://{}/items", server.addr()))
+        .send()
+        .await
+        .expect("Failed to get")
+        .html()
+        .await
+        .expect("Failed to parse html");
+
+    assert_eq!(doc.title().as_deref(), Some("Items"));
+    let links = doc.select("a.item").unwrap();
+    let hrefs = links
+        .iter()
+        .filter_map(|a| a.attr("href"))
+        .collect::<Vec<_>>();
+    assert_eq!(hrefs, ["/1", "/2"]);
+    assert_eq!(links[1].text(), "Two");
+}
+
+#[tokio::test]
+#[cfg(all(feature = "json", feature = "stream"))]
+async fn response_json_lines() {
+    use futures_util::StreamExt;
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Row {
+        id: u32,
+    }
+
+    let _ = env_logger::try_init();
+
+    let server = server::http(move |_req| async {
+        // lines split across chunks, a blank line, a bad line, and no final newline
+        let chunks = [
+            "{\"id\":1}\n{\"i",
+            "d\":2}\r\n\n",
+            "{\"id\":\"x\"}\n",
+            "{\"id\":3}",
+        ];
+        let body = rquest::Body::wrap_stream(futures_util::stream::iter(
+            chunks.map(Ok::<_, std::convert::Infallible>),
+        ));
+        http::Response::new(body)
+    });
+
+    let rows = Client::new()
+        .get(format!("http://{}/export", server.addr()))
+        .send()
+        .await
+        .expect("Failed to get")
+        .json_lines::<Row>()
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0].as_ref().unwrap(), &Row { id: 1 });
+    assert_eq!(rows[1].as_ref().unwrap(), &Row { id: 2 });
+    assert!(rows[2].as_ref().unwrap_err().is_decode());
+    assert_eq!(rows[3].as_ref().unwrap(), &Row { id: 3 });
+}
+
+#[tokio::test]
+async fn range_request_byte_ranges() {
+    let _ = env_logger::try_init();
+
+    let server = server::http(move |req| async move {
+        let range = req.headers()["range"].to_str().unwrap().to_owned();
+        let res = http::Response::builder().status(206);
+        match range.as_str() {
+            "bytes=0-4" => res
+                .header("content-range", "bytes 0-4/11")
+                .body("hello".into())
+                .unwrap(),
+            "bytes=0-4,6-10" => res
+                .header("content-type", "multipart/byteranges; boundary=SEP")
+                .body(
+                    "--SEP\r\ncontent-range: bytes 0-4/11\r\n\r\nhello\r\n\
+                     --SEP\r\ncontent-range: bytes 6-10/11\r\n\r\nworld\r\n--SEP--\r\n"
+                        .into(),
+                )
+                .unwrap(),
+            other => panic!("unexpected range {other}"),
+        }
+    });
+
+    let client = Client::new();
+    let url = format!("http://{}/file", server.addr());
+
+    let parts = client
+        .get(&url)
+        .range(0..5)
+        .send()
+        .await
+        .unwrap()
+        .byte_ranges()
+        .await
+        .unwrap();
+    assert_eq!(parts.len(), 1);
+    assert_eq!(parts[0].range(), 0..5);
+    assert_eq!(parts[0].complete_length(), Some(11));
+    assert_eq!(parts[0].bytes(), "hello");
+
+    let parts = client
+        .get(&url)
+        .ranges([0..5, 6..11])
+        .send()
+        .await
+        .unwrap()
+        .byte_ranges()
+        .await
+        .unwrap();
+    assert_eq!(parts.len(), 2);
+    assert_eq!(parts[1].range(), 6..11);
+    assert_eq!(parts[1].bytes(), "world");
+
+    assert!(
+        client
+            .get(&url)
+            .range(3..3)
+            .build()
+            .unwrap_err()
+            .is_builder()
+    );
+}
+
+#[tokio::test]
+#[cfg(feature = "stream")]
+async fn downloader_writes_segments() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use rquest::downloader::Downloader;
+
+    let _ = env_logger::try_init();
+
+    let content: Vec<u8> = (0..100u8).collect();
+    let requests = Arc::new(AtomicUsize::new(0));
+    let failed_once = Arc::new(AtomicUsize::new(0));
+
+    let server = {
+        let content = content.clone();
+        let requests = requests.clone();
+        server::http(move |req| {
+            let content = content.clone();
+            let requests = requests.clone();
+            let failed_once = failed_once.clone();
+            async move {
+                requests.fetch_add(1, Ordering::SeqCst);
+                let range = req.headers()["range"].to_str().unwrap();
+                let (start, end) = range
+                    .strip_prefix("bytes=")
+                    .and_then(|r| r.split_once('-'))
+                    .unwrap();
+                let start: usize = start.parse().unwrap();
+                let end: usize = end.parse().unwrap();
+
+                // the first segment request after the probe fails once
+                if start == 0 && end > 0 && failed_once.fetch_add(1, Ordering::SeqCst) == 0 {
+                    return http::Response::builder()
+                        .status(500)
+                        .body(Default::default())
+                        .unwrap();
+                }
+
+                http::Response::builder()
+                    .status(206)
+                    .header("etag", "\"v1\"")
+                    .header("content-range", format!("bytes {start}-{end}/100"))
+                    .body(content[start..=end].to_vec().into())
+                    .unwrap()
+            }
+        })
+    };
+
+    let path = std::env::temp_dir().join(format!("rquest-download-{}", server.addr().port()));
+    let progress = Arc::new(AtomicUsize::new(0));
+    let downloaded = {
+        let progress = progress.clone();
+        Downloader::new(Client::new())
+            .segments(4)
+            .min_segment_size(10)
+            .on_progress(move |p| {
+                assert_eq!(p.total(), Some(100));
+                progress.store(p.downloaded() as usize, Ordering::SeqCst);
+            })
+            .download(format!("http://{}/file", server.addr()), &path)
+            .await
+            .unwrap()
+    };
+
+    assert_eq!(downloaded, 100);
+    assert_eq!(std::fs::read(&path).unwrap(), content);
+    assert_eq!(progress.load(Ordering::SeqCst), 100);
+    // the probe, four segments, and the retried one
+    assert_eq!(requests.load(Ordering::SeqCst), 6);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn form_params_keep_order_and_encoding() {
+    let _ = env_logger::try_init();
+
+    let server = server::http(move |req| async move {
+        assert_eq!(req.uri().query(), Some("b=2&a=1&b=3"));
+        assert_eq!(
+            req.headers()["content-type"],
+            "application/x-www-form-urlencoded"
+        );
+        let body = req.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "sort=+date&q=a%20b");
+        http::Response::default()
+    });
+
+    let query: rquest::Params = [("b", 2), ("a", 1), ("b", 3)].into_iter().collect();
+    let form = rquest::Params::new()
+        .append("sort", "+date")
+        .append("q", "a b")
+        .keep("+")
+        .space_as_plus(false);
+
+    let res = Client::new()
+        .post(format!("http://{}/form", server.addr()))
+        .query_params(&query)
+        .form_params(&form)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+}
+
+#[tokio::test]
+async fn base_url_joins_relative_urls() {
+    let server =
+        server::http(
+            move |req| async move { http::Response::new(req.uri().path().to_string().into()) },
+        );
+
+    let client = Client::builder()
+        .base_url(format!("http://{}/api/v2", server.addr()))
+        .build()
+        .unwrap();
+
+    let path = |url: String| {
+        let client = client.clone();
+        async move { client.get(url).send().await.unwrap().text().await.unwrap() }
+    };
+
+    assert_eq!(path("users/42".into()).await, "/api/v2/users/42");
+    assert_eq!(path("/health".into()).await, "/health");
+    assert_eq!(
+        path(format!("http://{}/other", server.addr())).await,
+        "/other"
+    );
+
+    let err = Client::builder().base_url("not a url").build().unwrap_err();
+    assert!(err.is_builder());
+}
+
+#[tokio::test]
+async fn request_extensions_reach_redirect_policy_and_response() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct CorrelationId(u32);
+
+    let server = server::http(move |req| async move {
+        if req.uri().path() == "/start" {
+            http::Response::builder()
+                .status(302)
+                .header("location", "/end")
+                .body(Default::default())
+                .unwrap()
+        } else {
+            http::Response::default()
+        }
+    });
+
+    let policy = rquest::redirect::Policy::custom(|attempt| {
+        assert_eq!(
+            attempt.extensions().get::<CorrelationId>(),
+            Some(&CorrelationId(7))
+        );
+        attempt.follow()
+    });
+
+    let res = Client::builder()
+        .redirect(policy)
+        .build()
+        .unwrap()
+        .get(format!("http://{}/start", server.addr()))
+        .extension(CorrelationId(7))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.url().path(), "/end");
+    assert_eq!(
+        res.extensions().get::<CorrelationId>(),
+        Some(&CorrelationId(7))
+    );
+}
+
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn file_body_can_be_cloned() {
+    let server = server::http(move |req| async move {
+        let body = req.into_body().collect().await.unwrap().to_bytes();
+        http::Response::new(body.into())
+    });
+
+    let path = std::env::temp_dir().join(format!("rquest-body-{}", server.addr().port()));
+    std::fs::write(&path, "file contents").unwrap();
+
+    let client = Client::new();
+    let req = client
+        .post(format!("http://{}/upload", server.addr()))
+        .body(rquest::Body::file(&path).await.unwrap())
+        .build()
+        .unwrap();
+    let clone = req.try_clone().expect("file body is replayable");
+
+    for req in [req, clone] {
+        let text = client.execute(req).await.unwrap().text().await.unwrap();
+        assert_eq!(text, "file contents");
+    }
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn expect_continue_waits_for_server() {
+    let server = server::http(move |req| async move {
+        assert_eq!(req.headers()["expect"], "100-continue");
+        let body = req.into_body().collect().await.unwrap().to_bytes();
+        http::Response::new(body.into())
+    });
+
+    let client = Client::builder()
+        .expect_continue_threshold(4)
+        .expect_continue_timeout(std::time::Duration::from_secs(30))
+        .build()
+        .unwrap();
+
+    let start = std::time::Instant::now();
+    let res = client
+        .post(format!("http://{}/upload", server.addr()))
+        .body("hello")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.text().await.unwrap(), "hello");
+    assert!(start.elapsed() < std::time::Duration::from_secs(10));
+}
+
+#[tokio::test]
+async fn expect_continue_skips_rejected_body() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let server = server::http(move |_req| async move {
+        http::Response::builder()
+            .status(413)
+            .body("too large".into())
+            .unwrap()
+    });
+
+    let client = Client::builder()
+        .expect_continue_timeout(std::time::Duration::from_secs(30))
+        .build()
+        .unwrap();
+
+    let sent = Arc::new(AtomicBool::new(false));
+    let res = client
+        .put(format!("http://{}/upload", server.addr()))
+        .body(vec![0u8; 64 * 1024])
+        .expect_continue(true)
+        .on_upload_progress({
+            let sent = sent.clone();
+            move |_, _| sent.store(true, Ordering::SeqCst)
+        })
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), rquest::StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(res.text().await.unwrap(), "too large");
+    assert!(!sent.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn trailers_are_sent_and_received() {
+    let server = server::http(move |req| async move {
+        assert_eq!(req.headers()[TRANSFER_ENCODING], "chunked");
+        let collected = req.into_body().collect().await.unwrap();
+        assert_eq!(collected.trailers().unwrap()["x-checksum"], "request");
+        assert_eq!(collected.to_bytes(), "hello");
+
+        let body = rquest::Body::from("world").with_trailers(async {
+            let mut trailers = HeaderMap::new();
+            trailers.insert("x-checksum", "response".parse().unwrap());
+            Ok::<_, std::io::Error>(trailers)
+        });
+        http::Response::builder()
+            .header("trailer", "x-checksum")
+            .body(body)
+            .unwrap()
+    });
+
+    let body = rquest::Body::from("hello").with_trailers(async {
+        let mut trailers = HeaderMap::new();
+        trailers.insert("x-checksum", "request".parse().unwrap());
+        Ok::<_, std::io::Error>(trailers)
+    });
+    let mut res = Client::new()
+        .post(format!("http://{}/trailers", server.addr()))
+        .header("te", "trailers")
+        .header("trailer", "x-checksum")
+        .body(body)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.chunk().await.unwrap().unwrap(), "world");
+    let trailers = res.trailers().await.unwrap().unwrap();
+    assert_eq!(trailers["x-checksum"], "response");
+}
+
+#[tokio::test]
+async fn informational_responses_are_observed() {
+    use std::sync::{Arc, Mutex};
+    use tokio::io::AsyncWriteExt;
+
+    let server = server::low_level_with_response(|_raw_request, client_socket| {
+        Box::new(async move {
+            client_socket
+                .write_all(
+                    b"HTTP/1.1 102 Processing\r\n\r\n\
+                      HTTP/1.1 103 Early Hints\r\nLink: </style.css>; rel=preload\r\n\r\n\
+                      HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello",
+                )
+                .await
+                .expect("response write_all failed");
+            client_socket.flush().await.expect("response flush failed");
+        })
+    });
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let res = Client::new()
+        .get(format!("http://{}/hints", server.addr()))
+        .on_informational({
+            let seen = seen.clone();
+            move |status, headers| {
+                let link = headers.get("link").cloned();
+                seen.lock().unwrap().push((status, link));
+            }
+        })
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+    assert_eq!(res.text().await.unwrap(), "hello");
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 2);
+    assert_eq!(seen[0], (rquest::StatusCode::PROCESSING, None));
+    assert_eq!(seen[1].0, rquest::StatusCode::EARLY_HINTS);
+    assert_eq!(seen[1].1.as_ref().unwrap(), "</style.css>; rel=preload");
+}
+
+#[tokio::test]
+async fn pushed_is_empty_without_http2_push() {
+    use futures_util::StreamExt;
+
+    let server = server::http(move |_req| async move { http::Response::new("hello".into()) });
+
+    let mut res = Client::new()
+        .get(format!("http://{}/push", server.addr()))
+        .send()
+        .await
+        .unwrap();
+
+    let mut pushed = res.pushed();
+    assert_eq!(res.text().await.unwrap(), "hello");
+    assert!(pushed.next().await.is_none());
+}
+
+#[tokio::test]
+async fn priority_sets_header() {
+    let server = server::http(move |req| async move {
+        assert_eq!(req.headers()["priority"], "u=1, i");
+        http::Response::default()
+    });
+
+    let res = Client::new()
+        .get(format!("http://{}/priority", server.addr()))
+        .priority(1, true)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+
+    let req = Client::new()
+        .get("https://example.com/")
+        .priority(3, true)
+        .build()
+        .unwrap();
+    assert_eq!(req.headers()["priority"], "i");
+
+    let err = Client::new()
+        .get("https://example.com/")
+        .priority(8, false)
+        .build()
+        .unwrap_err();
+    assert!(err.is_builder());
+}
+
+#[tokio::test]
+async fn http2_adaptive_window_downloads() {
+    let server = server::http(move |req| async move {
+        assert_eq!(req.version(), http::Version::HTTP_2);
+        http::Response::new(vec![b'x'; 1024 * 1024].into())
+    });
+
+    let client = Client::builder()
+        .http2_only()
+        .http2_initial_stream_window_size(65535)
+        .http2_initial_connection_window_size(65535)
+        .http2_adaptive_window(true)
+        .build()
+        .unwrap();
+
+    let res = client
+        .get(format!("http://{}/download", server.addr()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.bytes().await.unwrap().len(), 1024 * 1024);
+}
+
+#[tokio::test]
+async fn http2_keep_alive_pings_idle_connections() {
+    let server = server::http(move |req| async move {
+        assert_eq!(req.version(), http::Version::HTTP_2);
+        http::Response::default()
+    });
+
+    let client = Client::builder()
+        .http2_only()
+        .http2_keep_alive_interval(std::time::Duration::from_millis(50))
+        .http2_keep_alive_timeout(std::time::Duration::from_secs(5))
+        .http2_keep_alive_while_idle(true)
+        .build()
+        .unwrap();
+
+    let url = format!("http://{}/ping", server.addr());
+    let res = client.get(&url).send().await.unwrap();
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    let res = client.get(&url).send().await.unwrap();
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+}
+
+#[tokio::test]
+async fn preconnect_reuses_connection() {
+    let server = server::http(move |_req| async move { http::Response::default() });
+
+    let client = Client::new();
+    let url = format!("http://{}/", server.addr());
+    client.preconnect(&url).await.unwrap();
+    let stats = client.pool_stats();
+    assert_eq!(
+        stats.hosts().iter().map(|host| host.idle()).sum::<usize>(),
+        1
+    );
+    client
+        .preconnect_many([url.clone(), url.clone()])
+        .await
+        .unwrap();
+
+    let res = client.get(&url).send().await.unwrap();
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+    assert!(client.pool_stats().connections_reused() >= 1);
+
+    let err = client.preconnect("ftp://example.com").await.unwrap_err();
+    assert!(err.is_builder());
+}
+
+#[tokio::test]
+async fn close_connection_skips_pool() {
+    let server = server::http(move |_req| async move { http::Response::default() });
+
+    let client = Client::builder().no_proxy().build().unwrap();
+    let url = format!("http://{}/", server.addr());
+
+    for close in [false, true, false] {
+        let res = client
+            .get(&url)
+            .close_connection(close)
+            .send()
+            .await
+            .unwrap();
+        res.bytes().await.unwrap();
+        // give the connection time to return to the pool
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+
+    // the closing request neither took the idle connection nor left its own
+    let stats = client.pool_stats();
+    assert_eq!(stats.connections_created(), 2);
+    assert_eq!(stats.connections_reused(), 1);
+    assert_eq!(stats.hosts()[0].idle(), 1);
+}
+
+#[tokio::test]
+async fn pool_disable_for_host_opens_new_connections() {
+    let server = server::http(move |req| async move {
+        assert_eq!(req.headers()["connection"], "close");
+        http::Response::default()
+    });
+
+    let client = Client::builder()
+        .no_proxy()
+        .pool_disable_for_host("127.0.0.1")
+        .build()
+        .unwrap();
+    let url = format!("http://{}/", server.addr());
+
+    for _ in 0..2 {
+        let res = client.get(&url).send().await.unwrap();
+        assert_eq!(res.status(), rquest::StatusCode::OK);
+        res.bytes().await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+
+    let stats = client.pool_stats();
+    assert_eq!(stats.connections_created(), 2);
+    assert_eq!(stats.connections_reused(), 0);
+}
+
+#[tokio::test]
+async fn pool_max_requests_retires_connections() {
+    let server = server::http(move |_req| async move { http::Response::default() });
+
+    let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let client = Client::builder()
+        .pool_max_requests(1)
+        .pool_events(move |event| recorded.lock().unwrap().push(event.clone()))
+        .build()
+        .unwrap();
+
+    let url = format!("http://{}/", server.addr());
+    for _ in 0..2 {
+        let res = client.get(&url).send().await.unwrap();
+        assert_eq!(res.status(), rquest::StatusCode::OK);
+        res.bytes().await.unwrap();
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    assert_eq!(client.pool_stats().connections_created(), 2);
+    assert_eq!(client.pool_stats().connections_reused(), 0);
+
+    let events = events.lock().unwrap();
+    let created = events
+        .iter()
+        .filter(|event| event.kind() == rquest::PoolEventKind::Created)
+        .count();
+    assert_eq!(created, 2);
+    let retired = events
+        .iter()
+        .find(|event| {
+            event.kind() == rquest::PoolEventKind::Evicted(rquest::EvictReason::MaxRequests)
+        })
+        .expect("connection retired");
+    assert_eq!(retired.requests(), 1);
+    assert_eq!(retired.origin().port_u16(), Some(server.addr().port()));
+}
+
+#[tokio::test]
+async fn max_concurrent_requests_queues_until_body_is_read() {
+    let server = server::http(move |_req| async { http::Response::new("Hello".into()) });
+
+    let client = Client::builder()
+        .max_concurrent_requests(1)
+        .max_connections_per_host(1)
+        .queue_timeout(std::time::Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+    let url = format!("http://{}/", server.addr());
+    let first = client.get(&url).send().await.unwrap();
+
+    let err = client.get(&url).send().await.unwrap_err();
+    assert!(err.is_timeout());
+    assert_eq!(err.timeout_phase(), Some(rquest::TimeoutPhase::Queue));
+
+    assert_eq!(first.text().await.unwrap(), "Hello");
+
+    let res = client.get(&url).send().await.unwrap();
+    assert_eq!(res.text().await.unwrap(), "Hello");
+}
+
+#[tokio::test]
+async fn rate_limiter_spaces_out_requests() {
+    use rquest::ratelimit::{Rate, RateLimiter};
+    use std::time::{Duration, Instant};
+
+    let server = server::http(move |_req| async { http::Response::new("Hello".into()) });
+
+    let limiter = RateLimiter::new().host(
+        "127.0.0.1",
+        Rate::new(1, Duration::from_millis(200)).burst(1),
+    );
+    let client = Client::builder().rate_limiter(limiter).build().unwrap();
+
+    let url = format!("http://{}/", server.addr());
+    let start = Instant::now();
+    for _ in 0..3 {
+        let res = client.get(&url).send().await.unwrap();
+        assert_eq!(res.text().await.unwrap(), "Hello");
+    }
+    assert!(start.elapsed() >= Duration::from_millis(400));
+}
+
+#[tokio::test]
+async fn respect_retry_after_retries_429() {
+    let attempts = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let counter = attempts.clone();
+    let server = server::http(move |_req| {
+        let attempt = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        async move {
+            if attempt < 2 {
+                http::Response::builder()
+                    .status(429)
+                    .header("retry-after", "1")
+                    .body(Default::default())
+                    .unwrap()
+            } else {
+                http::Response::new("Hello".into())
+            }
+        }
+    });
+
+    let url = format!("http://{}/", server.addr());
+
+    let client = Client::builder()
+        .respect_retry_after(true)
+        .max_retry_after_wait(std::time::Duration::from_millis(500))
+        .build()
+        .unwrap();
+    let res = client.get(&url).send().await.unwrap();
+    assert_eq!(res.status(), rquest::StatusCode::TOO_MANY_REQUESTS);
+
+    let client = Client::builder().respect_retry_after(true).build().unwrap();
+    let res = client.get(&url).send().await.unwrap();
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+    assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 3);
+}
+
+/// A runtime without Tokio, which runs each task on a thread of its own.
+#[derive(Clone, Default)]
+struct ThreadRuntime {
+    spawned: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    slept: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+impl rquest::rt::Runtime for ThreadRuntime {
+    fn spawn(&self, fut: std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>) {
+        self.spawned
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        std::thread::spawn(move || block_on(fut));
+    }
+
+    fn sleep(
+        &self,
+        duration: std::time::Duration,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send + Sync>> {
+        self.slept.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let (tx, rx) = futures::channel::oneshot::channel::<()>();
+        std::thread::spawn(move || {
+            std::thread::sleep(duration);
+            let _ = tx.send(());
+        });
+        Box::pin(async move {
+            let _ = rx.await;
+        })
+    }
+}
+
+fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+    struct ThreadWaker(std::thread::Thread);
+
+    impl std::task::Wake for ThreadWaker {
+        fn wake(self: std::sync::Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = std::sync::Arc::new(ThreadWaker(std::thread::current())).into();
+    let mut cx = std::task::Context::from_waker(&waker);
+    let mut fut = std::pin::pin!(fut);
+    loop {
+        match fut.as_mut().poll(&mut cx) {
+            std::task::Poll::Ready(output) => return output,
+            std::task::Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+#[test]
+fn runtime_runs_client_without_tokio() {
+    let server = server::http(move |_req| async { http::Response::new("Hello".into()) });
+    let runtime = ThreadRuntime::default();
+
+    let client = Client::builder()
+        .runtime(runtime.clone())
+        .timeout(std::time::Duration::from_secs(5))
+        .read_timeout(std::time::Duration::from_secs(5))
+        .build()
+        .unwrap();
+    let url = format!("http://{}/", server.addr());
+    let body = block_on(async { client.get(&url).send().await?.text().await }).unwrap();
+
+    assert_eq!(body, "Hello");
+    assert!(runtime.spawned.load(std::sync::atomic::Ordering::SeqCst) > 0);
+    assert!(runtime.slept.load(std::sync::atomic::Ordering::SeqCst) > 0);
+}
+
+#[test]
+fn runtime_times_out_requests() {
+    let server = server::http(move |_req| async {
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        http::Response::new("Hello".into())
+    });
+    let runtime = ThreadRuntime::default();
+
+    let client = Client::builder()
+        .runtime(runtime.clone())
+        .timeout(std::time::Duration::from_millis(100))
+        .build()
+        .unwrap();
+    let url = format!("http://{}/", server.addr());
+    let err = block_on(client.get(&url).send()).unwrap_err();
+
+    assert!(err.is_timeout());
+    assert!(runtime.slept.load(std::sync::atomic::Ordering::SeqCst) > 0);
+}
+
+#[tokio::test]
+async fn execute_ref_sends_request_repeatedly() {
+    let server = server::http(move |req| async move {
+        assert_eq!(req.headers()["x-probe"], "1");
+        let body = req.into_body().collect().await.unwrap().to_bytes();
+        http::Response::new(body.into())
+    });
+
+    let (client, req) = Client::new()
+        .post(format!("http://{}/", server.addr()))
+        .header("x-probe", "1")
+        .body("ping")
+        .build_split();
+    let req = req.unwrap();
+
+    for _ in 0..3 {
+        let res = client.execute_ref(&req).await.unwrap();
+        assert_eq!(res.text().await.unwrap(), "ping");
+    }
+}
+
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn execute_ref_needs_reusable_body() {
+    let stream = futures_util::stream::once(async { Ok::<_, std::io::Error>("hello") });
+    let req = Client::new()
+        .post("http://example.com/")
+        .body(rquest::Body::wrap_stream(stream))
+        .build()
+        .unwrap();
+
+    let err = Client::new().execute_ref(&req).await.unwrap_err();
+    assert!(err.is_builder());
+    assert_eq!(err.url().map(|u| u.as_str()), Some("http://example.com/"));
+}
+
+#[tokio::test]
+async fn read_into_keeps_rest_of_chunk() {
+    let server = server::http(move |_req| async { http::Response::new("hello world".into()) });
+
+    let mut res = Client::new()
+        .get(format!("http://{}/", server.addr()))
+        .send()
+        .await
+        .unwrap();
+
+    let mut buf = [0u8; 4];
+    let n = res.read_into(&mut &mut buf[..]).await.unwrap();
+    assert_eq!(&buf[..n], b"hell");
+
+    let mut rest = Vec::new();
+    while res.read_into(&mut rest).await.unwrap() > 0 {}
+    assert_eq!(rest, b"o world");
+}
+
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn bytes_stream_with_capacity_splits_chunks() {
+    use futures_util::StreamExt;
+
+    let server = server::http(move |_req| async { http::Response::new("hello world".into()) });
+
+    let chunks = Client::new()
+        .get(format!("http://{}/", server.addr()))
+        .send()
+        .await
+        .unwrap()
+        .bytes_stream_with_capacity(4)
+        .map(|chunk| chunk.unwrap())
+        .collect::<Vec<_>>()
+        .await;
+
+    assert!(chunks.iter().all(|chunk| chunk.len() <= 4));
+    assert_eq!(chunks.concat(), b"hello world");
+}
+
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn text_stream_joins_split_characters() {
+    use futures_util::StreamExt;
+
+    let server = server::http(move |_req| async {
+        // "é" is split between the first two chunks
+        let chunks: [&'static [u8]; 3] = [b"caf\xC3", b"\xA9", b" au lait"];
+        let body = rquest::Body::wrap_stream(futures_util::stream::iter(
+            chunks.map(Ok::<_, std::convert::Infallible>),
+        ));
+        http::Response::builder()
+            .header("content-type", "text/plain; charset=utf-8")
+            .body(body)
+            .unwrap()
+    });
+
+    let texts = Client::new()
+        .get(format!("http://{}/", server.addr()))
+        .send()
+        .await
+        .unwrap()
+        .text_stream()
+        .map(|text| text.unwrap())
+        .collect::<Vec<_>>()
+        .await;
+
+    assert!(texts.iter().all(|text| !text.is_empty()));
+    assert_eq!(texts.concat(), "café au lait");
+}
+
+#[tokio::test]
+async fn body_channel_streams_chunks() {
+    let server = server::http(move |req| async move {
+        let body = req.into_body().collect().await.unwrap().to_bytes();
+        http::Response::new(body.into())
+    });
+
+    let (mut sender, body) = rquest::Body::channel();
+    tokio::spawn(async move {
+        sender.send_data("hello").await.unwrap();
+        sender.send_data(" world").await.unwrap();
+    });
+
+    let res = Client::new()
+        .post(format!("http://{}/", server.addr()))
+        .body(body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.text().await.unwrap(), "hello world");
+}
+
+#[tokio::test]
+async fn body_channel_abort_fails_request() {
+    let server = server::http(move |req| async move {
+        let _ = req.into_body().collect().await;
+        http::Response::default()
+    });
+
+    let (mut sender, body) = rquest::Body::channel();
+    tokio::spawn(async move {
+        sender.send_data("hello").await.unwrap();
+        sender.abort();
+    });
+
+    let res = Client::new()
+        .post(format!("http://{}/", server.addr()))
+        .body(body)
+        .send()
+        .await;
+    assert!(res.is_err());
+}
+
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn body_from_async_read_sets_content_length() {
+    let server = server::http(move |req| async move {
+        assert_eq!(req.headers()["content-length"], "11");
+        let body = req.into_body().collect().await.unwrap().to_bytes();
+        http::Response::new(body.into())
+    });
+
+    let data: &'static [u8] = b"hello world";
+    let res = Client::new()
+        .post(format!("http://{}/", server.addr()))
+        .body(rquest::Body::from_async_read(data, Some(data.len() as u64)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.text().await.unwrap(), "hello world");
+}
+
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn body_from_async_read_without_len_is_chunked() {
+    let server = server::http(move |req| async move {
+        assert_eq!(req.headers()["transfer-encoding"], "chunked");
+        let body = req.into_body().collect().await.unwrap().to_bytes();
+        http::Response::new(body.into())
+    });
+
+    let data: &'static [u8] = b"hello world";
+    let res = Client::new()
+        .post(format!("http://{}/", server.addr()))
+        .body(rquest::Body::from_async_read(data, None))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.text().await.unwrap(), "hello world");
+}
+
+#[tokio::test]
+async fn response_body_introspection() {
+    let server = server::http(move |_req| async { http::Response::new("hello world".into()) });
+
+    let mut res = Client::new()
+        .get(format!("http://{}/", server.addr()))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.content_length_hint(), (11, Some(11)));
+    assert_eq!(res.bytes_received(), 0);
+
+    while res.chunk().await.unwrap().is_some() {}
+    assert!(res.is_end_stream());
+    assert_eq!(res.bytes_received(), 11);
+}
+
+#[tokio::test]
+async fn truncated_body_is_incomplete() {
+    use tokio::io::AsyncWriteExt;
+
+    let server = server::low_level_with_response(|_raw_request, client_socket| {
+        Box::new(async move {
+            client_socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 20\r\n\r\nhello")
+                .await
+                .expect("response write_all failed");
+            client_socket
+                .shutdown()
+                .await
+                .expect("response shutdown failed");
+        })
+    });
+
+    let mut res = Client::new()
+        .get(format!("http://{}/", server.addr()))
+        .send()
+        .await
+        .unwrap();
+
+    let err = loop {
+        match res.chunk().await {
+            Ok(Some(_)) => continue,
+            Ok(None) => panic!("truncated body ended cleanly"),
+            Err(err) => break err,
+        }
+    };
+    assert!(err.is_incomplete_body(), "{err:?}");
+    assert_eq!(res.bytes_received(), 5);
+    assert!(!res.is_end_stream());
+}
+
+#[tokio::test]
+async fn max_response_size_fails_large_body() {
+    let server = server::http(move |_req| async { http::Response::new("hello world".into()) });
+
+    let client = Client::builder().max_response_size(5).build().unwrap();
+    let err = client
+        .get(format!("http://{}/", server.addr()))
+        .send()
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap_err();
+    assert!(err.is_body());
+    assert!(err.is_response_too_large());
+
+    let res = client
+        .get(format!("http://{}/", server.addr()))
+        .max_response_size(11)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.text().await.unwrap(), "hello world");
+}
+
+#[tokio::test]
+async fn max_response_size_counts_chunked_body() {
+    use tokio::io::AsyncWriteExt;
+
+    let server = server::low_level_with_response(|_raw_request, client_socket| {
+        Box::new(async move {
+            client_socket
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+                      5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n",
+                )
+                .await
+                .expect("response write_all failed");
+            client_socket.flush().await.expect("response flush failed");
+        })
+    });
+
+    let mut res = Client::new()
+        .get(format!("http://{}/", server.addr()))
+        .max_response_size(8)
+        .send()
+        .await
+        .unwrap();
+
+    let err = loop {
+        match res.chunk().await {
+            Ok(Some(_)) => continue,
+            Ok(None) => panic!("body over the limit ended cleanly"),
+            Err(err) => break err,
+        }
+    };
+    assert!(err.is_response_too_large());
+}
@@ -201,3 +201,262 @@ async fn cookie_store_path() {
     let url = format!("http://{}/subpath", server.addr());
     client.get(&url).send().await.unwrap();
 }
+
+#[cfg(feature = "json")]
+#[tokio::test]
+async fn cookie_jar_save_and_load_json() {
+    use rquest::cookie::{CookieStore, Jar};
+
+    let url = "https://yolo.local/".parse::<rquest::Url>().unwrap();
+    let jar = Jar::default();
+    jar.add_cookie_str("session=abc; Secure; HttpOnly", &url);
+    jar.add_cookie_str("persistent=1; Max-Age=3600; Path=/", &url);
+    jar.add_cookie_str("expired=1; Max-Age=0", &url);
+
+    let mut saved = Vec::new();
+    jar.save_json(&mut saved).unwrap();
+
+    let jar = Jar::load_json(&saved[..]).unwrap();
+    let mut cookies = jar
+        .cookies(&url)
+        .unwrap()
+        .iter()
+        .map(|value| value.to_str().unwrap().to_owned())
+        .collect::<Vec<_>>();
+    cookies.sort();
+    assert_eq!(cookies, ["persistent=1", "session=abc"]);
+}
+
+#[tokio::test]
+async fn async_cookie_store_redirect() {
+    use rquest::cookie::{AsyncCookieStore, CookieFuture};
+    use rquest::header::HeaderValue;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Remote {
+        cookies: Mutex<Vec<HeaderValue>>,
+        stored: Mutex<Vec<(String, usize)>>,
+    }
+
+    impl AsyncCookieStore for Remote {
+        fn store_cookies<'a>(
+            &'a self,
+            cookie_headers: Vec<HeaderValue>,
+            url: &'a rquest::Url,
+        ) -> CookieFuture<'a, ()> {
+            Box::pin(async move {
+                tokio::task::yield_now().await;
+                self.stored
+                    .lock()
+                    .unwrap()
+                    .push((url.path().to_owned(), cookie_headers.len()));
+                let mut cookies = self.cookies.lock().unwrap();
+                for header in cookie_headers {
+                    let pair = header.to_str().unwrap().split(';').next().unwrap();
+                    cookies.push(HeaderValue::from_str(pair).unwrap());
+                }
+            })
+        }
+
+        fn load_cookies<'a>(
+            &'a self,
+            _url: &'a rquest::Url,
+        ) -> CookieFuture<'a, Option<Vec<HeaderValue>>> {
+            Box::pin(async move {
+                tokio::task::yield_now().await;
+                let cookies = self.cookies.lock().unwrap();
+                (!cookies.is_empty()).then(|| cookies.clone())
+            })
+        }
+    }
+
+    let server = server::http(move |req| async move {
+        if req.uri() == "/login" {
+            http::Response::builder()
+                .status(302)
+                .header("Location", "/home")
+                .header("Set-Cookie", "session=abc; Path=/")
+                .header("Set-Cookie", "theme=dark")
+                .body(Default::default())
+                .unwrap()
+        } else {
+            assert_eq!(req.headers()["cookie"], "session=abc");
+            http::Response::default()
+        }
+    });
+
+    let store = Arc::new(Remote::default());
+    let client = rquest::Client::builder()
+        .cookie_provider(store.clone())
+        .build()
+        .unwrap();
+
+    let url = format!("http://{}/login", server.addr());
+    let res = client.get(&url).send().await.unwrap();
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+    assert_eq!(*store.stored.lock().unwrap(), [("/login".to_owned(), 2)]);
+}
+
+#[tokio::test]
+async fn cookie_jar_inspection() {
+    use rquest::cookie::Jar;
+    use std::sync::Arc;
+
+    let server = server::http(move |_req| async move {
+        http::Response::builder()
+            .header(
+                "Set-Cookie",
+                "session=abc; Max-Age=3600; SameSite=Lax; HttpOnly",
+            )
+            .header("Set-Cookie", "theme=dark; Path=/settings")
+            .body(Default::default())
+            .unwrap()
+    });
+
+    let jar = Arc::new(Jar::default());
+    let client = rquest::Client::builder()
+        .cookie_provider(jar.clone())
+        .build()
+        .unwrap();
+
+    let url = format!("http://{}/", server.addr())
+        .parse::<rquest::Url>()
+        .unwrap();
+    client.get(url.clone()).send().await.unwrap();
+
+    let session = jar.get(&url, "session").unwrap();
+    assert_eq!(session.value(), "abc");
+    assert!(session.http_only());
+    assert_eq!(session.same_site(), Some(rquest::cookie::SameSite::Lax));
+    assert!(session.expires().unwrap() > std::time::SystemTime::now());
+
+    // only sent to /settings
+    assert!(jar.get(&url, "theme").is_none());
+    let settings = url.join("/settings").unwrap();
+    assert_eq!(jar.get(&settings, "theme").unwrap().value(), "dark");
+    assert_eq!(jar.iter().count(), 2);
+
+    assert_eq!(jar.remove("session", &url).unwrap().value(), "abc");
+    assert!(jar.get(&url, "session").is_none());
+
+    jar.clear_domain(url.host_str().unwrap());
+    assert_eq!(jar.iter().count(), 0);
+}
+
+#[cfg(feature = "cookies-psl")]
+#[test]
+fn cookie_jar_rejects_public_suffix_domain() {
+    use rquest::cookie::{Jar, PublicSuffixList};
+
+    let list = PublicSuffixList::from_bytes(b"// ===BEGIN ICANN DOMAINS===\nuk\nco.uk\n").unwrap();
+    let jar = Jar::with_public_suffix_list(list);
+
+    let url = "https://shop.example.co.uk/"
+        .parse::<rquest::Url>()
+        .unwrap();
+    jar.add_cookie_str("tracker=1; Domain=co.uk", &url);
+    jar.add_cookie_str("session=abc; Domain=example.co.uk", &url);
+
+    assert!(jar.get(&url, "tracker").is_none());
+    assert_eq!(jar.get(&url, "session").unwrap().value(), "abc");
+}
+
+#[tokio::test]
+async fn cookie_jar_per_request() {
+    use rquest::cookie::Jar;
+    use std::sync::Arc;
+
+    let server = server::http(move |req| async move {
+        let user = req.uri().query().unwrap_or_default().to_owned();
+        let cookie = req
+            .headers()
+            .get("cookie")
+            .map(|value| value.to_str().unwrap().to_owned());
+        match req.uri().path() {
+            "/login" => http::Response::builder()
+                .header("Set-Cookie", format!("user={}", user))
+                .body(Default::default())
+                .unwrap(),
+            _ => http::Response::new(cookie.unwrap_or_default().into()),
+        }
+    });
+
+    let client = rquest::Client::builder()
+        .cookie_store(true)
+        .build()
+        .unwrap();
+    let alice = Arc::new(Jar::default());
+    let bob = Arc::new(Jar::default());
+
+    let base = format!("http://{}", server.addr());
+    for (jar, user) in [(&alice, "alice"), (&bob, "bob")] {
+        client
+            .get(format!("{}/login?{}", base, user))
+            .cookie_jar(jar.clone())
+            .send()
+            .await
+            .unwrap();
+    }
+
+    for (jar, user) in [(&alice, "alice"), (&bob, "bob")] {
+        let text = client
+            .get(format!("{}/me", base))
+            .cookie_jar(jar.clone())
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert_eq!(text, format!("user={}", user));
+    }
+
+    // the client's own store was left alone
+    let text = client
+        .get(format!("{}/me", base))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert_eq!(text, "");
+}
+
+#[tokio::test]
+async fn sessions_are_isolated() {
+    let server = server::http(move |req| async move {
+        if req.uri().path() == "/login" {
+            let user = req.headers()["x-user"].to_str().unwrap().to_owned();
+            http::Response::builder()
+                .header("Set-Cookie", format!("user={}", user))
+                .body(Default::default())
+                .unwrap()
+        } else {
+            assert_eq!(req.headers()["x-user"], "alice");
+            assert_eq!(req.headers()["cookie"], "user=alice");
+            http::Response::default()
+        }
+    });
+
+    let client = rquest::Client::new();
+    let base = format!("http://{}", server.addr());
+
+    let mut headers = rquest::header::HeaderMap::new();
+    headers.insert("x-user", "alice".parse().unwrap());
+    let alice = client.session().headers(headers);
+
+    let mut headers = rquest::header::HeaderMap::new();
+    headers.insert("x-user", "bob".parse().unwrap());
+    let bob = client.session().headers(headers);
+
+    alice.get(format!("{}/login", base)).send().await.unwrap();
+    bob.get(format!("{}/login", base)).send().await.unwrap();
+
+    let res = alice.get(format!("{}/inbox", base)).send().await.unwrap();
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+
+    let url = base.parse::<rquest::Url>().unwrap();
+    assert_eq!(bob.jar().get(&url, "user").unwrap().value(), "bob");
+}
@@ -1,6 +1,6 @@
 mod support;
 use flate2::Compression;
-use flate2::write::ZlibEncoder;
+use flate2::write::{DeflateEncoder, ZlibEncoder};
 use std::io::Write;
 use support::server;
 use tokio::io::AsyncWriteExt;
@@ -153,6 +153,53 @@ async fn deflate_case(response_size: usize, chunk_size: usize) {
     assert_eq!(body, content);
 }
 
+#[tokio::test]
+async fn raw_deflate_response() {
+    let content = "raw deflate data without a zlib header ".repeat(100);
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(content.as_bytes()).unwrap();
+    let deflated = encoder.finish().unwrap();
+
+    let server = server::http(move |_req| {
+        let deflated = deflated.clone();
+        async move {
+            http::Response::builder()
+                .header("content-encoding", "deflate")
+                .body(rquest::Body::from(deflated))
+                .unwrap()
+        }
+    });
+
+    let res = rquest::Client::new()
+        .get(format!("http://{}/raw", server.addr()))
+        .send()
+        .await
+        .expect("response");
+
+    assert_eq!(res.text().await.expect("text"), content);
+}
+
+#[tokio::test]
+async fn accept_encoding_lists_enabled_encodings() {
+    let server = server::http(move |req| async move {
+        let accept_encoding = req.headers()["accept-encoding"].to_str().unwrap();
+        assert!(accept_encoding.starts_with("deflate"));
+        assert!(!accept_encoding.contains("gzip"));
+        http::Response::default()
+    });
+
+    let res = rquest::Client::builder()
+        .no_gzip()
+        .build()
+        .unwrap()
+        .get(format!("http://{}/accept-encoding", server.addr()))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+}
+
 const COMPRESSED_RESPONSE_HEADERS: &[u8] = b"HTTP/1.1 200 OK\x0d\x0a\
             Content-Type: text/plain\x0d\x0a\
             Connection: keep-alive\x0d\x0a\
@@ -0,0 +1,142 @@
+mod support;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use http_body_util::BodyExt;
+use rquest::Client;
+use rquest::dns::DohResolver;
+use support::server;
+
+/// A DoH server answering `A` queries with `127.0.0.1` for `ttl` seconds and
+/// `AAAA` queries with no records, counting the queries it receives.
+fn doh_server(ttl: u32, queries: Arc<AtomicUsize>) -> server::Server {
+    server::http(move |req| {
+        let queries = queries.clone();
+        async move {
+            assert_eq!(req.method(), "POST");
+            assert_eq!(req.headers()["content-type"], "application/dns-message");
+            queries.fetch_add(1, Ordering::SeqCst);
+
+            let mut msg = req.into_body().collect().await.unwrap().to_bytes().to_vec();
+            let qtype = u16::from_be_bytes([msg[msg.len() - 4], msg[msg.len() - 3]]);
+
+            // turn the query into a response, answering it if it is for `A`
+            msg[2] = 0x81;
+            msg[3] = 0x80;
+            if qtype == 1 {
+                msg[7] = 1;
+                msg.extend_from_slice(&[0xc0, 12, 0, 1, 0, 1]);
+                msg.extend_from_slice(&ttl.to_be_bytes());
+                msg.extend_from_slice(&[0, 4, 127, 0, 0, 1]);
+            }
+
+            http::Response::builder()
+                .header("content-type", "application/dns-message")
+                .body(msg.into())
+                .unwrap()
+        }
+    })
+}
+
+fn client(resolver: DohResolver) -> Client {
+    let resolver = resolver.with_client(Client::builder().no_proxy().build().unwrap());
+    Client::builder()
+        .no_proxy()
+        .pool_max_idle_per_host(0)
+        .dns_resolver(Arc::new(resolver))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn doh_resolves_and_caches() {
+    let server = server::http(move |_req| async { http::Response::new("ok".into()) });
+    let queries = Arc::new(AtomicUsize::new(0));
+    let doh = doh_server(300, queries.clone());
+
+    let resolver = DohResolver::new(format!("http://{}/dns-query", doh.addr()))
+        .unwrap()
+        .fallback(false);
+    let client = client(resolver);
+    let url = format!("http://doh.test:{}/", server.addr().port());
+
+    for _ in 0..2 {
+        let res = client.get(&url).send().await.unwrap();
+        assert_eq!(res.text().await.unwrap(), "ok");
+    }
+
+    // one query for `A` and one for `AAAA`, then the second request hits the cache
+    assert_eq!(queries.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn doh_queries_again_once_ttl_expires() {
+    let server = server::http(move |_req| async { http::Response::new("ok".into()) });
+    let queries = Arc::new(AtomicUsize::new(0));
+    let doh = doh_server(1, queries.clone());
+
+    let resolver = DohResolver::new(format!("http://{}/dns-query", doh.addr()))
+        .unwrap()
+        .fallback(false);
+    let client = client(resolver);
+    let url = format!("http://doh.test:{}/", server.addr().port());
+
+    client.get(&url).send().await.unwrap();
+    assert_eq!(queries.load(Ordering::SeqCst), 2);
+
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+
+    client.get(&url).send().await.unwrap();
+    assert_eq!(queries.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn doh_falls_back_to_system_resolver() {
+    let server = server::http(move |_req| async { http::Response::new("ok".into()) });
+    let doh = server::http(move |_req| async {
+        http::Response::builder()
+            .status(500)
+            .body(Default::default())
+            .unwrap()
+    });
+    let endpoint = format!("http://{}/dns-query", doh.addr());
+    let url = format!("http://localhost:{}/", server.addr().port());
+
+    let res = client(DohResolver::new(&endpoint).unwrap())
+        .get(&url)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.text().await.unwrap(), "ok");
+
+    let err = client(DohResolver::new(&endpoint).unwrap().fallback(false))
+        .get(&url)
+        .send()
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), rquest::ErrorKind::Dns);
+}
+
+#[tokio::test]
+async fn doh_nxdomain_is_not_looked_up_again() {
+    let server = server::http(move |_req| async { http::Response::new("ok".into()) });
+    let doh = server::http(move |req| async move {
+        let mut msg = req.into_body().collect().await.unwrap().to_bytes().to_vec();
+        // answer NXDOMAIN
+        msg[2] = 0x81;
+        msg[3] = 0x83;
+        http::Response::builder()
+            .header("content-type", "application/dns-message")
+            .body(msg.into())
+            .unwrap()
+    });
+
+    // the system resolver would find `localhost`, but the DoH server's answer stands
+    let err = client(DohResolver::new(format!("http://{}/dns-query", doh.addr())).unwrap())
+        .get(format!("http://localhost:{}/", server.addr().port()))
+        .send()
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), rquest::ErrorKind::Dns);
+}
@@ -382,3 +382,113 @@ async fn disable_compression_request() {
     assert_eq!(res.url().as_str(), &url);
     assert_eq!(res.status(), rquest::StatusCode::OK);
 }
+
+#[tokio::test]
+async fn gzip_request_body() {
+    use http_body_util::BodyExt;
+    use std::io::Read;
+
+    let content = "compressed request body ".repeat(1000);
+    let expected = content.clone();
+
+    let server = server::http(move |req| {
+        let expected = expected.clone();
+        async move {
+            assert_eq!(req.headers()["content-encoding"], "gzip");
+            assert_eq!(req.headers().get("content-length"), None);
+
+            let body = req.into_body().collect().await.unwrap().to_bytes();
+            let mut decoded = String::new();
+            flate2::read::GzDecoder::new(&body[..])
+                .read_to_string(&mut decoded)
+                .unwrap();
+            assert_eq!(decoded, expected);
+
+            http::Response::default()
+        }
+    });
+
+    let res = rquest::Client::new()
+        .post(format!("http://{}/upload", server.addr()))
+        .header("content-length", content.len())
+        .body(content)
+        .compress_body(rquest::Encoding::Gzip)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+}
+
+fn gzip_bomb_server(size: usize) -> server::Server {
+    server::http(move |_req| async move {
+        let gzipped = gzip_compress(&vec![0; size]);
+        http::Response::builder()
+            .header("content-encoding", "gzip")
+            .body(gzipped.into())
+            .unwrap()
+    })
+}
+
+#[tokio::test]
+async fn max_decompressed_size_stops_bomb() {
+    let server = gzip_bomb_server(1024 * 1024);
+
+    let err = rquest::Client::builder()
+        .max_decompressed_size(64 * 1024)
+        .build()
+        .unwrap()
+        .get(format!("http://{}/bomb", server.addr()))
+        .send()
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap_err();
+
+    assert!(err.is_decode());
+    assert!(err.is_decompression_bomb());
+}
+
+#[tokio::test]
+async fn max_decompression_ratio_stops_bomb() {
+    let server = gzip_bomb_server(1024 * 1024);
+
+    let err = rquest::Client::builder()
+        .max_decompression_ratio(100)
+        .build()
+        .unwrap()
+        .get(format!("http://{}/bomb", server.addr()))
+        .send()
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap_err();
+
+    assert!(err.is_decompression_bomb());
+}
+
+#[tokio::test]
+async fn max_decompression_ratio_allows_text() {
+    let server = server::http(move |_req| async move {
+        http::Response::builder()
+            .header("content-encoding", "gzip")
+            .body(gzip_compress(RESPONSE_CONTENT.as_bytes()).into())
+            .unwrap()
+    });
+
+    let body = rquest::Client::builder()
+        .max_decompression_ratio(100)
+        .build()
+        .unwrap()
+        .get(format!("http://{}/text", server.addr()))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+
+    assert_eq!(body, RESPONSE_CONTENT);
+}
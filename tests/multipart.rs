@@ -175,3 +175,55 @@ async fn async_impl_file_part() {
     assert_eq!(res.url().as_str(), &url);
     assert_eq!(res.status(), rquest::StatusCode::OK);
 }
+
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn reader_part_with_length() {
+    let _ = env_logger::try_init();
+
+    let data = vec![b'x'; 64 * 1024];
+    let part = rquest::multipart::Part::reader_with_length(
+        std::io::Cursor::new(data.clone()),
+        data.len() as u64,
+    )
+    .file_name("data.bin");
+
+    let form = rquest::multipart::Form::new().part("upload", part);
+
+    let mut expected_body = format!(
+        "\
+         --{0}\r\n\
+         Content-Disposition: form-data; name=\"upload\"; filename=\"data.bin\"\r\n\
+         \r\n\
+         ",
+        form.boundary()
+    )
+    .into_bytes();
+    expected_body.extend_from_slice(&data);
+    expected_body.extend_from_slice(format!("\r\n--{}--\r\n", form.boundary()).as_bytes());
+
+    let server = server::http(move |req| {
+        let expected_body = expected_body.clone();
+        async move {
+            assert_eq!(
+                req.headers()["content-length"],
+                expected_body.len().to_string()
+            );
+            assert!(!req.headers().contains_key("transfer-encoding"));
+
+            let full = req.collect().await.unwrap().to_bytes();
+            assert_eq!(full, expected_body);
+
+            http::Response::default()
+        }
+    });
+
+    let res = rquest::Client::new()
+        .post(format!("http://{}/multipart/4", server.addr()))
+        .multipart(form)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+}
@@ -347,6 +347,210 @@ async fn tunnel_includes_proxy_auth() {
     );
 }
 
+#[tokio::test]
+async fn tunnel_exposes_connect_response() {
+    let url = "https://hyper.rs.local/prox";
+
+    let server = server::http(move |req| {
+        assert_eq!(req.method(), "CONNECT");
+        assert_eq!(req.headers()["x-proxy-session"], "abc123");
+
+        async {
+            http::Response::builder()
+                .status(http::StatusCode::PAYMENT_REQUIRED)
+                .header("x-proxy-error", "no balance")
+                .body(Default::default())
+                .unwrap()
+        }
+    });
+
+    let proxy = format!("http://{}", server.addr());
+    let proxy = rquest::Proxy::https(&proxy)
+        .unwrap()
+        .custom_connect_headers({
+            let mut headers = http::HeaderMap::new();
+            headers.insert("x-proxy-session", "abc123".parse().unwrap());
+            headers
+        });
+
+    let err = rquest::Client::builder()
+        .proxy(proxy)
+        .build()
+        .unwrap()
+        .get(url)
+        .send()
+        .await
+        .unwrap_err();
+
+    assert!(err.is_connect());
+    let res = err.proxy_connect_response().expect("connect response");
+    assert_eq!(res.status(), rquest::StatusCode::PAYMENT_REQUIRED);
+    assert_eq!(res.headers()["x-proxy-error"], "no balance");
+}
+
+#[tokio::test]
+async fn http_proxy_skips_connect_headers() {
+    let url = "http://hyper.rs.local/prox";
+    let server = server::http(move |req| {
+        assert_eq!(req.method(), "GET");
+        assert!(!req.headers().contains_key("x-proxy-session"));
+
+        async { http::Response::default() }
+    });
+
+    let proxy = format!("http://{}", server.addr());
+    let proxy = rquest::Proxy::http(&proxy)
+        .unwrap()
+        .custom_connect_headers({
+            let mut headers = http::HeaderMap::new();
+            headers.insert("x-proxy-session", "abc123".parse().unwrap());
+            headers
+        });
+
+    let res = rquest::Client::builder()
+        .proxy(proxy)
+        .build()
+        .unwrap()
+        .get(url)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+}
+
+#[tokio::test]
+async fn tunnel_looks_up_credentials_per_connection() {
+    let url = "https://hyper.rs.local/prox";
+
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let recorded = seen.clone();
+    let server = server::http(move |req| {
+        assert_eq!(req.method(), "CONNECT");
+        recorded
+            .lock()
+            .unwrap()
+            .push(req.headers()["proxy-authorization"].clone());
+
+        async {
+            // return 400 to not actually deal with TLS tunneling
+            let mut res = http::Response::default();
+            *res.status_mut() = http::StatusCode::BAD_REQUEST;
+            res
+        }
+    });
+
+    let session = std::sync::atomic::AtomicUsize::new(0);
+    let proxy = rquest::Proxy::https(format!("http://{}", server.addr()))
+        .unwrap()
+        .credentials_fn(move || {
+            let session = session.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            (format!("user-session-{}", session), "secret".to_owned())
+        });
+    let client = rquest::Client::builder().proxy(proxy).build().unwrap();
+
+    for _ in 0..2 {
+        client.get(url).send().await.unwrap_err();
+    }
+
+    // "user-session-0:secret" and "user-session-1:secret"
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 2);
+    assert_eq!(seen[0], "Basic dXNlci1zZXNzaW9uLTA6c2VjcmV0");
+    assert_eq!(seen[1], "Basic dXNlci1zZXNzaW9uLTE6c2VjcmV0");
+}
+
+#[tokio::test]
+async fn tunnel_answers_digest_challenge() {
+    let url = "https://hyper.rs.local/prox";
+
+    let server = server::http(move |req| {
+        assert_eq!(req.method(), "CONNECT");
+        let auth = req.headers().get("proxy-authorization").cloned();
+
+        async move {
+            let Some(auth) = auth else {
+                return http::Response::builder()
+                    .status(http::StatusCode::PROXY_AUTHENTICATION_REQUIRED)
+                    .header(
+                        "proxy-authenticate",
+                        "Digest realm=\"proxy\", nonce=\"abc\", qop=\"auth\"",
+                    )
+                    .body(Default::default())
+                    .unwrap();
+            };
+
+            let auth = auth.to_str().unwrap();
+            assert!(auth.starts_with("Digest username=\"Aladdin\", realm=\"proxy\""));
+            assert!(auth.contains("uri=\"hyper.rs.local:443\""));
+
+            // return 400 to not actually deal with TLS tunneling
+            let mut res = http::Response::default();
+            *res.status_mut() = http::StatusCode::BAD_REQUEST;
+            res
+        }
+    });
+
+    let proxy = rquest::Proxy::https(format!("http://{}", server.addr()))
+        .unwrap()
+        .digest_auth("Aladdin", "open sesame");
+
+    let err = rquest::Client::builder()
+        .proxy(proxy)
+        .build()
+        .unwrap()
+        .get(url)
+        .send()
+        .await
+        .unwrap_err();
+
+    let res = err.proxy_connect_response().expect("connect response");
+    assert_eq!(res.status(), rquest::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn http_proxy_answers_digest_challenge() {
+    let url = "http://hyper.rs.local/prox";
+
+    let server = server::http(move |req| {
+        assert_eq!(req.method(), "GET");
+        let auth = req.headers().get("proxy-authorization").cloned();
+
+        async move {
+            let Some(auth) = auth else {
+                return http::Response::builder()
+                    .status(http::StatusCode::PROXY_AUTHENTICATION_REQUIRED)
+                    .header(
+                        "proxy-authenticate",
+                        "Digest realm=\"proxy\", nonce=\"abc\", qop=\"auth\"",
+                    )
+                    .body(Default::default())
+                    .unwrap();
+            };
+
+            let auth = auth.to_str().unwrap();
+            assert!(auth.starts_with("Digest username=\"Aladdin\""));
+            assert!(auth.contains("uri=\"http://hyper.rs.local/prox\""));
+            http::Response::default()
+        }
+    });
+
+    let proxy = rquest::Proxy::http(format!("http://{}", server.addr()))
+        .unwrap()
+        .digest_auth("Aladdin", "open sesame");
+
+    let res = rquest::Client::builder()
+        .proxy(proxy)
+        .build()
+        .unwrap()
+        .get(url)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+}
+
 #[tokio::test]
 async fn tunnel_includes_user_agent() {
     let url = "https://hyper.rs.local/prox";
@@ -387,3 +591,113 @@ async fn tunnel_includes_user_agent() {
         err
     );
 }
+
+#[tokio::test]
+async fn request_proxy_basic_auth() {
+    let url = "http://hyper.rs.local/prox";
+    let server = server::http(move |req| {
+        assert_eq!(req.method(), "GET");
+        assert_eq!(req.uri(), url);
+        assert_eq!(
+            req.headers()["proxy-authorization"],
+            "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ=="
+        );
+
+        async { http::Response::default() }
+    });
+
+    let proxy = format!("http://{}", server.addr());
+
+    let res = rquest::Client::builder()
+        .no_proxy()
+        .build()
+        .unwrap()
+        .get(url)
+        .proxy(
+            rquest::Proxy::http(&proxy)
+                .unwrap()
+                .basic_auth("Aladdin", "open sesame"),
+        )
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.url().as_str(), url);
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+}
+
+#[tokio::test]
+async fn request_no_proxy_bypasses_client_proxy() {
+    let server = server::http(move |req| {
+        assert_eq!(req.method(), "GET");
+        assert_eq!(req.uri(), "/direct");
+        assert!(!req.headers().contains_key("proxy-authorization"));
+
+        async { http::Response::default() }
+    });
+
+    // the client proxy points at a port nobody listens on
+    let unused = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let proxy = rquest::Proxy::http(format!("http://{}", unused))
+        .unwrap()
+        .basic_auth("Aladdin", "open sesame");
+
+    let url = format!("http://{}/direct", server.addr());
+
+    let res = rquest::Client::builder()
+        .proxy(proxy)
+        .build()
+        .unwrap()
+        .get(&url)
+        .no_proxy()
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.url().as_str(), &url);
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+}
+
+#[tokio::test]
+async fn proxy_pool_skips_failed_proxy() {
+    let url = "http://hyper.rs.local/pool";
+    let hits = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let counter = hits.clone();
+    let server = server::http(move |req| {
+        assert_eq!(req.uri(), url);
+        counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+
+        async { http::Response::default() }
+    });
+
+    // the first proxy points at a port nobody listens on
+    let dead = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    let pool = rquest::ProxyPool::new([
+        rquest::Proxy::http(format!("http://{}", dead)).unwrap(),
+        rquest::Proxy::http(format!("http://{}", server.addr())).unwrap(),
+    ]);
+
+    let client = rquest::Client::builder()
+        .proxy_pool(pool.clone())
+        .retry(rquest::retry::Policy::limited(1).backoff(
+            std::time::Duration::from_millis(1),
+            std::time::Duration::from_millis(10),
+        ))
+        .build()
+        .unwrap();
+
+    for _ in 0..3 {
+        let res = client.get(url).send().await.unwrap();
+        assert_eq!(res.status(), rquest::StatusCode::OK);
+    }
+
+    assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 3);
+    assert_eq!(pool.unhealthy(), 1);
+}
@@ -141,6 +141,40 @@ async fn test_redirect_307_and_308_tries_to_post_again() {
     }
 }
 
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn test_redirect_307_resends_replayable_stream() {
+    let redirect = server::http(move |req| async move {
+        let path = req.uri().path().to_string();
+        let body = req.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(&*body, b"Hello");
+
+        if path == "/307" {
+            http::Response::builder()
+                .status(307)
+                .header("location", "/dst")
+                .body(Body::default())
+                .unwrap()
+        } else {
+            http::Response::default()
+        }
+    });
+
+    let body = Body::from_fn(|| {
+        let chunks: Vec<Result<_, std::io::Error>> = vec![Ok("Hel"), Ok("lo")];
+        futures_util::stream::iter(chunks)
+    });
+    let res = rquest::Client::new()
+        .post(format!("http://{}/307", redirect.addr()))
+        .body(body)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.url().path(), "/dst");
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+}
+
 #[tokio::test]
 async fn test_redirect_removes_sensitive_headers() {
     use tokio::sync::watch;
@@ -368,3 +402,138 @@ async fn test_redirect_limit_to_1() {
     );
     assert!(res.is_redirect());
 }
+
+#[tokio::test]
+async fn test_redirect_history_is_recorded() {
+    let server = server::http(move |req| async move {
+        match req.uri().path() {
+            "/first" => http::Response::builder()
+                .status(301)
+                .header("location", "/second")
+                .header("set-cookie", "hop=1")
+                .body(Body::default())
+                .unwrap(),
+            "/second" => http::Response::builder()
+                .status(307)
+                .header("location", "/dst")
+                .body(Body::default())
+                .unwrap(),
+            _ => {
+                assert_eq!(req.uri(), "/dst");
+                http::Response::default()
+            }
+        }
+    });
+
+    let first = format!("http://{}/first", server.addr());
+    let second = format!("http://{}/second", server.addr());
+    let dst = format!("http://{}/dst", server.addr());
+
+    let res = rquest::Client::new()
+        .get(&first)
+        .redirect(Policy::default())
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.url().as_str(), dst);
+
+    let history = res.redirect_history();
+    assert_eq!(history.len(), 2);
+
+    assert_eq!(history[0].status(), rquest::StatusCode::MOVED_PERMANENTLY);
+    assert_eq!(history[0].url().as_str(), first);
+    assert_eq!(history[0].next().as_str(), second);
+    assert_eq!(history[0].headers()["set-cookie"], "hop=1");
+
+    assert_eq!(history[1].status(), rquest::StatusCode::TEMPORARY_REDIRECT);
+    assert_eq!(history[1].url().as_str(), second);
+    assert_eq!(history[1].next().as_str(), dst);
+}
+
+#[tokio::test]
+async fn test_redirect_policy_body_preview() {
+    let server = server::http(move |req| async move {
+        assert_eq!(req.uri(), "/preview");
+        http::Response::builder()
+            .status(302)
+            .header("location", "/dst")
+            .header("set-cookie", "challenge=1")
+            .body(Body::from("<html>challenge page</html>"))
+            .unwrap()
+    });
+
+    let policy = Policy::custom(|attempt| {
+        assert_eq!(attempt.headers()["set-cookie"], "challenge=1");
+        assert_eq!(attempt.body(), Some(&b"<html>"[..]));
+        attempt.stop()
+    })
+    .body_preview(6);
+
+    let url = format!("http://{}/preview", server.addr());
+    let res = rquest::Client::new()
+        .get(&url)
+        .redirect(policy)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), rquest::StatusCode::FOUND);
+    assert_eq!(res.text().await.unwrap(), "<html>challenge page</html>");
+}
+
+#[tokio::test]
+async fn test_redirect_follow_refresh() {
+    let server = server::http(move |req| async move {
+        match req.uri().path() {
+            "/header" => http::Response::builder()
+                .header("refresh", "0; url=/meta")
+                .body(Body::default())
+                .unwrap(),
+            "/meta" => http::Response::builder()
+                .header("content-type", "text/html; charset=utf-8")
+                .body(Body::from(
+                    r#"<html><head><meta http-equiv="refresh" content="1; url=/dst"></head></html>"#,
+                ))
+                .unwrap(),
+            "/dst" => http::Response::builder()
+                .header("content-type", "text/html")
+                .body(Body::from(
+                    r#"<meta http-equiv="refresh" content="60; url=/dst">done"#,
+                ))
+                .unwrap(),
+            _ => unreachable!("unexpected request to {}", req.uri()),
+        }
+    });
+
+    let url = format!("http://{}/header", server.addr());
+    let dst = format!("http://{}/dst", server.addr());
+
+    let res = rquest::Client::new()
+        .get(&url)
+        .redirect(Policy::default().follow_refresh(true))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.url().as_str(), dst);
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+    assert_eq!(res.redirect_history().len(), 2);
+    assert!(res.text().await.unwrap().ends_with("done"));
+}
+
+#[tokio::test]
+async fn test_redirect_refresh_not_followed_by_default() {
+    let server = server::http(move |_req| async move {
+        http::Response::builder()
+            .header("refresh", "0; url=/dst")
+            .body(Body::from("refresh"))
+            .unwrap()
+    });
+
+    let url = format!("http://{}/src", server.addr());
+    let res = rquest::Client::new().get(&url).send().await.unwrap();
+
+    assert_eq!(res.url().as_str(), url);
+    assert_eq!(res.text().await.unwrap(), "refresh");
+}
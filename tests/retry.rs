@@ -0,0 +1,222 @@
+mod support;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use rquest::{Body, StatusCode, retry};
+use support::server;
+
+fn fast_policy(max: usize) -> retry::Policy {
+    retry::Policy::limited(max).backoff(Duration::from_millis(1), Duration::from_millis(10))
+}
+
+#[tokio::test]
+async fn retries_server_errors_until_success() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let server = server::http(move |_req| {
+        let counter = counter.clone();
+        async move {
+            let status = if counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                StatusCode::SERVICE_UNAVAILABLE
+            } else {
+                StatusCode::OK
+            };
+            http::Response::builder()
+                .status(status)
+                .body(Body::from("done"))
+                .unwrap()
+        }
+    });
+
+    let res = rquest::Client::builder()
+        .retry(fast_policy(3).retry_non_idempotent(true))
+        .build()
+        .unwrap()
+        .post(format!("http://{}/retry", server.addr()))
+        .body("payload")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(hits.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn does_not_retry_non_idempotent_requests_by_default() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let server = server::http(move |_req| {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            http::Response::builder()
+                .status(StatusCode::SERVICE_UNAVAILABLE)
+                .body(Body::default())
+                .unwrap()
+        }
+    });
+
+    let res = rquest::Client::builder()
+        .retry(fast_policy(3))
+        .build()
+        .unwrap()
+        .post(format!("http://{}/order", server.addr()))
+        .body("payload")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn retries_wait_for_rate_limiter() {
+    use rquest::ratelimit::{Rate, RateLimiter};
+    use std::time::Instant;
+
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let server = server::http(move |_req| {
+        let counter = counter.clone();
+        async move {
+            let status = if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                StatusCode::BAD_GATEWAY
+            } else {
+                StatusCode::OK
+            };
+            http::Response::builder()
+                .status(status)
+                .body(Body::default())
+                .unwrap()
+        }
+    });
+
+    let limiter = RateLimiter::new().host(
+        "127.0.0.1",
+        Rate::new(1, Duration::from_millis(300)).burst(1),
+    );
+    let client = rquest::Client::builder()
+        .retry(fast_policy(1))
+        .rate_limiter(limiter)
+        .build()
+        .unwrap();
+
+    let start = Instant::now();
+    let res = client
+        .get(format!("http://{}/", server.addr()))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+    assert!(start.elapsed() >= Duration::from_millis(250));
+}
+
+#[tokio::test]
+async fn returns_last_response_when_retries_exhausted() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let server = server::http(move |_req| {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            http::Response::builder()
+                .status(StatusCode::TOO_MANY_REQUESTS)
+                .header("retry-after", "0")
+                .body(Body::default())
+                .unwrap()
+        }
+    });
+
+    let res = rquest::Client::builder()
+        .retry(fast_policy(2))
+        .build()
+        .unwrap()
+        .get(format!("http://{}/limited", server.addr()))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(hits.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn does_not_wait_longer_than_max_retry_after() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let server = server::http(move |_req| {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            http::Response::builder()
+                .status(StatusCode::SERVICE_UNAVAILABLE)
+                .header("retry-after", "3600")
+                .body(Body::default())
+                .unwrap()
+        }
+    });
+
+    let res = rquest::Client::builder()
+        .retry(fast_policy(2).max_retry_after(Duration::from_secs(1)))
+        .build()
+        .unwrap()
+        .get(format!("http://{}/maintenance", server.addr()))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn connect_errors_report_attempts() {
+    // bind and drop a listener to find a port nobody is listening on
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    let err = rquest::Client::builder()
+        .retry(fast_policy(2))
+        .no_proxy()
+        .build()
+        .unwrap()
+        .get(format!("http://{}/", addr))
+        .send()
+        .await
+        .unwrap_err();
+
+    assert!(err.is_connect());
+    assert_eq!(err.attempts(), Some(3));
+}
+
+#[tokio::test]
+async fn no_retry_policy_by_default() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let server = server::http(move |_req| {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            http::Response::builder()
+                .status(StatusCode::BAD_GATEWAY)
+                .body(Body::default())
+                .unwrap()
+        }
+    });
+
+    let res = rquest::Client::new()
+        .get(format!("http://{}/", server.addr()))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_GATEWAY);
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,99 @@
+mod support;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use rquest::Body;
+use support::server;
+
+fn event_stream(body: &'static str) -> http::Response<Body> {
+    http::Response::builder()
+        .header("content-type", "text/event-stream")
+        .body(Body::from(body))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn response_events() {
+    let server = server::http(move |req| async move {
+        assert_eq!(req.uri(), "/events");
+        event_stream("event: greeting\ndata: hello\ndata: world\n\nid: 1\ndata: bye\n\n")
+    });
+
+    let mut events = rquest::Client::new()
+        .get(format!("http://{}/events", server.addr()))
+        .send()
+        .await
+        .unwrap()
+        .events();
+
+    let first = events.next().await.unwrap().unwrap();
+    assert_eq!(first.event(), "greeting");
+    assert_eq!(first.data(), "hello\nworld");
+    assert_eq!(first.id(), None);
+
+    let second = events.next().await.unwrap().unwrap();
+    assert_eq!(second.event(), "message");
+    assert_eq!(second.data(), "bye");
+    assert_eq!(second.id(), Some("1"));
+
+    assert!(events.next().await.is_none());
+    assert_eq!(events.last_event_id(), Some("1"));
+}
+
+#[tokio::test]
+async fn event_source_reconnects_with_last_event_id() {
+    let connections = Arc::new(AtomicUsize::new(0));
+    let counter = connections.clone();
+    let server = server::http(move |req| {
+        let counter = counter.clone();
+        async move {
+            assert_eq!(req.headers()["accept"], "text/event-stream");
+            match counter.fetch_add(1, Ordering::SeqCst) {
+                0 => {
+                    assert!(!req.headers().contains_key("last-event-id"));
+                    event_stream("id: 41\ndata: first\n\n")
+                }
+                _ => {
+                    assert_eq!(req.headers()["last-event-id"], "41");
+                    event_stream("id: 42\ndata: second\n\n")
+                }
+            }
+        }
+    });
+
+    let mut events = rquest::Client::new()
+        .get(format!("http://{}/events", server.addr()))
+        .event_source()
+        .retry(Duration::from_millis(10));
+
+    let first = events.next().await.unwrap().unwrap();
+    assert_eq!(first.data(), "first");
+
+    let second = events.next().await.unwrap().unwrap();
+    assert_eq!(second.data(), "second");
+    assert_eq!(events.last_event_id(), Some("42"));
+    assert_eq!(connections.load(Ordering::SeqCst), 2);
+
+    events.close();
+    assert!(events.next().await.is_none());
+}
+
+#[tokio::test]
+async fn event_source_stops_on_error_status() {
+    let server = server::http(move |_req| async move {
+        http::Response::builder()
+            .status(http::StatusCode::NO_CONTENT)
+            .body(Body::default())
+            .unwrap()
+    });
+
+    let mut events = rquest::Client::new()
+        .get(format!("http://{}/events", server.addr()))
+        .event_source();
+
+    let err = events.next().await.unwrap().unwrap_err();
+    assert_eq!(err.status(), Some(rquest::StatusCode::NO_CONTENT));
+    assert!(events.next().await.is_none());
+}
@@ -0,0 +1,91 @@
+mod support;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rquest::testing::{Cassette, VcrLayer};
+use support::server;
+
+#[tokio::test]
+async fn records_and_plays_back() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let server = server::http(move |req| {
+        let hit = counter.fetch_add(1, Ordering::SeqCst);
+        async move {
+            http::Response::builder()
+                .header("x-hit", hit.to_string())
+                .body(format!("{} {}", req.method(), req.uri().path()).into())
+                .unwrap()
+        }
+    });
+    let url = format!("http://{}/recorded", server.addr());
+
+    let cassette = Cassette::new();
+    let client = rquest::Client::builder()
+        .layer(VcrLayer::record(cassette.clone()))
+        .build()
+        .unwrap();
+    for _ in 0..2 {
+        let res = client.get(&url).send().await.unwrap();
+        assert_eq!(res.text().await.unwrap(), "GET /recorded");
+    }
+    assert_eq!(cassette.len(), 2);
+
+    let path = std::env::temp_dir().join(format!("rquest-cassette-{}.json", server.addr().port()));
+    cassette.save(&path).unwrap();
+    let cassette = Cassette::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    drop(server);
+
+    let client = rquest::Client::builder()
+        .layer(VcrLayer::playback(cassette))
+        .build()
+        .unwrap();
+    for expected in ["0", "1", "1"] {
+        let res = client.get(&url).send().await.unwrap();
+        assert_eq!(res.headers()["x-hit"], expected);
+        assert_eq!(res.url().as_str(), url);
+        assert_eq!(res.text().await.unwrap(), "GET /recorded");
+    }
+
+    let err = client.post(&url).send().await.unwrap_err();
+    assert!(err.is_request());
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn mock_answers_without_network() {
+    use rquest::ResponseBuilderExt;
+
+    let client = rquest::Client::builder()
+        .mock(|req| match req.url().path() {
+            "/redirected" => http::Response::builder()
+                .status(200)
+                .url("https://example.com/final".parse().unwrap())
+                .body("moved")
+                .unwrap(),
+            _ => http::Response::builder()
+                .status(404)
+                .body("missing")
+                .unwrap(),
+        })
+        .build()
+        .unwrap();
+
+    let res = client
+        .get("https://example.com/users")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), rquest::StatusCode::NOT_FOUND);
+    assert_eq!(res.url().as_str(), "https://example.com/users");
+    assert_eq!(res.text().await.unwrap(), "missing");
+
+    let res = client
+        .get("https://example.com/redirected")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.url().as_str(), "https://example.com/final");
+    assert_eq!(res.text().await.unwrap(), "moved");
+}
@@ -113,6 +113,34 @@ async fn connect_many_timeout_succeeds() {
         .await;
 }
 
+#[tokio::test]
+async fn happy_eyeballs_races_ipv4() {
+    let _ = env_logger::try_init();
+
+    let server = server::http(move |_req| async { http::Response::default() });
+    let port = server.addr().port();
+
+    // 100::/64 is a discard-only prefix, so connecting over IPv6 never completes
+    let client = rquest::Client::builder()
+        .resolve_to_addrs(
+            "dual_stack",
+            &[format!("[100::1]:{port}").parse().unwrap(), server.addr()],
+        )
+        .happy_eyeballs_timeout(Duration::from_millis(50))
+        .no_proxy()
+        .build()
+        .unwrap();
+
+    let res = client
+        .get(format!("http://dual_stack:{port}/eyeballs"))
+        .timeout(Duration::from_millis(1000))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+}
+
 #[tokio::test]
 async fn connect_many_timeout() {
     let _ = env_logger::try_init();
@@ -232,6 +260,51 @@ async fn read_timeout_applies_to_body() {
     assert!(err.is_timeout());
 }
 
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn request_read_timeout_applies_to_bytes_stream() {
+    use futures_util::StreamExt;
+
+    let _ = env_logger::try_init();
+
+    let server = server::http(move |_req| {
+        async {
+            // the first chunk arrives, then the body stalls
+            let stalled = futures_util::stream::unfold(0, |state| async move {
+                if state > 0 {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+                Some((
+                    Ok::<_, std::convert::Infallible>(state.to_string()),
+                    state + 1,
+                ))
+            });
+            let body = rquest::Body::wrap_stream(stalled);
+
+            http::Response::new(body)
+        }
+    });
+
+    let client = rquest::Client::builder().no_proxy().build().unwrap();
+
+    let url = format!("http://{}/stalled", server.addr());
+    let mut stream = client
+        .get(&url)
+        // a long total timeout must not replace the read timeout
+        .timeout(Duration::from_secs(10))
+        .read_timeout(Duration::from_millis(100))
+        .send()
+        .await
+        .expect("Failed to get")
+        .bytes_stream();
+
+    let first = stream.next().await.unwrap().expect("first chunk");
+    assert_eq!(first, "0");
+
+    let err = stream.next().await.unwrap().unwrap_err();
+    assert!(err.is_timeout());
+}
+
 #[cfg(feature = "stream")]
 #[tokio::test]
 async fn read_timeout_allows_slow_response_body() {
@@ -291,3 +364,110 @@ async fn response_body_timeout_forwards_size_hint() {
 
     assert_eq!(res.content_length(), Some(5));
 }
+
+#[tokio::test]
+async fn first_byte_timeout_applies_to_each_attempt() {
+    let _ = env_logger::try_init();
+
+    let server = server::http(move |req| async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        match req.uri().path() {
+            "/redirect" => http::Response::builder()
+                .status(302)
+                .header("location", "/done")
+                .body(Default::default())
+                .unwrap(),
+            "/done" => http::Response::default(),
+            _ => {
+                tokio::time::sleep(Duration::from_millis(300)).await;
+                http::Response::default()
+            }
+        }
+    });
+
+    let client = rquest::Client::builder()
+        .timeouts(rquest::Timeouts::new().first_byte(Duration::from_millis(150)))
+        .redirect(rquest::redirect::Policy::limited(1))
+        .no_proxy()
+        .build()
+        .unwrap();
+
+    let res = client
+        .get(format!("http://{}/redirect", server.addr()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), rquest::StatusCode::OK);
+
+    let url = format!("http://{}/slow", server.addr());
+    let err = client.get(&url).send().await.unwrap_err();
+    assert!(err.is_timeout());
+    assert_eq!(err.url().map(|u| u.as_str()), Some(url.as_str()));
+}
+
+#[tokio::test]
+async fn dns_timeout() {
+    use rquest::dns::{Name, Resolve, Resolving};
+    use std::sync::Arc;
+
+    struct Stalled;
+
+    impl Resolve for Stalled {
+        fn resolve(&self, _: Name) -> Resolving {
+            Box::pin(std::future::pending())
+        }
+    }
+
+    let client = rquest::Client::builder()
+        .dns_resolver(Arc::new(Stalled))
+        .timeouts(rquest::Timeouts::new().dns(Duration::from_millis(100)))
+        .no_proxy()
+        .build()
+        .unwrap();
+
+    let err = client
+        .get("http://stalled.example/")
+        .send()
+        .await
+        .unwrap_err();
+
+    assert!(err.is_connect());
+    assert!(err.is_timeout());
+}
+
+#[tokio::test]
+async fn deadline_caps_redirects() {
+    let _ = env_logger::try_init();
+
+    let server = server::http(move |req| async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let next = match req.uri().path() {
+            "/0" => "/1",
+            "/1" => "/2",
+            _ => return http::Response::default(),
+        };
+        http::Response::builder()
+            .status(302)
+            .header("location", next)
+            .body(Default::default())
+            .unwrap()
+    });
+
+    let client = rquest::Client::builder().no_proxy().build().unwrap();
+
+    let err = client
+        .get(format!("http://{}/0", server.addr()))
+        .timeout(Duration::from_secs(5))
+        .deadline(std::time::Instant::now() + Duration::from_millis(250))
+        .send()
+        .await
+        .unwrap_err();
+
+    assert!(err.is_timeout());
+    assert_eq!(err.timeout_phase(), Some(rquest::TimeoutPhase::Request));
+    assert_eq!(
+        err.url().map(|u| u.path()),
+        Some("/2"),
+        "the deadline passed on the last hop"
+    );
+}
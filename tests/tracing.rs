@@ -0,0 +1,73 @@
+mod support;
+use support::server;
+
+use std::sync::{Arc, Mutex};
+
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Records the names of the spans opened, and the messages of the events.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+impl<S> Layer<S> for Recorder
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("span {}", attrs.metadata().name()));
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        struct Message<'a>(&'a mut Vec<String>);
+
+        impl tracing::field::Visit for Message<'_> {
+            fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+                if field.name() == "message" {
+                    self.0.push(format!("event {:?}", value));
+                }
+            }
+        }
+
+        event.record(&mut Message(&mut self.0.lock().unwrap()));
+    }
+}
+
+#[tokio::test]
+async fn request_phases_are_traced() {
+    let server = server::http(move |_req| async { http::Response::new("Hello".into()) });
+
+    let recorder = Recorder::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(recorder.clone()));
+
+    let url = format!("http://localhost:{}/traced", server.addr().port());
+    let res = rquest::Client::builder()
+        .no_proxy()
+        .build()
+        .unwrap()
+        .get(&url)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.text().await.unwrap(), "Hello");
+
+    let recorded = recorder.0.lock().unwrap().clone();
+    let position = |name: &str| {
+        recorded
+            .iter()
+            .position(|line| line == name)
+            .unwrap_or_else(|| panic!("{} not in {:?}", name, recorded))
+    };
+
+    assert!(position("span request") < position("span connect"));
+    assert!(position("span connect") < position("span dns"));
+    assert!(position("span dns") < position("span tcp_connect"));
+    assert!(position("event connection established") < position("event received response headers"));
+    assert!(position("event received response headers") < position("event response body complete"));
+}